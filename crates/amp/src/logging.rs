@@ -0,0 +1,77 @@
+//! Diagnostic logging on stderr via `tracing`.
+//!
+//! Command results stay on stdout; progress and diagnostics are events. The
+//! level comes from `--log-level`, else the `AMPERSONA_LOG` filter, else
+//! `info`. `--log-format text` renders events the way `amp` always printed
+//! them; `json` emits one object per line for log collectors.
+
+use std::fmt::{self, Write as _};
+
+use anyhow::{Context, Result};
+use tracing::field::{Field, Visit};
+use tracing::{Event, Level, Subscriber};
+use tracing_subscriber::fmt::format::Writer;
+use tracing_subscriber::fmt::{FmtContext, FormatEvent, FormatFields};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::{EnvFilter, Layer, Registry};
+
+pub const FILTER_ENV: &str = "AMPERSONA_LOG";
+
+/// Install the global subscriber. `extra` is stacked unfiltered (span export).
+pub fn init<L>(level: Option<&str>, format: &str, extra: L) -> Result<()>
+where
+    L: Layer<Registry> + Send + Sync + 'static,
+{
+    let directives = match level {
+        Some(l) => l.to_string(),
+        None => std::env::var(FILTER_ENV).unwrap_or_else(|_| "info".into()),
+    };
+    let filter = EnvFilter::try_new(&directives)
+        .with_context(|| format!("invalid log filter '{directives}' (see {FILTER_ENV})"))?;
+
+    let events = tracing_subscriber::fmt::layer().with_writer(std::io::stderr);
+    let events = match format {
+        "json" => events.json().with_filter(filter).boxed(),
+        _ => events.event_format(Human).with_filter(filter).boxed(),
+    };
+    tracing::subscriber::set_global_default(tracing_subscriber::registry().with(extra).with(events))
+        .context("installing tracing subscriber")
+}
+
+/// Plain `amp` output: the message only, with a level prefix below info.
+struct Human;
+
+impl<S, N> FormatEvent<S, N> for Human
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    N: for<'a> FormatFields<'a> + 'static,
+{
+    fn format_event(
+        &self,
+        _ctx: &FmtContext<'_, S, N>,
+        mut writer: Writer<'_>,
+        event: &Event<'_>,
+    ) -> fmt::Result {
+        let mut message = Message(String::new());
+        event.record(&mut message);
+        let prefix = match *event.metadata().level() {
+            Level::ERROR => "error: ",
+            Level::WARN => "  warn: ",
+            Level::INFO => "  ",
+            Level::DEBUG => "  debug: ",
+            Level::TRACE => "  trace: ",
+        };
+        writeln!(writer, "{prefix}{}", message.0)
+    }
+}
+
+struct Message(String);
+
+impl Visit for Message {
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "message" {
+            let _ = write!(self.0, "{value:?}");
+        }
+    }
+}
@@ -1,10 +1,20 @@
 #![forbid(unsafe_code)]
 
 use std::collections::HashMap;
-use std::io::{self, Read};
+use std::io::{self, Read, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+use std::time::Duration;
 
+use ampersona_engine::gates::notify::{ApprovalNotice, ReminderPolicy, Urgency};
+use ampersona_engine::state::store::{FileStateStore, InMemoryStateStore, StateStore};
 use anyhow::{bail, Result};
 use clap::{Parser, Subcommand};
+use tracing::{error, info, warn};
+
+mod logging;
+#[cfg(feature = "otel")]
+mod otel;
 
 #[derive(Parser)]
 #[command(
@@ -13,10 +23,126 @@ use clap::{Parser, Subcommand};
     about = "Agent identity, authority, and trust gates. Unix-friendly."
 )]
 struct Cli {
+    /// Seconds to wait for a state lock held by another writer.
+    #[arg(long, global = true, value_name = "SECS", default_value_t = 5)]
+    lock_timeout: u64,
+
+    /// Minimum level of diagnostics on stderr (overrides AMPERSONA_LOG).
+    #[arg(long, global = true, value_parser = ["error", "warn", "info", "debug", "trace"])]
+    log_level: Option<String>,
+
+    /// Diagnostics format on stderr: human text or JSON lines.
+    #[arg(long, global = true, default_value = "text", value_parser = ["text", "json"])]
+    log_format: String,
+
+    /// Extra authority defaults file, layered over the user-level
+    /// (~/.config/ampersona/defaults.json) and workspace defaults.
+    #[arg(long, global = true, value_name = "PATH")]
+    config: Option<String>,
+
+    /// Print JSON output on one line instead of indented. Files written and
+    /// canonical/signed forms are unaffected.
+    #[arg(long, global = true, overrides_with = "pretty")]
+    compact: bool,
+
+    /// Print JSON output indented (the default; overrides an earlier --compact).
+    #[arg(long, global = true, overrides_with = "compact")]
+    pretty: bool,
+
+    /// Where phase state and audit logs live: `files` (sidecars next to each
+    /// persona) or `sqlite` (.ampersona/state.db in each persona directory or
+    /// --state-dir; `sqlite` feature).
+    #[arg(long, global = true, default_value = "files", value_parser = ["files", "sqlite"])]
+    backend: String,
+
+    /// Keep state, audit, drift and checkpoint files under this directory
+    /// instead of next to each persona, mirroring the persona's path
+    /// (`personas/a.json` → `<dir>/personas/a.state.json`).
+    #[arg(long, global = true, value_name = "DIR")]
+    state_dir: Option<String>,
+
     #[command(subcommand)]
     cmd: Cmd,
 }
 
+/// Explicit sidecar locations for commands that touch state. Each defaults
+/// to a file next to the persona (`agent.json` → `agent.state.json`); with
+/// --state, the others default next to the state file instead.
+#[derive(clap::Args, Clone, Default)]
+struct SidecarArgs {
+    /// State file.
+    #[arg(long, value_name = "PATH")]
+    state: Option<String>,
+
+    /// Audit log.
+    #[arg(long, value_name = "PATH")]
+    audit: Option<String>,
+
+    /// Drift history (.drift.jsonl).
+    #[arg(long = "drift-file", id = "drift_file", value_name = "PATH")]
+    drift: Option<String>,
+
+    /// Audit checkpoint.
+    #[arg(long, value_name = "PATH")]
+    checkpoint: Option<String>,
+}
+
+impl SidecarArgs {
+    fn resolve(&self, file: &str) -> ampersona_core::sidecar::Sidecars {
+        let mut paths = match &self.state {
+            Some(state) => ampersona_core::sidecar::Sidecars::for_state(state),
+            None => default_sidecars(file),
+        };
+        if let Some(audit) = &self.audit {
+            paths.audit = audit.clone();
+        }
+        if let Some(drift) = &self.drift {
+            paths.drift = drift.clone();
+        }
+        if let Some(checkpoint) = &self.checkpoint {
+            paths.checkpoint = checkpoint.clone();
+        }
+        paths
+    }
+}
+
+/// Refuse to operate on a persona whose embedded signature does not cover
+/// the document as stored.
+#[derive(clap::Args, Default)]
+struct TrustArgs {
+    /// Verify the persona's signature against --pubkey before doing anything
+    /// else; a tampered document is refused.
+    #[arg(long, requires = "pubkey")]
+    verify_on_load: bool,
+
+    /// Ed25519 public key for --verify-on-load.
+    #[arg(long, requires = "verify_on_load")]
+    pubkey: Option<String>,
+
+    /// With --verify-on-load: accept a persona that carries no signature.
+    #[arg(long, requires = "verify_on_load")]
+    allow_unsigned: bool,
+}
+
+impl TrustArgs {
+    /// Check `data`, the persona as stored (before `$extends`, `$ref` and
+    /// env expansion), against --pubkey. A no-op without --verify-on-load.
+    fn check(&self, file: &str, data: &serde_json::Value) -> Result<()> {
+        let Some(pubkey) = self.pubkey.as_deref().filter(|_| self.verify_on_load) else {
+            return Ok(());
+        };
+        let key = load_verifying_key(pubkey)?;
+        match signature_status(data, &key, !self.allow_unsigned) {
+            (_, None) => Ok(()),
+            (_, Some(issue)) => bail!(
+                "{file}: {} ({}); refusing to load an untrusted persona",
+                issue.message,
+                issue.code
+            ),
+        }
+    }
+}
+
 #[derive(Subcommand)]
 enum Cmd {
     /// Generate a Markdown system prompt from a persona JSON.
@@ -32,6 +158,17 @@ enum Cmd {
         /// Include only these sections (comma-separated).
         #[arg(long, value_delimiter = ',')]
         sections: Vec<String>,
+
+        /// Add the current phase and resolved authority from the .state.json sidecar.
+        #[arg(long, conflicts_with = "toon")]
+        with_state: bool,
+
+        /// Expand ${VAR} / ${VAR:-default} in string fields from the environment.
+        #[arg(long)]
+        expand_env: bool,
+
+        #[command(flatten)]
+        trust: TrustArgs,
     },
 
     /// Validate persona JSON files against the ampersona schema.
@@ -39,6 +176,19 @@ enum Cmd {
         /// One or more .json file paths.
         #[arg(required = true)]
         files: Vec<String>,
+
+        /// Expand ${VAR} / ${VAR:-default} in string fields from the environment.
+        #[arg(long)]
+        expand_env: bool,
+
+        /// Also report keys the persona model does not know (E040; `ext` is exempt).
+        #[arg(long)]
+        strict_unknown_fields: bool,
+
+        /// Validate as overlays: top-level fields (name, role, ...) are
+        /// optional, but any field present must conform.
+        #[arg(long)]
+        as_overlay: bool,
     },
 
     /// Create a new persona from a built-in template.
@@ -46,10 +196,15 @@ enum Cmd {
         /// Template name: architect, worker, scout.
         template: String,
 
-        /// Set the persona name (AdjectiveNoun).
+        /// Set the persona name (AdjectiveNoun). Generated when omitted.
         #[arg(long)]
         name: Option<String>,
 
+        /// Seed for generated fields, so the same seed yields the same
+        /// persona (default: from entropy).
+        #[arg(long, conflicts_with = "name")]
+        seed: Option<u64>,
+
         /// Write to file instead of stdout.
         #[arg(short, long)]
         output: Option<String>,
@@ -58,11 +213,76 @@ enum Cmd {
     /// List available built-in templates.
     Templates,
 
+    /// Print the embedded JSON Schema that validate/check use.
+    Schema {
+        /// Schema version.
+        #[arg(long = "version", default_value = "1.0", value_parser = ["1.0", "0.2"])]
+        schema_version: String,
+
+        /// Write to file instead of stdout.
+        #[arg(short, long)]
+        out: Option<String>,
+    },
+
+    /// List the known action vocabulary for allow/deny lists.
+    Actions {
+        /// Also report custom and unknown actions referenced by this persona.
+        #[arg(long)]
+        file: Option<String>,
+
+        /// Output structured JSON.
+        #[arg(long)]
+        json: bool,
+    },
+
     /// Summarize persona files in a directory as a table.
     List {
         /// Directory containing .json persona files.
         #[arg(default_value = ".")]
         dir: String,
+
+        /// Output structured JSON.
+        #[arg(long)]
+        json: bool,
+
+        /// Sort by column: name, autonomy, phase, role.
+        #[arg(long, value_parser = ["name", "autonomy", "phase", "role"])]
+        sort: Option<String>,
+
+        /// Reverse the sort order.
+        #[arg(long)]
+        reverse: bool,
+
+        /// Walk subdirectories too (skips sidecars and `.ampersona/`).
+        #[arg(long)]
+        recursive: bool,
+    },
+
+    /// Build a JSON index of every persona under a directory (recursive).
+    Index {
+        /// Directory to scan.
+        #[arg(default_value = ".")]
+        dir: String,
+
+        /// Write the index to this file (atomically) instead of stdout.
+        #[arg(long)]
+        out: Option<String>,
+    },
+
+    /// Diagnose a persona directory: defaults, orphaned sidecars, stale locks,
+    /// broken audit logs and checkpoints, schema versions.
+    Doctor {
+        /// Directory to check.
+        #[arg(default_value = ".")]
+        dir: String,
+
+        /// Apply the safe fixes (remove stale writer locks).
+        #[arg(long)]
+        fix: bool,
+
+        /// Output structured JSON.
+        #[arg(long)]
+        json: bool,
     },
 
     /// Generate a register_agent MCP call from a persona JSON.
@@ -94,6 +314,27 @@ enum Cmd {
         /// Wrap output in JSON-RPC 2.0 envelope.
         #[arg(long)]
         rpc: bool,
+
+        /// Advertise capabilities.skills as MCP tool declarations (`tools`).
+        #[arg(long)]
+        tools: bool,
+
+        /// Fail if a declared capability maps to an action the authority denies.
+        #[arg(long)]
+        strict: bool,
+
+        /// Register every persona in this directory; emits an array of calls
+        /// (a JSON-RPC batch with --rpc).
+        #[arg(long, conflicts_with = "file")]
+        batch: Option<String>,
+
+        /// With --batch: stream one call per line (NDJSON) as each persona is
+        /// processed, instead of one array at the end.
+        #[arg(long, requires = "batch")]
+        ndjson: bool,
+
+        #[command(flatten)]
+        trust: TrustArgs,
     },
 
     /// Bootstrap a persona file or workspace.
@@ -101,6 +342,22 @@ enum Cmd {
         /// Initialize workspace defaults (.ampersona/defaults.json).
         #[arg(long)]
         workspace: bool,
+
+        /// Template for persona.json: architect, worker, scout, or a file in --template-dir.
+        #[arg(long, default_value = "worker", conflicts_with = "workspace")]
+        template: String,
+
+        /// Directory of custom `<name>.json` templates, checked before the built-ins.
+        #[arg(long, conflicts_with = "workspace")]
+        template_dir: Option<String>,
+
+        /// Set the persona name (AdjectiveNoun).
+        #[arg(long, conflicts_with = "workspace")]
+        name: Option<String>,
+
+        /// Default autonomy for workspace defaults.
+        #[arg(long, requires = "workspace", value_parser = ["readonly", "supervised", "full"])]
+        autonomy: Option<String>,
     },
 
     /// Unified validation: schema + consistency + action vocab + lint.
@@ -115,6 +372,18 @@ enum Cmd {
         /// Fail on warnings (not just errors).
         #[arg(long)]
         strict: bool,
+
+        /// Expand ${VAR} / ${VAR:-default} in string fields from the environment.
+        #[arg(long)]
+        expand_env: bool,
+
+        /// Apply safe fixes (W030-W033) and write the file back.
+        #[arg(long, conflicts_with = "expand_env")]
+        fix: bool,
+
+        /// Also report keys the persona model does not know (E040; `ext` is exempt).
+        #[arg(long)]
+        strict_unknown_fields: bool,
     },
 
     /// Migrate persona files from v0.2 to v1.0.
@@ -122,6 +391,41 @@ enum Cmd {
         /// One or more .json file paths.
         #[arg(required = true)]
         files: Vec<String>,
+
+        /// Write migrated files as canonical JSON (RFC 8785).
+        #[arg(long)]
+        canonical: bool,
+
+        /// Print each file's diff against its migrated form without writing.
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Keep a `<name>.bak` copy of each file before overwriting it.
+        #[arg(long, conflicts_with = "dry_run")]
+        backup: bool,
+    },
+
+    /// Copy phase state and audit logs between storage backends.
+    MigrateBackend {
+        /// Directory of personas whose sidecars to import.
+        #[arg(default_value = ".")]
+        dir: String,
+
+        /// Backend to read from (only `files` for now).
+        #[arg(long, value_parser = ["files", "sqlite"])]
+        from: String,
+
+        /// Backend to write to (only `sqlite` for now; `sqlite` feature).
+        #[arg(long, value_parser = ["files", "sqlite"])]
+        to: String,
+
+        /// Walk subdirectories too.
+        #[arg(long)]
+        recursive: bool,
+
+        /// Output structured JSON.
+        #[arg(long)]
+        json: bool,
     },
 
     /// Show phase, autonomy, elevations, and drift.
@@ -136,6 +440,40 @@ enum Cmd {
         /// Show drift trend.
         #[arg(long)]
         drift: bool,
+
+        /// Only drift entries at or after this RFC 3339 time.
+        #[arg(long, requires = "drift", value_parser = parse_rfc3339)]
+        since: Option<chrono::DateTime<chrono::Utc>>,
+
+        /// Only drift entries at or before this RFC 3339 time.
+        #[arg(long, requires = "drift", value_parser = parse_rfc3339)]
+        until: Option<chrono::DateTime<chrono::Utc>>,
+
+        /// How many of the (filtered) drift entries to show, newest last.
+        #[arg(long, requires = "drift", default_value_t = 5)]
+        limit: usize,
+
+        /// Print drift trends as numbers instead of Unicode sparklines.
+        #[arg(long, requires = "drift")]
+        plain: bool,
+
+        #[command(flatten)]
+        sidecars: SidecarArgs,
+    },
+
+    /// Compliance report: identity and fingerprint, signature, authority
+    /// matrix, phase and transition history, audit chain, compliance refs.
+    Report {
+        /// Path to persona .json file.
+        file: String,
+
+        /// Verify the persona's signature with this ed25519 public key.
+        #[arg(long)]
+        pubkey: Option<String>,
+
+        /// Output format.
+        #[arg(long, default_value = "markdown", value_parser = ["markdown", "json"])]
+        format: String,
     },
 
     /// Check if an action is allowed by authority.
@@ -144,8 +482,13 @@ enum Cmd {
         file: String,
 
         /// Action to check.
-        #[arg(long)]
-        check: String,
+        #[arg(long, required_unless_present_any = ["matrix", "show_overlay_effect", "what_if"])]
+        check: Option<String>,
+
+        /// Evaluate every vocabulary action (and custom actions the persona
+        /// references) and print the action → decision table.
+        #[arg(long, conflicts_with_all = ["check", "trace", "strict_vocab", "strict_review"])]
+        matrix: bool,
 
         /// Output structured JSON.
         #[arg(long)]
@@ -162,6 +505,36 @@ enum Cmd {
         /// Context as JSON object (merged with --context).
         #[arg(long)]
         context_json: Option<String>,
+
+        /// Show each precedence layer's contribution and the deciding layer.
+        #[arg(long)]
+        trace: bool,
+
+        /// Treat actions outside the known vocabulary as an error (exit 3).
+        #[arg(long)]
+        strict_vocab: bool,
+
+        /// Treat a deny past its review_by date as an error (exit 3) instead
+        /// of an ordinary deny with a warning.
+        #[arg(long)]
+        strict_review: bool,
+
+        /// Expand ${VAR} / ${VAR:-default} in string fields from the environment.
+        #[arg(long)]
+        expand_env: bool,
+
+        /// Print each field the active gate overlay (from state) changed
+        /// relative to the resolved base, with before/after values.
+        #[arg(long, conflicts_with_all = ["check", "matrix", "trace"])]
+        show_overlay_effect: bool,
+
+        /// Report the actions whose decision would change if this authority
+        /// overlay (JSON) were applied. Read-only.
+        #[arg(long, value_name = "OVERLAY", conflicts_with_all = ["check", "matrix", "trace", "show_overlay_effect"])]
+        what_if: Option<String>,
+
+        #[command(flatten)]
+        trust: TrustArgs,
     },
 
     /// Activate a temporary elevation.
@@ -176,21 +549,50 @@ enum Cmd {
         /// Reason for elevation.
         #[arg(long)]
         reason: String,
+
+        /// Override the elevation's TTL (e.g. `90m`, `2h30m`); capped at its max_ttl_seconds.
+        #[arg(long, value_parser = parse_ttl)]
+        ttl: Option<u64>,
+
+        #[command(flatten)]
+        sidecars: SidecarArgs,
     },
 
     /// Evaluate or override a gate.
     Gate {
-        /// Path to persona .json file.
+        /// Path to persona .json file, or `-` for stdin. A piped persona
+        /// without --state runs --evaluate on a fresh in-memory state and
+        /// writes nothing.
         file: String,
 
+        #[command(flatten)]
+        sidecars: SidecarArgs,
+
+        #[command(flatten)]
+        trust: TrustArgs,
+
         /// Gate ID to evaluate.
         #[arg(long)]
         evaluate: Option<String>,
 
-        /// Metrics file for evaluation.
+        /// Metrics source for evaluation: a file path or `file://`, `http://`
+        /// (`metrics-http` feature) or `prom://host:port` (`metrics-prom` feature) URI.
         #[arg(long)]
         metrics: Option<String>,
 
+        /// Re-run --evaluate even when it repeats the last transition's
+        /// (gate, metrics hash, state_rev). Cooldowns still apply.
+        #[arg(long, requires = "evaluate")]
+        force_reeval: bool,
+
+        /// Skip the gates' `on_pass.exec` / `on_fail.exec` hooks for this run.
+        #[arg(long, requires = "evaluate")]
+        no_hooks: bool,
+
+        /// Kill a gate hook still running after this long (e.g. `30s`, `5m`).
+        #[arg(long, requires = "evaluate", value_parser = parse_ttl, default_value = "60s")]
+        hook_timeout: u64,
+
         /// Gate ID to override.
         #[arg(long = "override")]
         override_gate: Option<String>,
@@ -207,11 +609,80 @@ enum Cmd {
         #[arg(long)]
         approve: Option<String>,
 
+        /// Administratively force the agent into this phase (requires --reason, --approver).
+        #[arg(long)]
+        reset_phase: Option<String>,
+
+        /// Allow --reset-phase to a phase no gate references, or --promote-to
+        /// on gates with no observed decisions.
+        #[arg(long)]
+        force: bool,
+
+        /// Replay a metrics timeline (.jsonl of {"ts", "metrics"}) in memory and
+        /// print the resulting phase trajectory. Never writes state.
+        #[arg(long)]
+        simulate: Option<String>,
+
+        /// Replay a drift history (.drift.jsonl) from a fresh state, in
+        /// timestamp order, and report the trajectory and which gates fired
+        /// when. Never reads or writes the real state.
+        #[arg(long, conflicts_with = "simulate")]
+        backtest: Option<String>,
+
+        /// List every gate and whether it is a candidate in the current phase.
+        /// Read-only; needs no metrics.
+        #[arg(long, conflicts_with_all = ["evaluate", "override_gate", "approve", "reset_phase", "simulate", "backtest"])]
+        list: bool,
+
+        /// Summarize, per observe-mode gate, how often the audit log records it
+        /// would have fired. Read-only.
+        #[arg(long, conflicts_with_all = ["evaluate", "override_gate", "approve", "reset_phase", "simulate", "backtest", "list"])]
+        observe_report: bool,
+
+        /// Flip observe-mode gates to this enforcement in the persona file
+        /// (keeps a `<name>.bak` backup). Select gates with --gate or --all-observe.
+        #[arg(long, value_parser = ["enforce"], conflicts_with_all = ["evaluate", "override_gate", "approve", "reset_phase", "simulate", "backtest", "list", "observe_report"])]
+        promote_to: Option<String>,
+
+        /// Gate ID to promote (repeatable).
+        #[arg(long = "gate", requires = "promote_to")]
+        promote_gates: Vec<String>,
+
+        /// Promote every observe-mode gate.
+        #[arg(long, requires = "promote_to", conflicts_with = "promote_gates")]
+        all_observe: bool,
+
+        /// Remind about a transition pending human approval for longer than
+        /// --remind-after (stderr, and the AMPERSONA_NOTIFY_URL webhook with
+        /// the `notify` feature). Read-only.
+        #[arg(long, conflicts_with_all = ["evaluate", "override_gate", "approve", "reset_phase", "simulate", "backtest", "list", "observe_report", "promote_to"])]
+        remind: bool,
+
+        /// With --remind: how long a transition may wait before reminders start
+        /// (e.g. `4h`, `1d`).
+        #[arg(long, requires = "remind", value_parser = parse_ttl, default_value = "24h")]
+        remind_after: u64,
+
+        /// With --remind: approval SLA; reminders past it escalate.
+        #[arg(long, requires = "remind", value_parser = parse_ttl)]
+        escalate_after: Option<u64>,
+
+        /// Show the transition pending human approval, if any: gate, phases,
+        /// and how long it has waited. Read-only.
+        #[arg(long, conflicts_with_all = ["evaluate", "override_gate", "approve", "reset_phase", "simulate", "backtest", "list", "observe_report", "promote_to", "remind"])]
+        pending: bool,
+
         /// Output structured JSON.
         #[arg(long)]
         json: bool,
     },
 
+    /// Work with gate metrics files.
+    Metrics {
+        #[command(subcommand)]
+        cmd: MetricsCmd,
+    },
+
     /// Sign a persona file.
     Sign {
         /// Path to persona .json file.
@@ -232,13 +703,61 @@ enum Cmd {
         file: String,
 
         /// Path to ed25519 public key.
+        #[arg(long, required_unless_present_any = ["expected_fingerprint", "keyring"])]
+        pubkey: Option<String>,
+
+        /// Directory of `<key_id>.pub` public keys; the signature's key_id
+        /// picks the key (instead of --pubkey).
+        #[arg(long, value_name = "DIR", conflicts_with = "pubkey")]
+        keyring: Option<String>,
+
+        /// Also check the audit chain, checkpoint and state_rev; print a JSON report.
+        #[arg(long, requires = "pubkey", conflicts_with = "keyring")]
+        all_sidecars: bool,
+
+        /// Fail unless the persona's fingerprint (see `amp fingerprint`) equals
+        /// this pinned value; checked with or without --pubkey.
+        #[arg(long, value_name = "FINGERPRINT")]
+        expected_fingerprint: Option<String>,
+    },
+
+    /// Sign or verify one manifest hashing a persona and all its sidecars.
+    Manifest {
+        /// Path to persona .json file.
+        file: String,
+
+        /// Hash persona, state, audit log and checkpoint and sign the manifest.
+        #[arg(long, requires = "key", conflicts_with = "verify")]
+        sign: bool,
+
+        /// Path to ed25519 private key (with --sign).
+        #[arg(long)]
+        key: Option<String>,
+
+        /// Key identifier for rotation.
+        #[arg(long, default_value = "default")]
+        key_id: String,
+
+        /// Check the manifest signature and re-hash every listed file.
+        #[arg(long, requires = "pubkey")]
+        verify: bool,
+
+        /// Path to ed25519 public key (with --verify).
+        #[arg(long)]
+        pubkey: Option<String>,
+
+        /// Manifest path (default: <name>.manifest.json).
+        #[arg(long)]
+        manifest: Option<String>,
+
+        /// Output structured JSON.
         #[arg(long)]
-        pubkey: String,
+        json: bool,
     },
 
     /// Verify audit log hash-chain.
     Audit {
-        /// Path to persona .json file.
+        /// Path to persona .json file (or a bundle with --import-bundle).
         file: String,
 
         /// Verify the hash chain.
@@ -249,6 +768,15 @@ enum Cmd {
         #[arg(long)]
         from: Option<u64>,
 
+        /// With --verify: decrypt every entry with this audit key file and print
+        /// the entries (`log` in JSON). Encrypted entries need the `crypto` feature.
+        #[arg(long, requires = "verify", value_name = "KEY")]
+        key: Option<String>,
+
+        /// Summarize event counts, timestamps and pending-approval timing.
+        #[arg(long, conflicts_with = "verify")]
+        stats: bool,
+
         /// Create an integrity checkpoint.
         #[arg(long)]
         checkpoint_create: bool,
@@ -257,9 +785,9 @@ enum Cmd {
         #[arg(long)]
         checkpoint_verify: bool,
 
-        /// Path to checkpoint file (for --checkpoint-create / --checkpoint-verify).
-        #[arg(long)]
-        checkpoint: Option<String>,
+        /// Add a co-signature to an existing checkpoint (requires --sign-key).
+        #[arg(long, requires = "sign_key")]
+        checkpoint_cosign: bool,
 
         /// Sign the checkpoint with this ed25519 private key.
         #[arg(long)]
@@ -270,12 +798,53 @@ enum Cmd {
         sign_key_id: String,
 
         /// Public key to verify signed checkpoint.
-        #[arg(long)]
+        #[arg(long, conflicts_with = "verify_keys")]
         verify_key: Option<String>,
 
+        /// Comma-separated public keys of checkpoint co-signers.
+        #[arg(long, value_delimiter = ',')]
+        verify_keys: Vec<String>,
+
+        /// Minimum number of valid signatures from distinct keys.
+        #[arg(long, default_value_t = 1)]
+        threshold: usize,
+
+        /// Anchor the checkpoint with an RFC 3161 timestamp from this TSA (requires the `tsa` feature).
+        #[arg(long, requires = "checkpoint_create")]
+        timestamp_url: Option<String>,
+
+        /// Check that the checkpoint's embedded RFC 3161 token was issued for its
+        /// current contents (imprint only; the TSA's signature is not checked;
+        /// requires the `tsa` feature).
+        #[arg(long, requires = "checkpoint_verify")]
+        timestamp_imprint: bool,
+
+        /// Export persona, state, audit log and checkpoint as one bundle file.
+        #[arg(long, value_name = "BUNDLE")]
+        export: Option<String>,
+
+        /// Unpack the bundle given as <FILE> and re-verify its audit chain.
+        #[arg(long, conflicts_with = "export")]
+        import_bundle: bool,
+
+        /// Directory to unpack an imported bundle into (default: the bundle's directory).
+        #[arg(long, requires = "import_bundle")]
+        dest: Option<String>,
+
+        /// Compare state_rev with the audited state mutations (report only).
+        #[arg(long)]
+        reconcile: bool,
+
+        /// With --reconcile: rewrite state_rev to the audited value and audit a Reconcile event.
+        #[arg(long, requires = "reconcile")]
+        fix: bool,
+
         /// Output structured JSON.
         #[arg(long)]
         json: bool,
+
+        #[command(flatten)]
+        sidecars: SidecarArgs,
     },
 
     /// Merge two personas (base + overlay).
@@ -285,6 +854,79 @@ enum Cmd {
 
         /// Overlay persona file.
         overlay: String,
+
+        /// Print canonical JSON (RFC 8785) instead of pretty-printed.
+        #[arg(long)]
+        canonical: bool,
+
+        /// Write the merged persona to a file instead of stdout.
+        #[arg(short, long, conflicts_with = "write")]
+        output: Option<String>,
+
+        /// Overwrite the base file in place (keeps a `<name>.bak` backup).
+        #[arg(long)]
+        write: bool,
+
+        /// With --output/--write: write even if the merged persona fails validation.
+        #[arg(long)]
+        force: bool,
+
+        /// Refuse the merge if it would raise autonomy anywhere
+        /// (full > supervised > readonly).
+        #[arg(long)]
+        no_upgrade_autonomy: bool,
+    },
+
+    /// Print a persona as canonical JSON (RFC 8785: sorted keys, no whitespace).
+    Canonicalize {
+        /// Path to persona .json (or "-" for stdin).
+        file: String,
+    },
+
+    /// Print a stable content hash of a persona (the digest a signature covers).
+    Fingerprint {
+        /// Path to persona .json file.
+        file: String,
+
+        /// Output structured JSON (fingerprint, version, name).
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Print a persona with its `$extends` chain resolved.
+    Resolve {
+        /// Path to persona .json (or "-" for stdin).
+        file: String,
+
+        /// Expand ${VAR} / ${VAR:-default} in string fields from the environment.
+        #[arg(long)]
+        expand_env: bool,
+
+        /// Keep `$ref` sections literal (resolve `$extends` only).
+        #[arg(long)]
+        no_deref: bool,
+    },
+
+    /// Mask or remove fields before sharing a persona externally.
+    Redact {
+        /// Path to persona .json (or "-" for stdin).
+        file: String,
+
+        /// Fields to redact: dotted paths or JSON pointers (comma-separated).
+        #[arg(long, value_delimiter = ',')]
+        fields: Vec<String>,
+
+        /// Add a built-in field set (external: backstory, signature, paths, ext).
+        #[arg(long, value_parser = ["external"])]
+        preset: Option<String>,
+
+        /// Remove fields instead of masking them (required fields are still masked).
+        #[arg(long)]
+        remove: bool,
+
+        /// Write to file instead of stdout.
+        #[arg(short, long)]
+        output: Option<String>,
     },
 
     /// Compare two personas.
@@ -293,6 +935,23 @@ enum Cmd {
         a: String,
         /// Second persona file.
         b: String,
+
+        /// Ignore cosmetic changes: set-like array order and float noise.
+        #[arg(long)]
+        semantic: bool,
+    },
+
+    /// Print the exit-code contract.
+    #[command(after_help = exit::TABLE)]
+    ExitCodes,
+
+    /// Exit 0 if two personas are equal under `diff --semantic` rules, else
+    /// print the diff and exit 1.
+    Eq {
+        /// First persona file.
+        a: String,
+        /// Second persona file.
+        b: String,
     },
 
     /// Import from external format.
@@ -300,9 +959,25 @@ enum Cmd {
         /// Path to external file.
         file: String,
 
-        /// Source format.
+        /// Source format: aieos, zeroclaw, toml, crewai (YAML or JSON).
         #[arg(long)]
         from: String,
+
+        /// CrewAI: the agent to import when the file defines several.
+        #[arg(long)]
+        agent: Option<String>,
+
+        /// AIEOS backstory layout: one paragraph or one bullet per part.
+        #[arg(long, default_value = "sentences", value_parser = ["sentences", "bullets"])]
+        backstory_style: String,
+
+        /// AIEOS: keep irrational fears as psychology notes, not constraints.
+        #[arg(long, conflicts_with = "drop_irrational_fears")]
+        separate_irrational_fears: bool,
+
+        /// AIEOS: drop irrational fears; only rational fears become constraints.
+        #[arg(long)]
+        drop_irrational_fears: bool,
     },
 
     /// Export to external format.
@@ -310,16 +985,38 @@ enum Cmd {
         /// Path to persona .json file.
         file: String,
 
-        /// Target format.
+        /// Target format: aieos, zeroclaw-config, toml.
         #[arg(long)]
         to: String,
     },
 
+    /// Export to an external format and import back, reporting lost fields.
+    Roundtrip {
+        /// Path to persona .json file.
+        file: String,
+
+        /// Format to round-trip through: aieos, toml.
+        #[arg(long)]
+        via: String,
+
+        /// Numbers within this distance count as unchanged.
+        #[arg(long, default_value_t = ampersona_core::canonical::DEFAULT_FLOAT_EPSILON)]
+        tolerance: f64,
+
+        /// Output structured JSON.
+        #[arg(long)]
+        json: bool,
+    },
+
     /// Fleet-level operations.
     Fleet {
         /// Directory containing persona files.
         dir: String,
 
+        /// Walk subdirectories too (skips sidecars and `.ampersona/`).
+        #[arg(long)]
+        recursive: bool,
+
         /// Show status summary.
         #[arg(long)]
         status: bool,
@@ -328,13 +1025,93 @@ enum Cmd {
         #[arg(long)]
         check: bool,
 
+        /// List the personas with a transition pending human approval.
+        #[arg(long)]
+        pending: bool,
+
         /// Output JSON report.
         #[arg(long)]
         json: bool,
 
-        /// Apply authority overlay to all.
-        #[arg(long)]
-        apply_overlay: Option<String>,
+        /// Output RFC 4180 CSV with a header row (--status, --check).
+        #[arg(long, conflicts_with = "json")]
+        csv: bool,
+
+        /// Stream one JSON object per file (NDJSON), flushed as each is
+        /// produced (--status, --check).
+        #[arg(long, conflicts_with_all = ["json", "csv"])]
+        ndjson: bool,
+
+        /// With --check: also verify each persona's signature against --pubkey.
+        #[arg(long, requires_all = ["check", "pubkey"])]
+        verify_sig: bool,
+
+        /// Path to ed25519 public key for --verify-sig.
+        #[arg(long)]
+        pubkey: Option<String>,
+
+        /// With --verify-sig: treat unsigned personas as failures.
+        #[arg(long, requires = "verify_sig")]
+        require_sig: bool,
+
+        /// Only operate on personas matching `field=value` or `field!=value`
+        /// (dotted path, or `phase` for the state file; trailing `*` = prefix).
+        /// Repeat to AND several filters.
+        #[arg(long, value_parser = parse_fleet_filter)]
+        filter: Vec<FleetFilter>,
+
+        /// Apply authority overlay to all.
+        #[arg(long)]
+        apply_overlay: Option<String>,
+
+        /// With --apply-overlay: print per-file diffs without writing.
+        #[arg(long, requires = "apply_overlay")]
+        dry_run: bool,
+
+        /// With --apply-overlay: skip the `<name>.bak` backup of each changed file.
+        #[arg(long, requires = "apply_overlay")]
+        no_backup: bool,
+
+        /// With --apply-overlay: refuse (writing nothing) if any file's
+        /// autonomy would be raised.
+        #[arg(long, requires = "apply_overlay")]
+        no_upgrade_autonomy: bool,
+
+        /// Evaluate every persona's gates against its metrics file in
+        /// --metrics-dir, several personas at a time.
+        #[arg(long, requires = "metrics_dir")]
+        evaluate: bool,
+
+        /// With --evaluate: directory of metrics files named like the
+        /// personas (`agent.json` reads `<metrics-dir>/agent.json`).
+        #[arg(long, requires = "evaluate")]
+        metrics_dir: Option<String>,
+    },
+}
+
+impl Cmd {
+    /// The sidecar overrides of commands that take them.
+    fn sidecar_args(&self) -> Option<&SidecarArgs> {
+        match self {
+            Cmd::Status { sidecars, .. }
+            | Cmd::Elevate { sidecars, .. }
+            | Cmd::Gate { sidecars, .. }
+            | Cmd::Audit { sidecars, .. } => Some(sidecars),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Subcommand)]
+enum MetricsCmd {
+    /// Scaffold a metrics file with every metric the persona's gates read.
+    Template {
+        /// Path to persona .json file.
+        file: String,
+
+        /// Emit the bare metrics JSON (ready for `amp gate --metrics`).
+        #[arg(long)]
+        json: bool,
     },
 }
 
@@ -346,8 +1123,146 @@ fn parse_context_kv(s: &str) -> Result<(String, String), String> {
     Ok((s[..pos].to_string(), s[pos + 1..].to_string()))
 }
 
+/// A `--filter` predicate for `amp fleet`.
+#[derive(Clone)]
+struct FleetFilter {
+    /// Dotted field path (`authority.autonomy`), or `phase` for the state file.
+    field: String,
+    negate: bool,
+    /// Literal to match; a trailing `*` matches by prefix.
+    pattern: String,
+}
+
+/// Parse "field=value" / "field!=value" for --filter.
+fn parse_fleet_filter(s: &str) -> Result<FleetFilter, String> {
+    let (field, negate, pattern) = if let Some(pos) = s.find("!=") {
+        (&s[..pos], true, &s[pos + 2..])
+    } else if let Some(pos) = s.find('=') {
+        (&s[..pos], false, &s[pos + 1..])
+    } else {
+        return Err(format!("invalid filter: no `=` or `!=` found in `{s}`"));
+    };
+    if field.is_empty() {
+        return Err(format!("invalid filter: empty field in `{s}`"));
+    }
+    Ok(FleetFilter {
+        field: field.to_string(),
+        negate,
+        pattern: pattern.to_string(),
+    })
+}
+
+impl FleetFilter {
+    /// Missing fields never equal the pattern, so they pass `!=` filters.
+    fn matches(&self, data: &serde_json::Value, file: &str) -> bool {
+        let actual = if self.field == "phase" {
+            stored_state(file).and_then(|s| s.current_phase)
+        } else {
+            let pointer = format!("/{}", self.field.replace('.', "/"));
+            data.pointer(&pointer).map(|v| match v {
+                serde_json::Value::String(s) => s.clone(),
+                other => other.to_string(),
+            })
+        };
+        let hit = actual.is_some_and(|a| match self.pattern.strip_suffix('*') {
+            Some(prefix) => a.starts_with(prefix),
+            None => a == self.pattern,
+        });
+        hit != self.negate
+    }
+}
+
 // ── CmdExit: structured exit for commands with semantic exit codes ──
 
+/// The exit-code contract scripts branch on (`amp exit-codes`). Values are stable.
+mod exit {
+    pub const OK: i32 = 0;
+    /// Authority Deny.
+    pub const DENY: i32 = 1;
+    /// No gate fired; failed verify/consistency check; `amp eq` mismatch; any
+    /// other error. Shares its value with [`DENY`].
+    pub const FAILURE: i32 = 1;
+    /// Authority NeedsApproval; gate decision `pending_human`.
+    pub const PENDING: i32 = 2;
+    /// Invalid command line or logging setup (clap's own usage code).
+    pub const USAGE: i32 = 2;
+    /// Structured error with an `E_*` code (a JSON body under `--json`).
+    pub const STRUCTURED_ERROR: i32 = 3;
+    /// State lock held by another writer (`E_LOCK_CONTENTION`).
+    pub const LOCK_CONTENTION: i32 = 5;
+
+    /// Printed by `amp exit-codes` and `amp help exit-codes`.
+    pub const TABLE: &str = "\
+Exit codes (stable; scripts may branch on them):
+  0  success; authority Allow; gate transition
+  1  authority Deny; no gate fired; failed check/verify; eq mismatch; other errors
+  2  authority NeedsApproval; gate pending_human; invalid command line
+  3  structured error with an E_* code (JSON body with --json)
+  4  reserved
+  5  state lock held by another writer (E_LOCK_CONTENTION)";
+}
+
+/// Set from `--compact` before any command runs.
+static COMPACT: AtomicBool = AtomicBool::new(false);
+
+/// Set from `--backend sqlite` before any command runs.
+static SQLITE_BACKEND: AtomicBool = AtomicBool::new(false);
+
+/// Set from `--state-dir` before any command runs.
+static STATE_DIR: OnceLock<std::path::PathBuf> = OnceLock::new();
+
+/// Default sidecars of the persona at `file`: next to it, or mirrored under
+/// `--state-dir` (whose subdirectory is created by the first write).
+fn default_sidecars(file: &str) -> ampersona_core::sidecar::Sidecars {
+    use ampersona_core::sidecar::Sidecars;
+
+    match STATE_DIR.get() {
+        Some(state_dir) => Sidecars::in_state_dir(file, state_dir),
+        None => Sidecars::for_persona(file),
+    }
+}
+
+/// The state store for `paths` under the selected `--backend`. The SQLite
+/// backend keys rows by the state path, so overrides like `--state` still apply
+/// (`--audit` is refused up front).
+fn state_store(paths: &ampersona_core::sidecar::Sidecars) -> Result<Box<dyn StateStore>> {
+    #[cfg(feature = "sqlite")]
+    if SQLITE_BACKEND.load(Ordering::Relaxed) {
+        return Ok(Box::new(sqlite_store(&paths.state)?));
+    }
+    Ok(Box::new(FileStateStore::new(&paths.state, &paths.audit)))
+}
+
+/// The SQLite rows of the state file at `state_path`, in the database of its
+/// workspace: `--state-dir` when set, else the state file's directory.
+#[cfg(feature = "sqlite")]
+fn sqlite_store(
+    state_path: &str,
+) -> ampersona_engine::error::Result<ampersona_engine::state::sqlite::SqliteStateStore> {
+    ampersona_engine::state::sqlite::SqliteStateStore::for_state(
+        state_path,
+        STATE_DIR.get().map(|d| d.as_path()),
+    )
+}
+
+/// Stored phase state of the persona at `file`; unreadable state counts as none.
+fn stored_state(file: &str) -> Option<ampersona_core::state::PhaseState> {
+    state_store(&default_sidecars(file))
+        .ok()?
+        .load()
+        .ok()
+        .flatten()
+}
+
+/// Serialize JSON for stdout: indented by default, one line with `--compact`.
+fn to_json<T: serde::Serialize + ?Sized>(value: &T) -> serde_json::Result<String> {
+    if COMPACT.load(Ordering::Relaxed) {
+        serde_json::to_string(value)
+    } else {
+        serde_json::to_string_pretty(value)
+    }
+}
+
 enum CmdExit {
     Ok,
     Code(i32),
@@ -361,34 +1276,131 @@ enum CmdExit {
 
 fn main() {
     let cli = Cli::parse();
+    let lock_timeout = Duration::from_secs(cli.lock_timeout);
+    let config = cli.config.clone();
+    COMPACT.store(cli.compact, Ordering::Relaxed);
+    if cli.backend == "sqlite" && !cfg!(feature = "sqlite") {
+        eprintln!("error: --backend sqlite requires a build with the `sqlite` feature");
+        std::process::exit(exit::USAGE);
+    }
+    if cli.backend == "sqlite" && cli.cmd.sidecar_args().is_some_and(|s| s.audit.is_some()) {
+        eprintln!("error: --audit cannot be used with --backend sqlite; the audit log lives in the database");
+        std::process::exit(exit::USAGE);
+    }
+    SQLITE_BACKEND.store(cli.backend == "sqlite", Ordering::Relaxed);
+    if let Some(dir) = &cli.state_dir {
+        let _ = STATE_DIR.set(dir.into());
+    }
+
+    #[cfg(feature = "otel")]
+    let telemetry = otel::init();
+    #[cfg(feature = "otel")]
+    let spans = telemetry
+        .as_ref()
+        .ok()
+        .and_then(Option::as_ref)
+        .map(otel::Telemetry::layer);
+    #[cfg(not(feature = "otel"))]
+    let spans = tracing_subscriber::layer::Identity::new();
+    if let Err(e) = logging::init(cli.log_level.as_deref(), &cli.log_format, spans) {
+        eprintln!("error: {e:#}");
+        std::process::exit(exit::USAGE);
+    }
+    #[cfg(feature = "otel")]
+    let telemetry = telemetry.unwrap_or_else(|e| {
+        tracing::warn!("{}: {e:#}", otel::ENDPOINT_ENV);
+        None
+    });
 
     let result = match cli.cmd {
         Cmd::Authority {
             file,
             check,
+            matrix,
             json,
             path,
             context,
             context_json,
-        } => cmd_authority(&file, &check, json, path, context, context_json),
+            trace,
+            strict_vocab,
+            strict_review,
+            expand_env,
+            show_overlay_effect,
+            what_if,
+            trust,
+        } => cmd_authority(AuthorityOpts {
+            config,
+            file,
+            action: check,
+            matrix,
+            json_out: json,
+            path,
+            context_kvs: context,
+            context_json,
+            trace,
+            strict_vocab,
+            strict_review,
+            expand_env,
+            show_overlay_effect,
+            what_if,
+            trust,
+        }),
 
         Cmd::Gate {
             file,
+            sidecars,
+            trust,
             evaluate,
             metrics,
+            force_reeval,
+            no_hooks,
+            hook_timeout,
             override_gate,
             reason,
             approver,
             approve,
+            reset_phase,
+            force,
+            simulate,
+            backtest,
+            list,
+            observe_report,
+            promote_to,
+            promote_gates,
+            all_observe,
+            remind,
+            remind_after,
+            escalate_after,
+            pending,
             json,
         } => cmd_gate(GateOpts {
             file,
+            sidecars,
+            trust,
             evaluate,
             metrics_file: metrics,
+            force_reeval,
+            no_hooks,
+            hook_timeout: Duration::from_secs(hook_timeout),
             override_gate,
             reason,
             approver,
             approve,
+            reset_phase,
+            force,
+            simulate,
+            backtest,
+            list,
+            observe_report,
+            promote_to,
+            promote_gates,
+            all_observe,
+            reminder: remind.then(|| ReminderPolicy {
+                remind_after: Duration::from_secs(remind_after),
+                escalate_after: escalate_after.map(Duration::from_secs),
+            }),
+            pending,
+            lock_timeout,
             json_out: json,
         }),
 
@@ -396,38 +1408,68 @@ fn main() {
             file,
             verify,
             from,
+            key,
+            stats,
             checkpoint_create,
             checkpoint_verify,
-            checkpoint,
+            checkpoint_cosign,
             sign_key,
             sign_key_id,
             verify_key,
+            verify_keys,
+            threshold,
+            timestamp_url,
+            timestamp_imprint,
+            export,
+            import_bundle,
+            dest,
+            reconcile,
+            fix,
             json,
+            sidecars,
         } => cmd_audit(AuditOpts {
+            sidecars: sidecars.resolve(&file),
             file,
             verify,
             from,
+            audit_key: key,
+            stats,
             checkpoint_create,
             checkpoint_verify,
-            checkpoint_path: checkpoint,
+            checkpoint_cosign,
             sign_key,
             sign_key_id,
-            verify_key,
+            verify_keys: verify_key.into_iter().chain(verify_keys).collect(),
+            threshold,
+            timestamp_url,
+            timestamp_imprint,
+            export,
+            import_bundle,
+            dest,
+            reconcile,
+            fix,
+            lock_timeout,
             json_out: json,
         }),
 
-        other => match run_other(other) {
+        Cmd::Eq { a, b } => cmd_eq(&a, &b),
+
+        other => match run_other(other, lock_timeout, config.as_deref()) {
             Ok(()) => CmdExit::Ok,
             Err(e) => CmdExit::Err(e),
         },
     };
 
+    // process::exit skips destructors; flush pending spans first.
+    #[cfg(feature = "otel")]
+    drop(telemetry);
+
     match result {
         CmdExit::Ok => {}
         CmdExit::Code(n) => std::process::exit(n),
         CmdExit::Err(e) => {
-            eprintln!("error: {e:#}");
-            std::process::exit(1);
+            error!("{e:#}");
+            std::process::exit(error_exit_code(&e));
         }
         CmdExit::JsonErr {
             code,
@@ -440,31 +1482,77 @@ fn main() {
                     "code": code,
                     "message": message,
                 });
-                println!("{}", serde_json::to_string_pretty(&err).unwrap());
+                println!("{}", to_json(&err).unwrap());
             } else {
-                eprintln!("error: {message}");
+                error!(code, "{message}");
             }
-            std::process::exit(3);
+            std::process::exit(if code == "E_LOCK_CONTENTION" {
+                exit::LOCK_CONTENTION
+            } else {
+                exit::STRUCTURED_ERROR
+            });
         }
     }
 }
 
+/// Exit 5 when a state lock couldn't be acquired, 1 for any other error.
+fn error_exit_code(e: &anyhow::Error) -> i32 {
+    match e.downcast_ref::<ampersona_engine::error::EngineError>() {
+        Some(ampersona_engine::error::EngineError::LockContention { .. }) => exit::LOCK_CONTENTION,
+        _ => exit::FAILURE,
+    }
+}
+
 /// Dispatch commands that return Result<()> (no special exit codes).
-fn run_other(cmd: Cmd) -> Result<()> {
+fn run_other(cmd: Cmd, lock_timeout: Duration, config: Option<&str>) -> Result<()> {
     match cmd {
         Cmd::Prompt {
             file,
             toon,
             sections,
-        } => cmd_prompt(&file, toon, &sections),
-        Cmd::Validate { files } => cmd_validate(&files),
+            with_state,
+            expand_env,
+            trust,
+        } => cmd_prompt(PromptOpts {
+            file,
+            toon_out: toon,
+            sections,
+            with_state,
+            expand_env,
+            trust,
+            config,
+        }),
+        Cmd::ExitCodes => {
+            println!("{}", exit::TABLE);
+            Ok(())
+        }
+        Cmd::Validate {
+            files,
+            expand_env,
+            strict_unknown_fields,
+            as_overlay,
+        } => cmd_validate(&files, expand_env, strict_unknown_fields, as_overlay),
         Cmd::New {
             template,
             name,
+            seed,
             output,
-        } => cmd_new(&template, name.as_deref(), output.as_deref()),
+        } => cmd_new(&template, name, seed, output.as_deref()),
         Cmd::Templates => cmd_templates(),
-        Cmd::List { dir } => cmd_list(&dir),
+        Cmd::Schema {
+            schema_version,
+            out,
+        } => cmd_schema(&schema_version, out.as_deref()),
+        Cmd::Actions { file, json } => cmd_actions(file.as_deref(), json),
+        Cmd::List {
+            dir,
+            json,
+            sort,
+            reverse,
+            recursive,
+        } => cmd_list(&dir, json, sort.as_deref(), reverse, recursive),
+        Cmd::Index { dir, out } => cmd_index(&dir, out.as_deref()),
+        Cmd::Doctor { dir, fix, json } => cmd_doctor(&dir, fix, json),
         Cmd::Register {
             file,
             project,
@@ -473,29 +1561,246 @@ fn run_other(cmd: Cmd) -> Result<()> {
             prompt,
             toon,
             rpc,
-        } => cmd_register(&file, &project, &program, &model, prompt, toon, rpc),
-        Cmd::Init { workspace } => cmd_init(workspace),
-        Cmd::Check { file, json, strict } => cmd_check(&file, json, strict),
-        Cmd::Migrate { files } => cmd_migrate(&files),
-        Cmd::Status { file, json, drift } => cmd_status(&file, json, drift),
+            tools,
+            strict,
+            batch,
+            ndjson,
+            trust,
+        } => cmd_register(RegisterOpts {
+            file,
+            project,
+            program,
+            model,
+            include_prompt: prompt,
+            toon,
+            rpc,
+            tools,
+            strict,
+            batch,
+            ndjson,
+            trust,
+        }),
+        Cmd::Init {
+            workspace,
+            template,
+            template_dir,
+            name,
+            autonomy,
+        } => cmd_init(InitOpts {
+            workspace,
+            template,
+            template_dir,
+            name,
+            autonomy,
+        }),
+        Cmd::Check {
+            file,
+            json,
+            strict,
+            expand_env,
+            fix,
+            strict_unknown_fields,
+        } => cmd_check(CheckOpts {
+            file,
+            json_out: json,
+            strict,
+            expand_env,
+            fix,
+            strict_unknown: strict_unknown_fields,
+        }),
+        Cmd::Migrate {
+            files,
+            canonical,
+            dry_run,
+            backup,
+        } => cmd_migrate(&files, canonical, dry_run, backup),
+        Cmd::MigrateBackend {
+            dir,
+            from,
+            to,
+            recursive,
+            json,
+        } => cmd_migrate_backend(&dir, &from, &to, recursive, json),
+        Cmd::Status {
+            file,
+            json,
+            drift,
+            since,
+            until,
+            limit,
+            plain,
+            sidecars,
+        } => cmd_status(
+            &file,
+            &sidecars.resolve(&file),
+            json,
+            drift.then_some(DriftWindow {
+                since,
+                until,
+                limit,
+                plain,
+            }),
+        ),
+        Cmd::Report {
+            file,
+            pubkey,
+            format,
+        } => cmd_report(&file, pubkey.as_deref(), &format, config),
         Cmd::Elevate {
             file,
             elevation,
             reason,
-        } => cmd_elevate(&file, &elevation, &reason),
+            ttl,
+            sidecars,
+        } => cmd_elevate(
+            &file,
+            &sidecars.resolve(&file),
+            &elevation,
+            &reason,
+            ttl,
+            lock_timeout,
+        ),
+        Cmd::Metrics {
+            cmd: MetricsCmd::Template { file, json },
+        } => cmd_metrics_template(&file, json),
         Cmd::Sign { file, key, key_id } => cmd_sign(&file, &key, &key_id),
-        Cmd::Verify { file, pubkey } => cmd_verify(&file, &pubkey),
-        Cmd::Compose { base, overlay } => cmd_compose(&base, &overlay),
-        Cmd::Diff { a, b } => cmd_diff(&a, &b),
-        Cmd::Import { file, from } => cmd_import(&file, &from),
+        Cmd::Verify {
+            file,
+            pubkey: Some(pubkey),
+            keyring: None,
+            all_sidecars: true,
+            expected_fingerprint,
+        } => cmd_verify_sidecars(&file, &pubkey, expected_fingerprint.as_deref()),
+        Cmd::Verify {
+            file,
+            pubkey,
+            keyring,
+            expected_fingerprint,
+            ..
+        } => cmd_verify(
+            &file,
+            pubkey.as_deref(),
+            keyring.as_deref(),
+            expected_fingerprint.as_deref(),
+        ),
+        Cmd::Manifest {
+            file,
+            sign,
+            key,
+            key_id,
+            verify,
+            pubkey,
+            manifest,
+            json,
+        } => {
+            let manifest = manifest
+                .unwrap_or_else(|| ampersona_core::sidecar::sidecar_path(&file, ".manifest.json"));
+            match (sign, verify, key, pubkey) {
+                (true, _, Some(key), _) => cmd_manifest_sign(&file, &manifest, &key, &key_id, json),
+                (_, true, _, Some(pubkey)) => cmd_manifest_verify(&file, &manifest, &pubkey, json),
+                _ => bail!("pass --sign --key <KEY> or --verify --pubkey <KEY>"),
+            }
+        }
+        Cmd::Compose {
+            base,
+            overlay,
+            canonical,
+            output,
+            write,
+            force,
+            no_upgrade_autonomy,
+        } => cmd_compose(ComposeOpts {
+            base,
+            overlay,
+            canonical,
+            output,
+            write,
+            force,
+            no_upgrade_autonomy,
+        }),
+        Cmd::Canonicalize { file } => cmd_canonicalize(&file),
+        Cmd::Fingerprint { file, json } => cmd_fingerprint(&file, json),
+        Cmd::Resolve {
+            file,
+            expand_env,
+            no_deref,
+        } => cmd_resolve(
+            &file,
+            ampersona_core::prompt::LoadOptions {
+                deref: !no_deref,
+                expand_env,
+            },
+        ),
+        Cmd::Redact {
+            file,
+            fields,
+            preset,
+            remove,
+            output,
+        } => cmd_redact(&file, fields, preset.as_deref(), remove, output.as_deref()),
+        Cmd::Diff { a, b, semantic } => cmd_diff(&a, &b, semantic),
+        Cmd::Import {
+            file,
+            from,
+            agent,
+            backstory_style,
+            separate_irrational_fears,
+            drop_irrational_fears,
+        } => {
+            let fears = if separate_irrational_fears {
+                ampersona_engine::convert::aieos::FearHandling::Separate
+            } else if drop_irrational_fears {
+                ampersona_engine::convert::aieos::FearHandling::DropIrrational
+            } else {
+                ampersona_engine::convert::aieos::FearHandling::Merge
+            };
+            cmd_import(&file, &from, agent.as_deref(), &backstory_style, fears)
+        }
         Cmd::Export { file, to } => cmd_export(&file, &to),
+        Cmd::Roundtrip {
+            file,
+            via,
+            tolerance,
+            json,
+        } => cmd_roundtrip(&file, &via, tolerance, json),
         Cmd::Fleet {
             dir,
+            recursive,
             status,
             check,
+            pending,
             json,
+            csv,
+            ndjson,
+            verify_sig,
+            pubkey,
+            require_sig,
+            filter,
+            apply_overlay,
+            dry_run,
+            no_backup,
+            no_upgrade_autonomy,
+            evaluate: _,
+            metrics_dir,
+        } => cmd_fleet(FleetOpts {
+            dir,
+            recursive,
+            status,
+            check,
+            pending,
+            json_out: json,
+            csv_out: csv,
+            ndjson,
+            verify_key: pubkey.filter(|_| verify_sig),
+            require_sig,
+            filters: filter,
             apply_overlay,
-        } => cmd_fleet(&dir, status, check, json, apply_overlay),
+            dry_run,
+            no_backup,
+            no_upgrade_autonomy,
+            metrics_dir,
+            lock_timeout,
+        }),
         // Authority, Gate, Audit are handled in main() directly
         _ => unreachable!(),
     }
@@ -504,39 +1809,157 @@ fn run_other(cmd: Cmd) -> Result<()> {
 // ── Existing commands (migrated from v0.2) ──────────────────────
 
 fn read_persona(file: &str) -> Result<serde_json::Value> {
+    read_persona_with(file, Default::default())
+}
+
+fn read_persona_with(
+    file: &str,
+    opts: ampersona_core::prompt::LoadOptions,
+) -> Result<serde_json::Value> {
     if file == "-" {
         let mut buf = String::new();
         io::stdin().read_to_string(&mut buf)?;
-        Ok(serde_json::from_str(&buf)?)
+        // `$extends` and `$ref` from stdin resolve relative to the working directory.
+        ampersona_core::prompt::resolve_with(serde_json::from_str(&buf)?, file, opts)
     } else {
-        ampersona_core::prompt::load_persona(file)
+        ampersona_core::prompt::load_persona_with(file, opts)
     }
 }
 
-fn cmd_prompt(file: &str, toon_out: bool, sections: &[String]) -> Result<()> {
-    let data = read_persona(file)?;
+/// [`read_persona_with`], refusing the document first if it fails `trust`.
+fn read_trusted_persona(
+    file: &str,
+    opts: ampersona_core::prompt::LoadOptions,
+    trust: &TrustArgs,
+) -> Result<serde_json::Value> {
+    use anyhow::Context;
+
+    if !trust.verify_on_load {
+        return read_persona_with(file, opts);
+    }
+    let content = if file == "-" {
+        let mut buf = String::new();
+        io::stdin().read_to_string(&mut buf)?;
+        buf
+    } else {
+        std::fs::read_to_string(file).with_context(|| format!("cannot read {file}"))?
+    };
+    let data = serde_json::from_str(&content).with_context(|| format!("{file}: invalid JSON"))?;
+    trust.check(file, &data)?;
+    ampersona_core::prompt::resolve_with(data, file, opts)
+}
+
+struct PromptOpts<'a> {
+    file: String,
+    toon_out: bool,
+    sections: Vec<String>,
+    with_state: bool,
+    expand_env: bool,
+    trust: TrustArgs,
+    config: Option<&'a str>,
+}
+
+fn cmd_prompt(opts: PromptOpts) -> Result<()> {
+    let PromptOpts {
+        file,
+        toon_out,
+        sections,
+        with_state,
+        expand_env,
+        trust,
+        config,
+    } = opts;
+    if with_state && file == "-" {
+        bail!("--with-state needs a persona file path to locate its .state.json");
+    }
+    let data = read_trusted_persona(
+        &file,
+        ampersona_core::prompt::LoadOptions {
+            expand_env,
+            ..Default::default()
+        },
+        &trust,
+    )?;
     if toon_out {
         println!("{}", ampersona_core::prompt::to_toon(&data)?);
     } else {
+        let state = if with_state {
+            Some(prompt_state(&file, &data, config)?)
+        } else {
+            None
+        };
         print!(
             "{}",
-            ampersona_core::prompt::to_system_prompt(&data, sections)
+            ampersona_core::prompt::to_system_prompt(&data, &sections, state.as_ref())
         );
     }
     Ok(())
 }
 
-fn cmd_validate(files: &[String]) -> Result<()> {
-    let (passed, failed) = ampersona_core::schema::validate_files(files)?;
-    eprintln!("\n{passed} passed, {failed} failed");
+/// Phase and resolved authority for `amp prompt --with-state`.
+///
+/// Resolution mirrors `amp authority`: workspace defaults, phase and persona
+/// layers, then active elevations, then the gate overlay recorded in state.
+fn prompt_state(
+    file: &str,
+    data: &serde_json::Value,
+    config: Option<&str>,
+) -> Result<ampersona_core::prompt::PromptState> {
+    use ampersona_engine::policy::precedence;
+
+    let paths = default_sidecars(file);
+    let state = state_store(&paths)?
+        .load()?
+        .ok_or_else(|| anyhow::anyhow!("no phase state at {}", paths.state))?;
+    let persona: ampersona_core::spec::Persona = serde_json::from_value(data.clone())?;
+    let authority = persona
+        .authority
+        .as_ref()
+        .ok_or_else(|| anyhow::anyhow!("{file}: no authority section defined"))?;
+
+    let workspace_defaults = precedence::load_defaults(config);
+    let layers: Vec<_> = precedence::base_layers(
+        workspace_defaults.as_ref(),
+        persona.phase_authority(state.current_phase.as_deref()),
+        authority,
+    )
+    .into_iter()
+    .map(|(_, layer)| layer)
+    .collect();
+    let mut resolved = precedence::resolve_with_elevations(
+        &layers,
+        &state.active_elevations,
+        authority.elevations.as_deref().unwrap_or(&[]),
+    );
+    if let Some(overlay) = &state.active_overlay {
+        resolved = precedence::apply_overlay(&resolved, overlay);
+    }
+
+    Ok(ampersona_core::prompt::PromptState {
+        phase: state.current_phase,
+        last_gate: state.last_transition.map(|t| t.gate_id),
+        authority: resolved,
+    })
+}
+
+fn cmd_validate(
+    files: &[String],
+    expand_env: bool,
+    strict_unknown: bool,
+    as_overlay: bool,
+) -> Result<()> {
+    let (passed, failed) =
+        ampersona_core::schema::validate_files(files, expand_env, strict_unknown, as_overlay)?;
+    info!("{passed} passed, {failed} failed");
     if failed > 0 {
         bail!("{failed} file(s) failed validation");
     }
     Ok(())
 }
 
-fn cmd_new(template: &str, name: Option<&str>, output: Option<&str>) -> Result<()> {
-    let persona = ampersona_core::templates::generate(template, name).ok_or_else(|| {
+/// Generate a built-in template, listing the available ones on a miss.
+fn builtin_template(template: &str, name: Option<&str>) -> Result<serde_json::Value> {
+    ampersona_core::templates::generate(template, name).ok_or_else(|| {
         let available: Vec<_> = ampersona_core::templates::list_templates()
             .iter()
             .map(|(n, _)| *n)
@@ -545,19 +1968,43 @@ fn cmd_new(template: &str, name: Option<&str>, output: Option<&str>) -> Result<(
             "unknown template \"{template}\". available: {}",
             available.join(", ")
         )
-    })?;
+    })
+}
 
-    let json = serde_json::to_string_pretty(&persona)?;
+fn cmd_new(
+    template: &str,
+    name: Option<String>,
+    seed: Option<u64>,
+    output: Option<&str>,
+) -> Result<()> {
+    let name = name.unwrap_or_else(|| {
+        let seed = seed.unwrap_or_else(entropy_seed);
+        ampersona_core::templates::generated_name(seed)
+    });
+    let persona = builtin_template(template, Some(&name))?;
 
     if let Some(path) = output {
-        std::fs::write(path, &json)?;
-        eprintln!("wrote {path}");
+        std::fs::write(path, serde_json::to_string_pretty(&persona)?)?;
+        info!("wrote {path}");
     } else {
-        println!("{json}");
+        println!("{}", to_json(&persona)?);
     }
     Ok(())
 }
 
+/// A per-process random seed, from the std hasher's random keys.
+fn entropy_seed() -> u64 {
+    use std::hash::{BuildHasher, Hasher};
+    let mut hasher = std::collections::hash_map::RandomState::new().build_hasher();
+    hasher.write_u128(
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or_default(),
+    );
+    hasher.finish()
+}
+
 fn cmd_templates() -> Result<()> {
     for (name, desc) in ampersona_core::templates::list_templates() {
         println!("  {name:<12} {desc}");
@@ -565,83 +2012,556 @@ fn cmd_templates() -> Result<()> {
     Ok(())
 }
 
-fn cmd_list(dir: &str) -> Result<()> {
-    let rows = ampersona_core::list::scan_dir(dir)?;
-    ampersona_core::list::print_table(&rows);
+fn cmd_schema(version: &str, out: Option<&str>) -> Result<()> {
+    let schema = ampersona_core::schema::embedded_schema(version);
+    if let Some(path) = out {
+        std::fs::write(path, schema)?;
+        info!("wrote {path}");
+    } else {
+        print!("{schema}");
+    }
     Ok(())
 }
 
-fn cmd_register(
-    file: &str,
-    project: &str,
-    program: &str,
-    model: &str,
-    include_prompt: bool,
-    toon: bool,
-    rpc: bool,
+fn cmd_actions(file: Option<&str>, json_out: bool) -> Result<()> {
+    use ampersona_core::actions::{ActionId, BuiltinAction};
+
+    // (action, vendor, list it appears in) for non-builtin references in the persona
+    let mut referenced: Vec<(ActionId, &'static str)> = Vec::new();
+    if let Some(f) = file {
+        let data = read_persona(f)?;
+        let authority: Option<ampersona_core::spec::authority::Authority> = data
+            .get("authority")
+            .map(|a| serde_json::from_value(a.clone()))
+            .transpose()
+            .map_err(|e| anyhow::anyhow!("{f}: invalid authority: {e}"))?;
+        if let Some(actions) = authority.as_ref().and_then(|a| a.actions.as_ref()) {
+            for id in actions.allow.iter().flatten() {
+                referenced.push((id.clone(), "allow"));
+            }
+            for entry in actions.deny.iter().flatten() {
+                referenced.push((entry.action_id().clone(), "deny"));
+            }
+        }
+        referenced.retain(|(id, _)| id.is_custom());
+    }
+
+    if json_out {
+        let builtin: Vec<serde_json::Value> = BuiltinAction::all()
+            .iter()
+            .map(|b| {
+                serde_json::json!({
+                    "action": b.as_str(),
+                    "namespace": "builtin",
+                    "description": b.description(),
+                })
+            })
+            .collect();
+        let custom: Vec<serde_json::Value> = referenced
+            .iter()
+            .map(|(id, list)| match id {
+                ActionId::Custom { vendor, action } if vendor == "_unknown" => {
+                    serde_json::json!({
+                        "action": action,
+                        "vendor": null,
+                        "list": list,
+                        "known": false,
+                        "suggestion": BuiltinAction::suggest(action),
+                    })
+                }
+                ActionId::Custom { vendor, action } => serde_json::json!({
+                    "action": id.to_string(),
+                    "vendor": vendor,
+                    "name": action,
+                    "list": list,
+                    "known": true,
+                }),
+                ActionId::Builtin(_) => unreachable!(),
+            })
+            .collect();
+        let output = serde_json::json!({
+            "builtin": builtin,
+            "custom": custom,
+            "custom_format": "custom:<vendor>/<action>",
+        });
+        println!("{}", to_json(&output)?);
+        return Ok(());
+    }
+
+    println!("Builtin actions:");
+    for b in BuiltinAction::all() {
+        println!("  {:<24} {}", b.as_str(), b.description());
+    }
+    if let Some(f) = file {
+        println!();
+        println!("Custom actions referenced by {f}:");
+        if referenced.is_empty() {
+            println!("  (none)");
+        }
+        for (id, list) in &referenced {
+            match id {
+                ActionId::Custom { vendor, action } if vendor == "_unknown" => {
+                    let hint = BuiltinAction::suggest(action)
+                        .map(|s| format!(" — did you mean '{s}'?"))
+                        .unwrap_or_default();
+                    println!("  {action:<24} [{list}] not in vocabulary{hint}");
+                }
+                _ => println!("  {:<24} [{list}]", id.to_string()),
+            }
+        }
+    }
+    println!();
+    println!("Vendor-specific actions use the form custom:<vendor>/<action>.");
+    Ok(())
+}
+
+fn cmd_list(
+    dir: &str,
+    json_out: bool,
+    sort: Option<&str>,
+    reverse: bool,
+    recursive: bool,
 ) -> Result<()> {
-    let data = read_persona(file)?;
-    let include_prompt = include_prompt || toon;
-    let args =
-        ampersona_core::register::build_args(&data, project, program, model, include_prompt, toon)?;
-    let output = if rpc {
-        ampersona_core::register::wrap_rpc(args)
+    let mut rows = ampersona_core::list::scan_dir(dir, recursive)?;
+    // scan_dir looks next to each persona; under --state-dir look there.
+    if STATE_DIR.get().is_some() {
+        for row in &mut rows {
+            let file = std::path::Path::new(dir).join(&row.file);
+            let file = file.to_string_lossy();
+            row.phase = stored_state(&file)
+                .and_then(|s| s.current_phase)
+                .unwrap_or_else(|| "-".into());
+            row.has_audit = std::path::Path::new(&default_sidecars(&file).audit).exists();
+        }
+    }
+    if let Some(column) = sort {
+        ampersona_core::list::sort_rows(&mut rows, column, reverse)?;
+    } else if reverse {
+        rows.reverse();
+    }
+    if json_out {
+        println!("{}", to_json(&rows)?);
     } else {
-        args
-    };
-    println!("{}", serde_json::to_string_pretty(&output)?);
+        ampersona_core::list::print_table(&rows);
+    }
     Ok(())
 }
 
-// ── New v1.0 commands ───────────────────────────────────────────
-
-fn cmd_init(workspace: bool) -> Result<()> {
-    if workspace {
-        std::fs::create_dir_all(".ampersona")?;
-        let defaults = serde_json::json!({
-            "authority": {
-                "autonomy": "supervised"
+/// Entries follow `amp list --recursive`; each adds the persona's
+/// fingerprint so consumers can spot content changes without re-reading files.
+fn cmd_index(dir: &str, out: Option<&str>) -> Result<()> {
+    let rows = ampersona_core::list::scan_dir(dir, true)?;
+    // A previous index written inside `dir` is not a persona.
+    let previous = out.and_then(|f| std::fs::canonicalize(f).ok());
+    let mut personas = Vec::new();
+    for row in rows {
+        let path = std::path::Path::new(dir).join(&row.file);
+        if previous.is_some() && std::fs::canonicalize(&path).ok() == previous {
+            continue;
+        }
+        let content = std::fs::read_to_string(&path)?;
+        let data: serde_json::Value = serde_json::from_str(&content)?;
+        let fingerprint = match ampersona_sign::sign::fingerprint(&data) {
+            Ok(fp) => fp,
+            Err(e) => {
+                warn!("skip {}: {e}", path.display());
+                continue;
             }
-        });
-        let json = serde_json::to_string_pretty(&defaults)?;
-        std::fs::write(".ampersona/defaults.json", &json)?;
-        eprintln!("created .ampersona/defaults.json");
-    } else {
-        let persona = ampersona_core::templates::generate("worker", Some("NewAgent")).unwrap();
-        let json = serde_json::to_string_pretty(&persona)?;
-        std::fs::write("persona.json", &json)?;
-        eprintln!("created persona.json (edit to customize)");
+        };
+        personas.push(serde_json::json!({
+            "file": row.file,
+            "name": row.name,
+            "role": row.role,
+            "autonomy": row.autonomy,
+            "phase": row.phase,
+            "signed": row.signed,
+            "fingerprint": fingerprint,
+        }));
+    }
+    let count = personas.len();
+    let index = serde_json::json!({
+        "index_version": "1",
+        "root": dir,
+        "personas": personas,
+    });
+
+    match out {
+        Some(file) => {
+            let json = serde_json::to_string_pretty(&index)?;
+            ampersona_engine::state::atomic::atomic_write(file, json.as_bytes())?;
+            info!("wrote {file} ({count} personas)");
+        }
+        None => println!("{}", to_json(&index)?),
     }
     Ok(())
 }
 
-fn cmd_check(file: &str, json_out: bool, strict: bool) -> Result<()> {
-    let content =
-        std::fs::read_to_string(file).map_err(|e| anyhow::anyhow!("cannot read {file}: {e}"))?;
-    let data: serde_json::Value =
-        serde_json::from_str(&content).map_err(|e| anyhow::anyhow!("{file}: invalid JSON: {e}"))?;
+/// Exits 1 while any error-level finding remains.
+fn cmd_doctor(dir: &str, fix: bool, json_out: bool) -> Result<()> {
+    use ampersona_engine::doctor::{apply_safe_fixes, diagnose, Severity};
 
-    let report = ampersona_core::schema::check(&data, file, strict);
+    let mut findings = diagnose(dir)?;
+    let fixed = if fix {
+        apply_safe_fixes(&findings)?
+    } else {
+        Vec::new()
+    };
+    findings.retain(|f| !fixed.contains(f));
+    let errors = findings
+        .iter()
+        .filter(|f| f.severity == Severity::Error)
+        .count();
 
     if json_out {
-        println!("{}", serde_json::to_string_pretty(&report)?);
+        let report = serde_json::json!({
+            "dir": dir,
+            "healthy": errors == 0,
+            "findings": findings,
+            "fixed": fixed,
+        });
+        println!("{}", to_json(&report)?);
     } else {
-        if report.pass {
-            eprintln!("  ok  {file} (v{})", report.version);
+        for f in &fixed {
+            info!("fixed    {}: {}", f.path, f.message);
+        }
+        for f in &findings {
+            let hint = if f.fixable { " (amp doctor --fix)" } else { "" };
+            info!("{:<8} {}: {}", f.severity.as_str(), f.path, f.message);
+            info!("         fix: {}{hint}", f.fix);
+        }
+        if findings.is_empty() {
+            info!("no problems found in {dir}");
+        }
+    }
+    if errors > 0 {
+        bail!("{errors} problem(s) need attention in {dir}");
+    }
+    Ok(())
+}
+
+/// Import every persona's state and audit sidecars into the workspace
+/// database. Personas already in the database are reported and left alone.
+#[cfg(feature = "sqlite")]
+fn cmd_migrate_backend(
+    dir: &str,
+    from: &str,
+    to: &str,
+    recursive: bool,
+    json_out: bool,
+) -> Result<()> {
+    if (from, to) != ("files", "sqlite") {
+        bail!("only --from files --to sqlite is supported");
+    }
+    let mut imported = Vec::new();
+    let mut failed = Vec::new();
+    for file in persona_files(dir, recursive)? {
+        let paths = default_sidecars(&file);
+        if !std::path::Path::new(&paths.state).exists()
+            && !std::path::Path::new(&paths.audit).exists()
+        {
+            continue;
+        }
+        match sqlite_store(&paths.state)
+            .and_then(|store| store.import_files(&paths.state, &paths.audit))
+        {
+            Ok(done) => {
+                if !json_out {
+                    info!(
+                        "{file}: imported {} (state: {}, {} audit entries)",
+                        done.key,
+                        if done.state { "yes" } else { "no" },
+                        done.audit_entries
+                    );
+                }
+                imported.push(done);
+            }
+            Err(e) => {
+                let e = anyhow::Error::from(e);
+                if !json_out {
+                    warn!("{file}: {e:#}");
+                }
+                failed.push(serde_json::json!({"file": file, "error": format!("{e:#}")}));
+            }
+        }
+    }
+    if json_out {
+        let report = serde_json::json!({
+            "imported": imported,
+            "failed": failed,
+        });
+        println!("{}", to_json(&report)?);
+    }
+    if !failed.is_empty() {
+        bail!("{} persona(s) not imported", failed.len());
+    }
+    Ok(())
+}
+
+#[cfg(not(feature = "sqlite"))]
+fn cmd_migrate_backend(
+    _dir: &str,
+    _from: &str,
+    _to: &str,
+    _recursive: bool,
+    _json_out: bool,
+) -> Result<()> {
+    bail!("migrate-backend requires a build with the `sqlite` feature")
+}
+
+struct RegisterOpts {
+    file: String,
+    project: String,
+    program: String,
+    model: String,
+    include_prompt: bool,
+    toon: bool,
+    rpc: bool,
+    tools: bool,
+    strict: bool,
+    batch: Option<String>,
+    ndjson: bool,
+    trust: TrustArgs,
+}
+
+fn cmd_register(opts: RegisterOpts) -> Result<()> {
+    let RegisterOpts {
+        file,
+        project,
+        program,
+        model,
+        include_prompt,
+        toon,
+        rpc,
+        tools,
+        strict,
+        batch,
+        ndjson,
+        trust,
+    } = opts;
+    let include_prompt = include_prompt || toon;
+    let files = match batch {
+        Some(ref dir) => persona_files(dir, false)?,
+        None => vec![file],
+    };
+
+    let mut calls = Vec::new();
+    for (i, file) in files.iter().enumerate() {
+        let data = read_trusted_persona(file, Default::default(), &trust)
+            .map_err(|e| ndjson_failed(ndjson, e))?;
+        if !strict {
+            for c in ampersona_core::register::capability_conflicts(&data) {
+                warn!(
+                    "{file}: capability '{}' implies '{}', which authority denies",
+                    c.skill, c.action
+                );
+            }
+        }
+        let mut args = ampersona_core::register::build_args(
+            &data,
+            &project,
+            &program,
+            &model,
+            include_prompt,
+            toon,
+            strict,
+        )
+        .map_err(|e| ndjson_failed(ndjson, anyhow::anyhow!("{file}: {e}")))?;
+        if tools {
+            args["tools"] = ampersona_core::register::skill_tools(&data);
+        }
+        if ndjson {
+            emit_ndjson(&if rpc {
+                ampersona_core::register::wrap_rpc_with_id(args, &(i + 1).to_string())
+            } else {
+                args
+            })?;
+        } else {
+            calls.push(args);
+        }
+    }
+    if ndjson {
+        return Ok(());
+    }
+
+    let output = match (batch.is_some(), rpc) {
+        (true, true) => ampersona_core::register::wrap_rpc_batch(calls),
+        (true, false) => serde_json::Value::Array(calls),
+        (false, true) => ampersona_core::register::wrap_rpc(calls.remove(0)),
+        (false, false) => calls.remove(0),
+    };
+    println!("{}", to_json(&output)?);
+    Ok(())
+}
+
+/// Print `value` as one NDJSON line and flush, so consumers see it immediately.
+fn emit_ndjson(value: &serde_json::Value) -> Result<()> {
+    let mut out = io::stdout().lock();
+    serde_json::to_writer(&mut out, value)?;
+    out.write_all(b"\n")?;
+    out.flush()?;
+    Ok(())
+}
+
+/// With `--ndjson`, end the stream with an `{"error": ...}` line before failing.
+fn ndjson_failed(ndjson: bool, e: anyhow::Error) -> anyhow::Error {
+    if ndjson {
+        let _ = emit_ndjson(&serde_json::json!({ "error": true, "message": format!("{e:#}") }));
+    }
+    e
+}
+
+// ── New v1.0 commands ───────────────────────────────────────────
+
+struct InitOpts {
+    workspace: bool,
+    template: String,
+    template_dir: Option<String>,
+    name: Option<String>,
+    autonomy: Option<String>,
+}
+
+fn cmd_init(opts: InitOpts) -> Result<()> {
+    if opts.workspace {
+        std::fs::create_dir_all(".ampersona")?;
+        let defaults = serde_json::json!({
+            "authority": {
+                "autonomy": opts.autonomy.as_deref().unwrap_or("supervised")
+            }
+        });
+        let json = serde_json::to_string_pretty(&defaults)?;
+        std::fs::write(".ampersona/defaults.json", &json)?;
+        info!("created .ampersona/defaults.json");
+    } else {
+        let name = opts.name.as_deref().unwrap_or("NewAgent");
+        let custom = opts
+            .template_dir
+            .as_ref()
+            .map(|dir| std::path::Path::new(dir).join(format!("{}.json", opts.template)))
+            .filter(|path| path.exists());
+        let persona = match custom {
+            Some(path) => {
+                let mut persona = read_persona(&path.to_string_lossy())?;
+                persona
+                    .as_object_mut()
+                    .ok_or_else(|| {
+                        anyhow::anyhow!("{}: template must be an object", path.display())
+                    })?
+                    .insert("name".into(), serde_json::json!(name));
+                persona
+            }
+            None => builtin_template(&opts.template, Some(name))?,
+        };
+
+        let report = ampersona_core::schema::check(&persona, "persona.json", false);
+        if !report.pass {
+            for e in &report.errors {
+                error!(
+                    "{}: {} {}",
+                    e.code,
+                    e.message,
+                    e.path.as_deref().unwrap_or("")
+                );
+            }
+            bail!(
+                "template \"{}\" does not produce a valid persona",
+                opts.template
+            );
+        }
+
+        let json = serde_json::to_string_pretty(&persona)?;
+        std::fs::write("persona.json", &json)?;
+        info!(
+            "created persona.json from template \"{}\" (edit to customize)",
+            opts.template
+        );
+    }
+    Ok(())
+}
+
+struct CheckOpts {
+    file: String,
+    json_out: bool,
+    strict: bool,
+    expand_env: bool,
+    fix: bool,
+    strict_unknown: bool,
+}
+
+fn cmd_check(opts: CheckOpts) -> Result<()> {
+    let CheckOpts {
+        ref file,
+        json_out,
+        strict,
+        expand_env,
+        fix,
+        strict_unknown,
+    } = opts;
+    let content =
+        std::fs::read_to_string(file).map_err(|e| anyhow::anyhow!("cannot read {file}: {e}"))?;
+    let mut data: serde_json::Value =
+        serde_json::from_str(&content).map_err(|e| anyhow::anyhow!("{file}: invalid JSON: {e}"))?;
+
+    // Fixes edit the file as written; resolving first would flatten the chain.
+    let fixes = if fix {
+        if data.get("$extends").is_some() {
+            bail!("{file}: --fix does not rewrite personas with $extends; fix the base instead");
+        }
+        let outcome = ampersona_core::fix::apply_fixes(&mut data);
+        if !outcome.applied.is_empty() {
+            let json = serde_json::to_string_pretty(&data)?;
+            ampersona_engine::state::atomic::atomic_write(file, json.as_bytes())?;
+        }
+        Some(outcome)
+    } else {
+        None
+    };
+
+    let mut data = ampersona_core::prompt::resolve_extends(data, file)?;
+    if expand_env {
+        data = ampersona_core::interpolate::expand_env(data)?;
+    }
+
+    let mut report = ampersona_core::schema::check(&data, file, strict);
+    if strict_unknown {
+        let unknown = ampersona_core::schema::check_unknown_fields(&data);
+        report.pass &= unknown.is_empty();
+        report.errors.extend(unknown);
+    }
+
+    if json_out {
+        let mut out = serde_json::to_value(&report)?;
+        if let Some(fixes) = &fixes {
+            out["fixes"] = serde_json::to_value(fixes)?;
+        }
+        println!("{}", to_json(&out)?);
+    } else {
+        if let Some(fixes) = &fixes {
+            for f in &fixes.applied {
+                info!("fixed {}: {} {}", f.code, f.description, f.path);
+            }
+            for f in &fixes.rejected {
+                info!(
+                    "skip  {}: {} {} (result would not validate)",
+                    f.code, f.description, f.path
+                );
+            }
+        }
+        if report.pass {
+            info!("ok  {file} (v{})", report.version);
         } else {
-            eprintln!("  FAIL {file} (v{})", report.version);
+            error!("FAIL {file} (v{})", report.version);
         }
         for e in &report.errors {
-            eprintln!(
-                "  error {}: {} {}",
+            error!(
+                "{}: {} {}",
                 e.code,
                 e.message,
                 e.path.as_deref().unwrap_or("")
             );
         }
         for w in &report.warnings {
-            eprintln!(
-                "  warn  {}: {} {}",
+            warn!(
+                "{}: {} {}",
                 w.code,
                 w.message,
                 w.path.as_deref().unwrap_or("")
@@ -655,14 +2575,68 @@ fn cmd_check(file: &str, json_out: bool, strict: bool) -> Result<()> {
     Ok(())
 }
 
-fn cmd_migrate(files: &[String]) -> Result<()> {
+fn cmd_migrate(files: &[String], canonical: bool, dry_run: bool, backup: bool) -> Result<()> {
     for file in files {
-        ampersona_core::migrate::migrate_file(file)?;
+        let Some(migration) = ampersona_core::migrate::plan_migration(file, canonical)? else {
+            info!("skip {file} (already v1.0)");
+            continue;
+        };
+        if dry_run {
+            // Diff what would actually be written, not the in-memory value
+            let written: serde_json::Value = serde_json::from_str(&migration.output)?;
+            println!("--- {file}");
+            diff_values("", &migration.original, &written, None);
+            continue;
+        }
+        if backup {
+            std::fs::copy(file, ampersona_core::sidecar::sidecar_path(file, ".bak"))?;
+        }
+        ampersona_engine::state::atomic::atomic_write(file, migration.output.as_bytes())?;
+        info!("migrated {file} (v0.2 \u{2192} v1.0)");
     }
     Ok(())
 }
 
-fn cmd_status(file: &str, json_out: bool, drift: bool) -> Result<()> {
+fn parse_rfc3339(s: &str) -> std::result::Result<chrono::DateTime<chrono::Utc>, String> {
+    chrono::DateTime::parse_from_rfc3339(s)
+        .map(|t| t.with_timezone(&chrono::Utc))
+        .map_err(|e| format!("invalid RFC 3339 time '{s}': {e}"))
+}
+
+/// Which drift entries `amp status --drift` reports.
+struct DriftWindow {
+    since: Option<chrono::DateTime<chrono::Utc>>,
+    until: Option<chrono::DateTime<chrono::Utc>>,
+    /// Entries shown, counted from the newest.
+    limit: usize,
+    /// Trends as numbers rather than sparklines.
+    plain: bool,
+}
+
+/// `▁▂▃▄▅▆▇█` scaled between the series' min and max.
+fn sparkline(values: &[f64]) -> String {
+    const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+    let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    values
+        .iter()
+        .map(|v| {
+            let level = if max > min {
+                ((v - min) / (max - min) * 7.0).round() as usize
+            } else {
+                0
+            };
+            BARS[level.min(7)]
+        })
+        .collect()
+}
+
+fn cmd_status(
+    file: &str,
+    sidecars: &ampersona_core::sidecar::Sidecars,
+    json_out: bool,
+    drift: Option<DriftWindow>,
+) -> Result<()> {
     let data = read_persona(file)?;
     let name = data
         .get("name")
@@ -675,15 +2649,24 @@ fn cmd_status(file: &str, json_out: bool, drift: bool) -> Result<()> {
         .unwrap_or("n/a");
 
     // Try to load state file
-    let state_path = file.replace(".json", ".state.json");
-    let state = ampersona_engine::state::phase::load_state(&state_path).ok();
-
-    // Load drift entries if requested
-    let drift_entries = if drift {
-        let drift_path = file.replace(".json", ".drift.jsonl");
-        ampersona_engine::state::drift::read_drift_entries(&drift_path).unwrap_or_default()
-    } else {
-        Vec::new()
+    let state = state_store(sidecars)?.load().ok().flatten();
+    let phase_info = state
+        .as_ref()
+        .and_then(|s| s.current_phase.as_deref())
+        .and_then(|phase| data.get("phases")?.get(phase));
+
+    // Load drift entries in the requested window
+    let drift_entries = match &drift {
+        Some(window) => ampersona_engine::state::drift::filter_drift_window(
+            ampersona_engine::state::drift::read_drift_entries(&sidecars.drift).unwrap_or_default(),
+            window.since,
+            window.until,
+        ),
+        None => Vec::new(),
+    };
+    let shown = match &drift {
+        Some(window) => &drift_entries[drift_entries.len().saturating_sub(window.limit)..],
+        None => &[][..],
     };
 
     if json_out {
@@ -695,39 +2678,69 @@ fn cmd_status(file: &str, json_out: bool, drift: bool) -> Result<()> {
             "state_rev": state.as_ref().map(|s| s.state_rev),
             "active_elevations": state.as_ref().map(|s| s.active_elevations.len()).unwrap_or(0),
         });
-        if drift {
+        if let Some(info) = phase_info {
+            status["phase_info"] = info.clone();
+        }
+        if drift.is_some() {
             status["drift_entries"] = serde_json::json!(drift_entries.len());
             if let Some(last) = drift_entries.last() {
                 status["last_drift"] = last.clone();
             }
+            status["drift"] = serde_json::json!(shown);
+            status["drift_series"] =
+                serde_json::json!(ampersona_engine::state::drift::metric_series(shown));
         }
-        println!("{}", serde_json::to_string_pretty(&status)?);
+        println!("{}", to_json(&status)?);
     } else {
-        eprintln!("  Name:      {name}");
-        eprintln!("  Version:   {version}");
-        eprintln!("  Autonomy:  {autonomy}");
+        info!("Name:      {name}");
+        info!("Version:   {version}");
+        info!("Autonomy:  {autonomy}");
         if let Some(s) = &state {
-            eprintln!(
-                "  Phase:     {}",
+            info!(
+                "Phase:     {}",
                 s.current_phase.as_deref().unwrap_or("(none)")
             );
-            eprintln!("  State rev: {}", s.state_rev);
-            eprintln!("  Elevations: {}", s.active_elevations.len());
+            let note = |field: &str| phase_info.and_then(|i| i.get(field)?.as_str());
+            if let Some(description) = note("description") {
+                info!("  {description}");
+            }
+            if let Some(entry_note) = note("entry_note") {
+                info!("  Entry note: {entry_note}");
+            }
+            info!("State rev: {}", s.state_rev);
+            info!("Elevations: {}", s.active_elevations.len());
         } else {
-            eprintln!("  Phase:     (no state file)");
+            info!("Phase:     (no state file)");
         }
-        if drift {
-            eprintln!("  Drift entries: {}", drift_entries.len());
-            // Show last 5 entries as trend
-            let start = drift_entries.len().saturating_sub(5);
-            for entry in &drift_entries[start..] {
+        if drift.is_some() {
+            info!("Drift entries: {}", drift_entries.len());
+            // Show the newest entries as trend
+            for entry in shown {
                 if let Some(obj) = entry.as_object() {
                     let ts = obj.get("ts").and_then(|v| v.as_str()).unwrap_or("?");
                     let metrics = obj
                         .get("metrics")
                         .map(|v| v.to_string())
                         .unwrap_or_else(|| "{}".into());
-                    eprintln!("    {ts}: {metrics}");
+                    info!("  {ts}: {metrics}");
+                }
+            }
+            let series = ampersona_engine::state::drift::metric_series(shown);
+            let plain = drift.as_ref().is_some_and(|w| w.plain);
+            if !series.is_empty() {
+                info!("Trend:");
+            }
+            for s in &series {
+                if plain {
+                    let values: Vec<String> = s.values.iter().map(f64::to_string).collect();
+                    info!("  {}: {} ({})", s.metric, values.join(" "), s.trend);
+                } else {
+                    let arrow = match s.trend {
+                        "rising" => '↑',
+                        "falling" => '↓',
+                        _ => '→',
+                    };
+                    info!("  {}: {} {arrow}", s.metric, sparkline(&s.values));
                 }
             }
         }
@@ -735,14 +2748,318 @@ fn cmd_status(file: &str, json_out: bool, drift: bool) -> Result<()> {
     Ok(())
 }
 
-fn cmd_authority(
-    file: &str,
-    action: &str,
+/// `amp report`: the governance posture of one persona in a single document.
+///
+/// Stitches together what `amp fingerprint`, `amp verify`, `amp authority
+/// --matrix`, `amp status` and `amp audit --verify` report separately.
+fn cmd_report(file: &str, pubkey: Option<&str>, format: &str, config: Option<&str>) -> Result<()> {
+    let content =
+        std::fs::read_to_string(file).map_err(|e| anyhow::anyhow!("cannot read {file}: {e}"))?;
+    let stored: serde_json::Value = serde_json::from_str(&content)?;
+    let data = read_persona(file)?;
+    let persona: ampersona_core::spec::Persona = serde_json::from_value(data.clone())?;
+    let paths = default_sidecars(file);
+
+    let signature = match pubkey {
+        Some(pubkey) => {
+            let (status, issue) = signature_status(&stored, &load_verifying_key(pubkey)?, false);
+            serde_json::json!({
+                "status": status,
+                "key_id": stored.pointer("/signature/key_id"),
+                "error": issue.map(|i| i.message),
+            })
+        }
+        None => serde_json::json!({
+            "status": if stored.get("signature").is_some() { "not_checked" } else { "unsigned" },
+            "key_id": stored.pointer("/signature/key_id"),
+        }),
+    };
+
+    let resolved = resolve_persona_authority(file, &persona, config);
+    let checker = ampersona_engine::policy::checker::DefaultPolicyChecker.cached();
+    let rows = decide_actions(
+        &checker,
+        resolved.as_ref(),
+        &vocabulary_actions(&persona),
+        None,
+        &HashMap::new(),
+    )?;
+    // Actions outside the vocabulary are shown as the persona spells them.
+    let written = |action: String| match action.strip_prefix("custom:_unknown/") {
+        Some(name) => name.to_string(),
+        None => action,
+    };
+    let matrix: Vec<serde_json::Value> = rows
+        .into_iter()
+        .map(|(action, decision, reason)| {
+            serde_json::json!({"action": written(action), "decision": decision, "reason": reason})
+        })
+        .collect();
+
+    let state = state_store(&paths)?.load().ok().flatten();
+    let (audit, transitions) = report_audit(&paths);
+
+    let compliance_refs: Vec<serde_json::Value> = persona
+        .authority
+        .as_ref()
+        .and_then(|a| a.actions.as_ref()?.deny.as_ref())
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| match entry {
+            ampersona_core::spec::authority::DenyEntry::WithReason {
+                action,
+                reason,
+                compliance_ref: Some(compliance_ref),
+                ..
+            } => Some(serde_json::json!({
+                "action": written(action.to_string()),
+                "reason": reason,
+                "compliance_ref": compliance_ref,
+            })),
+            _ => None,
+        })
+        .collect();
+
+    let report = serde_json::json!({
+        "generated_at": chrono::Utc::now().to_rfc3339(),
+        "persona": {
+            "file": file,
+            "name": persona.name,
+            "role": persona.role,
+            "version": ampersona_core::schema::detect_version(&stored),
+            "fingerprint": ampersona_sign::sign::fingerprint(&stored)?,
+        },
+        "signature": signature,
+        "authority": {
+            "autonomy": resolved.as_ref().map(|r| format!("{:?}", r.autonomy).to_lowercase()),
+            "matrix": matrix,
+        },
+        "state": {
+            "phase": state.as_ref().and_then(|s| s.current_phase.as_deref()),
+            "state_rev": state.as_ref().map(|s| s.state_rev),
+            "transitions": transitions,
+        },
+        "audit": audit,
+        "compliance": {
+            "markers": persona.audit.as_ref().and_then(|a| a.compliance_markers.as_ref()),
+            "refs": compliance_refs,
+        },
+    });
+
+    if format == "json" {
+        println!("{}", to_json(&report)?);
+    } else {
+        print!("{}", report_markdown(&report));
+    }
+    Ok(())
+}
+
+/// Chain verification of the persona's audit log, and the gate transitions
+/// it records (encrypted entries only show their event type, so they are
+/// not listed).
+fn report_audit(
+    paths: &ampersona_core::sidecar::Sidecars,
+) -> (serde_json::Value, Vec<serde_json::Value>) {
+    let error = |path: &str, e: anyhow::Error| {
+        let error = format!("{e:#}");
+        serde_json::json!({"path": path, "present": true, "valid": false, "error": error})
+    };
+    let store = match state_store(paths) {
+        Ok(store) => store,
+        Err(e) => return (error(&paths.state, e), Vec::new()),
+    };
+    let path = store.audit_location();
+    let audit = match store.audit_lines() {
+        Ok(Some(audit)) => audit,
+        Ok(None) => {
+            return (
+                serde_json::json!({"path": path, "present": false}),
+                Vec::new(),
+            )
+        }
+        Err(e) => return (error(&path, e.into()), Vec::new()),
+    };
+    let lines = || audit.iter().map(String::as_str);
+    let audit = match ampersona_engine::state::audit_log::verify_lines(&path, lines(), 0) {
+        Ok(entries) => {
+            serde_json::json!({"path": path, "present": true, "valid": true, "entries": entries})
+        }
+        Err(e) => error(&path, e.into()),
+    };
+    (audit, gate_transitions(lines()))
+}
+
+/// `GateTransition` audit entries, oldest first.
+fn gate_transitions<'a>(lines: impl IntoIterator<Item = &'a str>) -> Vec<serde_json::Value> {
+    lines
+        .into_iter()
+        .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+        .filter(|e| e["event_type"] == "GateTransition" && e.get("gate_id").is_some())
+        .map(|e| {
+            serde_json::json!({
+                "ts": e["ts"],
+                "gate_id": e["gate_id"],
+                "decision": e["decision"],
+                "from_phase": e["from_phase"],
+                "to_phase": e["to_phase"],
+                "is_override": e.get("is_override").cloned().unwrap_or(serde_json::Value::Bool(false)),
+            })
+        })
+        .collect()
+}
+
+/// Render an `amp report` document as Markdown.
+fn report_markdown(report: &serde_json::Value) -> String {
+    use std::fmt::Write as _;
+
+    let text = |v: &serde_json::Value| match v {
+        serde_json::Value::String(s) => s.clone(),
+        serde_json::Value::Null => "-".to_string(),
+        other => other.to_string(),
+    };
+    let persona = &report["persona"];
+    let mut md = String::new();
+    let _ = writeln!(md, "# Compliance Report: {}\n", text(&persona["name"]));
+    let _ = writeln!(md, "Generated: {}\n", text(&report["generated_at"]));
+
+    let _ = writeln!(md, "## Identity\n");
+    let _ = writeln!(md, "- **File:** {}", text(&persona["file"]));
+    let _ = writeln!(md, "- **Role:** {}", text(&persona["role"]));
+    let _ = writeln!(md, "- **Schema version:** {}", text(&persona["version"]));
+    let _ = writeln!(
+        md,
+        "- **Fingerprint:** `{}`\n",
+        text(&persona["fingerprint"])
+    );
+
+    let signature = &report["signature"];
+    let _ = writeln!(md, "## Signature\n");
+    let _ = writeln!(md, "- **Status:** {}", text(&signature["status"]));
+    let _ = writeln!(md, "- **Key ID:** {}", text(&signature["key_id"]));
+    if let Some(error) = signature["error"].as_str() {
+        let _ = writeln!(md, "- **Error:** {error}");
+    }
+
+    let authority = &report["authority"];
+    let _ = writeln!(md, "\n## Authority\n");
+    let _ = writeln!(
+        md,
+        "- **Effective autonomy:** {}\n",
+        text(&authority["autonomy"])
+    );
+    let _ = writeln!(md, "| Action | Decision | Reason |");
+    let _ = writeln!(md, "|--------|----------|--------|");
+    for row in authority["matrix"].as_array().into_iter().flatten() {
+        let _ = writeln!(
+            md,
+            "| {} | {} | {} |",
+            text(&row["action"]),
+            text(&row["decision"]),
+            text(&row["reason"]).replace('|', "\\|")
+        );
+    }
+
+    let state = &report["state"];
+    let _ = writeln!(md, "\n## Phase\n");
+    let _ = writeln!(md, "- **Current phase:** {}", text(&state["phase"]));
+    let _ = writeln!(md, "- **State rev:** {}\n", text(&state["state_rev"]));
+    match state["transitions"].as_array().filter(|t| !t.is_empty()) {
+        Some(transitions) => {
+            let _ = writeln!(md, "| Time | Gate | Decision | From | To |");
+            let _ = writeln!(md, "|------|------|----------|------|----|");
+            for t in transitions {
+                let _ = writeln!(
+                    md,
+                    "| {} | {} | {} | {} | {} |",
+                    text(&t["ts"]),
+                    text(&t["gate_id"]),
+                    text(&t["decision"]),
+                    text(&t["from_phase"]),
+                    text(&t["to_phase"])
+                );
+            }
+        }
+        None => {
+            let _ = writeln!(md, "No gate transitions recorded.");
+        }
+    }
+
+    let audit = &report["audit"];
+    let _ = writeln!(md, "\n## Audit Chain\n");
+    let _ = writeln!(md, "- **Log:** {}", text(&audit["path"]));
+    let verdict = match (audit["present"].as_bool(), audit["valid"].as_bool()) {
+        (Some(false), _) => "no audit log".to_string(),
+        (_, Some(true)) => format!("valid ({} entries)", text(&audit["entries"])),
+        _ => format!("INVALID: {}", text(&audit["error"])),
+    };
+    let _ = writeln!(md, "- **Chain:** {verdict}");
+
+    let compliance = &report["compliance"];
+    let _ = writeln!(md, "\n## Compliance\n");
+    if let Some(markers) = compliance["markers"].as_array() {
+        let markers: Vec<String> = markers.iter().map(text).collect();
+        let _ = writeln!(md, "- **Markers:** {}\n", markers.join(", "));
+    }
+    match compliance["refs"].as_array().filter(|r| !r.is_empty()) {
+        Some(refs) => {
+            let _ = writeln!(md, "| Denied action | Reference | Reason |");
+            let _ = writeln!(md, "|---------------|-----------|--------|");
+            for r in refs {
+                let _ = writeln!(
+                    md,
+                    "| {} | {} | {} |",
+                    text(&r["action"]),
+                    text(&r["compliance_ref"]),
+                    text(&r["reason"]).replace('|', "\\|")
+                );
+            }
+        }
+        None => {
+            let _ = writeln!(md, "No deny entries carry a compliance reference.");
+        }
+    }
+    md
+}
+
+struct AuthorityOpts {
+    file: String,
+    action: Option<String>,
+    matrix: bool,
     json_out: bool,
     path: Option<String>,
     context_kvs: Vec<(String, String)>,
     context_json: Option<String>,
-) -> CmdExit {
+    trace: bool,
+    strict_vocab: bool,
+    strict_review: bool,
+    expand_env: bool,
+    show_overlay_effect: bool,
+    what_if: Option<String>,
+    trust: TrustArgs,
+    config: Option<String>,
+}
+
+fn cmd_authority(opts: AuthorityOpts) -> CmdExit {
+    let AuthorityOpts {
+        file,
+        action,
+        matrix,
+        json_out,
+        path,
+        context_kvs,
+        context_json,
+        trace,
+        strict_vocab,
+        strict_review,
+        expand_env,
+        show_overlay_effect,
+        what_if,
+        trust,
+        config,
+    } = opts;
+    let file = file.as_str();
+    let config = config.as_deref();
+
     // Read persona file with structured error handling
     let content = match std::fs::read_to_string(file) {
         Ok(c) => c,
@@ -764,6 +3081,37 @@ fn cmd_authority(
             };
         }
     };
+    if let Err(e) = trust.check(file, &data) {
+        return CmdExit::JsonErr {
+            code: "E_UNTRUSTED_PERSONA",
+            message: format!("{e:#}"),
+            json: json_out,
+        };
+    }
+    let data = match ampersona_core::prompt::resolve_extends(data, file) {
+        Ok(d) => d,
+        Err(e) => {
+            return CmdExit::JsonErr {
+                code: "E_INVALID_EXTENDS",
+                message: format!("{e:#}"),
+                json: json_out,
+            };
+        }
+    };
+    let data = if expand_env {
+        match ampersona_core::interpolate::expand_env(data) {
+            Ok(d) => d,
+            Err(e) => {
+                return CmdExit::JsonErr {
+                    code: "E_ENV_EXPANSION",
+                    message: format!("{file}: {e:#}"),
+                    json: json_out,
+                };
+            }
+        }
+    } else {
+        data
+    };
     let persona: ampersona_core::spec::Persona = match serde_json::from_value(data.clone()) {
         Ok(p) => p,
         Err(e) => {
@@ -774,6 +3122,8 @@ fn cmd_authority(
             };
         }
     };
+    #[cfg(feature = "otel")]
+    let _span = tracing::info_span!("amp.authority", persona = %persona.name).entered();
 
     // Build context from --context and --context-json
     let mut ctx: HashMap<String, serde_json::Value> = HashMap::new();
@@ -788,53 +3138,97 @@ fn cmd_authority(
         }
     }
 
-    let (decision, resolved) = if let Some(authority) = &persona.authority {
-        let mut layers: Vec<&ampersona_core::spec::authority::Authority> = Vec::new();
-        let workspace_defaults = ampersona_engine::policy::precedence::load_workspace_defaults();
-        if let Some(ref wd) = workspace_defaults {
-            layers.push(wd);
-        }
-        layers.push(authority);
-
-        // Overlay is no longer a merge layer — it's applied as a post-resolution patch.
-        // See ADR-010: authority_overlay uses patch-replace semantics.
-
-        let state_path = file.replace(".json", ".state.json");
-        let state = ampersona_engine::state::phase::load_state(&state_path).ok();
-
-        let resolved = if let Some(ref s) = state {
-            let elevation_defs = authority.elevations.as_deref().unwrap_or(&[]);
-            ampersona_engine::policy::precedence::resolve_with_elevations(
-                &layers,
-                &s.active_elevations,
-                elevation_defs,
-            )
-        } else {
-            ampersona_engine::policy::precedence::resolve_authority(&layers)
+    if matrix {
+        return match print_authority_matrix(file, &persona, path, ctx, config, json_out) {
+            Ok(()) => CmdExit::Ok,
+            Err(e) => CmdExit::JsonErr {
+                code: "E_INTERNAL",
+                message: format!("{e:#}"),
+                json: json_out,
+            },
+        };
+    }
+    if show_overlay_effect {
+        return match print_overlay_effect(file, &persona, config, json_out) {
+            Ok(()) => CmdExit::Ok,
+            Err(e) => CmdExit::JsonErr {
+                code: "E_INTERNAL",
+                message: format!("{e:#}"),
+                json: json_out,
+            },
+        };
+    }
+    if let Some(overlay_path) = what_if {
+        return match print_what_if(file, &persona, &overlay_path, path, ctx, config, json_out) {
+            Ok(()) => CmdExit::Ok,
+            Err(e) => CmdExit::JsonErr {
+                code: "E_INTERNAL",
+                message: format!("{e:#}"),
+                json: json_out,
+            },
+        };
+    }
+    // clap requires --check unless --matrix, --show-overlay-effect or --what-if
+    let action = action.as_deref().unwrap_or_default();
+
+    let mut vocab = ampersona_engine::policy::action_registry::ActionVocabulary::new();
+    let (action_id, action_class) = vocab.classify(action);
+    let unknown_action =
+        action_class == ampersona_engine::policy::action_registry::ActionClass::Unknown;
+    let suggestion = if unknown_action {
+        ampersona_core::actions::BuiltinAction::suggest(action)
+    } else {
+        None
+    };
+    if unknown_action && strict_vocab {
+        let hint = suggestion
+            .map(|s| format!(" (did you mean '{s}'?)"))
+            .unwrap_or_default();
+        return CmdExit::JsonErr {
+            code: "E_UNKNOWN_ACTION",
+            message: format!("'{action}' is not in the action vocabulary{hint}"),
+            json: json_out,
         };
+    }
 
-        // Apply authority overlay as post-resolution patch (ADR-010).
+    let mut resolution_trace = None;
+    let (decision, resolved) = if let Some(authority) = &persona.authority {
+        // Overlay is no longer a merge layer — it's applied as a post-resolution patch.
+        // See ADR-010: authority_overlay uses patch-replace semantics.
         // Only reads from state.active_overlay — sidecar migration is cmd_gate's job.
-        let resolved = if let Some(overlay) = state.as_ref().and_then(|s| s.active_overlay.as_ref())
-        {
-            ampersona_engine::policy::precedence::apply_overlay(&resolved, overlay)
-        } else {
-            resolved
-        };
+        let state = stored_state(file);
+
+        let workspace_defaults = ampersona_engine::policy::precedence::load_defaults(config);
+        let layers = ampersona_engine::policy::precedence::base_layers(
+            workspace_defaults.as_ref(),
+            persona.phase_authority(state.as_ref().and_then(|s| s.current_phase.as_deref())),
+            authority,
+        );
 
         let checker = ampersona_engine::policy::checker::DefaultPolicyChecker;
 
         use ampersona_core::traits::AuthorityEnforcer;
         let req = ampersona_core::traits::PolicyRequest {
-            action: Some(action.parse().unwrap_or_else(|_| {
-                ampersona_core::actions::ActionId::Custom {
-                    vendor: "_unknown".into(),
-                    action: action.into(),
-                }
-            })),
+            action: Some(action_id),
             path: path.clone(),
             context: ctx.clone(),
         };
+
+        let elevation_defs = authority.elevations.as_deref().unwrap_or(&[]);
+        let traced = ampersona_engine::policy::precedence::resolve_traced(
+            &layers,
+            state
+                .as_ref()
+                .map(|s| s.active_elevations.as_slice())
+                .unwrap_or(&[]),
+            elevation_defs,
+            state.as_ref().and_then(|s| s.active_overlay.as_ref()),
+            &req,
+            &checker,
+        );
+        let resolved = traced.resolved.clone();
+        resolution_trace = Some(traced);
+
         match checker.evaluate(&req, &resolved) {
             Ok(d) => (d, Some(resolved)),
             Err(e) => {
@@ -854,11 +3248,31 @@ fn cmd_authority(
         )
     };
 
+    // A deny still in force but past its review date
+    let overdue_review = match &decision {
+        ampersona_core::errors::PolicyDecision::Deny { .. } => resolved
+            .as_ref()
+            .and_then(|r| r.deny_metadata.get(action))
+            .filter(|m| m.review_overdue())
+            .and_then(|m| m.review_by),
+        _ => None,
+    };
+    if let (true, Some(review_by)) = (strict_review, overdue_review) {
+        return CmdExit::JsonErr {
+            code: "E_DENY_REVIEW_OVERDUE",
+            message: format!(
+                "deny of '{action}' is past its review date {}",
+                review_by.format("%Y-%m-%d")
+            ),
+            json: json_out,
+        };
+    }
+
     // Determine exit code
     let exit_code = match &decision {
-        ampersona_core::errors::PolicyDecision::Allow { .. } => 0,
-        ampersona_core::errors::PolicyDecision::Deny { .. } => 1,
-        ampersona_core::errors::PolicyDecision::NeedsApproval { .. } => 2,
+        ampersona_core::errors::PolicyDecision::Allow { .. } => exit::OK,
+        ampersona_core::errors::PolicyDecision::Deny { .. } => exit::DENY,
+        ampersona_core::errors::PolicyDecision::NeedsApproval { .. } => exit::PENDING,
     };
 
     if json_out {
@@ -878,6 +3292,9 @@ fn cmd_authority(
                 serde_json::json!({
                     "reason": m.reason,
                     "compliance_ref": m.compliance_ref,
+                    "review_by": m.review_by,
+                    "expires_at": m.expires_at,
+                    "review_overdue": m.review_overdue(),
                 })
             });
 
@@ -886,8 +3303,9 @@ fn cmd_authority(
             .map(|r| format!("{:?}", r.autonomy).to_lowercase())
             .unwrap_or_else(|| "n/a".into());
 
-        let output = serde_json::json!({
+        let mut output = serde_json::json!({
             "action": action,
+            "vocab": action_class.as_str(),
             "decision": decision_str,
             "reason": reason,
             "autonomy": autonomy_str,
@@ -895,19 +3313,363 @@ fn cmd_authority(
             "path": path,
             "context": ctx,
         });
-        println!("{}", serde_json::to_string_pretty(&output).unwrap());
+        if let Some(s) = suggestion {
+            output["suggestion"] = serde_json::json!(s);
+        }
+        if trace {
+            output["trace"] = serde_json::json!({
+                "layers": resolution_trace.as_ref().map(|t| &t.steps),
+                "decided_by": resolution_trace.as_ref().and_then(|t| t.deciding_layer()),
+            });
+        }
+        println!("{}", to_json(&output).unwrap());
     } else {
+        if unknown_action {
+            match suggestion {
+                Some(s) => {
+                    warn!("'{action}' is not in the action vocabulary (did you mean '{s}'?)")
+                }
+                None => warn!("'{action}' is not in the action vocabulary"),
+            }
+        }
+        if let Some(review_by) = overdue_review {
+            warn!(
+                "deny of '{action}' is past its review date {}",
+                review_by.format("%Y-%m-%d")
+            );
+        }
+        if trace {
+            match &resolution_trace {
+                Some(t) => {
+                    for (i, step) in t.steps.iter().enumerate() {
+                        println!(
+                            "  {}. {} (autonomy: {}) -> {}",
+                            i + 1,
+                            step.layer,
+                            format!("{:?}", step.autonomy).to_lowercase(),
+                            step.decision
+                        );
+                        for change in &step.changes {
+                            println!("       {change}");
+                        }
+                    }
+                    if let Some(layer) = t.deciding_layer() {
+                        println!("  decided by: {layer}");
+                    }
+                }
+                None => println!("  no authority layers (no authority section defined)"),
+            }
+        }
         println!("{decision}");
     }
 
-    if exit_code == 0 {
+    if exit_code == exit::OK {
         CmdExit::Ok
     } else {
         CmdExit::Code(exit_code)
     }
 }
 
-fn cmd_elevate(file: &str, elevation_id: &str, reason: &str) -> Result<()> {
+/// `amp authority --show-overlay-effect`: the fields the active overlay in
+/// state changed relative to workspace defaults + phase + persona + elevations.
+fn print_overlay_effect(
+    file: &str,
+    persona: &ampersona_core::spec::Persona,
+    config: Option<&str>,
+    json_out: bool,
+) -> Result<()> {
+    use ampersona_engine::policy::precedence;
+
+    let state = stored_state(file);
+    let overlay = state.as_ref().and_then(|s| s.active_overlay.as_ref());
+    let gate_id = state
+        .as_ref()
+        .and_then(|s| s.last_transition.as_ref())
+        .map(|t| t.gate_id.as_str());
+    let changes = match (&persona.authority, overlay) {
+        (Some(authority), Some(overlay)) => {
+            let workspace_defaults = precedence::load_defaults(config);
+            let layers: Vec<_> = precedence::base_layers(
+                workspace_defaults.as_ref(),
+                persona.phase_authority(state.as_ref().and_then(|s| s.current_phase.as_deref())),
+                authority,
+            )
+            .into_iter()
+            .map(|(_, layer)| layer)
+            .collect();
+            let elevated = precedence::resolve_with_elevations(
+                &layers,
+                state
+                    .as_ref()
+                    .map(|s| s.active_elevations.as_slice())
+                    .unwrap_or(&[]),
+                authority.elevations.as_deref().unwrap_or(&[]),
+            );
+            precedence::apply_overlay_with_changes(&elevated, overlay).1
+        }
+        _ => Vec::new(),
+    };
+
+    if json_out {
+        let out = serde_json::json!({
+            "file": file,
+            "gate_id": gate_id,
+            "overlay": overlay,
+            "changes": changes,
+        });
+        println!("{}", to_json(&out)?);
+        return Ok(());
+    }
+    if overlay.is_none() {
+        println!("no active overlay");
+        return Ok(());
+    }
+    println!("overlay from gate {}:", gate_id.unwrap_or("?"));
+    if changes.is_empty() {
+        println!("  (no effect: every field already matched)");
+    }
+    for c in &changes {
+        println!("  {:<16} {} -> {}", c.field, c.before, c.after);
+    }
+    Ok(())
+}
+
+/// Every builtin action, plus the custom actions the persona's allow/deny
+/// lists reference.
+fn vocabulary_actions(
+    persona: &ampersona_core::spec::Persona,
+) -> Vec<ampersona_core::actions::ActionId> {
+    use ampersona_core::actions::{ActionId, BuiltinAction};
+
+    let mut actions: Vec<ActionId> = BuiltinAction::all()
+        .iter()
+        .map(|b| ActionId::Builtin(*b))
+        .collect();
+    if let Some(list) = persona.authority.as_ref().and_then(|a| a.actions.as_ref()) {
+        let referenced = list
+            .allow
+            .iter()
+            .flatten()
+            .chain(list.deny.iter().flatten().map(|d| d.action_id()));
+        for id in referenced {
+            if id.is_custom() && !actions.contains(id) {
+                actions.push(id.clone());
+            }
+        }
+    }
+    actions
+}
+
+/// The authority resolved from workspace defaults, the current phase, the
+/// persona, and the elevations and overlay in state; `None` without an
+/// authority section.
+fn resolve_persona_authority(
+    file: &str,
+    persona: &ampersona_core::spec::Persona,
+    config: Option<&str>,
+) -> Option<ampersona_core::traits::ResolvedAuthority> {
+    use ampersona_engine::policy::precedence;
+
+    persona.authority.as_ref().map(|authority| {
+        let state = stored_state(file);
+        let workspace_defaults = precedence::load_defaults(config);
+        let layers: Vec<_> = precedence::base_layers(
+            workspace_defaults.as_ref(),
+            persona.phase_authority(state.as_ref().and_then(|s| s.current_phase.as_deref())),
+            authority,
+        )
+        .into_iter()
+        .map(|(_, layer)| layer)
+        .collect();
+        let elevated = precedence::resolve_with_elevations(
+            &layers,
+            state
+                .as_ref()
+                .map(|s| s.active_elevations.as_slice())
+                .unwrap_or(&[]),
+            authority.elevations.as_deref().unwrap_or(&[]),
+        );
+        match state.as_ref().and_then(|s| s.active_overlay.as_ref()) {
+            Some(overlay) => precedence::apply_overlay(&elevated, overlay),
+            None => elevated,
+        }
+    })
+}
+
+/// `(action, decision, reason)` for each action against `resolved`, keyed
+/// once for all of them; every action is denied when there is no authority.
+fn decide_actions(
+    checker: &ampersona_engine::policy::checker::CachingChecker<
+        ampersona_engine::policy::checker::DefaultPolicyChecker,
+    >,
+    resolved: Option<&ampersona_core::traits::ResolvedAuthority>,
+    actions: &[ampersona_core::actions::ActionId],
+    path: Option<&str>,
+    ctx: &HashMap<String, serde_json::Value>,
+) -> Result<Vec<(String, &'static str, String)>> {
+    use ampersona_core::errors::PolicyDecision;
+
+    let keyed = resolved.map(ampersona_engine::policy::checker::KeyedAuthority::new);
+    let mut rows = Vec::new();
+    for action in actions {
+        let (decision, reason) = match &keyed {
+            Some(keyed) => {
+                let req = ampersona_core::traits::PolicyRequest {
+                    action: Some(action.clone()),
+                    path: path.map(str::to_string),
+                    context: ctx.clone(),
+                };
+                match checker.evaluate_keyed(&req, keyed)? {
+                    PolicyDecision::Allow { reason } => ("Allow", reason),
+                    PolicyDecision::Deny { reason } => ("Deny", reason),
+                    PolicyDecision::NeedsApproval { reason } => ("NeedsApproval", reason),
+                }
+            }
+            None => ("Deny", "no authority section defined".to_string()),
+        };
+        rows.push((action.to_string(), decision, reason));
+    }
+    Ok(rows)
+}
+
+/// `amp authority --what-if`: the actions whose decision would change if
+/// `overlay_path` were applied on top of the currently resolved authority.
+fn print_what_if(
+    file: &str,
+    persona: &ampersona_core::spec::Persona,
+    overlay_path: &str,
+    path: Option<String>,
+    ctx: HashMap<String, serde_json::Value>,
+    config: Option<&str>,
+    json_out: bool,
+) -> Result<()> {
+    let content = std::fs::read_to_string(overlay_path)
+        .map_err(|e| anyhow::anyhow!("cannot read {overlay_path}: {e}"))?;
+    let overlay: ampersona_core::spec::authority::AuthorityOverlay = serde_json::from_str(&content)
+        .map_err(|e| anyhow::anyhow!("{overlay_path}: invalid authority overlay: {e}"))?;
+
+    let actions = vocabulary_actions(persona);
+    let before = resolve_persona_authority(file, persona, config);
+    let after = before
+        .as_ref()
+        .map(|r| ampersona_engine::policy::precedence::apply_overlay(r, &overlay));
+    let checker = ampersona_engine::policy::checker::DefaultPolicyChecker.cached();
+    let rows_before = decide_actions(&checker, before.as_ref(), &actions, path.as_deref(), &ctx)?;
+    let rows_after = decide_actions(&checker, after.as_ref(), &actions, path.as_deref(), &ctx)?;
+    let flips: Vec<_> = rows_before
+        .iter()
+        .zip(&rows_after)
+        .filter(|(b, a)| b.1 != a.1)
+        .map(|((action, before, _), (_, after, reason))| (action, *before, *after, reason))
+        .collect();
+
+    if json_out {
+        let changes: Vec<serde_json::Value> = flips
+            .iter()
+            .map(|(action, before, after, reason)| {
+                serde_json::json!({
+                    "action": action,
+                    "before": before,
+                    "after": after,
+                    "reason": reason,
+                })
+            })
+            .collect();
+        let output = serde_json::json!({
+            "file": file,
+            "overlay": overlay_path,
+            "evaluated": actions.len(),
+            "changes": changes,
+        });
+        println!("{}", to_json(&output)?);
+        return Ok(());
+    }
+    if flips.is_empty() {
+        println!("no decision changes ({} actions evaluated)", actions.len());
+        return Ok(());
+    }
+    let w_action = flips.iter().map(|f| f.0.len()).max().unwrap_or(6).max(6);
+    println!(
+        "  {:<w_action$}  {:<13}  {:<13}  REASON",
+        "ACTION", "BEFORE", "AFTER"
+    );
+    for (action, before, after, reason) in &flips {
+        println!("  {action:<w_action$}  {before:<13}  {after:<13}  {reason}");
+    }
+    Ok(())
+}
+
+/// `amp authority --matrix`: every vocabulary action checked against the
+/// resolved authority (see [`resolve_persona_authority`]).
+///
+/// Each action is asked once, so the reported cache stats are all misses here;
+/// they show what a long-lived caller (a batch, a server) sharing one
+/// [`CachingChecker`](ampersona_engine::policy::checker::CachingChecker)
+/// would save.
+fn print_authority_matrix(
+    file: &str,
+    persona: &ampersona_core::spec::Persona,
+    path: Option<String>,
+    ctx: HashMap<String, serde_json::Value>,
+    config: Option<&str>,
+    json_out: bool,
+) -> Result<()> {
+    let actions = vocabulary_actions(persona);
+    let resolved = resolve_persona_authority(file, persona, config);
+    let checker = ampersona_engine::policy::checker::DefaultPolicyChecker.cached();
+    let rows = decide_actions(&checker, resolved.as_ref(), &actions, path.as_deref(), &ctx)?;
+
+    if json_out {
+        let matrix: Vec<serde_json::Value> = rows
+            .iter()
+            .map(|(action, decision, reason)| {
+                serde_json::json!({
+                    "action": action,
+                    "decision": decision,
+                    "reason": reason,
+                })
+            })
+            .collect();
+        let output = serde_json::json!({
+            "autonomy": resolved.as_ref().map(|r| format!("{:?}", r.autonomy).to_lowercase()),
+            "path": path,
+            "context": ctx,
+            "matrix": matrix,
+            "cache": checker.stats(),
+        });
+        println!("{}", to_json(&output)?);
+        return Ok(());
+    }
+
+    let w_action = rows.iter().map(|r| r.0.len()).max().unwrap_or(6).max(6);
+    println!("  {:<w_action$}  {:<13}  REASON", "ACTION", "DECISION");
+    for (action, decision, reason) in &rows {
+        println!("  {action:<w_action$}  {decision:<13}  {reason}");
+    }
+    let stats = checker.stats();
+    info!("cache: {} hits, {} misses", stats.hits, stats.misses);
+    Ok(())
+}
+
+/// Parse a human-readable duration (`90m`, `2h30m`) into whole seconds.
+fn parse_ttl(s: &str) -> std::result::Result<u64, String> {
+    let secs = humantime::parse_duration(s)
+        .map_err(|e| format!("invalid duration '{s}': {e}"))?
+        .as_secs();
+    if secs == 0 {
+        return Err(format!("TTL must be at least 1s, got '{s}'"));
+    }
+    Ok(secs)
+}
+
+fn cmd_elevate(
+    file: &str,
+    sidecars: &ampersona_core::sidecar::Sidecars,
+    elevation_id: &str,
+    reason: &str,
+    ttl_override: Option<u64>,
+    lock_timeout: Duration,
+) -> Result<()> {
     let data = read_persona(file)?;
     let persona: ampersona_core::spec::Persona = serde_json::from_value(data)?;
 
@@ -918,21 +3680,24 @@ fn cmd_elevate(file: &str, elevation_id: &str, reason: &str) -> Result<()> {
         .and_then(|elevs| elevs.iter().find(|e| e.id == elevation_id))
         .ok_or_else(|| anyhow::anyhow!("elevation '{elevation_id}' not found"))?;
 
-    let state_path = file.replace(".json", ".state.json");
-    let writer = ampersona_engine::state::writer::StateWriter::acquire(&state_path);
-    let mut state = ampersona_engine::state::phase::load_state(&state_path)
-        .unwrap_or_else(|_| ampersona_core::state::PhaseState::new(persona.name.clone()));
+    let store = state_store(sidecars)?;
+    let writer = ampersona_engine::state::writer::StateWriter::with_store(&*store, lock_timeout)?;
+    let mut state = store.load_or_new(&persona.name);
 
     // Enforce TTL on existing elevations
     let expired = ampersona_engine::state::elevation::enforce_ttl(&mut state);
     for eid in &expired {
-        eprintln!("  elevation '{eid}' expired");
+        info!("elevation '{eid}' expired");
     }
 
+    let ttl_seconds = ampersona_engine::state::elevation::effective_ttl(elev, ttl_override);
+    if let Some(requested) = ttl_override.filter(|r| *r > ttl_seconds) {
+        warn!("requested TTL {requested}s capped at max_ttl_seconds ({ttl_seconds}s)");
+    }
     ampersona_engine::state::elevation::activate(
         &mut state,
         elevation_id,
-        elev.ttl_seconds as i64,
+        ttl_seconds as i64,
         reason,
         "cli",
     );
@@ -945,36 +3710,311 @@ fn cmd_elevate(file: &str, elevation_id: &str, reason: &str) -> Result<()> {
         "elevation_id": elevation_id,
         "action": "activate",
         "reason": reason,
-        "ttl_seconds": elev.ttl_seconds,
+        "ttl_seconds": ttl_seconds,
+        "ttl_requested_seconds": ttl_override,
         "granted_by": "cli",
         "state_rev": state.state_rev,
     });
 
-    if let Ok(ref w) = writer {
-        w.maybe_audit(persona.audit.as_ref(), "ElevationChange", &audit_entry)?;
-        w.write_state(&state)?;
-    } else {
-        // Fallback: write without lock (backward compat)
-        let json = serde_json::to_string_pretty(&state)?;
-        ampersona_engine::state::atomic::atomic_write(&state_path, json.as_bytes())?;
-    }
+    writer.maybe_audit(persona.audit.as_ref(), "ElevationChange", &audit_entry)?;
+    writer.write_state(&state)?;
 
-    eprintln!(
-        "  elevation '{elevation_id}' activated (TTL: {}s)",
-        elev.ttl_seconds
+    info!(
+        elevation = elevation_id,
+        ttl_seconds, "elevation '{elevation_id}' activated (TTL: {ttl_seconds}s)"
     );
     Ok(())
 }
 
 struct GateOpts {
     file: String,
+    sidecars: SidecarArgs,
+    trust: TrustArgs,
     evaluate: Option<String>,
     metrics_file: Option<String>,
+    force_reeval: bool,
+    no_hooks: bool,
+    hook_timeout: Duration,
     override_gate: Option<String>,
     reason: Option<String>,
     approver: Option<String>,
     approve: Option<String>,
+    reset_phase: Option<String>,
+    force: bool,
+    simulate: Option<String>,
+    backtest: Option<String>,
+    list: bool,
+    observe_report: bool,
+    promote_to: Option<String>,
+    promote_gates: Vec<String>,
+    all_observe: bool,
+    reminder: Option<ReminderPolicy>,
+    pending: bool,
+    lock_timeout: Duration,
+    json_out: bool,
+}
+
+/// How long `pending` has waited at `now`, to the second.
+fn pending_age(
+    pending: &ampersona_core::state::PendingTransition,
+    now: chrono::DateTime<chrono::Utc>,
+) -> Duration {
+    Duration::from_secs((now - pending.created_at).num_seconds().max(0) as u64)
+}
+
+/// `pending` as `--pending --json` reports it.
+fn pending_entry(
+    pending: &ampersona_core::state::PendingTransition,
+    now: chrono::DateTime<chrono::Utc>,
+) -> serde_json::Value {
+    serde_json::json!({
+        "gate_id": pending.gate_id,
+        "from_phase": pending.from_phase,
+        "to_phase": pending.to_phase,
+        "created_at": pending.created_at,
+        "age_seconds": pending_age(pending, now).as_secs(),
+    })
+}
+
+/// Deliver `notice` to the AMPERSONA_NOTIFY_URL webhook, when one is set.
+/// A failed delivery is a warning: it must not undo the decision it reports.
+fn send_notice(notice: &ApprovalNotice) {
+    use ampersona_engine::gates::notify::URL_ENV;
+
+    let Ok(url) = std::env::var(URL_ENV) else {
+        return;
+    };
+    #[cfg(feature = "notify")]
+    if let Err(e) = ampersona_engine::gates::notify::post_webhook(&url, notice) {
+        warn!("{e}");
+    }
+    #[cfg(not(feature = "notify"))]
+    {
+        let _ = notice;
+        warn!("{URL_ENV}={url} ignored: built without the `notify` feature");
+    }
+}
+
+fn print_gate_list(
+    gates: &[ampersona_core::spec::gates::Gate],
+    state: &ampersona_core::state::PhaseState,
+    json_out: bool,
+) -> Result<()> {
+    let candidate = |g: &ampersona_core::spec::gates::Gate| g.from_phase == state.current_phase;
+
+    if json_out {
+        let rows: Vec<serde_json::Value> = gates
+            .iter()
+            .map(|g| {
+                serde_json::json!({
+                    "id": g.id,
+                    "direction": g.direction,
+                    "from_phase": g.from_phase,
+                    "to_phase": g.to_phase,
+                    "enforcement": g.enforcement,
+                    "approval": g.approval,
+                    "candidate": candidate(g),
+                })
+            })
+            .collect();
+        let output = serde_json::json!({
+            "current_phase": state.current_phase,
+            "gates": rows,
+        });
+        println!("{}", to_json(&output)?);
+        return Ok(());
+    }
+
+    println!(
+        "  current phase: {}",
+        state.current_phase.as_deref().unwrap_or("none")
+    );
+    if gates.is_empty() {
+        println!("  (no gates defined)");
+        return Ok(());
+    }
+    let label = |v: serde_json::Value| v.as_str().unwrap_or_default().to_string();
+    let transitions: Vec<String> = gates
+        .iter()
+        .map(|g| {
+            format!(
+                "{} \u{2192} {}",
+                g.from_phase.as_deref().unwrap_or("none"),
+                g.to_phase
+            )
+        })
+        .collect();
+    let w_id = gates.iter().map(|g| g.id.len()).max().unwrap_or(2).max(2);
+    // Width in chars: the arrow is one column but three bytes
+    let w_tr = transitions
+        .iter()
+        .map(|t| t.chars().count())
+        .max()
+        .unwrap_or(9)
+        .max(9);
+    println!(
+        "  {:<w_id$}  {:<9}  {:<w_tr$}  {:<11}  {:<8}  CANDIDATE",
+        "ID", "DIRECTION", "FROM \u{2192} TO", "ENFORCEMENT", "APPROVAL"
+    );
+    for (g, tr) in gates.iter().zip(&transitions) {
+        println!(
+            "  {:<w_id$}  {:<9}  {tr}{}  {:<11}  {:<8}  {}",
+            g.id,
+            label(serde_json::json!(g.direction)),
+            " ".repeat(w_tr - tr.chars().count()),
+            label(serde_json::json!(g.enforcement)),
+            label(serde_json::json!(g.approval)),
+            if candidate(g) { "yes" } else { "no" },
+        );
+    }
+    Ok(())
+}
+
+/// Rewrite the selected observe-mode gates of `file` to `enforcement`.
+///
+/// An empty `selected` means every observe-mode gate. Gates the audit log has
+/// never seen fire in observe mode are refused unless `force`; the result is
+/// validated before the file is backed up and atomically replaced.
+fn promote_observe_gates(
+    file: &str,
+    store: &dyn StateStore,
+    persona: &ampersona_core::spec::Persona,
+    selected: &[String],
+    enforcement: &str,
+    force: bool,
+    lock_timeout: Duration,
+) -> Result<Vec<String>> {
+    let content =
+        std::fs::read_to_string(file).map_err(|e| anyhow::anyhow!("cannot read {file}: {e}"))?;
+    let mut data: serde_json::Value =
+        serde_json::from_str(&content).map_err(|e| anyhow::anyhow!("{file}: invalid JSON: {e}"))?;
+    let Some(raw_gates) = data.get_mut("gates").and_then(|g| g.as_array_mut()) else {
+        bail!(
+            "{file}: no gates defined in this file (inherited gates must be promoted in the base)"
+        );
+    };
+
+    let is_observe = |g: &serde_json::Value| g["enforcement"] == "observe";
+    let targets: Vec<String> = if selected.is_empty() {
+        raw_gates
+            .iter()
+            .filter(|g| is_observe(g))
+            .filter_map(|g| g["id"].as_str().map(str::to_string))
+            .collect()
+    } else {
+        for id in selected {
+            let Some(gate) = raw_gates.iter().find(|g| g["id"] == *id.as_str()) else {
+                bail!("gate '{id}' not defined in {file}");
+            };
+            if !is_observe(gate) {
+                bail!("gate '{id}' is not in observe mode");
+            }
+        }
+        selected.to_vec()
+    };
+    if targets.is_empty() {
+        bail!("{file}: no observe-mode gates to promote");
+    }
+
+    let audit = store.audit_lines()?;
+    let report = ampersona_engine::gates::observe::observe_report_lines(
+        persona.gates.as_deref().unwrap_or_default(),
+        audit.iter().flatten().map(String::as_str),
+    );
+    let unobserved: Vec<&str> = targets
+        .iter()
+        .filter(|id| {
+            report
+                .iter()
+                .find(|s| s.gate_id == **id)
+                .is_none_or(|s| s.would_fire == 0)
+        })
+        .map(String::as_str)
+        .collect();
+    if !unobserved.is_empty() && !force {
+        bail!(
+            "no observed decisions for gate(s) {}; refusing to enforce untested gates (use --force)",
+            unobserved.join(", ")
+        );
+    }
+
+    for gate in raw_gates.iter_mut() {
+        if gate["id"]
+            .as_str()
+            .is_some_and(|id| targets.iter().any(|t| t == id))
+        {
+            gate["enforcement"] = enforcement.into();
+        }
+    }
+
+    let resolved = ampersona_core::prompt::resolve_extends(data.clone(), file)?;
+    let check = ampersona_core::schema::check(&resolved, file, false);
+    for e in &check.errors {
+        error!("{}: {}", e.code, e.message);
+    }
+    if !check.errors.is_empty() {
+        bail!(
+            "promoted persona has {} validation error(s); not writing {file}",
+            check.errors.len()
+        );
+    }
+
+    let writer = ampersona_engine::state::writer::StateWriter::with_store(store, lock_timeout)?;
+    std::fs::copy(file, ampersona_core::sidecar::sidecar_path(file, ".bak"))?;
+    let json = serde_json::to_string_pretty(&data)?;
+    ampersona_engine::state::atomic::atomic_write(file, json.as_bytes())?;
+
+    let audit_entry = serde_json::json!({
+        "event_type": "GateEnforcementChange",
+        "gate_ids": targets,
+        "from": "observe",
+        "to": enforcement,
+        "forced": !unobserved.is_empty(),
+    });
+    writer.maybe_audit(
+        persona.audit.as_ref(),
+        "GateEnforcementChange",
+        &audit_entry,
+    )?;
+    Ok(targets)
+}
+
+fn print_observe_report(
+    report: &[ampersona_engine::gates::observe::ObserveSummary],
     json_out: bool,
+) -> Result<()> {
+    if json_out {
+        let output = serde_json::json!({ "gates": report });
+        println!("{}", to_json(&output)?);
+        return Ok(());
+    }
+
+    if report.is_empty() {
+        println!("  (no observe-mode gates)");
+        return Ok(());
+    }
+    let label = |v: serde_json::Value| v.as_str().unwrap_or_default().to_string();
+    let w_id = report
+        .iter()
+        .map(|s| s.gate_id.len())
+        .max()
+        .unwrap_or(2)
+        .max(2);
+    println!(
+        "  {:<w_id$}  {:<11}  {:>10}  {:<25}  LAST SEEN",
+        "ID", "ENFORCEMENT", "WOULD FIRE", "FIRST SEEN"
+    );
+    for s in report {
+        println!(
+            "  {:<w_id$}  {:<11}  {:>10}  {:<25}  {}",
+            s.gate_id,
+            label(serde_json::json!(s.enforcement)),
+            s.would_fire,
+            s.first_seen.as_deref().unwrap_or("-"),
+            s.last_seen.as_deref().unwrap_or("-"),
+        );
+    }
+    Ok(())
 }
 
 fn cmd_gate(opts: GateOpts) -> CmdExit {
@@ -982,7 +4022,10 @@ fn cmd_gate(opts: GateOpts) -> CmdExit {
     match cmd_gate_inner(opts) {
         Ok(exit) => exit,
         Err(e) => CmdExit::JsonErr {
-            code: "E_INTERNAL",
+            code: e
+                .downcast_ref::<ampersona_engine::error::EngineError>()
+                .map(|e| e.code())
+                .unwrap_or("E_INTERNAL"),
             message: format!("{e:#}"),
             json: json_out,
         },
@@ -992,29 +4035,255 @@ fn cmd_gate(opts: GateOpts) -> CmdExit {
 fn cmd_gate_inner(opts: GateOpts) -> Result<CmdExit> {
     let GateOpts {
         ref file,
+        sidecars,
+        trust,
         evaluate,
         metrics_file,
+        force_reeval,
+        no_hooks,
+        hook_timeout,
         override_gate,
         reason,
         approver,
         approve,
+        reset_phase,
+        force,
+        simulate,
+        backtest,
+        list,
+        observe_report,
+        promote_to,
+        promote_gates,
+        all_observe,
+        reminder,
+        pending,
+        lock_timeout,
         json_out,
     } = opts;
-    let data = read_persona(file)?;
-    let persona: ampersona_core::spec::Persona = serde_json::from_value(data)?;
+    let data = read_trusted_persona(file, Default::default(), &trust)?;
 
-    // Handle --approve: apply a pending transition
-    if let Some(gate_id) = approve {
-        let state_path = file.replace(".json", ".state.json");
-        let writer = ampersona_engine::state::writer::StateWriter::acquire(&state_path);
-        let mut state = ampersona_engine::state::phase::load_state(&state_path)
-            .unwrap_or_else(|_| ampersona_core::state::PhaseState::new(persona.name.clone()));
+    // Sidecars sit next to the persona unless --state / --audit say otherwise.
+    // A piped persona without --state has none: it runs in memory.
+    let ephemeral = file == "-" && sidecars.state.is_none();
+    if file == "-" && promote_to.is_some() {
+        bail!("--promote-to rewrites the persona file; it cannot read from stdin");
+    }
+    if ephemeral && (override_gate.is_some() || approve.is_some() || reset_phase.is_some()) {
+        bail!("a persona read from stdin needs --state to override, approve or reset a phase");
+    }
+    if ephemeral && observe_report && sidecars.audit.is_none() {
+        bail!("a persona read from stdin needs --audit (or --state) for --observe-report");
+    }
+    let paths = sidecars.resolve(file);
+    let store: Box<dyn StateStore> = if ephemeral {
+        Box::new(InMemoryStateStore::default())
+    } else {
+        state_store(&paths)?
+    };
 
-        let pending = state
+    // Handle --pending: what is waiting on an approver, and since when.
+    // It needs only the name and the state, so the persona isn't parsed.
+    if pending {
+        let name = data["name"].as_str().unwrap_or_default();
+        let now = chrono::Utc::now();
+        let state = store.load_or_new(name);
+        let entry = state
             .pending_transition
             .as_ref()
-            .ok_or_else(|| anyhow::anyhow!("no pending transition"))?;
-
+            .map(|pending| pending_entry(pending, now));
+        if json_out {
+            let output = serde_json::json!({"name": name, "pending": entry});
+            println!("{}", to_json(&output)?);
+        } else if let Some(pending) = &state.pending_transition {
+            println!(
+                "{}: gate '{}' ({} \u{2192} {}) pending since {} ({} ago)",
+                name,
+                pending.gate_id,
+                pending.from_phase.as_deref().unwrap_or("none"),
+                pending.to_phase,
+                pending.created_at.to_rfc3339(),
+                humantime::format_duration(pending_age(pending, now))
+            );
+        } else {
+            info!("no pending approval");
+        }
+        return Ok(CmdExit::Ok);
+    }
+
+    let persona: ampersona_core::spec::Persona = serde_json::from_value(data)?;
+    #[cfg(feature = "otel")]
+    let _span = tracing::info_span!("amp.gate", persona = %persona.name).entered();
+    let current_state = || store.load_or_new(&persona.name);
+
+    // Handle --list: which gates apply in the current phase at all
+    if list {
+        let state = current_state();
+        print_gate_list(
+            persona.gates.as_deref().unwrap_or_default(),
+            &state,
+            json_out,
+        )?;
+        return Ok(CmdExit::Ok);
+    }
+
+    // Handle --remind: nag about an approval that has been waiting too long
+    if let Some(policy) = reminder {
+        let notice = ampersona_engine::gates::notify::pending_notice(
+            &current_state(),
+            &policy,
+            chrono::Utc::now(),
+        );
+        if let Some(notice) = &notice {
+            let message = format!(
+                "gate '{}' ({} \u{2192} {}) has waited {} for approval (use --approve {})",
+                notice.gate_id,
+                notice.from_phase.as_deref().unwrap_or("none"),
+                notice.to_phase,
+                humantime::format_duration(Duration::from_secs(notice.age_seconds)),
+                notice.gate_id
+            );
+            match notice.urgency {
+                Urgency::Escalation => error!(gate = %notice.gate_id, "escalation: {message}"),
+                _ => warn!(gate = %notice.gate_id, "reminder: {message}"),
+            }
+            send_notice(notice);
+        } else if !json_out {
+            info!("no pending approval is due a reminder");
+        }
+        if json_out {
+            let output = match &notice {
+                Some(notice) => serde_json::json!({"due": true, "notice": notice}),
+                None => serde_json::json!({"due": false}),
+            };
+            println!("{}", to_json(&output)?);
+        }
+        return Ok(CmdExit::Ok);
+    }
+
+    // Handle --observe-report: shadow-mode decisions tallied from the audit log
+    if observe_report {
+        // A piped persona's store is in memory; its --audit file is read directly.
+        let audit = if ephemeral {
+            FileStateStore::new(&paths.state, &paths.audit).audit_lines()?
+        } else {
+            store.audit_lines()?
+        };
+        let report = ampersona_engine::gates::observe::observe_report_lines(
+            persona.gates.as_deref().unwrap_or_default(),
+            audit.iter().flatten().map(String::as_str),
+        );
+        print_observe_report(&report, json_out)?;
+        return Ok(CmdExit::Ok);
+    }
+
+    // Handle --promote-to: end a shadow period by rewriting gate enforcement
+    if let Some(enforcement) = promote_to {
+        if promote_gates.is_empty() && !all_observe {
+            bail!("--promote-to requires --gate <id> or --all-observe");
+        }
+        let promoted = promote_observe_gates(
+            file,
+            &*store,
+            &persona,
+            &promote_gates,
+            &enforcement,
+            force,
+            lock_timeout,
+        )?;
+        if json_out {
+            let output = serde_json::json!({
+                "promoted": promoted,
+                "enforcement": enforcement,
+                "backup": ampersona_core::sidecar::sidecar_path(file, ".bak"),
+            });
+            println!("{}", to_json(&output)?);
+        } else {
+            for id in &promoted {
+                info!("{id}: observe \u{2192} {enforcement}");
+            }
+            info!("wrote {file}");
+        }
+        return Ok(CmdExit::Ok);
+    }
+
+    // Handle --simulate / --backtest: replay a timeline against an in-memory state.
+    // A simulation starts from the current state; a backtest replays history
+    // from a fresh one, as it was when the history began.
+    let replay = match (simulate, backtest) {
+        (Some(path), _) => Some((path, false)),
+        (None, Some(path)) => Some((path, true)),
+        (None, None) => None,
+    };
+    if let Some((timeline_path, is_backtest)) = replay {
+        let gates = persona
+            .gates
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("no gates defined"))?;
+        let content = std::fs::read_to_string(&timeline_path)
+            .map_err(|e| anyhow::anyhow!("cannot read {timeline_path}: {e}"))?;
+        let timeline = ampersona_engine::gates::simulate::parse_timeline(&content)?;
+
+        let initial = if is_backtest {
+            ampersona_core::state::PhaseState::new(persona.name.clone())
+        } else {
+            current_state()
+        };
+        let start_phase = initial.current_phase.clone();
+        let steps = ampersona_engine::gates::simulate::simulate(gates, initial, &timeline);
+        let transitions = steps.iter().filter(|s| s.transitioned()).count();
+
+        if json_out {
+            let mut output = serde_json::json!({
+                "start_phase": start_phase,
+                "end_phase": steps.last().map(|s| s.phase_after.clone()).unwrap_or(start_phase),
+                "transitions": transitions,
+                "steps": steps,
+            });
+            if is_backtest {
+                let fired: Vec<_> = steps
+                    .iter()
+                    .filter_map(|s| {
+                        Some(serde_json::json!({
+                            "ts": s.ts,
+                            "gate_id": s.gate_id.as_ref()?,
+                            "decision": s.decision,
+                        }))
+                    })
+                    .collect();
+                output["fired"] = serde_json::json!(fired);
+            }
+            println!("{}", to_json(&output)?);
+        } else {
+            for step in &steps {
+                let before = step.phase_before.as_deref().unwrap_or("none");
+                let after = step.phase_after.as_deref().unwrap_or("none");
+                match (&step.gate_id, &step.decision) {
+                    (Some(gate), Some(decision)) if step.transitioned() => println!(
+                        "  {}  {before} \u{2192} {after}  ({gate}: {decision})",
+                        step.ts.to_rfc3339()
+                    ),
+                    (Some(gate), Some(decision)) => {
+                        println!("  {}  {before}  ({gate}: {decision})", step.ts.to_rfc3339())
+                    }
+                    _ => println!("  {}  {before}", step.ts.to_rfc3339()),
+                }
+            }
+            println!("  {} snapshots, {transitions} transitions", steps.len());
+        }
+        return Ok(CmdExit::Ok);
+    }
+
+    // Handle --approve: apply a pending transition
+    if let Some(gate_id) = approve {
+        let writer =
+            ampersona_engine::state::writer::StateWriter::with_store(&*store, lock_timeout)?;
+        let mut state = store.load_or_new(&persona.name);
+
+        let pending = state
+            .pending_transition
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("no pending transition"))?;
+
         if pending.gate_id != gate_id {
             bail!("pending gate is '{}', not '{gate_id}'", pending.gate_id);
         }
@@ -1039,7 +4308,7 @@ fn cmd_gate_inner(opts: GateOpts) -> Result<CmdExit> {
         });
         state.pending_transition = None;
 
-        let audit_entry = serde_json::json!({
+        let mut audit_entry = serde_json::json!({
             "event_type": "GateTransition",
             "gate_id": p_gate_id,
             "decision": "approved",
@@ -1048,15 +4317,16 @@ fn cmd_gate_inner(opts: GateOpts) -> Result<CmdExit> {
             "state_rev": state.state_rev,
             "metrics_hash": metrics_hash,
         });
-
-        if let Ok(ref w) = writer {
-            w.maybe_audit(persona.audit.as_ref(), "GateTransition", &audit_entry)?;
-            w.write_state(&state)?;
-        } else {
-            let json = serde_json::to_string_pretty(&state)?;
-            ampersona_engine::state::atomic::atomic_write(&state_path, json.as_bytes())?;
+        if let Some(note) = persona
+            .phase(&to_phase)
+            .and_then(|p| p.entry_note.as_deref())
+        {
+            audit_entry["entry_note"] = note.into();
         }
 
+        writer.maybe_audit(persona.audit.as_ref(), "GateTransition", &audit_entry)?;
+        writer.write_state(&state)?;
+
         if json_out {
             let output = serde_json::json!({
                 "gate_id": p_gate_id,
@@ -1064,10 +4334,10 @@ fn cmd_gate_inner(opts: GateOpts) -> Result<CmdExit> {
                 "from_phase": from_phase,
                 "to_phase": to_phase,
             });
-            println!("{}", serde_json::to_string_pretty(&output)?);
+            println!("{}", to_json(&output)?);
         } else {
-            eprintln!(
-                "  approved: {} \u{2192} {}",
+            info!(
+                "approved: {} \u{2192} {}",
                 from_phase.as_deref().unwrap_or("none"),
                 to_phase
             );
@@ -1075,6 +4345,57 @@ fn cmd_gate_inner(opts: GateOpts) -> Result<CmdExit> {
         return Ok(CmdExit::Ok);
     }
 
+    // Handle --reset-phase: administrative phase change, not tied to any gate
+    if let Some(phase) = reset_phase {
+        let reason = reason.ok_or_else(|| anyhow::anyhow!("--reason required for reset-phase"))?;
+        let approver =
+            approver.ok_or_else(|| anyhow::anyhow!("--approver required for reset-phase"))?;
+
+        let referenced = persona.gates.as_ref().is_some_and(|gates| {
+            gates
+                .iter()
+                .any(|g| g.to_phase == phase || g.from_phase.as_deref() == Some(phase.as_str()))
+        });
+        if !referenced && !force {
+            bail!(
+                "reset-phase rejected: phase '{phase}' is not referenced by any gate (use --force)"
+            );
+        }
+
+        let writer =
+            ampersona_engine::state::writer::StateWriter::with_store(&*store, lock_timeout)?;
+        let mut state = store.load_or_new(&persona.name);
+
+        let from_phase = state.current_phase.clone();
+        state.current_phase = Some(phase.clone());
+        state.pending_transition = None;
+        state.active_overlay = None;
+        state.state_rev += 1;
+        state.updated_at = chrono::Utc::now();
+
+        let audit_entry = serde_json::json!({
+            "event_type": "AdminPhaseReset",
+            "from_phase": from_phase,
+            "to_phase": phase,
+            "reason": reason,
+            "approver": approver,
+            "forced": !referenced,
+            "state_rev": state.state_rev,
+        });
+
+        writer.maybe_audit(persona.audit.as_ref(), "AdminPhaseReset", &audit_entry)?;
+        writer.write_state(&state)?;
+
+        if !json_out {
+            info!(
+                "phase reset: {} \u{2192} {phase} (by {approver})",
+                from_phase.as_deref().unwrap_or("none")
+            );
+        }
+        println!("{}", to_json(&audit_entry)?);
+        return Ok(CmdExit::Ok);
+    }
+
     if let Some(gate_id) = override_gate {
         let reason = reason.ok_or_else(|| anyhow::anyhow!("--reason required for override"))?;
         let approver =
@@ -1086,10 +4407,9 @@ fn cmd_gate_inner(opts: GateOpts) -> Result<CmdExit> {
             .and_then(|g| g.iter().find(|g| g.id == gate_id))
             .ok_or_else(|| anyhow::anyhow!("gate '{gate_id}' not found"))?;
 
-        let state_path = file.replace(".json", ".state.json");
-        let writer = ampersona_engine::state::writer::StateWriter::acquire(&state_path);
-        let mut state = ampersona_engine::state::phase::load_state(&state_path)
-            .unwrap_or_else(|_| ampersona_core::state::PhaseState::new(persona.name.clone()));
+        let writer =
+            ampersona_engine::state::writer::StateWriter::with_store(&*store, lock_timeout)?;
+        let mut state = store.load_or_new(&persona.name);
 
         // Phase match check: gate.from_phase must match current phase
         if gate.from_phase.as_deref() != state.current_phase.as_deref() {
@@ -1103,33 +4423,15 @@ fn cmd_gate_inner(opts: GateOpts) -> Result<CmdExit> {
 
         // Criteria check: if metrics provided, criteria must be failing
         if let Some(ref mf) = metrics_file {
-            let mdata: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(mf)?)?;
-            struct JsonMetricsOvr(serde_json::Value);
-            impl ampersona_core::traits::MetricsProvider for JsonMetricsOvr {
-                fn get_metric(
-                    &self,
-                    query: &ampersona_core::traits::MetricQuery,
-                ) -> Result<ampersona_core::traits::MetricSample, ampersona_core::errors::MetricError>
-                {
-                    self.0
-                        .get(&query.name)
-                        .map(|v| ampersona_core::traits::MetricSample {
-                            name: query.name.clone(),
-                            value: v.clone(),
-                            sampled_at: chrono::Utc::now(),
-                        })
-                        .ok_or(ampersona_core::errors::MetricError::NotFound(
-                            query.name.clone(),
-                        ))
-                }
-            }
-            let m = JsonMetricsOvr(mdata);
+            let m = ampersona_engine::gates::metrics::provider_for(mf)?;
             let evaluator = ampersona_engine::gates::evaluator::DefaultGateEvaluator;
-            let (all_pass, _, _) = evaluator.evaluate_criteria(
+            let (all_pass, _, _) = evaluator.evaluate_criteria_at(
                 &gate.criteria,
-                &m,
+                m.as_ref(),
                 gate.direction,
                 gate.metrics_schema.as_ref(),
+                gate.max_age_seconds,
+                chrono::Utc::now(),
             );
             if all_pass {
                 bail!(
@@ -1170,22 +4472,17 @@ fn cmd_gate_inner(opts: GateOpts) -> Result<CmdExit> {
             "state_rev": state.state_rev,
         });
 
-        if let Ok(ref w) = writer {
-            w.maybe_audit(persona.audit.as_ref(), "Override", &audit_entry)?;
-            w.write_state(&state)?;
-        } else {
-            let json = serde_json::to_string_pretty(&state)?;
-            ampersona_engine::state::atomic::atomic_write(&state_path, json.as_bytes())?;
-        }
+        writer.maybe_audit(persona.audit.as_ref(), "Override", &audit_entry)?;
+        writer.write_state(&state)?;
 
         if !json_out {
-            eprintln!(
-                "  override: {} \u{2192} {} (by {approver})",
+            info!(
+                "override: {} \u{2192} {} (by {approver})",
                 record.from_phase.as_deref().unwrap_or("none"),
                 record.to_phase
             );
         }
-        println!("{}", serde_json::to_string_pretty(&record)?);
+        println!("{}", to_json(&record)?);
         return Ok(CmdExit::Ok);
     }
 
@@ -1197,37 +4494,24 @@ fn cmd_gate_inner(opts: GateOpts) -> Result<CmdExit> {
 
         let metrics_path =
             metrics_file.ok_or_else(|| anyhow::anyhow!("--metrics required for evaluate"))?;
-        let metrics_data: serde_json::Value =
-            serde_json::from_str(&std::fs::read_to_string(&metrics_path)?)?;
-
-        struct JsonMetrics(serde_json::Value);
-        impl ampersona_core::traits::MetricsProvider for JsonMetrics {
-            fn get_metric(
-                &self,
-                query: &ampersona_core::traits::MetricQuery,
-            ) -> Result<ampersona_core::traits::MetricSample, ampersona_core::errors::MetricError>
-            {
-                self.0
-                    .get(&query.name)
-                    .map(|v| ampersona_core::traits::MetricSample {
-                        name: query.name.clone(),
-                        value: v.clone(),
-                        sampled_at: chrono::Utc::now(),
-                    })
-                    .ok_or(ampersona_core::errors::MetricError::NotFound(
-                        query.name.clone(),
-                    ))
-            }
+        let metrics = ampersona_engine::gates::metrics::provider_for(&metrics_path)?;
+
+        if ephemeral {
+            return evaluate_in_memory(
+                gates,
+                &current_state(),
+                metrics.as_ref(),
+                &gate_id,
+                json_out,
+            );
         }
 
-        let metrics = JsonMetrics(metrics_data);
-        let state_path = file.replace(".json", ".state.json");
-        let writer = ampersona_engine::state::writer::StateWriter::acquire(&state_path);
-        let mut state = ampersona_engine::state::phase::load_state(&state_path)
-            .unwrap_or_else(|_| ampersona_core::state::PhaseState::new(persona.name.clone()));
+        let writer =
+            ampersona_engine::state::writer::StateWriter::with_store(&*store, lock_timeout)?;
+        let mut state = store.load_or_new(&persona.name);
 
         // Migrate legacy sidecar overlay into state (ADR-010)
-        let sidecar_path = file.replace(".json", ".authority_overlay.json");
+        let sidecar_path = ampersona_core::sidecar::sidecar_path(file, ".authority_overlay.json");
         if state.active_overlay.is_none() {
             if let Ok(sidecar_content) = std::fs::read_to_string(&sidecar_path) {
                 if let Ok(overlay) = serde_json::from_str::<
@@ -1237,7 +4521,7 @@ fn cmd_gate_inner(opts: GateOpts) -> Result<CmdExit> {
                     state.active_overlay = Some(overlay);
                     // Don't delete sidecar yet — wait until state is successfully written.
                     if !json_out {
-                        eprintln!("  migrated sidecar overlay to state");
+                        info!("migrated sidecar overlay to state");
                     }
                 }
             }
@@ -1247,12 +4531,16 @@ fn cmd_gate_inner(opts: GateOpts) -> Result<CmdExit> {
         ampersona_engine::state::elevation::enforce_ttl(&mut state);
 
         let evaluator = ampersona_engine::gates::evaluator::DefaultGateEvaluator;
-        let result = evaluator.evaluate(gates, &state, &metrics);
+        let result = if force_reeval {
+            evaluator.reevaluate(gates, &state, metrics.as_ref())
+        } else {
+            evaluator.evaluate(gates, &state, metrics.as_ref())
+        };
 
         if let Some(record) = result {
             if record.gate_id == gate_id || gate_id == "*" {
                 // Build audit entry once; each branch writes it exactly once.
-                let audit_entry = serde_json::json!({
+                let mut audit_entry = serde_json::json!({
                     "event_type": "GateTransition",
                     "gate_id": record.gate_id,
                     "direction": record.direction,
@@ -1266,25 +4554,14 @@ fn cmd_gate_inner(opts: GateOpts) -> Result<CmdExit> {
                     "state_rev": record.state_rev,
                     "metrics_hash": record.metrics_hash,
                 });
+                if record.forced_reeval {
+                    audit_entry["forced_reeval"] = serde_json::Value::Bool(true);
+                }
 
                 // Helper: write one audit entry via writer or fallback
-                let do_audit = |w: &Result<ampersona_engine::state::writer::StateWriter, _>,
-                                entry: &serde_json::Value|
-                 -> Result<()> {
-                    if let Ok(ref w) = w {
-                        w.maybe_audit(persona.audit.as_ref(), "GateTransition", entry)?;
-                    } else {
-                        let audit_path = file.replace(".json", ".audit.jsonl");
-                        let _ =
-                            ampersona_engine::state::audit_log::append_audit(&audit_path, entry);
-                    }
-                    Ok(())
-                };
-
                 // Write drift entry (always, regardless of decision)
-                let drift_path = file.replace(".json", ".drift.jsonl");
                 let _ = ampersona_engine::state::drift::append_drift(
-                    &drift_path,
+                    &paths.drift,
                     serde_json::json!(record.metrics_snapshot),
                 );
 
@@ -1301,42 +4578,45 @@ fn cmd_gate_inner(opts: GateOpts) -> Result<CmdExit> {
                     });
                     state.updated_at = chrono::Utc::now();
 
-                    do_audit(&writer, &audit_entry)?;
-                    if let Ok(ref w) = writer {
-                        w.write_state(&state)?;
-                    } else {
-                        let json = serde_json::to_string_pretty(&state)?;
-                        ampersona_engine::state::atomic::atomic_write(
-                            &state_path,
-                            json.as_bytes(),
-                        )?;
-                    }
+                    writer.maybe_audit(persona.audit.as_ref(), "GateTransition", &audit_entry)?;
+                    writer.write_state(&state)?;
                     // State written — safe to delete migrated sidecar now
                     let _ = std::fs::remove_file(&sidecar_path);
+                    // A slow webhook must not hold up other writers.
+                    drop(writer);
+                    if let Some(pending) = &state.pending_transition {
+                        send_notice(&ApprovalNotice::new(
+                            &persona.name,
+                            pending,
+                            Urgency::Pending,
+                            pending.created_at,
+                        ));
+                    }
 
                     if !json_out {
-                        eprintln!(
-                            "  pending human approval: {} \u{2192} {} (use --approve {})",
+                        info!(
+                            gate = %record.gate_id,
+                            "pending human approval: {} \u{2192} {} (use --approve {})",
                             record.from_phase.as_deref().unwrap_or("none"),
                             record.to_phase,
                             record.gate_id
                         );
                     }
-                    println!("{}", serde_json::to_string_pretty(&record)?);
-                    return Ok(CmdExit::Code(2));
+                    println!("{}", to_json(&record)?);
+                    return Ok(CmdExit::Code(exit::PENDING));
                 }
 
                 // Handle quorum error
                 if record.decision == "error_quorum_not_supported" {
-                    do_audit(&writer, &audit_entry)?;
+                    writer.maybe_audit(persona.audit.as_ref(), "GateTransition", &audit_entry)?;
                     if !json_out {
-                        eprintln!(
-                            "  error: quorum approval not yet supported (gate {})",
+                        error!(
+                            "quorum approval not yet supported (gate {})",
                             record.gate_id
                         );
                     }
-                    println!("{}", serde_json::to_string_pretty(&record)?);
-                    return Ok(CmdExit::Code(1));
+                    println!("{}", to_json(&record)?);
+                    return Ok(CmdExit::Code(exit::FAILURE));
                 }
 
                 if record.enforcement == ampersona_core::types::GateEnforcement::Enforce
@@ -1359,18 +4639,27 @@ fn cmd_gate_inner(opts: GateOpts) -> Result<CmdExit> {
 
                     // Apply authority overlay from on_pass (ADR-010: stored in state, not sidecar)
                     let previous_overlay = state.active_overlay.clone();
+                    // A declared phase supplies the default when the gate has none.
                     let fired_gate = gates.iter().find(|g| g.id == record.gate_id);
-                    if let Some(gate) = fired_gate {
-                        if let Some(effect) = &gate.on_pass {
-                            state.active_overlay = effect.authority_overlay.clone();
-                        } else {
-                            state.active_overlay = None;
+                    state.active_overlay = fired_gate
+                        .and_then(|g| g.on_pass.as_ref())
+                        .and_then(|effect| effect.authority_overlay.clone())
+                        .or_else(|| {
+                            persona
+                                .phase(&record.to_phase)
+                                .and_then(|p| p.authority_overlay.clone())
+                        });
+
+                    if let Some(note) = persona
+                        .phase(&record.to_phase)
+                        .and_then(|p| p.entry_note.as_deref())
+                    {
+                        audit_entry["entry_note"] = note.into();
+                        if !json_out {
+                            info!("entering '{}': {note}", record.to_phase);
                         }
-                    } else {
-                        state.active_overlay = None;
                     }
-
-                    do_audit(&writer, &audit_entry)?;
+                    writer.maybe_audit(persona.audit.as_ref(), "GateTransition", &audit_entry)?;
 
                     // Emit AuthorityOverlayChange audit event if overlay changed
                     let overlay_changed = match (&previous_overlay, &state.active_overlay) {
@@ -1387,61 +4676,59 @@ fn cmd_gate_inner(opts: GateOpts) -> Result<CmdExit> {
                             "previous_overlay": previous_overlay,
                             "new_overlay": state.active_overlay,
                         });
-                        if let Ok(ref w) = writer {
-                            w.maybe_audit(
-                                persona.audit.as_ref(),
-                                "AuthorityOverlayChange",
-                                &overlay_audit,
-                            )?;
-                        } else {
-                            let audit_path = file.replace(".json", ".audit.jsonl");
-                            let _ = ampersona_engine::state::audit_log::append_audit(
-                                &audit_path,
-                                &overlay_audit,
-                            );
-                        }
+                        writer.maybe_audit(
+                            persona.audit.as_ref(),
+                            "AuthorityOverlayChange",
+                            &overlay_audit,
+                        )?;
                         if !json_out {
                             if state.active_overlay.is_some() {
-                                eprintln!("  authority overlay applied from gate on_pass");
+                                info!("authority overlay applied from gate on_pass");
                             } else {
-                                eprintln!("  authority overlay cleared");
+                                info!("authority overlay cleared");
                             }
                         }
                     }
-                    if let Ok(ref w) = writer {
-                        w.write_state(&state)?;
-                    } else {
-                        let json = serde_json::to_string_pretty(&state)?;
-                        ampersona_engine::state::atomic::atomic_write(
-                            &state_path,
-                            json.as_bytes(),
-                        )?;
-                    }
+                    writer.write_state(&state)?;
                     // State written — safe to delete migrated sidecar now
                     let _ = std::fs::remove_file(&sidecar_path);
                     if !json_out {
-                        eprintln!(
-                            "  transition: {} \u{2192} {}",
+                        info!(
+                            gate = %record.gate_id,
+                            "transition: {} \u{2192} {}",
                             record.from_phase.as_deref().unwrap_or("none"),
                             record.to_phase
                         );
                     }
+                    // The transition is committed; the hook's outcome is only audited.
+                    // It runs unlocked so a slow hook can't hold up other writers.
+                    let pass_hook = fired_gate
+                        .and_then(|g| g.on_pass.as_ref()?.exec.as_ref())
+                        .filter(|_| !no_hooks);
+                    drop(writer);
+                    if let Some(argv) = pass_hook {
+                        let input = serde_json::to_value(&record)?;
+                        let result =
+                            run_gate_hook(&record.gate_id, "on_pass", argv, &input, hook_timeout);
+                        audit_hook_result(&*store, lock_timeout, persona.audit.as_ref(), result)?;
+                    }
                 } else if record.decision == "observed" {
-                    do_audit(&writer, &audit_entry)?;
+                    writer.maybe_audit(persona.audit.as_ref(), "GateTransition", &audit_entry)?;
                     if !json_out {
-                        eprintln!(
-                            "  observed (not applied): {} \u{2192} {}",
+                        info!(
+                            gate = %record.gate_id,
+                            "observed (not applied): {} \u{2192} {}",
                             record.from_phase.as_deref().unwrap_or("none"),
                             record.to_phase
                         );
                     }
                 }
-                println!("{}", serde_json::to_string_pretty(&record)?);
+                println!("{}", to_json(&record)?);
                 return Ok(CmdExit::Ok);
             } else {
                 if !json_out {
-                    eprintln!(
-                        "  gate '{gate_id}' did not fire (another gate matched: {})",
+                    info!(
+                        "gate '{gate_id}' did not fire (another gate matched: {})",
                         record.gate_id
                     );
                 }
@@ -1449,54 +4736,194 @@ fn cmd_gate_inner(opts: GateOpts) -> Result<CmdExit> {
         }
 
         // No gate fired (or requested gate didn't match).
+        let requested = gates.iter().find(|g| g.id == gate_id);
+        let fail_hook = requested
+            .and_then(|g| g.on_fail.as_ref()?.exec.as_ref())
+            .filter(|_| !no_hooks);
+        drop(writer);
+        if let (Some(gate), Some(argv)) = (requested, fail_hook) {
+            let diagnostic = diagnose_gate(gate, metrics.as_ref());
+            let result = run_gate_hook(&gate.id, "on_fail", argv, &diagnostic, hook_timeout);
+            audit_hook_result(&*store, lock_timeout, persona.audit.as_ref(), result)?;
+        }
         // If a specific gate was requested and --json, produce diagnostic.
         if json_out && gate_id != "*" {
             if let Some(gate) = gates.iter().find(|g| g.id == gate_id) {
-                let diagnostic = diagnose_gate(gate, &metrics);
-                println!("{}", serde_json::to_string_pretty(&diagnostic)?);
+                let diagnostic = diagnose_gate(gate, metrics.as_ref());
+                println!("{}", to_json(&diagnostic)?);
             } else {
                 let diagnostic = serde_json::json!({
                     "gate_id": gate_id,
                     "decision": "not_found",
                     "reason": format!("gate '{gate_id}' not defined"),
                 });
-                println!("{}", serde_json::to_string_pretty(&diagnostic)?);
+                println!("{}", to_json(&diagnostic)?);
             }
         } else if !json_out {
-            eprintln!("  no gate fired");
+            info!("no gate fired");
         }
-        return Ok(CmdExit::Code(1));
+        return Ok(CmdExit::Code(exit::FAILURE));
     }
 
     bail!("specify --evaluate or --override");
 }
 
+/// `amp gate - --evaluate` without --state: decide against `state` and print
+/// the record (or the diagnostic) like a normal run, writing no state, audit,
+/// drift or hooks.
+fn evaluate_in_memory(
+    gates: &[ampersona_core::spec::gates::Gate],
+    state: &ampersona_core::state::PhaseState,
+    metrics: &dyn ampersona_core::traits::MetricsProvider,
+    gate_id: &str,
+    json_out: bool,
+) -> Result<CmdExit> {
+    let evaluator = ampersona_engine::gates::evaluator::DefaultGateEvaluator;
+    let record = evaluator
+        .evaluate(gates, state, metrics)
+        .filter(|r| gate_id == "*" || r.gate_id == gate_id);
+    if let Some(record) = record {
+        if !json_out {
+            info!(
+                gate = %record.gate_id,
+                "{} (in memory, not applied): {} \u{2192} {}",
+                record.decision,
+                record.from_phase.as_deref().unwrap_or("none"),
+                record.to_phase
+            );
+        }
+        println!("{}", to_json(&record)?);
+        return Ok(CmdExit::Ok);
+    }
+    if json_out && gate_id != "*" {
+        let diagnostic = match gates.iter().find(|g| g.id == gate_id) {
+            Some(gate) => diagnose_gate(gate, metrics),
+            None => serde_json::json!({
+                "gate_id": gate_id,
+                "decision": "not_found",
+                "reason": format!("gate '{gate_id}' not defined"),
+            }),
+        };
+        println!("{}", to_json(&diagnostic)?);
+    } else if !json_out {
+        info!("no gate fired");
+    }
+    Ok(CmdExit::Code(exit::FAILURE))
+}
+
+/// Run a gate `exec` hook, killing it after `timeout`, and return its
+/// `HookResult` audit entry.
+///
+/// Call it without the state lock held. Without the `exec-hooks` feature the
+/// hook is skipped with a warning and there is nothing to audit.
+fn run_gate_hook(
+    gate_id: &str,
+    hook: &str,
+    argv: &[String],
+    input: &serde_json::Value,
+    timeout: Duration,
+) -> Option<serde_json::Value> {
+    #[cfg(feature = "exec-hooks")]
+    {
+        let outcome = ampersona_engine::gates::hooks::run_hook(argv, input, timeout);
+        if !outcome.succeeded() {
+            warn!(
+                gate = %gate_id,
+                "{hook}.exec failed ({}); decision stands",
+                outcome
+                    .error
+                    .clone()
+                    .or_else(|| outcome.exit_code.map(|c| format!("exit {c}")))
+                    .unwrap_or_else(|| "killed by signal".into())
+            );
+        }
+        Some(serde_json::json!({
+            "event_type": "HookResult",
+            "gate_id": gate_id,
+            "hook": hook,
+            "command": outcome.command,
+            "exit_code": outcome.exit_code,
+            "error": outcome.error,
+        }))
+    }
+    #[cfg(not(feature = "exec-hooks"))]
+    {
+        let _ = (argv, input, timeout);
+        warn!(gate = %gate_id, "{hook}.exec skipped: amp was built without the exec-hooks feature");
+        None
+    }
+}
+
+/// Append a hook's `HookResult` under a short-lived lock of its own.
+fn audit_hook_result(
+    store: &dyn StateStore,
+    lock_timeout: Duration,
+    audit: Option<&ampersona_core::spec::audit::AuditConfig>,
+    entry: Option<serde_json::Value>,
+) -> Result<()> {
+    let Some(entry) = entry else {
+        return Ok(());
+    };
+    let writer = ampersona_engine::state::writer::StateWriter::with_store(store, lock_timeout)?;
+    writer.maybe_audit(audit, "HookResult", &entry)?;
+    Ok(())
+}
+
+/// `amp metrics template`: scaffold a `--metrics` file from the gates' criteria.
+fn cmd_metrics_template(file: &str, json_out: bool) -> Result<()> {
+    let data = read_persona(file)?;
+    let gates: Vec<ampersona_core::spec::gates::Gate> = data
+        .get("gates")
+        .map(|g| serde_json::from_value(g.clone()))
+        .transpose()
+        .map_err(|e| anyhow::anyhow!("{file}: invalid gates: {e}"))?
+        .unwrap_or_default();
+
+    let template = ampersona_engine::gates::metrics::template(&gates);
+    let skeleton: serde_json::Map<String, serde_json::Value> = template
+        .iter()
+        .map(|m| (m.name.clone(), m.placeholder.clone()))
+        .collect();
+    let skeleton = to_json(&serde_json::Value::Object(skeleton))?;
+
+    if json_out {
+        println!("{skeleton}");
+        return Ok(());
+    }
+
+    if template.is_empty() {
+        println!("# {file} has no gate criteria; no metrics needed");
+        return Ok(());
+    }
+    println!("# Metrics for {file} — replace placeholders, drop these comment lines,");
+    println!("# then run: amp gate {file} --evaluate '*' --metrics <metrics.json>");
+    println!("#");
+    for m in &template {
+        println!(
+            "#   {:<24} {:<8} {}",
+            m.name,
+            m.metric_type,
+            m.used_by.join(", ")
+        );
+    }
+    println!("{skeleton}");
+    Ok(())
+}
+
 /// Produce diagnostic JSON for a gate whose criteria failed.
 fn diagnose_gate(
     gate: &ampersona_core::spec::gates::Gate,
     metrics: &dyn ampersona_core::traits::MetricsProvider,
 ) -> serde_json::Value {
-    let mut criteria_results = Vec::new();
-    for criterion in &gate.criteria {
-        let query = ampersona_core::traits::MetricQuery {
-            name: criterion.metric.clone(),
-            window: None,
-        };
-        let (actual, pass) = match metrics.get_metric(&query) {
-            Ok(sample) => {
-                let pass = compare_criterion(&criterion.op, &sample.value, &criterion.value);
-                (sample.value, pass)
-            }
-            Err(_) => (serde_json::Value::Null, false),
-        };
-        criteria_results.push(serde_json::json!({
-            "metric": criterion.metric,
-            "op": criterion.op,
-            "value": criterion.value,
-            "actual": actual,
-            "pass": pass,
-        }));
-    }
+    let (_, criteria_results, _) = ampersona_engine::gates::evaluator::DefaultGateEvaluator
+        .evaluate_criteria_at(
+            &gate.criteria,
+            metrics,
+            gate.direction,
+            gate.metrics_schema.as_ref(),
+            gate.max_age_seconds,
+            chrono::Utc::now(),
+        );
     serde_json::json!({
         "gate_id": gate.id,
         "decision": "no_match",
@@ -1505,183 +4932,659 @@ fn diagnose_gate(
     })
 }
 
-fn compare_criterion(
-    op: &ampersona_core::types::CriterionOp,
-    actual: &serde_json::Value,
-    expected: &serde_json::Value,
-) -> bool {
-    use ampersona_core::types::CriterionOp;
-    match op {
-        CriterionOp::Eq => actual == expected,
-        CriterionOp::Neq => actual != expected,
-        CriterionOp::Gt => cmp_num(actual, expected).is_some_and(|c| c > 0),
-        CriterionOp::Gte => cmp_num(actual, expected).is_some_and(|c| c >= 0),
-        CriterionOp::Lt => cmp_num(actual, expected).is_some_and(|c| c < 0),
-        CriterionOp::Lte => cmp_num(actual, expected).is_some_and(|c| c <= 0),
-    }
-}
-
-fn cmp_num(a: &serde_json::Value, b: &serde_json::Value) -> Option<i8> {
-    let a_f = a.as_f64()?;
-    let b_f = b.as_f64()?;
-    if a_f > b_f {
-        Some(1)
-    } else if a_f < b_f {
-        Some(-1)
-    } else {
-        Some(0)
-    }
-}
-
 fn cmd_sign(file: &str, key_path: &str, key_id: &str) -> Result<()> {
     let content = std::fs::read_to_string(file)?;
     let mut data: serde_json::Value = serde_json::from_str(&content)?;
 
-    let key_bytes =
-        std::fs::read(key_path).map_err(|e| anyhow::anyhow!("cannot read key {key_path}: {e}"))?;
-    let key_array: [u8; 32] = key_bytes
-        .get(..32)
-        .ok_or_else(|| anyhow::anyhow!("key must be at least 32 bytes"))?
-        .try_into()
-        .unwrap();
-    let signing_key = ed25519_dalek::SigningKey::from_bytes(&key_array);
-
+    let signing_key = load_signing_key(key_path)?;
     ampersona_sign::sign::sign_persona(&mut data, &signing_key, key_id, "cli")?;
 
     let json = serde_json::to_string_pretty(&data)?;
     std::fs::write(file, &json)?;
-    eprintln!("  signed {file} (key_id: {key_id})");
+    info!("signed {file} (key_id: {key_id})");
     Ok(())
 }
 
-fn cmd_verify(file: &str, pubkey_path: &str) -> Result<()> {
+fn cmd_verify(
+    file: &str,
+    pubkey_path: Option<&str>,
+    keyring: Option<&str>,
+    expected: Option<&str>,
+) -> Result<()> {
     let content = std::fs::read_to_string(file)?;
     let data: serde_json::Value = serde_json::from_str(&content)?;
 
-    let key_bytes = std::fs::read(pubkey_path)
-        .map_err(|e| anyhow::anyhow!("cannot read pubkey {pubkey_path}: {e}"))?;
-    let key_array: [u8; 32] = key_bytes
-        .get(..32)
-        .ok_or_else(|| anyhow::anyhow!("pubkey must be at least 32 bytes"))?
-        .try_into()
-        .unwrap();
-    let verifying_key = ed25519_dalek::VerifyingKey::from_bytes(&key_array)
-        .map_err(|e| anyhow::anyhow!("invalid pubkey: {e}"))?;
+    if let Some(expected) = expected {
+        let actual = ampersona_sign::sign::fingerprint(&data)?;
+        if actual != expected {
+            bail!("fingerprint mismatch: expected {expected}, got {actual}");
+        }
+        info!("fingerprint matches");
+    }
+    let valid = match (pubkey_path, keyring) {
+        (Some(pubkey_path), _) => {
+            let verifying_key = load_verifying_key(pubkey_path)?;
+            Some(ampersona_sign::verify::verify_persona(
+                &data,
+                &verifying_key,
+            )?)
+        }
+        (None, Some(keyring)) => Some(ampersona_sign::verify::verify_persona_with_keyring(
+            &data,
+            std::path::Path::new(keyring),
+        )?),
+        (None, None) => None,
+    };
+    if let Some(valid) = valid {
+        if valid {
+            info!("signature valid");
+        } else {
+            bail!("signature verification failed");
+        }
+    }
+    Ok(())
+}
+
+/// One-command integrity sweep: persona signature, audit chain, checkpoint and
+/// state_rev, reported together. Missing sidecars are reported, not failed.
+fn cmd_verify_sidecars(file: &str, pubkey_path: &str, expected: Option<&str>) -> Result<()> {
+    use ampersona_engine::state::audit_log;
+
+    let content = std::fs::read_to_string(file)?;
+    let data: serde_json::Value = serde_json::from_str(&content)?;
+    let verifying_key = load_verifying_key(pubkey_path)?;
+    let sidecars = default_sidecars(file);
+    let (state_path, cp_path) = (&sidecars.state, &sidecars.checkpoint);
+    let store = state_store(&sidecars)?;
+    let audit_path = store.audit_location();
+    let audit = store.audit_lines()?;
+    let audit_lines = || audit.iter().flatten().map(String::as_str);
+
+    let (status, issue) = signature_status(&data, &verifying_key, true);
+    let mut signature = serde_json::json!({"valid": issue.is_none(), "status": status});
+    if let Some(issue) = issue {
+        signature["error"] = serde_json::json!(issue.message);
+    }
+
+    let audit_chain = if audit.is_none() {
+        serde_json::json!({"valid": true, "present": false, "path": audit_path})
+    } else {
+        match audit_log::verify_lines(&audit_path, audit_lines(), 0) {
+            Ok(entries) => serde_json::json!({
+                "valid": true, "present": true, "path": audit_path, "entries": entries,
+            }),
+            Err(e) => serde_json::json!({
+                "valid": false, "present": true, "path": audit_path, "error": format!("{e:#}"),
+            }),
+        }
+    };
 
-    let valid = ampersona_sign::verify::verify_persona(&data, &verifying_key)?;
-    if valid {
-        eprintln!("  signature valid");
+    let checkpoint = if !std::path::Path::new(cp_path).exists() {
+        serde_json::json!({"valid": true, "present": false, "path": cp_path})
+    } else if audit.is_none() {
+        serde_json::json!({
+            "valid": false, "present": true, "path": cp_path,
+            "error": format!("no audit log at {audit_path}"),
+        })
     } else {
-        bail!("signature verification failed");
+        let mut check = match read_checkpoint(cp_path).and_then(|cp| {
+            Ok(audit_log::verify_checkpoint_lines(
+                &audit_path,
+                audit_lines(),
+                &cp,
+            )?)
+        }) {
+            Ok(valid) => serde_json::json!({"valid": valid, "present": true, "path": cp_path}),
+            Err(e) => serde_json::json!({
+                "valid": false, "present": true, "path": cp_path, "error": format!("{e:#}"),
+            }),
+        };
+        // Checkpoints may be co-signed by other parties; report, don't fail.
+        if let Ok(report) = verify_checkpoint_signatures(cp_path, &[pubkey_path.to_string()]) {
+            check["signed_by_pubkey"] = serde_json::json!(!report.valid.is_empty());
+        }
+        check
+    };
+
+    let state_rev = match store.load() {
+        Ok(Some(state)) if audit.is_some() => {
+            match audit_log::check_state_rev_lines(state.state_rev, &audit_path, audit_lines()) {
+                Ok(check) => {
+                    let mut out = serde_json::to_value(&check)?;
+                    out["valid"] = serde_json::json!(check.consistent);
+                    out["present"] = serde_json::json!(true);
+                    out
+                }
+                Err(e) => serde_json::json!({
+                    "valid": false, "present": true, "path": state_path, "error": format!("{e:#}"),
+                }),
+            }
+        }
+        Ok(_) => serde_json::json!({"valid": true, "present": false, "path": state_path}),
+        Err(e) => serde_json::json!({
+            "valid": false, "present": true, "path": state_path,
+            "error": format!("{:#}", anyhow::Error::from(e)),
+        }),
+    };
+
+    let mut checks = serde_json::json!({
+        "signature": signature,
+        "audit_chain": audit_chain,
+        "checkpoint": checkpoint,
+        "state_rev": state_rev,
+    });
+    if let Some(expected) = expected {
+        let actual = ampersona_sign::sign::fingerprint(&data)?;
+        checks["fingerprint"] = serde_json::json!({
+            "valid": actual == expected, "expected": expected, "actual": actual,
+        });
+    }
+    let valid = checks
+        .as_object()
+        .unwrap()
+        .values()
+        .all(|c| c["valid"] == true);
+    let report = serde_json::json!({"file": file, "valid": valid, "checks": checks});
+    println!("{}", to_json(&report)?);
+    if !valid {
+        bail!("integrity sweep failed for {file}");
     }
     Ok(())
 }
 
-struct AuditOpts {
-    file: String,
-    verify: bool,
-    from: Option<u64>,
-    checkpoint_create: bool,
-    checkpoint_verify: bool,
-    checkpoint_path: Option<String>,
-    sign_key: Option<String>,
-    sign_key_id: String,
-    verify_key: Option<String>,
+/// Hash the persona and its sidecars into a manifest and sign it.
+fn cmd_manifest_sign(
+    file: &str,
+    manifest_path: &str,
+    key_path: &str,
+    key_id: &str,
     json_out: bool,
+) -> Result<()> {
+    let mut manifest = ampersona_engine::state::manifest::build_manifest(file)?;
+    let signing_key = load_signing_key(key_path)?;
+    ampersona_sign::sign::sign_persona(&mut manifest, &signing_key, key_id, "cli")?;
+    let json = serde_json::to_string_pretty(&manifest)?;
+    ampersona_engine::state::atomic::atomic_write(manifest_path, json.as_bytes())?;
+    if json_out {
+        println!("{}", to_json(&manifest)?);
+    } else {
+        let covered = manifest["files"]
+            .as_array()
+            .map_or(0, |f| f.iter().filter(|e| !e["sha256"].is_null()).count());
+        info!("wrote {manifest_path} ({covered} files, key_id: {key_id})");
+    }
+    Ok(())
 }
 
-fn cmd_audit(opts: AuditOpts) -> CmdExit {
-    let AuditOpts {
-        file,
-        verify,
+/// Check a manifest's signature and report every covered file whose hash drifted.
+fn cmd_manifest_verify(
+    file: &str,
+    manifest_path: &str,
+    pubkey_path: &str,
+    json_out: bool,
+) -> Result<()> {
+    let content = std::fs::read_to_string(manifest_path)
+        .map_err(|e| anyhow::anyhow!("cannot read manifest {manifest_path}: {e}"))?;
+    let manifest: serde_json::Value = serde_json::from_str(&content)?;
+    let verifying_key = load_verifying_key(pubkey_path)?;
+
+    let signature_valid = manifest.get("signature").is_some()
+        && ampersona_sign::verify::verify_persona(&manifest, &verifying_key).unwrap_or(false);
+    let drift = ampersona_engine::state::manifest::manifest_drift(&manifest, file)?;
+    let valid = signature_valid && drift.is_empty();
+
+    if json_out {
+        let report = serde_json::json!({
+            "manifest": manifest_path,
+            "valid": valid,
+            "signature_valid": signature_valid,
+            "drift": drift,
+        });
+        println!("{}", to_json(&report)?);
+    } else {
+        if signature_valid {
+            info!("manifest signature valid");
+        } else {
+            error!("manifest signature INVALID");
+        }
+        for d in &drift {
+            info!(
+                "drift {} ({}): {} -> {}",
+                d.file,
+                d.role,
+                d.expected.as_deref().unwrap_or("absent"),
+                d.actual.as_deref().unwrap_or("absent")
+            );
+        }
+        if valid {
+            info!("all covered files match");
+        }
+    }
+    if !valid {
+        bail!("manifest verification failed for {file}");
+    }
+    Ok(())
+}
+
+fn load_signing_key(key_path: &str) -> Result<ed25519_dalek::SigningKey> {
+    let key_bytes =
+        std::fs::read(key_path).map_err(|e| anyhow::anyhow!("cannot read key {key_path}: {e}"))?;
+    let key_array: [u8; 32] = key_bytes
+        .get(..32)
+        .ok_or_else(|| anyhow::anyhow!("key must be at least 32 bytes"))?
+        .try_into()
+        .unwrap();
+    Ok(ed25519_dalek::SigningKey::from_bytes(&key_array))
+}
+
+fn load_verifying_key(pubkey_path: &str) -> Result<ed25519_dalek::VerifyingKey> {
+    let key_bytes = std::fs::read(pubkey_path)
+        .map_err(|e| anyhow::anyhow!("cannot read pubkey {pubkey_path}: {e}"))?;
+    let key_array: [u8; 32] = key_bytes
+        .get(..32)
+        .ok_or_else(|| anyhow::anyhow!("pubkey must be at least 32 bytes"))?
+        .try_into()
+        .unwrap();
+    ed25519_dalek::VerifyingKey::from_bytes(&key_array)
+        .map_err(|e| anyhow::anyhow!("invalid pubkey: {e}"))
+}
+
+/// Signature status for fleet checks: `valid`, `invalid` or `unsigned`, plus the
+/// check issue to record when the status counts as a failure.
+fn signature_status(
+    data: &serde_json::Value,
+    key: &ed25519_dalek::VerifyingKey,
+    require_sig: bool,
+) -> (&'static str, Option<ampersona_core::errors::CheckIssue>) {
+    let issue = |code: &str, message: String| ampersona_core::errors::CheckIssue {
+        code: code.to_string(),
+        check: "signature_verify".to_string(),
+        message,
+        path: Some("$.signature".to_string()),
+    };
+    if data.get("signature").is_none() {
+        let failure = require_sig.then(|| issue("E031", "persona is unsigned".into()));
+        return ("unsigned", failure);
+    }
+    match ampersona_sign::verify::verify_persona(data, key) {
+        Ok(true) => match unsigned_content(data) {
+            None => ("valid", None),
+            Some(gap) => ("invalid", Some(issue("E030", gap))),
+        },
+        Ok(false) => (
+            "invalid",
+            Some(issue("E030", "signature verification failed".into())),
+        ),
+        Err(e) => ("invalid", Some(issue("E030", format!("{e:#}")))),
+    }
+}
+
+/// What a signed persona loads that its signature does not cover: a
+/// top-level field outside `signed_fields`, or an `$extends` / `$ref` target,
+/// whose content lives in another (unsigned) file.
+fn unsigned_content(data: &serde_json::Value) -> Option<String> {
+    use ampersona_core::deref::REF_SECTIONS;
+
+    let obj = data.as_object()?;
+    let signed: Vec<&str> = data
+        .pointer("/signature/signed_fields")
+        .and_then(serde_json::Value::as_array)
+        .map(|fields| {
+            fields
+                .iter()
+                .filter_map(serde_json::Value::as_str)
+                .collect()
+        })
+        .unwrap_or_default();
+    if let Some(key) = obj
+        .keys()
+        .find(|k| !matches!(k.as_str(), "signature" | "$schema") && !signed.contains(&k.as_str()))
+    {
+        return Some(format!("field '{key}' is not covered by the signature"));
+    }
+    if let Some(base) = obj.get("$extends") {
+        return Some(format!(
+            "$extends base {base} is not covered by the signature; sign the output of `amp resolve`"
+        ));
+    }
+    let is_ref = |v: &serde_json::Value| v.get("$ref").is_some();
+    let section = REF_SECTIONS.iter().find(|key| match obj.get(**key) {
+        Some(serde_json::Value::Array(gates)) if **key == "gates" => gates.iter().any(is_ref),
+        Some(v) => is_ref(v),
+        None => false,
+    })?;
+    Some(format!(
+        "$ref target in '{section}' is not covered by the signature; sign the output of `amp resolve`"
+    ))
+}
+
+/// Every audit entry, decrypted with the key in `key_path` (`None` without a key).
+fn read_decrypted_audit<'a>(
+    audit_path: &str,
+    lines: impl IntoIterator<Item = &'a str>,
+    key_path: Option<&str>,
+) -> Result<Option<Vec<serde_json::Value>>> {
+    use ampersona_engine::state::audit_crypto::{decrypt_lines, AuditKey};
+
+    let Some(key_path) = key_path else {
+        return Ok(None);
+    };
+    let key = AuditKey::from_file(key_path)?;
+    Ok(Some(decrypt_lines(audit_path, lines, &key)?))
+}
+
+struct AuditOpts {
+    file: String,
+    sidecars: ampersona_core::sidecar::Sidecars,
+    verify: bool,
+    from: Option<u64>,
+    audit_key: Option<String>,
+    stats: bool,
+    checkpoint_create: bool,
+    checkpoint_verify: bool,
+    checkpoint_cosign: bool,
+    sign_key: Option<String>,
+    sign_key_id: String,
+    verify_keys: Vec<String>,
+    threshold: usize,
+    timestamp_url: Option<String>,
+    timestamp_imprint: bool,
+    export: Option<String>,
+    import_bundle: bool,
+    dest: Option<String>,
+    reconcile: bool,
+    fix: bool,
+    lock_timeout: Duration,
+    json_out: bool,
+}
+
+fn cmd_audit(opts: AuditOpts) -> CmdExit {
+    let AuditOpts {
+        file,
+        sidecars,
+        verify,
         from,
+        audit_key,
+        stats,
         checkpoint_create,
         checkpoint_verify,
-        checkpoint_path,
+        checkpoint_cosign,
         sign_key,
         sign_key_id,
-        verify_key,
+        verify_keys,
+        threshold,
+        timestamp_url,
+        timestamp_imprint,
+        export,
+        import_bundle,
+        dest,
+        reconcile,
+        fix,
+        lock_timeout,
         json_out,
     } = opts;
-    let audit_path = file.replace(".json", ".audit.jsonl");
+
+    if import_bundle {
+        return cmd_audit_import(&file, dest.as_deref(), &verify_keys, json_out);
+    }
+
+    let store = match state_store(&sidecars) {
+        Ok(store) => store,
+        Err(e) => return CmdExit::Err(e),
+    };
+
+    if reconcile {
+        return match reconcile_state_rev(&*store, fix, lock_timeout) {
+            Ok((check, fixed)) => {
+                if json_out {
+                    let mut output = serde_json::to_value(&check).unwrap();
+                    output["fixed"] = serde_json::json!(fixed);
+                    println!("{}", to_json(&output).unwrap());
+                } else if check.consistent {
+                    info!(
+                        "state_rev {} consistent with {} audited state mutations",
+                        check.state_rev, check.state_mutations
+                    );
+                } else if fixed {
+                    info!(
+                        "state_rev reconciled: {} -> {} (Reconcile event audited)",
+                        check.state_rev, check.expected_state_rev
+                    );
+                } else {
+                    warn!(
+                        "state_rev {} exceeds audited state mutations ({}) + 1; expected {} (rerun with --fix to repair)",
+                        check.state_rev, check.state_mutations, check.expected_state_rev
+                    );
+                }
+                if check.consistent || fixed {
+                    CmdExit::Ok
+                } else {
+                    CmdExit::Code(exit::FAILURE)
+                }
+            }
+            Err(e) => CmdExit::Err(e),
+        };
+    }
+
+    if let Some(ref bundle_path) = export {
+        return match export_audit_bundle(
+            &file,
+            &sidecars,
+            bundle_path,
+            sign_key.as_deref(),
+            &sign_key_id,
+        ) {
+            Ok(bundle) => {
+                if json_out {
+                    let output = serde_json::json!({
+                        "bundle": bundle_path,
+                        "chain": bundle["chain"],
+                        "signed": bundle.get("signature").is_some(),
+                    });
+                    println!("{}", to_json(&output).unwrap());
+                } else {
+                    info!(
+                        "exported {file} to {bundle_path} ({} audit entries, chain {})",
+                        bundle["audit"].as_array().map_or(0, Vec::len),
+                        if bundle["chain"]["valid"] == true {
+                            "valid"
+                        } else {
+                            "INVALID"
+                        }
+                    );
+                }
+                CmdExit::Ok
+            }
+            Err(e) => CmdExit::Err(e),
+        };
+    }
+
+    let audit_path = &store.audit_location();
+    let audit = match store.audit_lines() {
+        Ok(audit) => audit,
+        Err(e) => return CmdExit::Err(e.into()),
+    };
+    let audit_lines = || audit.iter().flatten().map(String::as_str);
+
+    if stats {
+        if audit.is_none() {
+            return CmdExit::Err(anyhow::anyhow!("no audit log at {audit_path}"));
+        }
+        return match ampersona_engine::state::audit_log::audit_stats_lines(
+            audit_path,
+            audit_lines(),
+        ) {
+            Ok(stats) => {
+                if json_out {
+                    println!("{}", to_json(&stats).unwrap());
+                } else {
+                    print_audit_stats(audit_path, &stats);
+                }
+                CmdExit::Ok
+            }
+            Err(e) => CmdExit::Err(e.into()),
+        };
+    }
 
     // Handle checkpoint create
     if checkpoint_create {
-        let cp_path = checkpoint_path.unwrap_or_else(|| file.replace(".json", ".checkpoint.json"));
-        if !std::path::Path::new(&audit_path).exists() {
+        let cp_path = &sidecars.checkpoint;
+        if audit.is_none() {
             return CmdExit::Err(anyhow::anyhow!("no audit log at {audit_path}"));
         }
-        match ampersona_engine::state::audit_log::create_checkpoint(&audit_path, &cp_path) {
+        match ampersona_engine::state::audit_log::build_checkpoint_lines(audit_path, audit_lines())
+        {
             Ok(mut checkpoint) => {
-                // Optionally sign the checkpoint
+                // Timestamp first so an optional signature also covers the token
+                if let Some(ref url) = timestamp_url {
+                    if let Err(e) = timestamp_checkpoint(&mut checkpoint, url) {
+                        return CmdExit::Err(e);
+                    }
+                }
                 if let Some(ref key_path) = sign_key {
-                    match sign_checkpoint(&mut checkpoint, key_path, &sign_key_id) {
-                        Ok(()) => {
-                            // Re-write with signature
-                            let json = serde_json::to_string_pretty(&checkpoint).unwrap();
-                            if let Err(e) = std::fs::write(&cp_path, json) {
-                                return CmdExit::Err(e.into());
-                            }
-                        }
-                        Err(e) => return CmdExit::Err(e),
+                    if let Err(e) = sign_checkpoint(&mut checkpoint, key_path, &sign_key_id) {
+                        return CmdExit::Err(e);
                     }
                 }
+                let json = serde_json::to_string_pretty(&checkpoint).unwrap();
+                if let Err(e) =
+                    ampersona_engine::state::atomic::atomic_write(cp_path, json.as_bytes())
+                {
+                    return CmdExit::Err(e.into());
+                }
                 if json_out {
-                    println!("{}", serde_json::to_string_pretty(&checkpoint).unwrap());
+                    println!("{}", to_json(&checkpoint).unwrap());
                 } else {
-                    eprintln!("  checkpoint created at {cp_path}");
+                    info!("checkpoint created at {cp_path}");
                 }
                 return CmdExit::Ok;
             }
-            Err(e) => return CmdExit::Err(e),
+            Err(e) => return CmdExit::Err(e.into()),
         }
     }
 
+    // Handle checkpoint co-sign
+    if checkpoint_cosign {
+        let cp_path = &sidecars.checkpoint;
+        let key_path = sign_key.unwrap_or_default();
+        return match cosign_checkpoint(audit_path, audit_lines(), cp_path, &key_path, &sign_key_id)
+        {
+            Ok(checkpoint) => {
+                if json_out {
+                    println!("{}", to_json(&checkpoint).unwrap());
+                } else {
+                    let count = checkpoint["signatures"].as_array().map_or(0, Vec::len);
+                    info!("checkpoint co-signed by {sign_key_id} ({count} signatures)");
+                }
+                CmdExit::Ok
+            }
+            Err(e) => CmdExit::Err(e),
+        };
+    }
+
     // Handle checkpoint verify
     if checkpoint_verify {
-        let cp_path = checkpoint_path.unwrap_or_else(|| file.replace(".json", ".checkpoint.json"));
-        if !std::path::Path::new(&audit_path).exists() {
+        let cp_path = &sidecars.checkpoint;
+        if audit.is_none() {
             return CmdExit::Err(anyhow::anyhow!("no audit log at {audit_path}"));
         }
         if !std::path::Path::new(&cp_path).exists() {
             return CmdExit::Err(anyhow::anyhow!("no checkpoint at {cp_path}"));
         }
 
-        // Verify signature if public key provided
-        if let Some(ref pubkey_path) = verify_key {
-            match verify_checkpoint_signature(&cp_path, pubkey_path) {
-                Ok(true) => {
+        // Verify signatures if public keys provided
+        let mut signers = None;
+        if !verify_keys.is_empty() {
+            if threshold == 0 || threshold > verify_keys.len() {
+                return CmdExit::Err(anyhow::anyhow!(
+                    "--threshold must be between 1 and the number of verify keys ({})",
+                    verify_keys.len()
+                ));
+            }
+            match verify_checkpoint_signatures(cp_path, &verify_keys) {
+                Ok(report) if report.valid.len() >= threshold => {
                     if !json_out {
-                        eprintln!("  checkpoint signature valid");
+                        info!(
+                            "checkpoint signatures valid ({}/{threshold}: {})",
+                            report.valid.len(),
+                            report.valid.join(", ")
+                        );
                     }
+                    signers = Some(report.valid);
                 }
-                Ok(false) => {
+                Ok(report) => {
                     if json_out {
                         let output = serde_json::json!({
                             "valid": false,
                             "error": "checkpoint signature verification failed",
+                            "valid_signers": report.valid,
+                            "invalid_signers": report.invalid,
+                            "threshold": threshold,
+                        });
+                        println!("{}", to_json(&output).unwrap());
+                    } else {
+                        error!(
+                            "checkpoint signature INVALID ({} of {threshold} required valid signatures)",
+                            report.valid.len()
+                        );
+                    }
+                    return CmdExit::Code(exit::FAILURE);
+                }
+                Err(e) => return CmdExit::Err(e),
+            }
+        }
+
+        let mut gen_time = None;
+        if timestamp_imprint {
+            match check_timestamp_imprint(cp_path) {
+                Ok(Ok(time)) => {
+                    if !json_out {
+                        info!(
+                            "checkpoint timestamp imprint matches (genTime {time}; TSA signature not checked)"
+                        );
+                    }
+                    gen_time = Some(time);
+                }
+                Ok(Err(reason)) => {
+                    if json_out {
+                        let output = serde_json::json!({
+                            "valid": false,
+                            "error": reason,
                         });
-                        println!("{}", serde_json::to_string_pretty(&output).unwrap());
+                        println!("{}", to_json(&output).unwrap());
                     } else {
-                        eprintln!("  checkpoint signature INVALID");
+                        error!("checkpoint timestamp MISMATCH: {reason}");
                     }
-                    return CmdExit::Code(1);
+                    return CmdExit::Code(exit::FAILURE);
                 }
                 Err(e) => return CmdExit::Err(e),
             }
         }
 
-        match ampersona_engine::state::audit_log::verify_checkpoint(&audit_path, &cp_path) {
+        let verified = read_checkpoint(cp_path).and_then(|checkpoint| {
+            Ok(ampersona_engine::state::audit_log::verify_checkpoint_lines(
+                audit_path,
+                audit_lines(),
+                &checkpoint,
+            )?)
+        });
+        match verified {
             Ok(true) => {
                 if json_out {
-                    let output = serde_json::json!({
+                    let mut output = serde_json::json!({
                         "valid": true,
                         "checkpoint": cp_path,
                         "audit_path": audit_path,
                     });
-                    println!("{}", serde_json::to_string_pretty(&output).unwrap());
+                    if let Some(signers) = signers {
+                        output["signers"] = serde_json::json!(signers);
+                    }
+                    if let Some(time) = gen_time {
+                        output["timestamp_gen_time"] = serde_json::json!(time);
+                    }
+                    println!("{}", to_json(&output).unwrap());
                 } else {
-                    eprintln!("  checkpoint valid");
+                    info!("checkpoint valid");
                 }
                 CmdExit::Ok
             }
@@ -1692,11 +5595,11 @@ fn cmd_audit(opts: AuditOpts) -> CmdExit {
                         "checkpoint": cp_path,
                         "audit_path": audit_path,
                     });
-                    println!("{}", serde_json::to_string_pretty(&output).unwrap());
+                    println!("{}", to_json(&output).unwrap());
                 } else {
-                    eprintln!("  checkpoint INVALID");
+                    error!("checkpoint INVALID");
                 }
-                CmdExit::Code(1)
+                CmdExit::Code(exit::FAILURE)
             }
             Err(e) => CmdExit::Err(e),
         }
@@ -1704,25 +5607,47 @@ fn cmd_audit(opts: AuditOpts) -> CmdExit {
         // Standard --verify
         if !verify {
             return CmdExit::Err(anyhow::anyhow!(
-                "specify --verify, --checkpoint-create, or --checkpoint-verify"
+                "specify --verify, --checkpoint-create, --checkpoint-verify, --checkpoint-cosign, --export, or --import-bundle"
             ));
         }
-        if !std::path::Path::new(&audit_path).exists() {
+        if audit.is_none() {
             if json_out {
                 let output = serde_json::json!({
                     "valid": true,
                     "entries": 0,
                     "audit_path": audit_path,
                 });
-                println!("{}", serde_json::to_string_pretty(&output).unwrap());
+                println!("{}", to_json(&output).unwrap());
             } else {
-                eprintln!("  no audit log found at {audit_path}");
+                info!("no audit log found at {audit_path}");
             }
             return CmdExit::Ok;
         }
         let from_entry = from.unwrap_or(0);
-        match ampersona_engine::state::audit_log::verify_chain_from(&audit_path, from_entry) {
+        match ampersona_engine::state::audit_log::verify_lines(
+            audit_path,
+            audit_lines(),
+            from_entry,
+        ) {
             Ok(count) => {
+                let decrypted =
+                    match read_decrypted_audit(audit_path, audit_lines(), audit_key.as_deref()) {
+                        Ok(entries) => entries,
+                        Err(e) => {
+                            let msg = format!("{e:#}");
+                            if json_out {
+                                let output = serde_json::json!({
+                                    "valid": false,
+                                    "error": msg,
+                                    "audit_path": audit_path,
+                                });
+                                println!("{}", to_json(&output).unwrap());
+                            } else {
+                                error!("audit chain valid, but entries do not decrypt: {msg}");
+                            }
+                            return CmdExit::Code(exit::FAILURE);
+                        }
+                    };
                 if json_out {
                     let mut output = serde_json::json!({
                         "valid": true,
@@ -1732,39 +5657,43 @@ fn cmd_audit(opts: AuditOpts) -> CmdExit {
                     if from_entry > 0 {
                         output["from_entry"] = serde_json::json!(from_entry);
                     }
+                    if let Some(entries) = &decrypted {
+                        output["log"] = serde_json::json!(entries);
+                    }
 
                     // state_rev consistency check
-                    let state_path = file.replace(".json", ".state.json");
-                    if let Ok(state) = ampersona_engine::state::phase::load_state(&state_path) {
-                        if std::path::Path::new(&audit_path).exists() {
-                            let mutations =
-                                ampersona_engine::state::audit_log::count_state_mutations(
-                                    &audit_path,
-                                )
-                                .unwrap_or(0);
-                            // state_rev should match audited state mutations.
-                            // Allow +1 slack for pending/approve flow.
-                            let consistent = state.state_rev <= mutations + 1;
-                            output["state_rev_check"] = serde_json::json!({
-                                "state_rev": state.state_rev,
-                                "state_mutations": mutations,
-                                "consistent": consistent,
-                            });
-                            if !consistent {
-                                eprintln!(
-                                    "  warn: state_rev ({}) exceeds audited state mutations ({}) + 1",
-                                    state.state_rev, mutations
-                                );
-                            }
+                    if let Ok(Some(state)) = store.load() {
+                        let mutations =
+                            ampersona_engine::state::audit_log::count_state_mutation_lines(
+                                audit_lines(),
+                            );
+                        // state_rev should match audited state mutations.
+                        // Allow +1 slack for pending/approve flow.
+                        let consistent = state.state_rev <= mutations + 1;
+                        output["state_rev_check"] = serde_json::json!({
+                            "state_rev": state.state_rev,
+                            "state_mutations": mutations,
+                            "consistent": consistent,
+                        });
+                        if !consistent {
+                            warn!(
+                                "state_rev ({}) exceeds audited state mutations ({}) + 1; see --reconcile",
+                                state.state_rev, mutations
+                            );
                         }
                     }
 
-                    println!("{}", serde_json::to_string_pretty(&output).unwrap());
+                    println!("{}", to_json(&output).unwrap());
                 } else {
+                    for entry in decrypted.iter().flatten() {
+                        println!("{entry}");
+                    }
                     if from_entry > 0 {
-                        eprintln!("  audit chain valid ({count} entries, verified from entry {from_entry})");
+                        info!(
+                            "audit chain valid ({count} entries, verified from entry {from_entry})"
+                        );
                     } else {
-                        eprintln!("  audit chain valid ({count} entries)");
+                        info!("audit chain valid ({count} entries)");
                     }
                 }
                 CmdExit::Ok
@@ -1777,177 +5706,760 @@ fn cmd_audit(opts: AuditOpts) -> CmdExit {
                         "error": msg,
                         "audit_path": audit_path,
                     });
-                    println!("{}", serde_json::to_string_pretty(&output).unwrap());
+                    println!("{}", to_json(&output).unwrap());
                 } else {
-                    eprintln!("  audit chain INVALID: {msg}");
+                    error!("audit chain INVALID: {msg}");
                 }
-                CmdExit::Code(1)
+                CmdExit::Code(exit::FAILURE)
             }
         }
     }
 }
 
+fn print_audit_stats(audit_path: &str, stats: &ampersona_engine::state::audit_log::AuditStats) {
+    let ts = |t: Option<chrono::DateTime<chrono::Utc>>| {
+        t.map_or_else(|| "-".to_string(), |t| t.to_rfc3339())
+    };
+    info!("{audit_path}: {} entries", stats.entries);
+    for (event_type, count) in &stats.event_counts {
+        info!("  {event_type:<24} {count}");
+    }
+    info!("first:     {}", ts(stats.first_ts));
+    info!("last:      {}", ts(stats.last_ts));
+    info!("overrides: {}", stats.overrides);
+    match stats.avg_pending_seconds {
+        Some(avg) => info!(
+            "approvals: {} (avg pending {:.1}s)",
+            stats.approval_cycles, avg
+        ),
+        None => info!("approvals: 0"),
+    }
+}
+
+/// Compare state_rev with the audit log; with `fix`, rewrite it under the state lock.
+///
+/// Returns the check as it stood before any repair, and whether a repair was written.
+fn reconcile_state_rev(
+    store: &dyn StateStore,
+    fix: bool,
+    lock_timeout: Duration,
+) -> Result<(ampersona_engine::state::audit_log::RevCheck, bool)> {
+    let audit_path = store.audit_location();
+    let check = || -> Result<_> {
+        let Some(lines) = store.audit_lines()? else {
+            bail!("no audit log at {audit_path}");
+        };
+        let Some(state) = store.load()? else {
+            bail!("no phase state to reconcile with {audit_path}");
+        };
+        let check = ampersona_engine::state::audit_log::check_state_rev_lines(
+            state.state_rev,
+            &audit_path,
+            lines.iter().map(String::as_str),
+        )?;
+        Ok((state, check))
+    };
+
+    let (_, before) = check()?;
+    if !fix || before.consistent {
+        return Ok((before, false));
+    }
+
+    // Check again under the lock: another writer may have moved on.
+    let writer = ampersona_engine::state::writer::StateWriter::with_store(store, lock_timeout)?;
+    let (mut state, check) = check()?;
+    if check.consistent {
+        return Ok((check, false));
+    }
+
+    state.state_rev = check.expected_state_rev;
+    state.updated_at = chrono::Utc::now();
+    writer.audit(&serde_json::json!({
+        "event_type": "Reconcile",
+        "previous_state_rev": check.state_rev,
+        "state_rev": check.expected_state_rev,
+        "state_mutations": check.state_mutations,
+    }))?;
+    writer.write_state(&state)?;
+    Ok((check, true))
+}
+
+/// Build an audit bundle, optionally sign it, and write it atomically.
+fn export_audit_bundle(
+    persona_path: &str,
+    sidecars: &ampersona_core::sidecar::Sidecars,
+    bundle_path: &str,
+    sign_key: Option<&str>,
+    key_id: &str,
+) -> Result<serde_json::Value> {
+    use ampersona_engine::state::bundle::{export_bundle, export_store_bundle};
+
+    // The files backend bundles the state file as written.
+    let mut bundle = if SQLITE_BACKEND.load(Ordering::Relaxed) {
+        export_store_bundle(persona_path, &*state_store(sidecars)?, &sidecars.checkpoint)?
+    } else {
+        export_bundle(persona_path, sidecars)?
+    };
+    if let Some(key_path) = sign_key {
+        let signing_key = load_signing_key(key_path)?;
+        ampersona_sign::sign::sign_persona(&mut bundle, &signing_key, key_id, "cli")?;
+    }
+    let json = serde_json::to_string_pretty(&bundle)?;
+    ampersona_engine::state::atomic::atomic_write(bundle_path, json.as_bytes())?;
+    Ok(bundle)
+}
+
+/// Unpack an audit bundle, checking its signature first when keys are given.
+fn cmd_audit_import(
+    bundle_path: &str,
+    dest: Option<&str>,
+    verify_keys: &[String],
+    json_out: bool,
+) -> CmdExit {
+    let bundle: serde_json::Value = match std::fs::read_to_string(bundle_path)
+        .map_err(anyhow::Error::from)
+        .and_then(|c| Ok(serde_json::from_str(&c)?))
+    {
+        Ok(b) => b,
+        Err(e) => return CmdExit::Err(e.context(format!("cannot read bundle {bundle_path}"))),
+    };
+
+    let signed = bundle.get("signature").is_some();
+    if !verify_keys.is_empty() {
+        let keys = match verify_keys
+            .iter()
+            .map(|p| load_verifying_key(p))
+            .collect::<Result<Vec<_>>>()
+        {
+            Ok(keys) => keys,
+            Err(e) => return CmdExit::Err(e),
+        };
+        let valid = signed
+            && keys
+                .iter()
+                .any(|k| ampersona_sign::verify::verify_persona(&bundle, k).unwrap_or(false));
+        if !valid {
+            let reason = if signed {
+                "bundle signature verification failed"
+            } else {
+                "bundle is not signed"
+            };
+            if json_out {
+                let output = serde_json::json!({"valid": false, "error": reason});
+                println!("{}", to_json(&output).unwrap());
+            } else {
+                error!("{reason}; nothing imported");
+            }
+            return CmdExit::Code(exit::FAILURE);
+        }
+    } else if signed && !json_out {
+        warn!("bundle is signed but no --verify-key was given; signature not checked");
+    }
+
+    let dest_dir = match dest {
+        Some(d) => std::path::PathBuf::from(d),
+        None => std::path::Path::new(bundle_path)
+            .parent()
+            .map(std::path::Path::to_path_buf)
+            .unwrap_or_default(),
+    };
+    let imported = match ampersona_engine::state::bundle::import_bundle(&bundle, &dest_dir) {
+        Ok(i) => i,
+        Err(e) => return CmdExit::Err(e.into()),
+    };
+    #[cfg(feature = "sqlite")]
+    let database = if SQLITE_BACKEND.load(Ordering::Relaxed) && imported.consistent() {
+        match import_into_database(&imported.persona_path) {
+            Ok(location) => Some(location),
+            Err(e) => return CmdExit::Err(e),
+        }
+    } else {
+        None
+    };
+    #[cfg(not(feature = "sqlite"))]
+    let database: Option<String> = None;
+
+    if json_out {
+        let mut output = serde_json::to_value(&imported).unwrap();
+        if let Some(location) = &database {
+            output["database"] = serde_json::json!(location);
+        }
+        output["valid"] = serde_json::json!(imported.consistent());
+        output["signature_checked"] = serde_json::json!(!verify_keys.is_empty());
+        println!("{}", to_json(&output).unwrap());
+    } else {
+        for f in &imported.files {
+            info!("wrote {f}");
+        }
+        if let Some(location) = &database {
+            info!("imported state and audit log into {location}");
+        }
+        if imported.consistent() {
+            info!("audit chain valid ({} entries)", imported.entries);
+        } else {
+            error!(
+                "audit chain INVALID after import (export: {}, now: {}{})",
+                if imported.export_chain_valid {
+                    "valid"
+                } else {
+                    "invalid"
+                },
+                if imported.chain_valid {
+                    "valid"
+                } else {
+                    "invalid"
+                },
+                if imported.checkpoint_valid == Some(false) {
+                    ", checkpoint mismatch"
+                } else {
+                    ""
+                }
+            );
+        }
+    }
+    if imported.consistent() {
+        CmdExit::Ok
+    } else {
+        CmdExit::Code(exit::FAILURE)
+    }
+}
+
+/// Copy the state file and audit log an import just wrote into the workspace
+/// database, as `amp migrate-backend` would; returns where they went.
+#[cfg(feature = "sqlite")]
+fn import_into_database(persona_path: &str) -> Result<String> {
+    let written = ampersona_core::sidecar::Sidecars::for_persona(persona_path);
+    let store = sqlite_store(&default_sidecars(persona_path).state)?;
+    store.import_files(&written.state, &written.audit)?;
+    Ok(store.location())
+}
+
 /// Sign a checkpoint JSON value with ed25519.
 fn sign_checkpoint(checkpoint: &mut serde_json::Value, key_path: &str, key_id: &str) -> Result<()> {
-    let key_bytes =
-        std::fs::read(key_path).map_err(|e| anyhow::anyhow!("cannot read key {key_path}: {e}"))?;
-    let key_array: [u8; 32] = key_bytes
-        .get(..32)
-        .ok_or_else(|| anyhow::anyhow!("key must be at least 32 bytes"))?
-        .try_into()
-        .unwrap();
-    let signing_key = ed25519_dalek::SigningKey::from_bytes(&key_array);
+    let signing_key = load_signing_key(key_path)?;
+    ampersona_sign::checkpoint::sign_checkpoint(checkpoint, &signing_key, key_id)
+}
 
-    // Sign the canonical checkpoint JSON (without any existing signature)
-    let mut signable = checkpoint.clone();
-    if let Some(obj) = signable.as_object_mut() {
-        obj.remove("signature");
+/// Add a signature to an existing checkpoint after checking it still matches the audit log.
+fn cosign_checkpoint<'a>(
+    audit_path: &str,
+    audit_lines: impl IntoIterator<Item = &'a str>,
+    checkpoint_path: &str,
+    key_path: &str,
+    key_id: &str,
+) -> Result<serde_json::Value> {
+    let mut checkpoint = read_checkpoint(checkpoint_path)?;
+    if !ampersona_engine::state::audit_log::verify_checkpoint_lines(
+        audit_path,
+        audit_lines,
+        &checkpoint,
+    )? {
+        anyhow::bail!(
+            "checkpoint {checkpoint_path} does not match {audit_path}; refusing to co-sign"
+        );
     }
-    let canonical = serde_json::to_string(&signable)?;
-    use ed25519_dalek::Signer;
-    let sig = signing_key.sign(canonical.as_bytes());
+    sign_checkpoint(&mut checkpoint, key_path, key_id)?;
+    let json = serde_json::to_string_pretty(&checkpoint)?;
+    ampersona_engine::state::atomic::atomic_write(checkpoint_path, json.as_bytes())?;
+    Ok(checkpoint)
+}
 
-    let sig_hex: String = sig.to_bytes().iter().map(|b| format!("{b:02x}")).collect();
-    if let Some(obj) = checkpoint.as_object_mut() {
-        obj.insert(
-            "signature".into(),
-            serde_json::json!({
-                "key_id": key_id,
-                "algorithm": "ed25519",
-                "value": sig_hex,
-            }),
+/// Parse the checkpoint file at `path`.
+fn read_checkpoint(path: &str) -> Result<serde_json::Value> {
+    use anyhow::Context;
+
+    let content = std::fs::read_to_string(path).with_context(|| format!("cannot read {path}"))?;
+    serde_json::from_str(&content).with_context(|| format!("{path}: invalid JSON"))
+}
+
+/// Verify a signed checkpoint file against one or more public keys.
+fn verify_checkpoint_signatures(
+    checkpoint_path: &str,
+    pubkey_paths: &[String],
+) -> Result<ampersona_sign::checkpoint::CheckpointSignatures> {
+    let checkpoint = read_checkpoint(checkpoint_path)?;
+    let keys = pubkey_paths
+        .iter()
+        .map(|p| load_verifying_key(p))
+        .collect::<Result<Vec<_>>>()?;
+    ampersona_sign::checkpoint::verify_checkpoint(&checkpoint, &keys)
+}
+
+/// Anchor a checkpoint with an RFC 3161 timestamp token.
+#[cfg(feature = "tsa")]
+fn timestamp_checkpoint(checkpoint: &mut serde_json::Value, tsa_url: &str) -> Result<()> {
+    ampersona_sign::timestamp::anchor(checkpoint, tsa_url)
+}
+
+#[cfg(not(feature = "tsa"))]
+fn timestamp_checkpoint(_checkpoint: &mut serde_json::Value, _tsa_url: &str) -> Result<()> {
+    anyhow::bail!("--timestamp-url requires amp built with the `tsa` feature")
+}
+
+/// Check a checkpoint's timestamp imprint: `Ok(Ok(gen_time))` when the token
+/// was issued for the file's current contents.
+#[cfg(feature = "tsa")]
+fn check_timestamp_imprint(checkpoint_path: &str) -> Result<Result<String, String>> {
+    let checkpoint = read_checkpoint(checkpoint_path)?;
+    Ok(ampersona_sign::timestamp::check_imprint(&checkpoint)
+        .map(|info| info.gen_time)
+        .map_err(|e| format!("{e:#}")))
+}
+
+#[cfg(not(feature = "tsa"))]
+fn check_timestamp_imprint(_checkpoint_path: &str) -> Result<Result<String, String>> {
+    anyhow::bail!("--timestamp-imprint requires amp built with the `tsa` feature")
+}
+
+struct ComposeOpts {
+    base: String,
+    overlay: String,
+    canonical: bool,
+    output: Option<String>,
+    write: bool,
+    force: bool,
+    no_upgrade_autonomy: bool,
+}
+
+fn cmd_compose(opts: ComposeOpts) -> Result<()> {
+    let base = ampersona_core::prompt::load_persona(&opts.base)?;
+    let overlay = ampersona_core::prompt::load_persona(&opts.overlay)?;
+    let merged = ampersona_core::compose::merge_personas(&base, &overlay);
+    if opts.no_upgrade_autonomy {
+        let upgrades = ampersona_core::compose::autonomy_upgrades(&base, &merged);
+        report_autonomy_upgrades(&opts.base, &upgrades);
+        if !upgrades.is_empty() {
+            bail!(
+                "overlay {} would raise autonomy (--no-upgrade-autonomy)",
+                opts.overlay
+            );
+        }
+    }
+    let json = if opts.canonical {
+        ampersona_core::canonical::canonicalize(&merged)
+    } else {
+        serde_json::to_string_pretty(&merged)?
+    };
+
+    let dest = if opts.write {
+        Some(opts.base.clone())
+    } else {
+        opts.output.clone()
+    };
+    let Some(dest) = dest else {
+        if opts.canonical {
+            println!("{json}");
+        } else {
+            println!("{}", to_json(&merged)?);
+        }
+        return Ok(());
+    };
+
+    let report = ampersona_core::schema::check(&merged, &dest, false);
+    for e in &report.errors {
+        error!("{}: {}", e.code, e.message);
+    }
+    if !report.errors.is_empty() && !opts.force {
+        bail!(
+            "merged persona has {} validation error(s); not writing {dest} (use --force)",
+            report.errors.len()
         );
     }
+    if opts.write {
+        std::fs::copy(&dest, ampersona_core::sidecar::sidecar_path(&dest, ".bak"))?;
+    }
+    ampersona_engine::state::atomic::atomic_write(&dest, json.as_bytes())?;
+    info!("wrote {dest}");
     Ok(())
 }
 
-/// Verify a signed checkpoint file.
-fn verify_checkpoint_signature(checkpoint_path: &str, pubkey_path: &str) -> Result<bool> {
-    let content = std::fs::read_to_string(checkpoint_path)?;
-    let checkpoint: serde_json::Value = serde_json::from_str(&content)?;
-
-    let sig_obj = checkpoint
-        .get("signature")
-        .ok_or_else(|| anyhow::anyhow!("checkpoint has no signature"))?;
-    let sig_hex = sig_obj
-        .get("value")
-        .and_then(|v| v.as_str())
-        .ok_or_else(|| anyhow::anyhow!("invalid signature format"))?;
-    let sig_bytes: Vec<u8> = (0..sig_hex.len())
-        .step_by(2)
-        .map(|i| u8::from_str_radix(&sig_hex[i..i + 2], 16))
-        .collect::<Result<Vec<u8>, _>>()
-        .map_err(|e| anyhow::anyhow!("invalid hex in signature: {e}"))?;
-    let sig = ed25519_dalek::Signature::from_slice(&sig_bytes)
-        .map_err(|e| anyhow::anyhow!("invalid signature: {e}"))?;
-
-    let key_bytes = std::fs::read(pubkey_path)
-        .map_err(|e| anyhow::anyhow!("cannot read pubkey {pubkey_path}: {e}"))?;
-    let key_array: [u8; 32] = key_bytes
-        .get(..32)
-        .ok_or_else(|| anyhow::anyhow!("pubkey must be at least 32 bytes"))?
-        .try_into()
-        .unwrap();
-    let verifying_key = ed25519_dalek::VerifyingKey::from_bytes(&key_array)
-        .map_err(|e| anyhow::anyhow!("invalid pubkey: {e}"))?;
+fn cmd_canonicalize(file: &str) -> Result<()> {
+    let data = read_persona(file)?;
+    println!("{}", ampersona_core::canonical::canonicalize(&data));
+    Ok(())
+}
 
-    // Reconstruct canonical form without signature
-    let mut signable = checkpoint.clone();
-    if let Some(obj) = signable.as_object_mut() {
-        obj.remove("signature");
+/// The file is hashed as written (no `$extends` resolution), like `amp sign`.
+fn cmd_fingerprint(file: &str, json_out: bool) -> Result<()> {
+    let content = std::fs::read_to_string(file)?;
+    let data: serde_json::Value = serde_json::from_str(&content)?;
+    let fingerprint = ampersona_sign::sign::fingerprint(&data)?;
+    if json_out {
+        let out = serde_json::json!({
+            "file": file,
+            "fingerprint": fingerprint,
+            "version": data.get("version"),
+            "name": data.get("name"),
+        });
+        println!("{}", to_json(&out)?);
+    } else {
+        println!("{fingerprint}");
     }
-    let canonical = serde_json::to_string(&signable)?;
+    Ok(())
+}
 
-    use ed25519_dalek::Verifier;
-    Ok(verifying_key.verify(canonical.as_bytes(), &sig).is_ok())
+fn cmd_resolve(file: &str, opts: ampersona_core::prompt::LoadOptions) -> Result<()> {
+    let data = read_persona_with(file, opts)?;
+    println!("{}", to_json(&data)?);
+    Ok(())
 }
 
-fn cmd_compose(base_path: &str, overlay_path: &str) -> Result<()> {
-    let base = ampersona_core::prompt::load_persona(base_path)?;
-    let overlay = ampersona_core::prompt::load_persona(overlay_path)?;
-    let merged = ampersona_core::compose::merge_personas(&base, &overlay);
-    println!("{}", serde_json::to_string_pretty(&merged)?);
+fn cmd_redact(
+    file: &str,
+    mut fields: Vec<String>,
+    preset: Option<&str>,
+    remove: bool,
+    output: Option<&str>,
+) -> Result<()> {
+    if let Some(name) = preset {
+        let preset = ampersona_core::redact::preset(name)
+            .ok_or_else(|| anyhow::anyhow!("unknown preset '{name}'"))?;
+        fields.extend(preset.iter().map(|f| f.to_string()));
+    }
+    if fields.is_empty() {
+        bail!("specify --fields or --preset");
+    }
+    let mut data = read_persona(file)?;
+    let outcome = ampersona_core::redact::redact(&mut data, &fields, remove)?;
+    for r in &outcome.redacted {
+        info!("{} {}", r.action, r.path);
+    }
+
+    if let Some(path) = output {
+        std::fs::write(path, serde_json::to_string_pretty(&data)?)?;
+        info!("wrote {path}");
+    } else {
+        println!("{}", to_json(&data)?);
+    }
     Ok(())
 }
 
-fn cmd_diff(a_path: &str, b_path: &str) -> Result<()> {
+fn cmd_diff(a_path: &str, b_path: &str, semantic: bool) -> Result<()> {
     let a = ampersona_core::prompt::load_persona(a_path)?;
     let b = ampersona_core::prompt::load_persona(b_path)?;
+    if semantic {
+        let a = ampersona_core::canonical::normalize_semantic(&a);
+        let b = ampersona_core::canonical::normalize_semantic(&b);
+        diff_values(
+            "",
+            &a,
+            &b,
+            Some(ampersona_core::canonical::DEFAULT_FLOAT_EPSILON),
+        );
+    } else {
+        diff_values("", &a, &b, None);
+    }
+    Ok(())
+}
 
-    fn diff_values(path: &str, a: &serde_json::Value, b: &serde_json::Value) {
-        if a == b {
-            return;
-        }
-        match (a, b) {
-            (serde_json::Value::Object(ao), serde_json::Value::Object(bo)) => {
-                let all_keys: std::collections::BTreeSet<_> = ao.keys().chain(bo.keys()).collect();
-                for key in all_keys {
-                    let subpath = if path.is_empty() {
-                        key.clone()
-                    } else {
-                        format!("{path}.{key}")
-                    };
-                    match (ao.get(key), bo.get(key)) {
-                        (Some(av), Some(bv)) => diff_values(&subpath, av, bv),
-                        (Some(av), None) => println!("- {subpath}: {av}"),
-                        (None, Some(bv)) => println!("+ {subpath}: {bv}"),
-                        (None, None) => {}
-                    }
+/// Scriptable counterpart to `amp diff --semantic`: silent on equality.
+fn cmd_eq(a_path: &str, b_path: &str) -> CmdExit {
+    use ampersona_core::canonical::{normalize_semantic, DEFAULT_FLOAT_EPSILON};
+
+    let load =
+        |path: &str| ampersona_core::prompt::load_persona(path).map(|v| normalize_semantic(&v));
+    let (a, b) = match (load(a_path), load(b_path)) {
+        (Ok(a), Ok(b)) => (a, b),
+        (Err(e), _) | (_, Err(e)) => return CmdExit::Err(e),
+    };
+    if ampersona_core::canonical::values_approx_equal(&a, &b, DEFAULT_FLOAT_EPSILON) {
+        return CmdExit::Ok;
+    }
+    diff_values("", &a, &b, Some(DEFAULT_FLOAT_EPSILON));
+    CmdExit::Code(exit::FAILURE)
+}
+
+/// Print a `- path: old` / `+ path: new` line diff between two JSON values.
+///
+/// With `eps`, values equal per [`values_approx_equal`] are skipped and
+/// same-length arrays are compared element by element.
+///
+/// [`values_approx_equal`]: ampersona_core::canonical::values_approx_equal
+fn diff_values(path: &str, a: &serde_json::Value, b: &serde_json::Value, eps: Option<f64>) {
+    use serde_json::Value;
+
+    let equal = match eps {
+        Some(eps) => ampersona_core::canonical::values_approx_equal(a, b, eps),
+        None => a == b,
+    };
+    if equal {
+        return;
+    }
+    match (a, b) {
+        (Value::Object(ao), Value::Object(bo)) => {
+            let all_keys: std::collections::BTreeSet<_> = ao.keys().chain(bo.keys()).collect();
+            for key in all_keys {
+                let subpath = if path.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{path}.{key}")
+                };
+                match (ao.get(key), bo.get(key)) {
+                    (Some(av), Some(bv)) => diff_values(&subpath, av, bv, eps),
+                    (Some(av), None) => println!("- {subpath}: {av}"),
+                    (None, Some(bv)) => println!("+ {subpath}: {bv}"),
+                    (None, None) => {}
                 }
             }
-            _ => {
-                println!("- {path}: {a}");
-                println!("+ {path}: {b}");
+        }
+        (Value::Array(aa), Value::Array(ba)) if eps.is_some() && aa.len() == ba.len() => {
+            for (i, (av, bv)) in aa.iter().zip(ba).enumerate() {
+                diff_values(&format!("{path}[{i}]"), av, bv, eps);
             }
         }
+        _ => {
+            println!("- {path}: {a}");
+            println!("+ {path}: {b}");
+        }
     }
-
-    diff_values("", &a, &b);
-    Ok(())
 }
 
-fn cmd_import(file: &str, from: &str) -> Result<()> {
+fn cmd_import(
+    file: &str,
+    from: &str,
+    agent: Option<&str>,
+    backstory_style: &str,
+    fears: ampersona_engine::convert::aieos::FearHandling,
+) -> Result<()> {
+    use ampersona_engine::convert::aieos::{BackstoryStyle, FearHandling, ImportOptions};
+
+    if from != "aieos" && backstory_style != "sentences" {
+        bail!("--backstory-style applies to --from aieos only");
+    }
+    if from != "aieos" && fears != FearHandling::Merge {
+        bail!("--separate-irrational-fears/--drop-irrational-fears apply to --from aieos only");
+    }
+    if from != "crewai" && agent.is_some() {
+        bail!("--agent applies to --from crewai only");
+    }
     let content = std::fs::read_to_string(file)?;
+    if from == "crewai" {
+        let data = ampersona_engine::convert::crewai::parse_crewai(&content)?;
+        let persona = ampersona_engine::convert::crewai::import_crewai_agent(&data, agent)?;
+        println!("{}", to_json(&persona)?);
+        return Ok(());
+    }
+    if from == "toml" {
+        let persona = ampersona_engine::convert::toml::import_toml(&content)?;
+        println!("{}", to_json(&persona)?);
+        return Ok(());
+    }
     let data: serde_json::Value = serde_json::from_str(&content)?;
     let persona = match from {
-        "aieos" => ampersona_engine::convert::aieos::import_aieos(&data)?,
+        "aieos" => {
+            let opts = ImportOptions {
+                backstory_style: match backstory_style {
+                    "bullets" => BackstoryStyle::Bullets,
+                    _ => BackstoryStyle::Sentences,
+                },
+                fears,
+            };
+            ampersona_engine::convert::aieos::import_aieos_with(&data, &opts)?
+        }
         "zeroclaw" => ampersona_engine::convert::zeroclaw::import_zeroclaw(&data)?,
-        _ => bail!("import from '{from}' not supported (use: aieos, zeroclaw)"),
+        _ => bail!("import from '{from}' not supported (use: aieos, zeroclaw, toml, crewai)"),
     };
-    println!("{}", serde_json::to_string_pretty(&persona)?);
+    println!("{}", to_json(&persona)?);
     Ok(())
 }
 
 fn cmd_export(file: &str, to: &str) -> Result<()> {
     let data = read_persona(file)?;
+    if to == "toml" {
+        print!("{}", ampersona_engine::convert::toml::export_toml(&data)?);
+        return Ok(());
+    }
     let exported = match to {
         "aieos" => ampersona_engine::convert::aieos::export_aieos(&data)?,
         "zeroclaw-config" | "zeroclaw" => {
             ampersona_engine::convert::zeroclaw::export_zeroclaw(&data)?
         }
-        _ => bail!("export to '{to}' not supported (use: aieos, zeroclaw-config)"),
+        _ => bail!("export to '{to}' not supported (use: aieos, zeroclaw-config, toml)"),
     };
-    println!("{}", serde_json::to_string_pretty(&exported)?);
+    println!("{}", to_json(&exported)?);
     Ok(())
 }
 
-fn cmd_fleet(
-    dir: &str,
+/// Export `file` via `via` and import it back; fields that did not survive
+/// (beyond `tolerance` for numbers) are reported and exit 1.
+fn cmd_roundtrip(file: &str, via: &str, tolerance: f64, json_out: bool) -> Result<()> {
+    use ampersona_engine::convert::{aieos, toml};
+
+    let data = read_persona(file)?;
+    let back = match via {
+        "aieos" => aieos::import_aieos(&aieos::export_aieos(&data)?)?,
+        "toml" => toml::import_toml(&toml::export_toml(&data)?)?,
+        _ => bail!("roundtrip via '{via}' not supported (use: aieos, toml)"),
+    };
+    let mut lossy = Vec::new();
+    lossy_paths("", &data, &back, tolerance, &mut lossy);
+
+    if json_out {
+        let report = serde_json::json!({
+            "file": file,
+            "via": via,
+            "tolerance": tolerance,
+            "lossless": lossy.is_empty(),
+            "lossy": lossy,
+        });
+        println!("{}", to_json(&report)?);
+    } else if lossy.is_empty() {
+        info!("{file}: lossless via {via}");
+    } else {
+        for path in &lossy {
+            info!("lossy {path}");
+        }
+    }
+    if !lossy.is_empty() {
+        bail!("{} field(s) of {file} did not survive {via}", lossy.len());
+    }
+    Ok(())
+}
+
+/// Collect the deepest object paths where `a` and `b` differ beyond `eps`.
+fn lossy_paths(
+    path: &str,
+    a: &serde_json::Value,
+    b: &serde_json::Value,
+    eps: f64,
+    out: &mut Vec<String>,
+) {
+    if ampersona_core::canonical::values_approx_equal(a, b, eps) {
+        return;
+    }
+    match (a, b) {
+        (serde_json::Value::Object(ao), serde_json::Value::Object(bo)) => {
+            let all_keys: std::collections::BTreeSet<_> = ao.keys().chain(bo.keys()).collect();
+            for key in all_keys {
+                let subpath = format!("{path}/{key}");
+                match (ao.get(key), bo.get(key)) {
+                    (Some(av), Some(bv)) => lossy_paths(&subpath, av, bv, eps, out),
+                    _ => out.push(subpath),
+                }
+            }
+        }
+        _ => out.push(path.to_string()),
+    }
+}
+
+struct FleetOpts {
+    dir: String,
+    recursive: bool,
     status: bool,
     check: bool,
+    pending: bool,
     json_out: bool,
+    csv_out: bool,
+    ndjson: bool,
+    verify_key: Option<String>,
+    require_sig: bool,
+    filters: Vec<FleetFilter>,
     apply_overlay: Option<String>,
-) -> Result<()> {
-    let entries = std::fs::read_dir(dir)?;
-    let mut files: Vec<String> = entries
-        .filter_map(|e| e.ok())
-        .filter(|e| e.path().extension().is_some_and(|ext| ext == "json"))
-        .filter(|e| !e.file_name().to_string_lossy().ends_with(".state.json"))
-        .map(|e| e.path().to_string_lossy().to_string())
-        .collect();
-    files.sort();
+    dry_run: bool,
+    no_backup: bool,
+    no_upgrade_autonomy: bool,
+    metrics_dir: Option<String>,
+    lock_timeout: Duration,
+}
+
+fn cmd_fleet(opts: FleetOpts) -> Result<()> {
+    let FleetOpts {
+        ref dir,
+        recursive,
+        status,
+        check,
+        pending,
+        json_out,
+        csv_out,
+        ndjson,
+        verify_key,
+        require_sig,
+        ref filters,
+        apply_overlay,
+        dry_run,
+        no_backup,
+        no_upgrade_autonomy,
+        metrics_dir,
+        lock_timeout,
+    } = opts;
+    let mut files = persona_files(dir, recursive)?;
+    if !filters.is_empty() {
+        let mut kept = Vec::new();
+        for file in files {
+            let data = ampersona_core::prompt::load_persona(&file)?;
+            if filters.iter().all(|f| f.matches(&data, &file)) {
+                kept.push(file);
+            }
+        }
+        files = kept;
+    }
+
+    if let Some(metrics_dir) = metrics_dir {
+        return fleet_evaluate(dir, &files, &metrics_dir, lock_timeout, json_out);
+    }
+
+    if pending {
+        let now = chrono::Utc::now();
+        let mut rows = Vec::new();
+        for file in &files {
+            let Some(pending) = stored_state(file).and_then(|s| s.pending_transition) else {
+                continue;
+            };
+            let data = ampersona_core::prompt::load_persona(file)?;
+            let mut row = pending_entry(&pending, now);
+            row["file"] = serde_json::json!(relative_name(dir, file));
+            row["name"] = data["name"].clone();
+            rows.push(row);
+        }
+        if json_out {
+            println!("{}", to_json(&rows)?);
+            return Ok(());
+        }
+        if rows.is_empty() {
+            info!("no pending approvals");
+            return Ok(());
+        }
+        println!(
+            "{:<30}  {:<10}  {:<12}  {:<24}  {:<10}",
+            "FILE", "NAME", "GATE", "TRANSITION", "WAITING"
+        );
+        for row in &rows {
+            let text = |key: &str| row[key].as_str().unwrap_or("none").to_string();
+            let age = Duration::from_secs(row["age_seconds"].as_u64().unwrap_or(0));
+            println!(
+                "{:<30}  {:<10}  {:<12}  {:<24}  {:<10}",
+                text("file"),
+                text("name"),
+                text("gate_id"),
+                format!("{} \u{2192} {}", text("from_phase"), text("to_phase")),
+                humantime::format_duration(age).to_string()
+            );
+        }
+        return Ok(());
+    }
 
     if status {
+        if csv_out {
+            print_csv_row(&["file", "name", "autonomy", "phase"]);
+            for file in &files {
+                let [fname, name, autonomy, phase] = fleet_status_row(dir, file)?;
+                print_csv_row(&[&fname, &name, &autonomy, &phase]);
+            }
+            return Ok(());
+        }
+        if ndjson {
+            for file in &files {
+                let [fname, name, autonomy, phase] =
+                    fleet_status_row(dir, file).map_err(|e| ndjson_failed(true, e))?;
+                emit_ndjson(&serde_json::json!({
+                    "file": fname,
+                    "name": name,
+                    "autonomy": autonomy,
+                    "phase": phase,
+                }))?;
+            }
+            return Ok(());
+        }
         println!(
             "{:<30}  {:<10}  {:<12}  {:<10}",
             "FILE", "NAME", "AUTONOMY", "PHASE"
@@ -1960,61 +6472,335 @@ fn cmd_fleet(
             "-".repeat(10)
         );
         for file in &files {
-            let data = ampersona_core::prompt::load_persona(file)?;
-            let name = data.get("name").and_then(|v| v.as_str()).unwrap_or("-");
-            let autonomy = data
-                .pointer("/authority/autonomy")
-                .and_then(|v| v.as_str())
-                .unwrap_or("-");
-            let state_path = file.replace(".json", ".state.json");
-            let phase = ampersona_engine::state::phase::load_state(&state_path)
-                .ok()
-                .and_then(|s| s.current_phase)
-                .unwrap_or_else(|| "-".into());
-            let fname = std::path::Path::new(file)
-                .file_name()
-                .map(|f| f.to_string_lossy().to_string())
-                .unwrap_or_default();
+            let [fname, name, autonomy, phase] = fleet_status_row(dir, file)?;
             println!("{fname:<30}  {name:<10}  {autonomy:<12}  {phase:<10}");
         }
         return Ok(());
     }
 
     if check {
+        let verifying_key = verify_key.as_deref().map(load_verifying_key).transpose()?;
         let mut reports = Vec::new();
+        let mut sig_statuses = Vec::new();
+        let mut sig_failures = 0;
         for file in &files {
-            let content = std::fs::read_to_string(file)?;
-            let data: serde_json::Value = serde_json::from_str(&content)?;
-            let report = ampersona_core::schema::check(&data, file, false);
-            if !json_out {
+            let data =
+                ampersona_core::prompt::load_persona(file).map_err(|e| ndjson_failed(ndjson, e))?;
+            let mut report = ampersona_core::schema::check(&data, file, false);
+            let sig_status = verifying_key.as_ref().map(|key| {
+                let (status, issue) = signature_status(&data, key, require_sig);
+                if let Some(issue) = issue {
+                    report.errors.push(issue);
+                    report.pass = false;
+                    sig_failures += 1;
+                }
+                status
+            });
+            if ndjson {
+                let mut v = serde_json::to_value(&report)?;
+                if let Some(status) = sig_status {
+                    v["signature"] = serde_json::json!(status);
+                }
+                emit_ndjson(&v)?;
+            } else if !json_out && !csv_out {
+                let sig_note = sig_status
+                    .map(|s| format!("  [signature: {s}]"))
+                    .unwrap_or_default();
                 if report.pass {
-                    eprintln!("  ok  {file}");
+                    info!("ok  {file}{sig_note}");
                 } else {
-                    eprintln!("  FAIL {file}");
+                    error!("FAIL {file}{sig_note}");
                     for e in &report.errors {
-                        eprintln!("    {}: {}", e.code, e.message);
+                        info!("  {}: {}", e.code, e.message);
                     }
                 }
             }
             reports.push(report);
+            sig_statuses.push(sig_status);
         }
         if json_out {
-            println!("{}", serde_json::to_string_pretty(&reports)?);
+            let mut out = Vec::new();
+            for (report, sig_status) in reports.iter().zip(&sig_statuses) {
+                let mut v = serde_json::to_value(report)?;
+                if let Some(status) = sig_status {
+                    v["signature"] = serde_json::json!(status);
+                }
+                out.push(v);
+            }
+            println!("{}", to_json(&out)?);
+        } else if csv_out {
+            let mut header = vec!["file", "pass", "error_count"];
+            if verifying_key.is_some() {
+                header.push("signature");
+            }
+            print_csv_row(&header);
+            for ((file, report), sig_status) in files.iter().zip(&reports).zip(&sig_statuses) {
+                let fname = relative_name(dir, file);
+                let pass = report.pass.to_string();
+                let error_count = report.errors.len().to_string();
+                let mut row = vec![fname.as_str(), pass.as_str(), error_count.as_str()];
+                if let Some(status) = sig_status {
+                    row.push(status);
+                }
+                print_csv_row(&row);
+            }
+        }
+        if sig_failures > 0 {
+            return Err(ndjson_failed(
+                ndjson,
+                anyhow::anyhow!("{sig_failures} file(s) failed signature verification"),
+            ));
         }
         return Ok(());
     }
 
     if let Some(overlay_path) = apply_overlay {
         let overlay = ampersona_core::prompt::load_persona(&overlay_path)?;
+        if no_upgrade_autonomy {
+            // Check the whole fleet first so a refusal leaves every file untouched.
+            let mut upgraded = 0;
+            for file in &files {
+                let base = ampersona_core::prompt::load_persona(file)?;
+                let merged = ampersona_core::compose::merge_personas(&base, &overlay);
+                let upgrades = ampersona_core::compose::autonomy_upgrades(&base, &merged);
+                report_autonomy_upgrades(file, &upgrades);
+                if !upgrades.is_empty() {
+                    upgraded += 1;
+                }
+            }
+            if upgraded > 0 {
+                bail!(
+                    "overlay would raise autonomy in {upgraded} of {} files (--no-upgrade-autonomy); nothing written",
+                    files.len()
+                );
+            }
+        }
+        let mut changed = 0;
         for file in &files {
             let base = ampersona_core::prompt::load_persona(file)?;
             let merged = ampersona_core::compose::merge_personas(&base, &overlay);
+            if merged == base {
+                continue;
+            }
+            changed += 1;
+            if dry_run {
+                println!("--- {file}");
+                diff_values("", &base, &merged, None);
+                continue;
+            }
+            if !no_backup {
+                std::fs::copy(file, ampersona_core::sidecar::sidecar_path(file, ".bak"))?;
+            }
             let json = serde_json::to_string_pretty(&merged)?;
-            std::fs::write(file, json)?;
-            eprintln!("  applied overlay to {file}");
+            ampersona_engine::state::atomic::atomic_write(file, json.as_bytes())?;
+            info!("applied overlay to {file}");
+        }
+        if dry_run {
+            info!("{changed} of {} files would change", files.len());
+        } else {
+            info!("{changed} of {} files changed", files.len());
         }
         return Ok(());
     }
 
-    bail!("specify --status, --check, or --apply-overlay");
+    bail!("specify --status, --check, --pending, --evaluate, or --apply-overlay");
+}
+
+/// `amp fleet --evaluate`: run `amp gate <file> --evaluate '*'` for every
+/// persona that has a metrics file of the same name in `metrics_dir`. Each
+/// persona is evaluated by its own `amp gate` process under its own state
+/// lock, up to one per CPU at a time; results keep the file order.
+fn fleet_evaluate(
+    dir: &str,
+    files: &[String],
+    metrics_dir: &str,
+    lock_timeout: Duration,
+    json_out: bool,
+) -> Result<()> {
+    use std::sync::atomic::AtomicUsize;
+    use std::sync::Mutex;
+
+    let exe = std::env::current_exe()?;
+    let workers = std::thread::available_parallelism()
+        .map_or(4, |n| n.get())
+        .min(files.len().max(1));
+    let next = AtomicUsize::new(0);
+    let rows = Mutex::new(Vec::with_capacity(files.len()));
+    std::thread::scope(|scope| {
+        for _ in 0..workers {
+            scope.spawn(|| loop {
+                let i = next.fetch_add(1, Ordering::Relaxed);
+                let Some(file) = files.get(i) else {
+                    break;
+                };
+                let row = evaluate_fleet_member(&exe, dir, file, metrics_dir, lock_timeout);
+                rows.lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .push((i, row));
+            });
+        }
+    });
+    let mut rows = rows.into_inner().unwrap_or_else(|e| e.into_inner());
+    rows.sort_by_key(|(i, _)| *i);
+    let rows: Vec<serde_json::Value> = rows.into_iter().map(|(_, row)| row).collect();
+
+    let count = |key: &str| rows.iter().filter(|r| r.get(key).is_some()).count();
+    let (evaluated, skipped, failed) = (count("result"), count("skipped"), count("error"));
+    if json_out {
+        let report = serde_json::json!({
+            "dir": dir,
+            "metrics_dir": metrics_dir,
+            "evaluated": evaluated,
+            "skipped": skipped,
+            "failed": failed,
+            "agents": rows,
+        });
+        println!("{}", to_json(&report)?);
+    } else {
+        for row in &rows {
+            let file = row["file"].as_str().unwrap_or_default();
+            if let Some(note) = row.get("skipped").and_then(|v| v.as_str()) {
+                info!("skip {file}: {note}");
+            } else if let Some(error) = row.get("error").and_then(|v| v.as_str()) {
+                error!("FAIL {file}: {error}");
+            } else {
+                let result = &row["result"];
+                match result.get("decision").and_then(|v| v.as_str()) {
+                    Some(decision) => info!(
+                        "{decision:<13} {file}  {}: {} \u{2192} {}",
+                        result["gate_id"].as_str().unwrap_or("?"),
+                        result["from_phase"].as_str().unwrap_or("none"),
+                        result["to_phase"].as_str().unwrap_or("?")
+                    ),
+                    None => info!("{:<13} {file}", "no gate fired"),
+                }
+            }
+        }
+        info!("{evaluated} evaluated, {skipped} skipped, {failed} failed");
+    }
+    if failed > 0 {
+        bail!("{failed} persona(s) could not be evaluated");
+    }
+    Ok(())
+}
+
+/// One row of the `amp fleet --evaluate` report: `result` holds the gate
+/// record (`null` when no gate fired), or `skipped` / `error` a note.
+fn evaluate_fleet_member(
+    exe: &std::path::Path,
+    dir: &str,
+    file: &str,
+    metrics_dir: &str,
+    lock_timeout: Duration,
+) -> serde_json::Value {
+    let mut row = serde_json::json!({"file": relative_name(dir, file)});
+    let metrics = std::path::Path::new(file)
+        .file_name()
+        .map(|name| std::path::Path::new(metrics_dir).join(name))
+        .filter(|m| m.is_file());
+    let Some(metrics) = metrics else {
+        row["skipped"] = serde_json::json!(format!("no metrics file in {metrics_dir}"));
+        return row;
+    };
+    row["metrics"] = serde_json::json!(metrics.to_string_lossy());
+
+    let mut cmd = std::process::Command::new(exe);
+    cmd.args(["gate", file, "--evaluate", "*", "--json", "--compact"])
+        .arg("--metrics")
+        .arg(&metrics)
+        .args(["--lock-timeout", &lock_timeout.as_secs().to_string()]);
+    if SQLITE_BACKEND.load(Ordering::Relaxed) {
+        cmd.args(["--backend", "sqlite"]);
+    }
+    if let Some(state_dir) = STATE_DIR.get() {
+        cmd.arg("--state-dir").arg(state_dir);
+    }
+    let out = match cmd.output() {
+        Ok(out) => out,
+        Err(e) => {
+            row["error"] = serde_json::json!(format!("cannot run amp gate: {e}"));
+            return row;
+        }
+    };
+    let code = out.status.code();
+    let record = serde_json::from_slice::<serde_json::Value>(&out.stdout).ok();
+    match (code, record) {
+        // Fired (0), pending approval (2), or fired without applying (1).
+        (Some(exit::OK | exit::PENDING | exit::FAILURE), Some(record))
+            if record.get("decision").is_some() =>
+        {
+            row["result"] = record;
+        }
+        (Some(exit::FAILURE), None) => row["result"] = serde_json::Value::Null,
+        (code, record) => {
+            let stderr = String::from_utf8_lossy(&out.stderr);
+            let message = record
+                .as_ref()
+                .and_then(|r| r.get("message").and_then(|m| m.as_str()).map(String::from))
+                .or_else(|| stderr.lines().last().map(|l| l.trim().to_string()))
+                .unwrap_or_else(|| format!("amp gate exited with {code:?}"));
+            row["error"] = serde_json::json!(message);
+        }
+    }
+    row
+}
+
+/// Print one stderr line per autonomy raise found in `file`.
+fn report_autonomy_upgrades(file: &str, upgrades: &[ampersona_core::compose::AutonomyUpgrade]) {
+    for u in upgrades {
+        info!(
+            "{file}: {} {} -> {}",
+            u.path,
+            u.from.as_str(),
+            u.to.as_str()
+        );
+    }
+}
+
+/// Persona `.json` files in `dir` (state sidecars excluded), sorted by path.
+fn persona_files(dir: &str, recursive: bool) -> Result<Vec<String>> {
+    Ok(ampersona_core::list::persona_paths(dir, recursive)?
+        .into_iter()
+        .map(|p| p.to_string_lossy().to_string())
+        .collect())
+}
+
+/// Per-file fields for `amp fleet --status`: path relative to the fleet
+/// directory, name, autonomy, phase.
+fn fleet_status_row(dir: &str, file: &str) -> Result<[String; 4]> {
+    let data = ampersona_core::prompt::load_persona(file)?;
+    let name = data.get("name").and_then(|v| v.as_str()).unwrap_or("-");
+    let autonomy = data
+        .pointer("/authority/autonomy")
+        .and_then(|v| v.as_str())
+        .unwrap_or("-");
+    let phase = stored_state(file)
+        .and_then(|s| s.current_phase)
+        .unwrap_or_else(|| "-".into());
+    Ok([
+        relative_name(dir, file),
+        name.into(),
+        autonomy.into(),
+        phase,
+    ])
+}
+
+fn relative_name(dir: &str, file: &str) -> String {
+    ampersona_core::list::relative_name(dir, std::path::Path::new(file))
+}
+
+/// Print one RFC 4180 record: fields containing `,`, `"` or line breaks are
+/// quoted with embedded quotes doubled; records end in CRLF.
+fn print_csv_row(fields: &[&str]) {
+    let row: Vec<String> = fields
+        .iter()
+        .map(|f| {
+            if f.contains([',', '"', '\r', '\n']) {
+                format!("\"{}\"", f.replace('"', "\"\""))
+            } else {
+                f.to_string()
+            }
+        })
+        .collect();
+    print!("{}\r\n", row.join(","));
 }
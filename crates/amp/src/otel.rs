@@ -0,0 +1,54 @@
+//! OTLP span export, enabled by the `otel` feature.
+//!
+//! When `AMPERSONA_OTEL_ENDPOINT` is set (e.g. `http://localhost:4318/v1/traces`),
+//! engine spans are exported over OTLP/HTTP. Unset means no exporter is built.
+
+use anyhow::{Context, Result};
+use opentelemetry::trace::TracerProvider as _;
+use opentelemetry_otlp::{SpanExporter, WithExportConfig};
+use opentelemetry_sdk::trace::SdkTracerProvider;
+use opentelemetry_sdk::Resource;
+use tracing::Subscriber;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::Layer;
+
+pub const ENDPOINT_ENV: &str = "AMPERSONA_OTEL_ENDPOINT";
+
+/// Flushes and shuts down the tracer provider on drop.
+pub struct Telemetry(SdkTracerProvider);
+
+impl Drop for Telemetry {
+    fn drop(&mut self) {
+        if let Err(e) = self.0.shutdown() {
+            tracing::warn!("otel shutdown failed: {e}");
+        }
+    }
+}
+
+impl Telemetry {
+    /// Layer forwarding spans to the exporter.
+    pub fn layer<S>(&self) -> impl Layer<S>
+    where
+        S: Subscriber + for<'a> LookupSpan<'a>,
+    {
+        tracing_opentelemetry::layer().with_tracer(self.0.tracer("ampersona"))
+    }
+}
+
+/// Build the OTLP exporter if `AMPERSONA_OTEL_ENDPOINT` is set.
+pub fn init() -> Result<Option<Telemetry>> {
+    let endpoint = match std::env::var(ENDPOINT_ENV) {
+        Ok(v) if !v.trim().is_empty() => v,
+        _ => return Ok(None),
+    };
+    let exporter = SpanExporter::builder()
+        .with_http()
+        .with_endpoint(endpoint)
+        .build()
+        .context("building OTLP span exporter")?;
+    let provider = SdkTracerProvider::builder()
+        .with_simple_exporter(exporter)
+        .with_resource(Resource::builder().with_service_name("amp").build())
+        .build();
+    Ok(Some(Telemetry(provider)))
+}
@@ -1,9 +1,9 @@
 //! Phase 5 conformance tests: CLI integration via JSON interface.
 
 mod test_helpers;
-use test_helpers::{amp_bin, amp_json, workspace_root};
+use test_helpers::{amp_bin, amp_json, amp_stdout, workspace_root};
 
-// ── Validation (3) ──────────────────────────────────────────────
+// ── Validation (7) ──────────────────────────────────────────────
 
 #[test]
 fn zeroclaw_check_passes() {
@@ -48,421 +48,4498 @@ fn odoov19_check_passes() {
     assert_eq!(v["pass"], true);
 }
 
-// ── Authority (7) ───────────────────────────────────────────────
+#[test]
+fn check_fix_adds_schema_and_version() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("agent.json");
+    let mut persona: serde_json::Value = serde_json::from_str(
+        &std::fs::read_to_string(workspace_root().join("examples/quiet_stone_v1.json")).unwrap(),
+    )
+    .unwrap();
+    let obj = persona.as_object_mut().unwrap();
+    obj.remove("$schema");
+    obj.remove("version");
+    std::fs::write(&path, persona.to_string()).unwrap();
+    let file = path.to_str().unwrap();
+
+    // Without --fix the file is reported as v0.2 and left alone
+    let v = amp_json(&["check", file, "--json"], 1);
+    let codes: Vec<_> = v["warnings"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|w| w["code"].as_str().unwrap())
+        .collect();
+    assert!(codes.contains(&"W030") && codes.contains(&"W031"), "{v}");
+    assert!(v.get("fixes").is_none());
+
+    let v = amp_json(&["check", file, "--fix", "--json"], 0);
+    assert_eq!(v["pass"], true, "{v}");
+    assert_eq!(v["version"], "1.0");
+    let fixed: Vec<_> = v["fixes"]["applied"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|f| f["code"].as_str().unwrap())
+        .collect();
+    assert_eq!(fixed, vec!["W031", "W030"]);
+
+    let written: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    assert_eq!(written["version"], "1.0");
+    assert_eq!(
+        written["$schema"],
+        "https://ampersona.dev/schema/v1.0/ampersona.schema.json"
+    );
+    assert_eq!(written["authority"], persona["authority"]);
+
+    // Second run has nothing left to fix
+    let v = amp_json(&["check", file, "--fix", "--json"], 0);
+    assert!(v["fixes"]["applied"].as_array().unwrap().is_empty());
+}
+
+#[test]
+fn strict_unknown_fields_flags_misspelled_authority() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("typo.json");
+    let mut data: serde_json::Value = serde_json::from_str(
+        &std::fs::read_to_string(workspace_root().join("examples/zeroclaw_agent.json")).unwrap(),
+    )
+    .unwrap();
+    data["autority"] = serde_json::json!({"autonomy": "full"});
+    // Open in the schema, so only the model check notices
+    data["gates"][0]["on_pass"] = serde_json::json!({"authority_overlay": {"autonomyy": "full"}});
+    std::fs::write(&path, data.to_string()).unwrap();
+    let file = path.to_str().unwrap();
+
+    let v = amp_json(&["check", file, "--strict-unknown-fields", "--json"], 1);
+    let mut unknown: Vec<&str> = v["errors"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|e| e["code"] == "E040")
+        .map(|e| e["path"].as_str().unwrap())
+        .collect();
+    unknown.sort_unstable();
+    assert_eq!(
+        unknown,
+        [
+            "$.autority",
+            "$.gates[0].on_pass.authority_overlay.autonomyy"
+        ]
+    );
+
+    let out = amp_bin()
+        .args(["validate", file, "--strict-unknown-fields"])
+        .output()
+        .unwrap();
+    assert_eq!(out.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&out.stderr).contains("unknown field 'autority'"));
+}
+
+#[test]
+fn validate_as_overlay_accepts_partial_documents() {
+    let dir = tempfile::tempdir().unwrap();
+    let overlay = dir.path().join("overlay.json");
+    std::fs::write(&overlay, r#"{"authority": {"autonomy": "readonly"}}"#).unwrap();
+    let overlay = overlay.to_str().unwrap();
+
+    let out = amp_bin().args(["validate", overlay]).output().unwrap();
+    assert_eq!(out.status.code(), Some(1));
+    let out = amp_bin()
+        .args(["validate", "--as-overlay", overlay])
+        .output()
+        .unwrap();
+    assert_eq!(out.status.code(), Some(0), "{out:?}");
+
+    let bad = dir.path().join("bad.json");
+    std::fs::write(&bad, r#"{"authority": {"autonomy": "root"}}"#).unwrap();
+    let out = amp_bin()
+        .args(["validate", "--as-overlay", bad.to_str().unwrap()])
+        .output()
+        .unwrap();
+    assert_eq!(out.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&out.stderr).contains("/authority/autonomy"));
+}
+
+#[test]
+fn check_json_compact_is_one_line() {
+    let run = |extra: &[&str]| {
+        let mut args = vec!["check", "examples/zeroclaw_agent.json", "--json"];
+        args.extend_from_slice(extra);
+        let out = amp_bin().args(&args).output().unwrap();
+        String::from_utf8(out.stdout).unwrap()
+    };
+    let pretty = run(&[]);
+    let compact = run(&["--compact"]);
+    assert!(pretty.trim_end().contains('\n'));
+    assert!(!compact.trim_end().contains('\n'), "{compact}");
+    assert_eq!(
+        serde_json::from_str::<serde_json::Value>(&compact).unwrap(),
+        serde_json::from_str::<serde_json::Value>(&pretty).unwrap()
+    );
+    // The last of --compact / --pretty wins
+    assert_eq!(run(&["--compact", "--pretty"]), pretty);
+}
+
+// ── Schema (1) ──────────────────────────────────────────────────
+
+#[test]
+fn schema_prints_embedded_validation_schema() {
+    for (version, file) in [
+        ("1.0", "ampersona-v1.0.schema.json"),
+        ("0.2", "ampersona-v0.2.schema.json"),
+    ] {
+        let printed = amp_stdout(&["schema", "--version", version]);
+        let embedded = std::fs::read_to_string(
+            workspace_root()
+                .join("crates/ampersona-core/schema")
+                .join(file),
+        )
+        .unwrap();
+        assert_eq!(
+            printed, embedded,
+            "v{version} differs from the embedded schema"
+        );
+        let schema: serde_json::Value = serde_json::from_str(&printed).unwrap();
+        assert_eq!(
+            schema["$schema"],
+            "https://json-schema.org/draft/2020-12/schema"
+        );
+        assert_eq!(schema["type"], "object");
+    }
+
+    let dir = tempfile::tempdir().unwrap();
+    let out = dir.path().join("persona.schema.json");
+    let status = amp_bin()
+        .args(["schema", "--out", out.to_str().unwrap()])
+        .status()
+        .unwrap();
+    assert!(status.success());
+    let written: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&out).unwrap()).unwrap();
+    assert_eq!(
+        written["$id"],
+        "https://ampersona.dev/schema/v1.0/ampersona.schema.json"
+    );
+}
+
+// ── Logging (2) ─────────────────────────────────────────────────
+
+#[test]
+fn log_format_and_level_control_stderr_only() {
+    let dir = tempfile::tempdir().unwrap();
+    let out = dir.path().join("persona.schema.json");
+    let schema_out = |extra: &[&str]| {
+        amp_bin()
+            .args(["schema", "--out", out.to_str().unwrap()])
+            .args(extra)
+            .output()
+            .unwrap()
+    };
+
+    let text = schema_out(&[]);
+    assert!(text.status.success());
+    assert!(text.stdout.is_empty());
+    assert_eq!(
+        String::from_utf8_lossy(&text.stderr).trim(),
+        format!("wrote {}", out.display())
+    );
+
+    let json = schema_out(&["--log-format", "json"]);
+    assert!(json.status.success());
+    assert!(json.stdout.is_empty());
+    let stderr = String::from_utf8_lossy(&json.stderr);
+    let event: serde_json::Value = serde_json::from_str(stderr.lines().next().unwrap()).unwrap();
+    assert_eq!(event["level"], "INFO");
+    assert_eq!(
+        event["fields"]["message"],
+        format!("wrote {}", out.display())
+    );
+
+    let quiet = schema_out(&["--log-level", "warn"]);
+    assert!(quiet.status.success());
+    assert!(quiet.stderr.is_empty());
+}
+
+#[test]
+fn json_log_format_covers_text_reports() {
+    let out = amp_bin()
+        .args([
+            "--log-format",
+            "json",
+            "status",
+            "examples/zeroclaw_agent.json",
+        ])
+        .current_dir(workspace_root())
+        .output()
+        .unwrap();
+    assert!(out.status.success());
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(stderr.contains("Autonomy:"), "{stderr}");
+    for line in stderr.lines() {
+        let event: serde_json::Value =
+            serde_json::from_str(line).unwrap_or_else(|e| panic!("not a JSON event: {line}: {e}"));
+        assert!(event["fields"]["message"].is_string(), "{line}");
+    }
+}
+
+// ── Prompt (2) ──────────────────────────────────────────────────
+
+#[test]
+fn prompt_with_state_reflects_phase() {
+    let dir = tempfile::tempdir().unwrap();
+    let persona_path = dir.path().join("agent.json");
+    let mut persona: serde_json::Value = serde_json::from_str(
+        &std::fs::read_to_string(workspace_root().join("examples/quiet_stone_v1.json")).unwrap(),
+    )
+    .unwrap();
+    persona["gates"]
+        .as_array_mut()
+        .unwrap()
+        .push(serde_json::json!({
+            "id": "probation",
+            "direction": "demote",
+            "from_phase": "active",
+            "to_phase": "probation",
+            "criteria": [{"metric": "policy_violations", "op": "gte", "value": 1}],
+            "on_pass": {
+                "prompt": "You are in probation; avoid destructive actions.",
+                "authority_overlay": {"autonomy": "readonly", "actions": {"deny": ["git_push"]}}
+            }
+        }));
+    std::fs::write(&persona_path, persona.to_string()).unwrap();
+    let file = persona_path.to_str().unwrap();
+
+    let prompt_in = |phase: &str, gate: &str, overlay: serde_json::Value| {
+        let state = serde_json::json!({
+            "name": "agent",
+            "current_phase": phase,
+            "state_rev": 1,
+            "last_transition": {
+                "gate_id": gate,
+                "from_phase": null,
+                "to_phase": phase,
+                "at": "2026-01-01T00:00:00Z",
+                "decision_id": "d-1",
+                "state_rev": 1
+            },
+            "active_overlay": overlay,
+            "updated_at": "2026-01-01T00:00:00Z"
+        });
+        std::fs::write(dir.path().join("agent.state.json"), state.to_string()).unwrap();
+        amp_stdout(&["prompt", file, "--with-state"])
+    };
+
+    let active = prompt_in("active", "onboarding", serde_json::Value::Null);
+    let probation = prompt_in(
+        "probation",
+        "probation",
+        persona["gates"][3]["on_pass"]["authority_overlay"].clone(),
+    );
+    assert_ne!(active, probation);
+
+    // No snippet declared for "active": generic line, persona authority
+    assert!(active.contains("**Phase:** active"));
+    assert!(active.contains("You are in the 'active' phase."));
+    assert!(active.contains("**Effective autonomy:** supervised"));
+
+    // Probation uses its snippet and the overlay-resolved authority
+    assert!(probation.contains("**Phase:** probation"));
+    assert!(probation.contains("You are in probation; avoid destructive actions."));
+    assert!(probation.contains("**Effective autonomy:** readonly"));
+    let denied = probation
+        .lines()
+        .find(|l| l.starts_with("**Denied actions:**"))
+        .unwrap();
+    assert!(denied.contains("git_push"));
+
+    // The static persona sections are unchanged
+    let plain = amp_stdout(&["prompt", file]);
+    assert!(!plain.contains("## Current State"));
+    assert!(active.contains(plain.lines().next().unwrap()));
+
+    // stdin has no sidecar to read
+    let out = amp_bin().args(["prompt", "--with-state"]).output().unwrap();
+    assert!(!out.status.success());
+    assert!(String::from_utf8_lossy(&out.stderr).contains(".state.json"));
+}
+
+#[test]
+fn verify_on_load_refuses_a_tampered_persona() {
+    let dir = tempfile::tempdir().unwrap();
+    let persona_path = dir.path().join("agent.json");
+    std::fs::copy(
+        workspace_root().join("examples/zeroclaw_agent.json"),
+        &persona_path,
+    )
+    .unwrap();
+    let persona = persona_path.to_str().unwrap();
+    let key_path = dir.path().join("sign.key");
+    std::fs::write(&key_path, [0x3Cu8; 32]).unwrap();
+    let pub_path = dir.path().join("sign.pub");
+    let public = ed25519_dalek::SigningKey::from_bytes(&[0x3Cu8; 32]).verifying_key();
+    std::fs::write(&pub_path, public.as_bytes()).unwrap();
+    let pubkey = pub_path.to_str().unwrap();
+    let prompt = |extra: &[&str]| {
+        amp_bin()
+            .args(["prompt", persona, "--verify-on-load", "--pubkey", pubkey])
+            .args(extra)
+            .output()
+            .unwrap()
+    };
+
+    // Unsigned: refused unless explicitly allowed
+    let out = prompt(&[]);
+    assert_eq!(out.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&out.stderr).contains("E031"));
+    assert!(prompt(&["--allow-unsigned"]).status.success());
+
+    amp_stdout(&["sign", persona, "--key", key_path.to_str().unwrap()]);
+    let out = prompt(&[]);
+    assert!(out.status.success());
+    assert!(!out.stdout.is_empty());
+
+    // An $extends base is another, unsigned file: refused whether the key was
+    // added after signing or signed along with the rest.
+    let signed = std::fs::read_to_string(&persona_path).unwrap();
+    std::fs::copy(
+        workspace_root().join("examples/zeroclaw_agent.json"),
+        dir.path().join("base.json"),
+    )
+    .unwrap();
+    let mut extended: serde_json::Value = serde_json::from_str(&signed).unwrap();
+    extended["$extends"] = serde_json::json!("base.json");
+    std::fs::write(&persona_path, extended.to_string()).unwrap();
+    let out = prompt(&[]);
+    assert_eq!(out.status.code(), Some(1));
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(
+        stderr.contains("field '$extends' is not covered"),
+        "{stderr}"
+    );
+    amp_stdout(&["sign", persona, "--key", key_path.to_str().unwrap()]);
+    let out = prompt(&[]);
+    assert_eq!(out.status.code(), Some(1));
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(stderr.contains("$extends base"), "{stderr}");
+    std::fs::write(&persona_path, &signed).unwrap();
+
+    let tampered =
+        std::fs::read_to_string(&persona_path)
+            .unwrap()
+            .replacen("\"full\"", "\"readonly\"", 1);
+    std::fs::write(&persona_path, tampered).unwrap();
+    let out = prompt(&["--allow-unsigned"]);
+    assert_eq!(out.status.code(), Some(1));
+    assert!(
+        out.stdout.is_empty(),
+        "nothing rendered from a tampered persona"
+    );
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(stderr.contains("E030"), "{stderr}");
+
+    // Without the flag the document still loads
+    assert!(amp_bin()
+        .args(["prompt", persona])
+        .output()
+        .unwrap()
+        .status
+        .success());
+}
+
+// ── Resolve (2) ─────────────────────────────────────────────────
+
+#[test]
+fn resolve_two_level_extends_chain() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::create_dir(dir.path().join("base")).unwrap();
+    std::fs::copy(
+        workspace_root().join("examples/quiet_stone_v1.json"),
+        dir.path().join("base/root.json"),
+    )
+    .unwrap();
+    std::fs::write(
+        dir.path().join("base/team.json"),
+        serde_json::json!({
+            "$extends": "root.json",
+            "role": "Platform Engineer",
+            "authority": {"autonomy": "readonly"}
+        })
+        .to_string(),
+    )
+    .unwrap();
+    let leaf = dir.path().join("leaf.json");
+    std::fs::write(
+        &leaf,
+        serde_json::json!({"$extends": "base/team.json", "name": "LeafStone"}).to_string(),
+    )
+    .unwrap();
+    let leaf = leaf.to_str().unwrap();
+
+    let resolved: serde_json::Value =
+        serde_json::from_str(&amp_stdout(&["resolve", leaf])).unwrap();
+    assert_eq!(resolved["name"], "LeafStone");
+    assert_eq!(resolved["role"], "Platform Engineer");
+    assert_eq!(resolved["authority"]["autonomy"], "readonly");
+    assert_eq!(resolved["authority"]["limits"]["max_actions_per_hour"], 50);
+    assert!(resolved.get("$extends").is_none());
+
+    // The leaf alone lacks required fields; validation sees the effective document
+    let out = amp_bin().args(["validate", leaf]).output().unwrap();
+    assert!(
+        out.status.success(),
+        "{}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    let check = amp_json(&["check", leaf, "--json"], 0);
+    assert_eq!(check["pass"], true);
+
+    // A cycle is reported, not followed
+    std::fs::write(
+        dir.path().join("base/root.json"),
+        serde_json::json!({"$extends": "team.json"}).to_string(),
+    )
+    .unwrap();
+    let out = amp_bin().args(["resolve", leaf]).output().unwrap();
+    assert!(!out.status.success());
+    assert!(String::from_utf8_lossy(&out.stderr).contains("$extends cycle"));
+}
+
+#[test]
+fn resolve_ref_external_gates_file() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::create_dir(dir.path().join("lib")).unwrap();
+    let mut persona: serde_json::Value = serde_json::from_str(
+        &std::fs::read_to_string(workspace_root().join("examples/quiet_stone_v1.json")).unwrap(),
+    )
+    .unwrap();
+    let gates = persona["gates"].clone();
+
+    // gates.json holds the library; its first entry is itself a ref
+    let mut library = gates.clone();
+    std::fs::write(
+        dir.path().join("lib/onboarding.json"),
+        library[0].to_string(),
+    )
+    .unwrap();
+    library[0] = serde_json::json!({"$ref": "onboarding.json"});
+    std::fs::write(dir.path().join("lib/gates.json"), library.to_string()).unwrap();
+
+    persona["gates"] = serde_json::json!({"$ref": "lib/gates.json"});
+    let path = dir.path().join("agent.json");
+    std::fs::write(&path, persona.to_string()).unwrap();
+    let file = path.to_str().unwrap();
+
+    let resolved: serde_json::Value =
+        serde_json::from_str(&amp_stdout(&["resolve", file])).unwrap();
+    assert_eq!(resolved["gates"], gates);
+
+    // Validation and prompts see the inlined document
+    let check = amp_json(&["check", file, "--json"], 0);
+    assert_eq!(check["errors"], serde_json::json!([]), "{check}");
+    let prompt = amp_stdout(&["prompt", file]);
+    assert!(prompt.contains("## Gates"));
+    assert!(prompt.contains("trust_decay"));
+
+    // --no-deref keeps the ref for editing
+    let literal: serde_json::Value =
+        serde_json::from_str(&amp_stdout(&["resolve", file, "--no-deref"])).unwrap();
+    assert_eq!(
+        literal["gates"],
+        serde_json::json!({"$ref": "lib/gates.json"})
+    );
+
+    // A ref back to the persona is a cycle
+    std::fs::write(
+        dir.path().join("lib/gates.json"),
+        serde_json::json!([{"$ref": "../agent.json"}]).to_string(),
+    )
+    .unwrap();
+    let out = amp_bin().args(["check", file]).output().unwrap();
+    assert!(!out.status.success());
+    assert!(String::from_utf8_lossy(&out.stderr).contains("$ref cycle"));
+}
+
+// ── Canonicalize (4) ────────────────────────────────────────────
+
+#[test]
+fn canonicalize_ignores_key_order() {
+    fn reversed(v: &serde_json::Value) -> serde_json::Value {
+        match v {
+            serde_json::Value::Object(obj) => serde_json::Value::Object(
+                obj.iter()
+                    .rev()
+                    .map(|(k, v)| (k.clone(), reversed(v)))
+                    .collect(),
+            ),
+            serde_json::Value::Array(items) => {
+                serde_json::Value::Array(items.iter().map(reversed).collect())
+            }
+            other => other.clone(),
+        }
+    }
+
+    let original = workspace_root().join("examples/quiet_stone_v1.json");
+    let data: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&original).unwrap()).unwrap();
+    let dir = tempfile::tempdir().unwrap();
+    let shuffled = dir.path().join("shuffled.json");
+    std::fs::write(&shuffled, serde_json::to_string(&reversed(&data)).unwrap()).unwrap();
+
+    let a = amp_stdout(&["canonicalize", original.to_str().unwrap()]);
+    let b = amp_stdout(&["canonicalize", shuffled.to_str().unwrap()]);
+    assert_eq!(a, b);
+    assert!(a.starts_with("{\"$schema\":"), "{a}");
+
+    // compose --canonical is the same document as compose, in one line
+    let args = [shuffled.to_str().unwrap(), original.to_str().unwrap()];
+    let pretty = amp_stdout(&["compose", args[0], args[1]]);
+    let canonical = amp_stdout(&["compose", "--canonical", args[0], args[1]]);
+    assert_eq!(canonical.lines().count(), 1);
+    assert_eq!(
+        serde_json::from_str::<serde_json::Value>(&canonical).unwrap(),
+        serde_json::from_str::<serde_json::Value>(&pretty).unwrap()
+    );
+}
+
+#[test]
+fn fingerprint_ignores_order_but_not_content() {
+    let dir = tempfile::tempdir().unwrap();
+    let a = dir.path().join("a.json");
+    let b = dir.path().join("b.json");
+    std::fs::write(
+        &a,
+        r#"{"version": "1.0", "name": "Ada", "role": "architect"}"#,
+    )
+    .unwrap();
+    std::fs::write(
+        &b,
+        r#"{"role": "architect", "name": "Ada", "version": "1.0"}"#,
+    )
+    .unwrap();
+
+    let fp = |path: &std::path::Path| amp_stdout(&["fingerprint", path.to_str().unwrap()]);
+    let original = fp(&a);
+    assert!(original.trim().starts_with("sha256:"), "{original}");
+    assert_eq!(original, fp(&b));
+
+    std::fs::write(
+        &b,
+        r#"{"role": "reviewer", "name": "Ada", "version": "1.0"}"#,
+    )
+    .unwrap();
+    assert_ne!(original, fp(&b));
+
+    let v = amp_json(&["fingerprint", a.to_str().unwrap(), "--json"], 0);
+    assert_eq!(v["fingerprint"], original.trim());
+    assert_eq!(v["name"], "Ada");
+    assert_eq!(v["version"], "1.0");
+}
+
+#[test]
+fn verify_expected_fingerprint_pins_content() {
+    let dir = tempfile::tempdir().unwrap();
+    let persona_path = dir.path().join("agent.json");
+    std::fs::copy(
+        workspace_root().join("examples/zeroclaw_agent.json"),
+        &persona_path,
+    )
+    .unwrap();
+    let persona = persona_path.to_str().unwrap();
+    let key_path = dir.path().join("sign.key");
+    std::fs::write(&key_path, [0x3Cu8; 32]).unwrap();
+    let pub_path = dir.path().join("sign.pub");
+    let public = ed25519_dalek::SigningKey::from_bytes(&[0x3Cu8; 32]).verifying_key();
+    std::fs::write(&pub_path, public.as_bytes()).unwrap();
+    let pubkey = pub_path.to_str().unwrap();
+
+    let pin = amp_stdout(&["fingerprint", persona]).trim().to_string();
+    let verify = |extra: &[&str]| {
+        amp_bin()
+            .args(["verify", persona, "--expected-fingerprint", &pin])
+            .args(extra)
+            .output()
+            .unwrap()
+    };
+    // No key needed; signing does not change the fingerprint
+    assert!(verify(&[]).status.success());
+    amp_stdout(&["sign", persona, "--key", key_path.to_str().unwrap()]);
+    assert!(verify(&["--pubkey", pubkey]).status.success());
+
+    let tampered =
+        std::fs::read_to_string(&persona_path)
+            .unwrap()
+            .replacen("\"full\"", "\"readonly\"", 1);
+    std::fs::write(&persona_path, tampered).unwrap();
+    let out = verify(&[]);
+    assert_eq!(out.status.code(), Some(1));
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(stderr.contains("fingerprint mismatch"), "{stderr}");
+
+    let v = amp_json(
+        &[
+            "verify",
+            persona,
+            "--pubkey",
+            pubkey,
+            "--all-sidecars",
+            "--expected-fingerprint",
+            &pin,
+        ],
+        1,
+    );
+    assert_eq!(v["checks"]["fingerprint"]["valid"], false);
+    assert_eq!(v["checks"]["fingerprint"]["expected"], pin.as_str());
+}
+
+#[test]
+fn verify_keyring_selects_key_by_key_id() {
+    let dir = tempfile::tempdir().unwrap();
+    let keyring = dir.path().join("keys");
+    std::fs::create_dir(&keyring).unwrap();
+    for (key_id, seed) in [("ops-2025", 0x25u8), ("ops-2026", 0x26u8)] {
+        std::fs::write(dir.path().join(format!("{key_id}.key")), [seed; 32]).unwrap();
+        let public = ed25519_dalek::SigningKey::from_bytes(&[seed; 32]).verifying_key();
+        std::fs::write(keyring.join(format!("{key_id}.pub")), public.as_bytes()).unwrap();
+    }
+    let keyring = keyring.to_str().unwrap();
+    let persona_path = dir.path().join("agent.json");
+    let persona = persona_path.to_str().unwrap();
+    let verify = || {
+        amp_bin()
+            .args(["verify", persona, "--keyring", keyring])
+            .output()
+            .unwrap()
+    };
+
+    // Each rotation is verified with its own key, picked by key_id
+    for key_id in ["ops-2025", "ops-2026"] {
+        std::fs::copy(
+            workspace_root().join("examples/zeroclaw_agent.json"),
+            &persona_path,
+        )
+        .unwrap();
+        let key = dir.path().join(format!("{key_id}.key"));
+        amp_stdout(&[
+            "sign",
+            persona,
+            "--key",
+            key.to_str().unwrap(),
+            "--key-id",
+            key_id,
+        ]);
+        let out = verify();
+        assert!(
+            out.status.success(),
+            "{key_id}: {}",
+            String::from_utf8_lossy(&out.stderr)
+        );
+    }
+
+    // A key_id the keyring does not hold is reported as such
+    amp_stdout(&[
+        "sign",
+        persona,
+        "--key",
+        dir.path().join("ops-2025.key").to_str().unwrap(),
+        "--key-id",
+        "ops-2027",
+    ]);
+    let out = verify();
+    assert_eq!(out.status.code(), Some(1));
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(
+        stderr.contains("no public key for key_id 'ops-2027'"),
+        "{stderr}"
+    );
+}
+
+// ── Diff (2) ────────────────────────────────────────────────────
+
+#[test]
+fn semantic_diff_ignores_goal_order() {
+    let original = workspace_root().join("examples/quiet_stone_v1.json");
+    let mut data: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&original).unwrap()).unwrap();
+    let goals = data["directives"]["goals"].as_array_mut().unwrap();
+    assert!(goals.len() > 1);
+    goals.reverse();
+    let dir = tempfile::tempdir().unwrap();
+    let reordered = dir.path().join("reordered.json");
+    std::fs::write(&reordered, serde_json::to_string_pretty(&data).unwrap()).unwrap();
+    let args = [original.to_str().unwrap(), reordered.to_str().unwrap()];
+
+    assert!(!amp_stdout(&["diff", args[0], args[1]]).is_empty());
+    assert_eq!(amp_stdout(&["diff", "--semantic", args[0], args[1]]), "");
+
+    // A real change still shows up
+    data["directives"]["goals"][0] = serde_json::json!("something new");
+    std::fs::write(&reordered, serde_json::to_string_pretty(&data).unwrap()).unwrap();
+    let diff = amp_stdout(&["diff", "--semantic", args[0], args[1]]);
+    assert!(diff.contains("+ directives.goals"), "{diff}");
+}
+
+#[test]
+fn eq_accepts_reordered_equal_personas() {
+    let original = workspace_root().join("examples/quiet_stone_v1.json");
+    let mut data: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&original).unwrap()).unwrap();
+    data["directives"]["goals"]
+        .as_array_mut()
+        .unwrap()
+        .reverse();
+    // Compact, unlike the pretty-printed original
+    let dir = tempfile::tempdir().unwrap();
+    let reordered = dir.path().join("reordered.json");
+    std::fs::write(&reordered, data.to_string()).unwrap();
+    let (a, b) = (original.to_str().unwrap(), reordered.to_str().unwrap());
+
+    let out = amp_bin().args(["eq", a, b]).output().unwrap();
+    assert_eq!(out.status.code(), Some(0));
+    assert!(out.stdout.is_empty());
+
+    data["role"] = "Someone else".into();
+    std::fs::write(&reordered, data.to_string()).unwrap();
+    let out = amp_bin().args(["eq", a, b]).output().unwrap();
+    assert_eq!(out.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&out.stdout).contains("+ role: \"Someone else\""));
+}
+
+// ── Compose (1) ─────────────────────────────────────────────────
+
+#[test]
+fn compose_writes_validated_output() {
+    let dir = tempfile::tempdir().unwrap();
+    let base_path = dir.path().join("base.json");
+    std::fs::copy(
+        workspace_root().join("examples/quiet_stone_v1.json"),
+        &base_path,
+    )
+    .unwrap();
+    let overlay_path = dir.path().join("overlay.json");
+    std::fs::write(
+        &overlay_path,
+        serde_json::json!({"role": "Release Engineer", "authority": {"autonomy": "readonly"}})
+            .to_string(),
+    )
+    .unwrap();
+    let (base, overlay) = (base_path.to_str().unwrap(), overlay_path.to_str().unwrap());
+
+    let out_path = dir.path().join("merged.json");
+    let out = out_path.to_str().unwrap();
+    amp_stdout(&["compose", base, overlay, "-o", out]);
+    let merged: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&out_path).unwrap()).unwrap();
+    assert_eq!(merged["role"], "Release Engineer");
+    assert_eq!(merged["authority"]["autonomy"], "readonly");
+    let v = amp_json(&["check", out, "--json"], 0);
+    assert_eq!(v["errors"].as_array().unwrap().len(), 0);
+
+    // An overlay that breaks the schema is refused unless --force
+    std::fs::write(&overlay_path, serde_json::json!({"name": 42}).to_string()).unwrap();
+    let bad = dir.path().join("bad.json");
+    let status = amp_bin()
+        .args(["compose", base, overlay, "-o", bad.to_str().unwrap()])
+        .output()
+        .unwrap()
+        .status;
+    assert_eq!(status.code(), Some(1));
+    assert!(!bad.exists());
+    amp_stdout(&[
+        "compose",
+        base,
+        overlay,
+        "-o",
+        bad.to_str().unwrap(),
+        "--force",
+    ]);
+    assert!(bad.exists());
+
+    // --write replaces the base and keeps a backup
+    let original = std::fs::read_to_string(&base_path).unwrap();
+    amp_stdout(&["compose", base, out, "--write"]);
+    assert_eq!(
+        std::fs::read_to_string(dir.path().join("base.bak")).unwrap(),
+        original
+    );
+    let written: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&base_path).unwrap()).unwrap();
+    assert_eq!(written["role"], "Release Engineer");
+}
+
+// ── Redact (1) ──────────────────────────────────────────────────
+
+#[test]
+fn redact_backstory_and_signature() {
+    let dir = tempfile::tempdir().unwrap();
+    let persona_path = dir.path().join("agent.json");
+    std::fs::copy(
+        workspace_root().join("examples/quiet_stone_v1.json"),
+        &persona_path,
+    )
+    .unwrap();
+    let persona = persona_path.to_str().unwrap();
+    let key_path = dir.path().join("sign.key");
+    std::fs::write(&key_path, [0x7Bu8; 32]).unwrap();
+    amp_stdout(&["sign", persona, "--key", key_path.to_str().unwrap()]);
+
+    let masked: serde_json::Value = serde_json::from_str(&amp_stdout(&[
+        "redact",
+        persona,
+        "--fields",
+        "backstory,signature",
+    ]))
+    .unwrap();
+    assert_eq!(masked["backstory"], "***");
+    assert_eq!(masked["signature"]["value"], "***");
+    assert_eq!(masked["name"], "QuietStone");
+
+    let out = dir.path().join("shared.json");
+    let out = out.to_str().unwrap();
+    amp_stdout(&[
+        "redact",
+        persona,
+        "--fields",
+        "backstory,signature,role",
+        "--remove",
+        "-o",
+        out,
+    ]);
+    let removed: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(out).unwrap()).unwrap();
+    assert!(removed.get("backstory").is_none());
+    assert!(removed.get("signature").is_none());
+    // Required fields are masked rather than removed
+    assert_eq!(removed["role"], "***");
+    let v = amp_json(&["check", out, "--json"], 0);
+    assert_eq!(v["errors"].as_array().unwrap().len(), 0);
+}
+
+// ── Authority (15) ──────────────────────────────────────────────
+
+#[test]
+fn zeroclaw_authority_allow() {
+    let v = amp_json(
+        &[
+            "authority",
+            "examples/zeroclaw_agent.json",
+            "--check",
+            "read_file",
+            "--json",
+        ],
+        0,
+    );
+    assert_eq!(v["decision"], "Allow");
+    assert_eq!(v["action"], "read_file");
+}
+
+#[test]
+fn zeroclaw_authority_deny_unknown() {
+    let v = amp_json(
+        &[
+            "authority",
+            "examples/zeroclaw_agent.json",
+            "--check",
+            "unknown_action",
+            "--json",
+        ],
+        1,
+    );
+    assert_eq!(v["decision"], "Deny");
+}
+
+#[test]
+fn zeroclaw_scoped_shell_blocked() {
+    let v = amp_json(
+        &[
+            "authority",
+            "examples/zeroclaw_agent.json",
+            "--check",
+            "run_command",
+            "--context",
+            "command=echo $(whoami)",
+            "--json",
+        ],
+        1,
+    );
+    assert_eq!(v["decision"], "Deny");
+}
+
+#[test]
+fn authority_path_scope_forbidden() {
+    let v = amp_json(
+        &[
+            "authority",
+            "examples/zeroclaw_agent.json",
+            "--check",
+            "read_file",
+            "--path",
+            "secrets/key.pem",
+            "--json",
+        ],
+        1,
+    );
+    assert_eq!(v["decision"], "Deny");
+}
+
+#[test]
+fn odoov19_deny_with_compliance_ref() {
+    let v = amp_json(
+        &[
+            "authority",
+            "examples/odoov19_quality.json",
+            "--check",
+            "auto_approve_capa",
+            "--json",
+        ],
+        1,
+    );
+    assert_eq!(v["decision"], "Deny");
+    assert!(v["deny_entry"]["compliance_ref"]
+        .as_str()
+        .unwrap()
+        .contains("ISO 9001"));
+}
+
+#[test]
+fn agent_mail_authority_allow() {
+    let v = amp_json(
+        &[
+            "authority",
+            "examples/agent_mail_worker.json",
+            "--check",
+            "send_message",
+            "--json",
+        ],
+        0,
+    );
+    assert_eq!(v["decision"], "Allow");
+}
+
+#[test]
+fn authority_exit_code_needs_approval() {
+    // quiet_stone_v1 has supervised autonomy + require_approval_for
+    let v = amp_json(
+        &[
+            "authority",
+            "examples/quiet_stone_v1.json",
+            "--check",
+            "read_file",
+            "--json",
+        ],
+        2,
+    );
+    assert_eq!(v["decision"], "NeedsApproval");
+}
+
+#[test]
+fn authority_unknown_action_flagged_in_vocab() {
+    let v = amp_json(
+        &[
+            "authority",
+            "examples/zeroclaw_agent.json",
+            "--check",
+            "read_fil",
+            "--json",
+        ],
+        1,
+    );
+    assert_eq!(v["decision"], "Deny");
+    assert_eq!(v["vocab"], "unknown");
+    assert_eq!(v["suggestion"], "read_file");
+
+    let known = amp_json(
+        &[
+            "authority",
+            "examples/zeroclaw_agent.json",
+            "--check",
+            "deploy",
+            "--json",
+        ],
+        1,
+    );
+    assert_eq!(known["vocab"], "builtin");
+    assert!(known.get("suggestion").is_none());
+}
+
+#[test]
+fn authority_strict_vocab_rejects_unknown() {
+    let v = amp_json(
+        &[
+            "authority",
+            "examples/zeroclaw_agent.json",
+            "--check",
+            "unknown_action",
+            "--strict-vocab",
+            "--json",
+        ],
+        3,
+    );
+    assert_eq!(v["code"], "E_UNKNOWN_ACTION");
+
+    let custom = amp_json(
+        &[
+            "authority",
+            "examples/zeroclaw_agent.json",
+            "--check",
+            "custom:acme/launch",
+            "--strict-vocab",
+            "--json",
+        ],
+        1,
+    );
+    assert_eq!(custom["vocab"], "custom");
+}
+
+#[test]
+fn authority_expand_env_in_allowed_paths() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::create_dir(dir.path().join("src")).unwrap();
+    let root = dir.path().to_str().unwrap();
+    let mut persona: serde_json::Value = serde_json::from_str(
+        &std::fs::read_to_string(workspace_root().join("examples/quiet_stone_v1.json")).unwrap(),
+    )
+    .unwrap();
+    persona["authority"]["autonomy"] = serde_json::json!("full");
+    persona["authority"]["scope"]["allowed_paths"] =
+        serde_json::json!(["${PROJECT_ROOT}/src/**", "${DOCS_DIR:-docs}/**"]);
+    let persona_path = dir.path().join("agent.json");
+    std::fs::write(&persona_path, persona.to_string()).unwrap();
+    let file = persona_path.to_str().unwrap();
+    let target = format!("{root}/src/main.rs");
+
+    let run = |extra: &[&str], project_root: Option<&str>| {
+        let mut cmd = amp_bin();
+        cmd.args([
+            "authority",
+            file,
+            "--check",
+            "write_file",
+            "--path",
+            &target,
+        ])
+        .args(["--json"])
+        .args(extra)
+        .env_remove("DOCS_DIR");
+        match project_root {
+            Some(v) => cmd.env("PROJECT_ROOT", v),
+            None => cmd.env_remove("PROJECT_ROOT"),
+        };
+        let out = cmd.output().unwrap();
+        let v: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+        (out.status.code(), v)
+    };
+
+    // Off by default: the placeholder is matched literally
+    let (code, v) = run(&[], Some(root));
+    assert_eq!(code, Some(1));
+    assert_eq!(v["decision"], "Deny");
+
+    let (code, v) = run(&["--expand-env"], Some(root));
+    assert_eq!(code, Some(0), "{v}");
+    assert_eq!(v["decision"], "Allow");
+
+    // Undefined without a default is an error, not an empty string
+    let (code, v) = run(&["--expand-env"], None);
+    assert_eq!(code, Some(3));
+    assert_eq!(v["code"], "E_ENV_EXPANSION");
+    assert!(v["message"].as_str().unwrap().contains("PROJECT_ROOT"));
+
+    let resolved: serde_json::Value = serde_json::from_slice(
+        &amp_bin()
+            .args(["resolve", file, "--expand-env"])
+            .env("PROJECT_ROOT", root)
+            .env_remove("DOCS_DIR")
+            .output()
+            .unwrap()
+            .stdout,
+    )
+    .unwrap();
+    assert_eq!(
+        resolved["authority"]["scope"]["allowed_paths"],
+        serde_json::json!([format!("{root}/src/**"), "docs/**"])
+    );
+    assert_eq!(resolved["authority"]["limits"]["max_actions_per_hour"], 50);
+}
+
+#[test]
+fn authority_deny_review_and_expiry() {
+    let dir = tempfile::tempdir().unwrap();
+    let persona_path = dir.path().join("zeroclaw_agent.json");
+    let mut persona: serde_json::Value = serde_json::from_str(
+        &std::fs::read_to_string(workspace_root().join("examples/zeroclaw_agent.json")).unwrap(),
+    )
+    .unwrap();
+    let dated = |action: &str, field: &str, ts: &str| {
+        serde_json::json!({
+            "action": action,
+            "reason": "CAPA hold",
+            "compliance_ref": "ISO 9001:2015 §10.2",
+            field: ts,
+        })
+    };
+    persona["authority"]["actions"]["deny"] = serde_json::json!([
+        dated("write_file", "review_by", "2999-01-01T00:00:00Z"),
+        dated("run_tests", "review_by", "2000-01-01T00:00:00Z"),
+        dated("read_file", "expires_at", "2000-01-01T00:00:00Z"),
+    ]);
+    std::fs::write(&persona_path, persona.to_string()).unwrap();
+    let file = persona_path.to_str().unwrap();
+
+    // Active deny
+    let v = amp_json(&["authority", file, "--check", "write_file", "--json"], 1);
+    assert_eq!(v["deny_entry"]["review_overdue"], false);
+
+    // Review overdue: still denied, flagged; an error under --strict-review
+    let v = amp_json(&["authority", file, "--check", "run_tests", "--json"], 1);
+    assert_eq!(v["decision"], "Deny");
+    assert_eq!(v["deny_entry"]["review_overdue"], true);
+    assert!(v["reason"].as_str().unwrap().contains("past review date"));
+    let v = amp_json(
+        &[
+            "authority",
+            file,
+            "--check",
+            "run_tests",
+            "--strict-review",
+            "--json",
+        ],
+        3,
+    );
+    assert_eq!(v["code"], "E_DENY_REVIEW_OVERDUE");
+
+    // Expired: no longer in force
+    let v = amp_json(&["authority", file, "--check", "read_file", "--json"], 0);
+    assert_eq!(v["decision"], "Allow");
+
+    let v = amp_json(&["check", file, "--json"], 0);
+    let codes: Vec<&str> = v["warnings"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter_map(|w| w["code"].as_str())
+        .filter(|c| *c == "W003" || *c == "W004")
+        .collect();
+    assert_eq!(codes, ["W003", "W004"]);
+}
+
+#[test]
+fn authority_matrix_readonly_denies_everything() {
+    let dir = tempfile::tempdir().unwrap();
+    let persona_path = dir.path().join("zeroclaw_agent.json");
+    let mut persona: serde_json::Value = serde_json::from_str(
+        &std::fs::read_to_string(workspace_root().join("examples/zeroclaw_agent.json")).unwrap(),
+    )
+    .unwrap();
+    persona["authority"]["autonomy"] = "readonly".into();
+    persona["authority"]["actions"]["deny"] =
+        serde_json::json!(["git_push_main", "custom:zeroclaw/sandbox_escape"]);
+    std::fs::write(&persona_path, persona.to_string()).unwrap();
+    let file = persona_path.to_str().unwrap();
+
+    let v = amp_json(&["authority", file, "--matrix", "--json"], 0);
+    assert_eq!(v["autonomy"], "readonly");
+    let matrix = v["matrix"].as_array().unwrap();
+    // One evaluation per distinct action: all misses
+    assert_eq!(v["cache"]["hits"], 0);
+    assert_eq!(v["cache"]["misses"], matrix.len());
+    let decision = |action: &str| {
+        matrix
+            .iter()
+            .find(|r| r["action"] == action)
+            .unwrap_or_else(|| panic!("{action} missing from matrix"))["decision"]
+            .clone()
+    };
+    assert!(matrix.iter().all(|r| r["decision"] == "Deny"));
+    assert_eq!(decision("write_file"), "Deny");
+    assert_eq!(decision("delete_file"), "Deny");
+    // Custom actions the persona references are part of the matrix
+    assert_eq!(decision("custom:zeroclaw/sandbox_escape"), "Deny");
+
+    // Same persona with full autonomy: the allow list decides
+    persona["authority"]["autonomy"] = "full".into();
+    std::fs::write(&persona_path, persona.to_string()).unwrap();
+    let text = amp_stdout(&["authority", file, "--matrix"]);
+    let row = |action: &str| {
+        text.lines()
+            .find(|l| l.split_whitespace().next() == Some(action))
+            .unwrap_or_else(|| panic!("{action} missing from table"))
+            .to_string()
+    };
+    assert!(row("write_file").contains("Allow"));
+    assert!(row("deploy").contains("Deny"));
+}
+
+#[test]
+fn authority_follows_declared_phase_without_overlay() {
+    let dir = tempfile::tempdir().unwrap();
+    let persona_path = dir.path().join("agent.json");
+    let mut persona: serde_json::Value = serde_json::from_str(
+        &std::fs::read_to_string(workspace_root().join("examples/zeroclaw_agent.json")).unwrap(),
+    )
+    .unwrap();
+    persona["authority"]["autonomy"] = "full".into();
+    persona["phases"] = serde_json::json!({
+        "active": {},
+        "trusted": {},
+        "probation": { "authority": { "autonomy": "readonly" } }
+    });
+    std::fs::write(&persona_path, persona.to_string()).unwrap();
+    let file = persona_path.to_str().unwrap();
+
+    let enter = |phase: &str| {
+        let state = serde_json::json!({
+            "name": "agent",
+            "current_phase": phase,
+            "state_rev": 1,
+            "active_overlay": null,
+            "updated_at": "2026-01-01T00:00:00Z"
+        });
+        std::fs::write(dir.path().join("agent.state.json"), state.to_string()).unwrap();
+    };
+
+    enter("trusted");
+    let v = amp_json(&["authority", file, "--matrix", "--json"], 0);
+    assert_eq!(v["autonomy"], "full");
+    let v = amp_json(&["authority", file, "--check", "read_file", "--json"], 0);
+    assert_eq!(v["decision"], "Allow");
+
+    enter("probation");
+    let v = amp_json(&["authority", file, "--matrix", "--json"], 0);
+    assert_eq!(v["autonomy"], "readonly");
+    let v = amp_json(&["authority", file, "--check", "read_file", "--json"], 1);
+    assert_eq!(v["decision"], "Deny");
+}
+
+#[test]
+fn authority_show_overlay_effect_reports_override() {
+    let dir = tempfile::tempdir().unwrap();
+    let persona_path = dir.path().join("agent.json");
+    let mut persona: serde_json::Value = serde_json::from_str(
+        &std::fs::read_to_string(workspace_root().join("examples/zeroclaw_agent.json")).unwrap(),
+    )
+    .unwrap();
+    persona["authority"]["autonomy"] = "supervised".into();
+    std::fs::write(&persona_path, persona.to_string()).unwrap();
+    let file = persona_path.to_str().unwrap();
+
+    let none = amp_stdout(&["authority", file, "--show-overlay-effect"]);
+    assert!(none.contains("no active overlay"), "{none}");
+
+    let state = serde_json::json!({
+        "name": "agent",
+        "current_phase": "trusted",
+        "state_rev": 1,
+        "last_transition": {
+            "gate_id": "trusted",
+            "from_phase": "active",
+            "to_phase": "trusted",
+            "at": "2026-01-01T00:00:00Z",
+            "decision_id": "d-1",
+            "state_rev": 1
+        },
+        "active_overlay": {"autonomy": "full"},
+        "updated_at": "2026-01-01T00:00:00Z"
+    });
+    std::fs::write(dir.path().join("agent.state.json"), state.to_string()).unwrap();
+
+    let text = amp_stdout(&["authority", file, "--show-overlay-effect"]);
+    assert!(text.contains("overlay from gate trusted"), "{text}");
+    assert!(
+        text.contains(r#"autonomy         "supervised" -> "full""#),
+        "{text}"
+    );
+
+    let v: serde_json::Value = serde_json::from_str(&amp_stdout(&[
+        "authority",
+        file,
+        "--show-overlay-effect",
+        "--json",
+    ]))
+    .unwrap();
+    assert_eq!(
+        v["changes"],
+        serde_json::json!([{"field": "autonomy", "before": "supervised", "after": "full"}])
+    );
+}
+
+#[test]
+fn authority_what_if_reports_flipped_decisions() {
+    let dir = tempfile::tempdir().unwrap();
+    let persona_path = dir.path().join("zeroclaw_agent.json");
+    std::fs::copy(
+        workspace_root().join("examples/zeroclaw_agent.json"),
+        &persona_path,
+    )
+    .unwrap();
+    let file = persona_path.to_str().unwrap();
+    let overlay_path = dir.path().join("overlay.json");
+    let overlay = overlay_path.to_str().unwrap();
+
+    std::fs::write(&overlay_path, r#"{"actions": {"deny": ["write_file"]}}"#).unwrap();
+    let v = amp_json(&["authority", file, "--what-if", overlay, "--json"], 0);
+    let changes = v["changes"].as_array().unwrap();
+    assert_eq!(changes.len(), 1, "{changes:?}");
+    assert_eq!(changes[0]["action"], "write_file");
+    assert_eq!(changes[0]["before"], "Allow");
+    assert_eq!(changes[0]["after"], "Deny");
+    // Read-only: no state or audit is created
+    assert!(!dir.path().join("zeroclaw_agent.state.json").exists());
+
+    std::fs::write(&overlay_path, "{}").unwrap();
+    let text = amp_stdout(&["authority", file, "--what-if", overlay]);
+    assert!(text.contains("no decision changes"), "{text}");
+}
+
+// ── Actions (1) ─────────────────────────────────────────────────
+
+#[test]
+fn actions_catalog_lists_builtin_and_referenced_custom() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("agent.json");
+    let persona = serde_json::json!({
+        "version": "1.0",
+        "name": "CatalogAgent",
+        "role": "test",
+        "authority": {
+            "autonomy": "full",
+            "actions": {
+                "allow": ["read_file", "custom:acme/launch"],
+                "deny": ["read_fil"]
+            }
+        }
+    });
+    std::fs::write(&path, serde_json::to_string_pretty(&persona).unwrap()).unwrap();
+
+    let v = amp_json(&["actions", "--file", path.to_str().unwrap(), "--json"], 0);
+    let builtin = v["builtin"].as_array().unwrap();
+    assert!(builtin
+        .iter()
+        .any(|b| b["action"] == "read_file" && b["namespace"] == "builtin"));
+    let custom = v["custom"].as_array().unwrap();
+    assert_eq!(custom.len(), 2);
+    assert_eq!(custom[0]["vendor"], "acme");
+    assert_eq!(custom[0]["list"], "allow");
+    assert_eq!(custom[1]["known"], false);
+    assert_eq!(custom[1]["suggestion"], "read_file");
+}
+
+// ── Elevate (1) ─────────────────────────────────────────────────
+
+#[test]
+fn elevate_ttl_override_and_cap() {
+    let dir = tempfile::tempdir().unwrap();
+    let persona_path = dir.path().join("agent.json");
+    let mut persona: serde_json::Value = serde_json::from_str(
+        &std::fs::read_to_string(workspace_root().join("examples/quiet_stone_v1.json")).unwrap(),
+    )
+    .unwrap();
+    std::fs::write(&persona_path, persona.to_string()).unwrap();
+    let file = persona_path.to_str().unwrap();
+    let elevate = |ttl: &str| {
+        amp_bin()
+            .args(["elevate", file, "--elevation", "release-deploy"])
+            .args(["--reason", "hotfix", "--ttl", ttl])
+            .output()
+            .unwrap()
+    };
+    let last_ttl = || {
+        let audit = std::fs::read_to_string(dir.path().join("agent.audit.jsonl")).unwrap();
+        let entry: serde_json::Value = serde_json::from_str(audit.lines().last().unwrap()).unwrap();
+        (
+            entry["ttl_seconds"].clone(),
+            entry["ttl_requested_seconds"].clone(),
+        )
+    };
+
+    assert!(elevate("90m").status.success());
+    assert_eq!(
+        last_ttl(),
+        (serde_json::json!(5400), serde_json::json!(5400))
+    );
+
+    // Declared cap wins over a longer request
+    persona["authority"]["elevations"][0]["max_ttl_seconds"] = serde_json::json!(3600);
+    std::fs::write(&persona_path, persona.to_string()).unwrap();
+    let out = elevate("2h30m");
+    assert!(out.status.success());
+    assert!(String::from_utf8_lossy(&out.stderr).contains("capped"));
+    assert_eq!(
+        last_ttl(),
+        (serde_json::json!(3600), serde_json::json!(9000))
+    );
+
+    // Zero and negative durations are rejected before anything is written
+    assert!(!elevate("0s").status.success());
+    assert!(!elevate("-5m").status.success());
+}
+
+// ── Metrics (1) ─────────────────────────────────────────────────
+
+#[test]
+fn metrics_template_scaffolds_typed_placeholders() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("agent.json");
+    let persona = serde_json::json!({
+        "version": "1.0",
+        "name": "MetricsAgent",
+        "role": "test",
+        "gates": [{
+            "id": "promote",
+            "direction": "promote",
+            "from_phase": null,
+            "to_phase": "active",
+            "criteria": [
+                {"metric": "tasks_completed", "op": "gte", "value": 10},
+                {"metric": "error_rate", "op": "lte", "value": 0},
+                {"metric": "schema_valid", "op": "eq", "value": true},
+                {"metric": "tier", "op": "eq", "value": "gold"}
+            ],
+            "metrics_schema": {"error_rate": {"type": "number"}}
+        }]
+    });
+    std::fs::write(&path, serde_json::to_string_pretty(&persona).unwrap()).unwrap();
+
+    let v = amp_json(
+        &["metrics", "template", path.to_str().unwrap(), "--json"],
+        0,
+    );
+    assert_eq!(v["tasks_completed"], 0);
+    assert!(v["error_rate"].is_f64());
+    assert_eq!(v["schema_valid"], false);
+    assert_eq!(v["tier"], "");
+}
+
+// ── New (1) ─────────────────────────────────────────────────────
+
+#[test]
+fn new_with_same_seed_is_byte_identical() {
+    let a = amp_stdout(&["new", "worker", "--seed", "42"]);
+    let b = amp_stdout(&["new", "worker", "--seed", "42"]);
+    assert_eq!(a, b);
+    let persona: serde_json::Value = serde_json::from_str(&a).unwrap();
+    let name = persona["name"].as_str().unwrap();
+    assert!(name.chars().next().unwrap().is_ascii_uppercase());
+    assert_ne!(name, "Worker", "omitting --name generates an AdjectiveNoun");
+}
+
+// ── Init (1) ────────────────────────────────────────────────────
+
+#[test]
+fn init_template_scout_with_name() {
+    let dir = tempfile::tempdir().unwrap();
+    let out = amp_bin()
+        .current_dir(dir.path())
+        .args(["init", "--template", "scout", "--name", "KeenEye"])
+        .output()
+        .unwrap();
+    assert!(
+        out.status.success(),
+        "init --template scout failed: {}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    let persona: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(dir.path().join("persona.json")).unwrap())
+            .unwrap();
+    assert_eq!(persona["name"], "KeenEye");
+    assert_eq!(persona["role"], "Research Analyst");
+
+    // Unknown templates fail without writing anything
+    let empty = tempfile::tempdir().unwrap();
+    let out = amp_bin()
+        .current_dir(empty.path())
+        .args(["init", "--template", "nope"])
+        .output()
+        .unwrap();
+    assert!(!out.status.success());
+    assert!(String::from_utf8_lossy(&out.stderr).contains("available: architect"));
+    assert!(!empty.path().join("persona.json").exists());
+}
+
+// ── Workspace Defaults (5) ──────────────────────────────────────
+
+#[test]
+fn workspace_init_creates_defaults_file() {
+    let dir = tempfile::tempdir().unwrap();
+
+    let out = amp_bin()
+        .current_dir(dir.path())
+        .args(["init", "--workspace"])
+        .output()
+        .unwrap();
+    assert!(
+        out.status.success(),
+        "init --workspace failed: {}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+
+    let defaults_path = dir.path().join(".ampersona/defaults.json");
+    assert!(defaults_path.exists(), "defaults file was not created");
+
+    let defaults_text = std::fs::read_to_string(&defaults_path).unwrap();
+    let defaults: serde_json::Value = serde_json::from_str(&defaults_text).unwrap();
+    assert_eq!(defaults["authority"]["autonomy"], "supervised");
+}
+
+#[test]
+fn workspace_init_sets_autonomy() {
+    let dir = tempfile::tempdir().unwrap();
+    let status = amp_bin()
+        .current_dir(dir.path())
+        .args(["init", "--workspace", "--autonomy", "readonly"])
+        .status()
+        .unwrap();
+    assert!(status.success());
+    let defaults: serde_json::Value = serde_json::from_str(
+        &std::fs::read_to_string(dir.path().join(".ampersona/defaults.json")).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(defaults["authority"]["autonomy"], "readonly");
+}
+
+#[test]
+fn workspace_defaults_restrict_authority() {
+    let dir = tempfile::tempdir().unwrap();
+    let persona_path = dir.path().join("zeroclaw_agent.json");
+    std::fs::copy(
+        workspace_root().join("examples/zeroclaw_agent.json"),
+        &persona_path,
+    )
+    .unwrap();
+    let persona = persona_path.to_str().unwrap();
+
+    // Baseline without workspace defaults: read_file is allowed for zeroclaw example.
+    let baseline = amp_bin()
+        .current_dir(dir.path())
+        .args(["authority", persona, "--check", "read_file", "--json"])
+        .output()
+        .unwrap();
+    assert_eq!(baseline.status.code(), Some(0));
+    let baseline_json: serde_json::Value = serde_json::from_slice(&baseline.stdout).unwrap();
+    assert_eq!(baseline_json["decision"], "Allow");
+
+    // Add restrictive workspace defaults and verify they are applied.
+    std::fs::create_dir_all(dir.path().join(".ampersona")).unwrap();
+    std::fs::write(
+        dir.path().join(".ampersona/defaults.json"),
+        r#"{"authority":{"autonomy":"readonly"}}"#,
+    )
+    .unwrap();
+
+    let restricted = amp_bin()
+        .current_dir(dir.path())
+        .args(["authority", persona, "--check", "read_file", "--json"])
+        .output()
+        .unwrap();
+    assert_eq!(restricted.status.code(), Some(1));
+    let restricted_json: serde_json::Value = serde_json::from_slice(&restricted.stdout).unwrap();
+    assert_eq!(restricted_json["decision"], "Deny");
+    assert_eq!(restricted_json["autonomy"], "readonly");
+}
+
+#[test]
+fn workspace_defaults_override_user_defaults() {
+    let dir = tempfile::tempdir().unwrap();
+    let home = dir.path().join("home");
+    let work = dir.path().join("work");
+    std::fs::create_dir_all(home.join(".config/ampersona")).unwrap();
+    std::fs::create_dir_all(work.join(".ampersona")).unwrap();
+    let persona_path = work.join("zeroclaw_agent.json");
+    std::fs::copy(
+        workspace_root().join("examples/zeroclaw_agent.json"),
+        &persona_path,
+    )
+    .unwrap();
+    let persona = persona_path.to_str().unwrap();
+    std::fs::write(
+        home.join(".config/ampersona/defaults.json"),
+        r#"{"authority":{"autonomy":"readonly"}}"#,
+    )
+    .unwrap();
+
+    let autonomy = |extra: &[&str]| {
+        let out = amp_bin()
+            .current_dir(&work)
+            .env("HOME", &home)
+            .env_remove("XDG_CONFIG_HOME")
+            .args(["authority", persona, "--check", "read_file", "--json"])
+            .args(extra)
+            .output()
+            .unwrap();
+        let v: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+        v["autonomy"].as_str().unwrap().to_string()
+    };
+
+    // User-level default alone applies
+    assert_eq!(autonomy(&[]), "readonly");
+
+    // The workspace file overrides it
+    std::fs::write(
+        work.join(".ampersona/defaults.json"),
+        r#"{"authority":{"autonomy":"full"}}"#,
+    )
+    .unwrap();
+    assert_eq!(autonomy(&[]), "full");
+
+    // --config overrides both
+    let config = dir.path().join("ci.json");
+    std::fs::write(&config, r#"{"authority":{"autonomy":"supervised"}}"#).unwrap();
+    assert_eq!(
+        autonomy(&["--config", config.to_str().unwrap()]),
+        "supervised"
+    );
+}
+
+#[test]
+fn authority_trace_reports_deciding_layer() {
+    let dir = tempfile::tempdir().unwrap();
+    let persona_path = dir.path().join("zeroclaw_agent.json");
+    std::fs::copy(
+        workspace_root().join("examples/zeroclaw_agent.json"),
+        &persona_path,
+    )
+    .unwrap();
+    std::fs::create_dir_all(dir.path().join(".ampersona")).unwrap();
+    std::fs::write(
+        dir.path().join(".ampersona/defaults.json"),
+        r#"{"authority":{"autonomy":"readonly"}}"#,
+    )
+    .unwrap();
+
+    let out = amp_bin()
+        .current_dir(dir.path())
+        .args([
+            "authority",
+            persona_path.to_str().unwrap(),
+            "--check",
+            "write_file",
+            "--trace",
+            "--json",
+        ])
+        .output()
+        .unwrap();
+    assert_eq!(out.status.code(), Some(1));
+    let v: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    let layers = v["trace"]["layers"].as_array().unwrap();
+    assert_eq!(layers[0]["layer"], "workspace_defaults");
+    assert_eq!(layers[1]["layer"], "persona");
+    assert_eq!(layers[1]["decision"], "Deny");
+    assert_eq!(v["trace"]["decided_by"], "workspace_defaults");
+}
+
+// ── Gate (18) ───────────────────────────────────────────────────
+
+#[test]
+fn zeroclaw_gate_evaluate() {
+    // Use tempdir so parallel tests don't interfere via state files.
+    let dir = tempfile::tempdir().unwrap();
+    let persona_path = dir.path().join("zeroclaw_agent.json");
+    std::fs::copy(
+        workspace_root().join("examples/zeroclaw_agent.json"),
+        &persona_path,
+    )
+    .unwrap();
+    let metrics_path = dir.path().join("zeroclaw_metrics.json");
+    std::fs::copy(
+        workspace_root().join("examples/zeroclaw_metrics.json"),
+        &metrics_path,
+    )
+    .unwrap();
+
+    // Phase null → onboarding fires first (→active).
+    let out = amp_bin()
+        .args([
+            "gate",
+            persona_path.to_str().unwrap(),
+            "--evaluate",
+            "*",
+            "--metrics",
+            metrics_path.to_str().unwrap(),
+            "--json",
+        ])
+        .output()
+        .expect("failed to run amp");
+    assert!(
+        out.status.success(),
+        "gate failed: {}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    let v: serde_json::Value = serde_json::from_slice(&out.stdout).expect("invalid JSON");
+    assert_eq!(v["gate_id"], "onboarding");
+    assert!(v["criteria_results"].is_array());
+}
+
+#[test]
+fn odoov19_gate_f2() {
+    // Use tempdir so parallel tests don't interfere via state files.
+    let dir = tempfile::tempdir().unwrap();
+    let persona_path = dir.path().join("odoov19_quality.json");
+    std::fs::copy(
+        workspace_root().join("examples/odoov19_quality.json"),
+        &persona_path,
+    )
+    .unwrap();
+    let metrics_path = dir.path().join("odoov19_metrics_f2.json");
+    std::fs::copy(
+        workspace_root().join("examples/odoov19_metrics_f2.json"),
+        &metrics_path,
+    )
+    .unwrap();
+
+    let out = amp_bin()
+        .args([
+            "gate",
+            persona_path.to_str().unwrap(),
+            "--evaluate",
+            "*",
+            "--metrics",
+            metrics_path.to_str().unwrap(),
+            "--json",
+        ])
+        .output()
+        .expect("failed to run amp");
+    assert!(
+        out.status.success(),
+        "gate failed: {}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    let v: serde_json::Value = serde_json::from_slice(&out.stdout).expect("invalid JSON");
+    assert_eq!(v["gate_id"], "f1_to_f2");
+    assert_eq!(v["to_phase"], "F2");
+}
+
+#[test]
+fn gate_json_no_match() {
+    // Request a specific gate that won't fire with bad metrics
+    let dir = tempfile::tempdir().unwrap();
+    let metrics_path = dir.path().join("bad_metrics.json");
+    std::fs::write(
+        &metrics_path,
+        r#"{"tasks_completed": 1, "error_rate": 0.99, "schema_valid": false}"#,
+    )
+    .unwrap();
+
+    // We need to set up state in "active" phase for the "trusted" gate to be a candidate.
+    // Create a state file so phase = "active".
+    let state_path = dir.path().join("zeroclaw_agent.state.json");
+    let state = serde_json::json!({
+        "name": "ZeroclawWorker",
+        "current_phase": "active",
+        "state_rev": 1,
+        "active_elevations": [],
+        "last_transition": null,
+        "updated_at": "2024-01-01T00:00:00Z"
+    });
+    std::fs::write(&state_path, serde_json::to_string_pretty(&state).unwrap()).unwrap();
+
+    // Copy persona next to state
+    let persona_path = dir.path().join("zeroclaw_agent.json");
+    std::fs::copy(
+        workspace_root().join("examples/zeroclaw_agent.json"),
+        &persona_path,
+    )
+    .unwrap();
+
+    let out = amp_bin()
+        .args([
+            "gate",
+            persona_path.to_str().unwrap(),
+            "--evaluate",
+            "trusted",
+            "--metrics",
+            metrics_path.to_str().unwrap(),
+            "--json",
+        ])
+        .output()
+        .expect("failed to run amp");
+
+    assert_eq!(out.status.code(), Some(1), "expected exit 1 for no match");
+    let v: serde_json::Value = serde_json::from_slice(&out.stdout).expect("invalid JSON");
+    assert_eq!(v["decision"], "no_match");
+    assert!(v["criteria_results"].is_array());
+    // Check that criteria_results has the expected structure
+    let results = v["criteria_results"].as_array().unwrap();
+    assert!(!results.is_empty());
+    for r in results {
+        assert!(r.get("metric").is_some());
+        assert!(r.get("pass").is_some());
+    }
+}
+
+#[test]
+fn gate_simulate_does_not_touch_state() {
+    let dir = tempfile::tempdir().unwrap();
+    let persona_path = dir.path().join("zeroclaw_agent.json");
+    std::fs::copy(
+        workspace_root().join("examples/zeroclaw_agent.json"),
+        &persona_path,
+    )
+    .unwrap();
+    let timeline_path = dir.path().join("timeline.jsonl");
+    std::fs::write(
+        &timeline_path,
+        concat!(
+            r#"{"ts":"2026-01-01T00:00:00Z","metrics":{"schema_valid":false}}"#,
+            "\n",
+            r#"{"ts":"2026-01-01T01:00:00Z","metrics":{"schema_valid":true}}"#,
+            "\n",
+        ),
+    )
+    .unwrap();
+
+    let v = amp_json(
+        &[
+            "gate",
+            persona_path.to_str().unwrap(),
+            "--simulate",
+            timeline_path.to_str().unwrap(),
+            "--json",
+        ],
+        0,
+    );
+    assert_eq!(v["transitions"], 1);
+    assert_eq!(v["end_phase"], "active");
+    assert_eq!(v["steps"][1]["gate_id"], "onboarding");
+    assert!(!dir.path().join("zeroclaw_agent.state.json").exists());
+    assert!(!dir.path().join("zeroclaw_agent.audit.jsonl").exists());
+}
+
+#[test]
+fn gate_backtest_replays_drift_history_from_fresh_state() {
+    let dir = tempfile::tempdir().unwrap();
+    let persona_path = dir.path().join("zeroclaw_agent.json");
+    std::fs::copy(
+        workspace_root().join("examples/zeroclaw_agent.json"),
+        &persona_path,
+    )
+    .unwrap();
+    // The real state is already past onboarding; the backtest must ignore it.
+    let state_path = dir.path().join("zeroclaw_agent.state.json");
+    let state = serde_json::json!({
+        "name": "zeroclaw_agent",
+        "current_phase": "active",
+        "state_rev": 1,
+        "updated_at": "2026-01-01T00:00:00Z"
+    })
+    .to_string();
+    std::fs::write(&state_path, &state).unwrap();
+    // Drift ledger lines, recorded out of timestamp order
+    let drift_path = dir.path().join("zeroclaw_agent.drift.jsonl");
+    std::fs::write(
+        &drift_path,
+        concat!(
+            r#"{"prev_hash":"genesis","ts":"2026-01-01T02:00:00Z","metrics":{"schema_valid":true}}"#,
+            "\n",
+            r#"{"prev_hash":"sha256:00","ts":"2026-01-01T01:00:00Z","metrics":{"schema_valid":false}}"#,
+            "\n",
+        ),
+    )
+    .unwrap();
+
+    let v = amp_json(
+        &[
+            "gate",
+            persona_path.to_str().unwrap(),
+            "--backtest",
+            drift_path.to_str().unwrap(),
+            "--json",
+        ],
+        0,
+    );
+    assert_eq!(v["start_phase"], serde_json::Value::Null);
+    assert_eq!(v["end_phase"], "active");
+    assert_eq!(v["transitions"], 1);
+    assert_eq!(
+        v["fired"],
+        serde_json::json!([{
+            "ts": "2026-01-01T02:00:00Z",
+            "gate_id": "onboarding",
+            "decision": "transition"
+        }])
+    );
+    assert_eq!(std::fs::read_to_string(&state_path).unwrap(), state);
+    assert!(!dir.path().join("zeroclaw_agent.audit.jsonl").exists());
+}
+
+#[cfg(all(feature = "exec-hooks", unix))]
+#[test]
+fn gate_exec_hooks_receive_decision_and_are_audited() {
+    let dir = tempfile::tempdir().unwrap();
+    let persona_path = dir.path().join("zeroclaw_agent.json");
+    let mut persona: serde_json::Value = serde_json::from_str(
+        &std::fs::read_to_string(workspace_root().join("examples/zeroclaw_agent.json")).unwrap(),
+    )
+    .unwrap();
+    // Hooks run after the state lock is released.
+    let lock = dir.path().join("zeroclaw_agent.state.json.lock");
+    let hook = |name: &str| {
+        let script = format!(
+            "cat > '{}' && test ! -e '{}'",
+            dir.path().join(name).display(),
+            lock.display()
+        );
+        serde_json::json!({ "exec": ["sh", "-c", script] })
+    };
+    persona["gates"][0]["on_pass"] = hook("passed.json");
+    persona["gates"][0]["on_fail"] = hook("failed.json");
+    std::fs::write(&persona_path, persona.to_string()).unwrap();
+    let metrics_path = dir.path().join("metrics.json");
+    let evaluate = |schema_valid: bool, extra: &[&str], exit: i32| {
+        std::fs::write(
+            &metrics_path,
+            serde_json::json!({ "schema_valid": schema_valid }).to_string(),
+        )
+        .unwrap();
+        let mut args = vec![
+            "gate",
+            persona_path.to_str().unwrap(),
+            "--evaluate",
+            "onboarding",
+            "--metrics",
+            metrics_path.to_str().unwrap(),
+            "--json",
+        ];
+        args.extend_from_slice(extra);
+        amp_json(&args, exit)
+    };
+
+    evaluate(false, &["--no-hooks"], 1);
+    assert!(!dir.path().join("failed.json").exists());
+
+    evaluate(false, &[], 1);
+    let failed: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(dir.path().join("failed.json")).unwrap())
+            .unwrap();
+    assert_eq!(failed["gate_id"], "onboarding");
+
+    let record = evaluate(true, &[], 0);
+    assert_eq!(record["decision"], "transition");
+    let passed: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(dir.path().join("passed.json")).unwrap())
+            .unwrap();
+    assert_eq!(passed["gate_id"], "onboarding");
+    assert_eq!(passed["to_phase"], "active");
+
+    let audit = std::fs::read_to_string(dir.path().join("zeroclaw_agent.audit.jsonl")).unwrap();
+    let hooks: Vec<serde_json::Value> = audit
+        .lines()
+        .map(|l| serde_json::from_str::<serde_json::Value>(l).unwrap())
+        .filter(|e| e["event_type"] == "HookResult")
+        .collect();
+    assert_eq!(hooks.len(), 2);
+    assert_eq!(hooks[0]["hook"], "on_fail");
+    assert_eq!(hooks[0]["exit_code"], 0);
+    assert_eq!(hooks[1]["hook"], "on_pass");
+    assert_eq!(hooks[1]["exit_code"], 0);
+}
+
+#[test]
+fn gate_reset_phase_to_active() {
+    let dir = tempfile::tempdir().unwrap();
+    let persona_path = dir.path().join("zeroclaw_agent.json");
+    std::fs::copy(
+        workspace_root().join("examples/zeroclaw_agent.json"),
+        &persona_path,
+    )
+    .unwrap();
+    let persona = persona_path.to_str().unwrap();
+
+    // Orphan phase is rejected without --force
+    let out = amp_bin()
+        .args([
+            "gate",
+            persona,
+            "--reset-phase",
+            "limbo",
+            "--reason",
+            "recovery",
+            "--approver",
+            "ops",
+        ])
+        .output()
+        .unwrap();
+    assert_ne!(out.status.code(), Some(0));
+    assert!(!dir.path().join("zeroclaw_agent.state.json").exists());
+
+    let v = amp_json(
+        &[
+            "gate",
+            persona,
+            "--reset-phase",
+            "active",
+            "--reason",
+            "onboarding recovery",
+            "--approver",
+            "ops",
+            "--json",
+        ],
+        0,
+    );
+    assert_eq!(v["event_type"], "AdminPhaseReset");
+    assert_eq!(v["to_phase"], "active");
+
+    let state: serde_json::Value = serde_json::from_str(
+        &std::fs::read_to_string(dir.path().join("zeroclaw_agent.state.json")).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(state["current_phase"], "active");
+    assert_eq!(state["state_rev"], 1);
+    assert!(state["pending_transition"].is_null());
+
+    let audit = std::fs::read_to_string(dir.path().join("zeroclaw_agent.audit.jsonl")).unwrap();
+    assert!(audit.contains("\"event_type\":\"AdminPhaseReset\""));
+    assert!(audit.contains("onboarding recovery"));
+}
+
+#[test]
+fn gate_lock_contention_exits_5() {
+    let dir = tempfile::tempdir().unwrap();
+    let persona_path = dir.path().join("zeroclaw_agent.json");
+    std::fs::copy(
+        workspace_root().join("examples/zeroclaw_agent.json"),
+        &persona_path,
+    )
+    .unwrap();
+    let persona = persona_path.to_str().unwrap();
+
+    // A live holder (this test process) with a fresh timestamp
+    let lock_path = dir.path().join("zeroclaw_agent.state.json.lock");
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+    std::fs::write(&lock_path, format!("{}\n{now}\n", std::process::id())).unwrap();
+
+    let v = amp_json(
+        &[
+            "gate",
+            persona,
+            "--reset-phase",
+            "active",
+            "--reason",
+            "recovery",
+            "--approver",
+            "ops",
+            "--lock-timeout",
+            "0",
+            "--json",
+        ],
+        5,
+    );
+    assert_eq!(v["code"], "E_LOCK_CONTENTION");
+    assert!(!dir.path().join("zeroclaw_agent.state.json").exists());
+    assert!(lock_path.exists());
+}
+
+#[test]
+fn gate_rejects_nested_metrics() {
+    let dir = tempfile::tempdir().unwrap();
+    let persona_path = dir.path().join("zeroclaw_agent.json");
+    std::fs::copy(
+        workspace_root().join("examples/zeroclaw_agent.json"),
+        &persona_path,
+    )
+    .unwrap();
+    let metrics_path = dir.path().join("metrics.json");
+    std::fs::write(
+        &metrics_path,
+        r#"{"tasks_completed": 12, "stats": {"success_rate": 0.9}, "gap": null}"#,
+    )
+    .unwrap();
+
+    let v = amp_json(
+        &[
+            "gate",
+            persona_path.to_str().unwrap(),
+            "--evaluate",
+            "*",
+            "--metrics",
+            metrics_path.to_str().unwrap(),
+            "--json",
+        ],
+        3,
+    );
+    assert_eq!(v["code"], "E_INVALID_METRICS");
+    let message = v["message"].as_str().unwrap();
+    assert!(message.contains("'stats'"), "{message}");
+    assert!(message.contains("'gap'"), "{message}");
+    assert!(!message.contains("tasks_completed"), "{message}");
+    assert!(!dir.path().join("zeroclaw_agent.state.json").exists());
+}
+
+#[test]
+fn gate_list_reflects_state_phase() {
+    let dir = tempfile::tempdir().unwrap();
+    let persona_path = dir.path().join("zeroclaw_agent.json");
+    std::fs::copy(
+        workspace_root().join("examples/zeroclaw_agent.json"),
+        &persona_path,
+    )
+    .unwrap();
+    let file = persona_path.to_str().unwrap();
+    let candidates = |v: &serde_json::Value| -> Vec<String> {
+        v["gates"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter(|g| g["candidate"] == true)
+            .map(|g| g["id"].as_str().unwrap().to_string())
+            .collect()
+    };
+
+    // No state yet: only gates leaving the null phase apply
+    let v = amp_json(&["gate", file, "--list", "--json"], 0);
+    assert!(v["current_phase"].is_null());
+    assert_eq!(v["gates"].as_array().unwrap().len(), 3);
+    assert_eq!(candidates(&v), ["onboarding"]);
+
+    let state = serde_json::json!({
+        "name": "zeroclaw",
+        "current_phase": "trusted",
+        "state_rev": 3,
+        "active_elevations": [],
+        "updated_at": "2026-01-01T00:00:00Z"
+    });
+    std::fs::write(
+        dir.path().join("zeroclaw_agent.state.json"),
+        state.to_string(),
+    )
+    .unwrap();
+    let v = amp_json(&["gate", file, "--list", "--json"], 0);
+    assert_eq!(v["current_phase"], "trusted");
+    assert_eq!(candidates(&v), ["trust_decay"]);
+
+    let text = amp_stdout(&["gate", file, "--list"]);
+    assert!(text.contains("current phase: trusted"));
+    assert!(text
+        .lines()
+        .any(|l| l.contains("trust_decay") && l.trim_end().ends_with("yes")));
+}
+
+#[test]
+fn gate_observe_report_counts_shadow_decisions() {
+    let dir = tempfile::tempdir().unwrap();
+    let persona_path = dir.path().join("zeroclaw_agent.json");
+    let mut persona: serde_json::Value = serde_json::from_str(
+        &std::fs::read_to_string(workspace_root().join("examples/zeroclaw_agent.json")).unwrap(),
+    )
+    .unwrap();
+    persona["gates"][0]["enforcement"] = "observe".into();
+    std::fs::write(&persona_path, persona.to_string()).unwrap();
+    let file = persona_path.to_str().unwrap();
+    let metrics = workspace_root().join("examples/zeroclaw_metrics.json");
+    let metrics = metrics.to_str().unwrap();
+
+    let v = amp_json(&["gate", file, "--observe-report", "--json"], 0);
+    assert_eq!(v["gates"].as_array().unwrap().len(), 1);
+    assert_eq!(v["gates"][0]["gate_id"], "onboarding");
+    assert_eq!(v["gates"][0]["would_fire"], 0);
+
+    // Observed decisions leave the phase alone, so each run logs one more
+    for _ in 0..3 {
+        let record = amp_json(
+            &[
+                "gate",
+                file,
+                "--evaluate",
+                "*",
+                "--metrics",
+                metrics,
+                "--json",
+            ],
+            0,
+        );
+        assert_eq!(record["decision"], "observed");
+    }
+
+    let v = amp_json(&["gate", file, "--observe-report", "--json"], 0);
+    let onboarding = &v["gates"][0];
+    assert_eq!(onboarding["would_fire"], 3);
+    assert!(onboarding["first_seen"].is_string());
+    assert!(onboarding["last_seen"].is_string());
+
+    let text = amp_stdout(&["gate", file, "--observe-report"]);
+    assert!(text
+        .lines()
+        .any(|l| l.contains("onboarding") && l.contains("observe") && l.contains(" 3 ")));
+}
+
+#[test]
+fn gate_promote_to_enforce_flips_observed_gate() {
+    let dir = tempfile::tempdir().unwrap();
+    let persona_path = dir.path().join("zeroclaw_agent.json");
+    let mut persona: serde_json::Value = serde_json::from_str(
+        &std::fs::read_to_string(workspace_root().join("examples/zeroclaw_agent.json")).unwrap(),
+    )
+    .unwrap();
+    persona["gates"][0]["enforcement"] = "observe".into();
+    persona["gates"][1]["enforcement"] = "observe".into();
+    std::fs::write(&persona_path, persona.to_string()).unwrap();
+    let file = persona_path.to_str().unwrap();
+    let metrics = workspace_root().join("examples/zeroclaw_metrics.json");
+    let promote = |extra: &[&str]| {
+        let mut args = vec!["gate", file, "--promote-to", "enforce"];
+        args.extend_from_slice(extra);
+        amp_bin().args(&args).output().unwrap()
+    };
+
+    // Neither gate has been observed firing yet
+    let out = promote(&["--gate", "onboarding"]);
+    assert!(!out.status.success());
+    assert!(String::from_utf8_lossy(&out.stderr).contains("--force"));
+
+    amp_json(
+        &[
+            "gate",
+            file,
+            "--evaluate",
+            "*",
+            "--metrics",
+            metrics.to_str().unwrap(),
+            "--json",
+        ],
+        0,
+    );
+    let out = promote(&["--gate", "onboarding", "--json"]);
+    assert!(
+        out.status.success(),
+        "{}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    let v: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    assert_eq!(v["promoted"], serde_json::json!(["onboarding"]));
+
+    let written: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&persona_path).unwrap()).unwrap();
+    assert_eq!(written["gates"][0]["enforcement"], "enforce");
+    assert_eq!(written["gates"][1]["enforcement"], "observe");
+    assert!(dir.path().join("zeroclaw_agent.bak").exists());
+
+    let audit = std::fs::read_to_string(dir.path().join("zeroclaw_agent.audit.jsonl")).unwrap();
+    let last: serde_json::Value = serde_json::from_str(audit.lines().last().unwrap()).unwrap();
+    assert_eq!(last["event_type"], "GateEnforcementChange");
+    assert_eq!(last["gate_ids"], serde_json::json!(["onboarding"]));
+    amp_json(&["audit", file, "--verify", "--json"], 0);
+}
+
+#[test]
+fn gate_force_reeval_fires_idempotent_repeat() {
+    let dir = tempfile::tempdir().unwrap();
+    let persona_path = dir.path().join("zeroclaw_agent.json");
+    std::fs::copy(
+        workspace_root().join("examples/zeroclaw_agent.json"),
+        &persona_path,
+    )
+    .unwrap();
+    let file = persona_path.to_str().unwrap();
+    let metrics = workspace_root().join("examples/zeroclaw_metrics.json");
+    let metrics = metrics.to_str().unwrap();
+    let state_path = dir.path().join("zeroclaw_agent.state.json");
+
+    // Onboarding fires once; its record carries the metrics hash
+    let first = amp_json(
+        &[
+            "gate",
+            file,
+            "--evaluate",
+            "*",
+            "--metrics",
+            metrics,
+            "--json",
+        ],
+        0,
+    );
+    assert_eq!(first["gate_id"], "onboarding");
+    assert!(first.get("forced_reeval").is_none());
+
+    // Rewind the phase but keep the transition record: an exact repeat
+    let mut state: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&state_path).unwrap()).unwrap();
+    state["current_phase"] = serde_json::Value::Null;
+    state["last_transition"]["state_rev"] = state["state_rev"].clone();
+    std::fs::write(&state_path, state.to_string()).unwrap();
+
+    let out = amp_bin()
+        .args([
+            "gate",
+            file,
+            "--evaluate",
+            "onboarding",
+            "--metrics",
+            metrics,
+        ])
+        .output()
+        .unwrap();
+    assert_eq!(out.status.code(), Some(1), "repeat should be skipped");
+
+    let forced = amp_json(
+        &[
+            "gate",
+            file,
+            "--evaluate",
+            "onboarding",
+            "--metrics",
+            metrics,
+            "--force-reeval",
+            "--json",
+        ],
+        0,
+    );
+    assert_eq!(forced["gate_id"], "onboarding");
+    assert_eq!(forced["forced_reeval"], true);
+    assert_eq!(forced["metrics_hash"], first["metrics_hash"]);
+
+    let audit = std::fs::read_to_string(dir.path().join("zeroclaw_agent.audit.jsonl")).unwrap();
+    let forced_entries = audit
+        .lines()
+        .filter(|l| l.contains("\"forced_reeval\":true"))
+        .count();
+    assert_eq!(forced_entries, 1);
+
+    // --force-reeval only makes sense with --evaluate
+    let out = amp_bin()
+        .args(["gate", file, "--force-reeval"])
+        .output()
+        .unwrap();
+    assert!(!out.status.success());
+}
+
+#[test]
+fn gate_stale_sample_blocks_promotion() {
+    let dir = tempfile::tempdir().unwrap();
+    let persona_path = dir.path().join("zeroclaw_agent.json");
+    let mut data: serde_json::Value = serde_json::from_str(
+        &std::fs::read_to_string(workspace_root().join("examples/zeroclaw_agent.json")).unwrap(),
+    )
+    .unwrap();
+    data["gates"][0]["max_age_seconds"] = serde_json::json!(3600);
+    std::fs::write(&persona_path, data.to_string()).unwrap();
+    let file = persona_path.to_str().unwrap();
+    let metrics_path = dir.path().join("metrics.json");
+    let metrics = metrics_path.to_str().unwrap();
+    let write_metrics = |ts: chrono::DateTime<chrono::Utc>| {
+        let doc = serde_json::json!({
+            "schema_valid": { "value": true, "ts": ts.to_rfc3339() }
+        });
+        std::fs::write(&metrics_path, doc.to_string()).unwrap();
+    };
+
+    write_metrics(chrono::Utc::now() - chrono::Duration::hours(6));
+    let v = amp_json(
+        &[
+            "gate",
+            file,
+            "--evaluate",
+            "onboarding",
+            "--metrics",
+            metrics,
+            "--json",
+        ],
+        1,
+    );
+    assert_eq!(v["decision"], "no_match");
+    assert_eq!(v["criteria_results"][0]["stale"], true);
+    assert_eq!(v["criteria_results"][0]["pass"], false);
+
+    write_metrics(chrono::Utc::now() - chrono::Duration::minutes(5));
+    let v = amp_json(
+        &[
+            "gate",
+            file,
+            "--evaluate",
+            "onboarding",
+            "--metrics",
+            metrics,
+            "--json",
+        ],
+        0,
+    );
+    assert_eq!(v["gate_id"], "onboarding");
+    assert_eq!(v["decision"], "transition");
+}
+
+#[test]
+fn gate_metrics_uri_schemes() {
+    let dir = tempfile::tempdir().unwrap();
+    let persona_path = dir.path().join("zeroclaw_agent.json");
+    std::fs::copy(
+        workspace_root().join("examples/zeroclaw_agent.json"),
+        &persona_path,
+    )
+    .unwrap();
+    let file = persona_path.to_str().unwrap();
+    let metrics = format!(
+        "file://{}",
+        workspace_root()
+            .join("examples/zeroclaw_metrics.json")
+            .display()
+    );
+
+    let v = amp_json(
+        &[
+            "gate",
+            file,
+            "--evaluate",
+            "*",
+            "--metrics",
+            &metrics,
+            "--json",
+        ],
+        0,
+    );
+    assert_eq!(v["gate_id"], "onboarding");
+
+    let v = amp_json(
+        &[
+            "gate",
+            file,
+            "--evaluate",
+            "*",
+            "--metrics",
+            "ftp://metrics.json",
+            "--json",
+        ],
+        3,
+    );
+    assert_eq!(v["code"], "E_METRICS_SOURCE");
+    assert!(v["message"]
+        .as_str()
+        .unwrap()
+        .contains("unknown scheme 'ftp'"));
+}
+
+#[test]
+fn gate_transition_surfaces_phase_entry_note() {
+    let dir = tempfile::tempdir().unwrap();
+    let persona_path = dir.path().join("agent.json");
+    let mut persona: serde_json::Value = serde_json::from_str(
+        &std::fs::read_to_string(workspace_root().join("examples/zeroclaw_agent.json")).unwrap(),
+    )
+    .unwrap();
+    persona["phases"] = serde_json::json!({
+        "active": {
+            "description": "Routine work under supervision.",
+            "entry_note": "Onboarding passed; keep changes small until trusted."
+        }
+    });
+    std::fs::write(&persona_path, persona.to_string()).unwrap();
+    let file = persona_path.to_str().unwrap();
+    let metrics = workspace_root()
+        .join("examples/zeroclaw_metrics.json")
+        .to_string_lossy()
+        .into_owned();
+
+    let v = amp_json(
+        &[
+            "gate",
+            file,
+            "--evaluate",
+            "onboarding",
+            "--metrics",
+            &metrics,
+            "--json",
+        ],
+        0,
+    );
+    assert_eq!(v["to_phase"], "active");
+
+    let audit = std::fs::read_to_string(dir.path().join("agent.audit.jsonl")).unwrap();
+    let transition: serde_json::Value = audit
+        .lines()
+        .map(|l| serde_json::from_str::<serde_json::Value>(l).unwrap())
+        .find(|e| e["event_type"] == "GateTransition")
+        .unwrap();
+    assert_eq!(
+        transition["entry_note"],
+        "Onboarding passed; keep changes small until trusted."
+    );
+
+    let status = amp_json(&["status", file, "--json"], 0);
+    assert_eq!(
+        status["phase_info"]["description"],
+        "Routine work under supervision."
+    );
+
+    let prompt = amp_stdout(&["prompt", file, "--with-state"]);
+    assert!(
+        prompt.contains("**About this phase:** Routine work under supervision."),
+        "{prompt}"
+    );
+}
+
+#[test]
+fn gate_evaluates_piped_persona_with_explicit_sidecars() {
+    use std::io::Write;
+
+    let dir = tempfile::tempdir().unwrap();
+    let persona = std::fs::read(workspace_root().join("examples/zeroclaw_agent.json")).unwrap();
+    let metrics = workspace_root().join("examples/zeroclaw_metrics.json");
+    let state_path = dir.path().join("pipeline.state.json");
+    let audit_path = dir.path().join("decisions.jsonl");
+
+    let gate = |extra: &[&str], exit: i32| {
+        let mut child = amp_bin()
+            .args(["gate", "-", "--evaluate", "*", "--json", "--metrics"])
+            .arg(&metrics)
+            .args(extra)
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::piped())
+            .spawn()
+            .unwrap();
+        child.stdin.take().unwrap().write_all(&persona).unwrap();
+        let out = child.wait_with_output().unwrap();
+        assert_eq!(
+            out.status.code(),
+            Some(exit),
+            "{}",
+            String::from_utf8_lossy(&out.stderr)
+        );
+        serde_json::from_slice::<serde_json::Value>(&out.stdout).unwrap()
+    };
+
+    let sidecars = [
+        "--state",
+        state_path.to_str().unwrap(),
+        "--audit",
+        audit_path.to_str().unwrap(),
+    ];
+    let v = gate(&sidecars, 0);
+    assert_eq!(v["gate_id"], "onboarding");
+    let state: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&state_path).unwrap()).unwrap();
+    assert_eq!(state["current_phase"], "active");
+    let audit = std::fs::read_to_string(&audit_path).unwrap();
+    assert!(audit.contains("GateTransition"), "{audit}");
+    assert!(dir.path().join("pipeline.drift.jsonl").exists());
+
+    // The explicit state carries over: the next evaluation starts from "active",
+    // where the next gate needs human approval.
+    let v = gate(&sidecars, 2);
+    assert_eq!(v["from_phase"], "active");
+    assert_eq!(v["decision"], "pending_human");
+
+    // Without --state the run is in memory: always from a fresh state, nothing written.
+    let before = std::fs::read_dir(dir.path()).unwrap().count();
+    let v = gate(&[], 0);
+    assert_eq!(v["gate_id"], "onboarding");
+    assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), before);
+}
+
+#[test]
+fn gate_remind_nags_only_past_the_threshold() {
+    let dir = tempfile::tempdir().unwrap();
+    let persona_path = dir.path().join("agent.json");
+    std::fs::copy(
+        workspace_root().join("examples/zeroclaw_agent.json"),
+        &persona_path,
+    )
+    .unwrap();
+    let persona = persona_path.to_str().unwrap();
+    let waiting_since = |age: chrono::Duration| {
+        let state = serde_json::json!({
+            "name": "ZeroclawWorker",
+            "current_phase": "active",
+            "state_rev": 1,
+            "active_elevations": [],
+            "last_transition": null,
+            "pending_transition": {
+                "gate_id": "trusted",
+                "from_phase": "active",
+                "to_phase": "trusted",
+                "decision": "pending_human",
+                "metrics_hash": "sha256:00",
+                "state_rev": 1,
+                "created_at": (chrono::Utc::now() - age).to_rfc3339(),
+            },
+            "updated_at": "2026-01-01T00:00:00Z"
+        });
+        std::fs::write(dir.path().join("agent.state.json"), state.to_string()).unwrap();
+    };
+    let remind = || {
+        amp_json(
+            &[
+                "gate",
+                persona,
+                "--remind",
+                "--remind-after",
+                "4h",
+                "--escalate-after",
+                "2d",
+                "--json",
+            ],
+            0,
+        )
+    };
+
+    waiting_since(chrono::Duration::minutes(5));
+    assert_eq!(remind()["due"], false);
+
+    waiting_since(chrono::Duration::hours(6));
+    let v = remind();
+    assert_eq!(v["due"], true);
+    assert_eq!(v["notice"]["urgency"], "reminder");
+    assert_eq!(v["notice"]["gate_id"], "trusted");
+    assert!(v["notice"]["age_seconds"].as_u64().unwrap() >= 6 * 3600);
+
+    waiting_since(chrono::Duration::days(3));
+    assert_eq!(remind()["notice"]["urgency"], "escalation");
+}
+
+// ── Import/Export roundtrip (6) ─────────────────────────────────
+
+#[test]
+fn zeroclaw_import_aieos() {
+    let v = amp_json(
+        &["import", "examples/aieos_identity.json", "--from", "aieos"],
+        0,
+    );
+    assert_eq!(v["version"], "1.0");
+    assert!(v["name"].as_str().is_some());
+    assert!(v["psychology"].is_object());
+}
+
+#[test]
+fn import_crewai_yaml_picks_agent() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("agents.yaml");
+    std::fs::write(
+        &path,
+        "researcher:\n  role: Senior Researcher\n  goal: Find sources\n  backstory: Reads a lot.\n  tools: [SerperDevTool]\nwriter:\n  role: Writer\n  goal: Explain findings\n",
+    )
+    .unwrap();
+    let file = path.to_str().unwrap();
+
+    let out = amp_bin()
+        .args(["import", file, "--from", "crewai"])
+        .output()
+        .unwrap();
+    assert!(!out.status.success());
+    assert!(String::from_utf8_lossy(&out.stderr).contains("--agent"));
+
+    let v = amp_json(
+        &["import", file, "--from", "crewai", "--agent", "researcher"],
+        0,
+    );
+    assert_eq!(v["name"], "Researcher");
+    assert_eq!(v["directives"]["core_drive"], "Find sources");
+    assert_eq!(v["capabilities"]["skills"][0]["name"], "SerperDevTool");
+}
+
+#[test]
+fn zeroclaw_export_config() {
+    let v = amp_json(
+        &["export", "examples/zeroclaw_agent.json", "--to", "zeroclaw"],
+        0,
+    );
+    assert!(v["security_policy"].is_object() || v.get("security_policy").is_some());
+}
+
+#[test]
+fn import_export_roundtrip_stable() {
+    // Import AIEOS → ampersona, then export to zeroclaw, check key fields preserved
+    let imported = amp_json(
+        &["import", "examples/aieos_identity.json", "--from", "aieos"],
+        0,
+    );
+    assert!(imported["name"].as_str().is_some());
+    assert!(imported["role"].as_str().is_some());
+    // The imported persona should have psychology and voice sections
+    assert!(imported["psychology"].is_object());
+    assert!(imported["voice"].is_object());
+}
+
+#[test]
+fn toml_export_import_roundtrip() {
+    let dir = tempfile::tempdir().unwrap();
+    let toml_path = dir.path().join("quiet_stone.toml");
+    let toml = amp_stdout(&["export", "examples/quiet_stone_v1.json", "--to", "toml"]);
+    assert!(toml.contains("name = \"QuietStone\""));
+    std::fs::write(&toml_path, &toml).unwrap();
+
+    let imported = amp_json(
+        &["import", toml_path.to_str().unwrap(), "--from", "toml"],
+        0,
+    );
+    let original: serde_json::Value = serde_json::from_str(
+        &std::fs::read_to_string(workspace_root().join("examples/quiet_stone_v1.json")).unwrap(),
+    )
+    .unwrap();
+    assert!(
+        ampersona_core::canonical::values_approx_equal(
+            &imported,
+            &original,
+            ampersona_core::canonical::DEFAULT_FLOAT_EPSILON
+        ),
+        "{imported:#}"
+    );
+
+    // A null TOML cannot carry is refused, not dropped
+    let mut bad = original;
+    bad["backstory"] = serde_json::Value::Null;
+    let bad_path = dir.path().join("bad.json");
+    std::fs::write(&bad_path, bad.to_string()).unwrap();
+    let out = amp_bin()
+        .args(["export", bad_path.to_str().unwrap(), "--to", "toml"])
+        .output()
+        .unwrap();
+    assert_eq!(out.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&out.stderr).contains("/backstory is null"));
+}
+
+#[test]
+fn roundtrip_reports_lossy_fields_only() {
+    let file = "examples/quiet_stone_v1.json";
+    let v = amp_json(
+        &[
+            "roundtrip",
+            file,
+            "--via",
+            "toml",
+            "--tolerance",
+            "1e-6",
+            "--json",
+        ],
+        0,
+    );
+    assert_eq!(v["lossless"], true);
+    assert_eq!(v["tolerance"], 1e-6);
+
+    // AIEOS drops deny reasons and scoped actions; that loss is reported, exit 1
+    let v = amp_json(&["roundtrip", file, "--via", "aieos", "--json"], 1);
+    assert_eq!(v["lossless"], false);
+    let lossy: Vec<_> = v["lossy"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|p| p.as_str().unwrap())
+        .collect();
+    assert!(
+        lossy.iter().any(|p| p.starts_with("/authority/actions")),
+        "{lossy:?}"
+    );
+    assert!(!lossy.contains(&"/name"), "{lossy:?}");
+}
+
+// ── List (4) ────────────────────────────────────────────────────
+
+#[test]
+fn list_json_sorted_by_name() {
+    let dir = tempfile::tempdir().unwrap();
+    for name in ["zeroclaw_agent", "quiet_stone_v1", "warm_birch"] {
+        std::fs::copy(
+            workspace_root().join(format!("examples/{name}.json")),
+            dir.path().join(format!("{name}.json")),
+        )
+        .unwrap();
+    }
+    std::fs::write(
+        dir.path().join("zeroclaw_agent.state.json"),
+        serde_json::json!({
+            "name": "ZeroclawWorker",
+            "current_phase": "active",
+            "state_rev": 1,
+            "updated_at": "2026-01-01T00:00:00Z"
+        })
+        .to_string(),
+    )
+    .unwrap();
+    let d = dir.path().to_str().unwrap();
+
+    let v = amp_json(&["list", d, "--json", "--sort", "name"], 0);
+    let rows = v.as_array().unwrap();
+    // The state sidecar is not listed as a persona
+    assert_eq!(rows.len(), 3);
+    for field in [
+        "file", "name", "mbti", "role", "autonomy", "phase", "skills",
+    ] {
+        assert!(rows[0].get(field).is_some(), "missing {field}: {}", rows[0]);
+    }
+    let names: Vec<&str> = rows.iter().map(|r| r["name"].as_str().unwrap()).collect();
+    assert_eq!(names, ["QuietStone", "WarmBirch", "ZeroclawWorker"]);
+    assert_eq!(rows[2]["phase"], "active");
+    assert_eq!(rows[2]["autonomy"], "full");
+
+    let v = amp_json(&["list", d, "--json", "--sort", "name", "--reverse"], 0);
+    assert_eq!(v[0]["name"], "ZeroclawWorker");
+}
+
+#[test]
+fn list_and_fleet_recursive() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    std::fs::create_dir_all(root.join("platform/ops")).unwrap();
+    std::fs::create_dir_all(root.join(".ampersona")).unwrap();
+    let copy = |example: &str, dest: &str| {
+        std::fs::copy(
+            workspace_root().join(format!("examples/{example}.json")),
+            root.join(dest),
+        )
+        .unwrap();
+    };
+    copy("warm_birch", "warm_birch.json");
+    copy("quiet_stone_v1", "platform/quiet_stone.json");
+    copy("zeroclaw_agent", "platform/ops/zeroclaw.json");
+    std::fs::write(root.join("platform/ops/zeroclaw.state.json"), "{}").unwrap();
+    std::fs::write(root.join(".ampersona/defaults.json"), "{}").unwrap();
+    #[cfg(unix)]
+    std::os::unix::fs::symlink(root, root.join("platform/ops/loop")).unwrap();
+    let d = root.to_str().unwrap();
+
+    let v = amp_json(&["list", d, "--json"], 0);
+    assert_eq!(v.as_array().unwrap().len(), 1);
+
+    let v = amp_json(&["list", d, "--json", "--recursive"], 0);
+    let files: Vec<&str> = v
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|r| r["file"].as_str().unwrap())
+        .collect();
+    assert_eq!(
+        files,
+        [
+            "platform/ops/zeroclaw.json",
+            "platform/quiet_stone.json",
+            "warm_birch.json"
+        ]
+    );
+
+    let csv = amp_stdout(&["fleet", d, "--recursive", "--status", "--csv"]);
+    assert!(
+        csv.contains("platform/ops/zeroclaw.json,ZeroclawWorker,full,-"),
+        "{csv}"
+    );
+    let v = amp_json(&["fleet", d, "--recursive", "--check", "--json"], 0);
+    assert_eq!(v.as_array().unwrap().len(), 3);
+}
+
+#[test]
+fn list_signed_and_audit_columns() {
+    let dir = tempfile::tempdir().unwrap();
+    for name in ["signed", "audited", "plain"] {
+        std::fs::copy(
+            workspace_root().join("examples/zeroclaw_agent.json"),
+            dir.path().join(format!("{name}.json")),
+        )
+        .unwrap();
+    }
+    let key_path = dir.path().join("sign.key");
+    std::fs::write(&key_path, [0x11u8; 32]).unwrap();
+    let signed = dir.path().join("signed.json");
+    amp_stdout(&[
+        "sign",
+        signed.to_str().unwrap(),
+        "--key",
+        key_path.to_str().unwrap(),
+    ]);
+    let audited = dir.path().join("audited.json");
+    let metrics = workspace_root().join("examples/zeroclaw_metrics.json");
+    amp_bin()
+        .args(["gate", audited.to_str().unwrap(), "--evaluate", "*"])
+        .arg("--metrics")
+        .arg(&metrics)
+        .status()
+        .unwrap();
+    assert!(dir.path().join("audited.audit.jsonl").exists());
+
+    let v = amp_json(&["list", dir.path().to_str().unwrap(), "--json"], 0);
+    let flags: Vec<(&str, bool, bool)> = v
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|r| {
+            (
+                r["file"].as_str().unwrap(),
+                r["signed"].as_bool().unwrap(),
+                r["has_audit"].as_bool().unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        flags,
+        [
+            ("audited.json", false, true),
+            ("plain.json", false, false),
+            ("signed.json", true, false),
+        ]
+    );
+
+    let table = amp_stdout(&["list", dir.path().to_str().unwrap()]);
+    assert!(table.lines().next().unwrap().ends_with("SIGNED  AUDIT"));
+}
+
+#[test]
+fn index_over_examples_matches_files() {
+    let dir = tempfile::tempdir().unwrap();
+    let out = dir.path().join("index.json");
+    amp_stdout(&["index", "examples", "--out", out.to_str().unwrap()]);
+    let index: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&out).unwrap()).unwrap();
+    assert_eq!(index["index_version"], "1");
+
+    let mut expected: Vec<String> = std::fs::read_dir(workspace_root().join("examples"))
+        .unwrap()
+        .map(|e| e.unwrap().file_name().to_string_lossy().into_owned())
+        .filter(|n| n.ends_with(".json") && !n.ends_with(".state.json"))
+        .collect();
+    expected.sort();
+    let entries = index["personas"].as_array().unwrap();
+    let files: Vec<&str> = entries
+        .iter()
+        .map(|e| e["file"].as_str().unwrap())
+        .collect();
+    assert_eq!(files, expected);
+
+    for entry in entries {
+        let path = format!("examples/{}", entry["file"].as_str().unwrap());
+        let data: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(workspace_root().join(&path)).unwrap())
+                .unwrap();
+        assert_eq!(
+            entry["fingerprint"].as_str().unwrap(),
+            amp_stdout(&["fingerprint", &path]).trim()
+        );
+        assert_eq!(
+            entry["name"],
+            data.get("name").cloned().unwrap_or("-".into())
+        );
+        assert_eq!(entry["signed"], data.get("signature").is_some());
+    }
+    let zeroclaw = entries
+        .iter()
+        .find(|e| e["file"] == "zeroclaw_agent.json")
+        .unwrap();
+    assert_eq!(zeroclaw["autonomy"], "full");
+    assert_eq!(zeroclaw["phase"], "-");
+
+    // Without --out the same index goes to stdout
+    let v = amp_json(&["index", "examples"], 0);
+    assert_eq!(v, index);
+}
+
+// ── Agent_mail register (5) ─────────────────────────────────────
+
+#[test]
+fn agent_mail_register_mcp_payload() {
+    let v = amp_json(
+        &[
+            "register",
+            "examples/agent_mail_worker.json",
+            "--project",
+            "/data/projects/test",
+            "--rpc",
+        ],
+        0,
+    );
+    // Should be a JSON-RPC envelope
+    assert_eq!(v["jsonrpc"], "2.0");
+    assert!(v["params"]["arguments"]["name"].as_str().is_some());
+}
+
+#[test]
+fn agent_mail_register_with_prompt() {
+    let v = amp_json(
+        &[
+            "register",
+            "examples/agent_mail_worker.json",
+            "--project",
+            "/data/projects/test",
+            "--prompt",
+            "--toon",
+            "--rpc",
+        ],
+        0,
+    );
+    let task_desc = v["params"]["arguments"]["task_description"]
+        .as_str()
+        .unwrap();
+    assert!(
+        !task_desc.is_empty(),
+        "task_description should contain prompt"
+    );
+}
+
+#[test]
+fn agent_mail_register_tools_from_skills() {
+    let v = amp_json(
+        &[
+            "register",
+            "examples/agent_mail_worker.json",
+            "--project",
+            "/data/projects/test",
+            "--tools",
+            "--rpc",
+        ],
+        0,
+    );
+    let tools = v["params"]["arguments"]["tools"].as_array().unwrap();
+    let names: Vec<_> = tools.iter().map(|t| t["name"].as_str().unwrap()).collect();
+    assert_eq!(names, vec!["messaging", "coordination", "status_reporting"]);
+    assert_eq!(
+        tools[0]["description"],
+        "Send and receive inter-agent messages."
+    );
+    assert!(tools[0].get("priority").is_none());
+
+    // Without --tools the payload is unchanged
+    let v = amp_json(
+        &[
+            "register",
+            "examples/agent_mail_worker.json",
+            "--project",
+            "/data/projects/test",
+        ],
+        0,
+    );
+    assert!(v.get("tools").is_none());
+}
+
+#[test]
+fn agent_mail_register_flags_denied_capabilities() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("agent.json");
+    let persona = serde_json::json!({
+        "version": "1.0",
+        "name": "ShellAgent",
+        "role": "worker",
+        "capabilities": {
+            "skills": [
+                {"name": "shell_execution", "description": "Run shell commands.", "priority": 1},
+                {"name": "messaging", "description": "Talk to agents.", "priority": 2}
+            ]
+        },
+        "authority": {
+            "autonomy": "supervised",
+            "actions": {
+                "deny": [{"action": "run_command", "reason": "sandboxed"}]
+            }
+        }
+    });
+    std::fs::write(&path, serde_json::to_string_pretty(&persona).unwrap()).unwrap();
+    let file = path.to_str().unwrap();
+
+    // Default: warn on stderr, still emit the payload
+    let out = amp_bin()
+        .args(["register", file, "--project", "/data/projects/test"])
+        .output()
+        .unwrap();
+    assert!(out.status.success());
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(stderr.contains("'shell_execution'"), "{stderr}");
+    assert!(stderr.contains("run_command"), "{stderr}");
+    assert!(!stderr.contains("messaging"), "{stderr}");
+
+    // --strict: refuse to register
+    let out = amp_bin()
+        .args([
+            "register",
+            file,
+            "--project",
+            "/data/projects/test",
+            "--strict",
+        ])
+        .output()
+        .unwrap();
+    assert_eq!(out.status.code(), Some(1));
+    assert!(out.stdout.is_empty());
+    assert!(String::from_utf8_lossy(&out.stderr).contains("shell_execution"));
+}
+
+#[test]
+fn agent_mail_register_batch() {
+    let dir = tempfile::tempdir().unwrap();
+    for name in ["agent_mail_worker", "zeroclaw_agent"] {
+        std::fs::copy(
+            workspace_root().join(format!("examples/{name}.json")),
+            dir.path().join(format!("{name}.json")),
+        )
+        .unwrap();
+    }
+    std::fs::write(dir.path().join("zeroclaw_agent.state.json"), "{}").unwrap();
+    let fleet = dir.path().to_str().unwrap();
+
+    let v = amp_json(
+        &[
+            "register",
+            "--batch",
+            fleet,
+            "--project",
+            "/data/projects/test",
+            "--program",
+            "fleet",
+        ],
+        0,
+    );
+    let calls = v.as_array().unwrap();
+    assert_eq!(calls.len(), 2);
+    assert_eq!(calls[0]["name"], "MailWorker");
+    assert_eq!(calls[1]["name"], "ZeroclawWorker");
+    assert!(calls.iter().all(|c| c["program"] == "fleet"));
+
+    let v = amp_json(
+        &[
+            "register",
+            "--batch",
+            fleet,
+            "--project",
+            "/data/projects/test",
+            "--rpc",
+        ],
+        0,
+    );
+    let batch = v.as_array().unwrap();
+    assert_eq!(batch.len(), 2);
+    assert_eq!(batch[0]["id"], "1");
+    assert_eq!(batch[1]["id"], "2");
+    assert_eq!(batch[1]["params"]["arguments"]["name"], "ZeroclawWorker");
+}
+
+// ── Fleet (7) ───────────────────────────────────────────────────
+
+#[test]
+fn fleet_csv_status_and_check() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::copy(
+        workspace_root().join("examples/zeroclaw_agent.json"),
+        dir.path().join("ops, east.json"),
+    )
+    .unwrap();
+    let fleet = dir.path().to_str().unwrap();
+
+    let status = amp_stdout(&["fleet", fleet, "--status", "--csv"]);
+    let rows: Vec<&str> = status.split("\r\n").collect();
+    assert_eq!(rows[0], "file,name,autonomy,phase");
+    assert_eq!(rows[1], "\"ops, east.json\",ZeroclawWorker,full,-");
+    assert_eq!(rows[2], "");
+
+    let check = amp_stdout(&["fleet", fleet, "--check", "--csv"]);
+    let rows: Vec<&str> = check.lines().collect();
+    assert_eq!(
+        rows,
+        vec!["file,pass,error_count", "\"ops, east.json\",true,0"]
+    );
+}
+
+#[test]
+fn fleet_filter_selects_by_field_and_phase() {
+    let dir = tempfile::tempdir().unwrap();
+    for (name, autonomy) in [
+        ("alpha", "full"),
+        ("bravo", "readonly"),
+        ("charlie", "supervised"),
+    ] {
+        let persona = serde_json::json!({
+            "version": "1.0",
+            "name": format!("Agent{name}"),
+            "role": "test",
+            "authority": {"autonomy": autonomy}
+        });
+        std::fs::write(
+            dir.path().join(format!("{name}.json")),
+            serde_json::to_string(&persona).unwrap(),
+        )
+        .unwrap();
+    }
+    std::fs::write(
+        dir.path().join("charlie.state.json"),
+        r#"{"name":"Agentcharlie","current_phase":"probation","state_rev":1,"active_elevations":[],"last_transition":null,"pending_transition":null,"updated_at":"2026-01-01T00:00:00Z"}"#,
+    )
+    .unwrap();
+    let fleet = dir.path().to_str().unwrap();
+    let files = |filters: &[&str]| -> Vec<String> {
+        let mut args = vec!["fleet", fleet, "--status", "--csv"];
+        for f in filters {
+            args.extend(["--filter", f]);
+        }
+        amp_stdout(&args)
+            .lines()
+            .skip(1)
+            .map(|row| row.split(',').next().unwrap().to_string())
+            .collect()
+    };
+
+    assert_eq!(files(&["authority.autonomy=full"]), vec!["alpha.json"]);
+    assert_eq!(
+        files(&["authority.autonomy!=full"]),
+        vec!["bravo.json", "charlie.json"]
+    );
+    assert_eq!(files(&["phase=probation"]), vec!["charlie.json"]);
+    assert_eq!(files(&["phase=prob*"]), vec!["charlie.json"]);
+    assert_eq!(
+        files(&["name=Agent*", "authority.autonomy!=readonly"]),
+        vec!["alpha.json", "charlie.json"]
+    );
+    assert!(files(&["authority.autonomy=full", "phase=probation"]).is_empty());
+
+    let out = amp_bin()
+        .args(["fleet", fleet, "--status", "--filter", "autonomy"])
+        .output()
+        .unwrap();
+    assert_ne!(out.status.code(), Some(0));
+}
+
+#[test]
+fn fleet_apply_overlay_dry_run_writes_nothing() {
+    let dir = tempfile::tempdir().unwrap();
+    let fleet_dir = dir.path().join("fleet");
+    std::fs::create_dir(&fleet_dir).unwrap();
+    let persona_path = fleet_dir.join("worker.json");
+    std::fs::copy(
+        workspace_root().join("examples/zeroclaw_agent.json"),
+        &persona_path,
+    )
+    .unwrap();
+    let original = std::fs::read_to_string(&persona_path).unwrap();
+    let overlay_path = dir.path().join("overlay.json");
+    std::fs::write(&overlay_path, r#"{"authority": {"autonomy": "readonly"}}"#).unwrap();
+    let fleet = fleet_dir.to_str().unwrap();
+    let overlay = overlay_path.to_str().unwrap();
+
+    let diff = amp_stdout(&["fleet", fleet, "--apply-overlay", overlay, "--dry-run"]);
+    assert!(diff.contains("worker.json"), "{diff}");
+    assert!(
+        diff.contains("+ authority.autonomy: \"readonly\""),
+        "{diff}"
+    );
+    assert_eq!(std::fs::read_to_string(&persona_path).unwrap(), original);
+    assert_eq!(std::fs::read_dir(&fleet_dir).unwrap().count(), 1);
+
+    amp_stdout(&["fleet", fleet, "--apply-overlay", overlay]);
+    let merged: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&persona_path).unwrap()).unwrap();
+    assert_eq!(merged["authority"]["autonomy"], "readonly");
+    assert_eq!(
+        std::fs::read_to_string(fleet_dir.join("worker.bak")).unwrap(),
+        original
+    );
+}
+
+#[test]
+fn no_upgrade_autonomy_rejects_raising_overlay() {
+    let dir = tempfile::tempdir().unwrap();
+    let fleet_dir = dir.path().join("fleet");
+    std::fs::create_dir(&fleet_dir).unwrap();
+    std::fs::copy(
+        workspace_root().join("examples/zeroclaw_agent.json"),
+        fleet_dir.join("worker.json"),
+    )
+    .unwrap();
+    let mut locked: serde_json::Value = serde_json::from_str(
+        &std::fs::read_to_string(workspace_root().join("examples/zeroclaw_agent.json")).unwrap(),
+    )
+    .unwrap();
+    locked["authority"]["autonomy"] = "readonly".into();
+    locked.as_object_mut().unwrap().remove("gates");
+    let locked_path = fleet_dir.join("locked.json");
+    std::fs::write(&locked_path, locked.to_string()).unwrap();
+    let original = std::fs::read_to_string(&locked_path).unwrap();
+    let overlay_path = dir.path().join("overlay.json");
+    std::fs::write(
+        &overlay_path,
+        r#"{"gates": [{"id": "promote", "on_pass": {"authority_overlay": {"autonomy": "full"}}}]}"#,
+    )
+    .unwrap();
+    let fleet = fleet_dir.to_str().unwrap();
+    let overlay = overlay_path.to_str().unwrap();
+
+    let out = amp_bin()
+        .args([
+            "fleet",
+            fleet,
+            "--apply-overlay",
+            overlay,
+            "--no-upgrade-autonomy",
+        ])
+        .output()
+        .unwrap();
+    assert_ne!(out.status.code(), Some(0));
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(
+        stderr
+            .contains("locked.json: /gates/0/on_pass/authority_overlay/autonomy readonly -> full"),
+        "{stderr}"
+    );
+    assert!(!stderr.contains("worker.json:"), "{stderr}");
+    assert_eq!(std::fs::read_to_string(&locked_path).unwrap(), original);
+    assert!(!fleet_dir.join("worker.bak").exists());
+
+    let out = amp_bin()
+        .args([
+            "compose",
+            locked_path.to_str().unwrap(),
+            overlay,
+            "--no-upgrade-autonomy",
+        ])
+        .output()
+        .unwrap();
+    assert_ne!(out.status.code(), Some(0));
+    assert!(out.stdout.is_empty());
+}
 
 #[test]
-fn zeroclaw_authority_allow() {
-    let v = amp_json(
-        &[
-            "authority",
-            "examples/zeroclaw_agent.json",
+fn fleet_check_verifies_signatures() {
+    let dir = tempfile::tempdir().unwrap();
+    let fleet_dir = dir.path().join("fleet");
+    std::fs::create_dir(&fleet_dir).unwrap();
+    for name in ["signed", "tampered", "unsigned"] {
+        std::fs::copy(
+            workspace_root().join("examples/zeroclaw_agent.json"),
+            fleet_dir.join(format!("{name}.json")),
+        )
+        .unwrap();
+    }
+    let key_path = dir.path().join("sign.key");
+    std::fs::write(&key_path, [0xAAu8; 32]).unwrap();
+    let pub_path = dir.path().join("sign.pub");
+    let signing = ed25519_dalek::SigningKey::from_bytes(&[0xAAu8; 32]);
+    std::fs::write(&pub_path, signing.verifying_key().as_bytes()).unwrap();
+    for name in ["signed", "tampered"] {
+        let path = fleet_dir.join(format!("{name}.json"));
+        amp_stdout(&[
+            "sign",
+            path.to_str().unwrap(),
+            "--key",
+            key_path.to_str().unwrap(),
+        ]);
+    }
+    let tampered_path = fleet_dir.join("tampered.json");
+    let mut tampered: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&tampered_path).unwrap()).unwrap();
+    tampered["role"] = serde_json::json!("rogue");
+    std::fs::write(&tampered_path, tampered.to_string()).unwrap();
+
+    let fleet = fleet_dir.to_str().unwrap();
+    let pubkey = pub_path.to_str().unwrap();
+    let sweep = |extra: &[&str], exit: i32| {
+        let mut args = vec![
+            "fleet",
+            fleet,
             "--check",
-            "read_file",
+            "--verify-sig",
+            "--pubkey",
+            pubkey,
+        ];
+        args.extend_from_slice(extra);
+        args.push("--json");
+        amp_json(&args, exit)
+    };
+
+    let v = sweep(&[], 1);
+    let status = |i: usize| v[i]["signature"].as_str().unwrap().to_string();
+    assert_eq!(
+        [status(0), status(1), status(2)],
+        ["valid", "invalid", "unsigned"]
+    );
+    assert_eq!(v[1]["pass"], false);
+    assert!(v[1]["errors"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(|e| e["code"] == "E030"));
+    assert_eq!(v[2]["pass"], true);
+
+    // Unsigned alone is not a failure unless --require-sig
+    std::fs::remove_file(&tampered_path).unwrap();
+    sweep(&[], 0);
+    let v = sweep(&["--require-sig"], 1);
+    assert_eq!(v[1]["errors"][0]["code"], "E031");
+}
+
+#[test]
+fn fleet_ndjson_streams_one_report_per_line() {
+    let dir = tempfile::tempdir().unwrap();
+    for name in ["alpha", "bravo"] {
+        std::fs::copy(
+            workspace_root().join("examples/zeroclaw_agent.json"),
+            dir.path().join(format!("{name}.json")),
+        )
+        .unwrap();
+    }
+    let fleet = dir.path().to_str().unwrap();
+
+    let parse = |out: &[u8]| -> Vec<serde_json::Value> {
+        std::str::from_utf8(out)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).expect("each line is one JSON value"))
+            .collect()
+    };
+    let out = amp_bin()
+        .args(["fleet", fleet, "--check", "--ndjson"])
+        .output()
+        .unwrap();
+    assert!(out.status.success());
+    let lines = parse(&out.stdout);
+    assert_eq!(lines.len(), 2);
+    assert!(lines.iter().all(|l| l["pass"] == true));
+
+    // A file that fails to load mid-stream ends it with an error line
+    std::fs::write(dir.path().join("charlie.json"), "{ not json").unwrap();
+    let out = amp_bin()
+        .args(["fleet", fleet, "--check", "--ndjson"])
+        .output()
+        .unwrap();
+    assert_eq!(out.status.code(), Some(1));
+    let lines = parse(&out.stdout);
+    assert_eq!(lines.len(), 3);
+    assert_eq!(lines[2]["error"], true);
+    assert!(lines[2]["message"]
+        .as_str()
+        .unwrap()
+        .contains("charlie.json"));
+}
+
+#[test]
+fn fleet_evaluate_runs_each_gate_with_its_own_metrics() {
+    let dir = tempfile::tempdir().unwrap();
+    let metrics = tempfile::tempdir().unwrap();
+    for name in ["a", "b", "c"] {
+        std::fs::copy(
+            workspace_root().join("examples/zeroclaw_agent.json"),
+            dir.path().join(format!("{name}.json")),
+        )
+        .unwrap();
+    }
+    std::fs::copy(
+        workspace_root().join("examples/zeroclaw_metrics.json"),
+        metrics.path().join("a.json"),
+    )
+    .unwrap();
+    std::fs::write(metrics.path().join("b.json"), r#"{"schema_valid": false}"#).unwrap();
+
+    let report = amp_json(
+        &[
+            "fleet",
+            dir.path().to_str().unwrap(),
+            "--evaluate",
+            "--metrics-dir",
+            metrics.path().to_str().unwrap(),
             "--json",
         ],
         0,
     );
-    assert_eq!(v["decision"], "Allow");
-    assert_eq!(v["action"], "read_file");
+    assert_eq!(report["evaluated"], 2);
+    assert_eq!(report["skipped"], 1);
+    assert_eq!(report["failed"], 0);
+    let agents = report["agents"].as_array().unwrap();
+    assert_eq!(agents[0]["file"], "a.json");
+    assert_eq!(agents[0]["result"]["gate_id"], "onboarding");
+    assert_eq!(agents[0]["result"]["decision"], "transition");
+    assert!(agents[1]["result"].is_null(), "no gate fires for b");
+    assert!(agents[2]["skipped"].is_string());
+
+    assert!(dir.path().join("a.state.json").exists());
+    assert!(!dir.path().join("c.state.json").exists());
 }
 
 #[test]
-fn zeroclaw_authority_deny_unknown() {
+fn fleet_pending_lists_only_agents_awaiting_approval() {
+    let dir = tempfile::tempdir().unwrap();
+    for name in ["waiting", "idle"] {
+        let persona = serde_json::json!({
+            "version": "1.0",
+            "name": format!("Agent{name}"),
+            "role": "test",
+        });
+        std::fs::write(
+            dir.path().join(format!("{name}.json")),
+            serde_json::to_string(&persona).unwrap(),
+        )
+        .unwrap();
+    }
+    let state = serde_json::json!({
+        "name": "Agentwaiting",
+        "current_phase": "active",
+        "state_rev": 1,
+        "active_elevations": [],
+        "last_transition": null,
+        "pending_transition": {
+            "gate_id": "trusted",
+            "from_phase": "active",
+            "to_phase": "trusted",
+            "decision": "pending_human",
+            "metrics_hash": "sha256:00",
+            "state_rev": 1,
+            "created_at": "2026-01-01T00:00:00Z",
+        },
+        "updated_at": "2026-01-01T00:00:00Z"
+    });
+    std::fs::write(dir.path().join("waiting.state.json"), state.to_string()).unwrap();
+    let fleet = dir.path().to_str().unwrap();
+
+    let rows = amp_json(&["fleet", fleet, "--pending", "--json"], 0);
+    let rows = rows.as_array().unwrap();
+    assert_eq!(rows.len(), 1, "only the waiting agent: {rows:?}");
+    assert_eq!(rows[0]["file"], "waiting.json");
+    assert_eq!(rows[0]["name"], "Agentwaiting");
+    assert_eq!(rows[0]["gate_id"], "trusted");
+    assert_eq!(rows[0]["to_phase"], "trusted");
+    assert!(rows[0]["age_seconds"].as_u64().unwrap() > 0);
+
+    let waiting = dir.path().join("waiting.json");
+    let shown = amp_json(
+        &["gate", waiting.to_str().unwrap(), "--pending", "--json"],
+        0,
+    );
+    assert_eq!(shown["pending"]["gate_id"], "trusted");
+    assert_eq!(shown["pending"]["from_phase"], "active");
+    let idle = dir.path().join("idle.json");
+    let shown = amp_json(&["gate", idle.to_str().unwrap(), "--pending", "--json"], 0);
+    assert_eq!(shown["name"], "Agentidle");
+    assert!(shown["pending"].is_null());
+}
+
+// ── Status (4) ──────────────────────────────────────────────────
+
+#[test]
+fn state_dir_keeps_sidecars_out_of_the_persona_dir() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::create_dir(dir.path().join("personas")).unwrap();
+    std::fs::copy(
+        workspace_root().join("examples/zeroclaw_agent.json"),
+        dir.path().join("personas/agent.json"),
+    )
+    .unwrap();
+    let metrics = workspace_root().join("examples/zeroclaw_metrics.json");
+    let amp = |args: &[&str]| {
+        let out = amp_bin()
+            .current_dir(dir.path())
+            .args(args)
+            .args(["--state-dir", ".run", "--json"])
+            .output()
+            .unwrap();
+        assert!(
+            out.status.success(),
+            "{}",
+            String::from_utf8_lossy(&out.stderr)
+        );
+        serde_json::from_slice::<serde_json::Value>(&out.stdout).unwrap()
+    };
+
+    // Reading creates nothing; the first write creates the mirror directory.
+    amp(&["status", "personas/agent.json"]);
+    assert!(!dir.path().join(".run").exists());
+    let record = amp(&[
+        "gate",
+        "personas/agent.json",
+        "--evaluate",
+        "onboarding",
+        "--metrics",
+        metrics.to_str().unwrap(),
+    ]);
+    assert_eq!(record["decision"], "transition");
+    assert!(dir.path().join(".run/personas/agent.state.json").exists());
+    assert!(dir.path().join(".run/personas/agent.audit.jsonl").exists());
+    let beside: Vec<_> = std::fs::read_dir(dir.path().join("personas"))
+        .unwrap()
+        .map(|e| e.unwrap().file_name())
+        .collect();
+    assert_eq!(beside, vec!["agent.json"]);
+
+    // Found again by the next command, however the persona is spelled.
+    let abs = dir.path().join("personas/agent.json");
+    for persona in [
+        "personas/agent.json",
+        "./personas/agent.json",
+        abs.to_str().unwrap(),
+    ] {
+        let status = amp(&["status", persona]);
+        assert_eq!(status["phase"], "active", "{persona}");
+    }
+}
+
+#[test]
+fn status_drift_window_filters_entries() {
+    let dir = tempfile::tempdir().unwrap();
+    let persona_path = dir.path().join("agent.json");
+    std::fs::copy(
+        workspace_root().join("examples/zeroclaw_agent.json"),
+        &persona_path,
+    )
+    .unwrap();
+    let persona = persona_path.to_str().unwrap();
+    let drift: String = (1..=9)
+        .map(|day| {
+            let entry = serde_json::json!({
+                "prev_hash": "genesis",
+                "metrics": {"day": day},
+                "ts": format!("2026-03-0{day}T12:00:00Z"),
+            });
+            format!("{entry}\n")
+        })
+        .collect();
+    std::fs::write(dir.path().join("agent.drift.jsonl"), drift).unwrap();
+    let days = |v: &serde_json::Value| -> Vec<u64> {
+        v["drift"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|e| e["metrics"]["day"].as_u64().unwrap())
+            .collect()
+    };
+
+    let v = amp_json(&["status", persona, "--json", "--drift"], 0);
+    assert_eq!(v["drift_entries"], 9);
+    assert_eq!(days(&v), [5, 6, 7, 8, 9]);
+
     let v = amp_json(
         &[
-            "authority",
-            "examples/zeroclaw_agent.json",
-            "--check",
-            "unknown_action",
+            "status",
+            persona,
             "--json",
+            "--drift",
+            "--since",
+            "2026-03-02T00:00:00Z",
+            "--until",
+            "2026-03-06T12:00:00+00:00",
         ],
-        1,
+        0,
     );
-    assert_eq!(v["decision"], "Deny");
+    assert_eq!(v["drift_entries"], 5);
+    assert_eq!(v["last_drift"]["metrics"]["day"], 6);
+    assert_eq!(days(&v), [2, 3, 4, 5, 6]);
+
+    let v = amp_json(
+        &[
+            "status",
+            persona,
+            "--json",
+            "--drift",
+            "--since",
+            "2026-03-02T00:00:00Z",
+            "--limit",
+            "2",
+        ],
+        0,
+    );
+    assert_eq!(v["drift_entries"], 8);
+    assert_eq!(days(&v), [8, 9]);
+
+    let out = amp_bin()
+        .args(["status", persona, "--drift", "--since", "yesterday"])
+        .output()
+        .unwrap();
+    assert_eq!(out.status.code(), Some(2));
 }
 
 #[test]
-fn zeroclaw_scoped_shell_blocked() {
+fn status_drift_trend_shows_rising_metric() {
+    let dir = tempfile::tempdir().unwrap();
+    let persona_path = dir.path().join("agent.json");
+    std::fs::copy(
+        workspace_root().join("examples/zeroclaw_agent.json"),
+        &persona_path,
+    )
+    .unwrap();
+    let persona = persona_path.to_str().unwrap();
+    let samples = [
+        serde_json::json!({"error_rate": 0.01, "uptime": 99.9}),
+        serde_json::json!({"error_rate": 0.02}),
+        serde_json::json!({"error_rate": 0.05, "uptime": 98.0}),
+    ];
+    let drift: String = samples
+        .iter()
+        .enumerate()
+        .map(|(i, metrics)| {
+            let entry = serde_json::json!({
+                "prev_hash": "genesis",
+                "metrics": metrics,
+                "ts": format!("2026-03-0{}T12:00:00Z", i + 1),
+            });
+            format!("{entry}\n")
+        })
+        .collect();
+    std::fs::write(dir.path().join("agent.drift.jsonl"), drift).unwrap();
+
+    let stderr = |extra: &[&str]| {
+        let out = amp_bin()
+            .args(["status", persona, "--drift"])
+            .args(extra)
+            .output()
+            .unwrap();
+        assert!(out.status.success());
+        String::from_utf8(out.stderr).unwrap()
+    };
+    let text = stderr(&[]);
+    assert!(text.contains("error_rate: ▁▃█ ↑"), "{text}");
+    assert!(text.contains("uptime: █▁ ↓"), "{text}");
+    let plain = stderr(&["--plain"]);
+    assert!(
+        plain.contains("error_rate: 0.01 0.02 0.05 (rising)"),
+        "{plain}"
+    );
+
+    let v = amp_json(&["status", persona, "--json", "--drift"], 0);
+    assert_eq!(v["drift_series"][0]["metric"], "error_rate");
+    assert_eq!(v["drift_series"][0]["trend"], "rising");
+    assert_eq!(
+        v["drift_series"][1]["values"],
+        serde_json::json!([99.9, 98.0])
+    );
+}
+
+#[test]
+fn sidecars_follow_file_stem_and_explicit_paths() {
+    let dir = tempfile::tempdir().unwrap();
+    let nested = dir.path().join("cfg.json.d");
+    std::fs::create_dir(&nested).unwrap();
+    let persona_path = nested.join("v1.0.json");
+    std::fs::copy(
+        workspace_root().join("examples/zeroclaw_agent.json"),
+        &persona_path,
+    )
+    .unwrap();
+    let persona = persona_path.to_str().unwrap();
+    let metrics = workspace_root().join("examples/zeroclaw_metrics.json");
+    let metrics = metrics.to_str().unwrap();
+
+    // `.json` in the directory name is left alone.
+    amp_json(
+        &[
+            "gate",
+            persona,
+            "--evaluate",
+            "*",
+            "--metrics",
+            metrics,
+            "--json",
+        ],
+        0,
+    );
+    for sidecar in ["v1.0.state.json", "v1.0.audit.jsonl", "v1.0.drift.jsonl"] {
+        assert!(nested.join(sidecar).exists(), "missing {sidecar}");
+    }
+    let v = amp_json(&["status", persona, "--json"], 0);
+    assert_eq!(v["phase"], "active");
+
+    // An explicit --state moves the other defaults next to it.
+    let run = dir.path().join("run.state.json");
+    let run = run.to_str().unwrap();
+    let v = amp_json(&["status", persona, "--json", "--state", run], 0);
+    assert!(v["phase"].is_null());
+    amp_json(
+        &[
+            "gate",
+            persona,
+            "--evaluate",
+            "*",
+            "--metrics",
+            metrics,
+            "--json",
+            "--state",
+            run,
+        ],
+        0,
+    );
+    assert!(dir.path().join("run.audit.jsonl").exists());
     let v = amp_json(
         &[
-            "authority",
-            "examples/zeroclaw_agent.json",
-            "--check",
-            "run_command",
-            "--context",
-            "command=echo $(whoami)",
+            "audit",
+            persona,
+            "--verify",
             "--json",
+            "--audit",
+            dir.path().join("run.audit.jsonl").to_str().unwrap(),
         ],
-        1,
+        0,
     );
-    assert_eq!(v["decision"], "Deny");
+    assert_eq!(v["valid"], true);
 }
 
+// ── Report (1) ──────────────────────────────────────────────────
+
 #[test]
-fn authority_path_scope_forbidden() {
+fn report_lists_compliance_refs_and_signature() {
+    let dir = tempfile::tempdir().unwrap();
+    let persona_path = dir.path().join("quality.json");
+    std::fs::copy(
+        workspace_root().join("examples/odoov19_quality.json"),
+        &persona_path,
+    )
+    .unwrap();
+    let persona = persona_path.to_str().unwrap();
+    let key_path = dir.path().join("sign.key");
+    std::fs::write(&key_path, [0x5Au8; 32]).unwrap();
+    let pub_path = dir.path().join("sign.pub");
+    let public = ed25519_dalek::SigningKey::from_bytes(&[0x5Au8; 32]).verifying_key();
+    std::fs::write(&pub_path, public.as_bytes()).unwrap();
+    amp_stdout(&["sign", persona, "--key", key_path.to_str().unwrap()]);
+
+    let report = amp_json(
+        &[
+            "report",
+            persona,
+            "--pubkey",
+            pub_path.to_str().unwrap(),
+            "--format",
+            "json",
+        ],
+        0,
+    );
+    assert_eq!(report["signature"]["status"], "valid");
+    assert_eq!(
+        report["persona"]["fingerprint"],
+        amp_stdout(&["fingerprint", persona]).trim()
+    );
+
+    // A field added after signing is not vouched for.
+    let signed = std::fs::read_to_string(&persona_path).unwrap();
+    let mut extended: serde_json::Value = serde_json::from_str(&signed).unwrap();
+    extended["phases"] = serde_json::json!({});
+    std::fs::write(&persona_path, extended.to_string()).unwrap();
+    let unsigned = amp_json(
+        &[
+            "report",
+            persona,
+            "--pubkey",
+            pub_path.to_str().unwrap(),
+            "--format",
+            "json",
+        ],
+        0,
+    );
+    assert_eq!(unsigned["signature"]["status"], "invalid");
+    assert_eq!(
+        unsigned["signature"]["error"],
+        "field 'phases' is not covered by the signature"
+    );
+    std::fs::write(&persona_path, &signed).unwrap();
+    let refs = report["compliance"]["refs"].as_array().unwrap();
+    let capa = refs
+        .iter()
+        .find(|r| r["action"] == "auto_approve_capa")
+        .unwrap();
+    assert_eq!(capa["compliance_ref"], "ISO 9001:2015 \u{a7}10.2");
+    let denied = report["authority"]["matrix"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|r| r["action"] == "auto_approve_capa")
+        .unwrap();
+    assert_eq!(denied["decision"], "Deny");
+    assert_eq!(report["audit"]["present"], false);
+
+    let markdown = amp_stdout(&["report", persona]);
+    assert!(markdown.starts_with("# Compliance Report: "));
+    assert!(markdown.contains("| auto_approve_capa | ISO 9001:2015 \u{a7}10.2 |"));
+    assert!(markdown.contains("- **Status:** not_checked"));
+}
+
+// ── Doctor (1) ──────────────────────────────────────────────────
+
+#[test]
+fn doctor_reports_seeded_problems_and_fixes_stale_lock() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    let persona_path = root.join("agent.json");
+    std::fs::copy(
+        workspace_root().join("examples/zeroclaw_agent.json"),
+        &persona_path,
+    )
+    .unwrap();
+    let persona = persona_path.to_str().unwrap();
+    let metrics = workspace_root().join("examples/zeroclaw_metrics.json");
+    amp_bin()
+        .args(["gate", persona, "--evaluate", "*", "--metrics"])
+        .arg(&metrics)
+        .status()
+        .unwrap();
+    amp_stdout(&["audit", persona, "--checkpoint-create"]);
+    // Truncate the log below the checkpoint, orphan a drift file, leave a dead lock
+    std::fs::write(root.join("agent.audit.jsonl"), "").unwrap();
+    std::fs::write(root.join("retired.drift.jsonl"), "").unwrap();
+    let lock = root.join("agent.state.json.lock");
+    std::fs::write(&lock, "4294967295\n0\n").unwrap();
+    let d = root.to_str().unwrap();
+
+    let v = amp_json(&["doctor", d, "--json"], 1);
+    assert_eq!(v["healthy"], false);
+    let codes: Vec<&str> = v["findings"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|f| f["code"].as_str().unwrap())
+        .collect();
+    assert_eq!(
+        codes,
+        ["checkpoint", "stale_lock", "orphan_sidecar", "defaults"]
+    );
+    assert_eq!(v["findings"][0]["severity"], "error");
+    assert!(v["findings"][0]["message"]
+        .as_str()
+        .unwrap()
+        .contains("truncated"));
+
+    let v = amp_json(&["doctor", d, "--fix", "--json"], 1);
+    assert_eq!(v["fixed"][0]["code"], "stale_lock");
+    assert!(!lock.exists());
+    assert_eq!(v["findings"].as_array().unwrap().len(), 3);
+}
+
+// ── Audit (7) ───────────────────────────────────────────────────
+
+#[test]
+fn audit_verify_json() {
+    // Persona with no audit log → valid with 0 entries
     let v = amp_json(
         &[
-            "authority",
+            "audit",
             "examples/zeroclaw_agent.json",
-            "--check",
-            "read_file",
-            "--path",
-            "secrets/key.pem",
+            "--verify",
             "--json",
         ],
-        1,
+        0,
     );
-    assert_eq!(v["decision"], "Deny");
+    assert_eq!(v["valid"], true);
+    assert!(v["entries"].as_u64().is_some());
 }
 
 #[test]
-fn odoov19_deny_with_compliance_ref() {
+fn audit_checkpoint_timestamp_failure_writes_nothing() {
+    // Unreachable TSA (or a build without `tsa`) must not leave an un-anchored checkpoint
+    let dir = tempfile::tempdir().unwrap();
+    let persona = dir.path().join("agent.json");
+    std::fs::write(&persona, "{}").unwrap();
+    std::fs::write(dir.path().join("agent.audit.jsonl"), "").unwrap();
+
+    let out = amp_bin()
+        .args([
+            "audit",
+            persona.to_str().unwrap(),
+            "--checkpoint-create",
+            "--timestamp-url",
+            "http://127.0.0.1:1/tsr",
+        ])
+        .output()
+        .unwrap();
+    assert_eq!(out.status.code(), Some(1));
+    assert!(!dir.path().join("agent.checkpoint.json").exists());
+}
+
+#[test]
+fn audit_bundle_round_trip() {
+    let src = tempfile::tempdir().unwrap();
+    let persona_path = src.path().join("agent.json");
+    std::fs::copy(
+        workspace_root().join("examples/zeroclaw_agent.json"),
+        &persona_path,
+    )
+    .unwrap();
+    let persona = persona_path.to_str().unwrap();
+    let metrics = workspace_root().join("examples/zeroclaw_metrics.json");
+    amp_bin()
+        .args(["gate", persona, "--evaluate", "*", "--metrics"])
+        .arg(&metrics)
+        .status()
+        .unwrap();
+    let audit = std::fs::read_to_string(src.path().join("agent.audit.jsonl")).unwrap();
+
+    let key_path = src.path().join("sign.key");
+    std::fs::write(&key_path, [0x42u8; 32]).unwrap();
+    let pub_path = src.path().join("sign.pub");
+    let public = ed25519_dalek::SigningKey::from_bytes(&[0x42u8; 32]).verifying_key();
+    std::fs::write(&pub_path, public.as_bytes()).unwrap();
+
+    let bundle_path = src.path().join("handoff.bundle.json");
+    let bundle = bundle_path.to_str().unwrap();
     let v = amp_json(
         &[
-            "authority",
-            "examples/odoov19_quality.json",
-            "--check",
-            "auto_approve_capa",
+            "audit",
+            persona,
+            "--export",
+            bundle,
+            "--sign-key",
+            key_path.to_str().unwrap(),
             "--json",
         ],
-        1,
+        0,
     );
-    assert_eq!(v["decision"], "Deny");
-    assert!(v["deny_entry"]["compliance_ref"]
-        .as_str()
-        .unwrap()
-        .contains("ISO 9001"));
-}
+    assert_eq!(v["chain"]["valid"], true);
+    assert_eq!(v["signed"], true);
 
-#[test]
-fn agent_mail_authority_allow() {
+    let dest = tempfile::tempdir().unwrap();
     let v = amp_json(
         &[
-            "authority",
-            "examples/agent_mail_worker.json",
-            "--check",
-            "send_message",
+            "audit",
+            bundle,
+            "--import-bundle",
+            "--dest",
+            dest.path().to_str().unwrap(),
+            "--verify-key",
+            pub_path.to_str().unwrap(),
             "--json",
         ],
         0,
     );
-    assert_eq!(v["decision"], "Allow");
-}
+    assert_eq!(v["valid"], true);
+    assert_eq!(v["chain_valid"], true);
+    assert_eq!(
+        std::fs::read_to_string(dest.path().join("agent.audit.jsonl")).unwrap(),
+        audit
+    );
+    assert!(dest.path().join("agent.state.json").exists());
+    let imported = dest.path().join("agent.json");
+    amp_json(
+        &["audit", imported.to_str().unwrap(), "--verify", "--json"],
+        0,
+    );
 
-#[test]
-fn authority_exit_code_needs_approval() {
-    // quiet_stone_v1 has supervised autonomy + require_approval_for
+    // Edited bundle no longer matches its signature → nothing imported
+    let mut doc: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&bundle_path).unwrap()).unwrap();
+    doc["audit"][0]["event_type"] = serde_json::json!("Forged");
+    std::fs::write(&bundle_path, doc.to_string()).unwrap();
+    let dest2 = tempfile::tempdir().unwrap();
     let v = amp_json(
         &[
-            "authority",
-            "examples/quiet_stone_v1.json",
-            "--check",
-            "read_file",
+            "audit",
+            bundle,
+            "--import-bundle",
+            "--dest",
+            dest2.path().to_str().unwrap(),
+            "--verify-key",
+            pub_path.to_str().unwrap(),
             "--json",
         ],
-        2,
+        1,
     );
-    assert_eq!(v["decision"], "NeedsApproval");
+    assert_eq!(v["valid"], false);
+    assert!(!dest2.path().join("agent.json").exists());
 }
 
-// ── Workspace Defaults (2) ──────────────────────────────────────
-
 #[test]
-fn workspace_init_creates_defaults_file() {
+fn verify_all_sidecars_sweep() {
     let dir = tempfile::tempdir().unwrap();
-
-    let out = amp_bin()
-        .current_dir(dir.path())
-        .args(["init", "--workspace"])
-        .output()
+    let persona_path = dir.path().join("agent.json");
+    std::fs::copy(
+        workspace_root().join("examples/zeroclaw_agent.json"),
+        &persona_path,
+    )
+    .unwrap();
+    let persona = persona_path.to_str().unwrap();
+    let key_path = dir.path().join("sign.key");
+    std::fs::write(&key_path, [0x5Au8; 32]).unwrap();
+    let pub_path = dir.path().join("sign.pub");
+    let public = ed25519_dalek::SigningKey::from_bytes(&[0x5Au8; 32]).verifying_key();
+    std::fs::write(&pub_path, public.as_bytes()).unwrap();
+    let pubkey = pub_path.to_str().unwrap();
+
+    amp_stdout(&["sign", persona, "--key", key_path.to_str().unwrap()]);
+    let metrics = workspace_root().join("examples/zeroclaw_metrics.json");
+    amp_bin()
+        .args(["gate", persona, "--evaluate", "*", "--metrics"])
+        .arg(&metrics)
+        .status()
         .unwrap();
-    assert!(
-        out.status.success(),
-        "init --workspace failed: {}",
-        String::from_utf8_lossy(&out.stderr)
-    );
-
-    let defaults_path = dir.path().join(".ampersona/defaults.json");
-    assert!(defaults_path.exists(), "defaults file was not created");
+    amp_stdout(&[
+        "audit",
+        persona,
+        "--checkpoint-create",
+        "--sign-key",
+        key_path.to_str().unwrap(),
+    ]);
+
+    let sweep = |exit: i32| {
+        amp_json(
+            &["verify", persona, "--pubkey", pubkey, "--all-sidecars"],
+            exit,
+        )
+    };
+    let v = sweep(0);
+    assert_eq!(v["valid"], true);
+    for check in ["signature", "audit_chain", "checkpoint", "state_rev"] {
+        assert_eq!(v["checks"][check]["valid"], true, "{check}: {v}");
+    }
+    assert_eq!(v["checks"]["checkpoint"]["present"], true);
+    assert_eq!(v["checks"]["checkpoint"]["signed_by_pubkey"], true);
+    assert_eq!(v["checks"]["state_rev"]["present"], true);
 
-    let defaults_text = std::fs::read_to_string(&defaults_path).unwrap();
-    let defaults: serde_json::Value = serde_json::from_str(&defaults_text).unwrap();
-    assert_eq!(defaults["authority"]["autonomy"], "supervised");
+    // Rewriting the checkpointed head fails the sweep; the signature still holds
+    let audit_path = dir.path().join("agent.audit.jsonl");
+    let audit = std::fs::read_to_string(&audit_path).unwrap();
+    std::fs::write(&audit_path, audit.replacen("GateTransition", "Forged", 1)).unwrap();
+    let v = sweep(1);
+    assert_eq!(v["valid"], false);
+    assert_eq!(v["checks"]["signature"]["valid"], true);
+    assert_eq!(v["checks"]["checkpoint"]["valid"], false);
 }
 
 #[test]
-fn workspace_defaults_restrict_authority() {
+fn manifest_sign_verify_detects_state_drift() {
     let dir = tempfile::tempdir().unwrap();
-    let persona_path = dir.path().join("zeroclaw_agent.json");
+    let persona_path = dir.path().join("agent.json");
     std::fs::copy(
         workspace_root().join("examples/zeroclaw_agent.json"),
         &persona_path,
     )
     .unwrap();
     let persona = persona_path.to_str().unwrap();
-
-    // Baseline without workspace defaults: read_file is allowed for zeroclaw example.
-    let baseline = amp_bin()
-        .current_dir(dir.path())
-        .args(["authority", persona, "--check", "read_file", "--json"])
-        .output()
+    let metrics = workspace_root().join("examples/zeroclaw_metrics.json");
+    amp_bin()
+        .args(["gate", persona, "--evaluate", "*", "--metrics"])
+        .arg(&metrics)
+        .status()
         .unwrap();
-    assert_eq!(baseline.status.code(), Some(0));
-    let baseline_json: serde_json::Value = serde_json::from_slice(&baseline.stdout).unwrap();
-    assert_eq!(baseline_json["decision"], "Allow");
 
-    // Add restrictive workspace defaults and verify they are applied.
-    std::fs::create_dir_all(dir.path().join(".ampersona")).unwrap();
-    std::fs::write(
-        dir.path().join(".ampersona/defaults.json"),
-        r#"{"authority":{"autonomy":"readonly"}}"#,
-    )
-    .unwrap();
+    let key_path = dir.path().join("sign.key");
+    std::fs::write(&key_path, [0x42u8; 32]).unwrap();
+    let pub_path = dir.path().join("sign.pub");
+    let public = ed25519_dalek::SigningKey::from_bytes(&[0x42u8; 32]).verifying_key();
+    std::fs::write(&pub_path, public.as_bytes()).unwrap();
+    let (key, pubkey) = (key_path.to_str().unwrap(), pub_path.to_str().unwrap());
+
+    let manifest = amp_json(&["manifest", persona, "--sign", "--key", key, "--json"], 0);
+    assert!(manifest["signature"].is_object());
+    assert!(dir.path().join("agent.manifest.json").exists());
+    let roles: Vec<_> = manifest["files"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|f| f["role"].as_str().unwrap())
+        .collect();
+    assert_eq!(roles, ["persona", "state", "audit", "checkpoint"]);
+    // The manifest is a sidecar: listings and fleet checks skip it
+    let listed = amp_json(&["list", dir.path().to_str().unwrap(), "--json"], 0);
+    assert_eq!(listed.as_array().unwrap().len(), 1, "{listed}");
 
-    let restricted = amp_bin()
-        .current_dir(dir.path())
-        .args(["authority", persona, "--check", "read_file", "--json"])
-        .output()
-        .unwrap();
-    assert_eq!(restricted.status.code(), Some(1));
-    let restricted_json: serde_json::Value = serde_json::from_slice(&restricted.stdout).unwrap();
-    assert_eq!(restricted_json["decision"], "Deny");
-    assert_eq!(restricted_json["autonomy"], "readonly");
-}
+    let v = amp_json(
+        &[
+            "manifest", persona, "--verify", "--pubkey", pubkey, "--json",
+        ],
+        0,
+    );
+    assert_eq!(v["valid"], true);
+    assert!(v["drift"].as_array().unwrap().is_empty());
 
-// ── Gate (3) ────────────────────────────────────────────────────
+    // Editing the state is caught by hash, not by signature
+    let state_path = dir.path().join("agent.state.json");
+    let mut state: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&state_path).unwrap()).unwrap();
+    state["state_rev"] = serde_json::json!(99);
+    std::fs::write(&state_path, serde_json::to_string_pretty(&state).unwrap()).unwrap();
+    let v = amp_json(
+        &[
+            "manifest", persona, "--verify", "--pubkey", pubkey, "--json",
+        ],
+        1,
+    );
+    assert_eq!(v["valid"], false);
+    assert_eq!(v["signature_valid"], true);
+    assert_eq!(v["drift"][0]["file"], "agent.state.json");
+
+    // Rewriting the manifest to match breaks its signature instead
+    let manifest_path = dir.path().join("agent.manifest.json");
+    let mut doc: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&manifest_path).unwrap()).unwrap();
+    doc["files"][1]["sha256"] = v["drift"][0]["actual"].clone();
+    std::fs::write(&manifest_path, doc.to_string()).unwrap();
+    let v = amp_json(
+        &[
+            "manifest", persona, "--verify", "--pubkey", pubkey, "--json",
+        ],
+        1,
+    );
+    assert_eq!(v["signature_valid"], false);
+    assert!(v["drift"].as_array().unwrap().is_empty());
+}
 
+#[cfg(feature = "crypto")]
 #[test]
-fn zeroclaw_gate_evaluate() {
-    // Use tempdir so parallel tests don't interfere via state files.
+fn audit_encryption_round_trip() {
     let dir = tempfile::tempdir().unwrap();
-    let persona_path = dir.path().join("zeroclaw_agent.json");
+    let persona_path = dir.path().join("agent.json");
     std::fs::copy(
         workspace_root().join("examples/zeroclaw_agent.json"),
         &persona_path,
     )
     .unwrap();
-    let metrics_path = dir.path().join("zeroclaw_metrics.json");
-    std::fs::copy(
-        workspace_root().join("examples/zeroclaw_metrics.json"),
-        &metrics_path,
-    )
-    .unwrap();
+    let persona = persona_path.to_str().unwrap();
+    let key_path = dir.path().join("audit.key");
+    std::fs::write(&key_path, "2a".repeat(32)).unwrap();
+    let key = key_path.to_str().unwrap();
+
+    amp_bin()
+        .args(["gate", persona, "--evaluate", "*", "--metrics"])
+        .arg(workspace_root().join("examples/zeroclaw_metrics.json"))
+        .env("AMPERSONA_AUDIT_KEY_FILE", &key_path)
+        .status()
+        .unwrap();
+    let raw = std::fs::read_to_string(dir.path().join("agent.audit.jsonl")).unwrap();
+    assert!(raw.contains("\"enc\""), "{raw}");
+    assert!(!raw.contains("\"gate_id\""), "{raw}");
 
-    // Phase null → onboarding fires first (→active).
-    let out = amp_bin()
-        .args([
-            "gate",
-            persona_path.to_str().unwrap(),
-            "--evaluate",
-            "*",
-            "--metrics",
-            metrics_path.to_str().unwrap(),
-            "--json",
-        ])
-        .output()
-        .expect("failed to run amp");
-    assert!(
-        out.status.success(),
-        "gate failed: {}",
-        String::from_utf8_lossy(&out.stderr)
-    );
-    let v: serde_json::Value = serde_json::from_slice(&out.stdout).expect("invalid JSON");
-    assert_eq!(v["gate_id"], "onboarding");
-    assert!(v["criteria_results"].is_array());
+    // The chain verifies over ciphertext without the key
+    let v = amp_json(&["audit", persona, "--verify", "--json"], 0);
+    assert_eq!(v["valid"], true);
+    assert!(v.get("log").is_none());
+
+    let v = amp_json(&["audit", persona, "--verify", "--key", key, "--json"], 0);
+    let log = v["log"].as_array().unwrap();
+    assert_eq!(log.len() as u64, v["entries"].as_u64().unwrap());
+    assert!(log.iter().any(|e| e["gate_id"].is_string()), "{v}");
+
+    std::fs::write(&key_path, "2b".repeat(32)).unwrap();
+    let v = amp_json(&["audit", persona, "--verify", "--key", key, "--json"], 1);
+    assert_eq!(v["valid"], false);
 }
 
+#[cfg(feature = "notify")]
 #[test]
-fn odoov19_gate_f2() {
-    // Use tempdir so parallel tests don't interfere via state files.
+fn pending_notice_is_sent_after_the_state_lock_is_released() {
+    use std::io::{Read, Write};
+
     let dir = tempfile::tempdir().unwrap();
-    let persona_path = dir.path().join("odoov19_quality.json");
+    let persona_path = dir.path().join("agent.json");
     std::fs::copy(
-        workspace_root().join("examples/odoov19_quality.json"),
+        workspace_root().join("examples/zeroclaw_agent.json"),
         &persona_path,
     )
     .unwrap();
-    let metrics_path = dir.path().join("odoov19_metrics_f2.json");
-    std::fs::copy(
-        workspace_root().join("examples/odoov19_metrics_f2.json"),
-        &metrics_path,
-    )
-    .unwrap();
-
-    let out = amp_bin()
-        .args([
+    let persona = persona_path.to_str().unwrap();
+    let metrics = workspace_root().join("examples/zeroclaw_metrics.json");
+    let metrics = metrics.to_str().unwrap();
+    amp_json(
+        &[
             "gate",
-            persona_path.to_str().unwrap(),
+            persona,
             "--evaluate",
             "*",
             "--metrics",
-            metrics_path.to_str().unwrap(),
+            metrics,
             "--json",
-        ])
-        .output()
-        .expect("failed to run amp");
-    assert!(
-        out.status.success(),
-        "gate failed: {}",
-        String::from_utf8_lossy(&out.stderr)
+        ],
+        0,
     );
-    let v: serde_json::Value = serde_json::from_slice(&out.stdout).expect("invalid JSON");
-    assert_eq!(v["gate_id"], "f1_to_f2");
-    assert_eq!(v["to_phase"], "F2");
-}
-
-#[test]
-fn gate_json_no_match() {
-    // Request a specific gate that won't fire with bad metrics
-    let dir = tempfile::tempdir().unwrap();
-    let metrics_path = dir.path().join("bad_metrics.json");
-    std::fs::write(
-        &metrics_path,
-        r#"{"tasks_completed": 1, "error_rate": 0.99, "schema_valid": false}"#,
-    )
-    .unwrap();
 
-    // We need to set up state in "active" phase for the "trusted" gate to be a candidate.
-    // Create a state file so phase = "active".
-    let state_path = dir.path().join("zeroclaw_agent.state.json");
-    let state = serde_json::json!({
-        "name": "ZeroclawWorker",
-        "current_phase": "active",
-        "state_rev": 1,
-        "active_elevations": [],
-        "last_transition": null,
-        "updated_at": "2024-01-01T00:00:00Z"
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/hook", listener.local_addr().unwrap());
+    let lock = dir.path().join("agent.state.json.lock");
+    let webhook = std::thread::spawn(move || {
+        let (mut conn, _) = listener.accept().unwrap();
+        let locked = lock.exists();
+        let mut buf = [0u8; 4096];
+        let _ = conn.read(&mut buf);
+        conn.write_all(b"HTTP/1.1 204 No Content\r\nContent-Length: 0\r\n\r\n")
+            .unwrap();
+        locked
     });
-    std::fs::write(&state_path, serde_json::to_string_pretty(&state).unwrap()).unwrap();
-
-    // Copy persona next to state
-    let persona_path = dir.path().join("zeroclaw_agent.json");
-    std::fs::copy(
-        workspace_root().join("examples/zeroclaw_agent.json"),
-        &persona_path,
-    )
-    .unwrap();
 
     let out = amp_bin()
         .args([
             "gate",
-            persona_path.to_str().unwrap(),
+            persona,
             "--evaluate",
-            "trusted",
+            "*",
             "--metrics",
-            metrics_path.to_str().unwrap(),
+            metrics,
             "--json",
         ])
+        .env("AMPERSONA_NOTIFY_URL", &url)
         .output()
-        .expect("failed to run amp");
-
-    assert_eq!(out.status.code(), Some(1), "expected exit 1 for no match");
-    let v: serde_json::Value = serde_json::from_slice(&out.stdout).expect("invalid JSON");
-    assert_eq!(v["decision"], "no_match");
-    assert!(v["criteria_results"].is_array());
-    // Check that criteria_results has the expected structure
-    let results = v["criteria_results"].as_array().unwrap();
-    assert!(!results.is_empty());
-    for r in results {
-        assert!(r.get("metric").is_some());
-        assert!(r.get("pass").is_some());
-    }
-}
-
-// ── Import/Export roundtrip (3) ─────────────────────────────────
-
-#[test]
-fn zeroclaw_import_aieos() {
-    let v = amp_json(
-        &["import", "examples/aieos_identity.json", "--from", "aieos"],
-        0,
+        .unwrap();
+    assert_eq!(out.status.code(), Some(2));
+    assert!(
+        !webhook.join().unwrap(),
+        "notice sent while holding the lock"
     );
-    assert_eq!(v["version"], "1.0");
-    assert!(v["name"].as_str().is_some());
-    assert!(v["psychology"].is_object());
 }
 
+#[cfg(feature = "sqlite")]
 #[test]
-fn zeroclaw_export_config() {
-    let v = amp_json(
-        &["export", "examples/zeroclaw_agent.json", "--to", "zeroclaw"],
-        0,
+fn sqlite_backend_imports_sidecars_and_verifies_rows() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::copy(
+        workspace_root().join("examples/zeroclaw_agent.json"),
+        dir.path().join("agent.json"),
+    )
+    .unwrap();
+    let metrics = workspace_root().join("examples/zeroclaw_metrics.json");
+    let amp = |args: &[&str]| {
+        let out = amp_bin()
+            .args(args)
+            .current_dir(dir.path())
+            .output()
+            .unwrap();
+        assert!(
+            out.status.success(),
+            "amp {}: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&out.stderr)
+        );
+        serde_json::from_slice::<serde_json::Value>(&out.stdout).unwrap()
+    };
+
+    amp(&[
+        "gate",
+        "agent.json",
+        "--evaluate",
+        "*",
+        "--json",
+        "--metrics",
+        metrics.to_str().unwrap(),
+    ]);
+    let v = amp(&[
+        "migrate-backend",
+        "--from",
+        "files",
+        "--to",
+        "sqlite",
+        "--json",
+    ]);
+    assert_eq!(v["imported"][0]["key"], "agent.state.json");
+    assert!(v["imported"][0]["audit_entries"].as_u64().unwrap() > 0);
+    assert!(dir.path().join(".ampersona/state.db").exists());
+
+    // The sidecars are no longer needed.
+    std::fs::remove_file(dir.path().join("agent.state.json")).unwrap();
+    std::fs::remove_file(dir.path().join("agent.audit.jsonl")).unwrap();
+    let v = amp(&["--backend", "sqlite", "status", "agent.json", "--json"]);
+    assert_eq!(v["phase"], "active");
+
+    // The database belongs to the persona's directory, not the working one.
+    let absolute = dir.path().join("agent.json");
+    let out = amp_bin()
+        .args([
+            "--backend",
+            "sqlite",
+            "status",
+            absolute.to_str().unwrap(),
+            "--json",
+        ])
+        .current_dir(workspace_root())
+        .output()
+        .unwrap();
+    assert!(
+        out.status.success(),
+        "{}",
+        String::from_utf8_lossy(&out.stderr)
     );
-    assert!(v["security_policy"].is_object() || v.get("security_policy").is_some());
+    let v: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    assert_eq!(v["phase"], "active");
+    assert!(!workspace_root().join(".ampersona").exists());
+    let v = amp(&[
+        "--backend",
+        "sqlite",
+        "audit",
+        "agent.json",
+        "--verify",
+        "--json",
+    ]);
+    assert_eq!(v["valid"], true);
+    assert!(!dir.path().join("agent.audit.jsonl").exists());
 }
 
+#[cfg(feature = "sqlite")]
 #[test]
-fn import_export_roundtrip_stable() {
-    // Import AIEOS → ampersona, then export to zeroclaw, check key fields preserved
-    let imported = amp_json(
-        &["import", "examples/aieos_identity.json", "--from", "aieos"],
+fn sqlite_backend_serves_audit_reports_and_sweeps() {
+    let dir = tempfile::tempdir().unwrap();
+    let persona_path = dir.path().join("agent.json");
+    let mut data: serde_json::Value = serde_json::from_str(
+        &std::fs::read_to_string(workspace_root().join("examples/zeroclaw_agent.json")).unwrap(),
+    )
+    .unwrap();
+    data["gates"][1]["enforcement"] = "observe".into();
+    std::fs::write(&persona_path, data.to_string()).unwrap();
+    let persona = persona_path.to_str().unwrap();
+    let key_path = dir.path().join("sign.key");
+    std::fs::write(&key_path, [0x5Bu8; 32]).unwrap();
+    let pub_path = dir.path().join("sign.pub");
+    let public = ed25519_dalek::SigningKey::from_bytes(&[0x5Bu8; 32]).verifying_key();
+    std::fs::write(&pub_path, public.as_bytes()).unwrap();
+    let (key, pubkey) = (key_path.to_str().unwrap(), pub_path.to_str().unwrap());
+    let metrics = workspace_root().join("examples/zeroclaw_metrics.json");
+    let sqlite = |args: &[&str], exit: i32| {
+        let args: Vec<&str> = ["--backend", "sqlite"]
+            .iter()
+            .chain(args)
+            .copied()
+            .collect();
+        amp_json(&args, exit)
+    };
+
+    amp_stdout(&["sign", persona, "--key", key]);
+    amp_bin()
+        .args(["--backend", "sqlite", "gate", persona, "--evaluate", "*"])
+        .arg("--metrics")
+        .arg(&metrics)
+        .status()
+        .unwrap();
+    assert!(!dir.path().join("agent.audit.jsonl").exists());
+
+    let stats = sqlite(&["audit", persona, "--stats", "--json"], 0);
+    assert!(stats["entries"].as_u64().unwrap() > 0);
+    let v = sqlite(&["audit", persona, "--checkpoint-create", "--json"], 0);
+    assert!(v["audit_file"].as_str().unwrap().contains("state.db#"));
+    let v = sqlite(&["audit", persona, "--checkpoint-verify", "--json"], 0);
+    assert_eq!(v["valid"], true);
+    let v = sqlite(&["audit", persona, "--reconcile", "--json"], 0);
+    assert_eq!(v["consistent"], true);
+    let v = sqlite(&["gate", persona, "--observe-report", "--json"], 0);
+    assert_eq!(v["gates"][0]["gate_id"], data["gates"][1]["id"]);
+
+    let v = sqlite(
+        &["verify", persona, "--pubkey", pubkey, "--all-sidecars"],
         0,
     );
-    assert!(imported["name"].as_str().is_some());
-    assert!(imported["role"].as_str().is_some());
-    // The imported persona should have psychology and voice sections
-    assert!(imported["psychology"].is_object());
-    assert!(imported["voice"].is_object());
-}
-
-// ── Agent_mail register (2) ─────────────────────────────────────
+    assert_eq!(v["valid"], true, "{v}");
+    assert_eq!(v["checks"]["audit_chain"]["present"], true);
+    assert_eq!(v["checks"]["state_rev"]["present"], true);
+    assert_eq!(v["checks"]["checkpoint"]["valid"], true);
 
-#[test]
-fn agent_mail_register_mcp_payload() {
-    let v = amp_json(
+    let bundle = dir.path().join("agent.bundle.json");
+    let v = sqlite(
         &[
-            "register",
-            "examples/agent_mail_worker.json",
-            "--project",
-            "/data/projects/test",
-            "--rpc",
+            "audit",
+            persona,
+            "--export",
+            bundle.to_str().unwrap(),
+            "--json",
         ],
         0,
     );
-    // Should be a JSON-RPC envelope
-    assert_eq!(v["jsonrpc"], "2.0");
-    assert!(v["params"]["arguments"]["name"].as_str().is_some());
-}
-
-#[test]
-fn agent_mail_register_with_prompt() {
-    let v = amp_json(
+    assert_eq!(v["chain"]["valid"], true);
+    assert!(v["chain"]["entries"].as_u64().unwrap() > 0);
+    let dest = dir.path().join("restored");
+    std::fs::create_dir(&dest).unwrap();
+    let v = sqlite(
         &[
-            "register",
-            "examples/agent_mail_worker.json",
-            "--project",
-            "/data/projects/test",
-            "--prompt",
-            "--toon",
-            "--rpc",
+            "audit",
+            bundle.to_str().unwrap(),
+            "--import-bundle",
+            "--dest",
+            dest.to_str().unwrap(),
+            "--json",
         ],
         0,
     );
-    let task_desc = v["params"]["arguments"]["task_description"]
-        .as_str()
-        .unwrap();
-    assert!(
-        !task_desc.is_empty(),
-        "task_description should contain prompt"
-    );
-}
-
-// ── Audit (1) ───────────────────────────────────────────────────
+    assert_eq!(v["valid"], true);
+    assert!(v["database"].as_str().unwrap().contains("restored"));
 
-#[test]
-fn audit_verify_json() {
-    // Persona with no audit log → valid with 0 entries
-    let v = amp_json(
+    let v = sqlite(
         &[
-            "audit",
-            "examples/zeroclaw_agent.json",
-            "--verify",
+            "gate",
+            persona,
+            "--promote-to",
+            "enforce",
+            "--all-observe",
+            "--force",
             "--json",
         ],
         0,
     );
-    assert_eq!(v["valid"], true);
-    assert!(v["entries"].as_u64().is_some());
+    assert_eq!(v["promoted"][0], data["gates"][1]["id"]);
+    let v = sqlite(&["audit", persona, "--stats", "--json"], 0);
+    assert_eq!(v["event_counts"]["GateEnforcementChange"], 1);
+
+    // The audit log is in the database; a path for it would be ignored.
+    let out = amp_bin()
+        .args(["--backend", "sqlite", "audit", persona, "--verify"])
+        .args(["--audit", "other.audit.jsonl"])
+        .output()
+        .unwrap();
+    assert_eq!(out.status.code(), Some(2));
 }
 
 // ── Edge cases (3) ──────────────────────────────────────────────
@@ -560,6 +4637,72 @@ fn extension_roundtrip_preserved() {
     assert_eq!(before_parsed["gates"], after_parsed["gates"]);
 }
 
+// ── Migrate (2) ─────────────────────────────────────────────────
+
+#[test]
+fn migrate_dry_run_previews_without_writing() {
+    let dir = tempfile::tempdir().unwrap();
+    let persona_path = dir.path().join("quiet_stone.json");
+    std::fs::copy(
+        workspace_root().join("examples/quiet_stone.json"),
+        &persona_path,
+    )
+    .unwrap();
+    let file = persona_path.to_str().unwrap();
+    let before = std::fs::read_to_string(&persona_path).unwrap();
+
+    let preview = amp_stdout(&["migrate", file, "--dry-run"]);
+    assert!(preview.contains(&format!("--- {file}")));
+    assert!(preview.lines().any(|l| l == "+ version: \"1.0\""));
+    assert!(preview.lines().any(|l| l.starts_with("+ $schema: ")));
+    assert_eq!(std::fs::read_to_string(&persona_path).unwrap(), before);
+    assert!(!dir.path().join("quiet_stone.bak").exists());
+
+    let out = amp_bin()
+        .args(["migrate", file, "--backup"])
+        .output()
+        .unwrap();
+    assert!(out.status.success());
+    assert_eq!(
+        std::fs::read_to_string(dir.path().join("quiet_stone.bak")).unwrap(),
+        before
+    );
+    let after: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&persona_path).unwrap()).unwrap();
+    assert_eq!(after["version"], "1.0");
+}
+
+#[test]
+fn migrate_preserves_ext_blob() {
+    let dir = tempfile::tempdir().unwrap();
+    let persona_path = dir.path().join("quiet_stone.json");
+    let mut persona: serde_json::Value = serde_json::from_str(
+        &std::fs::read_to_string(workspace_root().join("examples/quiet_stone.json")).unwrap(),
+    )
+    .unwrap();
+    assert!(persona.get("version").is_none(), "fixture must be v0.2");
+    let custom = serde_json::json!({
+        "key": 42,
+        "nested": { "deep": true, "weights": [0.1, 0.2] }
+    });
+    persona["ext"] = serde_json::json!({ "custom": custom });
+    std::fs::write(&persona_path, persona.to_string()).unwrap();
+
+    let out = amp_bin()
+        .args(["migrate", persona_path.to_str().unwrap()])
+        .output()
+        .unwrap();
+    assert!(out.status.success());
+
+    let after: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&persona_path).unwrap()).unwrap();
+    assert_eq!(after["version"], "1.0");
+    assert_eq!(after["ext"]["custom"], custom);
+    for (key, value) in persona.as_object().unwrap() {
+        assert_eq!(&after[key], value, "{key} changed during migration");
+    }
+}
+
 // ── E2E workflow (1) ────────────────────────────────────────────
 
 #[test]
@@ -1334,6 +5477,89 @@ fn checkpoint_missing_signature_errors() {
     );
 }
 
+/// Co-signed checkpoint: two distinct signers satisfy --threshold 2, one does not.
+#[test]
+fn checkpoint_cosign_threshold() {
+    let dir = tempfile::tempdir().unwrap();
+    let persona_path = dir.path().join("agent.json");
+    std::fs::copy(
+        workspace_root().join("examples/zeroclaw_agent.json"),
+        &persona_path,
+    )
+    .unwrap();
+    let persona = persona_path.to_str().unwrap();
+    std::fs::write(dir.path().join("agent.audit.jsonl"), "").unwrap();
+
+    let mut pubs = Vec::new();
+    for (name, byte) in [("alice", 0xA1u8), ("bob", 0xB2), ("eve", 0xE3)] {
+        let key_path = dir.path().join(format!("{name}.key"));
+        std::fs::write(&key_path, [byte; 32]).unwrap();
+        let public = ed25519_dalek::SigningKey::from_bytes(&[byte; 32]).verifying_key();
+        let pub_path = dir.path().join(format!("{name}.pub"));
+        std::fs::write(&pub_path, public.as_bytes()).unwrap();
+        pubs.push(pub_path.to_str().unwrap().to_string());
+    }
+    let key = |name: &str| {
+        dir.path()
+            .join(format!("{name}.key"))
+            .to_str()
+            .unwrap()
+            .to_string()
+    };
+
+    let status = amp_bin()
+        .args([
+            "audit",
+            persona,
+            "--checkpoint-create",
+            "--sign-key",
+            &key("alice"),
+        ])
+        .args(["--sign-key-id", "alice"])
+        .status()
+        .unwrap();
+    assert!(status.success());
+    let cosign = |name: &str| {
+        amp_bin()
+            .args([
+                "audit",
+                persona,
+                "--checkpoint-cosign",
+                "--sign-key",
+                &key(name),
+            ])
+            .args(["--sign-key-id", name])
+            .status()
+            .unwrap()
+    };
+    assert!(cosign("bob").success());
+    // Same key_id twice is rejected
+    assert!(!cosign("bob").success());
+
+    let verify = |keys: String| {
+        amp_json(
+            &[
+                "audit",
+                persona,
+                "--checkpoint-verify",
+                "--verify-keys",
+                &keys,
+                "--threshold",
+                "2",
+                "--json",
+            ],
+            if keys.contains("eve") { 1 } else { 0 },
+        )
+    };
+    let v = verify(format!("{},{}", pubs[0], pubs[1]));
+    assert_eq!(v["valid"], true);
+    assert_eq!(v["signers"], serde_json::json!(["alice", "bob"]));
+
+    let v = verify(format!("{},{}", pubs[0], pubs[2]));
+    assert_eq!(v["valid"], false);
+    assert_eq!(v["invalid_signers"], serde_json::json!(["bob"]));
+}
+
 /// state_rev vs audit: detect inconsistency when state advanced without audit.
 #[test]
 fn state_rev_audit_consistency_check() {
@@ -1401,6 +5627,60 @@ fn state_rev_audit_consistency_check() {
     );
 }
 
+/// --reconcile reports the drift, --fix repairs it and audits a Reconcile event.
+#[test]
+fn audit_reconcile_repairs_state_rev() {
+    let dir = tempfile::tempdir().unwrap();
+    let persona_path = dir.path().join("agent.json");
+    std::fs::copy(
+        workspace_root().join("examples/zeroclaw_agent.json"),
+        &persona_path,
+    )
+    .unwrap();
+    let persona = persona_path.to_str().unwrap();
+    let metrics = workspace_root().join("examples/zeroclaw_metrics.json");
+    amp_bin()
+        .args(["gate", persona, "--evaluate", "*", "--metrics"])
+        .arg(&metrics)
+        .status()
+        .unwrap();
+
+    let state_path = dir.path().join("agent.state.json");
+    let mut state: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&state_path).unwrap()).unwrap();
+    state["state_rev"] = serde_json::json!(99);
+    std::fs::write(&state_path, serde_json::to_string_pretty(&state).unwrap()).unwrap();
+
+    // Report only: inconsistent, nothing written
+    let v = amp_json(&["audit", persona, "--reconcile", "--json"], 1);
+    assert_eq!(v["consistent"], false);
+    assert_eq!(v["fixed"], false);
+    let expected = v["expected_state_rev"].as_u64().unwrap();
+    assert!(expected < 99);
+
+    let v = amp_json(&["audit", persona, "--reconcile", "--fix", "--json"], 0);
+    assert_eq!(v["fixed"], true);
+    let state: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&state_path).unwrap()).unwrap();
+    assert_eq!(state["state_rev"], expected);
+    let audit = std::fs::read_to_string(dir.path().join("agent.audit.jsonl")).unwrap();
+    let last: serde_json::Value = serde_json::from_str(audit.lines().last().unwrap()).unwrap();
+    assert_eq!(last["event_type"], "Reconcile");
+    assert_eq!(last["previous_state_rev"], 99);
+
+    let v = amp_json(&["audit", persona, "--verify", "--json"], 0);
+    assert_eq!(v["state_rev_check"]["consistent"], true);
+
+    // A broken chain is never reconciled against
+    let tampered = audit.replacen("GateTransition", "Forged", 1);
+    std::fs::write(dir.path().join("agent.audit.jsonl"), tampered).unwrap();
+    let out = amp_bin()
+        .args(["audit", persona, "--reconcile", "--fix"])
+        .output()
+        .unwrap();
+    assert_eq!(out.status.code(), Some(1));
+}
+
 /// Audit chain stays valid through a full pending/approve lifecycle.
 #[test]
 fn audit_valid_after_pending_approve_lifecycle() {
@@ -1452,6 +5732,13 @@ fn audit_valid_after_pending_approve_lifecycle() {
     let audit: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
     assert_eq!(audit["valid"], true);
     assert!(audit["entries"].as_u64().unwrap() >= 3);
+
+    // 5. Stats pair the pending transition with its approval
+    let stats = amp_json(&["audit", persona, "--stats", "--json"], 0);
+    assert_eq!(stats["entries"], audit["entries"]);
+    assert_eq!(stats["approval_cycles"], 1);
+    assert!(stats["avg_pending_seconds"].as_f64().unwrap() >= 0.0);
+    assert!(stats["event_counts"]["GateTransition"].as_u64().unwrap() >= 3);
 }
 
 /// Sidecar .authority_overlay.json is migrated into state on gate evaluate.
@@ -1698,3 +5985,79 @@ fn needs_approval_autonomy_matrix() {
         );
     }
 }
+
+// ── Exit-code contract (1) ──────────────────────────────────────
+
+#[test]
+fn exit_code_contract() {
+    let table = amp_stdout(&["exit-codes"]);
+    for code in ["0 ", "1 ", "2 ", "3 ", "5 "] {
+        assert!(table.contains(&format!("  {code}")), "{table}");
+    }
+    let help = amp_bin().args(["help", "exit-codes"]).output().unwrap();
+    assert!(String::from_utf8_lossy(&help.stdout).contains("E_LOCK_CONTENTION"));
+
+    let dir = tempfile::tempdir().unwrap();
+    let persona_path = dir.path().join("zeroclaw_agent.json");
+    std::fs::copy(
+        workspace_root().join("examples/zeroclaw_agent.json"),
+        &persona_path,
+    )
+    .unwrap();
+    let persona = persona_path.to_str().unwrap();
+    let metrics = workspace_root().join("examples/zeroclaw_metrics.json");
+    let metrics = metrics.to_str().unwrap();
+    let code = |args: &[&str]| amp_bin().args(args).output().unwrap().status.code();
+
+    // 0 allow, 1 deny
+    assert_eq!(
+        code(&["authority", persona, "--check", "read_file"]),
+        Some(0)
+    );
+    assert_eq!(
+        code(&["authority", persona, "--check", "delete_production_data"]),
+        Some(1)
+    );
+    // 0 transition, then 2 pending_human
+    let evaluate = ["gate", persona, "--evaluate", "*", "--metrics", metrics];
+    assert_eq!(code(&evaluate), Some(0));
+    assert_eq!(code(&evaluate), Some(2));
+    // 2 invalid command line
+    assert_eq!(code(&["authority", persona, "--bogus-flag"]), Some(2));
+    // 3 structured error
+    assert_eq!(
+        code(&[
+            "authority",
+            "/nonexistent/agent.json",
+            "--check",
+            "read_file",
+            "--json"
+        ]),
+        Some(3)
+    );
+    // 5 lock contention
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+    std::fs::write(
+        dir.path().join("zeroclaw_agent.state.json.lock"),
+        format!("{}\n{now}\n", std::process::id()),
+    )
+    .unwrap();
+    assert_eq!(
+        code(&[
+            "gate",
+            persona,
+            "--reset-phase",
+            "active",
+            "--reason",
+            "recovery",
+            "--approver",
+            "ops",
+            "--lock-timeout",
+            "0",
+        ]),
+        Some(5)
+    );
+}
@@ -71,3 +71,38 @@ fn v10_prompt_includes_authority_and_gates() {
     );
     assert!(stdout.contains("trust_decay"), "missing trust_decay gate");
 }
+
+#[test]
+fn v10_prompt_authority_guardrails() {
+    let out = amp_bin()
+        .args([
+            "prompt",
+            "examples/quiet_stone_v1.json",
+            "--sections",
+            "authority",
+        ])
+        .output()
+        .expect("failed to run amp");
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(out.status.success(), "prompt failed");
+    assert!(stdout.contains("### Guardrails"), "missing guardrails");
+    assert!(
+        stdout.contains("- You may: read_file, write_file,"),
+        "missing allow list"
+    );
+    assert!(
+        stdout.contains(
+            "delete_production_data (reason: Retention policy; ref: ISO 9001:2015 \u{a7}7.5)"
+        ),
+        "missing deny reason"
+    );
+    assert!(stdout.contains("- Never touch: .env, secrets/**."));
+    assert!(!stdout.contains("## Identity"), "sections filter ignored");
+
+    // The default prompt keeps the descriptive summary only
+    let full = amp_bin()
+        .args(["prompt", "examples/quiet_stone_v1.json"])
+        .output()
+        .expect("failed to run amp");
+    assert!(!String::from_utf8_lossy(&full.stdout).contains("### Guardrails"));
+}
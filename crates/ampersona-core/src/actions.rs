@@ -176,6 +176,38 @@ impl BuiltinAction {
         }
     }
 
+    /// Short human-readable description for catalogs (`amp actions`).
+    pub fn description(self) -> &'static str {
+        match self {
+            Self::ReadFile => "Read a file from the workspace",
+            Self::WriteFile => "Create or modify a file",
+            Self::DeleteFile => "Delete a file",
+            Self::RunTests => "Run the project's test suite",
+            Self::RunCommand => "Execute a shell command",
+            Self::GitCommit => "Create a git commit",
+            Self::GitPush => "Push commits to a remote branch",
+            Self::GitPushMain => "Push directly to the main branch",
+            Self::GitPull => "Pull from a remote",
+            Self::CreateBranch => "Create a git branch",
+            Self::DeleteBranch => "Delete a git branch",
+            Self::CreatePr => "Open a pull request",
+            Self::MergePr => "Merge a pull request",
+            Self::Deploy => "Deploy to an environment",
+            Self::InstallPackage => "Install a dependency or package",
+            Self::ModifyConfig => "Change configuration files or settings",
+            Self::AccessNetwork => "Make outbound network requests",
+            Self::SendMessage => "Send a message to another agent or human",
+            Self::ApproveChange => "Approve a change on behalf of a reviewer",
+            Self::DeleteProductionData => "Delete data in production systems",
+            Self::AutoApproveCapa => "Auto-approve a corrective/preventive action (CAPA)",
+            Self::FetchInbox => "Fetch messages from an inbox",
+            Self::RegisterAgent => "Register an agent with a coordinator",
+            Self::DeleteHistoricalData => "Delete historical or archived records",
+            Self::CreateReport => "Generate a report",
+            Self::ReviewCapa => "Review a corrective/preventive action (CAPA)",
+        }
+    }
+
     /// All builtin actions for enumeration.
     pub fn all() -> &'static [BuiltinAction] {
         &[
@@ -220,6 +252,70 @@ impl BuiltinAction {
     }
 }
 
+/// Capability words and the builtin actions a skill containing one implies.
+///
+/// Matched against whole words of normalized skill names (lowercase, split on
+/// `-`, `_` and spaces), so `"Shell Execution"` and `code_execution` both map
+/// to `run_command` while `digital_profile` implies nothing.
+const CAPABILITY_ACTIONS: &[(&[&str], &[BuiltinAction])] = &[
+    (&["shell"], &[BuiltinAction::RunCommand]),
+    (&["command", "commands"], &[BuiltinAction::RunCommand]),
+    (&["execute", "execution"], &[BuiltinAction::RunCommand]),
+    (
+        &["file", "files", "filesystem"],
+        &[BuiltinAction::ReadFile, BuiltinAction::WriteFile],
+    ),
+    (&["test", "tests", "testing"], &[BuiltinAction::RunTests]),
+    (
+        &["git", "github", "gitlab"],
+        &[BuiltinAction::GitCommit, BuiltinAction::GitPush],
+    ),
+    (&["merge", "merging"], &[BuiltinAction::MergePr]),
+    (
+        &["deploy", "deployment", "deployments"],
+        &[BuiltinAction::Deploy],
+    ),
+    (
+        &["install", "installation"],
+        &[BuiltinAction::InstallPackage],
+    ),
+    (&["package", "packages"], &[BuiltinAction::InstallPackage]),
+    (&["config", "configuration"], &[BuiltinAction::ModifyConfig]),
+    (
+        &["network", "networking", "web"],
+        &[BuiltinAction::AccessNetwork],
+    ),
+    (
+        &["message", "messages", "messaging"],
+        &[BuiltinAction::SendMessage],
+    ),
+    (
+        &["approve", "approval", "approvals"],
+        &[BuiltinAction::ApproveChange],
+    ),
+    (
+        &["report", "reports", "reporting"],
+        &[BuiltinAction::CreateReport],
+    ),
+];
+
+/// Builtin actions implied by a declared capability (skill name), deduplicated.
+pub fn actions_for_capability(skill: &str) -> Vec<BuiltinAction> {
+    let normalized = skill.to_lowercase();
+    let words: Vec<&str> = normalized.split(['-', '_', ' ']).collect();
+    let mut actions = Vec::new();
+    for (keywords, implied) in CAPABILITY_ACTIONS {
+        if keywords.iter().any(|k| words.contains(k)) {
+            for a in *implied {
+                if !actions.contains(a) {
+                    actions.push(*a);
+                }
+            }
+        }
+    }
+    actions
+}
+
 /// Simple Levenshtein distance for typo suggestions.
 fn edit_distance(a: &str, b: &str) -> usize {
     let a: Vec<char> = a.chars().collect();
@@ -275,6 +371,49 @@ mod tests {
         assert_eq!(BuiltinAction::suggest("git_pussh"), Some("git_push"));
     }
 
+    #[test]
+    fn every_builtin_has_description() {
+        for b in BuiltinAction::all() {
+            assert!(
+                !b.description().is_empty(),
+                "{} lacks description",
+                b.as_str()
+            );
+        }
+    }
+
+    #[test]
+    fn capability_maps_to_actions() {
+        assert_eq!(
+            actions_for_capability("Shell Execution"),
+            vec![BuiltinAction::RunCommand]
+        );
+        assert_eq!(
+            actions_for_capability("file_operations"),
+            vec![BuiltinAction::ReadFile, BuiltinAction::WriteFile]
+        );
+        assert!(actions_for_capability("empathy").is_empty());
+    }
+
+    #[test]
+    fn capability_keywords_match_whole_words() {
+        assert_eq!(
+            actions_for_capability("Shell Execution"),
+            [BuiltinAction::RunCommand]
+        );
+        assert_eq!(
+            actions_for_capability("git_operations"),
+            [BuiltinAction::GitCommit, BuiltinAction::GitPush]
+        );
+        assert_eq!(
+            actions_for_capability("status-reporting"),
+            [BuiltinAction::CreateReport]
+        );
+        for innocent in ["digital_profile", "latest_attestation", "cobweb_cleanup"] {
+            assert!(actions_for_capability(innocent).is_empty(), "{innocent}");
+        }
+    }
+
     #[test]
     fn serde_roundtrip() {
         let id: ActionId = "write_file".parse().unwrap();
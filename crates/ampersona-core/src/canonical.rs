@@ -0,0 +1,260 @@
+use serde_json::Value;
+
+/// Canonical JSON per JCS (RFC 8785).
+///
+/// Object keys are sorted by UTF-16 code units, there is no insignificant
+/// whitespace, numbers use the ECMAScript shortest form (`1e+21`, `1e-7`) and
+/// strings escape only what JSON requires. Two documents that differ only in
+/// key order or formatting produce identical output.
+pub fn canonicalize(value: &Value) -> String {
+    let mut out = String::new();
+    write_value(&mut out, value);
+    out
+}
+
+/// Arrays whose element order carries no meaning, by field name.
+pub const SET_LIKE_ARRAYS: &[&str] = &[
+    "goals",
+    "constraints",
+    "catchphrases",
+    "core_values",
+    "forbidden_words",
+    "compliance_markers",
+    "descriptors",
+    "allowed_paths",
+    "forbidden_paths",
+    "require_approval_for",
+    "can_delegate_to",
+];
+
+/// Normalize for a semantic comparison: every array under a
+/// [`SET_LIKE_ARRAYS`] key is sorted by the [`canonicalize`] form of its
+/// elements, at any depth. Ordered arrays (gates, criteria, deny lists) are
+/// left alone.
+pub fn normalize_semantic(value: &Value) -> Value {
+    match value {
+        Value::Object(obj) => Value::Object(
+            obj.iter()
+                .map(|(key, v)| {
+                    let mut v = normalize_semantic(v);
+                    if let Value::Array(items) = &mut v {
+                        if SET_LIKE_ARRAYS.contains(&key.as_str()) {
+                            items.sort_by_cached_key(canonicalize);
+                        }
+                    }
+                    (key.clone(), v)
+                })
+                .collect(),
+        ),
+        Value::Array(items) => Value::Array(items.iter().map(normalize_semantic).collect()),
+        other => other.clone(),
+    }
+}
+
+/// Default tolerance for [`values_approx_equal`]: well above `f64` rounding
+/// noise, well below any meaningful trait or threshold difference.
+pub const DEFAULT_FLOAT_EPSILON: f64 = 1e-9;
+
+/// Structural equality where numbers only need to be within `eps`.
+///
+/// Objects must have the same keys and arrays the same length; integers and
+/// everything other than numbers compare exactly.
+pub fn values_approx_equal(a: &Value, b: &Value, eps: f64) -> bool {
+    match (a, b) {
+        // Two integers compare exactly; f64 cannot hold every u64.
+        (Value::Number(x), Value::Number(y)) if x.is_f64() || y.is_f64() => {
+            match (x.as_f64(), y.as_f64()) {
+                (Some(x), Some(y)) => (x - y).abs() <= eps,
+                _ => false,
+            }
+        }
+        (Value::Array(xs), Value::Array(ys)) => {
+            xs.len() == ys.len()
+                && xs
+                    .iter()
+                    .zip(ys)
+                    .all(|(x, y)| values_approx_equal(x, y, eps))
+        }
+        (Value::Object(xo), Value::Object(yo)) => {
+            xo.len() == yo.len()
+                && xo
+                    .iter()
+                    .all(|(k, x)| yo.get(k).is_some_and(|y| values_approx_equal(x, y, eps)))
+        }
+        _ => a == b,
+    }
+}
+
+fn write_value(out: &mut String, value: &Value) {
+    match value {
+        Value::Null => out.push_str("null"),
+        Value::Bool(b) => out.push_str(if *b { "true" } else { "false" }),
+        Value::Number(n) => {
+            if let Some(i) = n.as_i64() {
+                out.push_str(&i.to_string());
+            } else if let Some(u) = n.as_u64() {
+                out.push_str(&u.to_string());
+            } else {
+                out.push_str(&format_number(n.as_f64().unwrap_or(0.0)));
+            }
+        }
+        Value::String(s) => write_string(out, s),
+        Value::Array(items) => {
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_value(out, item);
+            }
+            out.push(']');
+        }
+        Value::Object(obj) => {
+            let mut keys: Vec<&String> = obj.keys().collect();
+            keys.sort_by(|a, b| a.encode_utf16().cmp(b.encode_utf16()));
+            out.push('{');
+            for (i, key) in keys.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_string(out, key);
+                out.push(':');
+                write_value(out, &obj[key]);
+            }
+            out.push('}');
+        }
+    }
+}
+
+fn write_string(out: &mut String, s: &str) {
+    out.push('"');
+    for ch in s.chars() {
+        match ch {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\x08' => out.push_str("\\b"),
+            '\x0C' => out.push_str("\\f"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c < '\x20' => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+/// ECMAScript `Number.prototype.toString` for finite doubles.
+fn format_number(f: f64) -> String {
+    if f == 0.0 || !f.is_finite() {
+        return "0".to_string();
+    }
+    // `{:e}` yields the shortest round-trip digits: "d.ddde±x".
+    let sci = format!("{:e}", f.abs());
+    let (mantissa, exp) = sci.split_once('e').unwrap_or((&sci, "0"));
+    let digits: String = mantissa.chars().filter(char::is_ascii_digit).collect();
+    let k = digits.len() as i32;
+    let n = exp.parse::<i32>().unwrap_or(0) + 1;
+
+    let body = if k <= n && n <= 21 {
+        format!("{digits}{}", "0".repeat((n - k) as usize))
+    } else if 0 < n && n <= 21 {
+        format!("{}.{}", &digits[..n as usize], &digits[n as usize..])
+    } else if -6 < n && n <= 0 {
+        format!("0.{}{digits}", "0".repeat((-n) as usize))
+    } else {
+        let sign = if n - 1 < 0 { '-' } else { '+' };
+        let (first, rest) = digits.split_at(1);
+        if rest.is_empty() {
+            format!("{first}e{sign}{}", (n - 1).abs())
+        } else {
+            format!("{first}.{rest}e{sign}{}", (n - 1).abs())
+        }
+    };
+    if f < 0.0 {
+        format!("-{body}")
+    } else {
+        body
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn key_order_and_whitespace_do_not_matter() {
+        let a: Value = serde_json::from_str(
+            r#"{"name": "Ada", "authority": {"autonomy": "full", "actions": {"deny": ["x"], "allow": ["y"]}}}"#,
+        )
+        .unwrap();
+        let b: Value = serde_json::from_str(
+            r#"{"authority":{"actions":{"allow":["y"],"deny":["x"]},"autonomy":"full"},"name":"Ada"}"#,
+        )
+        .unwrap();
+        assert_ne!(a.to_string(), b.to_string());
+        assert_eq!(canonicalize(&a), canonicalize(&b));
+        assert_eq!(
+            canonicalize(&a),
+            r#"{"authority":{"actions":{"allow":["y"],"deny":["x"]},"autonomy":"full"},"name":"Ada"}"#
+        );
+    }
+
+    #[test]
+    fn semantic_normalization_sorts_only_set_like_arrays() {
+        let a = json!({"directives": {"goals": ["b", "a"]}, "gates": ["b", "a"]});
+        let b = json!({"directives": {"goals": ["a", "b"]}, "gates": ["b", "a"]});
+        assert_eq!(normalize_semantic(&a), normalize_semantic(&b));
+        assert_eq!(normalize_semantic(&a)["gates"], json!(["b", "a"]));
+    }
+
+    #[test]
+    fn float_noise_is_within_tolerance() {
+        let a = json!({"psychology": {"traits": [0.1 + 0.2, 1]}});
+        let b = json!({"psychology": {"traits": [0.3, 1]}});
+        assert_ne!(a, b);
+        assert!(values_approx_equal(&a, &b, DEFAULT_FLOAT_EPSILON));
+        assert!(!values_approx_equal(&a, &b, 0.0));
+        assert!(!values_approx_equal(
+            &json!({"x": 0.3}),
+            &json!({"x": 0.31}),
+            DEFAULT_FLOAT_EPSILON
+        ));
+        assert!(!values_approx_equal(
+            &json!({"x": 0.3}),
+            &json!({"x": 0.3, "y": 1}),
+            DEFAULT_FLOAT_EPSILON
+        ));
+    }
+
+    #[test]
+    fn numbers_follow_ecmascript() {
+        let cases = [
+            (json!(0.5), "0.5"),
+            (json!(1.0), "1"),
+            (json!(-0.0), "0"),
+            (json!(100), "100"),
+            (json!(1e21), "1e+21"),
+            (json!(1e20), "100000000000000000000"),
+            (json!(1.5e-7), "1.5e-7"),
+            (json!(0.000001), "0.000001"),
+            (json!(-123.456), "-123.456"),
+            (json!(u64::MAX), "18446744073709551615"),
+        ];
+        for (value, expected) in cases {
+            assert_eq!(canonicalize(&value), expected, "{value}");
+        }
+    }
+
+    #[test]
+    fn strings_escape_minimally() {
+        assert_eq!(
+            canonicalize(&json!("a\"b\\c\nd\u{1}é€")),
+            r#""a\"b\\c\nd\u0001é€""#
+        );
+        // UTF-16 ordering: U+FB01 sorts after U+1F600's surrogate pair.
+        let v = json!({"\u{fb01}": 1, "\u{1f600}": 2});
+        assert_eq!(canonicalize(&v), "{\"\u{1f600}\":2,\"\u{fb01}\":1}");
+    }
+}
@@ -1,5 +1,7 @@
 use serde_json::Value;
 
+use crate::types::AutonomyLevel;
+
 /// Merge two persona JSON values (base + overlay).
 ///
 /// Overlay fields take precedence. For authority, merge rules apply:
@@ -25,6 +27,65 @@ pub fn merge_personas(base: &Value, overlay: &Value) -> Value {
     result
 }
 
+/// A place where a merge raised autonomy (ordering: full > supervised > readonly).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AutonomyUpgrade {
+    /// JSON pointer into the merged persona.
+    pub path: String,
+    pub from: AutonomyLevel,
+    pub to: AutonomyLevel,
+}
+
+/// Every autonomy level in `merged` that exceeds what `base` allowed.
+///
+/// Checks `/authority/autonomy` against the base's level, and each gate's
+/// `on_pass.authority_overlay.autonomy` against the highest level the base
+/// could reach (its own autonomy or any of its gate overlays). A base with no
+/// authority section is denied everything, so it counts as readonly.
+pub fn autonomy_upgrades(base: &Value, merged: &Value) -> Vec<AutonomyUpgrade> {
+    let level =
+        |v: Option<&Value>| v.and_then(|v| serde_json::from_value::<AutonomyLevel>(v.clone()).ok());
+    let gate_levels = |persona: &Value| {
+        persona
+            .get("gates")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .enumerate()
+            .filter_map(|(i, g)| {
+                level(g.pointer("/on_pass/authority_overlay/autonomy")).map(|l| (i, l))
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let mut upgrades = Vec::new();
+    let base_level = level(base.pointer("/authority/autonomy")).unwrap_or(AutonomyLevel::Readonly);
+    if let Some(to) = level(merged.pointer("/authority/autonomy")) {
+        if to > base_level {
+            upgrades.push(AutonomyUpgrade {
+                path: "/authority/autonomy".into(),
+                from: base_level,
+                to,
+            });
+        }
+    }
+
+    let ceiling = gate_levels(base)
+        .into_iter()
+        .map(|(_, l)| l)
+        .fold(base_level, Ord::max);
+    for (i, to) in gate_levels(merged) {
+        if to > ceiling {
+            upgrades.push(AutonomyUpgrade {
+                path: format!("/gates/{i}/on_pass/authority_overlay/autonomy"),
+                from: ceiling,
+                to,
+            });
+        }
+    }
+    upgrades
+}
+
 fn merge_authority(base: &Value, overlay: &Value) -> Value {
     let mut result = base.clone();
     if let (Some(base_obj), Some(overlay_obj)) = (result.as_object_mut(), overlay.as_object()) {
@@ -133,3 +194,49 @@ fn min_autonomy_str(a: &str, b: &str) -> &'static str {
         _ => "full",
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn upgrades_from_missing_authority_and_gate_overlays() {
+        let base = serde_json::json!({
+            "name": "a",
+            "gates": [{ "id": "g", "on_pass": { "authority_overlay": { "autonomy": "supervised" } } }]
+        });
+        let overlay = serde_json::json!({
+            "authority": { "autonomy": "full" },
+            "gates": [{ "id": "g", "on_pass": { "authority_overlay": { "autonomy": "full" } } }]
+        });
+        let merged = merge_personas(&base, &overlay);
+        let paths: Vec<_> = autonomy_upgrades(&base, &merged)
+            .into_iter()
+            .map(|u| (u.path, u.from, u.to))
+            .collect();
+        assert_eq!(
+            paths,
+            [
+                (
+                    "/authority/autonomy".to_string(),
+                    AutonomyLevel::Readonly,
+                    AutonomyLevel::Full
+                ),
+                (
+                    "/gates/0/on_pass/authority_overlay/autonomy".to_string(),
+                    AutonomyLevel::Supervised,
+                    AutonomyLevel::Full
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn restricting_merge_is_not_an_upgrade() {
+        let base = serde_json::json!({ "authority": { "autonomy": "supervised" } });
+        let overlay = serde_json::json!({ "authority": { "autonomy": "full" } });
+        let merged = merge_personas(&base, &overlay);
+        assert_eq!(merged["authority"]["autonomy"], "supervised");
+        assert!(autonomy_upgrades(&base, &merged).is_empty());
+    }
+}
@@ -0,0 +1,187 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use serde_json::Value;
+
+/// Top-level sections that may be a `{"$ref": "file.json"}` instead of inline.
+///
+/// Entries of `gates` may also be refs individually.
+pub const REF_SECTIONS: &[&str] = &[
+    "psychology",
+    "voice",
+    "capabilities",
+    "directives",
+    "authority",
+    "gates",
+    "audit",
+];
+
+/// Longest chain of refs-to-refs [`deref`] will follow.
+pub const MAX_REF_DEPTH: usize = 8;
+
+/// Inline `$ref` sections of the persona at `path`.
+///
+/// A ref is an object whose only key is `$ref`: a file path relative to the
+/// document containing it, optionally followed by a `#/json/pointer` into that
+/// file. A referenced file may itself be a ref (or, for `gates`, hold refs to
+/// individual gates). Cycles and chains longer than [`MAX_REF_DEPTH`] are
+/// rejected.
+pub fn deref(mut data: Value, path: &str) -> Result<Value> {
+    let path = Path::new(path);
+    let mut chain = vec![canonical(path)];
+    let Some(obj) = data.as_object_mut() else {
+        return Ok(data);
+    };
+    for key in REF_SECTIONS {
+        let Some(slot) = obj.get_mut(*key) else {
+            continue;
+        };
+        let (mut section, origin) = follow(slot.take(), path, &mut chain)?;
+        if *key == "gates" {
+            if let Some(items) = section.as_array_mut() {
+                for item in items {
+                    *item = follow(item.take(), &origin, &mut chain)?.0;
+                }
+            }
+        }
+        *slot = section;
+    }
+    Ok(data)
+}
+
+/// Follow `value` while it is a ref; returns the target and the file it came from.
+fn follow(value: Value, from: &Path, chain: &mut Vec<PathBuf>) -> Result<(Value, PathBuf)> {
+    let Some(target) = ref_target(&value, from)? else {
+        return Ok((value, from.to_path_buf()));
+    };
+    let (file, pointer) = target.split_once('#').unwrap_or((target.as_str(), ""));
+    if file.is_empty() {
+        bail!(
+            "{}: $ref \"{target}\" must name a file (internal refs are not supported)",
+            from.display()
+        );
+    }
+    let ref_path = from.parent().unwrap_or(Path::new("")).join(file);
+    let key = canonical(&ref_path);
+    if chain.contains(&key) {
+        let cycle: Vec<String> = chain
+            .iter()
+            .chain(std::iter::once(&key))
+            .map(|p| p.display().to_string())
+            .collect();
+        bail!("$ref cycle: {}", cycle.join(" -> "));
+    }
+    if chain.len() > MAX_REF_DEPTH {
+        bail!("{}: $ref chain deeper than {MAX_REF_DEPTH}", from.display());
+    }
+
+    let display = ref_path.display();
+    let content = std::fs::read_to_string(&ref_path)
+        .with_context(|| format!("{}: cannot read $ref {display}", from.display()))?;
+    let doc: Value =
+        serde_json::from_str(&content).with_context(|| format!("{display}: invalid JSON"))?;
+    let fragment = if pointer.is_empty() {
+        doc
+    } else {
+        doc.pointer(pointer)
+            .cloned()
+            .with_context(|| format!("{display}: no value at #{pointer}"))?
+    };
+
+    chain.push(key);
+    let resolved = follow(fragment, &ref_path, chain);
+    chain.pop();
+    resolved
+}
+
+fn ref_target(value: &Value, from: &Path) -> Result<Option<String>> {
+    let Some(obj) = value.as_object() else {
+        return Ok(None);
+    };
+    let Some(target) = obj.get("$ref") else {
+        return Ok(None);
+    };
+    match (target.as_str(), obj.len()) {
+        (Some(t), 1) => Ok(Some(t.to_string())),
+        (None, _) => bail!("{}: $ref must be a string", from.display()),
+        _ => bail!(
+            "{}: $ref must be the only key in its object",
+            from.display()
+        ),
+    }
+}
+
+pub(crate) fn canonical(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn write(dir: &Path, name: &str, data: Value) -> String {
+        let path = dir.join(name);
+        std::fs::write(&path, data.to_string()).unwrap();
+        path.to_str().unwrap().to_string()
+    }
+
+    #[test]
+    fn sections_and_gate_entries_are_inlined() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("lib")).unwrap();
+        write(
+            dir.path(),
+            "lib/gates.json",
+            json!([{"$ref": "probation.json"}, {"id": "inline"}]),
+        );
+        write(dir.path(), "lib/probation.json", json!({"id": "probation"}));
+        write(
+            dir.path(),
+            "lib/policy.json",
+            json!({"strict": {"autonomy": "readonly"}}),
+        );
+        let persona = write(
+            dir.path(),
+            "agent.json",
+            json!({
+                "name": "Agent",
+                "gates": {"$ref": "lib/gates.json"},
+                "authority": {"$ref": "lib/policy.json#/strict"},
+                "backstory": {"$ref": "ignored.json"},
+            }),
+        );
+
+        let data = deref(
+            serde_json::from_str(&std::fs::read_to_string(&persona).unwrap()).unwrap(),
+            &persona,
+        )
+        .unwrap();
+        assert_eq!(
+            data["gates"],
+            json!([{"id": "probation"}, {"id": "inline"}])
+        );
+        assert_eq!(data["authority"]["autonomy"], "readonly");
+        // Only section boundaries are refs
+        assert_eq!(data["backstory"], json!({"$ref": "ignored.json"}));
+    }
+
+    #[test]
+    fn ref_cycles_are_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        write(dir.path(), "a.json", json!({"$ref": "b.json"}));
+        write(dir.path(), "b.json", json!({"$ref": "a.json"}));
+        let persona = write(
+            dir.path(),
+            "agent.json",
+            json!({"voice": {"$ref": "a.json"}}),
+        );
+        let data = json!({"voice": {"$ref": "a.json"}});
+        let err = deref(data, &persona).unwrap_err().to_string();
+        assert!(err.contains("$ref cycle"), "{err}");
+
+        let data = json!({"gates": [{"$ref": "agent.json"}]});
+        let err = deref(data, &persona).unwrap_err().to_string();
+        assert!(err.contains("$ref cycle"), "{err}");
+    }
+}
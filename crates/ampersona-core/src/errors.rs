@@ -34,6 +34,8 @@ pub enum MetricError {
     TypeMismatch(String),
     #[error("metrics provider unavailable")]
     ProviderUnavailable,
+    #[error("derived metric {0} is undefined: {1}")]
+    Undefined(String, String),
 }
 
 #[derive(Debug, thiserror::Error)]
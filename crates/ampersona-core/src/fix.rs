@@ -0,0 +1,250 @@
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::schema::{check, detect_version};
+
+/// Check codes [`apply_fixes`] repairs. Everything else stays manual.
+///
+/// - `W030` missing `$schema`: set to the `$id` of the detected version's schema
+/// - `W031` missing `version`: set to `"1.0"` when the persona validates as v1.0
+/// - `W032` un-normalized alignment: rewrite to the canonical enum spelling
+/// - `W033` skills without priority: number them in list order (1–10), only
+///   when no skill declares one
+pub const FIXABLE_CODES: &[&str] = &["W031", "W030", "W032", "W033"];
+
+pub const ALIGNMENTS: &[&str] = &[
+    "lawful-good",
+    "neutral-good",
+    "chaotic-good",
+    "lawful-neutral",
+    "true-neutral",
+    "chaotic-neutral",
+    "lawful-evil",
+    "neutral-evil",
+    "chaotic-evil",
+];
+
+/// One change made (or refused) by [`apply_fixes`].
+#[derive(Debug, Clone, Serialize)]
+pub struct Fix {
+    pub code: String,
+    pub path: String,
+    pub description: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct FixOutcome {
+    pub applied: Vec<Fix>,
+    /// Fixes left unapplied because the result had more validation errors.
+    pub rejected: Vec<Fix>,
+}
+
+/// Apply every allowlisted fix to `data` in place.
+///
+/// Fixes run one code at a time, in [`FIXABLE_CODES`] order. After each, the
+/// candidate is re-checked; if it has more errors than before, it is
+/// discarded and reported under `rejected`.
+pub fn apply_fixes(data: &mut Value) -> FixOutcome {
+    let mut outcome = FixOutcome::default();
+    for code in FIXABLE_CODES {
+        let mut candidate = data.clone();
+        let fixes = fix_code(code, &mut candidate);
+        if fixes.is_empty() {
+            continue;
+        }
+        let before = check(data, "", false).errors.len();
+        let after = check(&candidate, "", false).errors.len();
+        if after > before {
+            outcome.rejected.extend(fixes);
+        } else {
+            *data = candidate;
+            outcome.applied.extend(fixes);
+        }
+    }
+    outcome
+}
+
+/// Canonical spelling of an alignment (`"True Neutral"` → `"true-neutral"`).
+pub fn normalize_alignment(raw: &str) -> Option<&'static str> {
+    let slug = raw
+        .trim()
+        .to_lowercase()
+        .replace(|c: char| c == '_' || c.is_whitespace(), "-");
+    let slug = match slug.as_str() {
+        "neutral" | "neutral-neutral" => "true-neutral",
+        other => other,
+    };
+    ALIGNMENTS.iter().copied().find(|a| *a == slug)
+}
+
+/// Pointers of skills lacking `priority`, and whether the W033 fix applies.
+pub(crate) fn skills_without_priority(data: &Value) -> (Vec<usize>, bool) {
+    let Some(skills) = data
+        .pointer("/capabilities/skills")
+        .and_then(Value::as_array)
+    else {
+        return (Vec::new(), false);
+    };
+    let missing: Vec<usize> = skills
+        .iter()
+        .enumerate()
+        .filter(|(_, s)| s.is_object() && s.get("priority").is_none())
+        .map(|(i, _)| i)
+        .collect();
+    let fixable = !missing.is_empty() && missing.len() == skills.len();
+    (missing, fixable)
+}
+
+fn fix_code(code: &str, data: &mut Value) -> Vec<Fix> {
+    let fix = |path: &str, description: String| Fix {
+        code: code.to_string(),
+        path: path.to_string(),
+        description,
+    };
+    let Some(obj) = data.as_object_mut() else {
+        return Vec::new();
+    };
+    match code {
+        "W031" if !obj.contains_key("version") => {
+            obj.insert("version".into(), Value::String("1.0".into()));
+            vec![fix("$.version", "set version to \"1.0\"".into())]
+        }
+        "W030" if !obj.contains_key("$schema") => {
+            let id = schema_id(detect_version(data));
+            data.as_object_mut()
+                .unwrap()
+                .insert("$schema".into(), Value::String(id.to_string()));
+            vec![fix("$.$schema", format!("set $schema to {id}"))]
+        }
+        "W032" => {
+            let Some(slot) = data.pointer_mut("/psychology/moral_compass/alignment") else {
+                return Vec::new();
+            };
+            let Some(raw) = slot.as_str().map(String::from) else {
+                return Vec::new();
+            };
+            match normalize_alignment(&raw) {
+                Some(canonical) if canonical != raw => {
+                    *slot = Value::String(canonical.into());
+                    vec![fix(
+                        "$.psychology.moral_compass.alignment",
+                        format!("normalized alignment '{raw}' to '{canonical}'"),
+                    )]
+                }
+                _ => Vec::new(),
+            }
+        }
+        "W033" => {
+            let (missing, fixable) = skills_without_priority(data);
+            if !fixable {
+                return Vec::new();
+            }
+            let skills = data
+                .pointer_mut("/capabilities/skills")
+                .and_then(Value::as_array_mut)
+                .unwrap();
+            missing
+                .into_iter()
+                .map(|i| {
+                    let priority = (i + 1).min(10);
+                    skills[i]
+                        .as_object_mut()
+                        .unwrap()
+                        .insert("priority".into(), priority.into());
+                    fix(
+                        &format!("$.capabilities.skills[{i}].priority"),
+                        format!("set priority to {priority} (list order)"),
+                    )
+                })
+                .collect()
+        }
+        _ => Vec::new(),
+    }
+}
+
+fn schema_id(version: &str) -> &'static str {
+    match version {
+        "1.0" => "https://ampersona.dev/schema/v1.0/ampersona.schema.json",
+        _ => "https://ampersona.dev/schema/v0.2/ampersona.schema.json",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn v02() -> Value {
+        serde_json::json!({
+            "name": "Test",
+            "role": "test",
+            "psychology": {
+                "neural_matrix": {
+                    "creativity": 0.5, "empathy": 0.5, "logic": 0.5,
+                    "adaptability": 0.5, "charisma": 0.5, "reliability": 0.5
+                },
+                "traits": {
+                    "mbti": "INTJ", "temperament": "phlegmatic",
+                    "ocean": {
+                        "openness": 0.5, "conscientiousness": 0.5, "extraversion": 0.5,
+                        "agreeableness": 0.5, "neuroticism": 0.5
+                    }
+                },
+                "moral_compass": { "alignment": "True Neutral", "core_values": ["test"] },
+                "emotional_profile": { "base_mood": "calm", "volatility": 0.1 }
+            },
+            "voice": {
+                "style": { "descriptors": ["terse"], "formality": 0.5, "verbosity": 0.3 },
+                "syntax": { "structure": "declarative", "contractions": true },
+                "idiolect": { "catchphrases": ["test"], "forbidden_words": [] }
+            },
+            "capabilities": {
+                "skills": [
+                    { "name": "a", "description": "first" },
+                    { "name": "b", "description": "second" }
+                ]
+            }
+        })
+    }
+
+    #[test]
+    fn fixes_all_allowlisted_codes() {
+        let mut data = v02();
+        let before = check(&data, "t.json", false);
+        assert!(!before.errors.is_empty(), "alignment should fail the enum");
+
+        let outcome = apply_fixes(&mut data);
+        let codes: Vec<_> = outcome.applied.iter().map(|f| f.code.as_str()).collect();
+        assert_eq!(codes, vec!["W031", "W030", "W032", "W033", "W033"]);
+        assert!(outcome.rejected.is_empty());
+        assert_eq!(data["version"], "1.0");
+        assert_eq!(
+            data["psychology"]["moral_compass"]["alignment"],
+            "true-neutral"
+        );
+        assert_eq!(data["capabilities"]["skills"][1]["priority"], 2);
+
+        let after = check(&data, "t.json", false);
+        assert!(after.errors.is_empty(), "{:?}", after.errors);
+        assert!(after
+            .warnings
+            .iter()
+            .all(|w| !FIXABLE_CODES.contains(&w.code.as_str())));
+    }
+
+    #[test]
+    fn fix_that_breaks_validation_is_rejected() {
+        // Unknown actions are one schema error as v0.2 but two more as v1.0.
+        let mut data = v02();
+        data["psychology"]["moral_compass"]["alignment"] = "lawful-good".into();
+        data["capabilities"]["skills"][0]["priority"] = 1.into();
+        data["authority"] = serde_json::json!({"autonomy": "full", "actions": {"allow": ["frobnicate", "defenestrate"]}});
+        let before = check(&data, "t.json", false).errors.len();
+
+        let outcome = apply_fixes(&mut data);
+        assert!(outcome.rejected.iter().any(|f| f.code == "W031"));
+        assert!(data.get("version").is_none());
+        assert_eq!(check(&data, "t.json", false).errors.len(), before);
+        // Mixed priorities are ambiguous and left alone.
+        assert!(data["capabilities"]["skills"][1].get("priority").is_none());
+    }
+}
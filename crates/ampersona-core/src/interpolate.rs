@@ -0,0 +1,133 @@
+use anyhow::{bail, Result};
+use serde_json::Value;
+
+/// Expand `${VAR}` and `${VAR:-default}` in every string leaf of `data`.
+///
+/// Keys, numbers and structure are left alone. `$${` escapes a literal `${`.
+/// An undefined variable without a default is an error naming the JSON
+/// pointer of the offending string.
+pub fn expand_env(data: Value) -> Result<Value> {
+    expand_with(data, &|name| std::env::var(name).ok())
+}
+
+/// [`expand_env`] with a caller-supplied variable lookup.
+pub fn expand_with(data: Value, lookup: &dyn Fn(&str) -> Option<String>) -> Result<Value> {
+    walk(data, "", lookup)
+}
+
+fn walk(value: Value, pointer: &str, lookup: &dyn Fn(&str) -> Option<String>) -> Result<Value> {
+    Ok(match value {
+        Value::String(s) => Value::String(expand_str(&s, lookup).map_err(|e| {
+            let loc = if pointer.is_empty() {
+                "(root)"
+            } else {
+                pointer
+            };
+            anyhow::anyhow!("{loc}: {e}")
+        })?),
+        Value::Array(items) => Value::Array(
+            items
+                .into_iter()
+                .enumerate()
+                .map(|(i, v)| walk(v, &format!("{pointer}/{i}"), lookup))
+                .collect::<Result<_>>()?,
+        ),
+        Value::Object(obj) => Value::Object(
+            obj.into_iter()
+                .map(|(k, v)| {
+                    let child = format!("{pointer}/{}", k.replace('~', "~0").replace('/', "~1"));
+                    Ok((k, walk(v, &child, lookup)?))
+                })
+                .collect::<Result<_>>()?,
+        ),
+        other => other,
+    })
+}
+
+fn expand_str(s: &str, lookup: &dyn Fn(&str) -> Option<String>) -> Result<String> {
+    let mut out = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(start) = rest.find('$') {
+        out.push_str(&rest[..start]);
+        let tail = &rest[start..];
+        if let Some(after) = tail.strip_prefix("$${") {
+            out.push_str("${");
+            rest = after;
+            continue;
+        }
+        let Some(body) = tail.strip_prefix("${") else {
+            out.push('$');
+            rest = &tail[1..];
+            continue;
+        };
+        let Some(end) = body.find('}') else {
+            bail!("unterminated ${{ in \"{s}\"");
+        };
+        let (name, default) = match body[..end].split_once(":-") {
+            Some((name, default)) => (name, Some(default)),
+            None => (&body[..end], None),
+        };
+        if !is_var_name(name) {
+            bail!("invalid variable name \"{name}\"");
+        }
+        match (lookup(name), default) {
+            (Some(v), _) => out.push_str(&v),
+            (None, Some(d)) => out.push_str(d),
+            (None, None) => bail!("undefined environment variable {name}"),
+        }
+        rest = &body[end + 1..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+fn is_var_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn lookup(name: &str) -> Option<String> {
+        (name == "PROJECT_ROOT").then(|| "/srv/app".to_string())
+    }
+
+    #[test]
+    fn expands_string_leaves_only() {
+        let data = json!({
+            "name": "${PROJECT_ROOT}",
+            "scope": {"allowed_paths": ["${PROJECT_ROOT}/src/**", "${TIER:-dev}/tmp"]},
+            "limits": {"max_actions_per_hour": 50},
+            "${PROJECT_ROOT}": "key untouched",
+            "price": "$5 and $${LITERAL}",
+        });
+        let out = expand_with(data, &lookup).unwrap();
+        assert_eq!(out["name"], "/srv/app");
+        assert_eq!(
+            out["scope"]["allowed_paths"],
+            json!(["/srv/app/src/**", "dev/tmp"])
+        );
+        assert_eq!(out["limits"]["max_actions_per_hour"], 50);
+        assert_eq!(out["${PROJECT_ROOT}"], "key untouched");
+        assert_eq!(out["price"], "$5 and ${LITERAL}");
+    }
+
+    #[test]
+    fn undefined_and_malformed_are_errors() {
+        let err = expand_with(json!({"scope": {"paths": ["${MISSING}"]}}), &lookup)
+            .unwrap_err()
+            .to_string();
+        assert_eq!(
+            err,
+            "/scope/paths/0: undefined environment variable MISSING"
+        );
+        assert!(expand_with(json!("${OPEN"), &lookup).is_err());
+        assert!(expand_with(json!("${1BAD}"), &lookup).is_err());
+    }
+}
@@ -1,13 +1,19 @@
 #![forbid(unsafe_code)]
 
 pub mod actions;
+pub mod canonical;
 pub mod compose;
+pub mod deref;
 pub mod errors;
+pub mod fix;
+pub mod interpolate;
 pub mod list;
 pub mod migrate;
 pub mod prompt;
+pub mod redact;
 pub mod register;
 pub mod schema;
+pub mod sidecar;
 pub mod spec;
 pub mod state;
 pub mod templates;
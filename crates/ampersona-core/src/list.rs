@@ -1,32 +1,88 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
+use serde::Serialize;
 use serde_json::Value;
 
+use crate::state::PhaseState;
+
+/// Columns `amp list --sort` accepts.
+pub const SORT_COLUMNS: &[&str] = &["name", "autonomy", "phase", "role"];
+
 /// Summary row for a single persona file.
+#[derive(Debug, Clone, Serialize)]
 pub struct PersonaRow {
     pub file: String,
     pub name: String,
     pub mbti: String,
     pub role: String,
+    pub autonomy: String,
+    /// Current phase from the `.state.json` sidecar, `-` when there is none.
+    pub phase: String,
     pub skills: usize,
+    /// The persona carries a `signature` block (not verified).
+    pub signed: bool,
+    /// A `<name>.audit.jsonl` sidecar exists.
+    pub has_audit: bool,
 }
 
-/// Scan a directory for .json files and produce summary rows.
-pub fn scan_dir(dir: &str) -> Result<Vec<PersonaRow>> {
-    let mut rows = Vec::new();
-    let entries = std::fs::read_dir(dir).with_context(|| format!("cannot read directory {dir}"))?;
+/// Sidecar files that sit next to personas but are not personas.
+const SIDECAR_SUFFIXES: &[&str] = &[".state.json", ".checkpoint.json", ".manifest.json"];
 
-    let mut paths: Vec<_> = entries
-        .filter_map(|e| e.ok())
-        .filter(|e| e.path().extension().is_some_and(|ext| ext == "json"))
-        .map(|e| e.path())
-        .collect();
+/// Persona `.json` files under `dir`, sorted by path.
+///
+/// Sidecars are skipped. With `recursive`, subdirectories are walked too,
+/// following symlinks (loops are reported and skipped) but never descending
+/// into `.ampersona/`.
+pub fn persona_paths(dir: &str, recursive: bool) -> Result<Vec<PathBuf>> {
+    if !Path::new(dir).is_dir() {
+        bail!("cannot read directory {dir}");
+    }
+    let walker = walkdir::WalkDir::new(dir)
+        .min_depth(1)
+        .max_depth(if recursive { usize::MAX } else { 1 })
+        .follow_links(recursive)
+        .into_iter()
+        .filter_entry(|e| !(e.file_type().is_dir() && e.file_name() == ".ampersona"));
+
+    let mut paths = Vec::new();
+    for entry in walker {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(e) => {
+                eprintln!("  skip {e}");
+                continue;
+            }
+        };
+        let name = entry.file_name().to_string_lossy();
+        if entry.file_type().is_file()
+            && name.ends_with(".json")
+            && !SIDECAR_SUFFIXES.iter().any(|s| name.ends_with(s))
+        {
+            paths.push(entry.into_path());
+        }
+    }
     paths.sort();
+    Ok(paths)
+}
+
+/// `path` relative to the scanned `root`, for display.
+pub fn relative_name(root: &str, path: &Path) -> String {
+    path.strip_prefix(root)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .to_string()
+}
 
-    for path in paths {
+/// Scan a directory for .json files and produce summary rows.
+pub fn scan_dir(dir: &str, recursive: bool) -> Result<Vec<PersonaRow>> {
+    let mut rows = Vec::new();
+    for path in persona_paths(dir, recursive)? {
         match load_row(&path) {
-            Ok(row) => rows.push(row),
+            Ok(mut row) => {
+                row.file = relative_name(dir, &path);
+                rows.push(row);
+            }
             Err(e) => {
                 eprintln!("  skip {}: {e}", path.display());
             }
@@ -35,6 +91,33 @@ pub fn scan_dir(dir: &str) -> Result<Vec<PersonaRow>> {
     Ok(rows)
 }
 
+/// Sort rows by one of [`SORT_COLUMNS`], case-insensitively. Rows without a
+/// value (`-`) go last; ties keep file order.
+pub fn sort_rows(rows: &mut [PersonaRow], column: &str, reverse: bool) -> Result<()> {
+    let key = |r: &PersonaRow| -> String {
+        match column {
+            "name" => r.name.clone(),
+            "autonomy" => r.autonomy.clone(),
+            "phase" => r.phase.clone(),
+            _ => r.role.clone(),
+        }
+    };
+    if !SORT_COLUMNS.contains(&column) {
+        bail!(
+            "unknown sort column '{column}' (expected one of: {})",
+            SORT_COLUMNS.join(", ")
+        );
+    }
+    rows.sort_by_key(|r| {
+        let k = key(r);
+        (k == "-", k.to_lowercase())
+    });
+    if reverse {
+        rows.reverse();
+    }
+    Ok(())
+}
+
 fn load_row(path: &Path) -> Result<PersonaRow> {
     let content =
         std::fs::read_to_string(path).with_context(|| format!("cannot read {}", path.display()))?;
@@ -61,18 +144,36 @@ fn load_row(path: &Path) -> Result<PersonaRow> {
         .and_then(Value::as_str)
         .unwrap_or("-")
         .to_string();
+    let autonomy = data
+        .pointer("/authority/autonomy")
+        .and_then(Value::as_str)
+        .unwrap_or("-")
+        .to_string();
     let skills = data
         .pointer("/capabilities/skills")
         .and_then(Value::as_array)
         .map(|a| a.len())
         .unwrap_or(0);
+    let signed = data.get("signature").is_some();
+    let sidecars = crate::sidecar::Sidecars::for_persona(&path.to_string_lossy());
+    let has_audit = Path::new(&sidecars.audit).exists();
+    let state_path = sidecars.state;
+    let phase = std::fs::read_to_string(state_path)
+        .ok()
+        .and_then(|c| serde_json::from_str::<PhaseState>(&c).ok())
+        .and_then(|s| s.current_phase)
+        .unwrap_or_else(|| "-".into());
 
     Ok(PersonaRow {
         file,
         name,
         mbti,
         role,
+        autonomy,
+        phase,
         skills,
+        signed,
+        has_audit,
     })
 }
 
@@ -85,24 +186,43 @@ pub fn print_table(rows: &[PersonaRow]) {
     let w_file = rows.iter().map(|r| r.file.len()).max().unwrap_or(4).max(4);
     let w_name = rows.iter().map(|r| r.name.len()).max().unwrap_or(4).max(4);
     let w_role = rows.iter().map(|r| r.role.len()).max().unwrap_or(4).max(4);
+    let w_auto = rows
+        .iter()
+        .map(|r| r.autonomy.len())
+        .max()
+        .unwrap_or(8)
+        .max(8);
+    let w_phase = rows.iter().map(|r| r.phase.len()).max().unwrap_or(5).max(5);
+
+    let yes_no = |b: bool| if b { "yes" } else { "no" };
 
-    let header = "SKILLS";
-    let separator = "------";
     println!(
-        "{:<w_file$}  {:<w_name$}  {:<4}  {:<w_role$}  {header}",
-        "FILE", "NAME", "MBTI", "ROLE"
+        "{:<w_file$}  {:<w_name$}  {:<4}  {:<w_role$}  {:<w_auto$}  {:<w_phase$}  {:<6}  {:<6}  AUDIT",
+        "FILE", "NAME", "MBTI", "ROLE", "AUTONOMY", "PHASE", "SKILLS", "SIGNED"
     );
     println!(
-        "{:<w_file$}  {:<w_name$}  {:<4}  {:<w_role$}  {separator}",
+        "{:<w_file$}  {:<w_name$}  {:<4}  {:<w_role$}  {:<w_auto$}  {:<w_phase$}  {:<6}  {:<6}  -----",
         "-".repeat(w_file),
         "-".repeat(w_name),
         "----",
-        "-".repeat(w_role)
+        "-".repeat(w_role),
+        "-".repeat(w_auto),
+        "-".repeat(w_phase),
+        "------",
+        "------"
     );
     for r in rows {
         println!(
-            "{:<w_file$}  {:<w_name$}  {:<4}  {:<w_role$}  {}",
-            r.file, r.name, r.mbti, r.role, r.skills
+            "{:<w_file$}  {:<w_name$}  {:<4}  {:<w_role$}  {:<w_auto$}  {:<w_phase$}  {:<6}  {:<6}  {}",
+            r.file,
+            r.name,
+            r.mbti,
+            r.role,
+            r.autonomy,
+            r.phase,
+            r.skills,
+            yes_no(r.signed),
+            yes_no(r.has_audit)
         );
     }
 }
@@ -6,6 +6,10 @@ use crate::schema::detect_version;
 /// Migrate a persona from v0.2 to v1.0.
 ///
 /// Returns the migrated value. If already v1.0, returns as-is.
+///
+/// Only `version` and `$schema` are written; everything else, `ext` and
+/// keys the typed `Persona` does not know included, passes through verbatim.
+/// Keep this on `Value`: deserializing into `Persona` would drop them.
 pub fn migrate_to_v1(data: &Value) -> Result<Value> {
     let version = detect_version(data);
     if version == "1.0" {
@@ -38,22 +42,48 @@ pub fn migrate_to_v1(data: &Value) -> Result<Value> {
     Ok(migrated)
 }
 
-/// Migrate a file in-place.
-pub fn migrate_file(path: &str) -> Result<()> {
+/// A planned file migration: the document before and after, and the exact
+/// text that would be written.
+#[derive(Debug, Clone)]
+pub struct Migration {
+    pub original: Value,
+    pub migrated: Value,
+    pub output: String,
+}
+
+/// Run the full migration of `path` without writing anything. Returns `None`
+/// if the file is already v1.0.
+pub fn plan_migration(path: &str, canonical: bool) -> Result<Option<Migration>> {
     let content =
         std::fs::read_to_string(path).map_err(|e| anyhow::anyhow!("cannot read {path}: {e}"))?;
     let data: Value =
         serde_json::from_str(&content).map_err(|e| anyhow::anyhow!("{path}: invalid JSON: {e}"))?;
 
-    let version = detect_version(&data);
-    if version == "1.0" {
-        eprintln!("  skip {path} (already v1.0)");
-        return Ok(());
+    if detect_version(&data) == "1.0" {
+        return Ok(None);
     }
 
     let migrated = migrate_to_v1(&data)?;
-    let json = serde_json::to_string_pretty(&migrated)?;
-    std::fs::write(path, json)?;
+    let output = if canonical {
+        crate::canonical::canonicalize(&migrated)
+    } else {
+        serde_json::to_string_pretty(&migrated)?
+    };
+    Ok(Some(Migration {
+        original: data,
+        migrated,
+        output,
+    }))
+}
+
+/// Migrate a file in-place. With `canonical`, the result is written as JCS
+/// (RFC 8785) instead of pretty-printed JSON.
+pub fn migrate_file(path: &str, canonical: bool) -> Result<()> {
+    let Some(migration) = plan_migration(path, canonical)? else {
+        eprintln!("  skip {path} (already v1.0)");
+        return Ok(());
+    };
+    std::fs::write(path, migration.output)?;
     eprintln!("  migrated {path} (v0.2 → v1.0)");
     Ok(())
 }
@@ -122,4 +152,22 @@ mod tests {
         let result = migrate_to_v1(&v10).unwrap();
         assert_eq!(result, v10);
     }
+
+    #[test]
+    fn migrate_preserves_ext_and_unknown_fields() {
+        let v02 = json!({
+            "name": "Test",
+            "role": "Tester",
+            "ext": {
+                "custom": { "key": 42, "nested": { "deep": true, "list": [1, "two", null] } }
+            },
+            "x_vendor": { "opaque": "kept" }
+        });
+
+        let migrated = migrate_to_v1(&v02).unwrap();
+        let mut expected = v02.clone();
+        expected["version"] = json!("1.0");
+        expected["$schema"] = json!("https://ampersona.dev/schema/v1.0/ampersona.schema.json");
+        assert_eq!(migrated, expected);
+    }
 }
@@ -1,14 +1,40 @@
-use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
 use serde_json::Value;
 
+use crate::deref::canonical;
+use crate::traits::ResolvedAuthority;
+
+/// Runtime state rendered as a "Current State" section of the prompt.
+#[derive(Debug, Clone)]
+pub struct PromptState {
+    /// Current phase, `None` before the first transition.
+    pub phase: Option<String>,
+    /// Gate that produced the last transition, preferred when picking a snippet.
+    pub last_gate: Option<String>,
+    /// Authority after defaults, elevations and the active overlay are applied.
+    pub authority: ResolvedAuthority,
+}
+
 /// Convert an ampersona JSON to a Markdown system prompt.
-pub fn to_system_prompt(data: &Value, sections: &[String]) -> String {
+///
+/// With `state`, a "Current State" section (selectable as `state`) follows the
+/// identity, so the agent's self-description matches what is enforced.
+/// Selecting `authority` explicitly also renders imperative guardrails, taken
+/// from the resolved authority in `state` when given, else from the spec.
+pub fn to_system_prompt(data: &Value, sections: &[String], state: Option<&PromptState>) -> String {
     let mut out = String::with_capacity(2048);
     let all = sections.is_empty();
 
     if all || sections.iter().any(|s| s == "identity") {
         emit_identity(&mut out, data);
     }
+    if let Some(state) = state {
+        if all || sections.iter().any(|s| s == "state") {
+            emit_state(&mut out, data, state);
+        }
+    }
     if let Some(psych) = data.get("psychology") {
         if all || sections.iter().any(|s| s == "psychology") {
             emit_psychology(&mut out, psych);
@@ -30,9 +56,14 @@ pub fn to_system_prompt(data: &Value, sections: &[String]) -> String {
         }
     }
     if let Some(auth) = data.get("authority") {
-        if all || sections.iter().any(|s| s == "authority") {
+        // Guardrails are opt-in: only an explicit `authority` section adds them.
+        let explicit = sections.iter().any(|s| s == "authority");
+        if all || explicit {
             emit_authority(&mut out, auth);
         }
+        if explicit {
+            emit_guardrails(&mut out, auth, state.map(|s| &s.authority));
+        }
     }
     if let Some(gates) = data.get("gates") {
         if all || sections.iter().any(|s| s == "gates") {
@@ -42,6 +73,51 @@ pub fn to_system_prompt(data: &Value, sections: &[String]) -> String {
     out
 }
 
+/// The prompt snippet for `phase`, or a generic line when none is declared.
+///
+/// Snippets live on gates as `on_pass.prompt`. The gate named by `last_gate`
+/// wins if it leads to `phase`; otherwise the first gate into `phase` that
+/// declares one is used.
+pub fn phase_preamble(data: &Value, phase: Option<&str>, last_gate: Option<&str>) -> String {
+    let Some(phase) = phase else {
+        return "No phase transition has been recorded yet.".to_string();
+    };
+    let gates = data
+        .get("gates")
+        .and_then(Value::as_array)
+        .map(Vec::as_slice)
+        .unwrap_or(&[]);
+    fn snippet<'a>(gate: &'a Value, phase: &str) -> Option<&'a str> {
+        if gate.get("to_phase").and_then(Value::as_str) != Some(phase) {
+            return None;
+        }
+        gate.pointer("/on_pass/prompt")
+            .and_then(Value::as_str)
+            .filter(|p| !p.trim().is_empty())
+    }
+    last_gate
+        .and_then(|id| {
+            gates
+                .iter()
+                .find(|g| g.get("id").and_then(Value::as_str) == Some(id))
+        })
+        .and_then(|g| snippet(g, phase))
+        .or_else(|| gates.iter().find_map(|g| snippet(g, phase)))
+        .map(str::to_string)
+        .unwrap_or_else(|| {
+            format!("You are in the '{phase}' phase. Act within the authority listed below.")
+        })
+}
+
+/// `phases.<phase>.description`, when declared and non-empty.
+pub fn phase_description<'a>(data: &'a Value, phase: &str) -> Option<&'a str> {
+    data.get("phases")?
+        .get(phase)?
+        .get("description")?
+        .as_str()
+        .filter(|d| !d.trim().is_empty())
+}
+
 /// Convert to TOON format.
 pub fn to_toon(data: &Value) -> Result<String> {
     let json_str = serde_json::to_string(data).context("serialize for TOON")?;
@@ -50,10 +126,104 @@ pub fn to_toon(data: &Value) -> Result<String> {
     Ok(toon::encode(parsed, None))
 }
 
-/// Load persona JSON from a file path.
+/// Longest `$extends` chain [`load_persona`] will follow.
+pub const MAX_EXTENDS_DEPTH: usize = 8;
+
+/// How [`load_persona_with`] turns a file into the effective persona.
+#[derive(Debug, Clone, Copy)]
+pub struct LoadOptions {
+    /// Inline `$ref` sections (see [`crate::deref::deref`]). On by default.
+    pub deref: bool,
+    /// Expand `${VAR}` in string fields (see [`crate::interpolate::expand_env`]).
+    pub expand_env: bool,
+}
+
+impl Default for LoadOptions {
+    fn default() -> Self {
+        Self {
+            deref: true,
+            expand_env: false,
+        }
+    }
+}
+
+/// Load persona JSON from a file path, inlining `$ref`s and resolving `$extends`.
 pub fn load_persona(path: &str) -> Result<Value> {
+    load_persona_with(path, LoadOptions::default())
+}
+
+/// [`load_persona`] with explicit [`LoadOptions`].
+pub fn load_persona_with(path: &str, opts: LoadOptions) -> Result<Value> {
     let content = std::fs::read_to_string(path).with_context(|| format!("cannot read {path}"))?;
-    serde_json::from_str(&content).with_context(|| format!("{path}: invalid JSON"))
+    let data = serde_json::from_str(&content).with_context(|| format!("{path}: invalid JSON"))?;
+    resolve_with(data, path, opts)
+}
+
+/// Turn an already-parsed document at `path` into the effective persona.
+pub fn resolve_with(data: Value, path: &str, opts: LoadOptions) -> Result<Value> {
+    let data = resolve_extends_with(data, path, opts.deref)?;
+    if opts.expand_env {
+        crate::interpolate::expand_env(data)
+            .with_context(|| format!("{path}: cannot expand environment variables"))
+    } else {
+        Ok(data)
+    }
+}
+
+/// Resolve a top-level `$extends` field against the file at `path`.
+///
+/// The base is loaded relative to `path`'s directory (itself resolved
+/// recursively) and `data` is applied on top with
+/// [`merge_personas`](crate::compose::merge_personas). The `$extends` key is
+/// dropped from the result. Cycles and chains longer than
+/// [`MAX_EXTENDS_DEPTH`] are rejected. Each document's `$ref` sections are
+/// inlined, relative to that document, before merging.
+pub fn resolve_extends(data: Value, path: &str) -> Result<Value> {
+    resolve_extends_with(data, path, true)
+}
+
+fn resolve_extends_with(data: Value, path: &str, deref: bool) -> Result<Value> {
+    let mut chain = vec![canonical(Path::new(path))];
+    extend(data, Path::new(path), deref, &mut chain)
+}
+
+fn extend(data: Value, path: &Path, deref: bool, chain: &mut Vec<PathBuf>) -> Result<Value> {
+    let mut data = if deref {
+        crate::deref::deref(data, &path.to_string_lossy())?
+    } else {
+        data
+    };
+    let Some(base_ref) = data.as_object_mut().and_then(|o| o.remove("$extends")) else {
+        return Ok(data);
+    };
+    let Some(base_ref) = base_ref.as_str() else {
+        bail!("{}: $extends must be a string", path.display());
+    };
+    let base_path = path.parent().unwrap_or(Path::new("")).join(base_ref);
+    let key = canonical(&base_path);
+    if chain.contains(&key) {
+        let cycle: Vec<String> = chain
+            .iter()
+            .chain(std::iter::once(&key))
+            .map(|p| p.display().to_string())
+            .collect();
+        bail!("$extends cycle: {}", cycle.join(" -> "));
+    }
+    if chain.len() > MAX_EXTENDS_DEPTH {
+        bail!(
+            "{}: $extends chain deeper than {MAX_EXTENDS_DEPTH}",
+            path.display()
+        );
+    }
+    chain.push(key);
+
+    let display = base_path.display();
+    let content = std::fs::read_to_string(&base_path)
+        .with_context(|| format!("{}: cannot read $extends base {display}", path.display()))?;
+    let base =
+        serde_json::from_str(&content).with_context(|| format!("{display}: invalid JSON"))?;
+    let base = extend(base, &base_path, deref, chain)?;
+    Ok(crate::compose::merge_personas(&base, &data))
 }
 
 // ── Helpers ─────────────────────────────────────────────────────
@@ -179,6 +349,10 @@ fn emit_psychology(out: &mut String, v: &Value) {
             out.push_str(&format!("**Mood:** {mood} (volatility={vol})\n"));
         }
     }
+    let notes = arr_strings(v, "notes");
+    if !notes.is_empty() {
+        out.push_str(&format!("**Notes:** {}\n", notes.join("; ")));
+    }
     out.push('\n');
 }
 
@@ -310,6 +484,158 @@ fn emit_authority(out: &mut String, v: &Value) {
     out.push('\n');
 }
 
+fn emit_state(out: &mut String, data: &Value, state: &PromptState) {
+    out.push_str("## Current State\n\n");
+    let phase = state.phase.as_deref();
+    out.push_str(&format!("**Phase:** {}\n", phase.unwrap_or("(none)")));
+    out.push_str(&format!(
+        "\n{}\n\n",
+        phase_preamble(data, phase, state.last_gate.as_deref())
+    ));
+    if let Some(description) = phase.and_then(|p| phase_description(data, p)) {
+        out.push_str(&format!("**About this phase:** {description}\n\n"));
+    }
+
+    let auth = &state.authority;
+    let autonomy = serde_json::to_value(auth.autonomy)
+        .ok()
+        .and_then(|v| v.as_str().map(String::from))
+        .unwrap_or_default();
+    out.push_str(&format!("**Effective autonomy:** {autonomy}\n"));
+    let join = |ids: &[crate::actions::ActionId]| {
+        ids.iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    if !auth.allowed_actions.is_empty() {
+        out.push_str(&format!(
+            "**Allowed actions:** {}\n",
+            join(&auth.allowed_actions)
+        ));
+    }
+    if !auth.denied_actions.is_empty() {
+        out.push_str(&format!(
+            "**Denied actions:** {}\n",
+            join(&auth.denied_actions)
+        ));
+    }
+    if let Some(limits) = &auth.limits {
+        if let Some(ma) = limits.max_actions_per_hour {
+            out.push_str(&format!("**Rate limit:** {ma} actions/hour\n"));
+        }
+        if let Some(mc) = limits.max_cost_per_day_cents {
+            out.push_str(&format!("**Cost limit:** {mc} cents/day\n"));
+        }
+    }
+    out.push('\n');
+}
+
+/// A denied action with the reason and compliance ref from its deny entry.
+struct DenyLine {
+    action: String,
+    reason: Option<String>,
+    compliance_ref: Option<String>,
+}
+
+fn emit_guardrails(out: &mut String, v: &Value, resolved: Option<&ResolvedAuthority>) {
+    let (autonomy, allow, deny, scope) = match resolved {
+        Some(r) => (
+            serde_json::to_value(r.autonomy)
+                .ok()
+                .and_then(|a| a.as_str().map(String::from))
+                .unwrap_or_default(),
+            r.allowed_actions.iter().map(ToString::to_string).collect(),
+            r.denied_actions
+                .iter()
+                .map(|id| {
+                    let action = id.to_string();
+                    let meta = r.deny_metadata.get(&action);
+                    DenyLine {
+                        reason: meta.and_then(|m| m.reason.clone()),
+                        compliance_ref: meta.and_then(|m| m.compliance_ref.clone()),
+                        action,
+                    }
+                })
+                .collect(),
+            r.scope
+                .as_ref()
+                .and_then(|sc| serde_json::to_value(sc).ok()),
+        ),
+        None => {
+            let actions = v.get("actions").cloned().unwrap_or(Value::Null);
+            let deny: Vec<DenyLine> = actions
+                .get("deny")
+                .and_then(Value::as_array)
+                .map(|entries| {
+                    entries
+                        .iter()
+                        .filter_map(|d| {
+                            let action = d.as_str().or_else(|| d.get("action")?.as_str())?;
+                            let opt = |key| Some(s(d, key)).filter(|x| !x.is_empty());
+                            Some(DenyLine {
+                                action: action.to_string(),
+                                reason: opt("reason"),
+                                compliance_ref: opt("compliance_ref"),
+                            })
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+            (
+                s(v, "autonomy"),
+                arr_strings(&actions, "allow"),
+                deny,
+                v.get("scope").cloned(),
+            )
+        }
+    };
+
+    out.push_str("### Guardrails\n\n");
+    match autonomy.as_str() {
+        "readonly" => out.push_str("- Observe and report only. Do not change anything.\n"),
+        "supervised" => out.push_str("- Ask for approval before any action with side effects.\n"),
+        "full" => out.push_str("- You may act without approval, within the limits below.\n"),
+        _ => {}
+    }
+    if !allow.is_empty() {
+        out.push_str(&format!("- You may: {}.\n", allow.join(", ")));
+    }
+    if !deny.is_empty() {
+        let items: Vec<String> = deny
+            .iter()
+            .map(|d| match (&d.reason, &d.compliance_ref) {
+                (Some(r), Some(c)) => format!("{} (reason: {r}; ref: {c})", d.action),
+                (Some(r), None) => format!("{} (reason: {r})", d.action),
+                (None, Some(c)) => format!("{} (ref: {c})", d.action),
+                (None, None) => d.action.clone(),
+            })
+            .collect();
+        out.push_str(&format!("- You must not: {}.\n", items.join(", ")));
+    }
+    if let Some(scope) = scope {
+        let allowed = arr_strings(&scope, "allowed_paths");
+        if !allowed.is_empty() {
+            out.push_str(&format!(
+                "- Only touch paths matching: {}.\n",
+                allowed.join(", ")
+            ));
+        }
+        let forbidden = arr_strings(&scope, "forbidden_paths");
+        if !forbidden.is_empty() {
+            out.push_str(&format!("- Never touch: {}.\n", forbidden.join(", ")));
+        }
+        if scope
+            .get("workspace_only")
+            .and_then(Value::as_bool)
+            .unwrap_or(false)
+        {
+            out.push_str("- Stay inside the workspace.\n");
+        }
+    }
+    out.push_str("- If a request falls outside these guardrails, refuse and say why.\n\n");
+}
+
 fn emit_gates(out: &mut String, v: &Value) {
     let gates = match v.as_array() {
         Some(g) if !g.is_empty() => g,
@@ -365,3 +691,69 @@ fn emit_directives(out: &mut String, v: &Value) {
     }
     out.push('\n');
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(dir: &Path, name: &str, data: Value) -> String {
+        let path = dir.join(name);
+        std::fs::write(&path, data.to_string()).unwrap();
+        path.to_str().unwrap().to_string()
+    }
+
+    #[test]
+    fn extends_chain_is_resolved_in_order() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("base")).unwrap();
+        write(
+            dir.path(),
+            "base/root.json",
+            serde_json::json!({"name": "Root", "role": "root", "directives": {"core_drive": "ship"}}),
+        );
+        write(
+            dir.path(),
+            "base/team.json",
+            serde_json::json!({"$extends": "root.json", "role": "team"}),
+        );
+        let leaf = write(
+            dir.path(),
+            "leaf.json",
+            serde_json::json!({"$extends": "base/team.json", "name": "Leaf"}),
+        );
+
+        let resolved = load_persona(&leaf).unwrap();
+        assert_eq!(resolved["name"], "Leaf");
+        assert_eq!(resolved["role"], "team");
+        assert_eq!(resolved["directives"]["core_drive"], "ship");
+        assert!(resolved.get("$extends").is_none());
+    }
+
+    #[test]
+    fn extends_cycle_and_depth_are_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        write(
+            dir.path(),
+            "a.json",
+            serde_json::json!({"$extends": "b.json"}),
+        );
+        let b = write(
+            dir.path(),
+            "b.json",
+            serde_json::json!({"$extends": "a.json"}),
+        );
+        let err = load_persona(&b).unwrap_err().to_string();
+        assert!(err.contains("$extends cycle"), "{err}");
+
+        let mut leaf = write(dir.path(), "p0.json", serde_json::json!({"name": "p0"}));
+        for i in 1..=MAX_EXTENDS_DEPTH + 1 {
+            leaf = write(
+                dir.path(),
+                &format!("p{i}.json"),
+                serde_json::json!({"$extends": format!("p{}.json", i - 1)}),
+            );
+        }
+        let err = load_persona(&leaf).unwrap_err().to_string();
+        assert!(err.contains("deeper than"), "{err}");
+    }
+}
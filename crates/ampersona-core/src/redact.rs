@@ -0,0 +1,180 @@
+use anyhow::{bail, Result};
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::schema::check;
+
+/// Placeholder written over masked strings.
+pub const MASK: &str = "***";
+
+/// Fields `--preset external` redacts: free-form history, local paths and
+/// commands, vendor extensions, and the (now stale) signature.
+pub const EXTERNAL_PRESET: &[&str] = &[
+    "backstory",
+    "signature",
+    "authority.scope.allowed_paths",
+    "authority.scope.forbidden_paths",
+    "authority.actions.scoped",
+    "ext",
+];
+
+/// Field lists accepted by `--preset`.
+pub fn preset(name: &str) -> Option<&'static [&'static str]> {
+    match name {
+        "external" => Some(EXTERNAL_PRESET),
+        _ => None,
+    }
+}
+
+/// One redacted field.
+#[derive(Debug, Clone, Serialize)]
+pub struct Redaction {
+    /// JSON pointer of the field.
+    pub path: String,
+    /// `masked` or `removed`.
+    pub action: &'static str,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RedactOutcome {
+    pub redacted: Vec<Redaction>,
+    /// Requested fields absent from the document.
+    pub missing: Vec<String>,
+}
+
+/// Redact `fields` (dotted paths or JSON pointers) in place.
+///
+/// Masking keeps the field's shape: every string inside it becomes `"***"`,
+/// numbers and booleans stay. With `remove`, a field is dropped unless that
+/// adds validation errors (a required field), in which case it is masked.
+pub fn redact(data: &mut Value, fields: &[String], remove: bool) -> Result<RedactOutcome> {
+    let mut outcome = RedactOutcome::default();
+    for field in fields {
+        let pointer = to_pointer(field)?;
+        if data.pointer(&pointer).is_none() {
+            outcome.missing.push(pointer);
+            continue;
+        }
+        if remove {
+            let mut candidate = data.clone();
+            remove_at(&mut candidate, &pointer);
+            let before = check(data, "", false).errors.len();
+            if check(&candidate, "", false).errors.len() <= before {
+                *data = candidate;
+                outcome.redacted.push(Redaction {
+                    path: pointer,
+                    action: "removed",
+                });
+                continue;
+            }
+        }
+        if let Some(slot) = data.pointer_mut(&pointer) {
+            mask(slot);
+        }
+        outcome.redacted.push(Redaction {
+            path: pointer,
+            action: "masked",
+        });
+    }
+    Ok(outcome)
+}
+
+/// `authority.scope.allowed_paths` → `/authority/scope/allowed_paths`;
+/// pointers pass through.
+fn to_pointer(field: &str) -> Result<String> {
+    let field = field.trim();
+    if field.is_empty() || field == "/" {
+        bail!("cannot redact the whole document");
+    }
+    if field.starts_with('/') {
+        return Ok(field.to_string());
+    }
+    if field.split('.').any(str::is_empty) {
+        bail!("invalid field path '{field}'");
+    }
+    Ok(field
+        .split('.')
+        .map(|seg| format!("/{}", seg.replace('~', "~0").replace('/', "~1")))
+        .collect())
+}
+
+fn remove_at(data: &mut Value, pointer: &str) {
+    let (parent, last) = pointer.rsplit_once('/').unwrap_or(("", pointer));
+    let key = last.replace("~1", "/").replace("~0", "~");
+    match data.pointer_mut(parent) {
+        Some(Value::Object(obj)) => {
+            obj.shift_remove(&key);
+        }
+        Some(Value::Array(items)) => {
+            if let Ok(i) = key.parse::<usize>() {
+                if i < items.len() {
+                    items.remove(i);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+fn mask(value: &mut Value) {
+    match value {
+        Value::String(s) => *s = MASK.to_string(),
+        Value::Array(items) => items.iter_mut().for_each(mask),
+        Value::Object(obj) => obj.values_mut().for_each(mask),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn persona() -> Value {
+        json!({
+            "version": "1.0",
+            "name": "Ada",
+            "role": "Reviewer",
+            "backstory": "Worked at an internal team.",
+            "psychology": {
+                "neural_matrix": {"creativity": 0.5, "empathy": 0.5, "logic": 0.5,
+                    "adaptability": 0.5, "charisma": 0.5, "reliability": 0.5},
+                "traits": {"ocean": {"openness": 0.5, "conscientiousness": 0.5,
+                    "extraversion": 0.5, "agreeableness": 0.5, "neuroticism": 0.5},
+                    "mbti": "INTJ"}
+            },
+            "voice": {"style": {"descriptors": ["terse"], "formality": 0.5, "verbosity": 0.5}},
+            "authority": {"autonomy": "supervised",
+                "scope": {"allowed_paths": ["/srv/internal/**"], "workspace_only": true}},
+            "signature": {"key_id": "k1", "value": "abc"}
+        })
+    }
+
+    #[test]
+    fn masks_backstory_and_signature() {
+        let mut data = persona();
+        let fields = vec!["backstory".to_string(), "/signature".to_string()];
+        let outcome = redact(&mut data, &fields, false).unwrap();
+        assert_eq!(outcome.redacted.len(), 2);
+        assert_eq!(data["backstory"], MASK);
+        assert_eq!(data["signature"], json!({"key_id": MASK, "value": MASK}));
+        assert_eq!(data["authority"]["scope"]["workspace_only"], true);
+    }
+
+    #[test]
+    fn remove_keeps_required_fields_masked() {
+        let mut data = persona();
+        let fields: Vec<String> = ["backstory", "signature", "role", "nope"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let outcome = redact(&mut data, &fields, true).unwrap();
+        let actions: Vec<_> = outcome.redacted.iter().map(|r| r.action).collect();
+        assert_eq!(actions, ["removed", "removed", "masked"]);
+        assert_eq!(outcome.missing, ["/nope"]);
+        assert!(data.get("backstory").is_none() && data.get("signature").is_none());
+        assert_eq!(data["role"], MASK);
+        let errors = check(&data, "", false).errors;
+        assert!(errors.is_empty(), "{errors:?}");
+    }
+}
@@ -1,9 +1,20 @@
-use anyhow::Result;
+use anyhow::{bail, Result};
 use serde_json::{json, Value};
 
+use crate::actions::actions_for_capability;
 use crate::prompt;
 
+/// A declared capability that maps to an action the persona's authority denies.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CapabilityConflict {
+    pub skill: String,
+    pub action: &'static str,
+}
+
 /// Build the `register_agent` arguments from a persona JSON.
+///
+/// With `strict`, fails if any declared capability maps to a denied action
+/// (see [`capability_conflicts`]).
 pub fn build_args(
     data: &Value,
     project: &str,
@@ -11,7 +22,22 @@ pub fn build_args(
     model: &str,
     include_prompt: bool,
     toon: bool,
+    strict: bool,
 ) -> Result<Value> {
+    if strict {
+        let conflicts = capability_conflicts(data);
+        if !conflicts.is_empty() {
+            let list: Vec<String> = conflicts
+                .iter()
+                .map(|c| format!("'{}' → {}", c.skill, c.action))
+                .collect();
+            bail!(
+                "capabilities conflict with denied actions: {}",
+                list.join(", ")
+            );
+        }
+    }
+
     let name = data
         .get("name")
         .and_then(Value::as_str)
@@ -21,7 +47,7 @@ pub fn build_args(
         if toon {
             prompt::to_toon(data)?
         } else {
-            prompt::to_system_prompt(data, &[])
+            prompt::to_system_prompt(data, &[], None)
         }
     } else {
         data.get("role")
@@ -39,11 +65,82 @@ pub fn build_args(
     }))
 }
 
+/// Capabilities (`capabilities.skills`) whose implied actions appear in
+/// `authority.actions.deny`.
+pub fn capability_conflicts(data: &Value) -> Vec<CapabilityConflict> {
+    let denied: Vec<&str> = data
+        .pointer("/authority/actions/deny")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|d| {
+            d.as_str()
+                .or_else(|| d.get("action").and_then(Value::as_str))
+        })
+        .collect();
+    if denied.is_empty() {
+        return Vec::new();
+    }
+
+    let mut conflicts = Vec::new();
+    for skill in data
+        .pointer("/capabilities/skills")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|s| s.get("name").and_then(Value::as_str))
+    {
+        for action in actions_for_capability(skill) {
+            if denied.contains(&action.as_str()) {
+                conflicts.push(CapabilityConflict {
+                    skill: skill.to_string(),
+                    action: action.as_str(),
+                });
+            }
+        }
+    }
+    conflicts
+}
+
+/// MCP tool declarations derived from `capabilities.skills` (name, description).
+pub fn skill_tools(data: &Value) -> Value {
+    let tools: Vec<Value> = data
+        .pointer("/capabilities/skills")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|skill| {
+            let name = skill.get("name").and_then(Value::as_str)?;
+            let description = skill
+                .get("description")
+                .and_then(Value::as_str)
+                .unwrap_or("");
+            Some(json!({ "name": name, "description": description }))
+        })
+        .collect();
+    Value::Array(tools)
+}
+
 /// Wrap arguments in a JSON-RPC 2.0 envelope for `register_agent`.
 pub fn wrap_rpc(args: Value) -> Value {
+    wrap_rpc_with_id(args, "1")
+}
+
+/// Wrap several argument sets in a JSON-RPC 2.0 batch (ids `"1"`, `"2"`, ...).
+pub fn wrap_rpc_batch(args: Vec<Value>) -> Value {
+    Value::Array(
+        args.into_iter()
+            .enumerate()
+            .map(|(i, a)| wrap_rpc_with_id(a, &(i + 1).to_string()))
+            .collect(),
+    )
+}
+
+/// [`wrap_rpc`] with an explicit request id (for streamed batches).
+pub fn wrap_rpc_with_id(args: Value, id: &str) -> Value {
     json!({
         "jsonrpc": "2.0",
-        "id": "1",
+        "id": id,
         "method": "tools/call",
         "params": {
             "name": "register_agent",
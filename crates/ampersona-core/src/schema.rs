@@ -15,14 +15,32 @@ pub fn detect_version(data: &Value) -> &'static str {
     }
 }
 
-/// Create a validator for the given version.
-pub fn validator_for(version: &str) -> Result<Validator> {
-    let schema_str = match version {
+/// The embedded JSON Schema text for `version` (anything but "1.0" is v0.2).
+/// This is the exact document the validators compile.
+pub fn embedded_schema(version: &str) -> &'static str {
+    match version {
         "1.0" => SCHEMA_V10,
         _ => SCHEMA_V02,
-    };
-    let schema: Value =
-        serde_json::from_str(schema_str).context("embedded schema is invalid JSON")?;
+    }
+}
+
+/// Create a validator for the given version.
+pub fn validator_for(version: &str) -> Result<Validator> {
+    let schema: Value = serde_json::from_str(embedded_schema(version))
+        .context("embedded schema is invalid JSON")?;
+    Validator::new(&schema).map_err(|e| anyhow::anyhow!("schema compilation failed: {e}"))
+}
+
+/// Create a validator for overlay documents (partial personas).
+///
+/// Same schema as [`validator_for`] minus the top-level `required` list: any
+/// subset of fields is accepted, but each field present must still conform.
+pub fn overlay_validator_for(version: &str) -> Result<Validator> {
+    let mut schema: Value = serde_json::from_str(embedded_schema(version))
+        .context("embedded schema is invalid JSON")?;
+    if let Some(obj) = schema.as_object_mut() {
+        obj.remove("required");
+    }
     Validator::new(&schema).map_err(|e| anyhow::anyhow!("schema compilation failed: {e}"))
 }
 
@@ -51,16 +69,39 @@ pub fn validate(data: &Value) -> Result<()> {
 }
 
 /// Validate multiple files, printing results. Returns (passed, failed) counts.
-pub fn validate_files(paths: &[String]) -> Result<(usize, usize)> {
+///
+/// `$ref` sections and `$extends` chains are resolved first, so the effective
+/// persona is checked.
+/// With `expand_env`, `${VAR}` references are expanded before validation too.
+/// With `as_overlay`, files are checked as overlays (see
+/// [`overlay_validator_for`]); one without a `version` is checked against v1.0.
+pub fn validate_files(
+    paths: &[String],
+    expand_env: bool,
+    strict_unknown: bool,
+    as_overlay: bool,
+) -> Result<(usize, usize)> {
     let mut passed = 0usize;
     let mut failed = 0usize;
     for path in paths {
-        let content =
-            std::fs::read_to_string(path).with_context(|| format!("cannot read {path}"))?;
-        let data: Value =
-            serde_json::from_str(&content).with_context(|| format!("{path}: invalid JSON"))?;
-        let v = validator(&data)?;
-        if v.is_valid(&data) {
+        let opts = crate::prompt::LoadOptions {
+            expand_env,
+            ..Default::default()
+        };
+        let data = crate::prompt::load_persona_with(path, opts)?;
+        let v = if !as_overlay {
+            validator(&data)?
+        } else if data.get("version").is_some() {
+            overlay_validator_for(detect_version(&data))?
+        } else {
+            overlay_validator_for("1.0")?
+        };
+        let unknown = if strict_unknown {
+            check_unknown_fields(&data)
+        } else {
+            Vec::new()
+        };
+        if v.is_valid(&data) && unknown.is_empty() {
             eprintln!("  ok  {path}");
             passed += 1;
         } else {
@@ -71,6 +112,10 @@ pub fn validate_files(paths: &[String]) -> Result<(usize, usize)> {
                 let loc = if p.is_empty() { "(root)".into() } else { p };
                 eprintln!("       {loc}: {error}");
             }
+            for issue in &unknown {
+                let loc = issue.path.as_deref().unwrap_or("$");
+                eprintln!("       {loc}: {}", issue.message);
+            }
         }
     }
     Ok((passed, failed))
@@ -122,10 +167,14 @@ pub fn check(data: &Value, file: &str, strict: bool) -> CheckReport {
     // Contract version check (opt-in)
     check_contract(data, &mut warnings);
 
+    // Mechanically fixable issues (W030-W033, see `amp check --fix`)
+    check_fixable(data, &mut warnings);
+
     // Lint checks
     lint_checks(data, &version, strict, &mut warnings);
 
-    let pass = errors.is_empty() && (!strict || warnings.is_empty());
+    // Fixable issues are advisory: each is optional in the schema or already an error.
+    let pass = errors.is_empty() && (!strict || warnings.iter().all(|w| w.check == "fixable"));
     CheckReport {
         file: file.to_string(),
         version,
@@ -135,6 +184,65 @@ pub fn check(data: &Value, file: &str, strict: bool) -> CheckReport {
     }
 }
 
+/// Keys the typed persona model does not know (`--strict-unknown-fields`).
+///
+/// The persona is deserialized into [`crate::spec::Persona`] and serialized
+/// back; any non-null key that does not survive is unknown (`E040`). Subtrees
+/// under `ext` are free-form and never reported. A document the model cannot
+/// deserialize yields a single `E041`.
+pub fn check_unknown_fields(data: &Value) -> Vec<CheckIssue> {
+    let issue = |code: &str, message: String, path: String| CheckIssue {
+        code: code.to_string(),
+        check: "unknown_field".to_string(),
+        message,
+        path: Some(path),
+    };
+    let modeled = serde_json::from_value::<crate::spec::Persona>(data.clone())
+        .map_err(|e| e.to_string())
+        .and_then(|p| serde_json::to_value(&p).map_err(|e| e.to_string()));
+    let modeled = match modeled {
+        Ok(m) => m,
+        Err(e) => {
+            return vec![issue(
+                "E041",
+                format!("cannot check for unknown fields: {e}"),
+                "$".into(),
+            )]
+        }
+    };
+    let mut unknown = Vec::new();
+    collect_unknown("$", data, &modeled, &mut unknown);
+    unknown
+        .into_iter()
+        .map(|(path, key)| issue("E040", format!("unknown field '{key}'"), path))
+        .collect()
+}
+
+fn collect_unknown(path: &str, data: &Value, modeled: &Value, out: &mut Vec<(String, String)>) {
+    match (data, modeled) {
+        (Value::Object(obj), Value::Object(known)) => {
+            for (key, value) in obj {
+                if key == "ext" {
+                    continue;
+                }
+                let child = format!("{path}.{key}");
+                match known.get(key) {
+                    Some(m) => collect_unknown(&child, value, m, out),
+                    // The model omits `None` and `false` defaults on the way back
+                    None if value.is_null() || *value == Value::Bool(false) => {}
+                    None => out.push((child, key.clone())),
+                }
+            }
+        }
+        (Value::Array(items), Value::Array(known)) => {
+            for (i, (value, m)) in items.iter().zip(known).enumerate() {
+                collect_unknown(&format!("{path}[{i}]"), value, m, out);
+            }
+        }
+        _ => {}
+    }
+}
+
 fn check_action_vocabulary(
     data: &Value,
     errors: &mut Vec<CheckIssue>,
@@ -349,7 +457,7 @@ fn decode_base64(s: &str) -> Option<Vec<u8>> {
     base64::engine::general_purpose::STANDARD.decode(s).ok()
 }
 
-/// Minimal JCS (RFC 8785) canonicalization for signature verification.
+/// JCS (RFC 8785) canonical bytes of the signed fields.
 fn jcs_canonicalize_fields(data: &Value, signed_fields: &[String]) -> Vec<u8> {
     let mut obj = serde_json::Map::new();
     if let Some(source) = data.as_object() {
@@ -359,59 +467,7 @@ fn jcs_canonicalize_fields(data: &Value, signed_fields: &[String]) -> Vec<u8> {
             }
         }
     }
-    jcs_canonicalize(&Value::Object(obj)).into_bytes()
-}
-
-fn jcs_canonicalize(value: &Value) -> String {
-    match value {
-        Value::Null => "null".to_string(),
-        Value::Bool(b) => if *b { "true" } else { "false" }.to_string(),
-        Value::Number(n) => {
-            if let Some(i) = n.as_i64() {
-                i.to_string()
-            } else if let Some(f) = n.as_f64() {
-                if f.fract() == 0.0 && f.abs() < (1i64 << 53) as f64 {
-                    (f as i64).to_string()
-                } else {
-                    format!("{f}")
-                }
-            } else {
-                n.to_string()
-            }
-        }
-        Value::String(s) => format!("\"{}\"", jcs_escape(s)),
-        Value::Array(arr) => {
-            let items: Vec<String> = arr.iter().map(jcs_canonicalize).collect();
-            format!("[{}]", items.join(","))
-        }
-        Value::Object(obj) => {
-            let mut keys: Vec<&String> = obj.keys().collect();
-            keys.sort();
-            let items: Vec<String> = keys
-                .iter()
-                .map(|k| format!("\"{}\":{}", jcs_escape(k), jcs_canonicalize(&obj[*k])))
-                .collect();
-            format!("{{{}}}", items.join(","))
-        }
-    }
-}
-
-fn jcs_escape(s: &str) -> String {
-    let mut result = String::with_capacity(s.len());
-    for ch in s.chars() {
-        match ch {
-            '"' => result.push_str("\\\""),
-            '\\' => result.push_str("\\\\"),
-            '\x08' => result.push_str("\\b"),
-            '\x0C' => result.push_str("\\f"),
-            '\n' => result.push_str("\\n"),
-            '\r' => result.push_str("\\r"),
-            '\t' => result.push_str("\\t"),
-            c if c < '\x20' => result.push_str(&format!("\\u{:04x}", c as u32)),
-            c => result.push(c),
-        }
-    }
-    result
+    crate::canonical::canonicalize(&Value::Object(obj)).into_bytes()
 }
 
 /// Known contract versions.
@@ -456,7 +512,29 @@ fn check_gate_consistency(data: &Value, warnings: &mut Vec<CheckIssue>) {
         }
     }
 
-    // E021: metrics_schema references metric not used in criteria
+    // E024: Malformed derived metric (`ratio(a, b)`, `sum(a, b, ...)`, `diff(a, b)`).
+    // The evaluator treats it as a missing metric, so the criterion never passes.
+    for (i, gate) in gates.iter().enumerate() {
+        let criteria = gate.get("criteria").and_then(Value::as_array);
+        for (j, c) in criteria.into_iter().flatten().enumerate() {
+            let Some(metric) = c.get("metric").and_then(Value::as_str) else {
+                continue;
+            };
+            if let Err(e) = crate::spec::gates::DerivedMetric::parse(metric) {
+                warnings.push(CheckIssue {
+                    code: "E024".to_string(),
+                    check: "consistency".to_string(),
+                    message: e,
+                    path: Some(format!("$.gates[{i}].criteria[{j}].metric")),
+                });
+            }
+        }
+    }
+
+    // W_METRIC_UNDECLARED / W_SCHEMA_UNUSED: criteria and metrics_schema must agree.
+    // When a gate declares a metrics_schema, a criterion metric missing from it skips
+    // the type check silently, and a schema entry no criterion uses is dead.
+    // Derived metrics are checked through their operands.
     for (i, gate) in gates.iter().enumerate() {
         if let Some(schema) = gate.get("metrics_schema").and_then(Value::as_object) {
             let criteria = gate
@@ -464,15 +542,30 @@ fn check_gate_consistency(data: &Value, warnings: &mut Vec<CheckIssue>) {
                 .and_then(Value::as_array)
                 .cloned()
                 .unwrap_or_default();
-            let criteria_metrics: Vec<String> = criteria
+            let criteria_metrics: Vec<Vec<String>> = criteria
                 .iter()
                 .filter_map(|c| c.get("metric").and_then(Value::as_str))
-                .map(|s| s.to_string())
+                .map(|m| match crate::spec::gates::DerivedMetric::parse(m) {
+                    Ok(Some(derived)) => derived.operands,
+                    _ => vec![m.to_string()],
+                })
                 .collect();
+            for (j, metrics) in criteria_metrics.iter().enumerate() {
+                for metric in metrics.iter().filter(|m| !schema.contains_key(*m)) {
+                    warnings.push(CheckIssue {
+                        code: "W_METRIC_UNDECLARED".to_string(),
+                        check: "consistency".to_string(),
+                        message: format!(
+                            "criterion metric '{metric}' is not declared in metrics_schema"
+                        ),
+                        path: Some(format!("$.gates[{i}].criteria[{j}].metric")),
+                    });
+                }
+            }
             for key in schema.keys() {
-                if !criteria_metrics.contains(key) {
+                if !criteria_metrics.iter().flatten().any(|m| m == key) {
                     warnings.push(CheckIssue {
-                        code: "E021".to_string(),
+                        code: "W_SCHEMA_UNUSED".to_string(),
                         check: "consistency".to_string(),
                         message: format!(
                             "metrics_schema declares '{key}' but no criterion references it"
@@ -484,6 +577,26 @@ fn check_gate_consistency(data: &Value, warnings: &mut Vec<CheckIssue>) {
         }
     }
 
+    // W_PHASE_UNDECLARED: with a `phases` section, gates may only reference
+    // declared phases. A null from_phase (initial state) needs no declaration.
+    if let Some(declared) = data.get("phases").and_then(Value::as_object) {
+        for (i, gate) in gates.iter().enumerate() {
+            for field in ["from_phase", "to_phase"] {
+                let Some(phase) = gate.get(field).and_then(Value::as_str) else {
+                    continue;
+                };
+                if !declared.contains_key(phase) {
+                    warnings.push(CheckIssue {
+                        code: "W_PHASE_UNDECLARED".to_string(),
+                        check: "consistency".to_string(),
+                        message: format!("{field} '{phase}' is not declared in phases"),
+                        path: Some(format!("$.gates[{i}].{field}")),
+                    });
+                }
+            }
+        }
+    }
+
     // E022: Duplicate gate id
     let mut seen_ids: Vec<String> = Vec::new();
     for (i, gate) in gates.iter().enumerate() {
@@ -501,7 +614,7 @@ fn check_gate_consistency(data: &Value, warnings: &mut Vec<CheckIssue>) {
         }
     }
 
-    // E023: Gate references phase not declared in any gate's from_phase/to_phase set
+    // E023: Gate references phase not declared in phases or any gate's from_phase/to_phase
     let mut known_phases: std::collections::HashSet<String> = std::collections::HashSet::new();
     for gate in gates {
         if let Some(f) = gate.get("from_phase").and_then(Value::as_str) {
@@ -511,6 +624,9 @@ fn check_gate_consistency(data: &Value, warnings: &mut Vec<CheckIssue>) {
             known_phases.insert(t.to_string());
         }
     }
+    if let Some(declared) = data.get("phases").and_then(Value::as_object) {
+        known_phases.extend(declared.keys().cloned());
+    }
     // Check on_pass.next_phase references
     for (i, gate) in gates.iter().enumerate() {
         if let Some(on_pass) = gate.get("on_pass") {
@@ -544,6 +660,48 @@ fn check_contract(data: &Value, warnings: &mut Vec<CheckIssue>) {
     // No warning for missing field — opt-in
 }
 
+fn check_fixable(data: &Value, warnings: &mut Vec<CheckIssue>) {
+    let mut warn = |code: &str, message: String, path: String| {
+        warnings.push(CheckIssue {
+            code: code.to_string(),
+            check: "fixable".to_string(),
+            message,
+            path: Some(path),
+        });
+    };
+    if data.get("version").is_none() {
+        warn(
+            "W031",
+            "version missing (treated as v0.2)".into(),
+            "$.version".into(),
+        );
+    }
+    if data.get("$schema").is_none() {
+        warn("W030", "$schema missing".into(), "$.$schema".into());
+    }
+    if let Some(raw) = data
+        .pointer("/psychology/moral_compass/alignment")
+        .and_then(Value::as_str)
+    {
+        if let Some(canonical) = crate::fix::normalize_alignment(raw) {
+            if canonical != raw {
+                warn(
+                    "W032",
+                    format!("alignment '{raw}' is not normalized (expected '{canonical}')"),
+                    "$.psychology.moral_compass.alignment".into(),
+                );
+            }
+        }
+    }
+    for i in crate::fix::skills_without_priority(data).0 {
+        warn(
+            "W033",
+            "skill without priority".into(),
+            format!("$.capabilities.skills[{i}].priority"),
+        );
+    }
+}
+
 fn lint_checks(data: &Value, version: &str, _strict: bool, warnings: &mut Vec<CheckIssue>) {
     if version != "1.0" {
         return;
@@ -582,14 +740,63 @@ fn lint_checks(data: &Value, version: &str, _strict: bool, warnings: &mut Vec<Ch
                     path: Some(format!("$.authority.actions.deny[{i}]")),
                 });
             }
+
+            // W003/W004: deny past its review date / expired (no longer enforced)
+            let Ok(entry) =
+                serde_json::from_value::<crate::spec::authority::DenyEntry>(entry.clone())
+            else {
+                continue;
+            };
+            let action = entry.action_id();
+            let dated = if entry.is_expired() {
+                entry.expires_at().map(|t| {
+                    (
+                        "W004",
+                        format!(
+                            "deny '{action}' expired {} and is no longer in force",
+                            day(t)
+                        ),
+                    )
+                })
+            } else if entry.review_overdue() {
+                entry.review_by().map(|t| {
+                    (
+                        "W003",
+                        format!("deny '{action}' is past its review date {}", day(t)),
+                    )
+                })
+            } else {
+                None
+            };
+            if let Some((code, message)) = dated {
+                warnings.push(CheckIssue {
+                    code: code.to_string(),
+                    check: "lint".to_string(),
+                    message,
+                    path: Some(format!("$.authority.actions.deny[{i}]")),
+                });
+            }
         }
     }
 }
 
+fn day(t: chrono::DateTime<chrono::Utc>) -> String {
+    t.format("%Y-%m-%d").to_string()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn embedded_schemas_are_valid_json_schema() {
+        for version in ["1.0", "0.2"] {
+            let schema: Value = serde_json::from_str(embedded_schema(version)).unwrap();
+            jsonschema::meta::validate(&schema)
+                .unwrap_or_else(|e| panic!("v{version} schema invalid: {e}"));
+        }
+    }
+
     fn minimal_v10() -> Value {
         serde_json::json!({
             "version": "1.0",
@@ -618,6 +825,43 @@ mod tests {
         })
     }
 
+    #[test]
+    fn deny_review_and_expiry_lints() {
+        let mut data = minimal_v10();
+        data["authority"] = serde_json::json!({
+            "autonomy": "full",
+            "actions": {
+                "deny": [
+                    { "action": "deploy", "reason": "r", "compliance_ref": "c",
+                      "review_by": "2999-01-01T00:00:00Z" },
+                    { "action": "merge_pr", "reason": "r", "compliance_ref": "c",
+                      "review_by": "2000-01-01T00:00:00Z" },
+                    { "action": "git_push", "reason": "r", "compliance_ref": "c",
+                      "review_by": "2000-01-01T00:00:00Z", "expires_at": "2001-01-01T00:00:00Z" }
+                ]
+            }
+        });
+        let report = check(&data, "test.json", false);
+        assert!(report.errors.is_empty(), "{:?}", report.errors);
+        let dated: Vec<_> = report
+            .warnings
+            .iter()
+            .filter(|w| w.code == "W003" || w.code == "W004")
+            .map(|w| (w.code.as_str(), w.path.as_deref().unwrap()))
+            .collect();
+        assert_eq!(
+            dated,
+            [
+                ("W003", "$.authority.actions.deny[1]"),
+                ("W004", "$.authority.actions.deny[2]")
+            ]
+        );
+        assert!(report
+            .warnings
+            .iter()
+            .any(|w| w.message.contains("2000-01-01")));
+    }
+
     #[test]
     fn contract_known_version_no_warning() {
         let mut data = minimal_v10();
@@ -805,4 +1049,139 @@ mod tests {
             "should warn on unknown contract version"
         );
     }
+
+    fn with_gate(metric: &str, schema_key: &str) -> Value {
+        let mut data = minimal_v10();
+        data.as_object_mut().unwrap().insert(
+            "gates".into(),
+            serde_json::json!([{
+                "id": "trust_decay",
+                "direction": "demote",
+                "from_phase": "active",
+                "to_phase": "restricted",
+                "criteria": [{ "metric": metric, "op": "gt", "value": 0 }],
+                "metrics_schema": { schema_key: { "type": "integer" } }
+            }]),
+        );
+        data
+    }
+
+    #[test]
+    fn criterion_metric_missing_from_schema_warns() {
+        let data = with_gate("policy_violation", "policy_violations");
+        let report = check(&data, "test.json", false);
+        let undeclared: Vec<_> = report
+            .warnings
+            .iter()
+            .filter(|w| w.code == "W_METRIC_UNDECLARED")
+            .collect();
+        assert_eq!(undeclared.len(), 1);
+        assert_eq!(
+            undeclared[0].path.as_deref(),
+            Some("$.gates[0].criteria[0].metric")
+        );
+        assert!(report.warnings.iter().any(|w| w.code == "W_SCHEMA_UNUSED"
+            && w.path.as_deref() == Some("$.gates[0].metrics_schema.policy_violations")));
+    }
+
+    #[test]
+    fn membership_ops_require_array_value() {
+        let mut data = with_gate("phase_tag", "phase_tag");
+        data["gates"][0]["metrics_schema"]["phase_tag"]["type"] = "string".into();
+        data["gates"][0]["criteria"][0] =
+            serde_json::json!({ "metric": "phase_tag", "op": "not_in", "value": ["a", "b"] });
+        assert!(check(&data, "test.json", false).errors.is_empty());
+
+        data["gates"][0]["criteria"][0]["value"] = "a".into();
+        let report = check(&data, "test.json", false);
+        assert!(report.errors.iter().any(|e| e.code == "E001"));
+    }
+
+    #[test]
+    fn contains_ops_require_string_value() {
+        let mut data = with_gate("last_error", "last_error");
+        data["gates"][0]["metrics_schema"]["last_error"]["type"] = "string".into();
+        data["gates"][0]["criteria"][0] = serde_json::json!(
+            { "metric": "last_error", "op": "not_contains", "value": "panic", "ci": false }
+        );
+        assert!(check(&data, "test.json", false).errors.is_empty());
+        assert!(check_unknown_fields(&data).is_empty());
+
+        data["gates"][0]["criteria"][0]["value"] = serde_json::json!(["panic"]);
+        let report = check(&data, "test.json", false);
+        assert!(report.errors.iter().any(|e| e.code == "E001"));
+    }
+
+    #[test]
+    fn derived_metric_operands_match_schema() {
+        let mut data = with_gate("ratio(errors, requests)", "errors");
+        data["gates"][0]["metrics_schema"]["requests"] = serde_json::json!({ "type": "integer" });
+        let report = check(&data, "test.json", false);
+        assert!(report
+            .warnings
+            .iter()
+            .all(|w| w.code != "W_METRIC_UNDECLARED"
+                && w.code != "W_SCHEMA_UNUSED"
+                && w.code != "E024"));
+
+        data["gates"][0]["criteria"][0]["metric"] = "ratio(errors)".into();
+        let report = check(&data, "test.json", false);
+        assert!(report.warnings.iter().any(
+            |w| w.code == "E024" && w.path.as_deref() == Some("$.gates[0].criteria[0].metric")
+        ));
+    }
+
+    #[test]
+    fn gates_must_reference_declared_phases() {
+        let mut data = with_gate("policy_violations", "policy_violations");
+        data["gates"][0]["from_phase"] = Value::Null;
+        let undeclared = |data: &Value| -> Vec<String> {
+            check(data, "test.json", false)
+                .warnings
+                .into_iter()
+                .filter(|w| w.code == "W_PHASE_UNDECLARED")
+                .filter_map(|w| w.path)
+                .collect()
+        };
+        // Without a phases section, phases stay implicit.
+        assert!(undeclared(&data).is_empty());
+
+        data["phases"] = serde_json::json!({ "restricted": { "description": "Read-only" } });
+        assert!(undeclared(&data).is_empty());
+
+        data["gates"][0]["from_phase"] = "active".into();
+        data["gates"][0]["to_phase"] = "restrcted".into();
+        assert_eq!(
+            undeclared(&data),
+            ["$.gates[0].from_phase", "$.gates[0].to_phase"]
+        );
+    }
+
+    #[test]
+    fn matching_metrics_schema_no_warning() {
+        let data = with_gate("policy_violations", "policy_violations");
+        let report = check(&data, "test.json", false);
+        assert!(report
+            .warnings
+            .iter()
+            .all(|w| w.code != "W_METRIC_UNDECLARED" && w.code != "W_SCHEMA_UNUSED"));
+    }
+
+    #[test]
+    fn unknown_fields_reported_except_under_ext() {
+        let mut data = minimal_v10();
+        data["voice"]["tts"] = serde_json::json!({"provider": "p", "voice_id": "v", "speeed": 2});
+        data["authority"] = serde_json::json!({
+            "autonomy": "supervised",
+            "ext": {"vendor": {"anything": true}}
+        });
+        data["backstory"] = Value::Null;
+        let issues = check_unknown_fields(&data);
+        let paths: Vec<_> = issues.iter().map(|i| i.path.as_deref().unwrap()).collect();
+        assert_eq!(paths, ["$.voice.tts.speeed"]);
+        assert_eq!(issues[0].code, "E040");
+
+        let issues = check_unknown_fields(&serde_json::json!({"name": "x"}));
+        assert_eq!(issues[0].code, "E041");
+    }
 }
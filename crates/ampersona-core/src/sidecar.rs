@@ -0,0 +1,149 @@
+//! Locations of the files kept next to a persona: state, audit log, drift
+//! history, checkpoint and the like.
+//!
+//! Defaults replace the persona's `.json` extension: `dir/agent.json` →
+//! `dir/agent.state.json`. Only a trailing `.json` on the file name is
+//! stripped, so `.json` in a directory name (`jsonly/`, `cfg.json.d/`) or
+//! mid-name (`agent.json.bak`) survives; any other name gets the suffix
+//! appended (`agent.json.bak.state.json`).
+//!
+//! With a state directory, the same names are kept under that directory
+//! instead, namespaced by the persona's path (see [`relocate`]).
+
+use std::path::{Component, Path, PathBuf};
+
+pub const STATE: &str = ".state.json";
+pub const AUDIT: &str = ".audit.jsonl";
+pub const DRIFT: &str = ".drift.jsonl";
+pub const CHECKPOINT: &str = ".checkpoint.json";
+
+/// `persona` with a trailing `.json` replaced by `suffix`.
+pub fn sidecar_path(persona: &str, suffix: &str) -> String {
+    let path = Path::new(persona);
+    let Some(name) = path.file_name().map(|n| n.to_string_lossy()) else {
+        return format!("{persona}{suffix}");
+    };
+    let stem = name
+        .strip_suffix(".json")
+        .filter(|stem| !stem.is_empty())
+        .unwrap_or(&name);
+    path.with_file_name(format!("{stem}{suffix}"))
+        .to_string_lossy()
+        .into_owned()
+}
+
+/// Where `persona` sits when mirrored under `state_dir`: its directory
+/// relative to the current one (or, outside it, its absolute path) is kept,
+/// so `personas/a.json` → `<state_dir>/personas/a.json`. The directory is
+/// canonicalized first, so `./a.json`, `a.json` and `/abs/cwd/a.json` all map
+/// to the same place.
+pub fn relocate(persona: &str, state_dir: &Path) -> PathBuf {
+    let path = Path::new(persona);
+    let name = path.file_name().unwrap_or(path.as_os_str());
+    let parent = path
+        .parent()
+        .filter(|p| !p.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    let cwd = std::env::current_dir()
+        .and_then(|d| d.canonicalize())
+        .unwrap_or_default();
+    let dir = parent.canonicalize().unwrap_or_else(|_| cwd.join(parent));
+    let namespace: PathBuf = dir
+        .strip_prefix(&cwd)
+        .unwrap_or(&dir)
+        .components()
+        .filter(|c| matches!(c, Component::Normal(_)))
+        .collect();
+    state_dir.join(namespace).join(name)
+}
+
+/// The state-bearing sidecars of one persona.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sidecars {
+    pub state: String,
+    pub audit: String,
+    pub drift: String,
+    pub checkpoint: String,
+}
+
+impl Sidecars {
+    /// Defaults next to `persona`.
+    pub fn for_persona(persona: &str) -> Self {
+        Self {
+            state: sidecar_path(persona, STATE),
+            audit: sidecar_path(persona, AUDIT),
+            drift: sidecar_path(persona, DRIFT),
+            checkpoint: sidecar_path(persona, CHECKPOINT),
+        }
+    }
+
+    /// Defaults for `persona` kept under `state_dir` instead of next to it.
+    pub fn in_state_dir(persona: &str, state_dir: &Path) -> Self {
+        Self::for_persona(&relocate(persona, state_dir).to_string_lossy())
+    }
+
+    /// Defaults next to an explicit state file: `run.state.json` keeps its
+    /// siblings at `run.audit.jsonl`, `run.drift.jsonl`, ...
+    pub fn for_state(state: &str) -> Self {
+        let base = match state.strip_suffix(STATE) {
+            Some(stem) => format!("{stem}.json"),
+            None => state.to_string(),
+        };
+        Self {
+            state: state.to_string(),
+            ..Self::for_persona(&base)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strips_only_a_trailing_json_extension() {
+        let cases = [
+            ("agent.json", STATE, "agent.state.json"),
+            ("/data/jsonly/a.json", STATE, "/data/jsonly/a.state.json"),
+            ("cfg.json.d/v1.0.json", AUDIT, "cfg.json.d/v1.0.audit.jsonl"),
+            ("agent.json.bak", STATE, "agent.json.bak.state.json"),
+            ("my.json.agent.json", DRIFT, "my.json.agent.drift.jsonl"),
+            ("dir/agent.toml", STATE, "dir/agent.toml.state.json"),
+            ("dir/.json", CHECKPOINT, "dir/.json.checkpoint.json"),
+            ("agent", DRIFT, "agent.drift.jsonl"),
+        ];
+        for (persona, suffix, expected) in cases {
+            assert_eq!(sidecar_path(persona, suffix), expected, "{persona}");
+        }
+    }
+
+    #[test]
+    fn state_dir_mirrors_the_persona_directory() {
+        let cwd = std::env::current_dir().unwrap();
+        let state_dir = Path::new("/var/lib/amp");
+        let paths = Sidecars::in_state_dir("src/agent.json", state_dir);
+        assert_eq!(paths.state, "/var/lib/amp/src/agent.state.json");
+        assert_eq!(paths.audit, "/var/lib/amp/src/agent.audit.jsonl");
+
+        let absolute = cwd.join("src").join("agent.json");
+        assert_eq!(
+            Sidecars::in_state_dir(&absolute.to_string_lossy(), state_dir),
+            Sidecars::in_state_dir("./src/agent.json", state_dir)
+        );
+        assert_eq!(
+            relocate("agent.json", state_dir),
+            Path::new("/var/lib/amp/agent.json")
+        );
+    }
+
+    #[test]
+    fn state_override_moves_the_other_defaults() {
+        let paths = Sidecars::for_state("/tmp/run.state.json");
+        assert_eq!(paths.state, "/tmp/run.state.json");
+        assert_eq!(paths.audit, "/tmp/run.audit.jsonl");
+
+        let paths = Sidecars::for_state("s.json");
+        assert_eq!(paths.state, "s.json");
+        assert_eq!(paths.checkpoint, "s.checkpoint.json");
+    }
+}
@@ -1,3 +1,4 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -83,6 +84,12 @@ pub enum DenyEntry {
         reason: String,
         #[serde(skip_serializing_if = "Option::is_none")]
         compliance_ref: Option<String>,
+        /// Date by which the deny should be re-reviewed; still enforced after it.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        review_by: Option<DateTime<Utc>>,
+        /// After this instant the deny is no longer in force.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        expires_at: Option<DateTime<Utc>>,
     },
 }
 
@@ -93,6 +100,30 @@ impl DenyEntry {
             DenyEntry::WithReason { action, .. } => action,
         }
     }
+
+    pub fn review_by(&self) -> Option<DateTime<Utc>> {
+        match self {
+            DenyEntry::Simple(_) => None,
+            DenyEntry::WithReason { review_by, .. } => *review_by,
+        }
+    }
+
+    pub fn expires_at(&self) -> Option<DateTime<Utc>> {
+        match self {
+            DenyEntry::Simple(_) => None,
+            DenyEntry::WithReason { expires_at, .. } => *expires_at,
+        }
+    }
+
+    /// Whether the deny has lapsed and should no longer be enforced.
+    pub fn is_expired(&self) -> bool {
+        self.expires_at().is_some_and(|t| Utc::now() > t)
+    }
+
+    /// Whether the deny is past its review date (and not yet expired).
+    pub fn review_overdue(&self) -> bool {
+        !self.is_expired() && self.review_by().is_some_and(|t| Utc::now() > t)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -160,6 +191,9 @@ pub struct Elevation {
     pub grants: serde_json::Value,
     pub requires: GateApproval,
     pub ttl_seconds: u64,
+    /// Upper bound for `amp elevate --ttl` overrides (and the default TTL).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_ttl_seconds: Option<u64>,
     #[serde(default)]
     pub reason_required: bool,
 }
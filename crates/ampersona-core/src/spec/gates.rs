@@ -1,7 +1,7 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
-use crate::spec::authority::AuthorityOverlay;
+use crate::spec::authority::{Authority, AuthorityOverlay};
 use crate::types::{CriterionOp, GateApproval, GateDirection, GateEnforcement};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -15,6 +15,16 @@ pub struct Gate {
     #[serde(default)]
     pub priority: i32,
 
+    /// Explicit evaluation order within a direction (ascending). Gates with
+    /// `order` are evaluated before gates without it, replacing the
+    /// `(priority DESC, id ASC)` tie-break.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub order: Option<u32>,
+
+    /// Gate-wide freshness limit for criteria without their own `max_age_seconds`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_age_seconds: Option<u64>,
+
     #[serde(default)]
     pub cooldown_seconds: u64,
 
@@ -31,6 +41,10 @@ pub struct Gate {
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub on_pass: Option<GateEffect>,
+
+    /// Effect when `amp gate --evaluate <id>` names this gate and it does not fire.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub on_fail: Option<GateFailEffect>,
 }
 
 fn default_auto() -> GateApproval {
@@ -45,6 +59,19 @@ pub struct Criterion {
     /// Optional time window in seconds for windowed metric queries.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub window_seconds: Option<u64>,
+    /// Hysteresis exit threshold (demote gates only). `value` is the enter
+    /// threshold; after this gate demotes, the reverse promotion is held until
+    /// the metric no longer satisfies `op` against `exit`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exit: Option<serde_json::Value>,
+    /// Case-insensitive matching for `contains` / `not_contains`. Matching is
+    /// case-sensitive unless set; other operators ignore it.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub ci: bool,
+    /// Oldest acceptable sample age. A staler metric fails closed: demote
+    /// fires, promote is blocked. Overrides the gate's `max_age_seconds`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_age_seconds: Option<u64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -57,4 +84,137 @@ pub struct MetricSchema {
 pub struct GateEffect {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub authority_overlay: Option<AuthorityOverlay>,
+    /// Prompt snippet rendered while in this gate's `to_phase`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prompt: Option<String>,
+    /// Command (argv, no shell) run after the transition is committed, with the
+    /// decision record as JSON on stdin. Needs the `exec-hooks` feature.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exec: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GateFailEffect {
+    /// Command (argv, no shell) run with the gate diagnostic as JSON on stdin.
+    /// Needs the `exec-hooks` feature.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exec: Option<Vec<String>>,
+}
+
+/// One declared phase, under `phases.<name>`. When a persona declares
+/// phases, gates should only reference those names (`amp check` warns).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PhaseSpec {
+    /// What being in this phase means; shown by `amp status` and in prompts
+    /// rendered with state.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Recorded with the `GateTransition` audit entry of every gate that
+    /// enters this phase.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub entry_note: Option<String>,
+    /// Overlay applied on entering this phase when the gate that fired has
+    /// no `on_pass.authority_overlay` of its own.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub authority_overlay: Option<AuthorityOverlay>,
+    /// Baseline authority while in this phase, merged between workspace
+    /// defaults and the persona's authority (see `policy::precedence`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub authority: Option<Authority>,
+}
+
+/// Function of a computed criterion metric.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DerivedFn {
+    /// `ratio(a, b)` = a / b; undefined when b is zero.
+    Ratio,
+    /// `sum(a, b, ...)` over two or more metrics.
+    Sum,
+    /// `diff(a, b)` = a - b.
+    Diff,
+}
+
+/// A computed criterion metric such as `ratio(errors, requests)`, whose
+/// operands are plain metric names resolved through the metrics provider.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DerivedMetric {
+    pub func: DerivedFn,
+    pub operands: Vec<String>,
+}
+
+impl DerivedMetric {
+    /// Parse a criterion `metric`. Plain names (no parenthesis) yield
+    /// `Ok(None)`; anything else must be a well-formed, non-nested call.
+    pub fn parse(metric: &str) -> Result<Option<Self>, String> {
+        let Some((name, rest)) = metric.split_once('(') else {
+            return Ok(None);
+        };
+        let func = match name.trim() {
+            "ratio" => DerivedFn::Ratio,
+            "sum" => DerivedFn::Sum,
+            "diff" => DerivedFn::Diff,
+            other => {
+                return Err(format!(
+                    "unknown metric function '{other}' (expected ratio, sum or diff)"
+                ))
+            }
+        };
+        let args = rest
+            .trim_end()
+            .strip_suffix(')')
+            .ok_or_else(|| format!("'{metric}' is missing a closing parenthesis"))?;
+        if args.contains(['(', ')']) {
+            return Err(format!("'{metric}': metric functions cannot be nested"));
+        }
+        let operands: Vec<String> = args.split(',').map(|a| a.trim().to_string()).collect();
+        if operands.iter().any(String::is_empty) {
+            return Err(format!("'{metric}' has an empty operand"));
+        }
+        let arity_ok = match func {
+            DerivedFn::Ratio | DerivedFn::Diff => operands.len() == 2,
+            DerivedFn::Sum => operands.len() >= 2,
+        };
+        if !arity_ok {
+            return Err(format!(
+                "'{metric}' takes {} operands, got {}",
+                if func == DerivedFn::Sum {
+                    "two or more"
+                } else {
+                    "two"
+                },
+                operands.len()
+            ));
+        }
+        Ok(Some(Self { func, operands }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_derived_metrics() {
+        assert_eq!(DerivedMetric::parse("errors"), Ok(None));
+        assert_eq!(
+            DerivedMetric::parse("ratio(errors, requests)"),
+            Ok(Some(DerivedMetric {
+                func: DerivedFn::Ratio,
+                operands: vec!["errors".into(), "requests".into()],
+            }))
+        );
+        assert_eq!(
+            DerivedMetric::parse("sum(a,b,c)")
+                .unwrap()
+                .unwrap()
+                .operands,
+            ["a", "b", "c"]
+        );
+        assert!(DerivedMetric::parse("ratio(errors)").is_err());
+        assert!(DerivedMetric::parse("diff(a, b, c)").is_err());
+        assert!(DerivedMetric::parse("avg(a, b)").is_err());
+        assert!(DerivedMetric::parse("ratio(a, sum(b, c))").is_err());
+        assert!(DerivedMetric::parse("ratio(a, b").is_err());
+        assert!(DerivedMetric::parse("sum(a, )").is_err());
+    }
 }
@@ -10,6 +10,9 @@ pub struct Psychology {
     pub moral_compass: Option<MoralCompass>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub emotional_profile: Option<EmotionalProfile>,
+    /// Free-form traits with no structured field (e.g. imported irrational fears).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notes: Option<Vec<String>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -4,11 +4,13 @@ pub mod gates;
 pub mod identity;
 pub mod signature;
 
+use std::collections::BTreeMap;
+
 use serde::{Deserialize, Serialize};
 
 use self::audit::AuditConfig;
 use self::authority::Authority;
-use self::gates::Gate;
+use self::gates::{Gate, PhaseSpec};
 use self::identity::{Capabilities, Directives, Psychology, Voice};
 use self::signature::Signature;
 
@@ -45,11 +47,26 @@ pub struct Persona {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub gates: Option<Vec<Gate>>,
 
+    /// Per-phase descriptions and entry notes, keyed by phase name.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub phases: Option<BTreeMap<String, PhaseSpec>>,
+
     #[serde(skip_serializing_if = "Option::is_none")]
     pub audit: Option<AuditConfig>,
 }
 
 impl Persona {
+    /// The declaration of `phase` under `phases`, if any.
+    pub fn phase(&self, phase: &str) -> Option<&PhaseSpec> {
+        self.phases.as_ref()?.get(phase)
+    }
+
+    /// The baseline authority declared for `phase` (the current phase from
+    /// state; `None` before the first transition).
+    pub fn phase_authority(&self, phase: Option<&str>) -> Option<&Authority> {
+        self.phase(phase?)?.authority.as_ref()
+    }
+
     /// Detect version: "1.0" if version field present, else "0.2".
     pub fn detected_version(&self) -> &str {
         match &self.version {
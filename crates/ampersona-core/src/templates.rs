@@ -18,6 +18,34 @@ pub fn list_templates() -> Vec<(&'static str, &'static str)> {
     ]
 }
 
+const ADJECTIVES: &[&str] = &[
+    "Amber", "Bold", "Brisk", "Calm", "Clever", "Deft", "Eager", "Keen", "Lucid", "Nimble",
+    "Quiet", "Rapid", "Silent", "Steady", "Swift", "Vivid",
+];
+
+const NOUNS: &[&str] = &[
+    "Anchor", "Beacon", "Cipher", "Compass", "Falcon", "Forge", "Harbor", "Lantern", "Meridian",
+    "Otter", "Pilot", "Quill", "Raven", "Sentinel", "Spark", "Willow",
+];
+
+/// An AdjectiveNoun persona name drawn from `seed`; the same seed always
+/// yields the same name.
+pub fn generated_name(seed: u64) -> String {
+    let mut state = seed;
+    let adjective = ADJECTIVES[(splitmix64(&mut state) % ADJECTIVES.len() as u64) as usize];
+    let noun = NOUNS[(splitmix64(&mut state) % NOUNS.len() as u64) as usize];
+    format!("{adjective}{noun}")
+}
+
+/// SplitMix64 step: small, dependency-free and stable across platforms.
+fn splitmix64(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
 pub fn generate(template: &str, name: Option<&str>) -> Option<Value> {
     match template {
         "architect" => Some(architect(name)),
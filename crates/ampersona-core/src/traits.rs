@@ -43,6 +43,17 @@ pub struct DenyMeta {
     pub reason: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub compliance_ref: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub review_by: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+impl DenyMeta {
+    /// Whether the deny is past its review date.
+    pub fn review_overdue(&self) -> bool {
+        self.review_by.is_some_and(|t| Utc::now() > t)
+    }
 }
 
 /// Authority with all layers resolved (workspace+persona+gate+elevation).
@@ -109,6 +120,9 @@ pub struct CriteriaResult {
     pub pass: bool,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub type_mismatch: Option<String>,
+    /// The sample was older than the criterion's `max_age_seconds`.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub stale: bool,
 }
 
 /// Elevation lifecycle event.
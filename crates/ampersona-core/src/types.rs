@@ -58,6 +58,15 @@ impl AutonomyLevel {
             (Full, Full) => Full,
         }
     }
+
+    /// The lowercase name used in persona JSON.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            AutonomyLevel::Readonly => "readonly",
+            AutonomyLevel::Supervised => "supervised",
+            AutonomyLevel::Full => "full",
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -69,6 +78,16 @@ pub enum CriterionOp {
     Gte,
     Lt,
     Lte,
+    /// `value` is an array; passes when the metric equals one of its items.
+    In,
+    /// `value` is an array; passes when the metric equals none of its items.
+    #[serde(rename = "not_in")]
+    NotIn,
+    /// String metric contains the string `value` (see `Criterion::ci`).
+    Contains,
+    /// String metric does not contain the string `value`.
+    #[serde(rename = "not_contains")]
+    NotContains,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -126,6 +145,7 @@ pub enum AuditEventType {
     SignatureVerify,
     StateChange,
     AuthorityOverlayChange,
+    GateEnforcementChange,
 }
 
 /// MBTI personality types.
@@ -0,0 +1,100 @@
+//! `cargo bench -p ampersona-engine --bench policy_cache`
+//!
+//! Replays an `amp authority --matrix` workload (every builtin action, one
+//! path, shell `validate_symlinks` on) the way a long-lived caller would, with
+//! and without the decision cache. No external harness: prints wall time.
+
+use std::collections::HashMap;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use ampersona_core::actions::{ActionId, BuiltinAction};
+use ampersona_core::traits::{AuthorityEnforcer, PolicyRequest, ResolvedAuthority};
+use ampersona_engine::policy::checker::{CachingChecker, DefaultPolicyChecker, KeyedAuthority};
+
+const ROUNDS: usize = 200;
+
+fn authority() -> ResolvedAuthority {
+    let actions: Vec<String> = BuiltinAction::all()
+        .iter()
+        .map(|b| ActionId::Builtin(*b).to_string())
+        .collect();
+    serde_json::from_value(serde_json::json!({
+        "autonomy": "supervised",
+        "allowed_actions": actions,
+        "denied_actions": ["deploy"],
+        "scope": {
+            "workspace_only": true,
+            "allowed_paths": ["src/**", "benches/**", "*.toml"],
+            "forbidden_paths": ["target/**", "../*"]
+        },
+        "limits": null,
+        "scoped_actions": {
+            "shell": { "$type": "shell", "commands": ["cargo"], "validate_symlinks": true }
+        }
+    }))
+    .expect("bench authority")
+}
+
+fn request(action: BuiltinAction) -> PolicyRequest {
+    PolicyRequest {
+        action: Some(ActionId::Builtin(action)),
+        path: Some("src/lib.rs".into()),
+        context: HashMap::new(),
+    }
+}
+
+fn run(checker: &dyn AuthorityEnforcer, authority: &ResolvedAuthority) -> Duration {
+    let start = Instant::now();
+    for _ in 0..ROUNDS {
+        for action in BuiltinAction::all() {
+            black_box(
+                checker
+                    .evaluate(&request(*action), authority)
+                    .expect("evaluate"),
+            );
+        }
+    }
+    start.elapsed()
+}
+
+fn run_keyed(
+    checker: &CachingChecker<DefaultPolicyChecker>,
+    authority: &ResolvedAuthority,
+) -> Duration {
+    let start = Instant::now();
+    let keyed = KeyedAuthority::new(authority);
+    for _ in 0..ROUNDS {
+        for action in BuiltinAction::all() {
+            black_box(
+                checker
+                    .evaluate_keyed(&request(*action), &keyed)
+                    .expect("evaluate"),
+            );
+        }
+    }
+    start.elapsed()
+}
+
+fn main() {
+    let authority = authority();
+    let evaluations = ROUNDS * BuiltinAction::all().len();
+
+    let uncached = run(&DefaultPolicyChecker, &authority);
+    // Through the trait: the authority is re-hashed on every call
+    let cached = run(&DefaultPolicyChecker.cached(), &authority);
+    let keyed_checker = DefaultPolicyChecker.cached();
+    let keyed = run_keyed(&keyed_checker, &authority);
+    let stats = keyed_checker.stats();
+
+    let speedup = |d: Duration| uncached.as_secs_f64() / d.as_secs_f64();
+    println!("policy_cache: {evaluations} evaluations ({ROUNDS} matrix rounds)");
+    println!("  uncached      {uncached:>10.2?}");
+    println!("  cached        {cached:>10.2?}  {:.1}x", speedup(cached));
+    println!(
+        "  cached+keyed  {keyed:>10.2?}  {:.1}x  ({} hits, {} misses)",
+        speedup(keyed),
+        stats.hits,
+        stats.misses
+    );
+}
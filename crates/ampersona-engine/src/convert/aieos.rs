@@ -12,7 +12,7 @@
 //! - Alignment format conversion (`"Lawful Good"` → `"lawful-good"`)
 //! - Fear merging (`fears.rational` + `fears.irrational` → constraints)
 
-use anyhow::Result;
+use crate::error::{EngineError, Result};
 use serde_json::{Map, Value};
 
 // ── Normalization utilities (ported from zeroclaw identity.rs) ──────
@@ -184,7 +184,7 @@ fn normalize_alignment(raw: &str) -> Option<String> {
 // ── AIEOS → ampersona normalization ─────────────────────────────────
 
 /// Normalize AIEOS `identity` section → ampersona `name`, `role`, `backstory`.
-fn normalize_identity(aieos: &Value, obj: &mut Map<String, Value>) {
+fn normalize_identity(aieos: &Value, obj: &mut Map<String, Value>, style: BackstoryStyle) {
     // Name: top-level or identity.names
     let name = aieos
         .get("name")
@@ -223,22 +223,37 @@ fn normalize_identity(aieos: &Value, obj: &mut Map<String, Value>) {
     };
 
     let mut parts = Vec::new();
-    if let Some(bio) = identity.get("bio").and_then(value_to_text) {
-        parts.push(bio);
-    }
-    if let Some(origin) = identity.get("origin").and_then(value_to_text) {
-        parts.push(format!("Origin: {origin}"));
-    }
-    if let Some(residence) = identity.get("residence").and_then(value_to_text) {
-        parts.push(format!("Residence: {residence}"));
-    }
+    let mut push = |label: &str, value: Option<&Value>| match (style, value) {
+        // One bullet per object field rather than a "; "-joined summary
+        (BackstoryStyle::Bullets, Some(Value::Object(map))) => {
+            summarize_object_into_parts(label, map, &mut parts)
+        }
+        (_, Some(value)) => {
+            if let Some(text) = value_to_text(value) {
+                parts.push(if label.is_empty() {
+                    text
+                } else {
+                    format!("{label}: {text}")
+                });
+            }
+        }
+        (_, None) => {}
+    };
+    push("", identity.get("bio"));
+    push("Origin", identity.get("origin"));
+    push("Residence", identity.get("residence"));
     // History section as backstory supplement
-    if let Some(story) = value_at_path(aieos, &["history", "origin_story"]).and_then(value_to_text)
-    {
-        parts.push(story);
-    }
+    push("", value_at_path(aieos, &["history", "origin_story"]));
     if !parts.is_empty() {
-        obj.insert("backstory".into(), Value::String(parts.join(". ")));
+        let backstory = match style {
+            BackstoryStyle::Sentences => parts.join(". "),
+            BackstoryStyle::Bullets => parts
+                .iter()
+                .map(|p| format!("- {p}"))
+                .collect::<Vec<_>>()
+                .join("\n"),
+        };
+        obj.insert("backstory".into(), Value::String(backstory));
     }
 }
 
@@ -526,6 +541,7 @@ fn normalize_capabilities(aieos: &Value) -> Option<Value> {
             }))
         })
         .collect();
+    let skills = super::normalize_skills(skills);
 
     if skills.is_empty() {
         return None;
@@ -535,7 +551,7 @@ fn normalize_capabilities(aieos: &Value) -> Option<Value> {
 }
 
 /// Normalize AIEOS `motivations` → ampersona `directives`.
-fn normalize_directives(aieos: &Value) -> Option<Value> {
+fn normalize_directives(aieos: &Value, fear_handling: FearHandling) -> Option<Value> {
     let motivations = aieos.get("motivations");
 
     let core_drive = motivations
@@ -574,25 +590,13 @@ fn normalize_directives(aieos: &Value) -> Option<Value> {
 
     // Constraints: from motivations.fears + identity.constraints
     let mut constraints = Vec::new();
-    if let Some(m) = motivations {
-        if let Some(fears) = m.get("fears") {
-            let fear_items = if fears.is_object() {
-                let mut combined = non_empty_list_at(m, &["fears", "rational"]).unwrap_or_default();
-                if let Some(mut irrational) = non_empty_list_at(m, &["fears", "irrational"]) {
-                    combined.append(&mut irrational);
-                }
-                if combined.is_empty() {
-                    list_from_value(fears)
-                } else {
-                    combined
-                }
-            } else {
-                list_from_value(fears)
-            };
-            for fear in fear_items {
-                constraints.push(format!("Avoid: {fear}"));
-            }
-        }
+    let (rational, irrational) = fear_lists(aieos);
+    let merged = match fear_handling {
+        FearHandling::Merge => irrational,
+        FearHandling::Separate | FearHandling::DropIrrational => Vec::new(),
+    };
+    for fear in rational.into_iter().chain(merged) {
+        constraints.push(format!("Avoid: {fear}"));
     }
     if let Some(c) = value_at_path(aieos, &["identity", "constraints"]).and_then(|v| {
         let items = list_from_value(v);
@@ -629,6 +633,25 @@ fn normalize_directives(aieos: &Value) -> Option<Value> {
     Some(Value::Object(directives))
 }
 
+/// `motivations.fears` split into (rational, irrational).
+///
+/// A flat list, or an object with neither key, counts as rational.
+fn fear_lists(aieos: &Value) -> (Vec<String>, Vec<String>) {
+    let Some(fears) = value_at_path(aieos, &["motivations", "fears"]) else {
+        return (Vec::new(), Vec::new());
+    };
+    if !fears.is_object() {
+        return (list_from_value(fears), Vec::new());
+    }
+    let rational = non_empty_list_at(fears, &["rational"]).unwrap_or_default();
+    let irrational = non_empty_list_at(fears, &["irrational"]).unwrap_or_default();
+    if rational.is_empty() && irrational.is_empty() {
+        (list_from_value(fears), Vec::new())
+    } else {
+        (rational, irrational)
+    }
+}
+
 /// Normalize AIEOS `security_policy` → ampersona `authority`.
 fn normalize_authority(aieos: &Value) -> Option<Value> {
     let policy = aieos
@@ -704,13 +727,53 @@ fn normalize_authority(aieos: &Value) -> Option<Value> {
 
 // ── Public API ──────────────────────────────────────────────────────
 
+/// How backstory parts (bio, origin, residence, origin story) are joined.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BackstoryStyle {
+    /// One paragraph, parts joined with ". ".
+    #[default]
+    Sentences,
+    /// One `- ` line per part; object fields get a line each.
+    Bullets,
+}
+
+/// Where irrational fears (`motivations.fears.irrational`) end up.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FearHandling {
+    /// Constraints prefixed "Avoid: ", like rational fears.
+    #[default]
+    Merge,
+    /// `psychology.notes` entries prefixed "Irrational fear: "; only rational
+    /// fears become constraints.
+    Separate,
+    /// Dropped; only rational fears become constraints.
+    DropIrrational,
+}
+
+/// Prefix of `psychology.notes` entries carrying an irrational fear.
+const IRRATIONAL_FEAR_NOTE: &str = "Irrational fear: ";
+
+/// Options for [`import_aieos_with`].
+#[derive(Debug, Clone, Default)]
+pub struct ImportOptions {
+    pub backstory_style: BackstoryStyle,
+    pub fears: FearHandling,
+}
+
 /// Convert an AIEOS identity JSON to ampersona v1.0 format.
 ///
 /// Handles both the simplified shape (flat fields) and the canonical
 /// AIEOS v1.1 generator shape (nested `traits`, `goals`, `fears`, etc.).
 pub fn import_aieos(aieos: &Value) -> Result<Value> {
+    import_aieos_with(aieos, &ImportOptions::default())
+}
+
+/// [`import_aieos`] with explicit [`ImportOptions`].
+pub fn import_aieos_with(aieos: &Value, opts: &ImportOptions) -> Result<Value> {
     if !aieos.is_object() {
-        anyhow::bail!("AIEOS payload must be a JSON object");
+        return Err(EngineError::InvalidPersona(
+            "AIEOS payload must be a JSON object".into(),
+        ));
     }
 
     let mut obj = serde_json::Map::new();
@@ -721,10 +784,25 @@ pub fn import_aieos(aieos: &Value) -> Result<Value> {
     obj.insert("version".into(), Value::String("1.0".into()));
 
     // Identity → name, role, backstory
-    normalize_identity(aieos, &mut obj);
+    normalize_identity(aieos, &mut obj, opts.backstory_style);
 
     // Psychology
-    if let Some(psych) = normalize_psychology(aieos) {
+    let mut psychology = normalize_psychology(aieos);
+    if opts.fears == FearHandling::Separate {
+        let notes: Vec<Value> = fear_lists(aieos)
+            .1
+            .into_iter()
+            .map(|f| Value::String(format!("{IRRATIONAL_FEAR_NOTE}{f}")))
+            .collect();
+        if !notes.is_empty() {
+            psychology
+                .get_or_insert_with(|| Value::Object(Map::new()))
+                .as_object_mut()
+                .expect("psychology is an object")
+                .insert("notes".into(), Value::Array(notes));
+        }
+    }
+    if let Some(psych) = psychology {
         obj.insert("psychology".into(), psych);
     }
 
@@ -739,7 +817,7 @@ pub fn import_aieos(aieos: &Value) -> Result<Value> {
     }
 
     // Directives (from motivations)
-    if let Some(dir) = normalize_directives(aieos) {
+    if let Some(dir) = normalize_directives(aieos, opts.fears) {
         obj.insert("directives".into(), dir);
     }
 
@@ -835,8 +913,10 @@ pub fn export_aieos(persona: &Value) -> Result<Value> {
         }
     }
 
-    // Directives → motivations
-    if let Some(dir) = persona.get("directives") {
+    // Directives → motivations (irrational-fear notes alone also qualify)
+    let notes = persona.pointer("/psychology/notes");
+    let no_directives = Value::Null;
+    if let Some(dir) = persona.get("directives").or(notes.map(|_| &no_directives)) {
         let mut mot = serde_json::Map::new();
         if let Some(cd) = dir.get("core_drive") {
             mot.insert("core_drive".into(), cd.clone());
@@ -844,18 +924,32 @@ pub fn export_aieos(persona: &Value) -> Result<Value> {
         if let Some(goals) = dir.get("goals") {
             mot.insert("goals".into(), serde_json::json!({"short_term": goals}));
         }
-        // Constraints with "Avoid: " prefix → fears
-        if let Some(constraints) = dir.get("constraints").and_then(Value::as_array) {
-            let fears: Vec<Value> = constraints
-                .iter()
-                .filter_map(|c| {
-                    c.as_str()
-                        .map(|s| Value::String(s.strip_prefix("Avoid: ").unwrap_or(s).to_string()))
-                })
-                .collect();
-            if !fears.is_empty() {
-                mot.insert("fears".into(), Value::Array(fears));
-            }
+        // Constraints with "Avoid: " prefix → fears; irrational-fear notes
+        // (FearHandling::Separate) → fears.irrational
+        let fears: Vec<Value> = dir
+            .get("constraints")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(|c| {
+                c.as_str()
+                    .map(|s| Value::String(s.strip_prefix("Avoid: ").unwrap_or(s).to_string()))
+            })
+            .collect();
+        let irrational: Vec<Value> = notes
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(|n| n.as_str()?.strip_prefix(IRRATIONAL_FEAR_NOTE))
+            .map(|f| Value::String(f.to_string()))
+            .collect();
+        if !irrational.is_empty() {
+            mot.insert(
+                "fears".into(),
+                serde_json::json!({"rational": fears, "irrational": irrational}),
+            );
+        } else if !fears.is_empty() {
+            mot.insert("fears".into(), Value::Array(fears));
         }
         if !mot.is_empty() {
             aieos.insert("motivations".into(), Value::Object(mot));
@@ -1258,6 +1352,108 @@ mod tests {
         assert_eq!(exported["motivations"]["core_drive"], "Understand deeply");
     }
 
+    #[test]
+    fn backstory_bullets_one_line_per_part() {
+        let aieos = serde_json::json!({
+            "name": "Marta",
+            "identity": {
+                "bio": "Gardener from Krakow",
+                "origin": { "city": "Krakow", "country": "Poland" },
+                "residence": "Warsaw"
+            },
+            "history": { "origin_story": "Grew up on a farm" }
+        });
+
+        let sentences = import_aieos(&aieos).unwrap();
+        assert_eq!(
+            sentences["backstory"],
+            "Gardener from Krakow. Origin: city: Krakow; country: Poland. Residence: Warsaw. Grew up on a farm"
+        );
+
+        let opts = ImportOptions {
+            backstory_style: BackstoryStyle::Bullets,
+            ..Default::default()
+        };
+        let bullets = import_aieos_with(&aieos, &opts).unwrap();
+        let lines: Vec<_> = bullets["backstory"].as_str().unwrap().lines().collect();
+        assert_eq!(
+            lines,
+            [
+                "- Gardener from Krakow",
+                "- Origin city: Krakow",
+                "- Origin country: Poland",
+                "- Residence: Warsaw",
+                "- Grew up on a farm",
+            ]
+        );
+    }
+
+    #[test]
+    fn fear_handling_modes() {
+        let rational_only = serde_json::json!({
+            "name": "Bot",
+            "motivations": { "fears": { "rational": ["Data loss"] } }
+        });
+        for fears in [
+            FearHandling::Merge,
+            FearHandling::Separate,
+            FearHandling::DropIrrational,
+        ] {
+            let opts = ImportOptions {
+                fears,
+                ..Default::default()
+            };
+            let result = import_aieos_with(&rational_only, &opts).unwrap();
+            assert_eq!(
+                result["directives"]["constraints"],
+                serde_json::json!(["Avoid: Data loss"])
+            );
+            assert!(result.pointer("/psychology/notes").is_none());
+        }
+
+        let mixed = serde_json::json!({
+            "name": "Bot",
+            "motivations": {
+                "fears": { "rational": ["Data loss"], "irrational": ["Spiders"] }
+            }
+        });
+        let merged = import_aieos(&mixed).unwrap();
+        assert_eq!(
+            merged["directives"]["constraints"],
+            serde_json::json!(["Avoid: Data loss", "Avoid: Spiders"])
+        );
+
+        let opts = ImportOptions {
+            fears: FearHandling::Separate,
+            ..Default::default()
+        };
+        let separate = import_aieos_with(&mixed, &opts).unwrap();
+        assert_eq!(
+            separate["directives"]["constraints"],
+            serde_json::json!(["Avoid: Data loss"])
+        );
+        assert_eq!(
+            separate["psychology"]["notes"],
+            serde_json::json!(["Irrational fear: Spiders"])
+        );
+        let exported = export_aieos(&separate).unwrap();
+        assert_eq!(
+            exported["motivations"]["fears"],
+            serde_json::json!({ "rational": ["Data loss"], "irrational": ["Spiders"] })
+        );
+
+        let opts = ImportOptions {
+            fears: FearHandling::DropIrrational,
+            ..Default::default()
+        };
+        let dropped = import_aieos_with(&mixed, &opts).unwrap();
+        assert_eq!(
+            dropped["directives"]["constraints"],
+            serde_json::json!(["Avoid: Data loss"])
+        );
+        assert!(dropped.pointer("/psychology/notes").is_none());
+    }
+
     #[test]
     fn capabilities_from_string_array() {
         let aieos = serde_json::json!({
@@ -1270,6 +1466,26 @@ mod tests {
         assert_eq!(result["capabilities"]["skills"][1]["name"], "writing");
     }
 
+    #[test]
+    fn capabilities_deduplicated_and_sorted_by_priority() {
+        let aieos = serde_json::json!({
+            "name": "Bot",
+            "capabilities": { "skills": [
+                { "name": "Writing", "priority": 5 },
+                { "name": "coding", "description": "short", "priority": 3 },
+                { "name": "Coding", "description": "much longer description", "priority": 3 },
+                { "name": "analysis", "priority": 1 },
+                { "name": "WRITING", "description": "lower priority copy", "priority": 9 }
+            ] }
+        });
+        let result = import_aieos(&aieos).unwrap();
+        let skills = result["capabilities"]["skills"].as_array().unwrap();
+        let names: Vec<_> = skills.iter().map(|s| s["name"].as_str().unwrap()).collect();
+        assert_eq!(names, ["analysis", "Coding", "Writing"]);
+        assert_eq!(skills[1]["description"], "much longer description");
+        assert_eq!(skills[2]["priority"], 5);
+    }
+
     #[test]
     fn capabilities_from_identity_section() {
         let aieos = serde_json::json!({
@@ -0,0 +1,228 @@
+//! Import CrewAI agent definitions into ampersona v1.0.
+//!
+//! CrewAI describes an agent with `role`, `goal`, `backstory` and optional
+//! `tools`, usually in an `agents.yaml` keyed by agent name. Accepted shapes:
+//! - a single agent: `{role, goal, backstory, ...}`
+//! - agents keyed by name: `{researcher: {role, ...}, writer: {...}}`
+//! - a list of agents, each with a `name`, optionally under `agents:`
+//!
+//! Files with several agents need an explicit agent name. Only identity
+//! fields are mapped; CrewAI runtime options (`llm`, `verbose`, ...) are dropped.
+
+use serde_json::{Map, Value};
+
+use crate::error::{EngineError, Result};
+
+/// Parse a CrewAI YAML (or JSON) document.
+pub fn parse_crewai(content: &str) -> Result<Value> {
+    serde_yaml::from_str(content)
+        .map_err(|e| EngineError::InvalidPersona(format!("invalid CrewAI YAML: {e}")))
+}
+
+/// Import a single-agent CrewAI definition.
+pub fn import_crewai(data: &Value) -> Result<Value> {
+    import_crewai_agent(data, None)
+}
+
+/// Import one agent from a CrewAI definition, picking `agent` by name when the
+/// file defines several.
+pub fn import_crewai_agent(data: &Value, agent: Option<&str>) -> Result<Value> {
+    let agents = agents(data)?;
+    let (name, def) = match agent {
+        Some(wanted) => agents
+            .iter()
+            .find(|(name, _)| name.as_deref() == Some(wanted))
+            .ok_or_else(|| {
+                EngineError::InvalidPersona(format!(
+                    "no CrewAI agent named '{wanted}' (available: {})",
+                    names(&agents)
+                ))
+            })?,
+        None if agents.len() == 1 => &agents[0],
+        None => {
+            return Err(EngineError::InvalidPersona(format!(
+            "CrewAI file defines {} agents ({}); pick one with --agent <name> or split the file",
+            agents.len(),
+            names(&agents)
+        )))
+        }
+    };
+    convert_agent(name.as_deref(), def)
+}
+
+/// `(name, definition)` for every agent in the document.
+fn agents(data: &Value) -> Result<Vec<(Option<String>, &Value)>> {
+    let data = data.get("agents").unwrap_or(data);
+    let agents: Vec<_> = match data {
+        Value::Array(items) => items
+            .iter()
+            .map(|a| (a.get("name").and_then(text), a))
+            .collect(),
+        Value::Object(obj) if obj.contains_key("role") => {
+            vec![(data.get("name").and_then(text), data)]
+        }
+        Value::Object(obj) => obj
+            .iter()
+            .filter(|(_, v)| v.is_object())
+            .map(|(k, v)| (Some(k.clone()), v))
+            .collect(),
+        _ => Vec::new(),
+    };
+    if agents.is_empty() {
+        return Err(EngineError::InvalidPersona(
+            "no CrewAI agent found (expected role/goal/backstory)".into(),
+        ));
+    }
+    Ok(agents)
+}
+
+fn names(agents: &[(Option<String>, &Value)]) -> String {
+    agents
+        .iter()
+        .map(|(name, _)| name.as_deref().unwrap_or("(unnamed)"))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn convert_agent(name: Option<&str>, def: &Value) -> Result<Value> {
+    let role = def
+        .get("role")
+        .and_then(text)
+        .ok_or_else(|| EngineError::InvalidPersona("CrewAI agent is missing 'role'".into()))?;
+
+    let mut obj = Map::new();
+    obj.insert("version".into(), Value::String("1.0".into()));
+    obj.insert(
+        "name".into(),
+        Value::String(name.map_or_else(|| pascal_case(&role), pascal_case)),
+    );
+    obj.insert("role".into(), Value::String(role));
+    if let Some(backstory) = def.get("backstory").and_then(text) {
+        obj.insert("backstory".into(), Value::String(backstory));
+    }
+
+    let skills: Vec<Value> = def
+        .get("tools")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .enumerate()
+        .filter_map(|(i, tool)| {
+            let name = tool
+                .as_str()
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .map(String::from)
+                .or_else(|| tool.get("name").and_then(text))?;
+            let description = tool
+                .get("description")
+                .and_then(text)
+                .unwrap_or_else(|| format!("CrewAI tool {name}"));
+            Some(serde_json::json!({
+                "name": name,
+                "description": description,
+                "priority": (i + 1).min(10),
+            }))
+        })
+        .collect();
+    if !skills.is_empty() {
+        obj.insert(
+            "capabilities".into(),
+            serde_json::json!({ "skills": super::normalize_skills(skills) }),
+        );
+    }
+
+    if let Some(goal) = def.get("goal").and_then(text) {
+        obj.insert(
+            "directives".into(),
+            serde_json::json!({ "core_drive": goal }),
+        );
+    }
+
+    Ok(Value::Object(obj))
+}
+
+/// Trimmed, whitespace-collapsed string (YAML folded blocks end in newlines).
+fn text(value: &Value) -> Option<String> {
+    let joined = value
+        .as_str()?
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    (!joined.is_empty()).then_some(joined)
+}
+
+/// `senior_researcher` / `Senior Researcher` → `SeniorResearcher`.
+fn pascal_case(raw: &str) -> String {
+    raw.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(|w| {
+            let mut chars = w.chars();
+            chars
+                .next()
+                .map(|first| first.to_uppercase().chain(chars).collect::<String>())
+                .unwrap_or_default()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RESEARCHER: &str = r#"
+researcher:
+  role: >
+    Senior Data Researcher
+  goal: >
+    Uncover cutting-edge developments in AI agents
+  backstory: >
+    You're a seasoned researcher with a knack for
+    finding the most relevant information.
+  tools:
+    - SerperDevTool
+    - name: ScrapeWebsiteTool
+      description: Read the content of a web page.
+  verbose: true
+"#;
+
+    #[test]
+    fn single_agent_maps_identity_fields() {
+        let persona = import_crewai(&parse_crewai(RESEARCHER).unwrap()).unwrap();
+        assert_eq!(persona["name"], "Researcher");
+        assert_eq!(persona["role"], "Senior Data Researcher");
+        assert_eq!(
+            persona["directives"]["core_drive"],
+            "Uncover cutting-edge developments in AI agents"
+        );
+        assert_eq!(
+            persona["backstory"],
+            "You're a seasoned researcher with a knack for finding the most relevant information."
+        );
+        let skills = persona["capabilities"]["skills"].as_array().unwrap();
+        assert_eq!(skills[0]["name"], "SerperDevTool");
+        assert_eq!(skills[1]["description"], "Read the content of a web page.");
+        assert!(persona.get("verbose").is_none());
+    }
+
+    #[test]
+    fn unkeyed_agent_is_named_from_role() {
+        let data = parse_crewai("role: data analyst\ngoal: find trends\n").unwrap();
+        let persona = import_crewai(&data).unwrap();
+        assert_eq!(persona["name"], "DataAnalyst");
+        assert!(persona.get("capabilities").is_none());
+    }
+
+    #[test]
+    fn multi_agent_needs_a_choice() {
+        let yaml = format!("{RESEARCHER}\nwriter:\n  role: Tech Writer\n  goal: Explain\n");
+        let data = parse_crewai(&yaml).unwrap();
+        let err = import_crewai(&data).unwrap_err().to_string();
+        assert!(err.contains("researcher, writer"), "{err}");
+        assert!(err.contains("--agent"), "{err}");
+
+        let persona = import_crewai_agent(&data, Some("writer")).unwrap();
+        assert_eq!(persona["role"], "Tech Writer");
+        assert!(import_crewai_agent(&data, Some("editor")).is_err());
+    }
+}
@@ -1,2 +1,46 @@
 pub mod aieos;
+pub mod crewai;
+pub mod toml;
 pub mod zeroclaw;
+
+use serde_json::Value;
+
+/// Clean up imported skills: merge case-insensitive duplicate names and sort
+/// by priority ascending (1 first).
+///
+/// Of two duplicates the higher priority (lower number) wins, then the longer
+/// description. The sort is stable, so equal priorities keep source order.
+fn normalize_skills(skills: Vec<Value>) -> Vec<Value> {
+    let priority = |s: &Value| {
+        s.get("priority")
+            .and_then(Value::as_u64)
+            .unwrap_or(u64::MAX)
+    };
+    let desc_len = |s: &Value| {
+        s.get("description")
+            .and_then(Value::as_str)
+            .map_or(0, str::len)
+    };
+    let name = |s: &Value| {
+        s.get("name")
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_lowercase()
+    };
+
+    let mut out: Vec<Value> = Vec::with_capacity(skills.len());
+    for skill in skills {
+        match out.iter_mut().find(|kept| name(kept) == name(&skill)) {
+            Some(kept) => {
+                let better = (priority(&skill), std::cmp::Reverse(desc_len(&skill)))
+                    < (priority(kept), std::cmp::Reverse(desc_len(kept)));
+                if better {
+                    *kept = skill;
+                }
+            }
+            None => out.push(skill),
+        }
+    }
+    out.sort_by_key(priority);
+    out
+}
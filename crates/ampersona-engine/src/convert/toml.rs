@@ -0,0 +1,141 @@
+//! Convert between TOML and ampersona persona JSON.
+//!
+//! The document is carried as-is (no defaults are filled in) and must
+//! deserialize as a [`Persona`] in both directions. TOML has no `null`:
+//! `gates[].from_phase: null` (the schema's only nullable field) is omitted on
+//! export and restored on import; any other `null` is an error. Export
+//! re-imports its own output and fails unless the round trip reproduces the
+//! persona exactly, so nothing is dropped silently.
+
+use ampersona_core::spec::Persona;
+use serde_json::Value;
+
+use crate::error::{EngineError, Result};
+
+/// Render a persona as TOML.
+pub fn export_toml(data: &Value) -> Result<String> {
+    let err = |msg: String| EngineError::InvalidPersona(format!("cannot export to TOML: {msg}"));
+    serde_json::from_value::<Persona>(data.clone()).map_err(|e| err(e.to_string()))?;
+
+    let mut stripped = data.clone();
+    if let Some(gates) = stripped.get_mut("gates").and_then(Value::as_array_mut) {
+        for gate in gates.iter_mut().filter_map(Value::as_object_mut) {
+            if gate.get("from_phase") == Some(&Value::Null) {
+                gate.shift_remove("from_phase");
+            }
+        }
+    }
+    if let Some(path) = find_null("", &stripped) {
+        return Err(err(format!("{path} is null, which TOML cannot represent")));
+    }
+
+    let toml = toml::to_string_pretty(&stripped).map_err(|e| err(e.to_string()))?;
+    let back = import_toml(&toml)?;
+    if let Some(path) = first_difference("", data, &back) {
+        return Err(err(format!("{path} does not survive a TOML round trip")));
+    }
+    Ok(toml)
+}
+
+/// Parse a TOML persona into ampersona JSON.
+pub fn import_toml(content: &str) -> Result<Value> {
+    let err = |msg: String| EngineError::InvalidPersona(format!("invalid TOML persona: {msg}"));
+    let mut data: Value = toml::from_str(content).map_err(|e| err(e.to_string()))?;
+    if let Some(gates) = data.get_mut("gates").and_then(Value::as_array_mut) {
+        for gate in gates.iter_mut().filter_map(Value::as_object_mut) {
+            gate.entry("from_phase").or_insert(Value::Null);
+        }
+    }
+    serde_json::from_value::<Persona>(data.clone()).map_err(|e| err(e.to_string()))?;
+    Ok(data)
+}
+
+fn find_null(path: &str, value: &Value) -> Option<String> {
+    match value {
+        Value::Null => Some(if path.is_empty() {
+            "/".into()
+        } else {
+            path.into()
+        }),
+        Value::Object(obj) => obj
+            .iter()
+            .find_map(|(k, v)| find_null(&format!("{path}/{k}"), v)),
+        Value::Array(items) => items
+            .iter()
+            .enumerate()
+            .find_map(|(i, v)| find_null(&format!("{path}/{i}"), v)),
+        _ => None,
+    }
+}
+
+/// JSON pointer of the first place `a` and `b` disagree.
+fn first_difference(path: &str, a: &Value, b: &Value) -> Option<String> {
+    match (a, b) {
+        (Value::Object(ao), Value::Object(bo)) => {
+            for (k, av) in ao {
+                let child = format!("{path}/{k}");
+                match bo.get(k) {
+                    Some(bv) => {
+                        if let Some(p) = first_difference(&child, av, bv) {
+                            return Some(p);
+                        }
+                    }
+                    None => return Some(child),
+                }
+            }
+            bo.keys()
+                .find(|k| !ao.contains_key(*k))
+                .map(|k| format!("{path}/{k}"))
+        }
+        (Value::Array(aa), Value::Array(ba)) if aa.len() == ba.len() => aa
+            .iter()
+            .zip(ba)
+            .enumerate()
+            .find_map(|(i, (av, bv))| first_difference(&format!("{path}/{i}"), av, bv)),
+        (Value::Number(an), Value::Number(bn)) if an.as_f64() == bn.as_f64() => None,
+        _ if a == b => None,
+        _ => Some(if path.is_empty() {
+            "/".into()
+        } else {
+            path.into()
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn quiet_stone() -> Value {
+        let path = concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/../../examples/quiet_stone_v1.json"
+        );
+        serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn round_trip_keeps_null_from_phase() {
+        let data = quiet_stone();
+        let toml = export_toml(&data).unwrap();
+        assert!(!toml.contains("null"));
+        let back = import_toml(&toml).unwrap();
+        assert_eq!(first_difference("", &data, &back), None);
+        assert_eq!(back["gates"][0]["from_phase"], Value::Null);
+    }
+
+    #[test]
+    fn unsupported_shapes_are_errors() {
+        let mut data = quiet_stone();
+        data["gates"][1]["criteria"][0]["value"] = serde_json::json!([1, null]);
+        let err = export_toml(&data).unwrap_err().to_string();
+        assert!(err.contains("/gates/1/criteria/0/value/1 is null"), "{err}");
+
+        let mut data = quiet_stone();
+        data["backstory"] = Value::Null;
+        assert!(export_toml(&data).is_err());
+
+        let err = import_toml("name = [").unwrap_err().to_string();
+        assert!(err.contains("invalid TOML persona"), "{err}");
+    }
+}
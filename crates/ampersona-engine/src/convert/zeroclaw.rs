@@ -11,7 +11,7 @@
 //! - ZeroClaw-specific fields preserved in `authority.ext.zeroclaw`
 //! - Optional behavioral sections (psychology, voice, directives) with validation
 
-use anyhow::Result;
+use crate::error::{EngineError, Result};
 use serde_json::{Map, Value};
 
 // ── Utilities ────────────────────────────────────────────────────────
@@ -168,6 +168,7 @@ fn normalize_capabilities(zc: &Value) -> Option<Value> {
             }))
         })
         .collect();
+    let skills = super::normalize_skills(skills);
 
     if skills.is_empty() {
         return None;
@@ -587,7 +588,9 @@ fn normalize_directives(zc: &Value) -> Option<Value> {
 /// - `psychology`, `voice`, `directives` → behavioral sections (pass-through)
 pub fn import_zeroclaw(data: &Value) -> Result<Value> {
     if !data.is_object() {
-        anyhow::bail!("ZeroClaw config must be a JSON object");
+        return Err(EngineError::InvalidPersona(
+            "ZeroClaw config must be a JSON object".into(),
+        ));
     }
 
     let mut obj = Map::new();
@@ -963,6 +966,21 @@ mod tests {
         assert_eq!(persona["capabilities"]["skills"][1]["priority"], 2);
     }
 
+    #[test]
+    fn import_capabilities_merges_duplicates() {
+        let zc = serde_json::json!({
+            "name": "Bot",
+            "capabilities": ["testing", "rust", "Testing"]
+        });
+
+        // Defaulted priorities follow source order; the later duplicate loses.
+        let persona = import_zeroclaw(&zc).unwrap();
+        let skills = persona["capabilities"]["skills"].as_array().unwrap();
+        assert_eq!(skills.len(), 2);
+        assert_eq!(skills[0]["name"], "testing");
+        assert_eq!(skills[1]["name"], "rust");
+    }
+
     #[test]
     fn autonomy_level_mapping() {
         let cases = [
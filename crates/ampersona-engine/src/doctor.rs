@@ -0,0 +1,412 @@
+//! Health checks for a directory of personas and their sidecars (`amp doctor`).
+//!
+//! Problems are reported as [`Finding`]s, most severe first, each with a
+//! suggested fix. Only removing stale writer locks is considered safe to do
+//! automatically; everything else touches audit data or signed files.
+
+use std::path::{Path, PathBuf};
+
+use ampersona_core::sidecar;
+use ampersona_core::spec::authority::Authority;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::error::{EngineError, Result};
+use crate::state::{atomic, audit_log};
+
+/// How urgently a finding needs attention.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Error,
+    Warning,
+    Info,
+}
+
+impl Severity {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+            Severity::Info => "info",
+        }
+    }
+}
+
+/// One problem found by [`diagnose`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Finding {
+    pub severity: Severity,
+    /// Stable identifier: `defaults`, `invalid_json`, `schema_version`,
+    /// `orphan_sidecar`, `stale_lock`, `held_lock`, `audit_chain`, `checkpoint`.
+    pub code: &'static str,
+    pub path: String,
+    pub message: String,
+    /// Suggested remedy.
+    pub fix: String,
+    /// `amp doctor --fix` repairs this one.
+    pub fixable: bool,
+}
+
+/// Sidecar suffixes and the persona file each belongs to.
+const SIDECARS: &[&str] = &[
+    sidecar::STATE,
+    sidecar::AUDIT,
+    sidecar::DRIFT,
+    sidecar::CHECKPOINT,
+];
+
+/// Check `dir` (recursively, skipping `.ampersona/` itself) and its workspace
+/// defaults. Findings are sorted by severity, then path.
+pub fn diagnose(dir: &str) -> Result<Vec<Finding>> {
+    let meta = std::fs::metadata(dir).map_err(|e| EngineError::read(dir, e))?;
+    if !meta.is_dir() {
+        return Err(EngineError::io(
+            format!("cannot diagnose {dir}"),
+            std::io::ErrorKind::NotADirectory.into(),
+        ));
+    }
+    let mut findings = Vec::new();
+    check_defaults(dir, &mut findings);
+
+    let mut files = Vec::new();
+    walk(Path::new(dir), &mut files);
+    files.sort();
+    for file in &files {
+        let path = file.to_string_lossy();
+        if let Some(suffix) = SIDECARS.iter().find(|s| path.ends_with(*s)) {
+            check_sidecar(&path, suffix, &mut findings);
+        } else if path.ends_with(".state.json.lock") {
+            check_lock(&path, &mut findings);
+        } else if path.ends_with(".json") {
+            check_persona(&path, &mut findings);
+        }
+    }
+
+    findings.sort_by(|a, b| (a.severity, &a.path).cmp(&(b.severity, &b.path)));
+    Ok(findings)
+}
+
+/// Apply the fixable findings. Returns the ones repaired.
+pub fn apply_safe_fixes(findings: &[Finding]) -> Result<Vec<Finding>> {
+    let mut fixed = Vec::new();
+    for finding in findings.iter().filter(|f| f.fixable) {
+        if finding.code != "stale_lock" {
+            continue;
+        }
+        // Re-check: the holder may have released or refreshed it meanwhile.
+        if let Some(stale) = atomic::stale_lock(&finding.path) {
+            if atomic::reclaim_stale(&finding.path, &stale) {
+                fixed.push(finding.clone());
+            }
+        }
+    }
+    Ok(fixed)
+}
+
+fn walk(dir: &Path, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        if file_type.is_dir() {
+            if entry.file_name() != ".ampersona" {
+                walk(&entry.path(), files);
+            }
+        } else if file_type.is_file() {
+            files.push(entry.path());
+        }
+    }
+}
+
+fn finding(
+    severity: Severity,
+    code: &'static str,
+    path: &str,
+    message: impl Into<String>,
+    fix: impl Into<String>,
+) -> Finding {
+    Finding {
+        severity,
+        code,
+        path: path.to_string(),
+        message: message.into(),
+        fix: fix.into(),
+        fixable: false,
+    }
+}
+
+fn check_defaults(dir: &str, findings: &mut Vec<Finding>) {
+    let path = Path::new(dir).join(".ampersona/defaults.json");
+    let path = path.to_string_lossy();
+    let content = match std::fs::read_to_string(path.as_ref()) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            findings.push(finding(
+                Severity::Info,
+                "defaults",
+                &path,
+                "no workspace defaults",
+                "run `amp init --workspace` to set a baseline authority",
+            ));
+            return;
+        }
+        Err(e) => {
+            findings.push(finding(
+                Severity::Error,
+                "defaults",
+                &path,
+                format!("cannot read workspace defaults: {e}"),
+                "fix the file's permissions",
+            ));
+            return;
+        }
+    };
+    let problem = match serde_json::from_str::<Value>(&content) {
+        Err(e) => Some(format!("invalid JSON: {e}")),
+        Ok(data) => match data.get("authority") {
+            None => Some("missing 'authority' key".to_string()),
+            Some(authority) => serde_json::from_value::<Authority>(authority.clone())
+                .err()
+                .map(|e| format!("invalid authority: {e}")),
+        },
+    };
+    if let Some(problem) = problem {
+        findings.push(finding(
+            Severity::Error,
+            "defaults",
+            &path,
+            format!("workspace defaults are ignored: {problem}"),
+            "correct the file or recreate it with `amp init --workspace`",
+        ));
+    }
+}
+
+fn check_persona(path: &str, findings: &mut Vec<Finding>) {
+    let data = match std::fs::read_to_string(path)
+        .map_err(|e| e.to_string())
+        .and_then(|c| serde_json::from_str::<Value>(&c).map_err(|e| e.to_string()))
+    {
+        Ok(data) => data,
+        Err(e) => {
+            findings.push(finding(
+                Severity::Error,
+                "invalid_json",
+                path,
+                format!("cannot parse: {e}"),
+                "repair the JSON (amp check reports the position)",
+            ));
+            return;
+        }
+    };
+    // Metrics files, indexes and the like share the directory.
+    if data.get("name").is_none() {
+        return;
+    }
+    let version = ampersona_core::schema::detect_version(&data);
+    if version != "1.0" {
+        findings.push(finding(
+            Severity::Warning,
+            "schema_version",
+            path,
+            format!("persona uses schema v{version}"),
+            format!("run `amp migrate {path}`"),
+        ));
+    } else if let Some(schema) = data.get("$schema").and_then(Value::as_str) {
+        if !schema.contains(&format!("/v{version}/")) {
+            findings.push(finding(
+                Severity::Warning,
+                "schema_version",
+                path,
+                format!("$schema {schema} does not match version {version}"),
+                format!("point $schema at the v{version} schema (re-sign if signed)"),
+            ));
+        }
+    }
+}
+
+fn check_sidecar(path: &str, suffix: &str, findings: &mut Vec<Finding>) {
+    let persona = format!("{}.json", &path[..path.len() - suffix.len()]);
+    if !Path::new(&persona).exists() {
+        findings.push(finding(
+            Severity::Warning,
+            "orphan_sidecar",
+            path,
+            format!("no persona at {persona}"),
+            "restore or rename the persona, or archive this file",
+        ));
+    }
+    match suffix {
+        sidecar::AUDIT => {
+            if let Err(e) = audit_log::verify_chain(path) {
+                findings.push(finding(
+                    Severity::Error,
+                    "audit_chain",
+                    path,
+                    format!("audit chain does not verify: {}", e.with_causes()),
+                    "investigate with `amp audit --verify`; restore the log from a bundle or backup",
+                ));
+            }
+        }
+        sidecar::CHECKPOINT => check_checkpoint(path, suffix, findings),
+        _ => {}
+    }
+}
+
+fn check_checkpoint(path: &str, suffix: &str, findings: &mut Vec<Finding>) {
+    let audit = format!("{}.audit.jsonl", &path[..path.len() - suffix.len()]);
+    let covered = std::fs::read_to_string(path)
+        .ok()
+        .and_then(|c| serde_json::from_str::<Value>(&c).ok())
+        .and_then(|c| c.get("entries").and_then(Value::as_u64));
+    let Some(covered) = covered else {
+        findings.push(finding(
+            Severity::Error,
+            "checkpoint",
+            path,
+            "unreadable checkpoint",
+            "re-create it with `amp audit --checkpoint-create` once the log is trusted",
+        ));
+        return;
+    };
+    if !Path::new(&audit).exists() {
+        findings.push(finding(
+            Severity::Error,
+            "checkpoint",
+            path,
+            format!("checkpoint covers {covered} entries but {audit} is missing"),
+            "restore the audit log from a bundle or backup",
+        ));
+        return;
+    }
+    let entries = audit_log::verify_chain(&audit).ok();
+    let message = match entries {
+        Some(n) if n < covered => {
+            format!("checkpoint covers {covered} entries but the audit log has {n} (truncated?)")
+        }
+        _ if audit_log::verify_checkpoint(&audit, path).unwrap_or(false) => return,
+        _ => "checkpoint chain head does not match the audit log".to_string(),
+    };
+    findings.push(finding(
+        Severity::Error,
+        "checkpoint",
+        path,
+        message,
+        "restore the audit log from a bundle or backup; `amp audit --checkpoint-verify` shows the mismatch",
+    ));
+}
+
+fn check_lock(path: &str, findings: &mut Vec<Finding>) {
+    match atomic::stale_lock(path) {
+        Some(stale) => findings.push(Finding {
+            fixable: true,
+            ..finding(
+                Severity::Warning,
+                "stale_lock",
+                path,
+                format!(
+                    "stale writer lock ({}, pid {})",
+                    stale.reason,
+                    stale.pid.map_or_else(|| "?".to_string(), |p| p.to_string())
+                ),
+                "remove it; the next writer would reclaim it too",
+            )
+        }),
+        None => findings.push(finding(
+            Severity::Info,
+            "held_lock",
+            path,
+            "a writer currently holds this lock",
+            "wait for it to finish, or raise --lock-timeout",
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_orphans_and_truncated_checkpoints_first() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::write(root.join("ghost.state.json"), "{}").unwrap();
+        std::fs::write(
+            root.join("agent.json"),
+            r#"{"version": "1.0", "name": "A"}"#,
+        )
+        .unwrap();
+        let audit = root.join("agent.audit.jsonl");
+        let audit = audit.to_str().unwrap();
+        for _ in 0..3 {
+            audit_log::append_audit_with(audit, &serde_json::json!({"event_type": "x"}), None)
+                .unwrap();
+        }
+        let checkpoint = root.join("agent.checkpoint.json");
+        audit_log::create_checkpoint(audit, checkpoint.to_str().unwrap()).unwrap();
+        let content = std::fs::read_to_string(audit).unwrap();
+        let first = content.lines().next().unwrap();
+        std::fs::write(audit, format!("{first}\n")).unwrap();
+
+        let findings = diagnose(root.to_str().unwrap()).unwrap();
+        let codes: Vec<_> = findings.iter().map(|f| (f.severity, f.code)).collect();
+        assert_eq!(
+            codes,
+            [
+                (Severity::Error, "checkpoint"),
+                (Severity::Warning, "orphan_sidecar"),
+                (Severity::Info, "defaults"),
+            ]
+        );
+        assert!(
+            findings[0].message.contains("has 1"),
+            "{}",
+            findings[0].message
+        );
+    }
+
+    #[test]
+    fn fix_removes_only_stale_locks() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir(root.join(".ampersona")).unwrap();
+        std::fs::write(
+            root.join(".ampersona/defaults.json"),
+            r#"{"authority": {"autonomy": "supervised"}}"#,
+        )
+        .unwrap();
+        std::fs::write(
+            root.join("agent.json"),
+            r#"{"version": "0.2", "name": "A"}"#,
+        )
+        .unwrap();
+        let old = chrono::Utc::now().timestamp() - atomic::STALE_LOCK_SECS - 10;
+        let lock = root.join("agent.state.json.lock");
+        std::fs::write(&lock, format!("?\n{old}\n")).unwrap();
+
+        let findings = diagnose(root.to_str().unwrap()).unwrap();
+        let codes: Vec<_> = findings.iter().map(|f| f.code).collect();
+        assert_eq!(codes, ["schema_version", "stale_lock"]);
+
+        let fixed = apply_safe_fixes(&findings).unwrap();
+        assert_eq!(fixed.len(), 1);
+        assert!(!lock.exists());
+        assert!(root.join("agent.json").exists());
+    }
+
+    #[test]
+    fn rejects_a_file_as_not_a_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("agent.json");
+        std::fs::write(&file, "{}").unwrap();
+
+        let err = diagnose(file.to_str().unwrap()).unwrap_err();
+        assert_eq!(err.code(), "E_INTERNAL");
+        assert!(err.with_causes().ends_with("not a directory"));
+        let missing = diagnose(dir.path().join("gone").to_str().unwrap()).unwrap_err();
+        assert_eq!(missing.code(), "E_FILE_NOT_FOUND");
+    }
+}
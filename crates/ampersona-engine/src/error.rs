@@ -0,0 +1,173 @@
+use ampersona_core::errors::PolicyError;
+
+use crate::gates::metrics::MetricsIssue;
+
+/// Typed errors returned by engine operations.
+///
+/// Each variant maps to a stable `E_*` code (see [`EngineError::code`]) so
+/// callers can match on error kinds instead of parsing messages; the CLI
+/// renders the same codes in `--json` error output.
+#[derive(Debug, thiserror::Error)]
+pub enum EngineError {
+    #[error("cannot read {path}")]
+    FileNotFound {
+        path: String,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("{context}")]
+    Io {
+        context: String,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("{path}: invalid JSON")]
+    InvalidJson {
+        path: String,
+        #[source]
+        source: serde_json::Error,
+    },
+    #[error("{path}: invalid JSON at line {line}")]
+    InvalidJsonLine {
+        path: String,
+        line: usize,
+        #[source]
+        source: serde_json::Error,
+    },
+    #[error("{0}")]
+    InvalidPersona(String),
+    #[error("{path}: invalid metrics: {}", format_issues(.issues))]
+    InvalidMetrics {
+        path: String,
+        issues: Vec<MetricsIssue>,
+    },
+    #[error("metrics source {0}")]
+    MetricsSource(String),
+    #[error("invalid bundle: {0}")]
+    InvalidBundle(String),
+    #[error("state file is locked by another process (lock: {lock_path})")]
+    LockContention { lock_path: String },
+    #[error("{0}")]
+    ChainBroken(String),
+    #[error("audit encryption: {0}")]
+    AuditCrypto(String),
+    #[error("state database: {0}")]
+    Storage(String),
+    #[error("notification webhook {0}")]
+    Notify(String),
+    #[error("{path}: overlay would raise autonomy {from} -> {to}")]
+    AutonomyUpgrade {
+        path: String,
+        from: &'static str,
+        to: &'static str,
+    },
+    #[error("policy evaluation error: {0}")]
+    Policy(#[from] PolicyError),
+    #[error("serialization error: {0}")]
+    Serialize(#[from] serde_json::Error),
+}
+
+impl EngineError {
+    /// Stable machine-readable code, matching the CLI's `--json` error codes.
+    pub fn code(&self) -> &'static str {
+        match self {
+            EngineError::FileNotFound { .. } => "E_FILE_NOT_FOUND",
+            EngineError::InvalidJson { .. } | EngineError::InvalidJsonLine { .. } => {
+                "E_INVALID_JSON"
+            }
+            EngineError::InvalidPersona(_) => "E_INVALID_PERSONA",
+            EngineError::InvalidMetrics { .. } => "E_INVALID_METRICS",
+            EngineError::MetricsSource(_) => "E_METRICS_SOURCE",
+            EngineError::InvalidBundle(_) => "E_INVALID_BUNDLE",
+            EngineError::LockContention { .. } => "E_LOCK_CONTENTION",
+            EngineError::ChainBroken(_) => "E_CHAIN_BROKEN",
+            EngineError::AuditCrypto(_) => "E_AUDIT_CRYPTO",
+            EngineError::Storage(_) => "E_STORAGE",
+            EngineError::Notify(_) => "E_NOTIFY",
+            EngineError::AutonomyUpgrade { .. } => "E_AUTONOMY_UPGRADE",
+            EngineError::Policy(_) => "E_POLICY",
+            EngineError::Io { .. } | EngineError::Serialize(_) => "E_INTERNAL",
+        }
+    }
+
+    /// Classify a read failure: missing files become `FileNotFound`.
+    pub(crate) fn read(path: &str, source: std::io::Error) -> Self {
+        if source.kind() == std::io::ErrorKind::NotFound {
+            EngineError::FileNotFound {
+                path: path.to_string(),
+                source,
+            }
+        } else {
+            EngineError::Io {
+                context: format!("cannot read {path}"),
+                source,
+            }
+        }
+    }
+
+    pub(crate) fn io(context: impl Into<String>, source: std::io::Error) -> Self {
+        EngineError::Io {
+            context: context.into(),
+            source,
+        }
+    }
+
+    /// The message followed by its causes, as `{:#}` renders an `anyhow::Error`.
+    pub(crate) fn with_causes(&self) -> String {
+        let mut text = self.to_string();
+        let mut source = std::error::Error::source(self);
+        while let Some(cause) = source {
+            text.push_str(&format!(": {cause}"));
+            source = cause.source();
+        }
+        text
+    }
+}
+
+fn format_issues(issues: &[MetricsIssue]) -> String {
+    issues
+        .iter()
+        .map(|i| {
+            if i.key.is_empty() {
+                i.message.clone()
+            } else {
+                format!("'{}': {}", i.key, i.message)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+pub type Result<T> = std::result::Result<T, EngineError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_error_classifies_not_found() {
+        let err = std::fs::read_to_string("/nonexistent/ampersona/file.json")
+            .map_err(|e| EngineError::read("/nonexistent/ampersona/file.json", e))
+            .unwrap_err();
+        assert_eq!(err.code(), "E_FILE_NOT_FOUND");
+        assert!(err.to_string().starts_with("cannot read /nonexistent"));
+    }
+
+    #[test]
+    fn cause_is_rendered_once() {
+        let source = std::io::Error::other("disk on fire");
+        let err = EngineError::io("cannot write x.state.json", source);
+        assert_eq!(err.to_string(), "cannot write x.state.json");
+        assert_eq!(err.with_causes(), "cannot write x.state.json: disk on fire");
+    }
+
+    #[test]
+    fn codes_are_stable() {
+        let lock = EngineError::LockContention {
+            lock_path: "x.state.json.lock".into(),
+        };
+        assert_eq!(lock.code(), "E_LOCK_CONTENTION");
+        let policy = EngineError::from(PolicyError::InternalError("boom".into()));
+        assert_eq!(policy.code(), "E_POLICY");
+    }
+}
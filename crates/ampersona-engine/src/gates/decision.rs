@@ -18,4 +18,8 @@ pub struct GateDecisionRecord {
     pub is_override: bool,
     pub state_rev: u64,
     pub metrics_hash: String,
+    /// Set when `reevaluate` let this decision through although it repeats the
+    /// last transition's `(gate_id, metrics_hash, state_rev)`.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub forced_reeval: bool,
 }
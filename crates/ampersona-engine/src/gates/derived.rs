@@ -0,0 +1,134 @@
+//! Computed criterion metrics: `ratio(a, b)`, `sum(a, b, ...)`, `diff(a, b)`.
+
+use ampersona_core::errors::MetricError;
+use ampersona_core::spec::gates::{DerivedFn, DerivedMetric};
+use ampersona_core::traits::{MetricQuery, MetricSample, MetricsProvider};
+use chrono::{DateTime, Utc};
+
+/// Resolve a criterion metric, computing derived metrics from their operands.
+///
+/// Plain names go straight to the provider. Each operand of a derived metric
+/// is queried with the criterion's window and must be a number. A malformed
+/// expression, a missing or non-numeric operand, or a zero denominator is an
+/// error, which the evaluator treats like a missing metric (fail closed).
+pub fn resolve(
+    metrics: &dyn MetricsProvider,
+    query: &MetricQuery,
+) -> Result<MetricSample, MetricError> {
+    let derived = match DerivedMetric::parse(&query.name) {
+        Ok(Some(derived)) => derived,
+        Ok(None) => return metrics.get_metric(query),
+        Err(e) => return Err(MetricError::Undefined(query.name.clone(), e)),
+    };
+
+    let mut values = Vec::with_capacity(derived.operands.len());
+    let mut sampled_at: Option<DateTime<Utc>> = None;
+    for operand in &derived.operands {
+        let sample = metrics.get_metric(&MetricQuery {
+            name: operand.clone(),
+            window: query.window,
+        })?;
+        let value = sample
+            .value
+            .as_f64()
+            .ok_or_else(|| MetricError::TypeMismatch(operand.clone()))?;
+        values.push(value);
+        // The result is only as fresh as its oldest operand
+        sampled_at = Some(sampled_at.map_or(sample.sampled_at, |t| t.min(sample.sampled_at)));
+    }
+
+    let result = match derived.func {
+        DerivedFn::Ratio if values[1] == 0.0 => {
+            return Err(MetricError::Undefined(
+                query.name.clone(),
+                format!("{} is zero", derived.operands[1]),
+            ))
+        }
+        DerivedFn::Ratio => values[0] / values[1],
+        DerivedFn::Sum => values.iter().sum(),
+        DerivedFn::Diff => values[0] - values[1],
+    };
+    let value = serde_json::Number::from_f64(result)
+        .map(serde_json::Value::Number)
+        .ok_or_else(|| MetricError::Undefined(query.name.clone(), "not a finite number".into()))?;
+
+    Ok(MetricSample {
+        name: query.name.clone(),
+        value,
+        sampled_at: sampled_at.unwrap_or_else(Utc::now),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    struct TestMetrics(HashMap<String, serde_json::Value>);
+
+    impl MetricsProvider for TestMetrics {
+        fn get_metric(&self, query: &MetricQuery) -> Result<MetricSample, MetricError> {
+            self.0
+                .get(&query.name)
+                .map(|v| MetricSample {
+                    name: query.name.clone(),
+                    value: v.clone(),
+                    sampled_at: Utc::now(),
+                })
+                .ok_or(MetricError::NotFound(query.name.clone()))
+        }
+    }
+
+    fn metrics() -> TestMetrics {
+        TestMetrics(HashMap::from([
+            ("errors".to_string(), serde_json::json!(3)),
+            ("requests".to_string(), serde_json::json!(600)),
+            ("retries".to_string(), serde_json::json!(2)),
+            ("zero".to_string(), serde_json::json!(0)),
+            ("status".to_string(), serde_json::json!("ok")),
+        ]))
+    }
+
+    fn value(name: &str) -> Result<serde_json::Value, MetricError> {
+        let query = MetricQuery {
+            name: name.into(),
+            window: None,
+        };
+        resolve(&metrics(), &query).map(|s| s.value)
+    }
+
+    #[test]
+    fn computes_ratio_sum_and_diff() {
+        assert_eq!(value("ratio(errors, requests)").unwrap(), 0.005);
+        assert_eq!(value("sum(errors, retries, requests)").unwrap(), 605.0);
+        assert_eq!(value("diff(requests, errors)").unwrap(), 597.0);
+        // Plain names pass through untouched
+        assert_eq!(value("errors").unwrap(), 3);
+    }
+
+    #[test]
+    fn zero_denominator_is_undefined() {
+        assert!(matches!(
+            value("ratio(errors, zero)"),
+            Err(MetricError::Undefined(..))
+        ));
+        // A zero numerator is fine
+        assert_eq!(value("ratio(zero, requests)").unwrap(), 0.0);
+    }
+
+    #[test]
+    fn bad_operands_are_errors() {
+        assert!(matches!(
+            value("ratio(errors, missing)"),
+            Err(MetricError::NotFound(name)) if name == "missing"
+        ));
+        assert!(matches!(
+            value("sum(errors, status)"),
+            Err(MetricError::TypeMismatch(name)) if name == "status"
+        ));
+        assert!(matches!(
+            value("ratio(errors)"),
+            Err(MetricError::Undefined(..))
+        ));
+    }
+}
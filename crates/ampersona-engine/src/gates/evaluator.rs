@@ -1,22 +1,27 @@
+use std::cmp::Ordering;
 use std::collections::HashMap;
 use std::time::Duration;
 
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use sha2::{Digest, Sha256};
 
 use ampersona_core::spec::gates::{Criterion, Gate, MetricSchema};
 use ampersona_core::state::PhaseState;
-use ampersona_core::traits::{CriteriaResult, MetricQuery, MetricsProvider};
+use ampersona_core::traits::{CriteriaResult, MetricQuery, MetricSample, MetricsProvider};
 use ampersona_core::types::{CriterionOp, GateApproval, GateDirection, GateEnforcement};
 
 use super::decision::GateDecisionRecord;
+use super::derived;
 
 /// Deterministic gate evaluator.
 ///
 /// Algorithm:
 /// 1. Collect candidate gates whose from_phase matches current phase
-/// 2. Sort by (direction: demote > promote, priority DESC, id ASC)
+/// 2. Sort by (direction: demote > promote, priority DESC, id ASC); an explicit
+///    `order` on a gate replaces the (priority, id) tie-break, see [`evaluation_order`]
 /// 3. Check cooldown/hysteresis — skip if last transition was too recent
+///    Hold promotions that would reverse a demotion whose hysteresis band
+///    (criterion `exit` thresholds) has not been exited yet
 /// 4. Evaluate criteria for first passing gate
 /// 5. Check idempotency — skip if same (gate_id, metrics_hash, state_rev)
 /// 6. One transition per evaluation tick
@@ -29,25 +34,81 @@ impl DefaultGateEvaluator {
         state: &PhaseState,
         metrics: &dyn MetricsProvider,
     ) -> Option<GateDecisionRecord> {
-        let now = Utc::now();
+        self.evaluate_at(gates, state, metrics, Utc::now())
+    }
+
+    /// Evaluate with an explicit clock, used for cooldown checks.
+    ///
+    /// `evaluate` calls this with `Utc::now()`; simulation replays pass the
+    /// snapshot timestamp instead.
+    pub fn evaluate_at(
+        &self,
+        gates: &[Gate],
+        state: &PhaseState,
+        metrics: &dyn MetricsProvider,
+        now: DateTime<Utc>,
+    ) -> Option<GateDecisionRecord> {
+        self.run(gates, state, metrics, now, false)
+    }
+
+    /// Evaluate once without the idempotency skip.
+    ///
+    /// For re-running a gate when something outside the metrics changed: a
+    /// decision that repeats the last transition's `(gate_id, metrics_hash,
+    /// state_rev)` is returned with `forced_reeval` set instead of skipped.
+    /// Cooldowns and hysteresis holds still apply.
+    pub fn reevaluate(
+        &self,
+        gates: &[Gate],
+        state: &PhaseState,
+        metrics: &dyn MetricsProvider,
+    ) -> Option<GateDecisionRecord> {
+        self.run(gates, state, metrics, Utc::now(), true)
+    }
+
+    fn run(
+        &self,
+        gates: &[Gate],
+        state: &PhaseState,
+        metrics: &dyn MetricsProvider,
+        now: DateTime<Utc>,
+        force_reeval: bool,
+    ) -> Option<GateDecisionRecord> {
+        #[cfg(feature = "otel")]
+        let span = tracing::info_span!(
+            "ampersona.gate.evaluate",
+            persona = %state.name,
+            phase = state.current_phase.as_deref().unwrap_or("none"),
+            gate = tracing::field::Empty,
+            decision = tracing::field::Empty,
+        )
+        .entered();
+
+        let record = self.select(gates, state, metrics, now, force_reeval);
+
+        #[cfg(feature = "otel")]
+        if let Some(record) = &record {
+            span.record("gate", record.gate_id.as_str());
+            span.record("decision", record.decision.as_str());
+        }
+        record
+    }
 
+    fn select(
+        &self,
+        gates: &[Gate],
+        state: &PhaseState,
+        metrics: &dyn MetricsProvider,
+        now: DateTime<Utc>,
+        force_reeval: bool,
+    ) -> Option<GateDecisionRecord> {
         // Collect candidates matching current phase
         let mut candidates: Vec<&Gate> = gates
             .iter()
             .filter(|g| g.from_phase.as_deref() == state.current_phase.as_deref())
             .collect();
 
-        // Sort: demote > promote, then priority DESC, then id ASC
-        candidates.sort_by(|a, b| {
-            let dir_ord = |d: &GateDirection| match d {
-                GateDirection::Demote => 0,
-                GateDirection::Promote => 1,
-            };
-            dir_ord(&a.direction)
-                .cmp(&dir_ord(&b.direction))
-                .then_with(|| b.priority.cmp(&a.priority))
-                .then_with(|| a.id.cmp(&b.id))
-        });
+        candidates.sort_by(|a, b| evaluation_order(a, b));
 
         // Evaluate each candidate
         for gate in candidates {
@@ -63,11 +124,17 @@ impl DefaultGateEvaluator {
                 }
             }
 
-            let (all_pass, results, snapshot) = self.evaluate_criteria(
+            if self.held_by_hysteresis(gate, gates, state, metrics, now) {
+                continue;
+            }
+
+            let (all_pass, results, snapshot) = self.evaluate_criteria_at(
                 &gate.criteria,
                 metrics,
                 gate.direction,
                 gate.metrics_schema.as_ref(),
+                gate.max_age_seconds,
+                now,
             );
 
             if all_pass {
@@ -75,13 +142,13 @@ impl DefaultGateEvaluator {
                 let metrics_hash = compute_metrics_hash(&snapshot);
 
                 // Idempotency check: skip if same (gate_id, metrics_hash, state_rev)
-                if let Some(last) = &state.last_transition {
-                    if last.gate_id == gate.id
+                let repeat = state.last_transition.as_ref().is_some_and(|last| {
+                    last.gate_id == gate.id
                         && last.metrics_hash.as_deref() == Some(&metrics_hash)
                         && last.state_rev == state.state_rev
-                    {
-                        continue;
-                    }
+                });
+                if repeat && !force_reeval {
+                    continue;
                 }
 
                 // Handle approval type
@@ -100,6 +167,7 @@ impl DefaultGateEvaluator {
                             is_override: false,
                             state_rev: state.state_rev,
                             metrics_hash,
+                            forced_reeval: repeat,
                         });
                     }
                     GateApproval::Auto => {
@@ -123,6 +191,7 @@ impl DefaultGateEvaluator {
                     is_override: false,
                     state_rev: state.state_rev,
                     metrics_hash,
+                    forced_reeval: repeat,
                 });
             }
         }
@@ -130,6 +199,55 @@ impl DefaultGateEvaluator {
         None
     }
 
+    /// Whether `gate` is a promotion held back by the demote gate that last fired.
+    ///
+    /// A demote criterion with an `exit` threshold defines a band: the demotion
+    /// fires at `value` (enter), and the reverse promotion stays blocked while the
+    /// metric still satisfies `op` against `exit`. A missing or stale metric
+    /// keeps the hold.
+    fn held_by_hysteresis(
+        &self,
+        gate: &Gate,
+        gates: &[Gate],
+        state: &PhaseState,
+        metrics: &dyn MetricsProvider,
+        now: DateTime<Utc>,
+    ) -> bool {
+        if gate.direction != GateDirection::Promote {
+            return false;
+        }
+        let Some(last) = &state.last_transition else {
+            return false;
+        };
+        let Some(demote) = gates.iter().find(|g| {
+            g.id == last.gate_id
+                && g.direction == GateDirection::Demote
+                && g.to_phase.as_str() == gate.from_phase.as_deref().unwrap_or("")
+                && g.from_phase.as_deref() == Some(gate.to_phase.as_str())
+        }) else {
+            return false;
+        };
+
+        demote.criteria.iter().any(|c| {
+            let Some(exit) = &c.exit else {
+                return false;
+            };
+            let query = MetricQuery {
+                name: c.metric.clone(),
+                window: c.window_seconds.map(Duration::from_secs),
+            };
+            match derived::resolve(metrics, &query) {
+                Ok(sample)
+                    if is_stale(&sample, c.max_age_seconds.or(demote.max_age_seconds), now) =>
+                {
+                    true
+                }
+                Ok(sample) => compare_values(&c.op, c.ci, &sample.value, exit),
+                Err(_) => true,
+            }
+        })
+    }
+
     pub fn evaluate_criteria(
         &self,
         criteria: &[Criterion],
@@ -140,6 +258,31 @@ impl DefaultGateEvaluator {
         bool,
         Vec<CriteriaResult>,
         HashMap<String, serde_json::Value>,
+    ) {
+        self.evaluate_criteria_at(
+            criteria,
+            metrics,
+            direction,
+            metrics_schema,
+            None,
+            Utc::now(),
+        )
+    }
+
+    /// Evaluate criteria with a gate-wide `max_age_seconds` default and an
+    /// explicit clock for the freshness check.
+    pub fn evaluate_criteria_at(
+        &self,
+        criteria: &[Criterion],
+        metrics: &dyn MetricsProvider,
+        direction: GateDirection,
+        metrics_schema: Option<&HashMap<String, MetricSchema>>,
+        max_age_seconds: Option<u64>,
+        now: DateTime<Utc>,
+    ) -> (
+        bool,
+        Vec<CriteriaResult>,
+        HashMap<String, serde_json::Value>,
     ) {
         let mut all_pass = true;
         let mut results = Vec::new();
@@ -151,19 +294,26 @@ impl DefaultGateEvaluator {
                 window: criterion.window_seconds.map(Duration::from_secs),
             };
 
-            let (actual, pass, type_mismatch) = match metrics.get_metric(&query) {
+            let mut stale = false;
+            let (actual, pass, type_mismatch) = match derived::resolve(metrics, &query) {
                 Ok(sample) => {
                     snapshot.insert(criterion.metric.clone(), sample.value.clone());
+                    stale = is_stale(&sample, criterion.max_age_seconds.or(max_age_seconds), now);
 
                     // Type validation: check metric value matches declared schema type
-                    if let Some(mismatch) =
-                        check_metric_type(&criterion.metric, &sample.value, metrics_schema)
-                    {
+                    let mismatch =
+                        check_metric_type(&criterion.metric, &sample.value, metrics_schema);
+                    if stale || mismatch.is_some() {
                         // Fail-closed: demote fires, promote blocked
                         let pass = direction == GateDirection::Demote;
-                        (sample.value, pass, Some(mismatch))
+                        (sample.value, pass, mismatch)
                     } else {
-                        let pass = compare_values(&criterion.op, &sample.value, &criterion.value);
+                        let pass = compare_values(
+                            &criterion.op,
+                            criterion.ci,
+                            &sample.value,
+                            &criterion.value,
+                        );
                         (sample.value, pass, None)
                     }
                 }
@@ -184,6 +334,7 @@ impl DefaultGateEvaluator {
                 actual,
                 pass,
                 type_mismatch,
+                stale,
             });
         }
 
@@ -191,6 +342,30 @@ impl DefaultGateEvaluator {
     }
 }
 
+/// Whether a sample is older than `max_age_seconds` at `now`.
+fn is_stale(sample: &MetricSample, max_age_seconds: Option<u64>, now: DateTime<Utc>) -> bool {
+    max_age_seconds.is_some_and(|max| (now - sample.sampled_at).num_seconds() > max as i64)
+}
+
+/// Candidate order: demote > promote; then gates with an explicit `order`
+/// (ascending, id ASC on ties) before the rest, which sort by priority DESC,
+/// then id ASC.
+fn evaluation_order(a: &Gate, b: &Gate) -> Ordering {
+    let dir_ord = |d: &GateDirection| match d {
+        GateDirection::Demote => 0,
+        GateDirection::Promote => 1,
+    };
+    dir_ord(&a.direction)
+        .cmp(&dir_ord(&b.direction))
+        .then_with(|| match (a.order, b.order) {
+            (Some(x), Some(y)) => x.cmp(&y),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => b.priority.cmp(&a.priority),
+        })
+        .then_with(|| a.id.cmp(&b.id))
+}
+
 /// Check if a metric value matches the declared type in metrics_schema.
 /// Returns Some(mismatch_description) if there's a type mismatch, None if ok or no schema.
 fn check_metric_type(
@@ -247,6 +422,7 @@ fn compute_metrics_hash(snapshot: &HashMap<String, serde_json::Value>) -> String
 
 fn compare_values(
     op: &CriterionOp,
+    ci: bool,
     actual: &serde_json::Value,
     expected: &serde_json::Value,
 ) -> bool {
@@ -257,7 +433,42 @@ fn compare_values(
         CriterionOp::Gte => cmp_num(actual, expected).is_some_and(|c| c >= 0),
         CriterionOp::Lt => cmp_num(actual, expected).is_some_and(|c| c < 0),
         CriterionOp::Lte => cmp_num(actual, expected).is_some_and(|c| c <= 0),
+        CriterionOp::In => in_set(actual, expected).unwrap_or(false),
+        CriterionOp::NotIn => in_set(actual, expected).is_some_and(|found| !found),
+        CriterionOp::Contains => contains(actual, expected, ci).unwrap_or(false),
+        CriterionOp::NotContains => contains(actual, expected, ci).is_some_and(|found| !found),
+    }
+}
+
+/// Substring test for string metrics; `None` (fail closed) unless both the
+/// metric and the threshold are strings.
+fn contains(actual: &serde_json::Value, expected: &serde_json::Value, ci: bool) -> Option<bool> {
+    let haystack = actual.as_str()?;
+    let needle = expected.as_str()?;
+    Some(if ci {
+        haystack.to_lowercase().contains(&needle.to_lowercase())
+    } else {
+        haystack.contains(needle)
+    })
+}
+
+/// Membership of a scalar metric in an array threshold.
+///
+/// Returns `None` when the threshold is not an array or the metric is not a
+/// string, number or bool, so both `in` and `not_in` fail closed.
+fn in_set(actual: &serde_json::Value, expected: &serde_json::Value) -> Option<bool> {
+    let items = expected.as_array()?;
+    if !(actual.is_string() || actual.is_number() || actual.is_boolean()) {
+        return None;
     }
+    Some(
+        items
+            .iter()
+            .any(|item| match (actual.as_f64(), item.as_f64()) {
+                (Some(a), Some(b)) => a == b,
+                _ => item == actual,
+            }),
+    )
 }
 
 fn cmp_num(a: &serde_json::Value, b: &serde_json::Value) -> Option<i8> {
@@ -308,6 +519,8 @@ mod tests {
             direction,
             enforcement: GateEnforcement::Enforce,
             priority: 10,
+            order: None,
+            max_age_seconds: None,
             cooldown_seconds: 0,
             from_phase: Some(from.into()),
             to_phase: to.into(),
@@ -315,6 +528,7 @@ mod tests {
             metrics_schema: None,
             approval: ampersona_core::types::GateApproval::Auto,
             on_pass: None,
+            on_fail: None,
         }
     }
 
@@ -330,6 +544,9 @@ mod tests {
                     metric: "score".into(),
                     op: CriterionOp::Gte,
                     window_seconds: None,
+                    exit: None,
+                    ci: false,
+                    max_age_seconds: None,
                     value: serde_json::json!(5),
                 }],
             ),
@@ -342,6 +559,9 @@ mod tests {
                     metric: "violations".into(),
                     op: CriterionOp::Gte,
                     window_seconds: None,
+                    exit: None,
+                    ci: false,
+                    max_age_seconds: None,
                     value: serde_json::json!(3),
                 }],
             ),
@@ -372,6 +592,69 @@ mod tests {
         assert_eq!(record.direction, GateDirection::Demote);
     }
 
+    fn passing_promote(id: &str, priority: i32, order: Option<u32>) -> Gate {
+        let mut gate = make_gate(
+            id,
+            GateDirection::Promote,
+            "active",
+            id,
+            vec![Criterion {
+                metric: "score".into(),
+                op: CriterionOp::Gte,
+                window_seconds: None,
+                exit: None,
+                ci: false,
+                max_age_seconds: None,
+                value: serde_json::json!(1),
+            }],
+        );
+        gate.priority = priority;
+        gate.order = order;
+        gate
+    }
+
+    fn winner(gates: &[Gate]) -> String {
+        let mut state = PhaseState::new("test".into());
+        state.current_phase = Some("active".into());
+        let metrics = TestMetrics(HashMap::from([("score".into(), serde_json::json!(5))]));
+        DefaultGateEvaluator
+            .evaluate(gates, &state, &metrics)
+            .unwrap()
+            .gate_id
+    }
+
+    #[test]
+    fn same_priority_ties_break_by_id() {
+        let gates = vec![passing_promote("b", 5, None), passing_promote("a", 5, None)];
+        assert_eq!(winner(&gates), "a");
+        let gates = vec![passing_promote("a", 5, None), passing_promote("b", 9, None)];
+        assert_eq!(winner(&gates), "b");
+    }
+
+    #[test]
+    fn explicit_order_beats_priority_and_id() {
+        // Lower priority and later id, but pinned first
+        let gates = vec![
+            passing_promote("a", 9, None),
+            passing_promote("z", 1, Some(0)),
+        ];
+        assert_eq!(winner(&gates), "z");
+        // Among ordered gates, order ascends regardless of priority or id
+        let gates = vec![
+            passing_promote("a", 9, Some(2)),
+            passing_promote("b", 1, Some(1)),
+        ];
+        assert_eq!(winner(&gates), "b");
+    }
+
+    #[test]
+    fn explicit_order_does_not_outrank_demote() {
+        let mut demote = passing_promote("demote", 0, None);
+        demote.direction = GateDirection::Demote;
+        let gates = vec![passing_promote("promote", 9, Some(0)), demote];
+        assert_eq!(winner(&gates), "demote");
+    }
+
     #[test]
     fn cooldown_prevents_reevaluation() {
         let gates = vec![make_gate(
@@ -384,6 +667,9 @@ mod tests {
                 op: CriterionOp::Gte,
                 value: serde_json::json!(3),
                 window_seconds: None,
+                exit: None,
+                ci: false,
+                max_age_seconds: None,
             }],
         )];
         // Set cooldown
@@ -420,6 +706,58 @@ mod tests {
         assert!(result.is_none());
     }
 
+    #[test]
+    fn cooldown_does_not_hold_back_a_different_gate() {
+        let mut gate = make_gate(
+            "trust_decay",
+            GateDirection::Demote,
+            "trusted",
+            "active",
+            vec![Criterion {
+                metric: "violations".into(),
+                op: CriterionOp::Gte,
+                value: serde_json::json!(1),
+                window_seconds: None,
+                exit: None,
+                ci: false,
+                max_age_seconds: None,
+            }],
+        );
+        gate.cooldown_seconds = 3600;
+
+        let promoted_at = Utc::now();
+        let state = PhaseState {
+            name: "test".into(),
+            current_phase: Some("trusted".into()),
+            state_rev: 2,
+            active_elevations: vec![],
+            last_transition: Some(TransitionRecord {
+                gate_id: "promote_to_trusted".into(),
+                from_phase: Some("active".into()),
+                to_phase: "trusted".into(),
+                at: promoted_at,
+                decision_id: "gate-2".into(),
+                metrics_hash: None,
+                state_rev: 2,
+            }),
+            pending_transition: None,
+            active_overlay: None,
+            updated_at: Utc::now(),
+        };
+
+        let mut metrics_map = HashMap::new();
+        metrics_map.insert("violations".into(), serde_json::json!(2));
+        let metrics = TestMetrics(metrics_map);
+        let evaluator = DefaultGateEvaluator;
+        let gates = [gate];
+
+        // The demote's cooldown only counts its own transitions, so a
+        // safety demotion right after a promote still fires
+        let early = promoted_at + Duration::minutes(10);
+        let record = evaluator.evaluate_at(&gates, &state, &metrics, early);
+        assert_eq!(record.unwrap().gate_id, "trust_decay");
+    }
+
     #[test]
     fn observe_mode_does_not_block() {
         let mut gate = make_gate(
@@ -432,6 +770,9 @@ mod tests {
                 op: CriterionOp::Gte,
                 value: serde_json::json!(10),
                 window_seconds: None,
+                exit: None,
+                ci: false,
+                max_age_seconds: None,
             }],
         );
         gate.enforcement = GateEnforcement::Observe;
@@ -487,6 +828,9 @@ mod tests {
                     metric: "tasks_completed".into(),
                     op: CriterionOp::Gte,
                     window_seconds: None,
+                    exit: None,
+                    ci: false,
+                    max_age_seconds: None,
                     value: serde_json::json!(20),
                 }],
             ),
@@ -500,6 +844,9 @@ mod tests {
                         metric: "policy_violations".into(),
                         op: CriterionOp::Gte,
                         window_seconds: None,
+                        exit: None,
+                        ci: false,
+                        max_age_seconds: None,
                         value: serde_json::json!(3),
                     }],
                 );
@@ -561,6 +908,9 @@ mod tests {
                 op: CriterionOp::Gte,
                 value: serde_json::json!(100),
                 window_seconds: None,
+                exit: None,
+                ci: false,
+                max_age_seconds: None,
             }],
         )];
 
@@ -594,6 +944,9 @@ mod tests {
             metric: "temperature".into(),
             op: CriterionOp::Gte,
             window_seconds: None,
+            exit: None,
+            ci: false,
+            max_age_seconds: None,
             value: serde_json::json!(100),
         }];
         let mut schema = HashMap::new();
@@ -624,6 +977,9 @@ mod tests {
             metric: "temperature".into(),
             op: CriterionOp::Gte,
             window_seconds: None,
+            exit: None,
+            ci: false,
+            max_age_seconds: None,
             value: serde_json::json!(100),
         }];
         let mut schema = HashMap::new();
@@ -654,6 +1010,9 @@ mod tests {
             metric: "score".into(),
             op: CriterionOp::Gte,
             window_seconds: None,
+            exit: None,
+            ci: false,
+            max_age_seconds: None,
             value: serde_json::json!(10),
         }];
         let mut schema = HashMap::new();
@@ -673,6 +1032,285 @@ mod tests {
         assert!(results[0].type_mismatch.is_none());
     }
 
+    fn membership(op: CriterionOp) -> Vec<Criterion> {
+        vec![Criterion {
+            metric: "phase_tag".into(),
+            op,
+            window_seconds: None,
+            exit: None,
+            ci: false,
+            max_age_seconds: None,
+            value: serde_json::json!(["canary", "beta", "stable"]),
+        }]
+    }
+
+    #[test]
+    fn in_passes_when_metric_is_listed() {
+        let evaluator = DefaultGateEvaluator;
+        let mut metrics_map = HashMap::new();
+        metrics_map.insert("phase_tag".into(), serde_json::json!("beta"));
+        let metrics = TestMetrics(metrics_map);
+
+        let (all_pass, _, _) = evaluator.evaluate_criteria(
+            &membership(CriterionOp::In),
+            &metrics,
+            GateDirection::Promote,
+            None,
+        );
+        assert!(all_pass);
+        let (all_pass, _, _) = evaluator.evaluate_criteria(
+            &membership(CriterionOp::NotIn),
+            &metrics,
+            GateDirection::Promote,
+            None,
+        );
+        assert!(!all_pass);
+    }
+
+    #[test]
+    fn in_fails_when_metric_is_not_listed() {
+        let evaluator = DefaultGateEvaluator;
+        let mut metrics_map = HashMap::new();
+        metrics_map.insert("phase_tag".into(), serde_json::json!("alpha"));
+        let metrics = TestMetrics(metrics_map);
+
+        let (all_pass, _, _) = evaluator.evaluate_criteria(
+            &membership(CriterionOp::In),
+            &metrics,
+            GateDirection::Promote,
+            None,
+        );
+        assert!(!all_pass);
+        let (all_pass, _, _) = evaluator.evaluate_criteria(
+            &membership(CriterionOp::NotIn),
+            &metrics,
+            GateDirection::Promote,
+            None,
+        );
+        assert!(all_pass);
+
+        // Numbers match by value, strings never match numbers
+        assert!(compare_values(
+            &CriterionOp::In,
+            false,
+            &serde_json::json!(2.0),
+            &serde_json::json!([1, 2])
+        ));
+        assert!(!compare_values(
+            &CriterionOp::In,
+            false,
+            &serde_json::json!("2"),
+            &serde_json::json!([1, 2])
+        ));
+    }
+
+    #[test]
+    fn membership_with_missing_metric_fails_closed() {
+        // A missing metric never satisfies in or not_in: promote stays blocked
+        // and demote does not fire on absent data.
+        let evaluator = DefaultGateEvaluator;
+        let metrics = TestMetrics(HashMap::new());
+        for op in [CriterionOp::In, CriterionOp::NotIn] {
+            for direction in [GateDirection::Promote, GateDirection::Demote] {
+                let (all_pass, results, _) =
+                    evaluator.evaluate_criteria(&membership(op), &metrics, direction, None);
+                assert!(!all_pass, "{op:?} {direction:?} must not pass");
+                assert_eq!(results[0].actual, serde_json::Value::Null);
+            }
+        }
+        // A non-array threshold fails closed as well
+        assert!(!compare_values(
+            &CriterionOp::NotIn,
+            false,
+            &serde_json::json!("beta"),
+            &serde_json::json!("beta")
+        ));
+    }
+
+    fn last_error(op: CriterionOp, ci: bool) -> Vec<Criterion> {
+        vec![Criterion {
+            metric: "last_error".into(),
+            op,
+            window_seconds: None,
+            exit: None,
+            ci,
+            max_age_seconds: None,
+            value: serde_json::json!("panic"),
+        }]
+    }
+
+    fn passes(criteria: &[Criterion], actual: serde_json::Value) -> bool {
+        let mut metrics_map = HashMap::new();
+        metrics_map.insert("last_error".into(), actual);
+        let metrics = TestMetrics(metrics_map);
+        DefaultGateEvaluator
+            .evaluate_criteria(criteria, &metrics, GateDirection::Promote, None)
+            .0
+    }
+
+    #[test]
+    fn contains_matches_substring() {
+        let hit = serde_json::json!("thread 'main' panicked at src/lib.rs");
+        assert!(passes(
+            &last_error(CriterionOp::Contains, false),
+            hit.clone()
+        ));
+        assert!(!passes(&last_error(CriterionOp::NotContains, false), hit));
+    }
+
+    #[test]
+    fn contains_misses_absent_substring() {
+        let miss = serde_json::json!("connection reset by peer");
+        assert!(!passes(
+            &last_error(CriterionOp::Contains, false),
+            miss.clone()
+        ));
+        assert!(passes(&last_error(CriterionOp::NotContains, false), miss));
+    }
+
+    #[test]
+    fn contains_is_case_sensitive_unless_ci() {
+        let upper = serde_json::json!("PANIC: out of memory");
+        assert!(!passes(
+            &last_error(CriterionOp::Contains, false),
+            upper.clone()
+        ));
+        assert!(passes(
+            &last_error(CriterionOp::Contains, true),
+            upper.clone()
+        ));
+        assert!(!passes(&last_error(CriterionOp::NotContains, true), upper));
+    }
+
+    #[test]
+    fn contains_fails_closed_on_non_string_metric() {
+        assert!(!passes(
+            &last_error(CriterionOp::Contains, false),
+            serde_json::json!(0)
+        ));
+        assert!(!passes(
+            &last_error(CriterionOp::NotContains, false),
+            serde_json::json!(0)
+        ));
+    }
+
+    #[test]
+    fn ratio_criterion_fails_closed_on_zero_denominator() {
+        let evaluator = DefaultGateEvaluator;
+        let criteria = vec![Criterion {
+            metric: "ratio(errors, requests)".into(),
+            op: CriterionOp::Lt,
+            window_seconds: None,
+            exit: None,
+            ci: false,
+            max_age_seconds: None,
+            value: serde_json::json!(0.01),
+        }];
+        let mut metrics_map = HashMap::new();
+        metrics_map.insert("errors".into(), serde_json::json!(0));
+        metrics_map.insert("requests".into(), serde_json::json!(200));
+        let (all_pass, results, snapshot) = evaluator.evaluate_criteria(
+            &criteria,
+            &TestMetrics(metrics_map.clone()),
+            GateDirection::Promote,
+            None,
+        );
+        assert!(all_pass);
+        assert_eq!(results[0].actual, serde_json::json!(0.0));
+        assert!(snapshot.contains_key("ratio(errors, requests)"));
+
+        metrics_map.insert("requests".into(), serde_json::json!(0));
+        for direction in [GateDirection::Promote, GateDirection::Demote] {
+            let (all_pass, results, _) = evaluator.evaluate_criteria(
+                &criteria,
+                &TestMetrics(metrics_map.clone()),
+                direction,
+                None,
+            );
+            assert!(!all_pass, "0/0 must not pass for {direction:?}");
+            assert_eq!(results[0].actual, serde_json::Value::Null);
+        }
+    }
+
+    fn sampled(age_seconds: i64) -> crate::gates::metrics::JsonMetrics {
+        let ts = Utc::now() - Duration::seconds(age_seconds);
+        crate::gates::metrics::JsonMetrics(serde_json::json!({
+            "score": { "value": 10, "ts": ts.to_rfc3339() }
+        }))
+    }
+
+    fn fresh_within(max_age_seconds: Option<u64>) -> Vec<Criterion> {
+        vec![Criterion {
+            metric: "score".into(),
+            op: CriterionOp::Gte,
+            window_seconds: None,
+            exit: None,
+            ci: false,
+            max_age_seconds,
+            value: serde_json::json!(5),
+        }]
+    }
+
+    #[test]
+    fn fresh_sample_passes_normally() {
+        let evaluator = DefaultGateEvaluator;
+        let (all_pass, results, _) = evaluator.evaluate_criteria(
+            &fresh_within(Some(600)),
+            &sampled(60),
+            GateDirection::Promote,
+            None,
+        );
+        assert!(all_pass);
+        assert!(!results[0].stale);
+    }
+
+    #[test]
+    fn stale_sample_fails_closed() {
+        let evaluator = DefaultGateEvaluator;
+        let criteria = fresh_within(Some(600));
+        let (all_pass, results, _) =
+            evaluator.evaluate_criteria(&criteria, &sampled(3600), GateDirection::Promote, None);
+        assert!(!all_pass, "stale green metric must not promote");
+        assert!(results[0].stale);
+        assert_eq!(results[0].actual, serde_json::json!(10));
+
+        let (all_pass, results, _) =
+            evaluator.evaluate_criteria(&criteria, &sampled(3600), GateDirection::Demote, None);
+        assert!(all_pass, "stale metric must let demote fire");
+        assert!(results[0].stale);
+
+        // No limit, no staleness
+        let (all_pass, _, _) = evaluator.evaluate_criteria(
+            &fresh_within(None),
+            &sampled(3600),
+            GateDirection::Promote,
+            None,
+        );
+        assert!(all_pass);
+    }
+
+    #[test]
+    fn gate_max_age_applies_unless_criterion_overrides() {
+        let mut gate = passing_promote("promote", 0, None);
+        gate.criteria = fresh_within(None);
+        gate.max_age_seconds = Some(600);
+        let mut state = PhaseState::new("test".into());
+        state.current_phase = Some("active".into());
+        let evaluator = DefaultGateEvaluator;
+
+        assert!(evaluator
+            .evaluate(std::slice::from_ref(&gate), &state, &sampled(3600))
+            .is_none());
+        assert!(evaluator
+            .evaluate(std::slice::from_ref(&gate), &state, &sampled(60))
+            .is_some());
+
+        gate.criteria = fresh_within(Some(7200));
+        assert!(evaluator
+            .evaluate(&[gate], &state, &sampled(3600))
+            .is_some());
+    }
+
     #[test]
     fn window_seconds_passed_to_metric_query() {
         // Verify that criterion.window_seconds propagates to MetricQuery.window
@@ -697,6 +1335,9 @@ mod tests {
             metric: "sop.completion_rate".into(),
             op: CriterionOp::Gte,
             window_seconds: Some(604800),
+            exit: None,
+            ci: false,
+            max_age_seconds: None,
             value: serde_json::json!(0.9),
         }];
         let capture = WindowCapture(std::sync::Mutex::new(None));
@@ -709,6 +1350,9 @@ mod tests {
             metric: "sop.completion_rate".into(),
             op: CriterionOp::Gte,
             window_seconds: None,
+            exit: None,
+            ci: false,
+            max_age_seconds: None,
             value: serde_json::json!(0.9),
         }];
         let capture2 = WindowCapture(std::sync::Mutex::new(None));
@@ -729,6 +1373,9 @@ mod tests {
                 metric: "score".into(),
                 op: CriterionOp::Gte,
                 window_seconds: None,
+                exit: None,
+                ci: false,
+                max_age_seconds: None,
                 value: serde_json::json!(10),
             }],
         )];
@@ -801,4 +1448,185 @@ mod tests {
         let result3 = evaluator.evaluate(&gates, &state3, &metrics);
         assert!(result3.is_some(), "different state_rev must re-evaluate");
     }
+
+    fn repeated_state(gates: &[Gate], metrics: &TestMetrics) -> PhaseState {
+        let mut state = PhaseState::new("test".into());
+        state.current_phase = Some("active".into());
+        state.state_rev = 1;
+        let fired = DefaultGateEvaluator
+            .evaluate(gates, &state, metrics)
+            .unwrap();
+        state.last_transition = Some(TransitionRecord {
+            gate_id: fired.gate_id,
+            from_phase: Some("active".into()),
+            to_phase: "trusted".into(),
+            at: Utc::now(),
+            decision_id: "gate-1".into(),
+            metrics_hash: Some(fired.metrics_hash),
+            state_rev: 1,
+        });
+        state
+    }
+
+    #[test]
+    fn reevaluate_bypasses_idempotency_skip() {
+        let gates = vec![passing_promote("promote", 0, None)];
+        let metrics = TestMetrics(HashMap::from([("score".into(), serde_json::json!(5))]));
+        let state = repeated_state(&gates, &metrics);
+        let evaluator = DefaultGateEvaluator;
+
+        assert!(evaluator.evaluate(&gates, &state, &metrics).is_none());
+        let forced = evaluator.reevaluate(&gates, &state, &metrics).unwrap();
+        assert_eq!(forced.gate_id, "promote");
+        assert!(forced.forced_reeval);
+
+        // Without a repeat, reevaluate is an ordinary evaluation
+        let mut fresh = state.clone();
+        fresh.last_transition = None;
+        assert!(
+            !evaluator
+                .reevaluate(&gates, &fresh, &metrics)
+                .unwrap()
+                .forced_reeval
+        );
+    }
+
+    #[test]
+    fn reevaluate_still_honors_cooldown() {
+        let mut gate = passing_promote("promote", 0, None);
+        gate.cooldown_seconds = 3600;
+        let gates = vec![gate];
+        let metrics = TestMetrics(HashMap::from([("score".into(), serde_json::json!(5))]));
+        let state = repeated_state(&gates, &metrics);
+
+        assert!(DefaultGateEvaluator
+            .reevaluate(&gates, &state, &metrics)
+            .is_none());
+    }
+
+    #[test]
+    fn hysteresis_band_prevents_oscillation() {
+        // Demote at violations >= 3 (enter), hold re-promotion until violations < 1 (exit)
+        let mut demote = make_gate(
+            "trust_decay",
+            GateDirection::Demote,
+            "trusted",
+            "active",
+            vec![Criterion {
+                metric: "violations".into(),
+                op: CriterionOp::Gte,
+                value: serde_json::json!(3),
+                window_seconds: None,
+                exit: Some(serde_json::json!(1)),
+                ci: false,
+                max_age_seconds: None,
+            }],
+        );
+        demote.priority = 20;
+        let promote = make_gate(
+            "promote",
+            GateDirection::Promote,
+            "active",
+            "trusted",
+            vec![Criterion {
+                metric: "violations".into(),
+                op: CriterionOp::Lt,
+                value: serde_json::json!(3),
+                window_seconds: None,
+                exit: None,
+                ci: false,
+                max_age_seconds: None,
+            }],
+        );
+        let gates = vec![demote, promote];
+        let evaluator = DefaultGateEvaluator;
+
+        let mut state = PhaseState::new("test".into());
+        state.current_phase = Some("trusted".into());
+
+        let mut transitions = 0;
+        for v in [3, 2, 3, 2, 3, 2] {
+            let metrics = TestMetrics(HashMap::from([(
+                "violations".to_string(),
+                serde_json::json!(v),
+            )]));
+            if let Some(record) = evaluator.evaluate(&gates, &state, &metrics) {
+                transitions += 1;
+                state.state_rev += 1;
+                state.last_transition = Some(TransitionRecord {
+                    gate_id: record.gate_id.clone(),
+                    from_phase: record.from_phase.clone(),
+                    to_phase: record.to_phase.clone(),
+                    at: Utc::now(),
+                    decision_id: format!("gate-{}", state.state_rev),
+                    metrics_hash: Some(record.metrics_hash),
+                    state_rev: state.state_rev,
+                });
+                state.current_phase = Some(record.to_phase);
+            }
+        }
+        assert_eq!(transitions, 1, "only the first demotion should fire");
+        assert_eq!(state.current_phase.as_deref(), Some("active"));
+
+        // Crossing the exit threshold releases the hold
+        let metrics = TestMetrics(HashMap::from([(
+            "violations".to_string(),
+            serde_json::json!(0),
+        )]));
+        let record = evaluator.evaluate(&gates, &state, &metrics).unwrap();
+        assert_eq!(record.gate_id, "promote");
+    }
+
+    #[test]
+    fn single_threshold_criteria_unaffected_by_hysteresis() {
+        let demote = make_gate(
+            "trust_decay",
+            GateDirection::Demote,
+            "trusted",
+            "active",
+            vec![Criterion {
+                metric: "violations".into(),
+                op: CriterionOp::Gte,
+                value: serde_json::json!(3),
+                window_seconds: None,
+                exit: None,
+                ci: false,
+                max_age_seconds: None,
+            }],
+        );
+        let promote = make_gate(
+            "promote",
+            GateDirection::Promote,
+            "active",
+            "trusted",
+            vec![Criterion {
+                metric: "violations".into(),
+                op: CriterionOp::Lt,
+                value: serde_json::json!(3),
+                window_seconds: None,
+                exit: None,
+                ci: false,
+                max_age_seconds: None,
+            }],
+        );
+        let mut state = PhaseState::new("test".into());
+        state.current_phase = Some("active".into());
+        state.last_transition = Some(TransitionRecord {
+            gate_id: "trust_decay".into(),
+            from_phase: Some("trusted".into()),
+            to_phase: "active".into(),
+            at: Utc::now(),
+            decision_id: "gate-1".into(),
+            metrics_hash: None,
+            state_rev: 1,
+        });
+        let metrics = TestMetrics(HashMap::from([(
+            "violations".to_string(),
+            serde_json::json!(2),
+        )]));
+        let record = DefaultGateEvaluator
+            .evaluate(&[demote, promote], &state, &metrics)
+            .unwrap();
+        assert_eq!(record.gate_id, "promote");
+    }
 }
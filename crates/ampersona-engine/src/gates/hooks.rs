@@ -0,0 +1,135 @@
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+
+/// Outcome of one gate `exec` hook, recorded as a `HookResult` audit event.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct HookOutcome {
+    pub command: Vec<String>,
+    /// Exit status; `None` if the command did not start or was killed by a signal.
+    pub exit_code: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl HookOutcome {
+    pub fn succeeded(&self) -> bool {
+        self.exit_code == Some(0)
+    }
+}
+
+/// Interval between checks on a running hook.
+const POLL_INTERVAL: Duration = Duration::from_millis(25);
+
+/// Run `argv` directly (no shell) with `input` as JSON on stdin and wait for it,
+/// killing it once it has run for `timeout`.
+///
+/// The hook's stdout is sent to stderr so it cannot corrupt `--json` output.
+/// Failures are reported in the outcome, never as an error: a hook must not
+/// undo or block the decision that triggered it.
+pub fn run_hook(argv: &[String], input: &serde_json::Value, timeout: Duration) -> HookOutcome {
+    let failed = |error: String| HookOutcome {
+        command: argv.to_vec(),
+        exit_code: None,
+        error: Some(error),
+    };
+    let Some((program, args)) = argv.split_first() else {
+        return failed("empty exec command".into());
+    };
+    let mut child = match Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(std::io::stderr())
+        .spawn()
+    {
+        Ok(child) => child,
+        Err(e) => return failed(format!("cannot start {program}: {e}")),
+    };
+    if let Some(mut stdin) = child.stdin.take() {
+        // Written from a thread so a hook that never reads can't block us past
+        // the timeout; one that closes stdin early is fine.
+        let mut line = input.to_string().into_bytes();
+        line.push(b'\n');
+        std::thread::spawn(move || {
+            let _ = stdin.write_all(&line);
+        });
+    }
+    let deadline = Instant::now() + timeout;
+    loop {
+        match child.try_wait() {
+            Ok(Some(status)) => {
+                return HookOutcome {
+                    command: argv.to_vec(),
+                    exit_code: status.code(),
+                    error: None,
+                }
+            }
+            Ok(None) if Instant::now() >= deadline => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return failed(format!(
+                    "{program} timed out after {}s and was killed",
+                    timeout.as_secs_f64()
+                ));
+            }
+            Ok(None) => std::thread::sleep(POLL_INTERVAL),
+            Err(e) => return failed(format!("waiting for {program}: {e}")),
+        }
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    fn argv(args: &[&str]) -> Vec<String> {
+        args.iter().map(|a| a.to_string()).collect()
+    }
+
+    #[test]
+    fn hook_reads_input_and_reports_status() {
+        let dir = tempfile::tempdir().unwrap();
+        let out = dir.path().join("hook.json");
+        let script = format!("cat > '{}'; exit 3", out.display());
+        let outcome = run_hook(
+            &argv(&["sh", "-c", &script]),
+            &serde_json::json!({"gate_id": "g"}),
+            Duration::from_secs(10),
+        );
+        assert_eq!(outcome.exit_code, Some(3));
+        assert!(!outcome.succeeded());
+        let written: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(out).unwrap()).unwrap();
+        assert_eq!(written["gate_id"], "g");
+    }
+
+    #[test]
+    fn missing_program_is_reported_not_raised() {
+        let timeout = Duration::from_secs(10);
+        let outcome = run_hook(
+            &argv(&["/nonexistent/hook"]),
+            &serde_json::json!({}),
+            timeout,
+        );
+        assert_eq!(outcome.exit_code, None);
+        assert!(outcome.error.unwrap().contains("cannot start"));
+        assert!(run_hook(&[], &serde_json::json!({}), timeout)
+            .error
+            .is_some());
+    }
+
+    #[test]
+    fn hung_hook_is_killed_at_timeout() {
+        let started = Instant::now();
+        let outcome = run_hook(
+            &argv(&["sleep", "30"]),
+            &serde_json::json!({}),
+            Duration::from_millis(200),
+        );
+        assert!(started.elapsed() < Duration::from_secs(10));
+        assert_eq!(outcome.exit_code, None);
+        assert!(outcome.error.unwrap().contains("timed out"));
+    }
+}
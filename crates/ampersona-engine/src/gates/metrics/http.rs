@@ -0,0 +1,86 @@
+//! Plain-HTTP metrics sources: a metrics document served over `http://`.
+
+use super::{validate, JsonMetrics};
+use crate::error::{EngineError, Result};
+
+/// Fetch a metrics document once and check it like a metrics file.
+pub(super) fn fetch(url: &str) -> Result<JsonMetrics> {
+    let body = get(url)?;
+    let metrics: serde_json::Value =
+        serde_json::from_slice(&body).map_err(|source| EngineError::InvalidJson {
+            path: url.to_string(),
+            source,
+        })?;
+    let issues = validate(&metrics);
+    if !issues.is_empty() {
+        return Err(EngineError::InvalidMetrics {
+            path: url.to_string(),
+            issues,
+        });
+    }
+    Ok(JsonMetrics(metrics))
+}
+
+/// GET `url` over HTTP/1.0 and return the body of a 200 response.
+pub(super) fn get(url: &str) -> Result<Vec<u8>> {
+    let source = |reason: String| EngineError::MetricsSource(format!("{url}: {reason}"));
+    let response =
+        crate::http::request("GET", url, &[("Accept", "application/json")], &[]).map_err(source)?;
+    if response.status != 200 {
+        return Err(source(response.rejection()));
+    }
+    Ok(response.body)
+}
+
+#[cfg(test)]
+pub(super) mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+
+    /// Serve one canned HTTP response per accepted connection; returns the
+    /// base URL and a handle yielding the raw requests received.
+    pub(in crate::gates::metrics) fn serve(
+        responses: Vec<(u16, String)>,
+    ) -> (String, std::thread::JoinHandle<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        let handle = std::thread::spawn(move || {
+            let mut requests = Vec::new();
+            for (status, body) in responses {
+                let (mut conn, _) = listener.accept().unwrap();
+                let mut buf = [0u8; 4096];
+                let n = conn.read(&mut buf).unwrap();
+                requests.push(String::from_utf8_lossy(&buf[..n]).into_owned());
+                let reply =
+                    format!("HTTP/1.0 {status} X\r\nContent-Type: application/json\r\n\r\n{body}");
+                conn.write_all(reply.as_bytes()).unwrap();
+            }
+            requests
+        });
+        (base, handle)
+    }
+
+    #[test]
+    fn fetches_and_validates_document() {
+        let (base, server) = serve(vec![
+            (200, r#"{"tasks_completed": 25}"#.into()),
+            (200, r#"{"stats": {"nested": 1}}"#.into()),
+            (503, String::new()),
+        ]);
+        let url = format!("{base}/metrics.json");
+
+        let metrics = fetch(&url).unwrap();
+        assert_eq!(metrics.0["tasks_completed"], 25);
+        assert!(matches!(
+            fetch(&url),
+            Err(EngineError::InvalidMetrics { .. })
+        ));
+        let err = fetch(&url).err().unwrap();
+        assert_eq!(err.code(), "E_METRICS_SOURCE");
+        assert!(err.to_string().contains("503"));
+
+        let requests = server.join().unwrap();
+        assert!(requests[0].starts_with("GET /metrics.json HTTP/1.0"));
+    }
+}
@@ -0,0 +1,469 @@
+use ampersona_core::errors::MetricError;
+use ampersona_core::spec::gates::{DerivedMetric, Gate};
+use ampersona_core::traits::{MetricQuery, MetricSample, MetricsProvider};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::error::{EngineError, Result};
+
+#[cfg(feature = "metrics-http")]
+mod http;
+#[cfg(feature = "metrics-prom")]
+mod prom;
+
+/// A metrics key whose value has an unsupported shape.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MetricsIssue {
+    /// Offending metric name (empty for a non-object document).
+    pub key: String,
+    pub message: String,
+}
+
+/// Check that a metrics document is a flat map of name → value.
+///
+/// Accepted values are numbers, booleans, strings, a single timestamped sample
+/// `{"value": scalar, "ts": RFC 3339}`, and timeseries: an array whose items are
+/// all scalars or all `{"ts": string, "value": scalar}` samples. Anything else —
+/// other objects, nulls, nested arrays — is reported, since the evaluator would
+/// otherwise surface it only as a missing metric.
+pub fn validate(metrics: &Value) -> Vec<MetricsIssue> {
+    let Some(obj) = metrics.as_object() else {
+        return vec![MetricsIssue {
+            key: String::new(),
+            message: format!("metrics must be a JSON object, got {}", kind(metrics)),
+        }];
+    };
+
+    obj.iter()
+        .filter_map(|(key, value)| {
+            check_value(value).map(|message| MetricsIssue {
+                key: key.clone(),
+                message,
+            })
+        })
+        .collect()
+}
+
+/// Read and parse a metrics file, rejecting documents that fail [`validate`].
+pub fn load(path: &str) -> Result<Value> {
+    let content = std::fs::read_to_string(path).map_err(|e| EngineError::read(path, e))?;
+    let metrics: Value =
+        serde_json::from_str(&content).map_err(|source| EngineError::InvalidJson {
+            path: path.to_string(),
+            source,
+        })?;
+    let issues = validate(&metrics);
+    if !issues.is_empty() {
+        return Err(EngineError::InvalidMetrics {
+            path: path.to_string(),
+            issues,
+        });
+    }
+    Ok(metrics)
+}
+
+/// Open the metrics source named by `uri`.
+///
+/// | URI | Source |
+/// |-----|--------|
+/// | `path/to/metrics.json`, `file://path` | metrics file, checked by [`load`] |
+/// | `http://host[:port]/path` | metrics document fetched once (`metrics-http` feature) |
+/// | `prom://host[:port][/prefix]` | Prometheus instant queries (`metrics-prom` feature) |
+///
+/// Adding a source is one match arm here plus its provider module.
+pub fn provider_for(uri: &str) -> Result<Box<dyn MetricsProvider>> {
+    let Some((scheme, rest)) = uri.split_once("://") else {
+        return Ok(Box::new(JsonMetrics(load(uri)?)));
+    };
+    match scheme {
+        "file" => Ok(Box::new(JsonMetrics(load(rest)?))),
+        #[cfg(feature = "metrics-http")]
+        "http" => Ok(Box::new(http::fetch(uri)?)),
+        #[cfg(feature = "metrics-prom")]
+        "prom" => Ok(Box::new(prom::PromMetrics::new(rest))),
+        #[cfg(not(feature = "metrics-http"))]
+        "http" => Err(unsupported(uri, "requires the `metrics-http` feature")),
+        #[cfg(not(feature = "metrics-prom"))]
+        "prom" => Err(unsupported(uri, "requires the `metrics-prom` feature")),
+        "https" => Err(unsupported(uri, "https is not supported; use http://")),
+        other => Err(unsupported(
+            uri,
+            &format!("unknown scheme '{other}' (expected file, http or prom)"),
+        )),
+    }
+}
+
+fn unsupported(uri: &str, reason: &str) -> EngineError {
+    EngineError::MetricsSource(format!("{uri}: {reason}"))
+}
+
+/// Metrics provider over a parsed metrics document.
+///
+/// A `{"value", "ts"}` sample supplies its own `sampled_at`, which criteria
+/// with `max_age_seconds` check for freshness; bare values count as sampled
+/// now.
+pub struct JsonMetrics(pub Value);
+
+impl MetricsProvider for JsonMetrics {
+    fn get_metric(&self, query: &MetricQuery) -> std::result::Result<MetricSample, MetricError> {
+        let raw = self
+            .0
+            .get(&query.name)
+            .ok_or_else(|| MetricError::NotFound(query.name.clone()))?;
+        let (value, sampled_at) = match sample_ts(raw) {
+            Some(ts) => (raw["value"].clone(), ts),
+            None => (raw.clone(), Utc::now()),
+        };
+        Ok(MetricSample {
+            name: query.name.clone(),
+            value,
+            sampled_at,
+        })
+    }
+}
+
+/// Timestamp of a single `{"value", "ts"}` sample.
+fn sample_ts(value: &Value) -> Option<DateTime<Utc>> {
+    if !is_sample(value) {
+        return None;
+    }
+    DateTime::parse_from_rfc3339(value["ts"].as_str()?)
+        .ok()
+        .map(|ts| ts.with_timezone(&Utc))
+}
+
+/// One metric a persona's gates read, with a placeholder of the expected type.
+#[derive(Debug, Clone, Serialize)]
+pub struct MetricTemplate {
+    pub name: String,
+    /// `boolean`, `integer`, `number` or `string`.
+    #[serde(rename = "type")]
+    pub metric_type: String,
+    pub placeholder: Value,
+    /// Gate criteria referencing this metric, as `<gate-id>: <op> <value>`.
+    pub used_by: Vec<String>,
+}
+
+/// Collect every metric referenced by gate criteria, in first-seen order.
+///
+/// The type comes from the gate's `metrics_schema` when declared, otherwise it
+/// is inferred from the criterion's threshold value.
+pub fn template(gates: &[Gate]) -> Vec<MetricTemplate> {
+    let mut out: Vec<MetricTemplate> = Vec::new();
+    for gate in gates {
+        for c in &gate.criteria {
+            let ci = if c.ci { " (ci)" } else { "" };
+            // Derived metrics are computed from numeric operands, which are
+            // what the metrics file has to provide.
+            let (names, usage, derived) = match DerivedMetric::parse(&c.metric) {
+                Ok(Some(derived)) => (
+                    derived.operands,
+                    format!(
+                        "{}: {} {} {}{ci}",
+                        gate.id,
+                        c.metric,
+                        op_str(&c.op),
+                        c.value
+                    ),
+                    true,
+                ),
+                _ => (
+                    vec![c.metric.clone()],
+                    format!("{}: {} {}{ci}", gate.id, op_str(&c.op), c.value),
+                    false,
+                ),
+            };
+            for name in names {
+                if let Some(existing) = out.iter_mut().find(|m| m.name == name) {
+                    existing.used_by.push(usage.clone());
+                    continue;
+                }
+                let metric_type = gate
+                    .metrics_schema
+                    .as_ref()
+                    .and_then(|s| s.get(&name))
+                    .map(|s| s.metric_type.clone())
+                    .unwrap_or_else(|| {
+                        if derived {
+                            "number".to_string()
+                        } else {
+                            infer_type(&c.value).to_string()
+                        }
+                    });
+                out.push(MetricTemplate {
+                    name,
+                    placeholder: placeholder(&metric_type),
+                    metric_type,
+                    used_by: vec![usage.clone()],
+                });
+            }
+        }
+    }
+    out
+}
+
+fn infer_type(value: &Value) -> &'static str {
+    match value {
+        Value::Bool(_) => "boolean",
+        Value::String(_) => "string",
+        Value::Number(n) if n.is_f64() => "number",
+        Value::Number(_) => "integer",
+        // `in` / `not_in` thresholds: the metric has the type of the items
+        Value::Array(items) => items.first().map_or("string", infer_type),
+        _ => "number",
+    }
+}
+
+fn placeholder(metric_type: &str) -> Value {
+    match metric_type {
+        "boolean" => Value::Bool(false),
+        "integer" => serde_json::json!(0),
+        "string" => Value::String(String::new()),
+        _ => serde_json::json!(0.0),
+    }
+}
+
+fn op_str(op: &ampersona_core::types::CriterionOp) -> String {
+    serde_json::to_value(op)
+        .ok()
+        .and_then(|v| v.as_str().map(str::to_string))
+        .unwrap_or_default()
+}
+
+fn check_value(value: &Value) -> Option<String> {
+    match value {
+        Value::Number(_) | Value::Bool(_) | Value::String(_) => None,
+        Value::Null => Some("null is not a metric value".into()),
+        Value::Object(_) if is_sample(value) => sample_ts(value)
+            .is_none()
+            .then(|| "sample ts must be an RFC 3339 timestamp".into()),
+        Value::Object(_) => {
+            Some("unexpected nesting: only {\"value\", \"ts\"} samples may be objects".into())
+        }
+        Value::Array(items) => {
+            if items.iter().all(is_scalar) || items.iter().all(is_sample) {
+                None
+            } else {
+                Some(
+                    "timeseries items must all be scalars or all {\"ts\", \"value\"} samples"
+                        .into(),
+                )
+            }
+        }
+    }
+}
+
+fn is_scalar(value: &Value) -> bool {
+    matches!(value, Value::Number(_) | Value::Bool(_) | Value::String(_))
+}
+
+fn is_sample(value: &Value) -> bool {
+    value.as_object().is_some_and(|o| {
+        o.len() == 2
+            && o.get("ts").is_some_and(Value::is_string)
+            && o.get("value").is_some_and(is_scalar)
+    })
+}
+
+fn kind(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn flat_metrics_are_valid() {
+        let metrics = json!({
+            "tasks_completed": 12,
+            "success_rate": 0.97,
+            "onboarded": true,
+            "tier": "gold",
+            "latency_ms": [120, 98, 143],
+            "errors": [{"ts": "2026-01-01T00:00:00Z", "value": 0}],
+        });
+        assert!(validate(&metrics).is_empty());
+    }
+
+    #[test]
+    fn nested_and_null_values_are_reported() {
+        let metrics = json!({
+            "ok": 1,
+            "stats": {"tasks_completed": 12},
+            "missing": null,
+            "mixed": [1, {"ts": "2026-01-01T00:00:00Z", "value": 2}],
+        });
+        let keys: Vec<_> = validate(&metrics).into_iter().map(|i| i.key).collect();
+        assert_eq!(keys, vec!["stats", "missing", "mixed"]);
+    }
+
+    #[test]
+    fn template_types_numeric_boolean_and_string() {
+        use ampersona_core::spec::gates::{Criterion, MetricSchema};
+        use ampersona_core::types::{CriterionOp, GateApproval, GateDirection, GateEnforcement};
+        use std::collections::HashMap;
+
+        let criterion = |metric: &str, value: Value| Criterion {
+            metric: metric.into(),
+            op: CriterionOp::Eq,
+            value,
+            window_seconds: None,
+            exit: None,
+            ci: false,
+            max_age_seconds: None,
+        };
+        let gate = |id: &str, criteria, schema| Gate {
+            id: id.into(),
+            direction: GateDirection::Promote,
+            enforcement: GateEnforcement::Enforce,
+            priority: 0,
+            order: None,
+            max_age_seconds: None,
+            cooldown_seconds: 0,
+            from_phase: None,
+            to_phase: "active".into(),
+            criteria,
+            metrics_schema: schema,
+            approval: GateApproval::Auto,
+            on_pass: None,
+            on_fail: None,
+        };
+        let gates = vec![
+            gate(
+                "a",
+                vec![
+                    criterion("tasks", json!(10)),
+                    criterion("ratio", json!(0)),
+                    criterion("tier", json!("gold")),
+                ],
+                Some(HashMap::from([(
+                    "ratio".to_string(),
+                    MetricSchema {
+                        metric_type: "number".into(),
+                    },
+                )])),
+            ),
+            gate(
+                "b",
+                vec![
+                    criterion("onboarded", json!(true)),
+                    criterion("tasks", json!(20)),
+                    criterion("ratio(errors, tasks)", json!(0.01)),
+                ],
+                None,
+            ),
+        ];
+
+        let t = template(&gates);
+        let types: Vec<_> = t
+            .iter()
+            .map(|m| (m.name.as_str(), m.metric_type.as_str()))
+            .collect();
+        assert_eq!(
+            types,
+            vec![
+                ("tasks", "integer"),
+                ("ratio", "number"),
+                ("tier", "string"),
+                ("onboarded", "boolean"),
+                ("errors", "number"),
+            ]
+        );
+        assert_eq!(
+            t[0].used_by,
+            vec!["a: eq 10", "b: eq 20", "b: ratio(errors, tasks) eq 0.01"]
+        );
+        assert_eq!(t[3].placeholder, json!(false));
+        assert_eq!(t[2].placeholder, json!(""));
+
+        // The scaffold passes validation as-is.
+        let doc: serde_json::Map<_, _> = t.into_iter().map(|m| (m.name, m.placeholder)).collect();
+        assert!(validate(&Value::Object(doc)).is_empty());
+    }
+
+    #[test]
+    fn timestamped_samples_supply_sampled_at() {
+        let metrics = json!({
+            "score": {"value": 7, "ts": "2026-01-01T00:00:00Z"},
+            "plain": 3,
+        });
+        assert!(validate(&metrics).is_empty());
+
+        let provider = JsonMetrics(metrics);
+        let query = |name: &str| MetricQuery {
+            name: name.into(),
+            window: None,
+        };
+        let sample = provider.get_metric(&query("score")).unwrap();
+        assert_eq!(sample.value, json!(7));
+        assert_eq!(sample.sampled_at.to_rfc3339(), "2026-01-01T00:00:00+00:00");
+        assert_eq!(
+            provider.get_metric(&query("plain")).unwrap().value,
+            json!(3)
+        );
+
+        let bad = json!({"score": {"value": 7, "ts": "yesterday"}});
+        let issues = validate(&bad);
+        assert_eq!(issues.len(), 1);
+        assert!(issues[0].message.contains("RFC 3339"));
+    }
+
+    #[test]
+    fn provider_for_reads_file_scheme_and_bare_paths() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("m.json");
+        std::fs::write(&path, r#"{"tasks_completed": 25}"#).unwrap();
+        let query = MetricQuery {
+            name: "tasks_completed".into(),
+            window: None,
+        };
+
+        for uri in [
+            format!("file://{}", path.display()),
+            path.display().to_string(),
+        ] {
+            let provider = provider_for(&uri).unwrap();
+            assert_eq!(provider.get_metric(&query).unwrap().value, json!(25));
+        }
+
+        let missing = format!("file://{}", dir.path().join("nope.json").display());
+        assert_eq!(
+            provider_for(&missing).err().unwrap().code(),
+            "E_FILE_NOT_FOUND"
+        );
+    }
+
+    #[test]
+    fn provider_for_rejects_unknown_schemes() {
+        let err = provider_for("s3://bucket/metrics.json").err().unwrap();
+        assert_eq!(err.code(), "E_METRICS_SOURCE");
+        assert!(err.to_string().contains("unknown scheme 's3'"));
+
+        let err = provider_for("https://example.com/m.json").err().unwrap();
+        assert!(err.to_string().contains("https is not supported"));
+
+        #[cfg(not(feature = "metrics-prom"))]
+        assert!(provider_for("prom://localhost:9090")
+            .err()
+            .unwrap()
+            .to_string()
+            .contains("`metrics-prom` feature"));
+    }
+
+    #[test]
+    fn non_object_document_is_reported() {
+        let issues = validate(&json!([1, 2]));
+        assert_eq!(issues.len(), 1);
+        assert!(issues[0].message.contains("got array"));
+    }
+}
@@ -0,0 +1,128 @@
+//! Prometheus metrics source: one instant query per criterion metric.
+
+use ampersona_core::errors::MetricError;
+use ampersona_core::traits::{MetricQuery, MetricSample, MetricsProvider};
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+
+use super::http;
+
+/// Queries `<base>/api/v1/query` for each metric.
+///
+/// A criterion window becomes `last_over_time(<metric>[<window>s])`. The
+/// sample timestamp is Prometheus's, so `max_age_seconds` applies to it.
+pub(super) struct PromMetrics {
+    base: String,
+}
+
+impl PromMetrics {
+    /// `target` is the part after `prom://`: `host[:port][/prefix]`.
+    pub(super) fn new(target: &str) -> Self {
+        Self {
+            base: format!("http://{}", target.trim_end_matches('/')),
+        }
+    }
+
+    fn query_url(&self, query: &MetricQuery) -> String {
+        let expr = match query.window {
+            Some(window) => format!("last_over_time({}[{}s])", query.name, window.as_secs()),
+            None => query.name.clone(),
+        };
+        format!("{}/api/v1/query?query={}", self.base, encode(&expr))
+    }
+}
+
+impl MetricsProvider for PromMetrics {
+    fn get_metric(&self, query: &MetricQuery) -> Result<MetricSample, MetricError> {
+        let body =
+            http::get(&self.query_url(query)).map_err(|_| MetricError::ProviderUnavailable)?;
+        let response: Value =
+            serde_json::from_slice(&body).map_err(|_| MetricError::ProviderUnavailable)?;
+        let (sampled_at, value) =
+            parse_response(&response).ok_or_else(|| MetricError::NotFound(query.name.clone()))?;
+        Ok(MetricSample {
+            name: query.name.clone(),
+            value,
+            sampled_at,
+        })
+    }
+}
+
+/// First sample of a successful vector or scalar result.
+fn parse_response(response: &Value) -> Option<(DateTime<Utc>, Value)> {
+    if response["status"] != "success" {
+        return None;
+    }
+    let data = &response["data"];
+    let pair = match data["resultType"].as_str()? {
+        "vector" => &data["result"].as_array()?.first()?["value"],
+        "scalar" => &data["result"],
+        _ => return None,
+    };
+    let ts = pair.get(0)?.as_f64()?;
+    let value = pair.get(1)?.as_str()?.parse::<f64>().ok()?;
+    let sampled_at = DateTime::from_timestamp_millis((ts * 1000.0) as i64)?;
+    Some((sampled_at, serde_json::Number::from_f64(value)?.into()))
+}
+
+/// Percent-encode a query parameter (RFC 3986 unreserved characters pass).
+fn encode(s: &str) -> String {
+    s.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{b:02X}"),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn parses_vector_and_scalar_results() {
+        let vector = serde_json::json!({
+            "status": "success",
+            "data": {"resultType": "vector", "result": [
+                {"metric": {"__name__": "error_rate"}, "value": [1767225600.5, "0.03"]}
+            ]}
+        });
+        let (ts, value) = parse_response(&vector).unwrap();
+        assert_eq!(ts.to_rfc3339(), "2026-01-01T00:00:00.500+00:00");
+        assert_eq!(value, 0.03);
+
+        let scalar = serde_json::json!({
+            "status": "success",
+            "data": {"resultType": "scalar", "result": [1767225600, "7"]}
+        });
+        assert_eq!(parse_response(&scalar).unwrap().1, 7.0);
+
+        let empty = serde_json::json!({
+            "status": "success",
+            "data": {"resultType": "vector", "result": []}
+        });
+        assert!(parse_response(&empty).is_none());
+    }
+
+    #[test]
+    fn queries_prometheus_over_http() {
+        let body = r#"{"status":"success","data":{"resultType":"vector","result":[{"metric":{},"value":[1767225600,"4"]}]}}"#;
+        let (base, server) = http::tests::serve(vec![(200, body.into())]);
+        let prom = PromMetrics::new(base.trim_start_matches("http://"));
+        let sample = prom
+            .get_metric(&MetricQuery {
+                name: "policy_violations".into(),
+                window: Some(Duration::from_secs(300)),
+            })
+            .unwrap();
+        assert_eq!(sample.value, 4.0);
+
+        let requests = server.join().unwrap();
+        assert!(requests[0].starts_with(
+            "GET /api/v1/query?query=last_over_time%28policy_violations%5B300s%5D%29 HTTP/1.0"
+        ));
+    }
+}
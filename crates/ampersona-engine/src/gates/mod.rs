@@ -1,3 +1,10 @@
 pub mod decision;
+pub mod derived;
 pub mod evaluator;
+#[cfg(feature = "exec-hooks")]
+pub mod hooks;
+pub mod metrics;
+pub mod notify;
+pub mod observe;
 pub mod override_gate;
+pub mod simulate;
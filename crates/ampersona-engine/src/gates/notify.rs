@@ -0,0 +1,174 @@
+//! Notices for gate transitions waiting on human approval.
+//!
+//! A `pending_human` decision announces itself once when it is recorded.
+//! Afterwards [`pending_notice`] decides whether it is due a reminder
+//! (older than `remind_after`) or, past the `escalate_after` SLA, an
+//! escalation. Callers always report notices on stderr; with the `notify`
+//! feature they are also POSTed to a webhook ([`post_webhook`]).
+
+use std::time::Duration;
+
+use ampersona_core::state::{PendingTransition, PhaseState};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+/// Webhook that receives notices as JSON (`http://` only).
+pub const URL_ENV: &str = "AMPERSONA_NOTIFY_URL";
+
+/// When a pending transition is worth nagging about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReminderPolicy {
+    /// Age after which a pending transition gets reminders.
+    pub remind_after: Duration,
+    /// Approval SLA: age after which reminders escalate.
+    pub escalate_after: Option<Duration>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Urgency {
+    /// The transition just started waiting.
+    Pending,
+    /// Still waiting past `remind_after`.
+    Reminder,
+    /// Still waiting past the `escalate_after` SLA.
+    Escalation,
+}
+
+/// One notice about a transition awaiting approval.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ApprovalNotice {
+    pub event_type: &'static str,
+    pub urgency: Urgency,
+    pub persona: String,
+    pub gate_id: String,
+    pub from_phase: Option<String>,
+    pub to_phase: String,
+    pub created_at: DateTime<Utc>,
+    pub age_seconds: u64,
+}
+
+impl ApprovalNotice {
+    pub fn new(
+        persona: &str,
+        pending: &PendingTransition,
+        urgency: Urgency,
+        now: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            event_type: "PendingApproval",
+            urgency,
+            persona: persona.to_string(),
+            gate_id: pending.gate_id.clone(),
+            from_phase: pending.from_phase.clone(),
+            to_phase: pending.to_phase.clone(),
+            created_at: pending.created_at,
+            age_seconds: (now - pending.created_at).num_seconds().max(0) as u64,
+        }
+    }
+}
+
+/// The notice `state`'s pending transition is due at `now`, if any.
+pub fn pending_notice(
+    state: &PhaseState,
+    policy: &ReminderPolicy,
+    now: DateTime<Utc>,
+) -> Option<ApprovalNotice> {
+    let pending = state.pending_transition.as_ref()?;
+    let age = (now - pending.created_at).to_std().unwrap_or_default();
+    let urgency = match policy.escalate_after {
+        Some(sla) if age >= sla => Urgency::Escalation,
+        _ if age >= policy.remind_after => Urgency::Reminder,
+        _ => return None,
+    };
+    Some(ApprovalNotice::new(&state.name, pending, urgency, now))
+}
+
+/// POST `notice` as JSON to `url` over HTTP/1.0; any 2xx status is success.
+#[cfg(feature = "notify")]
+pub fn post_webhook(url: &str, notice: &ApprovalNotice) -> crate::error::Result<()> {
+    use crate::error::EngineError;
+
+    let failed = |reason: String| EngineError::Notify(format!("{url}: {reason}"));
+    let body = serde_json::to_vec(notice)?;
+    let response =
+        crate::http::request("POST", url, &[("Content-Type", "application/json")], &body)
+            .map_err(failed)?;
+    if !(200..300).contains(&response.status) {
+        return Err(failed(response.rejection()));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn waiting_since(created_at: DateTime<Utc>) -> PhaseState {
+        let mut state = PhaseState::new("agent".into());
+        state.pending_transition = Some(PendingTransition {
+            gate_id: "trusted".into(),
+            from_phase: Some("active".into()),
+            to_phase: "trusted".into(),
+            decision: "pending_human".into(),
+            metrics_hash: "sha256:00".into(),
+            state_rev: 1,
+            created_at,
+        });
+        state
+    }
+
+    #[test]
+    fn reminds_past_threshold_and_escalates_past_sla() {
+        let now = Utc::now();
+        let policy = ReminderPolicy {
+            remind_after: Duration::from_secs(3600),
+            escalate_after: Some(Duration::from_secs(86_400)),
+        };
+        let notice = |age: i64| {
+            pending_notice(
+                &waiting_since(now - chrono::Duration::seconds(age)),
+                &policy,
+                now,
+            )
+        };
+
+        assert!(notice(60).is_none(), "a fresh transition is left alone");
+        let reminder = notice(7200).unwrap();
+        assert_eq!(reminder.urgency, Urgency::Reminder);
+        assert_eq!(reminder.age_seconds, 7200);
+        assert_eq!(reminder.gate_id, "trusted");
+        assert_eq!(notice(2 * 86_400).unwrap().urgency, Urgency::Escalation);
+
+        assert!(pending_notice(&PhaseState::new("agent".into()), &policy, now).is_none());
+    }
+
+    #[cfg(feature = "notify")]
+    #[test]
+    fn posts_notice_to_webhook() {
+        use std::io::{Read, Write};
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/hooks/amp", listener.local_addr().unwrap());
+        let server = std::thread::spawn(move || {
+            let (mut conn, _) = listener.accept().unwrap();
+            let mut buf = [0u8; 4096];
+            let n = conn.read(&mut buf).unwrap();
+            conn.write_all(b"HTTP/1.0 204 No Content\r\n\r\n").unwrap();
+            String::from_utf8_lossy(&buf[..n]).into_owned()
+        });
+
+        let now = Utc::now();
+        let state = waiting_since(now);
+        let pending = state.pending_transition.as_ref().unwrap();
+        post_webhook(
+            &url,
+            &ApprovalNotice::new("agent", pending, Urgency::Pending, now),
+        )
+        .unwrap();
+
+        let request = server.join().unwrap();
+        assert!(request.starts_with("POST /hooks/amp HTTP/1.0"));
+        assert!(request.contains(r#""urgency":"pending""#));
+    }
+}
@@ -0,0 +1,136 @@
+//! Shadow-mode review: what observe-mode gates would have done.
+
+use ampersona_core::spec::gates::Gate;
+use ampersona_core::types::{GateDirection, GateEnforcement};
+use serde::Serialize;
+
+use crate::error::{EngineError, Result};
+
+/// Audit-log tally of one gate's `observed` decisions.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ObserveSummary {
+    pub gate_id: String,
+    pub direction: GateDirection,
+    /// The gate's current enforcement; an `enforce` gate appears here only
+    /// if it has observed decisions from before it was flipped.
+    pub enforcement: GateEnforcement,
+    pub from_phase: Option<String>,
+    pub to_phase: String,
+    /// Evaluations where the gate passed but was not applied.
+    pub would_fire: u64,
+    pub first_seen: Option<String>,
+    pub last_seen: Option<String>,
+}
+
+/// Count `GateTransition` entries with decision `observed`, per gate.
+///
+/// Every observe-mode gate is listed, in persona order, even with no
+/// observations; a missing audit log counts as empty. Entries for gates no
+/// longer in the persona are ignored.
+pub fn observe_report(gates: &[Gate], audit_path: &str) -> Result<Vec<ObserveSummary>> {
+    let content = match std::fs::read_to_string(audit_path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(EngineError::read(audit_path, e)),
+    };
+    Ok(observe_report_lines(gates, content.lines()))
+}
+
+/// [`observe_report`] over audit log lines from any source.
+pub fn observe_report_lines<'a>(
+    gates: &[Gate],
+    lines: impl IntoIterator<Item = &'a str>,
+) -> Vec<ObserveSummary> {
+    let mut report: Vec<ObserveSummary> = gates
+        .iter()
+        .map(|g| ObserveSummary {
+            gate_id: g.id.clone(),
+            direction: g.direction,
+            enforcement: g.enforcement,
+            from_phase: g.from_phase.clone(),
+            to_phase: g.to_phase.clone(),
+            would_fire: 0,
+            first_seen: None,
+            last_seen: None,
+        })
+        .collect();
+
+    for line in lines.into_iter().filter(|l| !l.trim().is_empty()) {
+        let Ok(entry) = serde_json::from_str::<serde_json::Value>(line) else {
+            continue;
+        };
+        if entry["event_type"] != "GateTransition" || entry["decision"] != "observed" {
+            continue;
+        }
+        let Some(summary) = report.iter_mut().find(|s| entry["gate_id"] == *s.gate_id) else {
+            continue;
+        };
+        summary.would_fire += 1;
+        let ts = entry["ts"].as_str().map(str::to_string);
+        if summary.first_seen.is_none() {
+            summary.first_seen = ts.clone();
+        }
+        summary.last_seen = ts;
+    }
+
+    report.retain(|s| s.enforcement == GateEnforcement::Observe || s.would_fire > 0);
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gate(id: &str, enforcement: GateEnforcement) -> Gate {
+        serde_json::from_value(serde_json::json!({
+            "id": id,
+            "direction": "promote",
+            "enforcement": enforcement,
+            "from_phase": "active",
+            "to_phase": "trusted",
+            "criteria": [{ "metric": "score", "op": "gte", "value": 1 }]
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn tallies_observed_decisions_per_gate() {
+        let dir = tempfile::tempdir().unwrap();
+        let audit = dir.path().join("p.audit.jsonl");
+        let lines = [
+            r#"{"event_type":"GateTransition","gate_id":"shadow","decision":"observed","ts":"2026-01-01T00:00:00Z"}"#,
+            r#"{"event_type":"GateTransition","gate_id":"live","decision":"transition","ts":"2026-01-02T00:00:00Z"}"#,
+            r#"{"event_type":"GateTransition","gate_id":"flipped","decision":"observed","ts":"2026-01-02T00:00:00Z"}"#,
+            r#"{"event_type":"GateTransition","gate_id":"shadow","decision":"observed","ts":"2026-01-03T00:00:00Z"}"#,
+            r#"{"event_type":"GateTransition","gate_id":"removed","decision":"observed","ts":"2026-01-03T00:00:00Z"}"#,
+        ];
+        std::fs::write(&audit, lines.join("\n")).unwrap();
+        let gates = vec![
+            gate("shadow", GateEnforcement::Observe),
+            gate("live", GateEnforcement::Enforce),
+            gate("flipped", GateEnforcement::Enforce),
+            gate("quiet", GateEnforcement::Observe),
+        ];
+
+        let report = observe_report(&gates, audit.to_str().unwrap()).unwrap();
+        let counts: Vec<_> = report
+            .iter()
+            .map(|s| (s.gate_id.as_str(), s.would_fire))
+            .collect();
+        assert_eq!(counts, [("shadow", 2), ("flipped", 1), ("quiet", 0)]);
+        assert_eq!(
+            report[0].first_seen.as_deref(),
+            Some("2026-01-01T00:00:00Z")
+        );
+        assert_eq!(report[0].last_seen.as_deref(), Some("2026-01-03T00:00:00Z"));
+        assert_eq!(report[2].last_seen, None);
+    }
+
+    #[test]
+    fn missing_audit_log_reports_zero() {
+        let gates = vec![gate("shadow", GateEnforcement::Observe)];
+        let report = observe_report(&gates, "/nonexistent/p.audit.jsonl").unwrap();
+        assert_eq!(report.len(), 1);
+        assert_eq!(report[0].would_fire, 0);
+    }
+}
@@ -29,5 +29,6 @@ pub fn process_override(req: &OverrideRequest) -> GateDecisionRecord {
         is_override: true,
         state_rev: req.state_rev,
         metrics_hash: String::new(),
+        forced_reeval: false,
     }
 }
@@ -0,0 +1,270 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use ampersona_core::errors::MetricError;
+use ampersona_core::spec::gates::Gate;
+use ampersona_core::state::{PendingTransition, PhaseState, TransitionRecord};
+use ampersona_core::traits::{MetricQuery, MetricSample, MetricsProvider};
+use ampersona_core::types::GateEnforcement;
+
+use super::evaluator::DefaultGateEvaluator;
+use crate::error::{EngineError, Result};
+
+/// One timestamped metrics snapshot in a simulation timeline.
+///
+/// Same shape as a drift ledger entry, so `.drift.jsonl` files replay directly.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimelineEntry {
+    pub ts: DateTime<Utc>,
+    pub metrics: HashMap<String, serde_json::Value>,
+}
+
+/// Outcome of one simulation tick.
+#[derive(Debug, Clone, Serialize)]
+pub struct SimulationStep {
+    pub ts: DateTime<Utc>,
+    pub phase_before: Option<String>,
+    pub phase_after: Option<String>,
+    /// Gate that fired this tick, if any.
+    pub gate_id: Option<String>,
+    pub decision: Option<String>,
+    pub state_rev: u64,
+}
+
+impl SimulationStep {
+    pub fn transitioned(&self) -> bool {
+        self.phase_before != self.phase_after
+    }
+}
+
+/// Parse a JSONL timeline (`{"ts": ..., "metrics": {...}}` per line), sorted by `ts`.
+pub fn parse_timeline(content: &str) -> Result<Vec<TimelineEntry>> {
+    let mut entries = Vec::new();
+    for (i, line) in content.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let entry: TimelineEntry =
+            serde_json::from_str(line).map_err(|source| EngineError::InvalidJsonLine {
+                path: "timeline".into(),
+                line: i + 1,
+                source,
+            })?;
+        entries.push(entry);
+    }
+    entries.sort_by_key(|e| e.ts);
+    Ok(entries)
+}
+
+struct SnapshotMetrics<'a>(&'a TimelineEntry);
+
+impl MetricsProvider for SnapshotMetrics<'_> {
+    fn get_metric(&self, query: &MetricQuery) -> std::result::Result<MetricSample, MetricError> {
+        self.0
+            .metrics
+            .get(&query.name)
+            .map(|v| MetricSample {
+                name: query.name.clone(),
+                value: v.clone(),
+                sampled_at: self.0.ts,
+            })
+            .ok_or(MetricError::NotFound(query.name.clone()))
+    }
+}
+
+/// Replay a metrics timeline through the gate evaluator against an in-memory state.
+///
+/// Each snapshot is evaluated at its own timestamp, so cooldowns and idempotency
+/// behave as they would in production. On top of that, a replay holds back a gate
+/// that would undo the last transition within its `cooldown_seconds`, so authors
+/// can see how much hysteresis a cooldown buys; live evaluation does not (see
+/// `reverses_recent`). Enforced transitions are applied to the in-memory state;
+/// `pending_human` decisions are recorded as pending but never approved. Nothing
+/// is written to disk.
+pub fn simulate(
+    gates: &[Gate],
+    initial: PhaseState,
+    timeline: &[TimelineEntry],
+) -> Vec<SimulationStep> {
+    let evaluator = DefaultGateEvaluator;
+    let mut state = initial;
+    let mut steps = Vec::with_capacity(timeline.len());
+
+    for entry in timeline {
+        let phase_before = state.current_phase.clone();
+        let eligible: Vec<Gate> = gates
+            .iter()
+            .filter(|gate| !reverses_recent(gate, &state, entry.ts))
+            .cloned()
+            .collect();
+        let record = evaluator.evaluate_at(&eligible, &state, &SnapshotMetrics(entry), entry.ts);
+
+        if let Some(ref record) = record {
+            if record.decision == "pending_human" {
+                state.pending_transition = Some(PendingTransition {
+                    gate_id: record.gate_id.clone(),
+                    from_phase: record.from_phase.clone(),
+                    to_phase: record.to_phase.clone(),
+                    decision: record.decision.clone(),
+                    metrics_hash: record.metrics_hash.clone(),
+                    state_rev: state.state_rev,
+                    created_at: entry.ts,
+                });
+            } else if record.enforcement == GateEnforcement::Enforce
+                && record.decision == "transition"
+            {
+                state.current_phase = Some(record.to_phase.clone());
+                state.state_rev += 1;
+                state.updated_at = entry.ts;
+                state.last_transition = Some(TransitionRecord {
+                    gate_id: record.gate_id.clone(),
+                    from_phase: record.from_phase.clone(),
+                    to_phase: record.to_phase.clone(),
+                    at: entry.ts,
+                    decision_id: format!("gate-{}", state.state_rev),
+                    metrics_hash: Some(record.metrics_hash.clone()),
+                    state_rev: state.state_rev,
+                });
+                state.pending_transition = None;
+            }
+        }
+
+        steps.push(SimulationStep {
+            ts: entry.ts,
+            phase_before,
+            phase_after: state.current_phase.clone(),
+            gate_id: record.as_ref().map(|r| r.gate_id.clone()),
+            decision: record.map(|r| r.decision),
+            state_rev: state.state_rev,
+        });
+    }
+
+    steps
+}
+
+/// Replay-only hysteresis: `gate` would undo the last transition less than its
+/// `cooldown_seconds` after it. Live evaluation only cools down the gate that
+/// made the last transition, so a demotion is never delayed by a promotion.
+fn reverses_recent(gate: &Gate, state: &PhaseState, now: DateTime<Utc>) -> bool {
+    let Some(last) = &state.last_transition else {
+        return false;
+    };
+    gate.cooldown_seconds > 0
+        && last.gate_id != gate.id
+        && last.from_phase.as_deref() == Some(gate.to_phase.as_str())
+        && gate.from_phase.as_deref() == Some(last.to_phase.as_str())
+        && (now - last.at).num_seconds() < gate.cooldown_seconds as i64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ampersona_core::spec::gates::Criterion;
+    use ampersona_core::types::{CriterionOp, GateApproval, GateDirection};
+    use chrono::Duration;
+
+    fn gate(id: &str, direction: GateDirection, from: &str, to: &str, metric: &str) -> Gate {
+        Gate {
+            id: id.into(),
+            direction,
+            enforcement: GateEnforcement::Enforce,
+            priority: 0,
+            order: None,
+            max_age_seconds: None,
+            cooldown_seconds: 0,
+            from_phase: Some(from.into()),
+            to_phase: to.into(),
+            criteria: vec![Criterion {
+                metric: metric.into(),
+                op: CriterionOp::Gte,
+                value: serde_json::json!(1),
+                window_seconds: None,
+                exit: None,
+                ci: false,
+                max_age_seconds: None,
+            }],
+            metrics_schema: None,
+            approval: GateApproval::Auto,
+            on_pass: None,
+            on_fail: None,
+        }
+    }
+
+    /// Hourly snapshots where both the promote and demote criteria hold,
+    /// so without hysteresis the phase flips on every tick.
+    fn hovering_timeline(ticks: i64) -> Vec<TimelineEntry> {
+        let start = Utc::now();
+        (0..ticks)
+            .map(|i| TimelineEntry {
+                ts: start + Duration::hours(i),
+                metrics: HashMap::from([
+                    ("score".to_string(), serde_json::json!(1)),
+                    ("violations".to_string(), serde_json::json!(i)),
+                ]),
+            })
+            .collect()
+    }
+
+    fn flapping_gates(cooldown_seconds: u64) -> Vec<Gate> {
+        let mut promote = gate(
+            "promote",
+            GateDirection::Promote,
+            "active",
+            "trusted",
+            "score",
+        );
+        let mut demote = gate(
+            "demote",
+            GateDirection::Demote,
+            "trusted",
+            "active",
+            "violations",
+        );
+        promote.cooldown_seconds = cooldown_seconds;
+        demote.cooldown_seconds = cooldown_seconds;
+        vec![promote, demote]
+    }
+
+    fn initial_state() -> PhaseState {
+        let mut state = PhaseState::new("sim".into());
+        state.current_phase = Some("active".into());
+        state
+    }
+
+    #[test]
+    fn short_cooldown_flaps() {
+        let steps = simulate(&flapping_gates(60), initial_state(), &hovering_timeline(6));
+        let transitions = steps.iter().filter(|s| s.transitioned()).count();
+        assert!(transitions >= 4, "expected flapping, got {transitions}");
+    }
+
+    #[test]
+    fn long_cooldown_prevents_flapping() {
+        let steps = simulate(
+            &flapping_gates(86400),
+            initial_state(),
+            &hovering_timeline(6),
+        );
+        let transitions = steps.iter().filter(|s| s.transitioned()).count();
+        assert_eq!(transitions, 1);
+        assert_eq!(
+            steps.last().unwrap().phase_after.as_deref(),
+            Some("trusted")
+        );
+    }
+
+    #[test]
+    fn parse_timeline_sorts_by_ts() {
+        let content = concat!(
+            r#"{"ts":"2026-01-02T00:00:00Z","metrics":{"a":2}}"#,
+            "\n\n",
+            r#"{"ts":"2026-01-01T00:00:00Z","metrics":{"a":1}}"#,
+            "\n"
+        );
+        let entries = parse_timeline(content).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].metrics["a"], 1);
+    }
+}
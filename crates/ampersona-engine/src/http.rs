@@ -0,0 +1,217 @@
+//! Minimal HTTP/1.0 client for `http://` URLs, shared by metrics sources and
+//! approval webhooks. No TLS, no redirects, no keep-alive.
+//!
+//! Errors are plain reasons; callers prefix the URL and wrap them in their own
+//! [`EngineError`](crate::error::EngineError) variant.
+
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpStream, ToSocketAddrs};
+use std::time::Duration;
+
+const NETWORK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Largest response (status line, headers and body) read before giving up.
+pub(crate) const MAX_RESPONSE_BYTES: u64 = 4 * 1024 * 1024;
+
+/// A parsed HTTP response.
+#[derive(Debug)]
+pub(crate) struct Response {
+    pub status: u16,
+    /// The status line as sent, for error messages.
+    pub status_line: String,
+    pub body: Vec<u8>,
+}
+
+impl Response {
+    /// Why a non-success response failed: the status line and the start of
+    /// the body, where servers usually explain themselves.
+    pub fn rejection(&self) -> String {
+        let body = String::from_utf8_lossy(&self.body);
+        let excerpt: String = body.trim().chars().take(200).collect();
+        if excerpt.is_empty() {
+            format!("server returned {}", self.status_line)
+        } else {
+            format!("server returned {}: {excerpt}", self.status_line)
+        }
+    }
+}
+
+/// The parts of an `http://` URL a request needs.
+#[derive(Debug, PartialEq, Eq)]
+struct Target<'a> {
+    /// `host[:port]` as written, for the `Host` header.
+    authority: &'a str,
+    /// Host to resolve, without IPv6 brackets.
+    host: &'a str,
+    port: u16,
+    path: &'a str,
+}
+
+fn parse_url(url: &str) -> Result<Target<'_>, String> {
+    let rest = url
+        .strip_prefix("http://")
+        .ok_or("expected an http:// URL")?;
+    let (authority, path) = match rest.find('/') {
+        Some(i) => (&rest[..i], &rest[i..]),
+        None => (rest, "/"),
+    };
+    // `[v6addr]` or `[v6addr]:port`; anything else splits at its only colon.
+    let (host, port) = if let Some(bracketed) = authority.strip_prefix('[') {
+        let (host, after) = bracketed.split_once(']').ok_or("unterminated [ in host")?;
+        match after {
+            "" => (host, None),
+            _ => (
+                host,
+                Some(after.strip_prefix(':').ok_or("junk after ] in host")?),
+            ),
+        }
+    } else {
+        match authority.split_once(':') {
+            Some((_, port)) if port.contains(':') => {
+                return Err("IPv6 addresses must be in brackets".into())
+            }
+            Some((host, port)) => (host, Some(port)),
+            None => (authority, None),
+        }
+    };
+    if host.is_empty() {
+        return Err("missing host".into());
+    }
+    let port = match port {
+        Some(port) => port.parse().map_err(|_| format!("bad port '{port}'"))?,
+        None => 80,
+    };
+    Ok(Target {
+        authority,
+        host,
+        port,
+        path,
+    })
+}
+
+/// Send one request to `url` and read the whole response, refusing one
+/// larger than [`MAX_RESPONSE_BYTES`]. Any status is returned; judging it is
+/// the caller's job.
+pub(crate) fn request(
+    method: &str,
+    url: &str,
+    headers: &[(&str, &str)],
+    body: &[u8],
+) -> Result<Response, String> {
+    let target = parse_url(url)?;
+    let io = |e: std::io::Error| e.to_string();
+    let addrs: Vec<SocketAddr> = (target.host, target.port)
+        .to_socket_addrs()
+        .map_err(io)?
+        .collect();
+    if addrs.is_empty() {
+        return Err(format!("cannot resolve {}", target.host));
+    }
+    let mut last_err = None;
+    let mut stream = None;
+    for addr in &addrs {
+        match TcpStream::connect_timeout(addr, NETWORK_TIMEOUT) {
+            Ok(s) => {
+                stream = Some(s);
+                break;
+            }
+            Err(e) => last_err = Some(e),
+        }
+    }
+    let Some(mut stream) = stream else {
+        return Err(last_err.map(io).unwrap_or_default());
+    };
+    stream.set_read_timeout(Some(NETWORK_TIMEOUT)).map_err(io)?;
+    stream
+        .set_write_timeout(Some(NETWORK_TIMEOUT))
+        .map_err(io)?;
+
+    let mut head = format!(
+        "{method} {} HTTP/1.0\r\nHost: {}\r\nConnection: close\r\n",
+        target.path, target.authority
+    );
+    for (name, value) in headers {
+        head.push_str(&format!("{name}: {value}\r\n"));
+    }
+    if !body.is_empty() {
+        head.push_str(&format!("Content-Length: {}\r\n", body.len()));
+    }
+    head.push_str("\r\n");
+    let mut message = head.into_bytes();
+    message.extend_from_slice(body);
+    stream.write_all(&message).map_err(io)?;
+
+    let mut response = Vec::new();
+    (&mut stream)
+        .take(MAX_RESPONSE_BYTES + 1)
+        .read_to_end(&mut response)
+        .map_err(io)?;
+    if response.len() as u64 > MAX_RESPONSE_BYTES {
+        return Err(format!("response larger than {MAX_RESPONSE_BYTES} bytes"));
+    }
+    parse_response(response)
+}
+
+fn parse_response(mut response: Vec<u8>) -> Result<Response, String> {
+    let split = response
+        .windows(4)
+        .position(|w| w == b"\r\n\r\n")
+        .ok_or("malformed HTTP response")?;
+    let head = String::from_utf8_lossy(&response[..split]);
+    let status_line = head.lines().next().unwrap_or("").to_string();
+    let status = status_line
+        .split_whitespace()
+        .nth(1)
+        .and_then(|code| code.parse().ok())
+        .ok_or_else(|| format!("malformed status line '{status_line}'"))?;
+    let body = response.split_off(split + 4);
+    Ok(Response {
+        status,
+        status_line,
+        body,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_hosts_ports_and_ipv6_literals() {
+        let t = parse_url("http://example.org/m.json").unwrap();
+        assert_eq!((t.host, t.port, t.path), ("example.org", 80, "/m.json"));
+        let t = parse_url("http://127.0.0.1:9090").unwrap();
+        assert_eq!((t.host, t.port, t.path), ("127.0.0.1", 9090, "/"));
+        let t = parse_url("http://[::1]:8080/hook").unwrap();
+        assert_eq!((t.authority, t.host, t.port), ("[::1]:8080", "::1", 8080));
+        let t = parse_url("http://[fe80::1]/").unwrap();
+        assert_eq!((t.host, t.port), ("fe80::1", 80));
+
+        assert!(parse_url("https://example.org/").is_err());
+        assert!(parse_url("http://::1:8080/").is_err());
+        assert!(parse_url("http://[::1/").is_err());
+        assert!(parse_url("http://host:port/").is_err());
+        assert!(parse_url("http://:80/").is_err());
+    }
+
+    #[test]
+    fn refuses_an_oversized_response() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        let server = std::thread::spawn(move || {
+            let (mut conn, _) = listener.accept().unwrap();
+            let mut buf = [0u8; 1024];
+            let _ = conn.read(&mut buf).unwrap();
+            let _ = conn.write_all(b"HTTP/1.0 200 OK\r\n\r\n");
+            let chunk = vec![b'x'; 64 * 1024];
+            for _ in 0..=MAX_RESPONSE_BYTES / chunk.len() as u64 {
+                if conn.write_all(&chunk).is_err() {
+                    break;
+                }
+            }
+        });
+        let err = request("GET", &url, &[], &[]).unwrap_err();
+        assert!(err.contains("larger than"), "{err}");
+        server.join().unwrap();
+    }
+}
@@ -1,6 +1,136 @@
 #![forbid(unsafe_code)]
 
 pub mod convert;
+pub mod doctor;
+pub mod error;
 pub mod gates;
+#[cfg(any(feature = "metrics-http", feature = "notify"))]
+mod http;
 pub mod policy;
 pub mod state;
+
+#[cfg(all(test, feature = "otel"))]
+mod otel_tests {
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
+
+    use tracing::field::{Field, Visit};
+    use tracing::span::{Attributes, Id, Record};
+    use tracing::{Event, Metadata, Subscriber};
+
+    use ampersona_core::spec::authority::{Authority, AuthorityOverlay};
+    use ampersona_core::state::PhaseState;
+    use ampersona_core::traits::{AuthorityEnforcer, MetricQuery, MetricSample, MetricsProvider};
+
+    type Spans = Arc<Mutex<Vec<(String, HashMap<String, String>)>>>;
+
+    /// Records every span's name and fields, including ones set via `record`.
+    #[derive(Default)]
+    struct Recorder {
+        spans: Spans,
+    }
+
+    struct Fields<'a>(&'a mut HashMap<String, String>);
+
+    impl Visit for Fields<'_> {
+        fn record_str(&mut self, field: &Field, value: &str) {
+            self.0.insert(field.name().into(), value.into());
+        }
+        fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+            self.0.insert(field.name().into(), format!("{value:?}"));
+        }
+    }
+
+    impl Subscriber for Recorder {
+        fn enabled(&self, _: &Metadata<'_>) -> bool {
+            true
+        }
+        fn new_span(&self, attrs: &Attributes<'_>) -> Id {
+            let mut fields = HashMap::new();
+            attrs.record(&mut Fields(&mut fields));
+            let mut spans = self.spans.lock().unwrap();
+            spans.push((attrs.metadata().name().to_string(), fields));
+            Id::from_u64(spans.len() as u64)
+        }
+        fn record(&self, span: &Id, values: &Record<'_>) {
+            let mut spans = self.spans.lock().unwrap();
+            let (_, fields) = &mut spans[span.into_u64() as usize - 1];
+            values.record(&mut Fields(fields));
+        }
+        fn record_follows_from(&self, _: &Id, _: &Id) {}
+        fn event(&self, _: &Event<'_>) {}
+        fn enter(&self, _: &Id) {}
+        fn exit(&self, _: &Id) {}
+    }
+
+    struct Score;
+
+    impl MetricsProvider for Score {
+        fn get_metric(
+            &self,
+            query: &MetricQuery,
+        ) -> Result<MetricSample, ampersona_core::errors::MetricError> {
+            Ok(MetricSample {
+                name: query.name.clone(),
+                value: serde_json::json!(5),
+                sampled_at: chrono::Utc::now(),
+            })
+        }
+    }
+
+    #[test]
+    fn evaluation_emits_spans() {
+        let recorder = Recorder::default();
+        let spans = recorder.spans.clone();
+
+        tracing::subscriber::with_default(recorder, || {
+            let authority: Authority = serde_json::from_value(serde_json::json!({
+                "autonomy": "full",
+                "actions": { "allow": ["read_file"] }
+            }))
+            .unwrap();
+            let resolved =
+                crate::policy::precedence::resolve_with_elevations(&[&authority], &[], &[]);
+            let resolved =
+                crate::policy::precedence::apply_overlay(&resolved, &AuthorityOverlay::default());
+            let req = ampersona_core::traits::PolicyRequest {
+                action: Some("read_file".parse().unwrap()),
+                path: None,
+                context: HashMap::new(),
+            };
+            crate::policy::checker::DefaultPolicyChecker
+                .evaluate(&req, &resolved)
+                .unwrap();
+
+            let gates = vec![serde_json::from_value(serde_json::json!({
+                "id": "onboarding",
+                "direction": "promote",
+                "from_phase": null,
+                "to_phase": "active",
+                "criteria": [{ "metric": "score", "op": "gte", "value": 1 }]
+            }))
+            .unwrap()];
+            crate::gates::evaluator::DefaultGateEvaluator
+                .evaluate(&gates, &PhaseState::new("Tracer".into()), &Score)
+                .unwrap();
+        });
+
+        let spans = spans.lock().unwrap();
+        let find = |name: &str| {
+            spans
+                .iter()
+                .find(|(n, _)| n == name)
+                .map(|(_, f)| f.clone())
+                .unwrap_or_else(|| panic!("no {name} span in {spans:?}"))
+        };
+        find("ampersona.resolve_with_elevations");
+        find("ampersona.apply_overlay");
+        let policy = find("ampersona.policy.evaluate");
+        assert_eq!(policy["action"], "read_file");
+        assert_eq!(policy["decision"], "Allow");
+        let gate = find("ampersona.gate.evaluate");
+        assert_eq!(gate["persona"], "Tracer");
+        assert_eq!(gate["gate"], "onboarding");
+        assert_eq!(gate["decision"], "transition");
+    }
+}
@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use ampersona_core::actions::{ActionId, BuiltinAction};
 
 /// Validate that an action ID is recognized.
@@ -12,3 +14,125 @@ pub fn validate_action(id: &ActionId) -> bool {
 pub fn is_builtin(name: &str) -> bool {
     BuiltinAction::from_str_opt(name).is_some()
 }
+
+/// Vocabulary classification of an action string.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ActionClass {
+    /// One of the builtin actions from `actions.rs`.
+    Builtin,
+    /// A well-formed `custom:<vendor>/<action>` reference.
+    Custom,
+    /// Neither — mapped to `Custom { vendor: "_unknown" }` and denied by default.
+    Unknown,
+}
+
+impl ActionClass {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ActionClass::Builtin => "builtin",
+            ActionClass::Custom => "custom",
+            ActionClass::Unknown => "unknown",
+        }
+    }
+}
+
+/// Action vocabulary with a parse cache.
+///
+/// Seeded once with every builtin; each other string is parsed on first sight
+/// and its result cached — including unknown strings (negative caching), so
+/// batch callers never re-parse the same input.
+#[derive(Debug, Clone)]
+pub struct ActionVocabulary {
+    cache: HashMap<String, (ActionId, ActionClass)>,
+}
+
+impl Default for ActionVocabulary {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ActionVocabulary {
+    pub fn new() -> Self {
+        let cache = BuiltinAction::all()
+            .iter()
+            .map(|b| {
+                (
+                    b.as_str().to_string(),
+                    (ActionId::Builtin(*b), ActionClass::Builtin),
+                )
+            })
+            .collect();
+        Self { cache }
+    }
+
+    /// Classify an action string, returning its `ActionId` and vocabulary class.
+    pub fn classify(&mut self, action: &str) -> (ActionId, ActionClass) {
+        if let Some(hit) = self.cache.get(action) {
+            return hit.clone();
+        }
+        let entry = match action.parse::<ActionId>() {
+            Ok(id @ ActionId::Builtin(_)) => (id, ActionClass::Builtin),
+            Ok(id @ ActionId::Custom { .. }) => (id, ActionClass::Custom),
+            Err(_) => (
+                ActionId::Custom {
+                    vendor: "_unknown".into(),
+                    action: action.into(),
+                },
+                ActionClass::Unknown,
+            ),
+        };
+        self.cache.insert(action.to_string(), entry.clone());
+        entry
+    }
+
+    /// Number of cached entries (builtins plus every string seen so far).
+    pub fn len(&self) -> usize {
+        self.cache.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cache.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classify_known_builtin() {
+        let mut vocab = ActionVocabulary::new();
+        let seeded = vocab.len();
+        let (id, class) = vocab.classify("read_file");
+        assert_eq!(class, ActionClass::Builtin);
+        assert_eq!(id.to_string(), "read_file");
+        assert_eq!(vocab.len(), seeded);
+    }
+
+    #[test]
+    fn classify_unknown_is_negatively_cached() {
+        let mut vocab = ActionVocabulary::new();
+        let seeded = vocab.len();
+        let (id, class) = vocab.classify("launch_rockets");
+        assert_eq!(class, ActionClass::Unknown);
+        assert!(!validate_action(&id));
+        assert_eq!(vocab.len(), seeded + 1);
+
+        // Second lookup hits the cache
+        let (again, _) = vocab.classify("launch_rockets");
+        assert_eq!(again, id);
+        assert_eq!(vocab.len(), seeded + 1);
+    }
+
+    #[test]
+    fn classify_custom_vendor() {
+        let mut vocab = ActionVocabulary::new();
+        let (id, class) = vocab.classify("custom:zeroclaw/sandbox_escape");
+        assert_eq!(class, ActionClass::Custom);
+        assert!(validate_action(&id));
+
+        let (_, malformed) = vocab.classify("custom:noslash");
+        assert_eq!(malformed, ActionClass::Unknown);
+    }
+}
@@ -1,3 +1,8 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use sha2::{Digest, Sha256};
+
 use ampersona_core::errors::{PolicyDecision, PolicyError};
 use ampersona_core::spec::authority::ScopedAction;
 use ampersona_core::traits::{AuthorityEnforcer, PolicyRequest, ResolvedAuthority};
@@ -17,13 +22,56 @@ impl AuthorityEnforcer for DefaultPolicyChecker {
         &self,
         req: &PolicyRequest,
         authority: &ResolvedAuthority,
+    ) -> Result<PolicyDecision, PolicyError> {
+        #[cfg(feature = "otel")]
+        let span = tracing::info_span!(
+            "ampersona.policy.evaluate",
+            action = req
+                .action
+                .as_ref()
+                .map(|a| a.to_string())
+                .unwrap_or_default(),
+            decision = tracing::field::Empty,
+        )
+        .entered();
+
+        let decision = self.decide(req, authority);
+
+        #[cfg(feature = "otel")]
+        span.record(
+            "decision",
+            match &decision {
+                Ok(PolicyDecision::Allow { .. }) => "Allow",
+                Ok(PolicyDecision::Deny { .. }) => "Deny",
+                Ok(PolicyDecision::NeedsApproval { .. }) => "NeedsApproval",
+                Err(_) => "Error",
+            },
+        );
+        decision
+    }
+}
+
+impl DefaultPolicyChecker {
+    fn decide(
+        &self,
+        req: &PolicyRequest,
+        authority: &ResolvedAuthority,
     ) -> Result<PolicyDecision, PolicyError> {
         // 1. Explicit deny always wins
         if let Some(action) = &req.action {
             if authority.denied_actions.contains(action) {
-                return Ok(PolicyDecision::Deny {
-                    reason: format!("action '{action}' is explicitly denied"),
-                });
+                let overdue = authority
+                    .deny_metadata
+                    .get(&action.to_string())
+                    .and_then(|m| m.review_by.filter(|_| m.review_overdue()));
+                let reason = match overdue {
+                    Some(t) => format!(
+                        "action '{action}' is explicitly denied (deny past review date {})",
+                        t.format("%Y-%m-%d")
+                    ),
+                    None => format!("action '{action}' is explicitly denied"),
+                };
+                return Ok(PolicyDecision::Deny { reason });
             }
         }
 
@@ -117,6 +165,11 @@ impl AuthorityEnforcer for DefaultPolicyChecker {
 }
 
 impl DefaultPolicyChecker {
+    /// Wrap in a [`CachingChecker`] that memoizes decisions within a run.
+    pub fn cached(self) -> CachingChecker<Self> {
+        CachingChecker::new(self)
+    }
+
     /// Check if symlink validation is enabled via any shell scoped action.
     fn should_validate_symlinks(&self, authority: &ResolvedAuthority) -> bool {
         if let Some(ScopedAction::Shell(shell)) = authority.scoped_actions.get("shell") {
@@ -259,6 +312,114 @@ impl DefaultPolicyChecker {
     }
 }
 
+/// Decision cache counters.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+}
+
+type DecisionKey = ([u8; 32], Option<String>, Option<String>, [u8; 32]);
+
+/// Memoizes an enforcer's decisions keyed by
+/// `(hash(resolved authority), action, path, hash(context))`.
+///
+/// Decisions are pure in those inputs, except that symlink validation reads
+/// the filesystem at evaluation time, so the cache is meant for one run (a
+/// matrix, a batch). Call [`clear_cache`](Self::clear_cache) whenever state
+/// changes. Errors are not cached.
+pub struct CachingChecker<C> {
+    inner: C,
+    cache: Mutex<(HashMap<DecisionKey, PolicyDecision>, CacheStats)>,
+}
+
+impl<C: AuthorityEnforcer> CachingChecker<C> {
+    pub fn new(inner: C) -> Self {
+        Self {
+            inner,
+            cache: Mutex::new((HashMap::new(), CacheStats::default())),
+        }
+    }
+
+    /// Drop every cached decision; the counters are kept.
+    pub fn clear_cache(&self) {
+        self.lock().0.clear();
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        self.lock().1
+    }
+
+    fn lock(
+        &self,
+    ) -> std::sync::MutexGuard<'_, (HashMap<DecisionKey, PolicyDecision>, CacheStats)> {
+        self.cache.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl<C: AuthorityEnforcer> CachingChecker<C> {
+    /// Like `evaluate`, without re-hashing an authority checked many times.
+    pub fn evaluate_keyed(
+        &self,
+        req: &PolicyRequest,
+        authority: &KeyedAuthority<'_>,
+    ) -> Result<PolicyDecision, PolicyError> {
+        let key = (
+            authority.digest,
+            req.action.as_ref().map(|a| a.to_string()),
+            req.path.clone(),
+            digest(&req.context),
+        );
+        {
+            let mut cache = self.lock();
+            if let Some(decision) = cache.0.get(&key).cloned() {
+                cache.1.hits += 1;
+                return Ok(decision);
+            }
+            cache.1.misses += 1;
+        }
+        let decision = self.inner.evaluate(req, authority.authority)?;
+        self.lock().0.insert(key, decision.clone());
+        Ok(decision)
+    }
+}
+
+impl<C: AuthorityEnforcer> AuthorityEnforcer for CachingChecker<C> {
+    fn evaluate(
+        &self,
+        req: &PolicyRequest,
+        authority: &ResolvedAuthority,
+    ) -> Result<PolicyDecision, PolicyError> {
+        self.evaluate_keyed(req, &KeyedAuthority::new(authority))
+    }
+}
+
+/// A resolved authority with its cache digest computed once.
+///
+/// Hashing the authority costs about as much as evaluating a request, so
+/// callers checking many requests against one authority (a matrix, a batch)
+/// should key it once and use [`CachingChecker::evaluate_keyed`].
+pub struct KeyedAuthority<'a> {
+    authority: &'a ResolvedAuthority,
+    digest: [u8; 32],
+}
+
+impl<'a> KeyedAuthority<'a> {
+    pub fn new(authority: &'a ResolvedAuthority) -> Self {
+        Self {
+            authority,
+            digest: digest(authority),
+        }
+    }
+}
+
+/// SHA-256 of the serialized value. Equal values whose maps iterate in a
+/// different order hash differently; that costs a miss, never a wrong hit.
+fn digest<T: serde::Serialize>(value: &T) -> [u8; 32] {
+    let bytes = serde_json::to_vec(value).unwrap_or_default();
+    Sha256::digest(bytes).into()
+}
+
 /// Check for subshell patterns in a command string.
 fn has_subshell(cmd: &str) -> bool {
     cmd.contains("$(") || cmd.contains('`') || cmd.contains("( ")
@@ -645,4 +806,38 @@ mod tests {
         let result = checker.evaluate(&req, &auth).unwrap();
         assert!(matches!(result, PolicyDecision::Deny { .. }));
     }
+
+    #[test]
+    fn caching_checker_memoizes_per_authority_and_request() {
+        let checker = DefaultPolicyChecker.cached();
+        let full = make_authority(AutonomyLevel::Full, vec!["read_file"], vec!["deploy"]);
+        let readonly = make_authority(AutonomyLevel::Readonly, vec!["read_file"], vec![]);
+        let req = |action: &str| PolicyRequest {
+            action: Some(action.parse().unwrap()),
+            path: None,
+            context: HashMap::new(),
+        };
+
+        let first = checker.evaluate(&req("read_file"), &full).unwrap();
+        assert_eq!(checker.evaluate(&req("read_file"), &full).unwrap(), first);
+        assert_eq!(checker.stats(), CacheStats { hits: 1, misses: 1 });
+
+        // A different authority or action is a different key
+        let denied = checker.evaluate(&req("read_file"), &readonly).unwrap();
+        assert!(matches!(denied, PolicyDecision::Deny { .. }));
+        checker.evaluate(&req("deploy"), &full).unwrap();
+        assert_eq!(checker.stats(), CacheStats { hits: 1, misses: 3 });
+
+        // Pre-keyed lookups share entries with the trait path
+        let keyed = KeyedAuthority::new(&full);
+        assert_eq!(
+            checker.evaluate_keyed(&req("read_file"), &keyed).unwrap(),
+            first
+        );
+        assert_eq!(checker.stats(), CacheStats { hits: 2, misses: 3 });
+
+        checker.clear_cache();
+        checker.evaluate(&req("read_file"), &full).unwrap();
+        assert_eq!(checker.stats(), CacheStats { hits: 2, misses: 4 });
+    }
 }
@@ -1,15 +1,20 @@
 use std::collections::HashMap;
 
+use serde::Serialize;
+
 use ampersona_core::actions::ActionId;
+use ampersona_core::errors::PolicyDecision;
 use ampersona_core::spec::authority::{Authority, AuthorityOverlay, DenyEntry, Elevation};
 use ampersona_core::state::ActiveElevation;
-use ampersona_core::traits::{DenyMeta, ResolvedAuthority};
+use ampersona_core::traits::{AuthorityEnforcer, DenyMeta, PolicyRequest, ResolvedAuthority};
 use ampersona_core::types::AutonomyLevel;
 
-/// Resolve authority from multiple layers (workspace defaults → persona → gate overlay → elevation).
+use crate::error::{EngineError, Result};
+
+/// Resolve authority from multiple layers (workspace defaults → phase → persona → gate overlay → elevation).
 ///
 /// Merge rules:
-/// - deny = union (all denies from all layers)
+/// - deny = union (all denies from all layers, minus expired entries)
 /// - allow = intersection minus deny
 /// - limits = minimum
 /// - autonomy = minimum
@@ -28,22 +33,12 @@ pub fn resolve_authority(layers: &[&Authority]) -> ResolvedAuthority {
 
         if let Some(actions) = &layer.actions {
             // Deny: union (with metadata preservation)
+            // Expired denies are no longer in force
             if let Some(deny) = &actions.deny {
-                for entry in deny {
+                for entry in deny.iter().filter(|e| !e.is_expired()) {
                     let id = entry.action_id().clone();
-                    if let DenyEntry::WithReason {
-                        reason,
-                        compliance_ref,
-                        ..
-                    } = entry
-                    {
-                        deny_metadata.insert(
-                            id.to_string(),
-                            DenyMeta {
-                                reason: Some(reason.clone()),
-                                compliance_ref: compliance_ref.clone(),
-                            },
-                        );
+                    if let Some(meta) = deny_meta(entry) {
+                        deny_metadata.insert(id.to_string(), meta);
                     }
                     all_denied.push(id);
                 }
@@ -108,12 +103,21 @@ pub fn resolve_authority(layers: &[&Authority]) -> ResolvedAuthority {
 /// 2. Active elevation grants (within TTL)
 /// 3. Gate authority_overlay (phase-specific)
 /// 4. Persona authority
-/// 5. Workspace defaults (.ampersona/defaults.json)
+/// 5. Declared authority of the current phase (`phases.<name>.authority`)
+/// 6. Workspace defaults (.ampersona/defaults.json)
 pub fn resolve_with_elevations(
     layers: &[&Authority],
     active_elevations: &[ActiveElevation],
     elevation_defs: &[Elevation],
 ) -> ResolvedAuthority {
+    #[cfg(feature = "otel")]
+    let _span = tracing::info_span!(
+        "ampersona.resolve_with_elevations",
+        layers = layers.len(),
+        elevations = active_elevations.len(),
+    )
+    .entered();
+
     let mut resolved = resolve_authority(layers);
 
     // Apply active (non-expired) elevation grants
@@ -171,6 +175,9 @@ pub fn resolve_with_elevations(
 /// - Actions.allow replaces allowed minus deny
 /// - Absent fields leave resolved values unchanged
 pub fn apply_overlay(base: &ResolvedAuthority, overlay: &AuthorityOverlay) -> ResolvedAuthority {
+    #[cfg(feature = "otel")]
+    let _span = tracing::info_span!("ampersona.apply_overlay").entered();
+
     let mut result = base.clone();
 
     // Autonomy: REPLACE (not min)
@@ -181,7 +188,7 @@ pub fn apply_overlay(base: &ResolvedAuthority, overlay: &AuthorityOverlay) -> Re
     if let Some(ref actions) = overlay.actions {
         // Deny: UNION (additive — deny never weakened)
         if let Some(ref deny) = actions.deny {
-            for entry in deny {
+            for entry in deny.iter().filter(|e| !e.is_expired()) {
                 let id = entry.action_id().clone();
                 if !result.denied_actions.contains(&id) {
                     result.denied_actions.push(id.clone());
@@ -189,19 +196,8 @@ pub fn apply_overlay(base: &ResolvedAuthority, overlay: &AuthorityOverlay) -> Re
                 result.allowed_actions.retain(|a| a != &id);
 
                 // Preserve deny metadata
-                if let DenyEntry::WithReason {
-                    reason,
-                    compliance_ref,
-                    ..
-                } = entry
-                {
-                    result.deny_metadata.insert(
-                        id.to_string(),
-                        DenyMeta {
-                            reason: Some(reason.clone()),
-                            compliance_ref: compliance_ref.clone(),
-                        },
-                    );
+                if let Some(meta) = deny_meta(entry) {
+                    result.deny_metadata.insert(id.to_string(), meta);
                 }
             }
         }
@@ -229,37 +225,363 @@ pub fn apply_overlay(base: &ResolvedAuthority, overlay: &AuthorityOverlay) -> Re
     result
 }
 
-/// Load workspace defaults from .ampersona/defaults.json and return as Authority if present.
-/// Logs a warning to stderr if the file exists but cannot be parsed.
-pub fn load_workspace_defaults() -> Option<Authority> {
-    let path = ".ampersona/defaults.json";
-    let content = match std::fs::read_to_string(path) {
-        Ok(c) => c,
-        Err(_) => return None, // file doesn't exist — not an error
+/// One resolved field an overlay replaced.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct OverlayChange {
+    /// `autonomy`, `allowed_actions`, `denied_actions`, `scope`, or `limits`.
+    pub field: String,
+    pub before: serde_json::Value,
+    pub after: serde_json::Value,
+}
+
+/// [`apply_overlay`], also listing each field whose value it changed.
+///
+/// Fields the overlay sets to the value already resolved are not listed.
+pub fn apply_overlay_with_changes(
+    base: &ResolvedAuthority,
+    overlay: &AuthorityOverlay,
+) -> (ResolvedAuthority, Vec<OverlayChange>) {
+    let patched = apply_overlay(base, overlay);
+    let fields = |r: &ResolvedAuthority| {
+        [
+            ("autonomy", serde_json::json!(r.autonomy)),
+            ("allowed_actions", serde_json::json!(r.allowed_actions)),
+            ("denied_actions", serde_json::json!(r.denied_actions)),
+            ("scope", serde_json::json!(r.scope)),
+            ("limits", serde_json::json!(r.limits)),
+        ]
     };
-    let data: serde_json::Value = match serde_json::from_str(&content) {
-        Ok(d) => d,
-        Err(e) => {
-            eprintln!("  warn: {path}: unparseable JSON: {e}");
-            return None;
+    let changes = fields(base)
+        .into_iter()
+        .zip(fields(&patched))
+        .filter(|((_, before), (_, after))| before != after)
+        .map(|((field, before), (_, after))| OverlayChange {
+            field: field.to_string(),
+            before,
+            after,
+        })
+        .collect();
+    (patched, changes)
+}
+
+/// [`apply_overlay`], refusing an overlay that would raise autonomy.
+///
+/// Overlays replace autonomy outright, so one meant to restrict can silently
+/// grant more (e.g. readonly -> full); this is the interlock for callers that
+/// only expect overlays to narrow authority.
+pub fn apply_overlay_no_upgrade(
+    base: &ResolvedAuthority,
+    overlay: &AuthorityOverlay,
+) -> Result<ResolvedAuthority> {
+    match overlay.autonomy {
+        Some(to) if to > base.autonomy => Err(EngineError::AutonomyUpgrade {
+            path: "authority_overlay.autonomy".into(),
+            from: base.autonomy.as_str(),
+            to: to.as_str(),
+        }),
+        _ => Ok(apply_overlay(base, overlay)),
+    }
+}
+
+fn deny_meta(entry: &DenyEntry) -> Option<DenyMeta> {
+    match entry {
+        DenyEntry::Simple(_) => None,
+        DenyEntry::WithReason {
+            reason,
+            compliance_ref,
+            review_by,
+            expires_at,
+            ..
+        } => Some(DenyMeta {
+            reason: Some(reason.clone()),
+            compliance_ref: compliance_ref.clone(),
+            review_by: *review_by,
+            expires_at: *expires_at,
+        }),
+    }
+}
+
+/// One layer's contribution to the resolved authority.
+#[derive(Debug, Clone, Serialize)]
+pub struct TraceStep {
+    /// Layer label: `workspace_defaults`, `phase`, `persona`, `elevations`, or `overlay`.
+    pub layer: String,
+    /// Human-readable changes this layer made to the cumulative result.
+    pub changes: Vec<String>,
+    /// Cumulative autonomy after this layer.
+    pub autonomy: AutonomyLevel,
+    /// Whether the checked action is in the allow list after this layer.
+    pub action_allowed: bool,
+    /// Whether the checked action is in the deny list after this layer.
+    pub action_denied: bool,
+    /// Policy decision if resolution stopped after this layer.
+    pub decision: String,
+    pub reason: String,
+}
+
+/// Resolution result with per-layer provenance.
+#[derive(Debug, Clone)]
+pub struct ResolutionTrace {
+    pub resolved: ResolvedAuthority,
+    pub steps: Vec<TraceStep>,
+}
+
+impl ResolutionTrace {
+    /// The layer that produced the final decision: the last layer whose
+    /// cumulative decision differs from the one before it.
+    pub fn deciding_layer(&self) -> Option<&str> {
+        let mut deciding = None;
+        let mut prev: Option<&str> = None;
+        for step in &self.steps {
+            if prev != Some(step.decision.as_str()) {
+                deciding = Some(step.layer.as_str());
+            }
+            prev = Some(step.decision.as_str());
         }
-    };
-    let authority = match data.get("authority") {
-        Some(a) => a,
+        deciding
+    }
+}
+
+/// Resolve authority like `resolve_with_elevations` + `apply_overlay`, recording
+/// what each layer contributed and the decision for `req` after each layer.
+///
+/// `layers` are labelled base layers in precedence order (lowest first).
+/// The elevation step is recorded only when `active_elevations` is non-empty,
+/// the overlay step only when `overlay` is present.
+pub fn resolve_traced(
+    layers: &[(&str, &Authority)],
+    active_elevations: &[ActiveElevation],
+    elevation_defs: &[Elevation],
+    overlay: Option<&AuthorityOverlay>,
+    req: &PolicyRequest,
+    checker: &dyn AuthorityEnforcer,
+) -> ResolutionTrace {
+    let mut steps = Vec::new();
+    let mut prev: Option<ResolvedAuthority> = None;
+    let mut refs: Vec<&Authority> = Vec::new();
+
+    for (label, layer) in layers {
+        refs.push(layer);
+        let current = resolve_authority(&refs);
+        steps.push(trace_step(label, prev.as_ref(), &current, req, checker));
+        prev = Some(current);
+    }
+
+    let mut resolved = prev.unwrap_or_else(|| resolve_authority(&[]));
+
+    if !active_elevations.is_empty() {
+        let elevated = resolve_with_elevations(&refs, active_elevations, elevation_defs);
+        steps.push(trace_step(
+            "elevations",
+            Some(&resolved),
+            &elevated,
+            req,
+            checker,
+        ));
+        resolved = elevated;
+    }
+
+    if let Some(overlay) = overlay {
+        let patched = apply_overlay(&resolved, overlay);
+        steps.push(trace_step(
+            "overlay",
+            Some(&resolved),
+            &patched,
+            req,
+            checker,
+        ));
+        resolved = patched;
+    }
+
+    ResolutionTrace { resolved, steps }
+}
+
+fn trace_step(
+    label: &str,
+    before: Option<&ResolvedAuthority>,
+    after: &ResolvedAuthority,
+    req: &PolicyRequest,
+    checker: &dyn AuthorityEnforcer,
+) -> TraceStep {
+    let mut changes = Vec::new();
+    let autonomy_name = |a: AutonomyLevel| format!("{a:?}").to_lowercase();
+
+    match before {
         None => {
-            eprintln!("  warn: {path}: missing 'authority' key");
-            return None;
+            changes.push(format!("autonomy = {}", autonomy_name(after.autonomy)));
+            for a in &after.allowed_actions {
+                changes.push(format!("allow {a}"));
+            }
+            for a in &after.denied_actions {
+                changes.push(format!("deny {a}"));
+            }
+            if after.scope.is_some() {
+                changes.push("scope set".into());
+            }
+            if after.limits.is_some() {
+                changes.push("limits set".into());
+            }
+        }
+        Some(b) => {
+            if b.autonomy != after.autonomy {
+                changes.push(format!(
+                    "autonomy {} -> {}",
+                    autonomy_name(b.autonomy),
+                    autonomy_name(after.autonomy)
+                ));
+            }
+            for a in &after.allowed_actions {
+                if !b.allowed_actions.contains(a) {
+                    changes.push(format!("allow {a}"));
+                }
+            }
+            for a in &b.allowed_actions {
+                if !after.allowed_actions.contains(a) {
+                    changes.push(format!("drop allow {a}"));
+                }
+            }
+            for a in &after.denied_actions {
+                if !b.denied_actions.contains(a) {
+                    changes.push(format!("deny {a}"));
+                }
+            }
+            let scope_json = |r: &ResolvedAuthority| serde_json::to_value(&r.scope).ok();
+            if scope_json(b) != scope_json(after) {
+                changes.push("scope replaced".into());
+            }
+            let limits_json = |r: &ResolvedAuthority| serde_json::to_value(&r.limits).ok();
+            if limits_json(b) != limits_json(after) {
+                changes.push("limits changed".into());
+            }
         }
+    }
+
+    let (decision, reason) = match checker.evaluate(req, after) {
+        Ok(PolicyDecision::Allow { reason }) => ("Allow", reason),
+        Ok(PolicyDecision::Deny { reason }) => ("Deny", reason),
+        Ok(PolicyDecision::NeedsApproval { reason }) => ("NeedsApproval", reason),
+        Err(e) => ("Error", e.to_string()),
     };
-    match serde_json::from_value(authority.clone()) {
+
+    let in_list = |list: &[ActionId]| req.action.as_ref().is_some_and(|a| list.contains(a));
+
+    TraceStep {
+        layer: label.to_string(),
+        changes,
+        autonomy: after.autonomy,
+        action_allowed: in_list(&after.allowed_actions),
+        action_denied: in_list(&after.denied_actions),
+        decision: decision.to_string(),
+        reason,
+    }
+}
+
+/// Labelled base layers in precedence order (lowest first): workspace
+/// defaults, the authority declared for the current phase, then the persona's
+/// own. The phase layer merges like the others (autonomy and limits take the
+/// minimum, denies accumulate), so a phase narrows the persona's authority
+/// but never widens it.
+pub fn base_layers<'a>(
+    defaults: Option<&'a Authority>,
+    phase: Option<&'a Authority>,
+    persona: &'a Authority,
+) -> Vec<(&'static str, &'a Authority)> {
+    let mut layers = Vec::new();
+    if let Some(defaults) = defaults {
+        layers.push(("workspace_defaults", defaults));
+    }
+    if let Some(phase) = phase {
+        layers.push(("phase", phase));
+    }
+    layers.push(("persona", persona));
+    layers
+}
+
+/// Workspace defaults file, relative to the working directory.
+pub const WORKSPACE_DEFAULTS: &str = ".ampersona/defaults.json";
+
+/// Defaults files in precedence order (lowest first), layered like git config:
+/// user-level `$XDG_CONFIG_HOME/ampersona/defaults.json` (`~/.config` when
+/// unset), then [`WORKSPACE_DEFAULTS`], then `config` (`amp --config`).
+pub fn defaults_paths(config: Option<&str>) -> Vec<String> {
+    let mut paths = Vec::new();
+    let config_home = std::env::var("XDG_CONFIG_HOME")
+        .ok()
+        .filter(|d| !d.is_empty())
+        .or_else(|| std::env::var("HOME").ok().map(|h| format!("{h}/.config")));
+    if let Some(dir) = config_home {
+        paths.push(format!("{dir}/ampersona/defaults.json"));
+    }
+    paths.push(WORKSPACE_DEFAULTS.to_string());
+    if let Some(config) = config {
+        paths.push(config.to_string());
+    }
+    paths
+}
+
+/// Load the defaults stack from [`defaults_paths`] as one Authority layer.
+///
+/// Each file's `authority` object overrides the ones before it key by key
+/// (nested objects merge, anything else is replaced), so a workspace can
+/// loosen a user-level default. Missing files are skipped, except an explicit
+/// `config`; files that cannot be parsed are skipped with a warning on stderr.
+pub fn load_defaults(config: Option<&str>) -> Option<Authority> {
+    let mut merged: Option<serde_json::Value> = None;
+    for path in defaults_paths(config) {
+        let content = match std::fs::read_to_string(&path) {
+            Ok(c) => c,
+            Err(e) => {
+                if config == Some(path.as_str()) {
+                    eprintln!("  warn: {path}: {e}");
+                }
+                continue;
+            }
+        };
+        let data: serde_json::Value = match serde_json::from_str(&content) {
+            Ok(d) => d,
+            Err(e) => {
+                eprintln!("  warn: {path}: unparseable JSON: {e}");
+                continue;
+            }
+        };
+        match data.get("authority") {
+            Some(a) => match merged.as_mut() {
+                Some(m) => override_json(m, a),
+                None => merged = Some(a.clone()),
+            },
+            None => eprintln!("  warn: {path}: missing 'authority' key"),
+        }
+    }
+    match serde_json::from_value(merged?) {
         Ok(a) => Some(a),
         Err(e) => {
-            eprintln!("  warn: {path}: invalid authority: {e}");
+            eprintln!("  warn: defaults: invalid authority: {e}");
             None
         }
     }
 }
 
+/// User-level and workspace defaults; [`load_defaults`] without `--config`.
+pub fn load_workspace_defaults() -> Option<Authority> {
+    load_defaults(None)
+}
+
+fn override_json(base: &mut serde_json::Value, top: &serde_json::Value) {
+    match (base.as_object_mut(), top.as_object()) {
+        (Some(b), Some(t)) => {
+            for (k, v) in t {
+                match b.get_mut(k) {
+                    Some(existing) => override_json(existing, v),
+                    None => {
+                        b.insert(k.clone(), v.clone());
+                    }
+                }
+            }
+        }
+        _ => *base = top.clone(),
+    }
+}
+
 fn merge_limits_opt(
     existing: Option<&ampersona_core::spec::authority::Limits>,
     new: &ampersona_core::spec::authority::Limits,
@@ -332,6 +654,52 @@ mod tests {
         assert_eq!(resolved.allowed_actions[0].to_string(), "read_file");
     }
 
+    #[test]
+    fn phase_layer_sits_between_defaults_and_persona() {
+        let defaults = make_authority(AutonomyLevel::Full, vec!["read_file"], vec![]);
+        let persona = make_authority(AutonomyLevel::Full, vec!["read_file"], vec![]);
+        let probation = make_authority(AutonomyLevel::Readonly, vec![], vec!["deploy"]);
+
+        let layers = base_layers(Some(&defaults), Some(&probation), &persona);
+        let labels: Vec<_> = layers.iter().map(|(label, _)| *label).collect();
+        assert_eq!(labels, ["workspace_defaults", "phase", "persona"]);
+
+        let refs: Vec<_> = layers.iter().map(|(_, layer)| *layer).collect();
+        let resolved = resolve_authority(&refs);
+        assert_eq!(resolved.autonomy, AutonomyLevel::Readonly);
+        assert_eq!(resolved.denied_actions[0].to_string(), "deploy");
+
+        // No declared phase authority: the persona alone decides.
+        let refs: Vec<_> = base_layers(None, None, &persona)
+            .into_iter()
+            .map(|(_, layer)| layer)
+            .collect();
+        assert_eq!(resolve_authority(&refs).autonomy, AutonomyLevel::Full);
+    }
+
+    #[test]
+    fn later_defaults_override_key_by_key() {
+        let mut user = serde_json::json!({
+            "autonomy": "readonly",
+            "limits": { "max_actions_per_hour": 10, "max_cost_per_day_cents": 100 },
+            "actions": { "deny": ["git_push"] }
+        });
+        let workspace = serde_json::json!({
+            "autonomy": "supervised",
+            "limits": { "max_actions_per_hour": 50 },
+            "actions": { "deny": ["deploy"] }
+        });
+        override_json(&mut user, &workspace);
+        assert_eq!(
+            user,
+            serde_json::json!({
+                "autonomy": "supervised",
+                "limits": { "max_actions_per_hour": 50, "max_cost_per_day_cents": 100 },
+                "actions": { "deny": ["deploy"] }
+            })
+        );
+    }
+
     #[test]
     fn autonomy_is_min() {
         let a = make_authority(AutonomyLevel::Full, vec![], vec![]);
@@ -414,6 +782,7 @@ mod tests {
             grants: serde_json::json!({"actions.allow": ["git_push_main"]}),
             requires: ampersona_core::types::GateApproval::Human,
             ttl_seconds: 3600,
+            max_ttl_seconds: None,
             reason_required: true,
         }];
         let active = vec![ActiveElevation {
@@ -441,6 +810,7 @@ mod tests {
             grants: serde_json::json!({"actions.allow": ["git_push_main"]}),
             requires: ampersona_core::types::GateApproval::Human,
             ttl_seconds: 3600,
+            max_ttl_seconds: None,
             reason_required: true,
         }];
         let active = vec![ActiveElevation {
@@ -472,6 +842,7 @@ mod tests {
             grants: serde_json::json!({"actions.allow": ["git_push_main"]}),
             requires: ampersona_core::types::GateApproval::Human,
             ttl_seconds: 3600,
+            max_ttl_seconds: None,
             reason_required: true,
         }];
         let active = vec![ActiveElevation {
@@ -540,6 +911,42 @@ mod tests {
         );
     }
 
+    #[test]
+    fn overlay_no_upgrade_rejects_autonomy_raise() {
+        let persona = make_authority(AutonomyLevel::Readonly, vec!["read_file"], vec![]);
+        let base = resolve_authority(&[&persona]);
+
+        let upgrade = make_overlay(Some(AutonomyLevel::Full), None, None);
+        let err = apply_overlay_no_upgrade(&base, &upgrade).unwrap_err();
+        assert_eq!(err.code(), "E_AUTONOMY_UPGRADE");
+        assert!(err.to_string().contains("readonly -> full"));
+
+        let same = make_overlay(Some(AutonomyLevel::Readonly), None, None);
+        let effective = apply_overlay_no_upgrade(&base, &same).unwrap();
+        assert_eq!(effective.autonomy, AutonomyLevel::Readonly);
+    }
+
+    #[test]
+    fn overlay_changes_report_autonomy_override() {
+        let persona = make_authority(AutonomyLevel::Supervised, vec!["read_file"], vec![]);
+        let base = resolve_authority(&[&persona]);
+
+        let overlay = make_overlay(Some(AutonomyLevel::Full), None, None);
+        let (patched, changes) = apply_overlay_with_changes(&base, &overlay);
+        assert_eq!(patched.autonomy, AutonomyLevel::Full);
+        assert_eq!(
+            changes,
+            [OverlayChange {
+                field: "autonomy".into(),
+                before: serde_json::json!("supervised"),
+                after: serde_json::json!("full"),
+            }]
+        );
+
+        let same = make_overlay(Some(AutonomyLevel::Supervised), None, None);
+        assert!(apply_overlay_with_changes(&base, &same).1.is_empty());
+    }
+
     #[test]
     fn overlay_adds_allowed_actions() {
         // Persona: [read_file], overlay: [read_file, deploy] → deploy should be allowed.
@@ -703,6 +1110,8 @@ mod tests {
                     action: "delete_production_data".parse().unwrap(),
                     reason: "Retention policy".into(),
                     compliance_ref: Some("ISO 9001:2015 §7.5".into()),
+                    review_by: None,
+                    expires_at: None,
                 }]),
                 scoped: None,
             }),
@@ -720,4 +1129,124 @@ mod tests {
         assert_eq!(meta.reason.as_deref(), Some("Retention policy"));
         assert_eq!(meta.compliance_ref.as_deref(), Some("ISO 9001:2015 §7.5"));
     }
+
+    fn dated_deny(
+        action: &str,
+        review_by: Option<chrono::DateTime<Utc>>,
+        expires_at: Option<chrono::DateTime<Utc>>,
+    ) -> DenyEntry {
+        DenyEntry::WithReason {
+            action: action.parse().unwrap(),
+            reason: "CAPA hold".into(),
+            compliance_ref: Some("ISO 9001:2015 §10.2".into()),
+            review_by,
+            expires_at,
+        }
+    }
+
+    #[test]
+    fn deny_review_and_expiry() {
+        let now = Utc::now();
+        let mut a = make_authority(
+            AutonomyLevel::Full,
+            vec!["read_file", "deploy", "merge_pr", "git_push"],
+            vec![],
+        );
+        a.actions.as_mut().unwrap().deny = Some(vec![
+            dated_deny("deploy", Some(now + Duration::days(30)), None),
+            dated_deny("merge_pr", Some(now - Duration::days(1)), None),
+            dated_deny("git_push", None, Some(now - Duration::days(1))),
+        ]);
+        let resolved = resolve_authority(&[&a]);
+        let checker = crate::policy::checker::DefaultPolicyChecker;
+        use ampersona_core::traits::AuthorityEnforcer;
+        let decide = |action: &str| checker.evaluate(&trace_request(action), &resolved).unwrap();
+
+        // Active: denied, no review note
+        match decide("deploy") {
+            PolicyDecision::Deny { reason } => assert!(!reason.contains("review")),
+            d => panic!("expected deny, got {d:?}"),
+        }
+        // Review overdue: still denied, but flagged
+        assert!(resolved.deny_metadata["merge_pr"].review_overdue());
+        match decide("merge_pr") {
+            PolicyDecision::Deny { reason } => assert!(reason.contains("past review date")),
+            d => panic!("expected deny, got {d:?}"),
+        }
+        // Expired: no longer in force, the allow list applies again
+        assert!(!resolved
+            .denied_actions
+            .contains(&"git_push".parse().unwrap()));
+        assert!(!resolved.deny_metadata.contains_key("git_push"));
+        assert!(matches!(decide("git_push"), PolicyDecision::Allow { .. }));
+    }
+
+    fn trace_request(action: &str) -> PolicyRequest {
+        PolicyRequest {
+            action: Some(action.parse().unwrap()),
+            path: None,
+            context: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn trace_records_each_layer_and_deciding_layer() {
+        let defaults = make_authority(AutonomyLevel::Full, vec!["read_file", "deploy"], vec![]);
+        let persona = make_authority(AutonomyLevel::Full, vec!["read_file"], vec!["deploy"]);
+        let checker = crate::policy::checker::DefaultPolicyChecker;
+
+        let trace = resolve_traced(
+            &[("workspace_defaults", &defaults), ("persona", &persona)],
+            &[],
+            &[],
+            None,
+            &trace_request("deploy"),
+            &checker,
+        );
+        assert_eq!(trace.steps.len(), 2);
+        assert!(trace.steps[0].action_allowed);
+        assert_eq!(trace.steps[0].decision, "Allow");
+        assert!(trace.steps[1].action_denied);
+        assert!(trace.steps[1].changes.iter().any(|c| c == "deny deploy"));
+        assert_eq!(trace.steps[1].decision, "Deny");
+        assert_eq!(trace.deciding_layer(), Some("persona"));
+    }
+
+    #[test]
+    fn trace_deciding_layer_is_earliest_stable_layer() {
+        let defaults = make_authority(AutonomyLevel::Readonly, vec![], vec![]);
+        let persona = make_authority(AutonomyLevel::Full, vec!["write_file"], vec![]);
+        let checker = crate::policy::checker::DefaultPolicyChecker;
+
+        let trace = resolve_traced(
+            &[("workspace_defaults", &defaults), ("persona", &persona)],
+            &[],
+            &[],
+            None,
+            &trace_request("write_file"),
+            &checker,
+        );
+        assert_eq!(trace.steps[1].decision, "Deny");
+        assert_eq!(trace.deciding_layer(), Some("workspace_defaults"));
+    }
+
+    #[test]
+    fn trace_includes_overlay_step() {
+        let persona = make_authority(AutonomyLevel::Readonly, vec!["write_file"], vec![]);
+        let overlay: AuthorityOverlay =
+            serde_json::from_value(serde_json::json!({"autonomy": "full"})).unwrap();
+        let checker = crate::policy::checker::DefaultPolicyChecker;
+
+        let trace = resolve_traced(
+            &[("persona", &persona)],
+            &[],
+            &[],
+            Some(&overlay),
+            &trace_request("write_file"),
+            &checker,
+        );
+        assert_eq!(trace.steps.last().unwrap().layer, "overlay");
+        assert_eq!(trace.resolved.autonomy, AutonomyLevel::Full);
+        assert_eq!(trace.deciding_layer(), Some("overlay"));
+    }
 }
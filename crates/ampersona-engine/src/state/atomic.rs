@@ -1,76 +1,289 @@
-use anyhow::{bail, Context, Result};
 use std::io::Write;
+use std::time::{Duration, Instant};
 
-/// Write content atomically: write to temp file, fsync, rename.
+use crate::error::{EngineError, Result};
+
+/// Write content atomically: write to temp file, fsync, rename, fsync directory.
+///
+/// Invariant: after this returns `Ok`, `path` holds either the previous content
+/// or `content` in full — never a truncated mix — even across a crash. The temp
+/// file is `sync_all`ed before the rename so its data is on disk before it becomes
+/// visible; with the `durable` feature (default) the parent directory is fsynced
+/// after the rename so the rename itself survives a crash. On error the temp file
+/// is removed and `path` is left untouched. A missing parent directory (e.g.
+/// under `--state-dir`) is created first.
 pub fn atomic_write(path: &str, content: &[u8]) -> Result<()> {
+    let dir = create_parent(path)?;
+    let temp_path = dir.join(format!(".{}.tmp", uuid_v4_simple()));
+
+    let result = write_and_rename(&temp_path, path, content);
+    if result.is_err() {
+        let _ = std::fs::remove_file(&temp_path);
+    }
+    result?;
+
+    #[cfg(feature = "durable")]
+    sync_dir(dir).map_err(|e| EngineError::io(format!("cannot sync directory for {path}"), e))?;
+
+    Ok(())
+}
+
+/// The directory `path` lives in, created if it doesn't exist yet.
+fn create_parent(path: &str) -> Result<&std::path::Path> {
     let dir = std::path::Path::new(path)
         .parent()
+        .filter(|d| !d.as_os_str().is_empty())
         .unwrap_or(std::path::Path::new("."));
-    let temp_path = dir.join(format!(".{}.tmp", uuid_v4_simple()));
+    std::fs::create_dir_all(dir)
+        .map_err(|e| EngineError::io(format!("cannot create directory for {path}"), e))?;
+    Ok(dir)
+}
 
-    let mut file = std::fs::File::create(&temp_path)
-        .with_context(|| format!("cannot create temp file for {path}"))?;
-    file.write_all(content)?;
-    file.sync_all()?;
+fn write_and_rename(temp_path: &std::path::Path, path: &str, content: &[u8]) -> Result<()> {
+    let mut file = std::fs::File::create(temp_path)
+        .map_err(|e| EngineError::io(format!("cannot create temp file for {path}"), e))?;
+    file.write_all(content)
+        .and_then(|_| file.sync_all())
+        .map_err(|e| EngineError::io(format!("cannot write temp file for {path}"), e))?;
     drop(file);
 
-    std::fs::rename(&temp_path, path).with_context(|| format!("cannot rename temp to {path}"))?;
+    std::fs::rename(temp_path, path)
+        .map_err(|e| EngineError::io(format!("cannot rename temp to {path}"), e))
+}
 
+/// Fsync a directory so a rename inside it is durable.
+///
+/// Directories can't be opened for syncing on Windows; there the rename is
+/// already durable once `MoveFileEx` returns, so this is a no-op.
+#[cfg(all(feature = "durable", unix))]
+fn sync_dir(dir: &std::path::Path) -> std::io::Result<()> {
+    std::fs::File::open(dir)?.sync_all()
+}
+
+#[cfg(all(feature = "durable", not(unix)))]
+fn sync_dir(_dir: &std::path::Path) -> std::io::Result<()> {
     Ok(())
 }
 
+/// Seconds after which a lock is considered stale when its holder can't be checked.
+pub const STALE_LOCK_SECS: i64 = 60;
+
+/// Interval between acquisition attempts while waiting on a held lock.
+const LOCK_RETRY_INTERVAL: Duration = Duration::from_millis(25);
+
+/// A stale lock that was removed during acquisition.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StaleLock {
+    /// PID recorded by the previous holder, if readable.
+    pub pid: Option<u32>,
+    /// Unix timestamp recorded by the previous holder, if readable.
+    pub locked_at: Option<i64>,
+    /// Why the lock was considered stale: `holder_dead` or `expired`.
+    pub reason: &'static str,
+}
+
 /// Advisory lock for state files. Prevents concurrent writers.
 ///
 /// Creates a .lock file alongside the state file.
 /// The lock file contains the PID and timestamp.
 pub struct AdvisoryLock {
     lock_path: String,
+    pid: u32,
+    locked_at: i64,
+    held: bool,
+    reclaimed: Option<StaleLock>,
 }
 
 impl AdvisoryLock {
     /// Acquire an advisory lock. Returns error if lock is already held.
     pub fn acquire(state_path: &str) -> Result<Self> {
-        let lock_path = format!("{state_path}.lock");
+        Self::acquire_with_timeout(state_path, Duration::ZERO)
+    }
 
-        // Check if lock already exists and is stale
-        if std::path::Path::new(&lock_path).exists() {
-            let content = std::fs::read_to_string(&lock_path).unwrap_or_default();
-            if let Some(ts_str) = content.lines().nth(1) {
-                if let Ok(ts) = ts_str.parse::<i64>() {
-                    let now = chrono::Utc::now().timestamp();
-                    // Consider lock stale after 60 seconds
-                    if now - ts > 60 {
-                        let _ = std::fs::remove_file(&lock_path);
-                    } else {
-                        bail!("state file is locked by another process (lock: {lock_path})");
+    /// Acquire an advisory lock, waiting up to `timeout` for a live holder to release it.
+    ///
+    /// Stale locks (see [`stale_reason`]) are removed and reported via
+    /// [`AdvisoryLock::reclaimed`].
+    pub fn acquire_with_timeout(state_path: &str, timeout: Duration) -> Result<Self> {
+        let lock_path = format!("{state_path}.lock");
+        create_parent(&lock_path)?;
+        let deadline = Instant::now() + timeout;
+        let mut reclaimed = None;
+
+        loop {
+            match std::fs::OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(&lock_path)
+            {
+                Ok(mut file) => {
+                    let pid = std::process::id();
+                    let ts = chrono::Utc::now().timestamp();
+                    file.write_all(format!("{pid}\n{ts}\n").as_bytes())
+                        .map_err(|e| {
+                            EngineError::io(format!("cannot acquire lock {lock_path}"), e)
+                        })?;
+                    return Ok(Self {
+                        lock_path,
+                        pid,
+                        locked_at: ts,
+                        held: true,
+                        reclaimed,
+                    });
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+                    if let Some(stale) = stale_lock(&lock_path) {
+                        if reclaim_stale(&lock_path, &stale) {
+                            reclaimed = Some(stale);
+                            continue;
+                        }
+                    }
+                    if Instant::now() >= deadline {
+                        return Err(EngineError::LockContention { lock_path });
                     }
+                    std::thread::sleep(LOCK_RETRY_INTERVAL);
+                }
+                Err(e) => {
+                    return Err(EngineError::io(
+                        format!("cannot acquire lock {lock_path}"),
+                        e,
+                    ))
                 }
             }
         }
+    }
 
-        let pid = std::process::id();
-        let ts = chrono::Utc::now().timestamp();
-        let lock_content = format!("{pid}\n{ts}\n");
-        std::fs::write(&lock_path, lock_content)
-            .with_context(|| format!("cannot acquire lock {lock_path}"))?;
-
-        Ok(Self { lock_path })
+    /// The stale lock removed while acquiring this one, if any.
+    pub fn reclaimed(&self) -> Option<&StaleLock> {
+        self.reclaimed.as_ref()
     }
 
     /// Release the advisory lock.
-    pub fn release(self) -> Result<()> {
-        if std::path::Path::new(&self.lock_path).exists() {
-            std::fs::remove_file(&self.lock_path)
-                .with_context(|| format!("cannot release lock {}", self.lock_path))?;
+    pub fn release(mut self) -> Result<()> {
+        self.unlock()
+            .map_err(|e| EngineError::io(format!("cannot release lock {}", self.lock_path), e))
+    }
+
+    /// Remove the lock file, unless it was reclaimed from us and someone else
+    /// holds it now.
+    fn unlock(&mut self) -> std::io::Result<()> {
+        if !std::mem::take(&mut self.held) {
+            return Ok(());
         }
-        Ok(())
+        remove_if_held_by(&self.lock_path, Some(self.pid), Some(self.locked_at)).map(|_| ())
     }
 }
 
 impl Drop for AdvisoryLock {
     fn drop(&mut self) {
-        let _ = std::fs::remove_file(&self.lock_path);
+        let _ = self.unlock();
+    }
+}
+
+/// Inspect an existing lock file and decide whether it can be reclaimed.
+///
+/// A lock whose contents can't be parsed (e.g. caught mid-write) falls back to
+/// the file's mtime for the age check.
+pub fn stale_lock(lock_path: &str) -> Option<StaleLock> {
+    let (pid, locked_at) = lock_holder(lock_path);
+    let now = chrono::Utc::now().timestamp();
+    stale_reason(pid, locked_at, now).map(|reason| StaleLock {
+        pid,
+        locked_at,
+        reason,
+    })
+}
+
+/// Why a lock held by `pid` since `locked_at` may be reclaimed at `now`, if it may.
+///
+/// A holder known to be dead frees its lock at once (`holder_dead`). Age only
+/// expires a lock whose holder can't be checked — no readable PID, or no way to
+/// probe it on this platform — once it is older than [`STALE_LOCK_SECS`]
+/// (`expired`). A running holder keeps its lock however long it takes.
+pub fn stale_reason(pid: Option<u32>, locked_at: Option<i64>, now: i64) -> Option<&'static str> {
+    match pid.and_then(pid_alive) {
+        Some(true) => None,
+        Some(false) => Some("holder_dead"),
+        None => match locked_at {
+            Some(ts) if now - ts > STALE_LOCK_SECS => Some("expired"),
+            _ => None,
+        },
+    }
+}
+
+/// The PID and timestamp recorded in a lock file, if readable.
+///
+/// A lock whose contents can't be parsed (e.g. caught mid-write) falls back to
+/// the file's mtime for the timestamp.
+fn lock_holder(lock_path: &str) -> (Option<u32>, Option<i64>) {
+    let content = std::fs::read_to_string(lock_path).unwrap_or_default();
+    let mut lines = content.lines();
+    let pid = lines.next().and_then(|l| l.trim().parse::<u32>().ok());
+    let locked_at = lines
+        .next()
+        .and_then(|l| l.trim().parse::<i64>().ok())
+        .or_else(|| {
+            std::fs::metadata(lock_path)
+                .and_then(|m| m.modified())
+                .ok()
+                .map(|t| chrono::DateTime::<chrono::Utc>::from(t).timestamp())
+        });
+    (pid, locked_at)
+}
+
+/// Remove the lock file judged `stale` without racing a writer that takes the
+/// lock in between. Returns whether the stale lock was removed here.
+pub fn reclaim_stale(lock_path: &str, stale: &StaleLock) -> bool {
+    remove_if_held_by(lock_path, stale.pid, stale.locked_at).unwrap_or(false)
+}
+
+/// Remove the lock file only if it still records `pid` and `locked_at`.
+///
+/// The file is first renamed aside, so a concurrent remover can't delete what
+/// this one moves. It is deleted only if it still is the expected lock; a
+/// fresh lock taken since is put back. Returns whether the lock was removed
+/// here; a lock file that is already gone is not an error.
+fn remove_if_held_by(
+    lock_path: &str,
+    pid: Option<u32>,
+    locked_at: Option<i64>,
+) -> std::io::Result<bool> {
+    let aside = format!(
+        "{lock_path}.{}-{}.stale",
+        std::process::id(),
+        uuid_v4_simple()
+    );
+    match std::fs::rename(lock_path, &aside) {
+        Ok(()) => {}
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(false),
+        Err(e) => return Err(e),
+    }
+    if lock_holder(&aside) == (pid, locked_at) {
+        std::fs::remove_file(&aside)?;
+        return Ok(true);
+    }
+    // A hard link never replaces a lock taken since the rename
+    if let Err(e) = std::fs::hard_link(&aside, lock_path) {
+        if e.kind() != std::io::ErrorKind::AlreadyExists {
+            let _ = std::fs::rename(&aside, lock_path);
+        }
     }
+    let _ = std::fs::remove_file(&aside);
+    Ok(false)
+}
+
+/// Whether a process with this PID is still running, if that can be told.
+///
+/// Only Linux (via `/proc`) can answer this without unsafe code; elsewhere the
+/// answer is unknown and staleness falls back to lock age.
+#[cfg(target_os = "linux")]
+fn pid_alive(pid: u32) -> Option<bool> {
+    Some(std::path::Path::new(&format!("/proc/{pid}")).exists())
+}
+
+#[cfg(not(target_os = "linux"))]
+fn pid_alive(_pid: u32) -> Option<bool> {
+    None
 }
 
 /// Simple pseudo-UUID for temp file names.
@@ -97,6 +310,36 @@ mod tests {
         assert_eq!(content, "hello world");
     }
 
+    #[test]
+    fn atomic_write_leaves_no_temp_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("state.json");
+        let path_str = path.to_str().unwrap();
+
+        atomic_write(path_str, b"one").unwrap();
+        atomic_write(path_str, b"two").unwrap();
+
+        let names: Vec<_> = std::fs::read_dir(dir.path())
+            .unwrap()
+            .map(|e| e.unwrap().file_name().into_string().unwrap())
+            .collect();
+        assert_eq!(names, vec!["state.json".to_string()]);
+    }
+
+    #[test]
+    fn atomic_write_failure_preserves_target() {
+        let dir = tempfile::tempdir().unwrap();
+        // Renaming a file over a non-empty directory fails after the temp
+        // file has been written; the target and directory must be untouched.
+        let target = dir.path().join("occupied");
+        std::fs::create_dir(&target).unwrap();
+        std::fs::write(target.join("keep"), "x").unwrap();
+
+        assert!(atomic_write(target.to_str().unwrap(), b"new").is_err());
+        assert_eq!(std::fs::read_to_string(target.join("keep")).unwrap(), "x");
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+
     #[test]
     fn advisory_lock_acquire_and_release() {
         let file = NamedTempFile::new().unwrap();
@@ -111,6 +354,24 @@ mod tests {
         assert!(!std::path::Path::new(&format!("{path}.lock")).exists());
     }
 
+    #[test]
+    fn dropping_a_reclaimed_lock_leaves_the_new_holder() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("state.json");
+        let path = path.to_str().unwrap();
+        let lock_path = format!("{path}.lock");
+
+        let lock = AdvisoryLock::acquire(path).unwrap();
+        // Another writer reclaimed ours and now holds the lock.
+        std::fs::write(&lock_path, "4294967295\n0\n").unwrap();
+        drop(lock);
+        assert_eq!(
+            std::fs::read_to_string(&lock_path).unwrap(),
+            "4294967295\n0\n"
+        );
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+
     #[test]
     fn advisory_lock_blocks_concurrent() {
         let file = NamedTempFile::new().unwrap();
@@ -118,7 +379,7 @@ mod tests {
 
         let _lock1 = AdvisoryLock::acquire(path).unwrap();
         let result = AdvisoryLock::acquire(path);
-        assert!(result.is_err());
+        assert!(matches!(result, Err(EngineError::LockContention { .. })));
     }
 
     #[test]
@@ -138,6 +399,100 @@ mod tests {
         let _lock2 = AdvisoryLock::acquire(&path).unwrap();
     }
 
+    #[test]
+    fn advisory_lock_waits_for_release() {
+        let file = NamedTempFile::new().unwrap();
+        let path = file.path().to_str().unwrap().to_string();
+
+        let lock1 = AdvisoryLock::acquire(&path).unwrap();
+        let waiter = {
+            let path = path.clone();
+            std::thread::spawn(move || {
+                AdvisoryLock::acquire_with_timeout(&path, Duration::from_secs(5)).map(|_| ())
+            })
+        };
+        std::thread::sleep(Duration::from_millis(100));
+        lock1.release().unwrap();
+
+        assert!(waiter.join().unwrap().is_ok());
+    }
+
+    #[test]
+    fn advisory_lock_times_out() {
+        let file = NamedTempFile::new().unwrap();
+        let path = file.path().to_str().unwrap();
+
+        let _lock1 = AdvisoryLock::acquire(path).unwrap();
+        let started = Instant::now();
+        let result = AdvisoryLock::acquire_with_timeout(path, Duration::from_millis(100));
+        assert!(matches!(result, Err(EngineError::LockContention { .. })));
+        assert!(started.elapsed() >= Duration::from_millis(100));
+    }
+
+    #[test]
+    fn advisory_lock_reclaims_expired() {
+        let file = NamedTempFile::new().unwrap();
+        let path = file.path().to_str().unwrap();
+        let old = chrono::Utc::now().timestamp() - STALE_LOCK_SECS - 10;
+        // No readable PID: only the age can retire this lock.
+        std::fs::write(format!("{path}.lock"), format!("?\n{old}\n")).unwrap();
+
+        let lock = AdvisoryLock::acquire(path).unwrap();
+        let stale = lock.reclaimed().unwrap();
+        assert_eq!(stale.reason, "expired");
+        assert_eq!(stale.locked_at, Some(old));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn advisory_lock_keeps_live_holder_past_age() {
+        let file = NamedTempFile::new().unwrap();
+        let path = file.path().to_str().unwrap();
+        let old = chrono::Utc::now().timestamp() - STALE_LOCK_SECS - 10;
+        std::fs::write(
+            format!("{path}.lock"),
+            format!("{}\n{old}\n", std::process::id()),
+        )
+        .unwrap();
+
+        let result = AdvisoryLock::acquire(path);
+        assert!(matches!(result, Err(EngineError::LockContention { .. })));
+    }
+
+    #[test]
+    fn reclaim_puts_back_a_lock_taken_since_the_check() {
+        let dir = tempfile::tempdir().unwrap();
+        let lock_path = dir.path().join("state.json.lock");
+        let lock_path = lock_path.to_str().unwrap();
+        let old = chrono::Utc::now().timestamp() - STALE_LOCK_SECS - 10;
+        std::fs::write(lock_path, format!("?\n{old}\n")).unwrap();
+        let stale = stale_lock(lock_path).unwrap();
+
+        // Another writer reclaims it and takes a fresh lock before we act
+        let now = chrono::Utc::now().timestamp();
+        std::fs::write(lock_path, format!("?\n{now}\n")).unwrap();
+
+        assert!(!reclaim_stale(lock_path, &stale));
+        assert_eq!(
+            std::fs::read_to_string(lock_path).unwrap(),
+            format!("?\n{now}\n")
+        );
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn advisory_lock_reclaims_dead_holder() {
+        let file = NamedTempFile::new().unwrap();
+        let path = file.path().to_str().unwrap();
+        let now = chrono::Utc::now().timestamp();
+        // PIDs are capped well below u32::MAX, so this holder can't be alive.
+        std::fs::write(format!("{path}.lock"), format!("4294967295\n{now}\n")).unwrap();
+
+        let lock = AdvisoryLock::acquire(path).unwrap();
+        assert_eq!(lock.reclaimed().unwrap().reason, "holder_dead");
+    }
+
     #[test]
     fn atomic_write_is_idempotent() {
         let file = NamedTempFile::new().unwrap();
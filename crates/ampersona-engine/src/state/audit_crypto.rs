@@ -0,0 +1,307 @@
+//! Optional encryption of audit entries at rest (AES-256-GCM).
+//!
+//! An encrypted entry keeps `event_type`, `prev_hash` and `ts` in the clear
+//! and moves the whole entry into an `enc` envelope:
+//!
+//! ```text
+//! {"event_type": "...", "prev_hash": "...", "ts": "...",
+//!  "enc": {"alg": "A256GCM", "nonce": hex, "ciphertext": hex}}
+//! ```
+//!
+//! The hash chain is computed over these lines as written, so
+//! `verify_chain` works without the key. `prev_hash` is bound to the
+//! ciphertext as associated data, so an envelope cannot be moved to another
+//! position in the chain. Encryption itself needs the `crypto` feature; without
+//! it a configured key makes appends fail instead of writing plaintext.
+
+use serde_json::Value;
+
+use crate::error::{EngineError, Result};
+
+/// Hex-encoded 32-byte key; when set, new audit entries are encrypted.
+pub const KEY_ENV: &str = "AMPERSONA_AUDIT_KEY";
+/// Path to a key file, used when [`KEY_ENV`] is unset.
+pub const KEY_FILE_ENV: &str = "AMPERSONA_AUDIT_KEY_FILE";
+
+/// Envelope `alg` value.
+pub const ALGORITHM: &str = "A256GCM";
+
+/// An AES-256 key for audit entries.
+#[derive(Clone)]
+pub struct AuditKey([u8; 32]);
+
+impl std::fmt::Debug for AuditKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("AuditKey(..)")
+    }
+}
+
+impl AuditKey {
+    /// 32 raw bytes, or 64 hex characters (surrounding whitespace ignored).
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        if let Ok(raw) = <[u8; 32]>::try_from(bytes) {
+            return Ok(Self(raw));
+        }
+        let text = std::str::from_utf8(bytes).unwrap_or_default().trim();
+        from_hex(text)
+            .and_then(|raw| <[u8; 32]>::try_from(raw).ok())
+            .map(Self)
+            .ok_or_else(|| {
+                EngineError::AuditCrypto("audit key must be 32 bytes or 64 hex characters".into())
+            })
+    }
+
+    /// Read a key file (see [`AuditKey::from_bytes`]).
+    pub fn from_file(path: &str) -> Result<Self> {
+        let bytes = std::fs::read(path).map_err(|e| EngineError::read(path, e))?;
+        Self::from_bytes(&bytes)
+    }
+
+    /// The key configured through [`KEY_ENV`] or [`KEY_FILE_ENV`], if any.
+    pub fn from_env() -> Result<Option<Self>> {
+        if let Ok(hex) = std::env::var(KEY_ENV) {
+            return Self::from_bytes(hex.as_bytes()).map(Some);
+        }
+        match std::env::var(KEY_FILE_ENV) {
+            Ok(path) => Self::from_file(&path).map(Some),
+            Err(_) => Ok(None),
+        }
+    }
+}
+
+/// Whether `entry` is an encrypted envelope.
+pub fn is_encrypted(entry: &Value) -> bool {
+    entry.get("enc").is_some_and(Value::is_object)
+}
+
+/// Wrap a complete entry (with `prev_hash` and `ts`) in an envelope.
+pub fn encrypt_entry(entry: &Value, key: &AuditKey) -> Result<Value> {
+    let prev_hash = entry
+        .get("prev_hash")
+        .and_then(Value::as_str)
+        .ok_or_else(|| EngineError::AuditCrypto("entry has no prev_hash".into()))?;
+    let plaintext = serde_json::to_vec(entry)?;
+    let (nonce, ciphertext) = seal(key, &plaintext, prev_hash.as_bytes())?;
+
+    let mut envelope = serde_json::Map::new();
+    for field in ["event_type", "prev_hash", "ts"] {
+        if let Some(value) = entry.get(field) {
+            envelope.insert(field.into(), value.clone());
+        }
+    }
+    envelope.insert(
+        "enc".into(),
+        serde_json::json!({
+            "alg": ALGORITHM,
+            "nonce": to_hex(&nonce),
+            "ciphertext": to_hex(&ciphertext),
+        }),
+    );
+    Ok(Value::Object(envelope))
+}
+
+/// Recover the original entry from an envelope; plaintext entries are
+/// returned unchanged.
+pub fn decrypt_entry(entry: &Value, key: &AuditKey) -> Result<Value> {
+    if !is_encrypted(entry) {
+        return Ok(entry.clone());
+    }
+    let enc = &entry["enc"];
+    if enc.get("alg").and_then(Value::as_str) != Some(ALGORITHM) {
+        return Err(EngineError::AuditCrypto(format!(
+            "unsupported audit encryption {}",
+            enc.get("alg").unwrap_or(&Value::Null)
+        )));
+    }
+    let field = |name: &str| {
+        enc.get(name)
+            .and_then(Value::as_str)
+            .and_then(from_hex)
+            .ok_or_else(|| EngineError::AuditCrypto(format!("envelope {name} is not hex")))
+    };
+    let prev_hash = entry
+        .get("prev_hash")
+        .and_then(Value::as_str)
+        .unwrap_or("genesis");
+    let plaintext = open(
+        key,
+        &field("nonce")?,
+        &field("ciphertext")?,
+        prev_hash.as_bytes(),
+    )?;
+    let decrypted: Value = serde_json::from_slice(&plaintext)?;
+    // The clear fields are not authenticated on their own; they must agree.
+    for name in ["event_type", "ts"] {
+        if entry.get(name) != decrypted.get(name) {
+            return Err(EngineError::AuditCrypto(format!(
+                "clear {name} does not match the encrypted entry"
+            )));
+        }
+    }
+    Ok(decrypted)
+}
+
+/// Read every entry of an audit log, decrypting envelopes with `key`.
+pub fn read_decrypted(path: &str, key: &AuditKey) -> Result<Vec<Value>> {
+    let content = std::fs::read_to_string(path).map_err(|e| EngineError::read(path, e))?;
+    decrypt_lines(path, content.lines(), key)
+}
+
+/// [`read_decrypted`] over log lines from any source; `path` labels errors.
+pub fn decrypt_lines<'a>(
+    path: &str,
+    lines: impl IntoIterator<Item = &'a str>,
+    key: &AuditKey,
+) -> Result<Vec<Value>> {
+    let mut entries = Vec::new();
+    for (i, line) in lines.into_iter().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let entry: Value =
+            serde_json::from_str(line).map_err(|source| EngineError::InvalidJsonLine {
+                path: path.to_string(),
+                line: i + 1,
+                source,
+            })?;
+        let entry = decrypt_entry(&entry, key).map_err(|e| {
+            EngineError::AuditCrypto(format!("{path}: entry at line {}: {e}", i + 1))
+        })?;
+        entries.push(entry);
+    }
+    Ok(entries)
+}
+
+#[cfg(feature = "crypto")]
+fn seal(key: &AuditKey, plaintext: &[u8], aad: &[u8]) -> Result<(Vec<u8>, Vec<u8>)> {
+    use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+    use aes_gcm::{Aes256Gcm, Key};
+
+    let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key.0));
+    let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+    let ciphertext = cipher
+        .encrypt(
+            &nonce,
+            Payload {
+                msg: plaintext,
+                aad,
+            },
+        )
+        .map_err(|_| EngineError::AuditCrypto("audit entry encryption failed".into()))?;
+    Ok((nonce.to_vec(), ciphertext))
+}
+
+#[cfg(feature = "crypto")]
+fn open(key: &AuditKey, nonce: &[u8], ciphertext: &[u8], aad: &[u8]) -> Result<Vec<u8>> {
+    use aes_gcm::aead::{Aead, KeyInit, Payload};
+    use aes_gcm::{Aes256Gcm, Key, Nonce};
+
+    if nonce.len() != 12 {
+        return Err(EngineError::AuditCrypto(
+            "envelope nonce must be 12 bytes".into(),
+        ));
+    }
+    let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key.0));
+    cipher
+        .decrypt(
+            Nonce::from_slice(nonce),
+            Payload {
+                msg: ciphertext,
+                aad,
+            },
+        )
+        .map_err(|_| {
+            EngineError::AuditCrypto("decryption failed (wrong key or tampered entry)".into())
+        })
+}
+
+#[cfg(not(feature = "crypto"))]
+fn seal(key: &AuditKey, _: &[u8], _: &[u8]) -> Result<(Vec<u8>, Vec<u8>)> {
+    Err(not_built(key))
+}
+
+#[cfg(not(feature = "crypto"))]
+fn open(key: &AuditKey, _: &[u8], _: &[u8], _: &[u8]) -> Result<Vec<u8>> {
+    Err(not_built(key))
+}
+
+/// The key is loaded and validated either way; only the cipher is missing.
+#[cfg(not(feature = "crypto"))]
+fn not_built(key: &AuditKey) -> EngineError {
+    let AuditKey(_bytes) = key;
+    EngineError::AuditCrypto("audit encryption requires the `crypto` feature".into())
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+fn from_hex(text: &str) -> Option<Vec<u8>> {
+    if !text.len().is_multiple_of(2) || !text.is_ascii() {
+        return None;
+    }
+    (0..text.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&text[i..i + 2], 16).ok())
+        .collect()
+}
+
+#[cfg(all(test, feature = "crypto"))]
+mod tests {
+    use super::*;
+    use crate::state::audit_log::{append_audit_with, verify_chain};
+
+    #[test]
+    fn encrypted_log_round_trips_and_verifies_without_key() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("agent.audit.jsonl");
+        let path = path.to_str().unwrap();
+        let key = AuditKey::from_bytes(&[7u8; 32]).unwrap();
+
+        let override_entry = serde_json::json!({
+            "event_type": "Override",
+            "reason": "incident 42",
+            "approver": "alice",
+        });
+        append_audit_with(path, &override_entry, Some(&key)).unwrap();
+        append_audit_with(
+            path,
+            &serde_json::json!({"event_type": "StateChange"}),
+            None,
+        )
+        .unwrap();
+        append_audit_with(path, &override_entry, Some(&key)).unwrap();
+
+        let raw = std::fs::read_to_string(path).unwrap();
+        assert!(!raw.contains("alice"), "{raw}");
+        assert_eq!(verify_chain(path).unwrap(), 3);
+
+        let entries = read_decrypted(path, &key).unwrap();
+        assert_eq!(entries[0]["approver"], "alice");
+        assert_eq!(entries[1]["event_type"], "StateChange");
+        assert_eq!(entries[2]["reason"], "incident 42");
+
+        let wrong = AuditKey::from_bytes("11".repeat(32).as_bytes()).unwrap();
+        let err = read_decrypted(path, &wrong).unwrap_err();
+        assert_eq!(err.code(), "E_AUDIT_CRYPTO");
+    }
+
+    #[test]
+    fn envelope_is_bound_to_its_chain_position() {
+        let key = AuditKey::from_bytes(&[9u8; 32]).unwrap();
+        let entry =
+            serde_json::json!({"event_type": "Override", "prev_hash": "genesis", "ts": "t"});
+        let mut envelope = encrypt_entry(&entry, &key).unwrap();
+        assert_eq!(decrypt_entry(&envelope, &key).unwrap(), entry);
+
+        envelope["prev_hash"] = "sha256:00".into();
+        assert!(decrypt_entry(&envelope, &key).is_err());
+    }
+
+    #[test]
+    fn key_accepts_raw_or_hex() {
+        assert!(AuditKey::from_bytes(&[0u8; 32]).is_ok());
+        assert!(AuditKey::from_bytes(format!("{}\n", "ab".repeat(32)).as_bytes()).is_ok());
+        assert!(AuditKey::from_bytes(b"short").is_err());
+    }
+}
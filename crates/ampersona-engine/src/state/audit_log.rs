@@ -1,26 +1,57 @@
-use anyhow::{bail, Context, Result};
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
 use sha2::{Digest, Sha256};
 
+use super::atomic::atomic_write;
+use super::audit_crypto::{encrypt_entry, AuditKey};
+use crate::error::{EngineError, Result};
+
 /// Append an audit entry to the log file, maintaining hash chain.
 ///
 /// Each entry gets a `prev_hash` field containing the SHA-256 of the previous entry.
-/// The first entry uses "genesis" as its prev_hash.
+/// The first entry uses "genesis" as its prev_hash. When an audit key is
+/// configured in the environment (see [`audit_crypto`]) the entry is encrypted.
+///
+/// [`audit_crypto`]: super::audit_crypto
 pub fn append_audit(path: &str, entry: &serde_json::Value) -> Result<String> {
+    append_audit_with(path, entry, AuditKey::from_env()?.as_ref())
+}
+
+/// [`append_audit`] with an explicit key instead of the environment's.
+pub fn append_audit_with(
+    path: &str,
+    entry: &serde_json::Value,
+    key: Option<&AuditKey>,
+) -> Result<String> {
     let content = if std::path::Path::new(path).exists() {
         std::fs::read_to_string(path).unwrap_or_default()
     } else {
         String::new()
     };
+    let last = content.lines().rev().find(|line| !line.trim().is_empty());
+    let entry_json = chain_line(last, entry, key)?;
+    let hash = format!("sha256:{:x}", Sha256::digest(entry_json.as_bytes()));
 
-    // Compute prev_hash from last entry
-    let prev_hash = content
-        .lines()
-        .rev()
-        .find(|line| !line.trim().is_empty())
+    let mut new_content = content;
+    new_content.push_str(&entry_json);
+    new_content.push('\n');
+    atomic_write(path, new_content.as_bytes())?;
+
+    Ok(hash)
+}
+
+/// The log line for `entry` appended after `last`: `prev_hash` and `ts`
+/// injected, then encrypted when `key` is set.
+pub(crate) fn chain_line(
+    last: Option<&str>,
+    entry: &serde_json::Value,
+    key: Option<&AuditKey>,
+) -> Result<String> {
+    let prev_hash = last
         .map(|line| format!("sha256:{:x}", Sha256::digest(line.as_bytes())))
         .unwrap_or_else(|| "genesis".to_string());
 
-    // Inject prev_hash into entry
     let mut entry = entry.clone();
     if let Some(obj) = entry.as_object_mut() {
         obj.insert("prev_hash".into(), serde_json::Value::String(prev_hash));
@@ -29,16 +60,10 @@ pub fn append_audit(path: &str, entry: &serde_json::Value) -> Result<String> {
             serde_json::Value::String(chrono::Utc::now().to_rfc3339()),
         );
     }
-
-    let entry_json = serde_json::to_string(&entry)?;
-    let hash = format!("sha256:{:x}", Sha256::digest(entry_json.as_bytes()));
-
-    let mut new_content = content;
-    new_content.push_str(&entry_json);
-    new_content.push('\n');
-    std::fs::write(path, new_content).with_context(|| format!("cannot write audit {path}"))?;
-
-    Ok(hash)
+    if let Some(key) = key {
+        entry = encrypt_entry(&entry, key)?;
+    }
+    Ok(serde_json::to_string(&entry)?)
 }
 
 /// Verify the hash chain in an audit log file.
@@ -53,18 +78,29 @@ pub fn verify_chain(path: &str) -> Result<u64> {
 /// Entries before `from_entry` are traversed to build the chain state but not
 /// verified against their prev_hash — this allows verifying a suffix of the chain.
 pub fn verify_chain_from(path: &str, from_entry: u64) -> Result<u64> {
-    let content =
-        std::fs::read_to_string(path).with_context(|| format!("cannot read audit {path}"))?;
+    let content = std::fs::read_to_string(path).map_err(|e| EngineError::read(path, e))?;
+    verify_lines(path, content.lines(), from_entry)
+}
 
+/// [`verify_chain_from`] over log lines from any source; `path` labels errors.
+pub fn verify_lines<'a>(
+    path: &str,
+    lines: impl IntoIterator<Item = &'a str>,
+    from_entry: u64,
+) -> Result<u64> {
     let mut count = 0u64;
     let mut prev_hash = "genesis".to_string();
 
-    for (i, line) in content.lines().enumerate() {
+    for (i, line) in lines.into_iter().enumerate() {
         if line.trim().is_empty() {
             continue;
         }
-        let entry: serde_json::Value = serde_json::from_str(line)
-            .with_context(|| format!("invalid JSON at line {}", i + 1))?;
+        let entry: serde_json::Value =
+            serde_json::from_str(line).map_err(|source| EngineError::InvalidJsonLine {
+                path: path.to_string(),
+                line: i + 1,
+                source,
+            })?;
 
         if count >= from_entry {
             let entry_prev = entry
@@ -73,12 +109,10 @@ pub fn verify_chain_from(path: &str, from_entry: u64) -> Result<u64> {
                 .unwrap_or("genesis");
 
             if entry_prev != prev_hash {
-                bail!(
+                return Err(EngineError::ChainBroken(format!(
                     "hash chain broken at entry {}: expected prev_hash '{}', got '{}'",
-                    count,
-                    prev_hash,
-                    entry_prev
-                );
+                    count, prev_hash, entry_prev
+                )));
             }
         }
 
@@ -95,13 +129,34 @@ pub fn verify_chain_from(path: &str, from_entry: u64) -> Result<u64> {
 /// Writes a JSON file recording the chain head hash and entry count,
 /// which can later be used to verify chain integrity from a known anchor.
 pub fn create_checkpoint(audit_path: &str, checkpoint_path: &str) -> Result<serde_json::Value> {
-    let count = verify_chain(audit_path)?;
-    let content = std::fs::read_to_string(audit_path)
-        .with_context(|| format!("cannot read audit {audit_path}"))?;
+    let checkpoint = build_checkpoint(audit_path)?;
+    let json = serde_json::to_string_pretty(&checkpoint)?;
+    atomic_write(checkpoint_path, json.as_bytes())?;
+    Ok(checkpoint)
+}
+
+/// Build a checkpoint for an audit chain without writing it.
+///
+/// Lets callers attach a signature or timestamp before the checkpoint
+/// reaches disk, so a failed step never leaves a partial checkpoint behind.
+pub fn build_checkpoint(audit_path: &str) -> Result<serde_json::Value> {
+    let content =
+        std::fs::read_to_string(audit_path).map_err(|e| EngineError::read(audit_path, e))?;
+    build_checkpoint_lines(audit_path, content.lines())
+}
+
+/// [`build_checkpoint`] over log lines from any source; `audit_path` labels
+/// errors and is recorded as the checkpoint's `audit_file`.
+pub fn build_checkpoint_lines<'a>(
+    audit_path: &str,
+    lines: impl IntoIterator<Item = &'a str>,
+) -> Result<serde_json::Value> {
+    let lines: Vec<&str> = lines.into_iter().collect();
+    let count = verify_lines(audit_path, lines.iter().copied(), 0)?;
 
     // Get hash of last entry
-    let chain_head = content
-        .lines()
+    let chain_head = lines
+        .iter()
         .rev()
         .find(|line| !line.trim().is_empty())
         .map(|line| format!("sha256:{:x}", Sha256::digest(line.as_bytes())))
@@ -114,42 +169,194 @@ pub fn create_checkpoint(audit_path: &str, checkpoint_path: &str) -> Result<serd
         "created_at": chrono::Utc::now().to_rfc3339(),
     });
 
-    let json = serde_json::to_string_pretty(&checkpoint)?;
-    std::fs::write(checkpoint_path, json)
-        .with_context(|| format!("cannot write checkpoint {checkpoint_path}"))?;
-
     Ok(checkpoint)
 }
 
+/// Comparison of a state file's `state_rev` with the audited mutations.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct RevCheck {
+    pub state_rev: u64,
+    pub state_mutations: u64,
+    /// `state_rev` a reconciled state file should carry.
+    pub expected_state_rev: u64,
+    pub consistent: bool,
+}
+
+/// Check `state_rev` against the state mutations recorded in the audit log.
+///
+/// `state_rev` may exceed the audited mutations by one (pending/approve flow);
+/// anything beyond that is inconsistent, and the expected value is the
+/// mutation count. Refuses to judge when the audit chain does not verify.
+pub fn check_state_rev(state_rev: u64, audit_path: &str) -> Result<RevCheck> {
+    let content =
+        std::fs::read_to_string(audit_path).map_err(|e| EngineError::read(audit_path, e))?;
+    check_state_rev_lines(state_rev, audit_path, content.lines())
+}
+
+/// [`check_state_rev`] over log lines from any source; `audit_path` labels errors.
+pub fn check_state_rev_lines<'a>(
+    state_rev: u64,
+    audit_path: &str,
+    lines: impl IntoIterator<Item = &'a str>,
+) -> Result<RevCheck> {
+    let lines: Vec<&str> = lines.into_iter().collect();
+    verify_lines(audit_path, lines.iter().copied(), 0)?;
+    let state_mutations = count_state_mutation_lines(lines);
+    let consistent = state_rev <= state_mutations + 1;
+    Ok(RevCheck {
+        state_rev,
+        state_mutations,
+        expected_state_rev: if consistent {
+            state_rev
+        } else {
+            state_mutations
+        },
+        consistent,
+    })
+}
+
 /// Count all audit events that correspond to a state_rev increment.
 ///
-/// Events: GateTransition, ElevationChange, Override.
+/// Events: GateTransition, ElevationChange, Override, AdminPhaseReset.
 pub fn count_state_mutations(path: &str) -> Result<u64> {
-    let content =
-        std::fs::read_to_string(path).with_context(|| format!("cannot read audit {path}"))?;
+    let content = std::fs::read_to_string(path).map_err(|e| EngineError::read(path, e))?;
+    Ok(count_state_mutation_lines(content.lines()))
+}
 
+/// [`count_state_mutations`] over log lines from any source.
+pub fn count_state_mutation_lines<'a>(lines: impl IntoIterator<Item = &'a str>) -> u64 {
     let mut count = 0u64;
-    for line in content.lines() {
+    for line in lines {
         if line.trim().is_empty() {
             continue;
         }
         if let Ok(entry) = serde_json::from_str::<serde_json::Value>(line) {
             if let Some(et) = entry.get("event_type").and_then(|v| v.as_str()) {
-                if matches!(et, "GateTransition" | "ElevationChange" | "Override") {
+                if matches!(
+                    et,
+                    "GateTransition" | "ElevationChange" | "Override" | "AdminPhaseReset"
+                ) {
                     count += 1;
                 }
             }
         }
     }
-    Ok(count)
+    count
+}
+
+/// Aggregate counts and timing over an audit log (`amp audit --stats`).
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize)]
+pub struct AuditStats {
+    pub entries: u64,
+    /// Entries per `event_type`; entries without one count as `unknown`.
+    pub event_counts: BTreeMap<String, u64>,
+    pub first_ts: Option<DateTime<Utc>>,
+    pub last_ts: Option<DateTime<Utc>>,
+    pub overrides: u64,
+    /// Pending transitions later approved for the same gate.
+    pub approval_cycles: u64,
+    /// Mean seconds from pending to approval over `approval_cycles`.
+    pub avg_pending_seconds: Option<f64>,
+}
+
+/// Compute [`AuditStats`] in a single pass over the log.
+///
+/// A pending transition is a `PendingTransition` event or a `GateTransition`
+/// with decision `pending_human`; it is paired with the next `approved`
+/// `GateTransition` carrying the same `gate_id`.
+pub fn audit_stats(path: &str) -> Result<AuditStats> {
+    let content = std::fs::read_to_string(path).map_err(|e| EngineError::read(path, e))?;
+    audit_stats_lines(path, content.lines())
+}
+
+/// [`audit_stats`] over log lines from any source; `path` labels errors.
+pub fn audit_stats_lines<'a>(
+    path: &str,
+    lines: impl IntoIterator<Item = &'a str>,
+) -> Result<AuditStats> {
+    let mut stats = AuditStats::default();
+    let mut pending: BTreeMap<String, DateTime<Utc>> = BTreeMap::new();
+    let mut pending_total = 0.0;
+
+    for (i, line) in lines.into_iter().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let entry: serde_json::Value =
+            serde_json::from_str(line).map_err(|source| EngineError::InvalidJsonLine {
+                path: path.to_string(),
+                line: i + 1,
+                source,
+            })?;
+        stats.entries += 1;
+
+        let event_type = entry
+            .get("event_type")
+            .and_then(serde_json::Value::as_str)
+            .unwrap_or("unknown");
+        *stats
+            .event_counts
+            .entry(event_type.to_string())
+            .or_default() += 1;
+
+        let ts = entry
+            .get("ts")
+            .and_then(serde_json::Value::as_str)
+            .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+            .map(|t| t.with_timezone(&Utc));
+        if let Some(ts) = ts {
+            stats.first_ts.get_or_insert(ts);
+            stats.last_ts = Some(ts);
+        }
+
+        let decision = entry.get("decision").and_then(serde_json::Value::as_str);
+        let gate_id = entry.get("gate_id").and_then(serde_json::Value::as_str);
+        match (event_type, decision, gate_id) {
+            ("Override", ..) => stats.overrides += 1,
+            ("PendingTransition", _, Some(gate))
+            | ("GateTransition", Some("pending_human"), Some(gate)) => {
+                if let Some(ts) = ts {
+                    pending.insert(gate.to_string(), ts);
+                }
+            }
+            ("GateTransition", Some("approved"), Some(gate)) => {
+                if let (Some(created), Some(approved)) = (pending.remove(gate), ts) {
+                    stats.approval_cycles += 1;
+                    pending_total += (approved - created).num_milliseconds() as f64 / 1000.0;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    if stats.approval_cycles > 0 {
+        stats.avg_pending_seconds = Some(pending_total / stats.approval_cycles as f64);
+    }
+    Ok(stats)
 }
 
 /// Verify a checkpoint against the current audit chain.
 pub fn verify_checkpoint(audit_path: &str, checkpoint_path: &str) -> Result<bool> {
     let checkpoint_content = std::fs::read_to_string(checkpoint_path)
-        .with_context(|| format!("cannot read checkpoint {checkpoint_path}"))?;
-    let checkpoint: serde_json::Value = serde_json::from_str(&checkpoint_content)?;
+        .map_err(|e| EngineError::read(checkpoint_path, e))?;
+    let checkpoint: serde_json::Value =
+        serde_json::from_str(&checkpoint_content).map_err(|source| EngineError::InvalidJson {
+            path: checkpoint_path.to_string(),
+            source,
+        })?;
 
+    let content =
+        std::fs::read_to_string(audit_path).map_err(|e| EngineError::read(audit_path, e))?;
+    verify_checkpoint_lines(audit_path, content.lines(), &checkpoint)
+}
+
+/// [`verify_checkpoint`] of an already-parsed checkpoint over log lines from
+/// any source; `audit_path` labels errors.
+pub fn verify_checkpoint_lines<'a>(
+    audit_path: &str,
+    lines: impl IntoIterator<Item = &'a str>,
+    checkpoint: &serde_json::Value,
+) -> Result<bool> {
     let expected_count = checkpoint
         .get("entries")
         .and_then(|v| v.as_u64())
@@ -160,14 +367,11 @@ pub fn verify_checkpoint(audit_path: &str, checkpoint_path: &str) -> Result<bool
         .unwrap_or("genesis");
 
     // Verify the chain up to the checkpoint's entry count
-    let content = std::fs::read_to_string(audit_path)
-        .with_context(|| format!("cannot read audit {audit_path}"))?;
-
     let mut count = 0u64;
     let mut prev_hash = "genesis".to_string();
     let mut last_hash = "genesis".to_string();
 
-    for (i, line) in content.lines().enumerate() {
+    for (i, line) in lines.into_iter().enumerate() {
         if line.trim().is_empty() {
             continue;
         }
@@ -175,8 +379,12 @@ pub fn verify_checkpoint(audit_path: &str, checkpoint_path: &str) -> Result<bool
             break;
         }
 
-        let entry: serde_json::Value = serde_json::from_str(line)
-            .with_context(|| format!("invalid JSON at line {}", i + 1))?;
+        let entry: serde_json::Value =
+            serde_json::from_str(line).map_err(|source| EngineError::InvalidJsonLine {
+                path: audit_path.to_string(),
+                line: i + 1,
+                source,
+            })?;
 
         let entry_prev = entry
             .get("prev_hash")
@@ -202,6 +410,60 @@ mod tests {
     use std::io::Write;
     use tempfile::NamedTempFile;
 
+    #[test]
+    fn state_rev_check_flags_unaudited_revisions() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("test.audit.jsonl");
+        let path = path.to_str().unwrap();
+        append_audit(path, &serde_json::json!({"event_type": "GateTransition"})).unwrap();
+        append_audit(path, &serde_json::json!({"event_type": "PolicyDecision"})).unwrap();
+        append_audit(path, &serde_json::json!({"event_type": "Override"})).unwrap();
+
+        let ok = check_state_rev(3, path).unwrap();
+        assert!(ok.consistent);
+        assert_eq!(ok.expected_state_rev, 3);
+
+        let bad = check_state_rev(99, path).unwrap();
+        assert!(!bad.consistent);
+        assert_eq!(bad.state_mutations, 2);
+        assert_eq!(bad.expected_state_rev, 2);
+
+        // A tampered chain is never reconciled against.
+        let content = std::fs::read_to_string(path).unwrap();
+        std::fs::write(path, content.replacen("GateTransition", "Forged", 1)).unwrap();
+        assert!(check_state_rev(99, path).is_err());
+    }
+
+    #[test]
+    fn stats_pair_pending_with_approval() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("test.audit.jsonl");
+        let lines = [
+            r#"{"event_type":"GateTransition","gate_id":"promote","decision":"pending_human","ts":"2026-01-01T00:00:00Z"}"#,
+            r#"{"event_type":"PolicyDecision","ts":"2026-01-01T00:01:00Z"}"#,
+            r#"{"event_type":"GateTransition","gate_id":"promote","decision":"approved","ts":"2026-01-01T00:02:00Z"}"#,
+            r#"{"event_type":"Override","gate_id":"promote","ts":"2026-01-01T00:05:00Z"}"#,
+            r#"{"event_type":"GateTransition","gate_id":"other","decision":"approved","ts":"2026-01-01T00:06:00Z"}"#,
+        ];
+        std::fs::write(&path, lines.join("\n")).unwrap();
+
+        let stats = audit_stats(path.to_str().unwrap()).unwrap();
+        assert_eq!(stats.entries, 5);
+        assert_eq!(stats.event_counts["GateTransition"], 3);
+        assert_eq!(stats.event_counts["PolicyDecision"], 1);
+        assert_eq!(stats.overrides, 1);
+        assert_eq!(stats.approval_cycles, 1);
+        assert_eq!(stats.avg_pending_seconds, Some(120.0));
+        assert_eq!(
+            stats.first_ts.unwrap().to_rfc3339(),
+            "2026-01-01T00:00:00+00:00"
+        );
+        assert_eq!(
+            stats.last_ts.unwrap().to_rfc3339(),
+            "2026-01-01T00:06:00+00:00"
+        );
+    }
+
     #[test]
     fn append_and_verify_chain() {
         let mut file = NamedTempFile::new().unwrap();
@@ -262,7 +524,7 @@ mod tests {
 
         // Verify should fail
         let result = verify_chain(&path);
-        assert!(result.is_err());
+        assert!(matches!(result, Err(EngineError::ChainBroken(_))));
     }
 
     #[test]
@@ -0,0 +1,326 @@
+use std::path::Path;
+
+use ampersona_core::sidecar::Sidecars;
+use serde::Serialize;
+use serde_json::Value;
+
+use super::atomic::atomic_write;
+use super::audit_log::{verify_chain, verify_checkpoint, verify_lines};
+use super::store::{FileStateStore, StateStore};
+use crate::error::{EngineError, Result};
+
+/// Format version written to `bundle_version`.
+pub const BUNDLE_VERSION: &str = "1";
+
+/// Result of unpacking a bundle: where it landed and how the chain re-verified.
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportedBundle {
+    pub persona_path: String,
+    pub files: Vec<String>,
+    /// Audit chain verification after the entries were written back out.
+    pub chain_valid: bool,
+    pub entries: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub chain_error: Option<String>,
+    /// Chain result recorded in the bundle at export time.
+    pub export_chain_valid: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub checkpoint_valid: Option<bool>,
+}
+
+impl ImportedBundle {
+    /// The chain verifies now and matches what the exporter saw.
+    pub fn consistent(&self) -> bool {
+        self.chain_valid && self.export_chain_valid && self.checkpoint_valid != Some(false)
+    }
+}
+
+/// Package a persona with its state, audit log and checkpoint into one document.
+///
+/// Missing sidecars are recorded as `null` (or an empty audit array). The audit
+/// chain is verified at export time and the outcome stored under `chain`, so
+/// the importer can tell a chain that was already broken from one damaged in
+/// transit. The sidecars are read from `sidecars`; the drift history is not
+/// bundled.
+pub fn export_bundle(persona_path: &str, sidecars: &Sidecars) -> Result<Value> {
+    let state = read_optional(&sidecars.state)?;
+    let audit = FileStateStore::new(&sidecars.state, &sidecars.audit).audit_lines()?;
+    assemble(
+        persona_path,
+        state,
+        &sidecars.audit,
+        audit.as_deref(),
+        &sidecars.checkpoint,
+    )
+}
+
+/// [`export_bundle`] with the state and audit log read from `store`; the
+/// checkpoint is still the file at `checkpoint_path`.
+pub fn export_store_bundle(
+    persona_path: &str,
+    store: impl StateStore,
+    checkpoint_path: &str,
+) -> Result<Value> {
+    let state = serde_json::to_value(store.load()?)?;
+    let audit = store.audit_lines()?;
+    assemble(
+        persona_path,
+        state,
+        &store.audit_location(),
+        audit.as_deref(),
+        checkpoint_path,
+    )
+}
+
+fn assemble(
+    persona_path: &str,
+    state: Value,
+    audit_path: &str,
+    audit_lines: Option<&[String]>,
+    checkpoint_path: &str,
+) -> Result<Value> {
+    let persona = read_json(persona_path)?;
+    let checkpoint = read_optional(checkpoint_path)?;
+
+    let (audit, chain) = if let Some(lines) = audit_lines {
+        let mut entries = Vec::new();
+        for (i, line) in lines.iter().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let entry: Value =
+                serde_json::from_str(line).map_err(|source| EngineError::InvalidJsonLine {
+                    path: audit_path.to_string(),
+                    line: i + 1,
+                    source,
+                })?;
+            entries.push(entry);
+        }
+        let chain = match verify_lines(audit_path, lines.iter().map(String::as_str), 0) {
+            Ok(count) => serde_json::json!({"valid": true, "entries": count}),
+            Err(e) => {
+                serde_json::json!({"valid": false, "entries": entries.len(), "error": e.with_causes()})
+            }
+        };
+        (entries, chain)
+    } else {
+        (Vec::new(), serde_json::json!({"valid": true, "entries": 0}))
+    };
+
+    Ok(serde_json::json!({
+        "bundle_version": BUNDLE_VERSION,
+        "exported_at": chrono::Utc::now().to_rfc3339(),
+        "persona_file": file_name(persona_path),
+        "persona": persona,
+        "state": state,
+        "audit": audit,
+        "checkpoint": checkpoint,
+        "chain": chain,
+    }))
+}
+
+/// Unpack a bundle into `dest_dir` and re-verify its audit chain.
+///
+/// Refuses to overwrite an existing persona or sidecar. Files are written even
+/// when the chain no longer verifies, so a damaged bundle can still be examined;
+/// check [`ImportedBundle::consistent`] before trusting it.
+pub fn import_bundle(bundle: &Value, dest_dir: &Path) -> Result<ImportedBundle> {
+    let version = bundle.get("bundle_version").and_then(Value::as_str);
+    if version != Some(BUNDLE_VERSION) {
+        return Err(EngineError::InvalidBundle(format!(
+            "unsupported bundle_version {}",
+            version.unwrap_or("(missing)")
+        )));
+    }
+    let name = bundle
+        .get("persona_file")
+        .and_then(Value::as_str)
+        .map(file_name)
+        .filter(|n| n.ends_with(".json") && !n.starts_with('.'))
+        .ok_or_else(|| EngineError::InvalidBundle("persona_file must name a .json file".into()))?;
+    let persona = bundle
+        .get("persona")
+        .filter(|p| p.is_object())
+        .ok_or_else(|| EngineError::InvalidBundle("bundle has no persona".into()))?;
+    let audit = bundle
+        .get("audit")
+        .and_then(Value::as_array)
+        .ok_or_else(|| EngineError::InvalidBundle("audit must be an array".into()))?;
+    let present = |key: &str| bundle.get(key).filter(|v| !v.is_null());
+
+    let persona_path = dest_dir.join(&name).to_string_lossy().into_owned();
+    let Sidecars {
+        state: state_path,
+        audit: audit_path,
+        checkpoint: checkpoint_path,
+        ..
+    } = Sidecars::for_persona(&persona_path);
+
+    let mut writes = vec![(persona_path.clone(), serde_json::to_string_pretty(persona)?)];
+    if let Some(state) = present("state") {
+        writes.push((state_path, serde_json::to_string_pretty(state)?));
+    }
+    if !audit.is_empty() {
+        let mut content = String::new();
+        for entry in audit {
+            content.push_str(&serde_json::to_string(entry)?);
+            content.push('\n');
+        }
+        writes.push((audit_path.clone(), content));
+    }
+    if let Some(checkpoint) = present("checkpoint") {
+        writes.push((
+            checkpoint_path.clone(),
+            serde_json::to_string_pretty(checkpoint)?,
+        ));
+    }
+
+    if let Some((path, _)) = writes.iter().find(|(p, _)| Path::new(p).exists()) {
+        return Err(EngineError::io(
+            format!("refusing to overwrite {path}"),
+            std::io::ErrorKind::AlreadyExists.into(),
+        ));
+    }
+    for (path, content) in &writes {
+        atomic_write(path, content.as_bytes())?;
+    }
+
+    let (chain_valid, entries, chain_error) = if audit.is_empty() {
+        (true, 0, None)
+    } else {
+        match verify_chain(&audit_path) {
+            Ok(count) => (true, count, None),
+            Err(e) => (false, audit.len() as u64, Some(e.with_causes())),
+        }
+    };
+    let checkpoint_valid = match present("checkpoint") {
+        Some(_) if !audit.is_empty() => {
+            Some(verify_checkpoint(&audit_path, &checkpoint_path).unwrap_or(false))
+        }
+        Some(_) => Some(false),
+        None => None,
+    };
+
+    Ok(ImportedBundle {
+        persona_path,
+        files: writes.into_iter().map(|(p, _)| p).collect(),
+        chain_valid,
+        entries,
+        chain_error,
+        export_chain_valid: bundle
+            .pointer("/chain/valid")
+            .and_then(Value::as_bool)
+            .unwrap_or(false),
+        checkpoint_valid,
+    })
+}
+
+fn read_json(path: &str) -> Result<Value> {
+    let content = std::fs::read_to_string(path).map_err(|e| EngineError::read(path, e))?;
+    serde_json::from_str(&content).map_err(|source| EngineError::InvalidJson {
+        path: path.to_string(),
+        source,
+    })
+}
+
+fn read_optional(path: &str) -> Result<Value> {
+    if Path::new(path).exists() {
+        read_json(path)
+    } else {
+        Ok(Value::Null)
+    }
+}
+
+fn file_name(path: &str) -> String {
+    Path::new(path)
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::audit_log::{append_audit, create_checkpoint};
+
+    fn fixture(dir: &Path) -> String {
+        let persona = dir.join("agent.json").to_str().unwrap().to_string();
+        std::fs::write(&persona, r#"{"name": "agent"}"#).unwrap();
+        std::fs::write(
+            dir.join("agent.state.json"),
+            r#"{"name": "agent", "state_rev": 2}"#,
+        )
+        .unwrap();
+        let audit = dir.join("agent.audit.jsonl").to_str().unwrap().to_string();
+        for i in 0..3 {
+            append_audit(
+                &audit,
+                &serde_json::json!({"event_type": "Test", "n": i, "rate": 0.1}),
+            )
+            .unwrap();
+        }
+        let checkpoint = dir.join("agent.checkpoint.json");
+        create_checkpoint(&audit, checkpoint.to_str().unwrap()).unwrap();
+        persona
+    }
+
+    fn without_checkpoint(persona: &str) -> Sidecars {
+        Sidecars {
+            checkpoint: "/nonexistent/checkpoint.json".into(),
+            ..Sidecars::for_persona(persona)
+        }
+    }
+
+    #[test]
+    fn bundle_round_trip_reverifies_chain() {
+        let src = tempfile::tempdir().unwrap();
+        let persona = fixture(src.path());
+        let bundle = export_bundle(&persona, &Sidecars::for_persona(&persona)).unwrap();
+        assert_eq!(bundle["chain"]["valid"], true);
+        assert_eq!(bundle["audit"].as_array().unwrap().len(), 3);
+
+        let dest = tempfile::tempdir().unwrap();
+        let imported = import_bundle(&bundle, dest.path()).unwrap();
+        assert!(imported.consistent());
+        assert_eq!(imported.entries, 3);
+        assert_eq!(imported.files.len(), 4);
+        assert_eq!(imported.checkpoint_valid, Some(true));
+        assert_eq!(
+            std::fs::read_to_string(src.path().join("agent.audit.jsonl")).unwrap(),
+            std::fs::read_to_string(dest.path().join("agent.audit.jsonl")).unwrap()
+        );
+
+        // A second import into the same directory must not clobber it.
+        assert!(import_bundle(&bundle, dest.path()).is_err());
+    }
+
+    #[test]
+    fn tampered_bundle_fails_reverification() {
+        let src = tempfile::tempdir().unwrap();
+        let persona = fixture(src.path());
+        let mut bundle = export_bundle(&persona, &without_checkpoint(&persona)).unwrap();
+        assert!(bundle["checkpoint"].is_null());
+        bundle["audit"][1]["n"] = serde_json::json!(42);
+
+        let dest = tempfile::tempdir().unwrap();
+        let imported = import_bundle(&bundle, dest.path()).unwrap();
+        assert!(!imported.chain_valid);
+        assert!(imported.export_chain_valid);
+        assert!(!imported.consistent());
+    }
+
+    #[test]
+    fn persona_file_cannot_escape_dest() {
+        let src = tempfile::tempdir().unwrap();
+        let persona = fixture(src.path());
+        let mut bundle = export_bundle(&persona, &without_checkpoint(&persona)).unwrap();
+        bundle["persona_file"] = serde_json::json!("../../escape.json");
+
+        let dest = tempfile::tempdir().unwrap();
+        let imported = import_bundle(&bundle, dest.path()).unwrap();
+        assert!(imported
+            .persona_path
+            .starts_with(dest.path().to_str().unwrap()));
+        assert!(imported.persona_path.ends_with("escape.json"));
+    }
+}
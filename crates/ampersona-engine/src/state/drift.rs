@@ -1,8 +1,13 @@
+use std::collections::BTreeMap;
+
 use ampersona_core::state::DriftEntry;
-use anyhow::{bail, Context, Result};
-use chrono::Utc;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
 use sha2::{Digest, Sha256};
 
+use super::atomic::atomic_write;
+use crate::error::{EngineError, Result};
+
 /// Append a drift entry to the ledger file, maintaining hash chain.
 ///
 /// Automatically reads the last entry's hash as prev_hash.
@@ -33,7 +38,7 @@ pub fn append_drift(path: &str, metrics: serde_json::Value) -> Result<String> {
     let mut new_content = content;
     new_content.push_str(&entry_json);
     new_content.push('\n');
-    std::fs::write(path, new_content).with_context(|| format!("cannot write drift {path}"))?;
+    atomic_write(path, new_content.as_bytes())?;
 
     Ok(hash)
 }
@@ -43,24 +48,92 @@ pub fn read_drift_entries(path: &str) -> Result<Vec<serde_json::Value>> {
     if !std::path::Path::new(path).exists() {
         return Ok(Vec::new());
     }
-    let content =
-        std::fs::read_to_string(path).with_context(|| format!("cannot read drift {path}"))?;
+    let content = std::fs::read_to_string(path).map_err(|e| EngineError::read(path, e))?;
     let mut entries = Vec::new();
-    for line in content.lines() {
+    for (i, line) in content.lines().enumerate() {
         if line.trim().is_empty() {
             continue;
         }
-        let entry: serde_json::Value = serde_json::from_str(line)
-            .with_context(|| format!("invalid JSON in drift ledger {path}"))?;
+        let entry: serde_json::Value =
+            serde_json::from_str(line).map_err(|source| EngineError::InvalidJsonLine {
+                path: path.to_string(),
+                line: i + 1,
+                source,
+            })?;
         entries.push(entry);
     }
     Ok(entries)
 }
 
+/// Keep the entries whose `ts` lies in `[since, until]`. With either bound set,
+/// entries without a parseable RFC 3339 `ts` are dropped.
+pub fn filter_drift_window(
+    entries: Vec<serde_json::Value>,
+    since: Option<DateTime<Utc>>,
+    until: Option<DateTime<Utc>>,
+) -> Vec<serde_json::Value> {
+    if since.is_none() && until.is_none() {
+        return entries;
+    }
+    entries
+        .into_iter()
+        .filter(|entry| {
+            let Some(ts) = entry
+                .get("ts")
+                .and_then(serde_json::Value::as_str)
+                .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+                .map(|t| t.with_timezone(&Utc))
+            else {
+                return false;
+            };
+            since.is_none_or(|s| ts >= s) && until.is_none_or(|u| ts <= u)
+        })
+        .collect()
+}
+
+/// One numeric metric across drift entries, oldest first.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MetricSeries {
+    pub metric: String,
+    /// Values from the entries that recorded the metric; others are skipped.
+    pub values: Vec<f64>,
+    /// `rising`, `falling` or `flat`, comparing the last value to the first.
+    pub trend: &'static str,
+}
+
+/// Per-metric series of the numeric values in `entries`, sorted by metric.
+pub fn metric_series(entries: &[serde_json::Value]) -> Vec<MetricSeries> {
+    let mut series: BTreeMap<&str, Vec<f64>> = BTreeMap::new();
+    for metrics in entries
+        .iter()
+        .filter_map(|e| e.get("metrics").and_then(serde_json::Value::as_object))
+    {
+        for (name, value) in metrics {
+            if let Some(n) = value.as_f64() {
+                series.entry(name).or_default().push(n);
+            }
+        }
+    }
+    series
+        .into_iter()
+        .map(|(metric, values)| {
+            let trend = match (values.first(), values.last()) {
+                (Some(first), Some(last)) if last > first => "rising",
+                (Some(first), Some(last)) if last < first => "falling",
+                _ => "flat",
+            };
+            MetricSeries {
+                metric: metric.to_string(),
+                values,
+                trend,
+            }
+        })
+        .collect()
+}
+
 /// Verify the hash chain in a drift ledger file.
 pub fn verify_drift_chain(path: &str) -> Result<u64> {
-    let content =
-        std::fs::read_to_string(path).with_context(|| format!("cannot read drift {path}"))?;
+    let content = std::fs::read_to_string(path).map_err(|e| EngineError::read(path, e))?;
 
     let mut count = 0u64;
     let mut prev_hash = "genesis".to_string();
@@ -69,8 +142,12 @@ pub fn verify_drift_chain(path: &str) -> Result<u64> {
         if line.trim().is_empty() {
             continue;
         }
-        let entry: serde_json::Value = serde_json::from_str(line)
-            .with_context(|| format!("invalid JSON at line {}", i + 1))?;
+        let entry: serde_json::Value =
+            serde_json::from_str(line).map_err(|source| EngineError::InvalidJsonLine {
+                path: path.to_string(),
+                line: i + 1,
+                source,
+            })?;
 
         let entry_prev = entry
             .get("prev_hash")
@@ -78,12 +155,12 @@ pub fn verify_drift_chain(path: &str) -> Result<u64> {
             .unwrap_or("genesis");
 
         if entry_prev != prev_hash {
-            bail!(
+            return Err(EngineError::ChainBroken(format!(
                 "drift chain broken at entry {}: expected '{}', got '{}'",
                 i + 1,
                 prev_hash,
                 entry_prev
-            );
+            )));
         }
 
         let entry_json = serde_json::to_string(&entry)?;
@@ -93,3 +170,24 @@ pub fn verify_drift_chain(path: &str) -> Result<u64> {
 
     Ok(count)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn series_skip_entries_without_the_metric() {
+        let entries = vec![
+            serde_json::json!({"metrics": {"error_rate": 0.01, "healthy": true}}),
+            serde_json::json!({"metrics": {"latency_ms": 120}}),
+            serde_json::json!({"metrics": {"error_rate": 0.04, "latency_ms": 80}}),
+        ];
+        let series = metric_series(&entries);
+        assert_eq!(series.len(), 2);
+        assert_eq!(series[0].metric, "error_rate");
+        assert_eq!(series[0].values, [0.01, 0.04]);
+        assert_eq!(series[0].trend, "rising");
+        assert_eq!(series[1].values, [120.0, 80.0]);
+        assert_eq!(series[1].trend, "falling");
+    }
+}
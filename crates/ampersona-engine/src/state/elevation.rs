@@ -1,3 +1,4 @@
+use ampersona_core::spec::authority::Elevation;
 use ampersona_core::state::{ActiveElevation, PhaseState};
 use chrono::{Duration, Utc};
 
@@ -19,6 +20,13 @@ pub fn activate(
     });
 }
 
+/// TTL for an activation: the requested override, or the declared
+/// `ttl_seconds`, capped at `max_ttl_seconds` when one is declared.
+pub fn effective_ttl(elevation: &Elevation, requested_seconds: Option<u64>) -> u64 {
+    let ttl = requested_seconds.unwrap_or(elevation.ttl_seconds);
+    elevation.max_ttl_seconds.map_or(ttl, |max| ttl.min(max))
+}
+
 /// Remove expired elevations, returns the list of expired IDs.
 pub fn enforce_ttl(state: &mut PhaseState) -> Vec<String> {
     let mut expired = Vec::new();
@@ -32,3 +40,28 @@ pub fn enforce_ttl(state: &mut PhaseState) -> Vec<String> {
     });
     expired
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn elevation(max_ttl_seconds: Option<u64>) -> Elevation {
+        Elevation {
+            id: "deploy".into(),
+            grants: serde_json::json!({}),
+            requires: ampersona_core::types::GateApproval::Human,
+            ttl_seconds: 3600,
+            max_ttl_seconds,
+            reason_required: false,
+        }
+    }
+
+    #[test]
+    fn effective_ttl_overrides_and_caps() {
+        assert_eq!(effective_ttl(&elevation(None), None), 3600);
+        assert_eq!(effective_ttl(&elevation(None), Some(5400)), 5400);
+        assert_eq!(effective_ttl(&elevation(Some(7200)), Some(5400)), 5400);
+        assert_eq!(effective_ttl(&elevation(Some(1800)), Some(5400)), 1800);
+        assert_eq!(effective_ttl(&elevation(Some(1800)), None), 1800);
+    }
+}
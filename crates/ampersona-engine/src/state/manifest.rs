@@ -0,0 +1,157 @@
+use std::path::Path;
+
+use ampersona_core::sidecar::Sidecars;
+use serde::Serialize;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use crate::error::{EngineError, Result};
+
+/// Format version written to `manifest_version`.
+pub const MANIFEST_VERSION: &str = "1";
+
+/// A covered file whose content no longer matches the manifest.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FileDrift {
+    pub role: String,
+    pub file: String,
+    /// Hash recorded in the manifest; `None` if the file was absent then.
+    pub expected: Option<String>,
+    /// Hash of the file now; `None` if it is missing.
+    pub actual: Option<String>,
+}
+
+/// The files a manifest covers for `persona_path`, as `(role, path)`.
+fn covered_files(persona_path: &str) -> [(&'static str, String); 4] {
+    let sidecars = Sidecars::for_persona(persona_path);
+    [
+        ("persona", persona_path.to_string()),
+        ("state", sidecars.state),
+        ("audit", sidecars.audit),
+        ("checkpoint", sidecars.checkpoint),
+    ]
+}
+
+/// Hash the persona and its state, audit log and checkpoint into one document.
+///
+/// Files are listed by name relative to the persona's directory; absent
+/// sidecars are recorded with a `null` hash so their later appearance counts
+/// as drift. The persona itself must exist.
+pub fn build_manifest(persona_path: &str) -> Result<Value> {
+    if !Path::new(persona_path).exists() {
+        return Err(EngineError::read(
+            persona_path,
+            std::io::ErrorKind::NotFound.into(),
+        ));
+    }
+    let files = covered_files(persona_path)
+        .into_iter()
+        .map(|(role, path)| {
+            Ok(serde_json::json!({
+                "role": role,
+                "file": file_name(&path),
+                "sha256": hash_file(&path)?,
+            }))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(serde_json::json!({
+        "manifest_version": MANIFEST_VERSION,
+        "created_at": chrono::Utc::now().to_rfc3339(),
+        "persona_file": file_name(persona_path),
+        "files": files,
+    }))
+}
+
+/// Re-hash every file listed in `manifest` next to `persona_path`.
+///
+/// Returns the files whose current hash differs from the recorded one; an
+/// empty list means the set is unchanged. Does not check the signature.
+pub fn manifest_drift(manifest: &Value, persona_path: &str) -> Result<Vec<FileDrift>> {
+    let version = manifest.get("manifest_version").and_then(Value::as_str);
+    if version != Some(MANIFEST_VERSION) {
+        return Err(EngineError::InvalidBundle(format!(
+            "unsupported manifest_version {}",
+            version.unwrap_or("(missing)")
+        )));
+    }
+    let files = manifest
+        .get("files")
+        .and_then(Value::as_array)
+        .ok_or_else(|| EngineError::InvalidBundle("manifest files must be an array".into()))?;
+    let dir = Path::new(persona_path).parent().unwrap_or(Path::new(""));
+
+    let mut drift = Vec::new();
+    for entry in files {
+        let field = |name: &str| entry.get(name).and_then(Value::as_str);
+        let (Some(role), Some(file)) = (field("role"), field("file")) else {
+            return Err(EngineError::InvalidBundle(
+                "manifest entries need role and file".into(),
+            ));
+        };
+        // Only bare names are listed; never follow a path out of the directory.
+        let path = dir.join(file_name(file)).to_string_lossy().into_owned();
+        let expected = field("sha256").map(str::to_string);
+        let actual = hash_file(&path)?;
+        if actual != expected {
+            drift.push(FileDrift {
+                role: role.to_string(),
+                file: file.to_string(),
+                expected,
+                actual,
+            });
+        }
+    }
+    Ok(drift)
+}
+
+fn hash_file(path: &str) -> Result<Option<String>> {
+    if !Path::new(path).exists() {
+        return Ok(None);
+    }
+    let bytes = std::fs::read(path).map_err(|e| EngineError::read(path, e))?;
+    Ok(Some(format!("sha256:{:x}", Sha256::digest(&bytes))))
+}
+
+fn file_name(path: &str) -> String {
+    Path::new(path)
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn drift_reports_changed_and_new_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let persona = dir.path().join("agent.json");
+        let persona = persona.to_str().unwrap();
+        std::fs::write(persona, r#"{"name": "agent"}"#).unwrap();
+        std::fs::write(dir.path().join("agent.state.json"), r#"{"state_rev": 1}"#).unwrap();
+
+        let manifest = build_manifest(persona).unwrap();
+        assert_eq!(manifest["files"].as_array().unwrap().len(), 4);
+        assert!(manifest["files"][2]["sha256"].is_null());
+        assert!(manifest_drift(&manifest, persona).unwrap().is_empty());
+
+        std::fs::write(dir.path().join("agent.state.json"), r#"{"state_rev": 2}"#).unwrap();
+        std::fs::write(dir.path().join("agent.audit.jsonl"), "{}\n").unwrap();
+        let drift = manifest_drift(&manifest, persona).unwrap();
+        let roles: Vec<_> = drift.iter().map(|d| d.role.as_str()).collect();
+        assert_eq!(roles, ["state", "audit"]);
+        assert!(drift[1].expected.is_none());
+
+        std::fs::remove_file(dir.path().join("agent.state.json")).unwrap();
+        let drift = manifest_drift(&manifest, persona).unwrap();
+        assert!(drift[0].actual.is_none());
+    }
+
+    #[test]
+    fn missing_persona_is_an_error() {
+        let err = build_manifest("/nonexistent/agent.json").unwrap_err();
+        assert_eq!(err.code(), "E_FILE_NOT_FOUND");
+    }
+}
@@ -1,6 +1,12 @@
 pub mod atomic;
+pub mod audit_crypto;
 pub mod audit_log;
+pub mod bundle;
 pub mod drift;
 pub mod elevation;
+pub mod manifest;
 pub mod phase;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+pub mod store;
 pub mod writer;
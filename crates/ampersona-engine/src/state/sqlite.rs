@@ -0,0 +1,507 @@
+//! Phase state and audit logs in one SQLite database per workspace
+//! (`.ampersona/state.db`).
+//!
+//! Rows are keyed by the state path the files backend would use, relative to
+//! the workspace (`agent.state.json`), so sidecars import one-to-one. An audit row holds
+//! exactly the JSON line the `.audit.jsonl` file would hold, so the hash chain
+//! and [`verify_lines`] work unchanged over the rows.
+
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use ampersona_core::state::PhaseState;
+use rusqlite::{params, Connection, OptionalExtension, TransactionBehavior};
+use serde::Serialize;
+use serde_json::Value;
+
+use super::atomic::stale_reason;
+use super::audit_crypto::AuditKey;
+use super::audit_log::{chain_line, verify_lines};
+use super::store::{StateStore, StoreLock};
+use crate::error::{EngineError, Result};
+
+/// Workspace database, relative to the workspace root.
+pub const DEFAULT_DB: &str = ".ampersona/state.db";
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS state (
+    key TEXT PRIMARY KEY,
+    json TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS audit (
+    key TEXT NOT NULL,
+    seq INTEGER NOT NULL,
+    line TEXT NOT NULL,
+    PRIMARY KEY (key, seq)
+);
+CREATE TABLE IF NOT EXISTS locks (
+    key TEXT PRIMARY KEY,
+    pid INTEGER NOT NULL,
+    locked_at INTEGER NOT NULL
+);
+";
+
+const LOCK_RETRY_INTERVAL: Duration = Duration::from_millis(50);
+
+/// One persona's state and audit log in the workspace database.
+pub struct SqliteStateStore {
+    db_path: String,
+    key: String,
+    conn: Mutex<Connection>,
+}
+
+impl SqliteStateStore {
+    /// Open (creating if needed) `db_path` for the persona keyed by `key`.
+    pub fn open(db_path: &str, key: &str) -> Result<Self> {
+        Ok(Self {
+            db_path: db_path.to_string(),
+            key: key.to_string(),
+            conn: Mutex::new(connect(db_path)?),
+        })
+    }
+
+    /// Open the workspace database holding the state file at `state_path`.
+    ///
+    /// The workspace is `root` when given, else the directory of the state
+    /// file. The row key is the state path relative to the canonicalized
+    /// workspace (absolute when outside it), so `a.state.json`,
+    /// `./a.state.json` and `/abs/a.state.json` name one row from any
+    /// working directory.
+    pub fn for_state(state_path: &str, root: Option<&Path>) -> Result<Self> {
+        let path = Path::new(state_path);
+        let dir = canonical(
+            path.parent()
+                .filter(|p| !p.as_os_str().is_empty())
+                .unwrap_or(Path::new(".")),
+        );
+        let state = dir.join(path.file_name().unwrap_or(path.as_os_str()));
+        let root = root.map(canonical).unwrap_or(dir);
+        let key = state.strip_prefix(&root).unwrap_or(&state);
+        Self::open(
+            &root.join(DEFAULT_DB).to_string_lossy(),
+            &key.to_string_lossy(),
+        )
+    }
+
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// `db#key`, naming this persona's rows in messages and reports.
+    pub fn location(&self) -> String {
+        format!("{}#{}", self.db_path, self.key)
+    }
+
+    /// Verify the audit hash chain from entry `from_entry`; see
+    /// [`verify_chain_from`](super::audit_log::verify_chain_from).
+    pub fn verify_chain_from(&self, from_entry: u64) -> Result<u64> {
+        let lines = self.audit_lines()?.unwrap_or_default();
+        verify_lines(
+            &self.location(),
+            lines.iter().map(String::as_str),
+            from_entry,
+        )
+    }
+
+    /// Copy a state file and audit log into the database, verbatim, so the
+    /// chain still verifies. Refuses if this key already has rows.
+    pub fn import_files(&self, state_path: &str, audit_path: &str) -> Result<Imported> {
+        let state = match std::fs::read_to_string(state_path) {
+            Ok(content) => Some(content),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+            Err(e) => return Err(EngineError::read(state_path, e)),
+        };
+        if let Some(content) = &state {
+            serde_json::from_str::<PhaseState>(content).map_err(|source| {
+                EngineError::InvalidJson {
+                    path: state_path.to_string(),
+                    source,
+                }
+            })?;
+        }
+        let audit = match std::fs::read_to_string(audit_path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(EngineError::read(audit_path, e)),
+        };
+        let lines: Vec<&str> = audit.lines().filter(|l| !l.trim().is_empty()).collect();
+        verify_lines(audit_path, lines.iter().copied(), 0)?;
+
+        let mut conn = self.conn();
+        let tx = conn
+            .transaction_with_behavior(TransactionBehavior::Immediate)
+            .map_err(db_err)?;
+        let existing: i64 = tx
+            .query_row(
+                "SELECT (SELECT COUNT(*) FROM state WHERE key = ?1)
+                      + (SELECT COUNT(*) FROM audit WHERE key = ?1)",
+                [&self.key],
+                |row| row.get(0),
+            )
+            .map_err(db_err)?;
+        if existing > 0 {
+            return Err(EngineError::Storage(format!(
+                "{} already holds {}; not importing over it",
+                self.db_path, self.key
+            )));
+        }
+        if let Some(content) = &state {
+            tx.execute(
+                "INSERT INTO state (key, json) VALUES (?1, ?2)",
+                params![self.key, content],
+            )
+            .map_err(db_err)?;
+        }
+        for (seq, line) in lines.iter().enumerate() {
+            tx.execute(
+                "INSERT INTO audit (key, seq, line) VALUES (?1, ?2, ?3)",
+                params![self.key, seq as i64, line],
+            )
+            .map_err(db_err)?;
+        }
+        tx.commit().map_err(db_err)?;
+        Ok(Imported {
+            db: self.db_path.clone(),
+            key: self.key.clone(),
+            state: state.is_some(),
+            audit_entries: lines.len(),
+        })
+    }
+
+    fn conn(&self) -> MutexGuard<'_, Connection> {
+        self.conn.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// What [`SqliteStateStore::import_files`] copied.
+#[derive(Debug, Clone, Serialize)]
+pub struct Imported {
+    pub db: String,
+    pub key: String,
+    pub state: bool,
+    pub audit_entries: usize,
+}
+
+impl StateStore for SqliteStateStore {
+    fn load(&self) -> Result<Option<PhaseState>> {
+        let json: Option<String> = self
+            .conn()
+            .query_row(
+                "SELECT json FROM state WHERE key = ?1",
+                [&self.key],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(db_err)?;
+        json.map(|json| {
+            serde_json::from_str(&json).map_err(|source| EngineError::InvalidJson {
+                path: self.location(),
+                source,
+            })
+        })
+        .transpose()
+    }
+
+    fn write(&self, state: &PhaseState) -> Result<()> {
+        let json = serde_json::to_string_pretty(state)?;
+        self.conn()
+            .execute(
+                "INSERT INTO state (key, json) VALUES (?1, ?2)
+                 ON CONFLICT (key) DO UPDATE SET json = excluded.json",
+                params![self.key, json],
+            )
+            .map_err(db_err)?;
+        Ok(())
+    }
+
+    /// Reads the chain head and inserts the next row in one transaction, so
+    /// concurrent appenders cannot fork the chain.
+    fn append_audit(&self, entry: &Value) -> Result<()> {
+        let key = AuditKey::from_env()?;
+        let mut conn = self.conn();
+        let tx = conn
+            .transaction_with_behavior(TransactionBehavior::Immediate)
+            .map_err(db_err)?;
+        let last: Option<(i64, String)> = tx
+            .query_row(
+                "SELECT seq, line FROM audit WHERE key = ?1 ORDER BY seq DESC LIMIT 1",
+                [&self.key],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()
+            .map_err(db_err)?;
+        let line = chain_line(last.as_ref().map(|(_, l)| l.as_str()), entry, key.as_ref())?;
+        let seq = last.map_or(0, |(seq, _)| seq + 1);
+        tx.execute(
+            "INSERT INTO audit (key, seq, line) VALUES (?1, ?2, ?3)",
+            params![self.key, seq, line],
+        )
+        .map_err(db_err)?;
+        tx.commit().map_err(db_err)
+    }
+
+    /// `None` when the key has no audit rows.
+    fn audit_lines(&self) -> Result<Option<Vec<String>>> {
+        let conn = self.conn();
+        let mut stmt = conn
+            .prepare("SELECT line FROM audit WHERE key = ?1 ORDER BY seq")
+            .map_err(db_err)?;
+        let lines = stmt
+            .query_map([&self.key], |row| row.get(0))
+            .map_err(db_err)?
+            .collect::<std::result::Result<Vec<String>, _>>()
+            .map_err(db_err)?;
+        Ok((!lines.is_empty()).then_some(lines))
+    }
+
+    fn audit_location(&self) -> String {
+        self.location()
+    }
+
+    /// A row in `locks`; a stale one (see [`stale_reason`]) is reclaimed and
+    /// audited as `LockReclaimed`, like a stale lock file.
+    fn lock(&self, timeout: Duration) -> Result<StoreLock> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            let now = chrono::Utc::now().timestamp();
+            let pid = std::process::id();
+            let conn = self.conn();
+            let inserted = conn
+                .execute(
+                    "INSERT OR IGNORE INTO locks (key, pid, locked_at) VALUES (?1, ?2, ?3)",
+                    params![self.key, pid, now],
+                )
+                .map_err(db_err)?;
+            if inserted == 1 {
+                drop(conn);
+                return Ok(StoreLock::new(SqliteLock {
+                    conn: connect(&self.db_path)?,
+                    key: self.key.clone(),
+                    pid,
+                    locked_at: now,
+                }));
+            }
+            let holder: Option<(u32, i64)> = conn
+                .query_row(
+                    "SELECT pid, locked_at FROM locks WHERE key = ?1",
+                    [&self.key],
+                    |row| Ok((row.get(0)?, row.get(1)?)),
+                )
+                .optional()
+                .map_err(db_err)?;
+            drop(conn);
+            let stale = holder.and_then(|(pid, locked_at)| {
+                stale_reason(Some(pid), Some(locked_at), now).map(|r| (pid, locked_at, r))
+            });
+            if let Some((pid, locked_at, reason)) = stale {
+                // Only the row judged stale: a holder that took over since keeps its lock
+                let removed = self
+                    .conn()
+                    .execute(
+                        "DELETE FROM locks WHERE key = ?1 AND pid = ?2 AND locked_at = ?3",
+                        params![self.key, pid, locked_at],
+                    )
+                    .map_err(db_err)?;
+                if removed == 1 {
+                    self.append_audit(&serde_json::json!({
+                        "event_type": "LockReclaimed",
+                        "lock_path": self.location(),
+                        "holder_pid": pid,
+                        "locked_at": locked_at,
+                        "reason": reason,
+                    }))?;
+                }
+                continue;
+            }
+            if Instant::now() >= deadline {
+                return Err(EngineError::LockContention {
+                    lock_path: self.location(),
+                });
+            }
+            std::thread::sleep(LOCK_RETRY_INTERVAL);
+        }
+    }
+}
+
+struct SqliteLock {
+    conn: Connection,
+    key: String,
+    pid: u32,
+    locked_at: i64,
+}
+
+impl Drop for SqliteLock {
+    fn drop(&mut self) {
+        // Only our row: if it was reclaimed, the new holder keeps its lock
+        let _ = self.conn.execute(
+            "DELETE FROM locks WHERE key = ?1 AND pid = ?2 AND locked_at = ?3",
+            params![self.key, self.pid, self.locked_at],
+        );
+    }
+}
+
+/// `path` canonicalized, or made absolute if it doesn't exist yet.
+fn canonical(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| {
+        std::env::current_dir()
+            .map(|cwd| cwd.join(path))
+            .unwrap_or_else(|_| path.to_path_buf())
+    })
+}
+
+fn connect(db_path: &str) -> Result<Connection> {
+    if let Some(dir) = std::path::Path::new(db_path)
+        .parent()
+        .filter(|d| !d.as_os_str().is_empty())
+    {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| EngineError::io(format!("cannot create {}", dir.display()), e))?;
+    }
+    let conn = Connection::open(db_path).map_err(db_err)?;
+    conn.busy_timeout(Duration::from_secs(5)).map_err(db_err)?;
+    conn.execute_batch(SCHEMA).map_err(db_err)?;
+    Ok(conn)
+}
+
+fn db_err(e: rusqlite::Error) -> EngineError {
+    EngineError::Storage(e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::audit_log::append_audit_with;
+    use crate::state::writer::StateWriter;
+
+    #[test]
+    fn lifecycle_round_trips_and_chain_verifies() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = dir.path().join("state.db");
+        let store = SqliteStateStore::open(db.to_str().unwrap(), "agent.state.json").unwrap();
+        assert!(store.load().unwrap().is_none());
+
+        for phase in ["trusted", "active"] {
+            let writer = StateWriter::with_store(&store, Duration::ZERO).unwrap();
+            let mut state = store.load_or_new("agent");
+            state.current_phase = Some(phase.into());
+            state.state_rev += 1;
+            writer
+                .audit(&serde_json::json!({"event_type": "GateTransition", "to_phase": phase}))
+                .unwrap();
+            writer.write_state(&state).unwrap();
+        }
+
+        let state = store.load().unwrap().unwrap();
+        assert_eq!(state.current_phase.as_deref(), Some("active"));
+        assert_eq!(state.state_rev, 2);
+        assert_eq!(store.verify_chain_from(0).unwrap(), 2);
+
+        // Another persona in the same database has its own chain and lock.
+        let other = SqliteStateStore::open(db.to_str().unwrap(), "other.state.json").unwrap();
+        let _held = other.lock(Duration::ZERO).unwrap();
+        assert!(store.lock(Duration::ZERO).is_ok());
+        assert!(other.lock(Duration::ZERO).is_err());
+        assert_eq!(other.verify_chain_from(0).unwrap(), 0);
+    }
+
+    #[test]
+    fn rows_are_keyed_relative_to_the_workspace() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("ns")).unwrap();
+        let at = |rel: &str| dir.path().join(rel).to_string_lossy().into_owned();
+
+        let store = SqliteStateStore::for_state(&at("a.state.json"), None).unwrap();
+        assert_eq!(store.key(), "a.state.json");
+        assert!(dir.path().join(DEFAULT_DB).exists());
+        let again = SqliteStateStore::for_state(&at("ns/../a.state.json"), None).unwrap();
+        assert_eq!(again.location(), store.location());
+
+        let nested = SqliteStateStore::for_state(&at("ns/a.state.json"), Some(dir.path())).unwrap();
+        assert_eq!(
+            nested.key(),
+            Path::new("ns").join("a.state.json").to_string_lossy()
+        );
+        let db = dir.path().canonicalize().unwrap().join(DEFAULT_DB);
+        assert!(nested.location().starts_with(&*db.to_string_lossy()));
+    }
+
+    #[test]
+    fn dropping_a_reclaimed_lock_leaves_the_new_holder() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = dir.path().join("state.db");
+        let store = SqliteStateStore::open(db.to_str().unwrap(), "a").unwrap();
+
+        let lock = store.lock(Duration::ZERO).unwrap();
+        // Another writer reclaimed ours and now holds the lock.
+        store
+            .conn()
+            .execute("UPDATE locks SET pid = 1, locked_at = locked_at - 1", [])
+            .unwrap();
+        drop(lock);
+        assert!(store.lock(Duration::ZERO).is_err());
+    }
+
+    #[test]
+    fn tampered_row_breaks_the_chain() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = dir.path().join("state.db");
+        let store = SqliteStateStore::open(db.to_str().unwrap(), "a").unwrap();
+        for i in 0..3 {
+            store
+                .append_audit(&serde_json::json!({"event_type": "StateChange", "n": i}))
+                .unwrap();
+        }
+        store
+            .conn()
+            .execute(
+                "UPDATE audit SET line = replace(line, '\"n\":1', '\"n\":9') WHERE seq = 1",
+                [],
+            )
+            .unwrap();
+        let err = store.verify_chain_from(0).unwrap_err();
+        assert_eq!(err.code(), "E_CHAIN_BROKEN");
+    }
+
+    #[test]
+    fn imports_sidecars_verbatim_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let state_path = dir.path().join("agent.state.json");
+        let audit_path = dir.path().join("agent.audit.jsonl");
+        let audit = audit_path.to_str().unwrap();
+        let mut state = PhaseState::new("agent".into());
+        state.current_phase = Some("active".into());
+        std::fs::write(&state_path, serde_json::to_string(&state).unwrap()).unwrap();
+        for _ in 0..2 {
+            append_audit_with(audit, &serde_json::json!({"event_type": "x"}), None).unwrap();
+        }
+
+        let db = dir.path().join("state.db");
+        let store = SqliteStateStore::open(db.to_str().unwrap(), "agent.state.json").unwrap();
+        let imported = store
+            .import_files(state_path.to_str().unwrap(), audit)
+            .unwrap();
+        assert!(imported.state);
+        assert_eq!(imported.audit_entries, 2);
+        assert_eq!(
+            store.audit_lines().unwrap().unwrap().join("\n") + "\n",
+            std::fs::read_to_string(audit).unwrap()
+        );
+        assert_eq!(store.verify_chain_from(0).unwrap(), 2);
+        assert_eq!(
+            store.load().unwrap().unwrap().current_phase.as_deref(),
+            Some("active")
+        );
+
+        // Appending after the import continues the imported chain.
+        store
+            .append_audit(&serde_json::json!({"event_type": "y"}))
+            .unwrap();
+        assert_eq!(store.verify_chain_from(0).unwrap(), 3);
+
+        let err = store
+            .import_files(state_path.to_str().unwrap(), audit)
+            .unwrap_err();
+        assert_eq!(err.code(), "E_STORAGE");
+    }
+}
@@ -0,0 +1,359 @@
+//! Where phase state and its audit trail live.
+//!
+//! [`FileStateStore`] is the on-disk layout (`<name>.state.json` next to
+//! `<name>.audit.jsonl`, guarded by an advisory lock file).
+//! [`InMemoryStateStore`] keeps both in memory for tests, stdin pipelines and
+//! long-running processes that persist state elsewhere.
+
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use ampersona_core::sidecar::Sidecars;
+use ampersona_core::state::PhaseState;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use super::atomic::{atomic_write, AdvisoryLock};
+use super::audit_log::append_audit;
+use crate::error::{EngineError, Result};
+
+/// Storage backend for one persona's phase state and audit log.
+pub trait StateStore {
+    /// The stored state, or `None` if none has been written yet.
+    fn load(&self) -> Result<Option<PhaseState>>;
+
+    /// Replace the stored state.
+    fn write(&self, state: &PhaseState) -> Result<()>;
+
+    /// Append an entry to the hash-chained audit log.
+    fn append_audit(&self, entry: &Value) -> Result<()>;
+
+    /// Exclusive write access, waiting up to `timeout` for another holder.
+    /// Released when the returned [`StoreLock`] is dropped.
+    fn lock(&self, timeout: Duration) -> Result<StoreLock>;
+
+    /// The audit log as stored, one entry per line, oldest first; `None` if
+    /// nothing has been logged yet.
+    fn audit_lines(&self) -> Result<Option<Vec<String>>>;
+
+    /// Where the audit log lives, naming it in reports and errors.
+    fn audit_location(&self) -> String;
+
+    /// The stored state, or a fresh one for `persona` when there is none or it
+    /// cannot be read.
+    fn load_or_new(&self, persona: &str) -> PhaseState {
+        self.load()
+            .ok()
+            .flatten()
+            .unwrap_or_else(|| PhaseState::new(persona.to_string()))
+    }
+}
+
+impl<S: StateStore + ?Sized> StateStore for &S {
+    fn load(&self) -> Result<Option<PhaseState>> {
+        (**self).load()
+    }
+
+    fn write(&self, state: &PhaseState) -> Result<()> {
+        (**self).write(state)
+    }
+
+    fn append_audit(&self, entry: &Value) -> Result<()> {
+        (**self).append_audit(entry)
+    }
+
+    fn lock(&self, timeout: Duration) -> Result<StoreLock> {
+        (**self).lock(timeout)
+    }
+
+    fn audit_lines(&self) -> Result<Option<Vec<String>>> {
+        (**self).audit_lines()
+    }
+
+    fn audit_location(&self) -> String {
+        (**self).audit_location()
+    }
+}
+
+/// A held store lock; dropping it releases the lock.
+pub struct StoreLock {
+    _guard: Box<dyn Send>,
+}
+
+impl StoreLock {
+    /// Wrap whatever releases the lock when dropped.
+    pub fn new(guard: impl Send + 'static) -> Self {
+        Self {
+            _guard: Box::new(guard),
+        }
+    }
+}
+
+/// State and audit log as files.
+#[derive(Debug, Clone)]
+pub struct FileStateStore {
+    state_path: String,
+    audit_path: String,
+}
+
+impl FileStateStore {
+    pub fn new(state_path: &str, audit_path: &str) -> Self {
+        Self {
+            state_path: state_path.to_string(),
+            audit_path: audit_path.to_string(),
+        }
+    }
+
+    /// The default sidecars of the persona at `persona`.
+    pub fn for_persona(persona: &str) -> Self {
+        let paths = Sidecars::for_persona(persona);
+        Self::new(&paths.state, &paths.audit)
+    }
+
+    pub fn state_path(&self) -> &str {
+        &self.state_path
+    }
+
+    pub fn audit_path(&self) -> &str {
+        &self.audit_path
+    }
+}
+
+impl StateStore for FileStateStore {
+    fn load(&self) -> Result<Option<PhaseState>> {
+        if !std::path::Path::new(&self.state_path).exists() {
+            return Ok(None);
+        }
+        super::phase::load_state(&self.state_path).map(Some)
+    }
+
+    fn write(&self, state: &PhaseState) -> Result<()> {
+        let json = serde_json::to_string_pretty(state)?;
+        atomic_write(&self.state_path, json.as_bytes())
+    }
+
+    fn append_audit(&self, entry: &Value) -> Result<()> {
+        append_audit(&self.audit_path, entry).map(|_| ())
+    }
+
+    /// If a stale lock had to be reclaimed, a `LockReclaimed` entry is
+    /// appended to the audit log before any mutation is recorded.
+    fn lock(&self, timeout: Duration) -> Result<StoreLock> {
+        let lock = AdvisoryLock::acquire_with_timeout(&self.state_path, timeout)?;
+        if let Some(stale) = lock.reclaimed() {
+            self.append_audit(&serde_json::json!({
+                "event_type": "LockReclaimed",
+                "lock_path": format!("{}.lock", self.state_path),
+                "holder_pid": stale.pid,
+                "locked_at": stale.locked_at,
+                "reason": stale.reason,
+            }))?;
+        }
+        Ok(StoreLock::new(lock))
+    }
+
+    fn audit_lines(&self) -> Result<Option<Vec<String>>> {
+        match std::fs::read_to_string(&self.audit_path) {
+            Ok(content) => Ok(Some(content.lines().map(str::to_string).collect())),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(EngineError::read(&self.audit_path, e)),
+        }
+    }
+
+    fn audit_location(&self) -> String {
+        self.audit_path.clone()
+    }
+}
+
+/// State and audit log in memory. Clones share the same storage.
+#[derive(Debug, Clone, Default)]
+pub struct InMemoryStateStore {
+    inner: Arc<Mutex<Memory>>,
+    held: Arc<(Mutex<bool>, Condvar)>,
+}
+
+#[derive(Debug, Default)]
+struct Memory {
+    state: Option<PhaseState>,
+    audit: Vec<Value>,
+}
+
+impl InMemoryStateStore {
+    /// A store that starts out holding `state`.
+    pub fn with_state(state: PhaseState) -> Self {
+        let store = Self::default();
+        store.memory().state = Some(state);
+        store
+    }
+
+    /// Audit entries in append order, with their `prev_hash` and `ts`.
+    pub fn audit_entries(&self) -> Vec<Value> {
+        self.memory().audit.clone()
+    }
+
+    fn memory(&self) -> MutexGuard<'_, Memory> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl StateStore for InMemoryStateStore {
+    fn load(&self) -> Result<Option<PhaseState>> {
+        Ok(self.memory().state.clone())
+    }
+
+    fn write(&self, state: &PhaseState) -> Result<()> {
+        self.memory().state = Some(state.clone());
+        Ok(())
+    }
+
+    /// Chains entries the way the file log does: `prev_hash` is the SHA-256
+    /// of the previous entry's serialized form.
+    fn append_audit(&self, entry: &Value) -> Result<()> {
+        let mut memory = self.memory();
+        let prev_hash = match memory.audit.last() {
+            Some(last) => format!(
+                "sha256:{:x}",
+                Sha256::digest(serde_json::to_string(last)?.as_bytes())
+            ),
+            None => "genesis".to_string(),
+        };
+        let mut entry = entry.clone();
+        if let Some(obj) = entry.as_object_mut() {
+            obj.insert("prev_hash".into(), Value::String(prev_hash));
+            obj.insert("ts".into(), Value::String(chrono::Utc::now().to_rfc3339()));
+        }
+        memory.audit.push(entry);
+        Ok(())
+    }
+
+    fn lock(&self, timeout: Duration) -> Result<StoreLock> {
+        let deadline = Instant::now() + timeout;
+        let (flag, released) = &*self.held;
+        let mut held = flag.lock().unwrap_or_else(|e| e.into_inner());
+        while *held {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return Err(EngineError::LockContention {
+                    lock_path: "<memory>".into(),
+                });
+            }
+            held = released
+                .wait_timeout(held, remaining)
+                .unwrap_or_else(|e| e.into_inner())
+                .0;
+        }
+        *held = true;
+        Ok(StoreLock::new(MemoryLock(Arc::clone(&self.held))))
+    }
+
+    fn audit_lines(&self) -> Result<Option<Vec<String>>> {
+        let memory = self.memory();
+        if memory.audit.is_empty() {
+            return Ok(None);
+        }
+        let lines = memory
+            .audit
+            .iter()
+            .map(serde_json::to_string)
+            .collect::<std::result::Result<_, _>>()?;
+        Ok(Some(lines))
+    }
+
+    fn audit_location(&self) -> String {
+        "<memory>".into()
+    }
+}
+
+struct MemoryLock(Arc<(Mutex<bool>, Condvar)>);
+
+impl Drop for MemoryLock {
+    fn drop(&mut self) {
+        let (flag, released) = &*self.0;
+        *flag.lock().unwrap_or_else(|e| e.into_inner()) = false;
+        released.notify_one();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::writer::StateWriter;
+
+    #[test]
+    fn in_memory_store_drives_gate_and_elevation_lifecycle() {
+        let store = InMemoryStateStore::default();
+        assert!(store.load().unwrap().is_none());
+
+        // Gate transition.
+        {
+            let writer = StateWriter::with_store(&store, Duration::ZERO).unwrap();
+            let mut state = store.load_or_new("agent");
+            state.current_phase = Some("active".into());
+            state.state_rev += 1;
+            writer
+                .audit(&serde_json::json!({"event_type": "GateTransition", "to_phase": "active"}))
+                .unwrap();
+            writer.write_state(&state).unwrap();
+        }
+
+        // Elevation.
+        {
+            let writer = StateWriter::with_store(&store, Duration::ZERO).unwrap();
+            let mut state = store.load_or_new("agent");
+            crate::state::elevation::activate(&mut state, "deploy", 60, "release", "test");
+            state.state_rev += 1;
+            writer
+                .maybe_audit(
+                    None,
+                    "ElevationChange",
+                    &serde_json::json!({"event_type": "ElevationChange"}),
+                )
+                .unwrap();
+            writer.write_state(&state).unwrap();
+        }
+
+        let state = store.load().unwrap().unwrap();
+        assert_eq!(state.current_phase.as_deref(), Some("active"));
+        assert_eq!(state.state_rev, 2);
+        assert_eq!(state.active_elevations.len(), 1);
+
+        let audit = store.audit_entries();
+        assert_eq!(audit.len(), 2);
+        assert_eq!(audit[0]["prev_hash"], "genesis");
+        let first = serde_json::to_string(&audit[0]).unwrap();
+        assert_eq!(
+            audit[1]["prev_hash"],
+            format!("sha256:{:x}", Sha256::digest(first.as_bytes()))
+        );
+    }
+
+    #[test]
+    fn in_memory_lock_is_exclusive_until_dropped() {
+        let store = InMemoryStateStore::default();
+        let held = store.lock(Duration::ZERO).unwrap();
+        let err = store.lock(Duration::from_millis(20)).err().unwrap();
+        assert_eq!(err.code(), "E_LOCK_CONTENTION");
+
+        let waiter = {
+            let store = store.clone();
+            std::thread::spawn(move || store.lock(Duration::from_secs(5)).map(|_| ()))
+        };
+        std::thread::sleep(Duration::from_millis(50));
+        drop(held);
+        waiter.join().unwrap().unwrap();
+    }
+
+    #[test]
+    fn file_store_reports_missing_state_as_none() {
+        let dir = tempfile::tempdir().unwrap();
+        let persona = dir.path().join("agent.json");
+        let store = FileStateStore::for_persona(persona.to_str().unwrap());
+        assert!(store.load().unwrap().is_none());
+
+        let mut state = PhaseState::new("agent".into());
+        state.state_rev = 3;
+        store.write(&state).unwrap();
+        assert_eq!(store.load().unwrap().unwrap().state_rev, 3);
+        assert!(store.state_path().ends_with("agent.state.json"));
+    }
+}
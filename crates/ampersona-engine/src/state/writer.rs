@@ -1,9 +1,10 @@
+use std::time::Duration;
+
+use super::store::{FileStateStore, StateStore, StoreLock};
+use crate::error::Result;
+use ampersona_core::sidecar::Sidecars;
 use ampersona_core::spec::audit::AuditConfig;
 use ampersona_core::state::PhaseState;
-use anyhow::{Context, Result};
-
-use super::atomic::{atomic_write, AdvisoryLock};
-use super::audit_log::append_audit;
 
 /// Coordinated state writer: lock → mutate → audit → atomic write → unlock.
 ///
@@ -14,29 +15,53 @@ use super::audit_log::append_audit;
 /// 4. Write audit entry (respecting AuditConfig)
 /// 5. Atomic write new state
 /// 6. Release lock (on drop)
-pub struct StateWriter {
-    state_path: String,
-    audit_path: String,
-    _lock: AdvisoryLock,
+///
+/// The writer works against any [`StateStore`]; the `acquire*` constructors
+/// use the on-disk [`FileStateStore`].
+pub struct StateWriter<'a> {
+    store: Box<dyn StateStore + 'a>,
+    _lock: StoreLock,
 }
 
-impl StateWriter {
+impl StateWriter<'static> {
     /// Acquire the advisory lock and prepare for state mutation.
     pub fn acquire(state_path: &str) -> Result<Self> {
-        let lock = AdvisoryLock::acquire(state_path)
-            .with_context(|| format!("cannot lock state {state_path}"))?;
-        let audit_path = state_path.replace(".state.json", ".audit.jsonl");
+        Self::acquire_with_timeout(state_path, Duration::ZERO)
+    }
+
+    /// Acquire the advisory lock, waiting up to `timeout` for another writer.
+    ///
+    /// If a stale lock had to be reclaimed, a `LockReclaimed` entry is appended
+    /// to the audit log before any mutation is recorded.
+    pub fn acquire_with_timeout(state_path: &str, timeout: Duration) -> Result<Self> {
+        let audit_path = Sidecars::for_state(state_path).audit;
+        Self::acquire_with_audit(state_path, &audit_path, timeout)
+    }
+
+    /// Like [`StateWriter::acquire_with_timeout`], with an explicit audit log
+    /// instead of the one derived from `state_path`.
+    pub fn acquire_with_audit(
+        state_path: &str,
+        audit_path: &str,
+        timeout: Duration,
+    ) -> Result<Self> {
+        Self::with_store(FileStateStore::new(state_path, audit_path), timeout)
+    }
+}
+
+impl<'a> StateWriter<'a> {
+    /// Lock `store` for writing, waiting up to `timeout` for another writer.
+    pub fn with_store(store: impl StateStore + 'a, timeout: Duration) -> Result<Self> {
+        let lock = store.lock(timeout)?;
         Ok(Self {
-            state_path: state_path.to_string(),
-            audit_path,
+            store: Box::new(store),
             _lock: lock,
         })
     }
 
     /// Write the updated state atomically.
     pub fn write_state(&self, state: &PhaseState) -> Result<()> {
-        let json = serde_json::to_string_pretty(state)?;
-        atomic_write(&self.state_path, json.as_bytes())
+        self.store.write(state)
     }
 
     /// Append an audit entry if the AuditConfig allows it for this event type.
@@ -47,20 +72,14 @@ impl StateWriter {
         entry: &serde_json::Value,
     ) -> Result<()> {
         if should_audit(audit_config, event_type) {
-            append_audit(&self.audit_path, entry)?;
+            self.store.append_audit(entry)?;
         }
         Ok(())
     }
 
     /// Unconditionally append an audit entry.
     pub fn audit(&self, entry: &serde_json::Value) -> Result<()> {
-        append_audit(&self.audit_path, entry)?;
-        Ok(())
-    }
-
-    /// Returns the audit path for manual operations.
-    pub fn audit_path(&self) -> &str {
-        &self.audit_path
+        self.store.append_audit(entry)
     }
 }
 
@@ -75,10 +94,15 @@ fn should_audit(config: Option<&AuditConfig>, event_type: &str) -> bool {
         "PolicyDecision" => config.log_decisions,
         // These event types are always audited (security-critical)
         "Override"
+        | "AdminPhaseReset"
+        | "LockReclaimed"
+        | "Reconcile"
         | "ElevationChange"
         | "SignatureVerify"
         | "StateChange"
-        | "AuthorityOverlayChange" => true,
+        | "AuthorityOverlayChange"
+        | "GateEnforcementChange"
+        | "HookResult" => true,
         _ => true,
     }
 }
@@ -107,6 +131,7 @@ mod tests {
         // Security-critical events always audited
         assert!(should_audit(Some(&config), "Override"));
         assert!(should_audit(Some(&config), "ElevationChange"));
+        assert!(should_audit(Some(&config), "AdminPhaseReset"));
     }
 
     #[test]
@@ -128,4 +153,47 @@ mod tests {
         let loaded = super::super::phase::load_state(state_str).unwrap();
         assert_eq!(loaded.state_rev, 1);
     }
+
+    #[test]
+    fn state_writer_second_writer_waits_then_proceeds() {
+        let dir = tempfile::tempdir().unwrap();
+        let state_path = dir.path().join("test.state.json");
+        let state_str = state_path.to_str().unwrap().to_string();
+
+        let first = StateWriter::acquire(&state_str).unwrap();
+        let second = {
+            let path = state_str.clone();
+            std::thread::spawn(move || {
+                let writer = StateWriter::acquire_with_timeout(&path, Duration::from_secs(5))?;
+                let mut state = PhaseState::new("test".to_string());
+                state.state_rev = 2;
+                writer.write_state(&state)
+            })
+        };
+
+        let mut state = PhaseState::new("test".to_string());
+        state.state_rev = 1;
+        first.write_state(&state).unwrap();
+        std::thread::sleep(Duration::from_millis(100));
+        drop(first);
+
+        second.join().unwrap().unwrap();
+        let loaded = super::super::phase::load_state(&state_str).unwrap();
+        assert_eq!(loaded.state_rev, 2);
+    }
+
+    #[test]
+    fn state_writer_audits_reclaimed_lock() {
+        let dir = tempfile::tempdir().unwrap();
+        let state_path = dir.path().join("test.state.json");
+        let state_str = state_path.to_str().unwrap();
+        let old = chrono::Utc::now().timestamp() - 3600;
+        std::fs::write(format!("{state_str}.lock"), format!("?\n{old}\n")).unwrap();
+
+        let _writer = StateWriter::acquire(state_str).unwrap();
+        let audit = std::fs::read_to_string(dir.path().join("test.audit.jsonl")).unwrap();
+        let entry: serde_json::Value = serde_json::from_str(audit.lines().next().unwrap()).unwrap();
+        assert_eq!(entry["event_type"], "LockReclaimed");
+        assert_eq!(entry["locked_at"], old);
+    }
 }
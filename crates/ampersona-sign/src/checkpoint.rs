@@ -0,0 +1,265 @@
+use anyhow::{bail, Result};
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use serde_json::Value;
+
+/// Outcome of verifying a checkpoint's signatures against a set of keys.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CheckpointSignatures {
+    /// key_ids whose signature verified under a distinct provided key.
+    pub valid: Vec<String>,
+    /// key_ids whose signature matched none of the remaining keys.
+    pub invalid: Vec<String>,
+}
+
+/// Add an ed25519 signature to a checkpoint's `signatures` array.
+///
+/// Every signature covers the checkpoint without `signature`/`signatures`, so
+/// parties can co-sign in any order. A legacy single `signature` object is
+/// moved into the array. Signing twice with the same `key_id` is rejected.
+pub fn sign_checkpoint(
+    checkpoint: &mut Value,
+    signing_key: &SigningKey,
+    key_id: &str,
+) -> Result<()> {
+    let mut signatures = collect_signatures(checkpoint)?;
+    if signatures.iter().any(|s| s.key_id == key_id) {
+        bail!("checkpoint already signed with key_id '{key_id}'");
+    }
+
+    let sig = signing_key.sign(&signable_bytes(checkpoint)?);
+    signatures.push(Entry {
+        key_id: key_id.to_string(),
+        value: sig.to_bytes().iter().map(|b| format!("{b:02x}")).collect(),
+    });
+
+    let obj = checkpoint
+        .as_object_mut()
+        .ok_or_else(|| anyhow::anyhow!("checkpoint must be a JSON object"))?;
+    obj.remove("signature");
+    obj.insert(
+        "signatures".into(),
+        Value::Array(
+            signatures
+                .into_iter()
+                .map(|s| {
+                    serde_json::json!({
+                        "key_id": s.key_id,
+                        "algorithm": "ed25519",
+                        "value": s.value,
+                    })
+                })
+                .collect(),
+        ),
+    );
+    Ok(())
+}
+
+/// Verify every checkpoint signature against `keys`.
+///
+/// Each key can satisfy at most one signature, so `valid.len()` is the number
+/// of distinct signers. Errors if the checkpoint is unsigned, a signature is
+/// malformed, or two signatures share a `key_id`.
+pub fn verify_checkpoint(
+    checkpoint: &Value,
+    keys: &[VerifyingKey],
+) -> Result<CheckpointSignatures> {
+    let signatures = collect_signatures(checkpoint)?;
+    if signatures.is_empty() {
+        bail!("checkpoint has no signature");
+    }
+    for (i, s) in signatures.iter().enumerate() {
+        if signatures[..i].iter().any(|prev| prev.key_id == s.key_id) {
+            bail!("duplicate key_id '{}' in checkpoint signatures", s.key_id);
+        }
+    }
+
+    let message = signable_bytes(checkpoint)?;
+    let legacy = legacy_signable_bytes(checkpoint)?;
+    let mut used = vec![false; keys.len()];
+    let mut report = CheckpointSignatures::default();
+    for s in signatures {
+        let sig = decode_signature(&s.value)?;
+        let matched = keys.iter().enumerate().find(|(i, key)| {
+            !used[*i] && (key.verify(&message, &sig).is_ok() || key.verify(&legacy, &sig).is_ok())
+        });
+        match matched {
+            Some((i, _)) => {
+                used[i] = true;
+                report.valid.push(s.key_id);
+            }
+            None => report.invalid.push(s.key_id),
+        }
+    }
+    Ok(report)
+}
+
+struct Entry {
+    key_id: String,
+    value: String,
+}
+
+/// The bytes every signature covers: the JCS form of the checkpoint minus its
+/// signatures, so reordering keys does not break verification.
+fn signable_bytes(checkpoint: &Value) -> Result<Vec<u8>> {
+    Ok(crate::canonical::canonicalize(&unsigned(checkpoint)))
+}
+
+/// Pre-JCS signing bytes (insertion-order serialization), still accepted so
+/// checkpoints signed by older releases keep verifying.
+fn legacy_signable_bytes(checkpoint: &Value) -> Result<Vec<u8>> {
+    Ok(serde_json::to_string(&unsigned(checkpoint))?.into_bytes())
+}
+
+fn unsigned(checkpoint: &Value) -> Value {
+    let mut signable = checkpoint.clone();
+    if let Some(obj) = signable.as_object_mut() {
+        obj.remove("signature");
+        obj.remove("signatures");
+    }
+    signable
+}
+
+/// Signatures from the `signatures` array plus any legacy `signature` object.
+fn collect_signatures(checkpoint: &Value) -> Result<Vec<Entry>> {
+    let legacy = checkpoint.get("signature").into_iter();
+    let array = checkpoint
+        .get("signatures")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten();
+    legacy
+        .chain(array)
+        .map(|s| {
+            let field = |name: &str| s.get(name).and_then(Value::as_str);
+            match (field("key_id"), field("value")) {
+                (Some(key_id), Some(value)) => Ok(Entry {
+                    key_id: key_id.to_string(),
+                    value: value.to_string(),
+                }),
+                _ => bail!("invalid signature format"),
+            }
+        })
+        .collect()
+}
+
+fn decode_signature(hex: &str) -> Result<Signature> {
+    if !hex.len().is_multiple_of(2) || !hex.is_ascii() {
+        bail!("invalid hex in signature");
+    }
+    let bytes: Vec<u8> = (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16))
+        .collect::<std::result::Result<_, _>>()
+        .map_err(|e| anyhow::anyhow!("invalid hex in signature: {e}"))?;
+    Signature::from_slice(&bytes).map_err(|e| anyhow::anyhow!("invalid signature: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn checkpoint() -> Value {
+        serde_json::json!({
+            "audit_file": "agent.audit.jsonl",
+            "entries": 3,
+            "chain_head": "sha256:00",
+            "created_at": "2026-01-01T00:00:00Z",
+        })
+    }
+
+    fn key(byte: u8) -> SigningKey {
+        SigningKey::from_bytes(&[byte; 32])
+    }
+
+    #[test]
+    fn two_cosigners_meet_threshold() {
+        let (alice, bob, eve) = (key(0xA1), key(0xB2), key(0xE3));
+        let mut cp = checkpoint();
+        sign_checkpoint(&mut cp, &alice, "alice").unwrap();
+        sign_checkpoint(&mut cp, &bob, "bob").unwrap();
+        assert_eq!(cp["signatures"].as_array().unwrap().len(), 2);
+
+        let both = verify_checkpoint(&cp, &[alice.verifying_key(), bob.verifying_key()]).unwrap();
+        assert_eq!(both.valid, vec!["alice", "bob"]);
+
+        let one = verify_checkpoint(&cp, &[alice.verifying_key(), eve.verifying_key()]).unwrap();
+        assert_eq!(one.valid, vec!["alice"]);
+        assert_eq!(one.invalid, vec!["bob"]);
+
+        // Passing the same key twice cannot satisfy two signatures.
+        let dup = verify_checkpoint(&cp, &[alice.verifying_key(), alice.verifying_key()]).unwrap();
+        assert_eq!(dup.valid.len(), 1);
+
+        cp["entries"] = serde_json::json!(4);
+        let tampered =
+            verify_checkpoint(&cp, &[alice.verifying_key(), bob.verifying_key()]).unwrap();
+        assert!(tampered.valid.is_empty());
+    }
+
+    #[test]
+    fn duplicate_key_ids_are_rejected() {
+        let mut cp = checkpoint();
+        sign_checkpoint(&mut cp, &key(1), "ops").unwrap();
+        assert!(sign_checkpoint(&mut cp, &key(2), "ops").is_err());
+
+        // A hand-edited duplicate fails verification outright.
+        let first = cp["signatures"][0].clone();
+        cp["signatures"].as_array_mut().unwrap().push(first);
+        let err = verify_checkpoint(&cp, &[key(1).verifying_key()]).unwrap_err();
+        assert!(err.to_string().contains("duplicate key_id"));
+    }
+
+    #[test]
+    fn legacy_single_signature_still_verifies() {
+        let alice = key(0xA1);
+        let mut cp = checkpoint();
+        let sig = alice.sign(&signable_bytes(&cp).unwrap());
+        let hex: String = sig.to_bytes().iter().map(|b| format!("{b:02x}")).collect();
+        cp["signature"] =
+            serde_json::json!({"key_id": "alice", "algorithm": "ed25519", "value": hex});
+
+        let report = verify_checkpoint(&cp, &[alice.verifying_key()]).unwrap();
+        assert_eq!(report.valid, vec!["alice"]);
+
+        // Co-signing migrates it into the array.
+        sign_checkpoint(&mut cp, &key(0xB2), "bob").unwrap();
+        assert!(cp.get("signature").is_none());
+        let report =
+            verify_checkpoint(&cp, &[alice.verifying_key(), key(0xB2).verifying_key()]).unwrap();
+        assert_eq!(report.valid.len(), 2);
+    }
+
+    #[test]
+    fn key_reordering_keeps_signature_valid() {
+        let alice = key(0xA1);
+        let mut cp = checkpoint();
+        sign_checkpoint(&mut cp, &alice, "alice").unwrap();
+
+        let reordered: Value = serde_json::from_str(
+            &serde_json::to_string(&serde_json::json!({
+                "signatures": cp["signatures"],
+                "created_at": cp["created_at"],
+                "chain_head": cp["chain_head"],
+                "entries": cp["entries"],
+                "audit_file": cp["audit_file"],
+            }))
+            .unwrap(),
+        )
+        .unwrap();
+        let report = verify_checkpoint(&reordered, &[alice.verifying_key()]).unwrap();
+        assert_eq!(report.valid, vec!["alice"]);
+    }
+
+    #[test]
+    fn pre_jcs_signature_still_verifies() {
+        let alice = key(0xA1);
+        let mut cp = checkpoint();
+        let sig = alice.sign(&legacy_signable_bytes(&cp).unwrap());
+        let hex: String = sig.to_bytes().iter().map(|b| format!("{b:02x}")).collect();
+        cp["signatures"] =
+            serde_json::json!([{"key_id": "alice", "algorithm": "ed25519", "value": hex}]);
+
+        let report = verify_checkpoint(&cp, &[alice.verifying_key()]).unwrap();
+        assert_eq!(report.valid, vec!["alice"]);
+    }
+}
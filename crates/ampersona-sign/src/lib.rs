@@ -1,5 +1,8 @@
 #![forbid(unsafe_code)]
 
 pub mod canonical;
+pub mod checkpoint;
 pub mod sign;
+#[cfg(feature = "tsa")]
+pub mod timestamp;
 pub mod verify;
@@ -13,23 +13,13 @@ pub fn sign_persona(
     key_id: &str,
     signer: &str,
 ) -> Result<()> {
-    let obj = data
-        .as_object()
-        .ok_or_else(|| anyhow::anyhow!("persona must be a JSON object"))?;
-
-    // Determine signed_fields: all top-level keys except "signature" and "$schema"
-    let signed_fields: Vec<String> = obj
-        .keys()
-        .filter(|k| *k != "signature" && *k != "$schema")
-        .cloned()
-        .collect();
+    let signed_fields = signable_fields(data)?;
 
     // Canonicalize
     let canonical = canonicalize_fields(data, &signed_fields);
 
     // Hash
-    let digest = Sha256::digest(&canonical);
-    let digest_hex = format!("sha256:{:x}", digest);
+    let digest_hex = digest(&canonical);
 
     // Sign
     let signature = signing_key.sign(&canonical);
@@ -62,3 +52,43 @@ pub fn sign_persona(
 
     Ok(())
 }
+
+/// Content fingerprint: the `sha256:` digest of the canonical form
+/// [`sign_persona`] signs, i.e. what a fresh signature would record as `digest`.
+/// Ignores `signature` and `$schema` and is independent of key order.
+pub fn fingerprint(data: &Value) -> Result<String> {
+    let fields = signable_fields(data)?;
+    Ok(digest(&canonicalize_fields(data, &fields)))
+}
+
+/// Top-level keys a signature covers: all except `signature` and `$schema`.
+fn signable_fields(data: &Value) -> Result<Vec<String>> {
+    let obj = data
+        .as_object()
+        .ok_or_else(|| anyhow::anyhow!("persona must be a JSON object"))?;
+    Ok(obj
+        .keys()
+        .filter(|k| *k != "signature" && *k != "$schema")
+        .cloned()
+        .collect())
+}
+
+fn digest(canonical: &[u8]) -> String {
+    format!("sha256:{:x}", Sha256::digest(canonical))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fingerprint_matches_signature_digest() {
+        let mut data = serde_json::json!({"name": "Ada", "role": "architect"});
+        let before = fingerprint(&data).unwrap();
+        let key = SigningKey::from_bytes(&[7; 32]);
+        sign_persona(&mut data, &key, "k", "test").unwrap();
+        assert_eq!(data["signature"]["digest"], before.as_str());
+        // The signature block itself is not part of the fingerprint
+        assert_eq!(fingerprint(&data).unwrap(), before);
+    }
+}
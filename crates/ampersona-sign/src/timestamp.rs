@@ -0,0 +1,504 @@
+//! RFC 3161 trusted timestamps for audit checkpoints.
+//!
+//! A checkpoint is anchored by sending the SHA-256 of its JCS form (without
+//! `timestamp` and any signatures) to a Time-Stamp Authority and storing the
+//! returned token alongside it:
+//!
+//! ```json
+//! "timestamp": {
+//!   "format": "rfc3161",
+//!   "tsa_url": "http://tsa.example/tsr",
+//!   "hash_algorithm": "sha256",
+//!   "message_imprint": "<hex sha256>",
+//!   "gen_time": "2026-01-01T00:00:00Z",
+//!   "token": "<base64 DER ContentInfo>"
+//! }
+//! ```
+//!
+//! [`check_imprint`] re-derives the imprint and compares it with the `TSTInfo`
+//! inside the token. That only shows the token names this checkpoint: the
+//! TSA's CMS signature and certificate chain are not validated here, so a
+//! forged token with the right imprint passes. Use `openssl ts -verify` with
+//! the TSA's CA to verify a token.
+
+use std::io::{Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+use base64::Engine;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use crate::canonical::canonicalize;
+
+const NETWORK_TIMEOUT: Duration = Duration::from_secs(30);
+
+const OID_SHA256: &[u8] = &[0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x01];
+const OID_SIGNED_DATA: &[u8] = &[0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x07, 0x02];
+const OID_TST_INFO: &[u8] = &[
+    0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x09, 0x10, 0x01, 0x04,
+];
+
+const TAG_BOOLEAN: u8 = 0x01;
+const TAG_INTEGER: u8 = 0x02;
+const TAG_OCTET_STRING: u8 = 0x04;
+const TAG_NULL: u8 = 0x05;
+const TAG_OID: u8 = 0x06;
+const TAG_GENERALIZED_TIME: u8 = 0x18;
+const TAG_SEQUENCE: u8 = 0x30;
+const TAG_SET: u8 = 0x31;
+const TAG_CONTEXT_0: u8 = 0xa0;
+
+/// Fields extracted from a token's `TSTInfo`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TstInfo {
+    pub message_imprint: Vec<u8>,
+    /// `genTime` as RFC 3339.
+    pub gen_time: String,
+    pub nonce: Option<Vec<u8>>,
+}
+
+/// SHA-256 over the JCS form of a checkpoint, excluding `timestamp` and signatures.
+pub fn checkpoint_imprint(checkpoint: &Value) -> [u8; 32] {
+    let mut hashable = checkpoint.clone();
+    if let Some(obj) = hashable.as_object_mut() {
+        obj.remove("timestamp");
+        obj.remove("signature");
+        obj.remove("signatures");
+    }
+    Sha256::digest(canonicalize(&hashable)).into()
+}
+
+/// Request a timestamp for `checkpoint` from `tsa_url` and embed the token.
+///
+/// Any failure — network, HTTP status, TSA rejection, or a token that does
+/// not cover this checkpoint — is an error; the checkpoint is left untouched.
+pub fn anchor(checkpoint: &mut Value, tsa_url: &str) -> Result<()> {
+    let imprint = checkpoint_imprint(checkpoint);
+    let nonce: [u8; 8] = rand::random();
+    let request = encode_request(&imprint, &nonce);
+    let response = post(tsa_url, &request)
+        .with_context(|| format!("timestamp request to {tsa_url} failed"))?;
+    let token = parse_response(&response)?;
+    let info = parse_token(&token)?;
+
+    if info.message_imprint != imprint {
+        bail!("TSA token does not cover this checkpoint (message imprint mismatch)");
+    }
+    if info.nonce.as_deref() != Some(strip_leading_zeros(&nonce)) {
+        bail!("TSA response nonce does not match the request");
+    }
+
+    let obj = checkpoint
+        .as_object_mut()
+        .ok_or_else(|| anyhow::anyhow!("checkpoint must be a JSON object"))?;
+    obj.insert(
+        "timestamp".into(),
+        serde_json::json!({
+            "format": "rfc3161",
+            "tsa_url": tsa_url,
+            "hash_algorithm": "sha256",
+            "message_imprint": hex(&imprint),
+            "gen_time": info.gen_time,
+            "token": base64::engine::general_purpose::STANDARD.encode(&token),
+        }),
+    );
+    Ok(())
+}
+
+/// Check that a checkpoint's embedded token names its current contents.
+///
+/// Returns the token's `TstInfo` when the imprint matches; errors when the
+/// timestamp block is missing, malformed, or covers different content. This is
+/// not signature verification: the token's CMS signature is never checked.
+pub fn check_imprint(checkpoint: &Value) -> Result<TstInfo> {
+    let ts = checkpoint
+        .get("timestamp")
+        .ok_or_else(|| anyhow::anyhow!("checkpoint has no timestamp"))?;
+    let format = ts.get("format").and_then(Value::as_str).unwrap_or("");
+    if format != "rfc3161" {
+        bail!("unsupported timestamp format: {format}");
+    }
+    let token_b64 = ts
+        .get("token")
+        .and_then(Value::as_str)
+        .ok_or_else(|| anyhow::anyhow!("timestamp has no token"))?;
+    let token = base64::engine::general_purpose::STANDARD
+        .decode(token_b64)
+        .context("invalid base64 in timestamp token")?;
+    let info = parse_token(&token)?;
+
+    if info.message_imprint != checkpoint_imprint(checkpoint) {
+        bail!("timestamp does not match checkpoint contents (message imprint mismatch)");
+    }
+    Ok(info)
+}
+
+/// DER-encode a `TimeStampReq` for a SHA-256 imprint, with `certReq` set.
+pub fn encode_request(imprint: &[u8; 32], nonce: &[u8]) -> Vec<u8> {
+    let algorithm = tlv(
+        TAG_SEQUENCE,
+        &[tlv(TAG_OID, OID_SHA256), tlv(TAG_NULL, &[])].concat(),
+    );
+    let message_imprint = tlv(
+        TAG_SEQUENCE,
+        &[algorithm, tlv(TAG_OCTET_STRING, imprint)].concat(),
+    );
+    tlv(
+        TAG_SEQUENCE,
+        &[
+            tlv(TAG_INTEGER, &[1]),
+            message_imprint,
+            tlv(TAG_INTEGER, &encode_uint(nonce)),
+            tlv(TAG_BOOLEAN, &[0xff]),
+        ]
+        .concat(),
+    )
+}
+
+/// Extract the token (a DER `ContentInfo`) from a `TimeStampResp`.
+pub fn parse_response(der: &[u8]) -> Result<Vec<u8>> {
+    let (body, _) = expect(der, TAG_SEQUENCE, "TimeStampResp")?;
+    let (status_info, rest) = expect(body, TAG_SEQUENCE, "PKIStatusInfo")?;
+    let (status, _) = expect(status_info, TAG_INTEGER, "PKIStatus")?;
+    // 0 = granted, 1 = grantedWithMods; everything else carries no token.
+    if !matches!(status, [0] | [1]) {
+        bail!("TSA rejected the request (PKIStatus {})", read_uint(status));
+    }
+    if rest.is_empty() {
+        bail!("TSA response carries no timeStampToken");
+    }
+    let (_, _, after) = read_tlv(rest)?;
+    Ok(rest[..rest.len() - after.len()].to_vec())
+}
+
+/// Walk a token down to its `TSTInfo` and extract the fields we check.
+pub fn parse_token(der: &[u8]) -> Result<TstInfo> {
+    let (content_info, _) = expect(der, TAG_SEQUENCE, "ContentInfo")?;
+    let (content_type, rest) = expect(content_info, TAG_OID, "contentType")?;
+    if content_type != OID_SIGNED_DATA {
+        bail!("timestamp token is not CMS SignedData");
+    }
+    let (explicit, _) = expect(rest, TAG_CONTEXT_0, "content")?;
+    let (signed_data, _) = expect(explicit, TAG_SEQUENCE, "SignedData")?;
+    let (_, rest) = expect(signed_data, TAG_INTEGER, "version")?;
+    let (_, rest) = expect(rest, TAG_SET, "digestAlgorithms")?;
+    let (encap, _) = expect(rest, TAG_SEQUENCE, "encapContentInfo")?;
+    let (econtent_type, rest) = expect(encap, TAG_OID, "eContentType")?;
+    if econtent_type != OID_TST_INFO {
+        bail!("timestamp token does not contain TSTInfo");
+    }
+    let (explicit, _) = expect(rest, TAG_CONTEXT_0, "eContent")?;
+    let (tst_der, _) = expect(explicit, TAG_OCTET_STRING, "eContent")?;
+
+    let (tst, _) = expect(tst_der, TAG_SEQUENCE, "TSTInfo")?;
+    let (_, rest) = expect(tst, TAG_INTEGER, "version")?;
+    let (_, rest) = expect(rest, TAG_OID, "policy")?;
+    let (imprint, rest) = expect(rest, TAG_SEQUENCE, "messageImprint")?;
+    let (algorithm, imprint_rest) = expect(imprint, TAG_SEQUENCE, "hashAlgorithm")?;
+    let (hash_oid, _) = expect(algorithm, TAG_OID, "hashAlgorithm")?;
+    if hash_oid != OID_SHA256 {
+        bail!("timestamp token uses an unsupported hash algorithm");
+    }
+    let (hashed, _) = expect(imprint_rest, TAG_OCTET_STRING, "hashedMessage")?;
+    let (_, rest) = expect(rest, TAG_INTEGER, "serialNumber")?;
+    let (gen_time, mut rest) = expect(rest, TAG_GENERALIZED_TIME, "genTime")?;
+
+    // Optional accuracy (SEQUENCE) and ordering (BOOLEAN) precede the nonce.
+    let mut nonce = None;
+    while !rest.is_empty() {
+        let (tag, content, after) = read_tlv(rest)?;
+        if tag == TAG_INTEGER {
+            nonce = Some(strip_leading_zeros(content).to_vec());
+            break;
+        }
+        rest = after;
+    }
+
+    Ok(TstInfo {
+        message_imprint: hashed.to_vec(),
+        gen_time: generalized_to_rfc3339(gen_time)?,
+        nonce,
+    })
+}
+
+/// POST a DER request over plain HTTP and return the response body.
+fn post(url: &str, body: &[u8]) -> Result<Vec<u8>> {
+    if url.starts_with("https://") {
+        bail!("https TSA URLs are not supported; use the TSA's http:// endpoint");
+    }
+    let rest = url
+        .strip_prefix("http://")
+        .ok_or_else(|| anyhow::anyhow!("TSA URL must start with http://"))?;
+    let (authority, path) = match rest.find('/') {
+        Some(i) => (&rest[..i], &rest[i..]),
+        None => (rest, "/"),
+    };
+    let (host, addr) = match authority.rsplit_once(':') {
+        Some((host, _)) => (host, authority.to_string()),
+        None => (authority, format!("{authority}:80")),
+    };
+
+    let socket = addr
+        .to_socket_addrs()?
+        .next()
+        .ok_or_else(|| anyhow::anyhow!("cannot resolve {host}"))?;
+    let mut stream = TcpStream::connect_timeout(&socket, NETWORK_TIMEOUT)?;
+    stream.set_read_timeout(Some(NETWORK_TIMEOUT))?;
+    stream.set_write_timeout(Some(NETWORK_TIMEOUT))?;
+
+    let header = format!(
+        "POST {path} HTTP/1.0\r\nHost: {host}\r\nContent-Type: application/timestamp-query\r\n\
+         Accept: application/timestamp-reply\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        body.len()
+    );
+    stream.write_all(header.as_bytes())?;
+    stream.write_all(body)?;
+
+    let mut response = Vec::new();
+    stream.read_to_end(&mut response)?;
+    let split = response
+        .windows(4)
+        .position(|w| w == b"\r\n\r\n")
+        .ok_or_else(|| anyhow::anyhow!("malformed HTTP response"))?;
+    let head = String::from_utf8_lossy(&response[..split]);
+    let status_line = head.lines().next().unwrap_or("");
+    if status_line.split_whitespace().nth(1) != Some("200") {
+        bail!("TSA returned {status_line}");
+    }
+    Ok(response[split + 4..].to_vec())
+}
+
+fn tlv(tag: u8, content: &[u8]) -> Vec<u8> {
+    let mut out = vec![tag];
+    let len = content.len();
+    if len < 0x80 {
+        out.push(len as u8);
+    } else {
+        let bytes: Vec<u8> = len
+            .to_be_bytes()
+            .into_iter()
+            .skip_while(|b| *b == 0)
+            .collect();
+        out.push(0x80 | bytes.len() as u8);
+        out.extend(bytes);
+    }
+    out.extend_from_slice(content);
+    out
+}
+
+/// Read one DER element: `(tag, content, rest)`.
+fn read_tlv(der: &[u8]) -> Result<(u8, &[u8], &[u8])> {
+    let (&tag, rest) = der
+        .split_first()
+        .ok_or_else(|| anyhow::anyhow!("truncated DER"))?;
+    let (&first, rest) = rest
+        .split_first()
+        .ok_or_else(|| anyhow::anyhow!("truncated DER"))?;
+    let (len, rest) = if first < 0x80 {
+        (first as usize, rest)
+    } else {
+        let n = (first & 0x7f) as usize;
+        if n == 0 || n > 4 || rest.len() < n {
+            bail!("unsupported DER length encoding");
+        }
+        let len = rest[..n]
+            .iter()
+            .fold(0usize, |acc, b| (acc << 8) | *b as usize);
+        (len, &rest[n..])
+    };
+    if rest.len() < len {
+        bail!("truncated DER");
+    }
+    Ok((tag, &rest[..len], &rest[len..]))
+}
+
+fn expect<'a>(der: &'a [u8], tag: u8, what: &str) -> Result<(&'a [u8], &'a [u8])> {
+    let (actual, content, rest) = read_tlv(der).with_context(|| format!("reading {what}"))?;
+    if actual != tag {
+        bail!("expected {what} (tag 0x{tag:02x}), found tag 0x{actual:02x}");
+    }
+    Ok((content, rest))
+}
+
+/// Encode unsigned big-endian bytes as DER INTEGER content.
+fn encode_uint(bytes: &[u8]) -> Vec<u8> {
+    let trimmed = strip_leading_zeros(bytes);
+    let mut out = Vec::with_capacity(trimmed.len() + 1);
+    if trimmed.first().is_none_or(|b| b & 0x80 != 0) {
+        out.push(0);
+    }
+    out.extend_from_slice(trimmed);
+    out
+}
+
+fn strip_leading_zeros(bytes: &[u8]) -> &[u8] {
+    let start = bytes.iter().position(|b| *b != 0).unwrap_or(bytes.len());
+    &bytes[start..]
+}
+
+fn read_uint(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0u64, |acc, b| (acc << 8) | *b as u64)
+}
+
+/// `YYYYMMDDHHMMSS[.fff]Z` → `YYYY-MM-DDTHH:MM:SS[.fff]Z`.
+fn generalized_to_rfc3339(raw: &[u8]) -> Result<String> {
+    let s = std::str::from_utf8(raw).context("genTime is not ASCII")?;
+    let valid = s.len() >= 15
+        && s.ends_with('Z')
+        && s[..14].bytes().all(|b| b.is_ascii_digit())
+        && (s.len() == 15 || s.as_bytes()[14] == b'.');
+    if !valid {
+        bail!("unsupported genTime format: {s}");
+    }
+    Ok(format!(
+        "{}-{}-{}T{}:{}:{}{}",
+        &s[..4],
+        &s[4..6],
+        &s[6..8],
+        &s[8..10],
+        &s[10..12],
+        &s[12..14],
+        &s[14..]
+    ))
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// Minimal well-formed token: SignedData with an empty signerInfos set.
+    fn token(imprint: &[u8], nonce: Option<&[u8]>) -> Vec<u8> {
+        let algorithm = tlv(
+            TAG_SEQUENCE,
+            &[tlv(TAG_OID, OID_SHA256), tlv(TAG_NULL, &[])].concat(),
+        );
+        let message_imprint = tlv(
+            TAG_SEQUENCE,
+            &[algorithm.clone(), tlv(TAG_OCTET_STRING, imprint)].concat(),
+        );
+        let mut fields = [
+            tlv(TAG_INTEGER, &[1]),
+            tlv(TAG_OID, &[0x2a, 0x03, 0x04]),
+            message_imprint,
+            tlv(TAG_INTEGER, &[0x2a]),
+            tlv(TAG_GENERALIZED_TIME, b"20260102030405.5Z"),
+            tlv(TAG_SEQUENCE, &tlv(TAG_INTEGER, &[1])),
+        ]
+        .concat();
+        if let Some(nonce) = nonce {
+            fields.extend(tlv(TAG_INTEGER, &encode_uint(nonce)));
+        }
+        let tst_info = tlv(TAG_SEQUENCE, &fields);
+        let encap = tlv(
+            TAG_SEQUENCE,
+            &[
+                tlv(TAG_OID, OID_TST_INFO),
+                tlv(TAG_CONTEXT_0, &tlv(TAG_OCTET_STRING, &tst_info)),
+            ]
+            .concat(),
+        );
+        let signed_data = tlv(
+            TAG_SEQUENCE,
+            &[
+                tlv(TAG_INTEGER, &[3]),
+                tlv(TAG_SET, &algorithm),
+                encap,
+                tlv(TAG_SET, &[]),
+            ]
+            .concat(),
+        );
+        tlv(
+            TAG_SEQUENCE,
+            &[
+                tlv(TAG_OID, OID_SIGNED_DATA),
+                tlv(TAG_CONTEXT_0, &signed_data),
+            ]
+            .concat(),
+        )
+    }
+
+    fn checkpoint() -> Value {
+        json!({
+            "audit_file": "agent.audit.jsonl",
+            "entries": 3,
+            "chain_head": "sha256:00",
+            "created_at": "2026-01-01T00:00:00Z",
+        })
+    }
+
+    #[test]
+    fn request_encodes_sha256_imprint_and_cert_req() {
+        let der = encode_request(&[0xab; 32], &[0x80, 0x01]);
+        assert_eq!(&der[..2], &[0x30, 0x3e]);
+        assert_eq!(&der[2..5], &[0x02, 0x01, 0x01]);
+        // AlgorithmIdentifier: sha256 OID followed by NULL.
+        assert_eq!(
+            &der[9..20],
+            &[0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x01]
+        );
+        assert_eq!(&der[20..22], &[0x05, 0x00]);
+        assert_eq!(&der[22..24], &[0x04, 0x20]);
+        // High-bit nonce gets a leading zero to stay positive.
+        assert_eq!(&der[56..61], &[0x02, 0x03, 0x00, 0x80, 0x01]);
+        assert_eq!(&der[61..], &[0x01, 0x01, 0xff]);
+    }
+
+    #[test]
+    fn response_and_token_round_trip() {
+        let imprint = [7u8; 32];
+        let tok = token(&imprint, Some(&[0x12, 0x34]));
+        let status = tlv(TAG_SEQUENCE, &tlv(TAG_INTEGER, &[0]));
+        let resp = tlv(TAG_SEQUENCE, &[status, tok.clone()].concat());
+
+        assert_eq!(parse_response(&resp).unwrap(), tok);
+        let info = parse_token(&tok).unwrap();
+        assert_eq!(info.message_imprint, imprint);
+        assert_eq!(info.gen_time, "2026-01-02T03:04:05.5Z");
+        assert_eq!(info.nonce.as_deref(), Some(&[0x12, 0x34][..]));
+    }
+
+    #[test]
+    fn rejected_response_is_an_error() {
+        let status = tlv(TAG_SEQUENCE, &tlv(TAG_INTEGER, &[2]));
+        let resp = tlv(TAG_SEQUENCE, &status);
+        let err = parse_response(&resp).unwrap_err();
+        assert!(err.to_string().contains("PKIStatus 2"));
+    }
+
+    #[test]
+    fn imprint_check_detects_modified_checkpoint() {
+        let mut cp = checkpoint();
+        let tok = token(&checkpoint_imprint(&cp), None);
+        cp["timestamp"] = json!({
+            "format": "rfc3161",
+            "token": base64::engine::general_purpose::STANDARD.encode(&tok),
+        });
+        assert_eq!(
+            check_imprint(&cp).unwrap().gen_time,
+            "2026-01-02T03:04:05.5Z"
+        );
+
+        // A signature added afterwards does not change the imprint.
+        cp["signature"] = json!({"value": "00"});
+        assert!(check_imprint(&cp).is_ok());
+
+        cp["entries"] = json!(4);
+        let err = check_imprint(&cp).unwrap_err();
+        assert!(err.to_string().contains("imprint mismatch"));
+    }
+
+    #[test]
+    fn unreachable_tsa_leaves_checkpoint_unanchored() {
+        let mut cp = checkpoint();
+        assert!(anchor(&mut cp, "http://127.0.0.1:1/tsr").is_err());
+        assert!(anchor(&mut cp, "https://tsa.example/tsr").is_err());
+        assert!(cp.get("timestamp").is_none());
+    }
+}
@@ -1,3 +1,5 @@
+use std::path::Path;
+
 use anyhow::{bail, Result};
 use base64::Engine;
 use ed25519_dalek::{Signature, Verifier, VerifyingKey};
@@ -58,3 +60,39 @@ pub fn verify_persona(data: &Value, verifying_key: &VerifyingKey) -> Result<bool
         Err(_) => Ok(false),
     }
 }
+
+/// Verify the signature on a persona JSON with the key its `key_id` names in
+/// `keyring`, a directory of `<key_id>.pub` files (raw 32-byte ed25519 keys).
+pub fn verify_persona_with_keyring(data: &Value, keyring: &Path) -> Result<bool> {
+    let key_id = data
+        .get("signature")
+        .ok_or_else(|| anyhow::anyhow!("no signature block found"))?
+        .get("key_id")
+        .and_then(Value::as_str)
+        .ok_or_else(|| anyhow::anyhow!("signature has no key_id"))?;
+    verify_persona(data, &keyring_key(keyring, key_id)?)
+}
+
+/// The public key stored for `key_id` in `keyring`.
+pub fn keyring_key(keyring: &Path, key_id: &str) -> Result<VerifyingKey> {
+    // A key_id comes from the signed document; never let it leave the keyring.
+    if key_id.is_empty() || key_id.starts_with('.') || key_id.contains(['/', '\\']) {
+        bail!("key_id '{key_id}' cannot name a keyring file");
+    }
+    let path = keyring.join(format!("{key_id}.pub"));
+    let bytes = match std::fs::read(&path) {
+        Ok(bytes) => bytes,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => bail!(
+            "no public key for key_id '{key_id}' in keyring {} (expected {})",
+            keyring.display(),
+            path.display()
+        ),
+        Err(e) => bail!("cannot read {}: {e}", path.display()),
+    };
+    let bytes: [u8; 32] = bytes
+        .get(..32)
+        .and_then(|b| b.try_into().ok())
+        .ok_or_else(|| anyhow::anyhow!("{}: pubkey must be at least 32 bytes", path.display()))?;
+    VerifyingKey::from_bytes(&bytes)
+        .map_err(|e| anyhow::anyhow!("{}: invalid pubkey: {e}", path.display()))
+}